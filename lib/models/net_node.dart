@@ -34,6 +34,8 @@ class NetNode {
 
   bool enable_ipv6 = true;
 
+  bool dhcp_ipv6 = false; // 是否从房间的 ULA 前缀自动分配虚拟 IPv6
+
   int mtu = 1360; //x
 
   bool latency_first = true; //x
@@ -55,6 +57,8 @@ class NetNode {
 
   int data_compress_algo = 1; //x
 
+  int data_compress_level = 0; // 0 使用算法的默认压缩等级
+
   bool bind_device = true; //x
 
   bool enable_kcp_proxy = true; //x
@@ -0,0 +1,64 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.10.0.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `fmt`, `from`, `from`
+
+Future<void> setBandwidthLimit({
+  required String instanceId,
+  required AstralBandwidthLimit limit,
+}) => RustLib.instance.api.crateApiBandwidthSetBandwidthLimit(
+  instanceId: instanceId,
+  limit: limit,
+);
+
+Future<void> removeBandwidthLimit({
+  required String instanceId,
+  int? peerId,
+  String? cidr,
+}) => RustLib.instance.api.crateApiBandwidthRemoveBandwidthLimit(
+  instanceId: instanceId,
+  peerId: peerId,
+  cidr: cidr,
+);
+
+Future<List<AstralBandwidthLimit>> listBandwidthLimits({
+  required String instanceId,
+}) => RustLib.instance.api.crateApiBandwidthListBandwidthLimits(
+  instanceId: instanceId,
+);
+
+class AstralBandwidthLimit {
+  final int? peerId;
+  final String? cidr;
+  final BigInt? uploadBps;
+  final BigInt? downloadBps;
+
+  const AstralBandwidthLimit({
+    this.peerId,
+    this.cidr,
+    this.uploadBps,
+    this.downloadBps,
+  });
+
+  @override
+  int get hashCode =>
+      peerId.hashCode ^
+      cidr.hashCode ^
+      uploadBps.hashCode ^
+      downloadBps.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralBandwidthLimit &&
+          runtimeType == other.runtimeType &&
+          peerId == other.peerId &&
+          cidr == other.cidr &&
+          uploadBps == other.uploadBps &&
+          downloadBps == other.downloadBps;
+}
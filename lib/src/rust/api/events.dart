@@ -0,0 +1,203 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.10.0.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+
+// These functions are ignored because they are not marked as `pub`: `convert_event`, `dispatch_event`, `handle_event`, `new_event`
+// These types are ignored because they are neither used by any `pub` functions nor (for structs and enums) marked `#[frb(unignore)]`: `EVENT_SINKS`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `clone`, `clone`, `clone`, `deref`, `eq`, `fmt`, `fmt`, `fmt`, `fmt`, `from`, `initialize`
+
+Stream<AstralEvent> subscribeEvents() =>
+    RustLib.instance.api.crateApiEventsSubscribeEvents();
+
+class AstralEvent {
+  final String instanceId;
+  final PlatformInt64 timestampMs;
+  final AstralEventKind kind;
+  final int? peerId;
+  final AstralPeerConn? conn;
+  final String? devName;
+  final String? url;
+  final String? ipVersion;
+  final String? localAddr;
+  final String? remoteAddr;
+  final String? portal;
+  final String? clientAddr;
+  final String? oldAddr;
+  final String? newAddr;
+  final String? addr;
+  final AstralPortForward? forward;
+  final BigInt? rotationId;
+  final BigInt? count;
+  final String? error;
+
+  const AstralEvent({
+    required this.instanceId,
+    required this.timestampMs,
+    required this.kind,
+    this.peerId,
+    this.conn,
+    this.devName,
+    this.url,
+    this.ipVersion,
+    this.localAddr,
+    this.remoteAddr,
+    this.portal,
+    this.clientAddr,
+    this.oldAddr,
+    this.newAddr,
+    this.addr,
+    this.forward,
+    this.rotationId,
+    this.count,
+    this.error,
+  });
+
+  @override
+  int get hashCode =>
+      instanceId.hashCode ^
+      timestampMs.hashCode ^
+      kind.hashCode ^
+      peerId.hashCode ^
+      conn.hashCode ^
+      devName.hashCode ^
+      url.hashCode ^
+      ipVersion.hashCode ^
+      localAddr.hashCode ^
+      remoteAddr.hashCode ^
+      portal.hashCode ^
+      clientAddr.hashCode ^
+      oldAddr.hashCode ^
+      newAddr.hashCode ^
+      addr.hashCode ^
+      forward.hashCode ^
+      rotationId.hashCode ^
+      count.hashCode ^
+      error.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralEvent &&
+          runtimeType == other.runtimeType &&
+          instanceId == other.instanceId &&
+          timestampMs == other.timestampMs &&
+          kind == other.kind &&
+          peerId == other.peerId &&
+          conn == other.conn &&
+          devName == other.devName &&
+          url == other.url &&
+          ipVersion == other.ipVersion &&
+          localAddr == other.localAddr &&
+          remoteAddr == other.remoteAddr &&
+          portal == other.portal &&
+          clientAddr == other.clientAddr &&
+          oldAddr == other.oldAddr &&
+          newAddr == other.newAddr &&
+          addr == other.addr &&
+          forward == other.forward &&
+          rotationId == other.rotationId &&
+          count == other.count &&
+          error == other.error;
+}
+
+enum AstralEventKind {
+  tunDeviceReady,
+  tunDeviceError,
+  peerAdded,
+  peerRemoved,
+  peerConnAdded,
+  peerConnRemoved,
+  listenerAdded,
+  listenerAddFailed,
+  listenerAcceptFailed,
+  connectionAccepted,
+  connectionError,
+  connecting,
+  connectError,
+  vpnPortalStarted,
+  vpnPortalClientConnected,
+  vpnPortalClientDisconnected,
+  dhcpIpv4Changed,
+  dhcpIpv4Conflicted,
+  portForwardAdded,
+  networkSecretRotated,
+  exitNodeChanged,
+  exitNodeDown,
+  eventsLagged,
+  eventChannelClosed,
+}
+
+class AstralPeerConn {
+  final String connId;
+  final int myPeerId;
+  final int peerId;
+  final String tunnelType;
+  final String localAddr;
+  final String remoteAddr;
+  final bool isClient;
+  final String networkName;
+
+  const AstralPeerConn({
+    required this.connId,
+    required this.myPeerId,
+    required this.peerId,
+    required this.tunnelType,
+    required this.localAddr,
+    required this.remoteAddr,
+    required this.isClient,
+    required this.networkName,
+  });
+
+  @override
+  int get hashCode =>
+      connId.hashCode ^
+      myPeerId.hashCode ^
+      peerId.hashCode ^
+      tunnelType.hashCode ^
+      localAddr.hashCode ^
+      remoteAddr.hashCode ^
+      isClient.hashCode ^
+      networkName.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralPeerConn &&
+          runtimeType == other.runtimeType &&
+          connId == other.connId &&
+          myPeerId == other.myPeerId &&
+          peerId == other.peerId &&
+          tunnelType == other.tunnelType &&
+          localAddr == other.localAddr &&
+          remoteAddr == other.remoteAddr &&
+          isClient == other.isClient &&
+          networkName == other.networkName;
+}
+
+class AstralPortForward {
+  final String bindAddr;
+  final String dstAddr;
+  final String proto;
+
+  const AstralPortForward({
+    required this.bindAddr,
+    required this.dstAddr,
+    required this.proto,
+  });
+
+  @override
+  int get hashCode => bindAddr.hashCode ^ dstAddr.hashCode ^ proto.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralPortForward &&
+          runtimeType == other.runtimeType &&
+          bindAddr == other.bindAddr &&
+          dstAddr == other.dstAddr &&
+          proto == other.proto;
+}
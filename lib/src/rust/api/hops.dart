@@ -6,6 +6,12 @@
 import '../frb_generated.dart';
 import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
 
+// These functions are ignored because they are not marked as `pub`: `list_interfaces`, `to_io_error`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `from`
+
+Future<List<AstralInterface>> listNetworkInterfaces() =>
+    RustLib.instance.api.crateApiHopsListNetworkInterfaces();
+
 Future<List<(String, int)>> getAllInterfacesMetrics() =>
     RustLib.instance.api.crateApiHopsGetAllInterfacesMetrics();
 
@@ -16,3 +22,49 @@ Future<void> setInterfaceMetric({
   interfaceName: interfaceName,
   metric: metric,
 );
+
+Future<void> preferInterfaceForBroadcast({
+  required String interfaceName,
+}) => RustLib.instance.api.crateApiHopsPreferInterfaceForBroadcast(
+  interfaceName: interfaceName,
+);
+
+/// 网卡信息
+class AstralInterface {
+  final int index;
+  final String name;
+  final int? metric;
+  final int mtu;
+  final List<String> addresses;
+  final bool up;
+
+  const AstralInterface({
+    required this.index,
+    required this.name,
+    this.metric,
+    required this.mtu,
+    required this.addresses,
+    required this.up,
+  });
+
+  @override
+  int get hashCode =>
+      index.hashCode ^
+      name.hashCode ^
+      metric.hashCode ^
+      mtu.hashCode ^
+      addresses.hashCode ^
+      up.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralInterface &&
+          runtimeType == other.runtimeType &&
+          index == other.index &&
+          name == other.name &&
+          metric == other.metric &&
+          mtu == other.mtu &&
+          addresses == other.addresses &&
+          up == other.up;
+}
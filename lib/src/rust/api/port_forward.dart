@@ -0,0 +1,76 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.10.0.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+// These functions are ignored because they are not marked as `pub`: `parse_forward`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `fmt`
+
+Future<void> addPortForward({
+  required String instanceId,
+  required Forward forward,
+}) => RustLib.instance.api.crateApiPortForwardAddPortForward(
+  instanceId: instanceId,
+  forward: forward,
+);
+
+Future<void> removePortForward({
+  required String instanceId,
+  required Forward forward,
+}) => RustLib.instance.api.crateApiPortForwardRemovePortForward(
+  instanceId: instanceId,
+  forward: forward,
+);
+
+Future<List<AstralPortForwardStatus>> listPortForwards({
+  required String instanceId,
+}) => RustLib.instance.api.crateApiPortForwardListPortForwards(
+  instanceId: instanceId,
+);
+
+class AstralPortForwardStatus {
+  final String bindAddr;
+  final String dstAddr;
+  final String proto;
+  final bool bound;
+  final String? error;
+  final int activeConnections;
+  final BigInt totalConnections;
+
+  const AstralPortForwardStatus({
+    required this.bindAddr,
+    required this.dstAddr,
+    required this.proto,
+    required this.bound,
+    this.error,
+    required this.activeConnections,
+    required this.totalConnections,
+  });
+
+  @override
+  int get hashCode =>
+      bindAddr.hashCode ^
+      dstAddr.hashCode ^
+      proto.hashCode ^
+      bound.hashCode ^
+      error.hashCode ^
+      activeConnections.hashCode ^
+      totalConnections.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralPortForwardStatus &&
+          runtimeType == other.runtimeType &&
+          bindAddr == other.bindAddr &&
+          dstAddr == other.dstAddr &&
+          proto == other.proto &&
+          bound == other.bound &&
+          error == other.error &&
+          activeConnections == other.activeConnections &&
+          totalConnections == other.totalConnections;
+}
@@ -0,0 +1,95 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.10.0.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `clone`, `fmt`, `fmt`
+
+Future<BigInt> startSecretRotation({
+  required String instanceId,
+  required String networkSecret,
+  required int gracePeriodSec,
+}) => RustLib.instance.api.crateApiSecretRotationStartSecretRotation(
+  instanceId: instanceId,
+  networkSecret: networkSecret,
+  gracePeriodSec: gracePeriodSec,
+);
+
+Future<AstralSecretRotationStatus> getSecretRotationStatus({
+  required String instanceId,
+}) => RustLib.instance.api.crateApiSecretRotationGetSecretRotationStatus(
+  instanceId: instanceId,
+);
+
+class AstralSecretRotationPeer {
+  final int peerId;
+  final String hostname;
+  final bool prepared;
+  final bool committed;
+  final String? error;
+
+  const AstralSecretRotationPeer({
+    required this.peerId,
+    required this.hostname,
+    required this.prepared,
+    required this.committed,
+    this.error,
+  });
+
+  @override
+  int get hashCode =>
+      peerId.hashCode ^
+      hostname.hashCode ^
+      prepared.hashCode ^
+      committed.hashCode ^
+      error.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralSecretRotationPeer &&
+          runtimeType == other.runtimeType &&
+          peerId == other.peerId &&
+          hostname == other.hostname &&
+          prepared == other.prepared &&
+          committed == other.committed &&
+          error == other.error;
+}
+
+class AstralSecretRotationStatus {
+  final BigInt rotationId;
+  final bool initiatedLocally;
+  final bool committed;
+  final int gracePeriodRemainingSec;
+  final List<AstralSecretRotationPeer> peers;
+
+  const AstralSecretRotationStatus({
+    required this.rotationId,
+    required this.initiatedLocally,
+    required this.committed,
+    required this.gracePeriodRemainingSec,
+    required this.peers,
+  });
+
+  @override
+  int get hashCode =>
+      rotationId.hashCode ^
+      initiatedLocally.hashCode ^
+      committed.hashCode ^
+      gracePeriodRemainingSec.hashCode ^
+      peers.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralSecretRotationStatus &&
+          runtimeType == other.runtimeType &&
+          rotationId == other.rotationId &&
+          initiatedLocally == other.initiatedLocally &&
+          committed == other.committed &&
+          gracePeriodRemainingSec == other.gracePeriodRemainingSec &&
+          peers == other.peers;
+}
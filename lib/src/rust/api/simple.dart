@@ -6,15 +6,9 @@
 import '../frb_generated.dart';
 import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
 
-// These functions are ignored because they are not marked as `pub`: `create_and_store_network_instance`, `peer_conn_info_to_string`
-// These types are ignored because they are neither used by any `pub` functions nor (for structs and enums) marked `#[frb(unignore)]`: `RT`
-// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `deref`, `initialize`
-
-Future<void> sendUdpToLocalhost({required String message}) =>
-    RustLib.instance.api.crateApiSimpleSendUdpToLocalhost(message: message);
-
-Future<JoinHandle> handleEvent({required EventBusSubscriber events}) =>
-    RustLib.instance.api.crateApiSimpleHandleEvent(events: events);
+// These functions are ignored because they are not marked as `pub`: `build_network_snapshot`, `collect_hops`, `conn_latency_ms`, `conn_to_astral`, `create_and_store_network_instance`, `instance_rpc_client`, `nat_type_to_string`, `parse_instance_id`, `peer_latency_and_loss`, `route_to_hop`
+// These types are ignored because they are neither used by any `pub` functions nor (for structs and enums) marked `#[frb(unignore)]`: `MANAGER`, `RT`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `clone`, `clone`, `clone`, `deref`, `deref`, `fmt`, `fmt`, `fmt`, `fmt`, `initialize`, `initialize`
 
 Future<String> easytierVersion() =>
     RustLib.instance.api.crateApiSimpleEasytierVersion();
@@ -22,15 +16,27 @@ Future<String> easytierVersion() =>
 Future<bool> isEasytierRunning() =>
     RustLib.instance.api.crateApiSimpleIsEasytierRunning();
 
-Future<List<String>> getIps() => RustLib.instance.api.crateApiSimpleGetIps();
+Future<bool> isInstanceRunning({required String instanceId}) => RustLib
+    .instance
+    .api
+    .crateApiSimpleIsInstanceRunning(instanceId: instanceId);
 
-Future<void> setTunFd({required int fd}) =>
-    RustLib.instance.api.crateApiSimpleSetTunFd(fd: fd);
+Future<List<String>> listInstances() =>
+    RustLib.instance.api.crateApiSimpleListInstances();
+
+Future<AstralNetworkSnapshot> getNetworkSnapshot({
+  required String instanceId,
+}) => RustLib.instance.api.crateApiSimpleGetNetworkSnapshot(
+  instanceId: instanceId,
+);
 
-Future<String> getRunningInfo() =>
-    RustLib.instance.api.crateApiSimpleGetRunningInfo();
+Future<List<String>> getIps({required String instanceId}) =>
+    RustLib.instance.api.crateApiSimpleGetIps(instanceId: instanceId);
 
-Future<JoinHandleResultString> createServer({
+Future<void> setTunFd({required String instanceId, required int fd}) =>
+    RustLib.instance.api.crateApiSimpleSetTunFd(instanceId: instanceId, fd: fd);
+
+Future<String> createServer({
   required String username,
   required bool enableDhcp,
   required String specifiedIp,
@@ -54,33 +60,244 @@ Future<JoinHandleResultString> createServer({
   flag: flag,
 );
 
-Future<void> closeServer() => RustLib.instance.api.crateApiSimpleCloseServer();
-
-Future<List<PeerRoutePair>> getPeerRoutePairs() =>
-    RustLib.instance.api.crateApiSimpleGetPeerRoutePairs();
+Future<void> closeServer({required String instanceId}) =>
+    RustLib.instance.api.crateApiSimpleCloseServer(instanceId: instanceId);
 
-Future<KVNetworkStatus> getNetworkStatus() =>
-    RustLib.instance.api.crateApiSimpleGetNetworkStatus();
+Future<void> closeAllServers() =>
+    RustLib.instance.api.crateApiSimpleCloseAllServers();
 
 Future<void> initApp() => RustLib.instance.api.crateApiSimpleInitApp();
 
-// Rust type: RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<EventBusSubscriber>>
-abstract class EventBusSubscriber implements RustOpaqueInterface {}
+class AstralConnection {
+  final String connId;
+  final String tunnelType;
+  final String localAddr;
+  final String remoteAddr;
+  final bool isClient;
+  final double latencyMs;
+  final double lossRate;
+  final BigInt rxBytes;
+  final BigInt txBytes;
+  final BigInt rxPackets;
+  final BigInt txPackets;
+
+  const AstralConnection({
+    required this.connId,
+    required this.tunnelType,
+    required this.localAddr,
+    required this.remoteAddr,
+    required this.isClient,
+    required this.latencyMs,
+    required this.lossRate,
+    required this.rxBytes,
+    required this.txBytes,
+    required this.rxPackets,
+    required this.txPackets,
+  });
+
+  @override
+  int get hashCode =>
+      connId.hashCode ^
+      tunnelType.hashCode ^
+      localAddr.hashCode ^
+      remoteAddr.hashCode ^
+      isClient.hashCode ^
+      latencyMs.hashCode ^
+      lossRate.hashCode ^
+      rxBytes.hashCode ^
+      txBytes.hashCode ^
+      rxPackets.hashCode ^
+      txPackets.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralConnection &&
+          runtimeType == other.runtimeType &&
+          connId == other.connId &&
+          tunnelType == other.tunnelType &&
+          localAddr == other.localAddr &&
+          remoteAddr == other.remoteAddr &&
+          isClient == other.isClient &&
+          latencyMs == other.latencyMs &&
+          lossRate == other.lossRate &&
+          rxBytes == other.rxBytes &&
+          txBytes == other.txBytes &&
+          rxPackets == other.rxPackets &&
+          txPackets == other.txPackets;
+}
+
+class AstralHop {
+  final int peerId;
+  final String hostname;
+  final String? ipv4;
+  final String? ipv6;
+  final double latencyMs;
+  final double lossRate;
 
-// Rust type: RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner< JoinHandle < () >>>
-abstract class JoinHandle implements RustOpaqueInterface {}
+  const AstralHop({
+    required this.peerId,
+    required this.hostname,
+    this.ipv4,
+    this.ipv6,
+    required this.latencyMs,
+    required this.lossRate,
+  });
 
-// Rust type: RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle < Result < () , String > >>>
-abstract class JoinHandleResultString implements RustOpaqueInterface {}
+  @override
+  int get hashCode =>
+      peerId.hashCode ^
+      hostname.hashCode ^
+      ipv4.hashCode ^
+      ipv6.hashCode ^
+      latencyMs.hashCode ^
+      lossRate.hashCode;
 
-// Rust type: RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<PeerRoutePair>>
-abstract class PeerRoutePair implements RustOpaqueInterface {}
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralHop &&
+          runtimeType == other.runtimeType &&
+          peerId == other.peerId &&
+          hostname == other.hostname &&
+          ipv4 == other.ipv4 &&
+          ipv6 == other.ipv6 &&
+          latencyMs == other.latencyMs &&
+          lossRate == other.lossRate;
+}
+
+class AstralNetworkSnapshot {
+  final String instanceId;
+  final String devName;
+  final bool running;
+  final String? errorMsg;
+  final List<String> listeners;
+  final List<AstralNode> nodes;
+
+  const AstralNetworkSnapshot({
+    required this.instanceId,
+    required this.devName,
+    required this.running,
+    this.errorMsg,
+    required this.listeners,
+    required this.nodes,
+  });
+
+  @override
+  int get hashCode =>
+      instanceId.hashCode ^
+      devName.hashCode ^
+      running.hashCode ^
+      errorMsg.hashCode ^
+      listeners.hashCode ^
+      nodes.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralNetworkSnapshot &&
+          runtimeType == other.runtimeType &&
+          instanceId == other.instanceId &&
+          devName == other.devName &&
+          running == other.running &&
+          errorMsg == other.errorMsg &&
+          listeners == other.listeners &&
+          nodes == other.nodes;
+}
+
+class AstralNode {
+  final int peerId;
+  final bool isLocal;
+  final String hostname;
+  final String version;
+  final String? ipv4;
+  final String? ipv6;
+  final List<String> proxyCidrs;
+  final String udpNatType;
+  final String tcpNatType;
+  final int nextHopPeerId;
+  final int cost;
+  final double latencyMs;
+  final double lossRate;
+  final List<String> tunnelTypes;
+  final BigInt rxBytes;
+  final BigInt txBytes;
+  final List<AstralConnection> connections;
+  final List<AstralHop> hops;
+
+  const AstralNode({
+    required this.peerId,
+    required this.isLocal,
+    required this.hostname,
+    required this.version,
+    this.ipv4,
+    this.ipv6,
+    required this.proxyCidrs,
+    required this.udpNatType,
+    required this.tcpNatType,
+    required this.nextHopPeerId,
+    required this.cost,
+    required this.latencyMs,
+    required this.lossRate,
+    required this.tunnelTypes,
+    required this.rxBytes,
+    required this.txBytes,
+    required this.connections,
+    required this.hops,
+  });
+
+  @override
+  int get hashCode =>
+      peerId.hashCode ^
+      isLocal.hashCode ^
+      hostname.hashCode ^
+      version.hashCode ^
+      ipv4.hashCode ^
+      ipv6.hashCode ^
+      proxyCidrs.hashCode ^
+      udpNatType.hashCode ^
+      tcpNatType.hashCode ^
+      nextHopPeerId.hashCode ^
+      cost.hashCode ^
+      latencyMs.hashCode ^
+      lossRate.hashCode ^
+      tunnelTypes.hashCode ^
+      rxBytes.hashCode ^
+      txBytes.hashCode ^
+      connections.hashCode ^
+      hops.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AstralNode &&
+          runtimeType == other.runtimeType &&
+          peerId == other.peerId &&
+          isLocal == other.isLocal &&
+          hostname == other.hostname &&
+          version == other.version &&
+          ipv4 == other.ipv4 &&
+          ipv6 == other.ipv6 &&
+          proxyCidrs == other.proxyCidrs &&
+          udpNatType == other.udpNatType &&
+          tcpNatType == other.tcpNatType &&
+          nextHopPeerId == other.nextHopPeerId &&
+          cost == other.cost &&
+          latencyMs == other.latencyMs &&
+          lossRate == other.lossRate &&
+          tunnelTypes == other.tunnelTypes &&
+          rxBytes == other.rxBytes &&
+          txBytes == other.txBytes &&
+          connections == other.connections &&
+          hops == other.hops;
+}
 
 class FlagsC {
   final String defaultProtocol;
   final String devName;
   final bool enableEncryption;
   final bool enableIpv6;
+  final bool dhcpIpv6;
   final int mtu;
   final bool latencyFirst;
   final bool enableExitNode;
@@ -92,6 +309,7 @@ class FlagsC {
   final bool disableUdpHolePunching;
   final bool multiThread;
   final int dataCompressAlgo;
+  final int dataCompressLevel;
   final bool bindDevice;
   final bool enableKcpProxy;
   final bool disableKcpInput;
@@ -107,6 +325,7 @@ class FlagsC {
     required this.devName,
     required this.enableEncryption,
     required this.enableIpv6,
+    required this.dhcpIpv6,
     required this.mtu,
     required this.latencyFirst,
     required this.enableExitNode,
@@ -118,6 +337,7 @@ class FlagsC {
     required this.disableUdpHolePunching,
     required this.multiThread,
     required this.dataCompressAlgo,
+    required this.dataCompressLevel,
     required this.bindDevice,
     required this.enableKcpProxy,
     required this.disableKcpInput,
@@ -135,6 +355,7 @@ class FlagsC {
       devName.hashCode ^
       enableEncryption.hashCode ^
       enableIpv6.hashCode ^
+      dhcpIpv6.hashCode ^
       mtu.hashCode ^
       latencyFirst.hashCode ^
       enableExitNode.hashCode ^
@@ -146,6 +367,7 @@ class FlagsC {
       disableUdpHolePunching.hashCode ^
       multiThread.hashCode ^
       dataCompressAlgo.hashCode ^
+      dataCompressLevel.hashCode ^
       bindDevice.hashCode ^
       enableKcpProxy.hashCode ^
       disableKcpInput.hashCode ^
@@ -165,6 +387,7 @@ class FlagsC {
           devName == other.devName &&
           enableEncryption == other.enableEncryption &&
           enableIpv6 == other.enableIpv6 &&
+          dhcpIpv6 == other.dhcpIpv6 &&
           mtu == other.mtu &&
           latencyFirst == other.latencyFirst &&
           enableExitNode == other.enableExitNode &&
@@ -176,6 +399,7 @@ class FlagsC {
           disableUdpHolePunching == other.disableUdpHolePunching &&
           multiThread == other.multiThread &&
           dataCompressAlgo == other.dataCompressAlgo &&
+          dataCompressLevel == other.dataCompressLevel &&
           bindDevice == other.bindDevice &&
           enableKcpProxy == other.enableKcpProxy &&
           disableKcpInput == other.disableKcpInput &&
@@ -210,154 +434,3 @@ class Forward {
           dstAddr == other.dstAddr &&
           proto == other.proto;
 }
-
-class KVNetworkStatus {
-  final BigInt totalNodes;
-  final List<KVNodeInfo> nodes;
-
-  const KVNetworkStatus({required this.totalNodes, required this.nodes});
-
-  @override
-  int get hashCode => totalNodes.hashCode ^ nodes.hashCode;
-
-  @override
-  bool operator ==(Object other) =>
-      identical(this, other) ||
-      other is KVNetworkStatus &&
-          runtimeType == other.runtimeType &&
-          totalNodes == other.totalNodes &&
-          nodes == other.nodes;
-}
-
-class KVNodeConnectionStats {
-  final String connType;
-  final BigInt rxBytes;
-  final BigInt txBytes;
-  final BigInt rxPackets;
-  final BigInt txPackets;
-
-  const KVNodeConnectionStats({
-    required this.connType,
-    required this.rxBytes,
-    required this.txBytes,
-    required this.rxPackets,
-    required this.txPackets,
-  });
-
-  @override
-  int get hashCode =>
-      connType.hashCode ^
-      rxBytes.hashCode ^
-      txBytes.hashCode ^
-      rxPackets.hashCode ^
-      txPackets.hashCode;
-
-  @override
-  bool operator ==(Object other) =>
-      identical(this, other) ||
-      other is KVNodeConnectionStats &&
-          runtimeType == other.runtimeType &&
-          connType == other.connType &&
-          rxBytes == other.rxBytes &&
-          txBytes == other.txBytes &&
-          rxPackets == other.rxPackets &&
-          txPackets == other.txPackets;
-}
-
-class KVNodeInfo {
-  final String hostname;
-  final String ipv4;
-  final double latencyMs;
-  final String nat;
-  final List<NodeHopStats> hops;
-  final double lossRate;
-  final List<KVNodeConnectionStats> connections;
-  final String tunnelProto;
-  final String connType;
-  final BigInt rxBytes;
-  final BigInt txBytes;
-  final String version;
-  final int cost;
-
-  const KVNodeInfo({
-    required this.hostname,
-    required this.ipv4,
-    required this.latencyMs,
-    required this.nat,
-    required this.hops,
-    required this.lossRate,
-    required this.connections,
-    required this.tunnelProto,
-    required this.connType,
-    required this.rxBytes,
-    required this.txBytes,
-    required this.version,
-    required this.cost,
-  });
-
-  @override
-  int get hashCode =>
-      hostname.hashCode ^
-      ipv4.hashCode ^
-      latencyMs.hashCode ^
-      nat.hashCode ^
-      hops.hashCode ^
-      lossRate.hashCode ^
-      connections.hashCode ^
-      tunnelProto.hashCode ^
-      connType.hashCode ^
-      rxBytes.hashCode ^
-      txBytes.hashCode ^
-      version.hashCode ^
-      cost.hashCode;
-
-  @override
-  bool operator ==(Object other) =>
-      identical(this, other) ||
-      other is KVNodeInfo &&
-          runtimeType == other.runtimeType &&
-          hostname == other.hostname &&
-          ipv4 == other.ipv4 &&
-          latencyMs == other.latencyMs &&
-          nat == other.nat &&
-          hops == other.hops &&
-          lossRate == other.lossRate &&
-          connections == other.connections &&
-          tunnelProto == other.tunnelProto &&
-          connType == other.connType &&
-          rxBytes == other.rxBytes &&
-          txBytes == other.txBytes &&
-          version == other.version &&
-          cost == other.cost;
-}
-
-class NodeHopStats {
-  final String targetIp;
-  final double latencyMs;
-  final double packetLoss;
-  final String nodeName;
-
-  const NodeHopStats({
-    required this.targetIp,
-    required this.latencyMs,
-    required this.packetLoss,
-    required this.nodeName,
-  });
-
-  @override
-  int get hashCode =>
-      targetIp.hashCode ^
-      latencyMs.hashCode ^
-      packetLoss.hashCode ^
-      nodeName.hashCode;
-
-  @override
-  bool operator ==(Object other) =>
-      identical(this, other) ||
-      other is NodeHopStats &&
-          runtimeType == other.runtimeType &&
-          targetIp == other.targetIp &&
-          latencyMs == other.latencyMs &&
-          packetLoss == other.packetLoss &&
-          nodeName == other.nodeName;
-}
@@ -4,9 +4,13 @@
 // ignore_for_file: unused_import, unused_element, unnecessary_import, duplicate_ignore, invalid_use_of_internal_member, annotate_overrides, non_constant_identifier_names, curly_braces_in_flow_control_structures, prefer_const_literals_to_create_immutables, unused_field
 
 import 'api/astral_wfp.dart';
+import 'api/bandwidth.dart';
+import 'api/events.dart';
 import 'api/firewall.dart';
 import 'api/hops.dart';
 import 'api/nt.dart';
+import 'api/port_forward.dart';
+import 'api/secret_rotation.dart';
 import 'api/simple.dart';
 import 'api/utils.dart';
 import 'dart:async';
@@ -68,7 +72,7 @@ class RustLib extends BaseEntrypoint<RustLibApi, RustLibApiImpl, RustLibWire> {
   String get codegenVersion => '2.10.0';
 
   @override
-  int get rustContentHash => 294099415;
+  int get rustContentHash => 1904519848;
 
   static const kDefaultExternalLibraryLoaderConfig =
       ExternalLibraryLoaderConfig(
@@ -266,11 +270,18 @@ abstract class RustLibApi extends BaseApi {
 
   Future<WfpController> crateApiAstralWfpWfpControllerNew();
 
+  Future<void> crateApiPortForwardAddPortForward({
+    required String instanceId,
+    required Forward forward,
+  });
+
   Future<bool> crateApiUtilsCheckSudo();
 
-  Future<void> crateApiSimpleCloseServer();
+  Future<void> crateApiSimpleCloseAllServers();
 
-  Future<JoinHandleResultString> crateApiSimpleCreateServer({
+  Future<void> crateApiSimpleCloseServer({required String instanceId});
+
+  Future<String> crateApiSimpleCreateServer({
     required String username,
     required bool enableDhcp,
     required String specifiedIp,
@@ -289,25 +300,56 @@ abstract class RustLibApi extends BaseApi {
 
   Future<bool> crateApiFirewallGetFirewallStatus({required int profileIndex});
 
-  Future<List<String>> crateApiSimpleGetIps();
+  Future<List<String>> crateApiSimpleGetIps({required String instanceId});
 
-  Future<KVNetworkStatus> crateApiSimpleGetNetworkStatus();
+  Future<AstralNetworkSnapshot> crateApiSimpleGetNetworkSnapshot({
+    required String instanceId,
+  });
 
   Future<String?> crateApiNtGetNtPath({required String dosPath});
 
-  Future<List<PeerRoutePair>> crateApiSimpleGetPeerRoutePairs();
-
-  Future<String> crateApiSimpleGetRunningInfo();
-
-  Future<JoinHandle> crateApiSimpleHandleEvent({
-    required EventBusSubscriber events,
+  Future<AstralSecretRotationStatus>
+  crateApiSecretRotationGetSecretRotationStatus({
+    required String instanceId,
   });
 
   Future<void> crateApiSimpleInitApp();
 
   Future<bool> crateApiSimpleIsEasytierRunning();
 
-  Future<void> crateApiSimpleSendUdpToLocalhost({required String message});
+  Future<bool> crateApiSimpleIsInstanceRunning({required String instanceId});
+
+  Future<List<AstralBandwidthLimit>> crateApiBandwidthListBandwidthLimits({
+    required String instanceId,
+  });
+
+  Future<List<String>> crateApiSimpleListInstances();
+
+  Future<List<AstralInterface>> crateApiHopsListNetworkInterfaces();
+
+  Future<List<AstralPortForwardStatus>> crateApiPortForwardListPortForwards({
+    required String instanceId,
+  });
+
+  Future<void> crateApiHopsPreferInterfaceForBroadcast({
+    required String interfaceName,
+  });
+
+  Future<void> crateApiBandwidthRemoveBandwidthLimit({
+    required String instanceId,
+    int? peerId,
+    String? cidr,
+  });
+
+  Future<void> crateApiPortForwardRemovePortForward({
+    required String instanceId,
+    required Forward forward,
+  });
+
+  Future<void> crateApiBandwidthSetBandwidthLimit({
+    required String instanceId,
+    required AstralBandwidthLimit limit,
+  });
 
   Future<void> crateApiFirewallSetFirewallStatus({
     required int profileIndex,
@@ -319,18 +361,20 @@ abstract class RustLibApi extends BaseApi {
     required int metric,
   });
 
-  Future<void> crateApiSimpleSetTunFd({required int fd});
-
-  Future<Uint16List> crateApiAstralWfpToWideString({required String s});
+  Future<void> crateApiSimpleSetTunFd({
+    required String instanceId,
+    required int fd,
+  });
 
-  RustArcIncrementStrongCountFnType
-  get rust_arc_increment_strong_count_EventBusSubscriber;
+  Future<BigInt> crateApiSecretRotationStartSecretRotation({
+    required String instanceId,
+    required String networkSecret,
+    required int gracePeriodSec,
+  });
 
-  RustArcDecrementStrongCountFnType
-  get rust_arc_decrement_strong_count_EventBusSubscriber;
+  Stream<AstralEvent> crateApiEventsSubscribeEvents();
 
-  CrossPlatformFinalizerArg
-  get rust_arc_decrement_strong_count_EventBusSubscriberPtr;
+  Future<Uint16List> crateApiAstralWfpToWideString({required String s});
 
   RustArcIncrementStrongCountFnType
   get rust_arc_increment_strong_count_FilterRule;
@@ -360,32 +404,6 @@ abstract class RustLibApi extends BaseApi {
 
   CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_IpNetworkPtr;
 
-  RustArcIncrementStrongCountFnType
-  get rust_arc_increment_strong_count_JoinHandle;
-
-  RustArcDecrementStrongCountFnType
-  get rust_arc_decrement_strong_count_JoinHandle;
-
-  CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_JoinHandlePtr;
-
-  RustArcIncrementStrongCountFnType
-  get rust_arc_increment_strong_count_JoinHandleResultString;
-
-  RustArcDecrementStrongCountFnType
-  get rust_arc_decrement_strong_count_JoinHandleResultString;
-
-  CrossPlatformFinalizerArg
-  get rust_arc_decrement_strong_count_JoinHandleResultStringPtr;
-
-  RustArcIncrementStrongCountFnType
-  get rust_arc_increment_strong_count_PeerRoutePair;
-
-  RustArcDecrementStrongCountFnType
-  get rust_arc_decrement_strong_count_PeerRoutePair;
-
-  CrossPlatformFinalizerArg
-  get rust_arc_decrement_strong_count_PeerRoutePairPtr;
-
   RustArcIncrementStrongCountFnType
   get rust_arc_increment_strong_count_WfpController;
 
@@ -1851,11 +1869,16 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       const TaskConstMeta(debugName: "WfpController_new", argNames: []);
 
   @override
-  Future<bool> crateApiUtilsCheckSudo() {
+  Future<void> crateApiPortForwardAddPortForward({
+    required String instanceId,
+    required Forward forward,
+  }) {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
           final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(instanceId, serializer);
+          sse_encode_box_autoadd_forward(forward, serializer);
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
@@ -1863,6 +1886,36 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
             port: port_,
           );
         },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_String,
+        ),
+        constMeta: kCrateApiPortForwardAddPortForwardConstMeta,
+        argValues: [instanceId, forward],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiPortForwardAddPortForwardConstMeta =>
+      const TaskConstMeta(
+        debugName: "add_port_forward",
+        argNames: ["instanceId", "forward"],
+      );
+
+  @override
+  Future<bool> crateApiUtilsCheckSudo() {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 43,
+            port: port_,
+          );
+        },
         codec: SseCodec(
           decodeSuccessData: sse_decode_bool,
           decodeErrorData: null,
@@ -1878,7 +1931,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       const TaskConstMeta(debugName: "check_sudo", argNames: []);
 
   @override
-  Future<void> crateApiSimpleCloseServer() {
+  Future<void> crateApiSimpleCloseAllServers() {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
@@ -1886,7 +1939,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 43,
+            funcId: 44,
             port: port_,
           );
         },
@@ -1894,18 +1947,46 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           decodeSuccessData: sse_decode_unit,
           decodeErrorData: null,
         ),
-        constMeta: kCrateApiSimpleCloseServerConstMeta,
+        constMeta: kCrateApiSimpleCloseAllServersConstMeta,
         argValues: [],
         apiImpl: this,
       ),
     );
   }
 
+  TaskConstMeta get kCrateApiSimpleCloseAllServersConstMeta =>
+      const TaskConstMeta(debugName: "close_all_servers", argNames: []);
+
+  @override
+  Future<void> crateApiSimpleCloseServer({required String instanceId}) {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(instanceId, serializer);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 45,
+            port: port_,
+          );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_String,
+        ),
+        constMeta: kCrateApiSimpleCloseServerConstMeta,
+        argValues: [instanceId],
+        apiImpl: this,
+      ),
+    );
+  }
+
   TaskConstMeta get kCrateApiSimpleCloseServerConstMeta =>
-      const TaskConstMeta(debugName: "close_server", argNames: []);
+      const TaskConstMeta(debugName: "close_server", argNames: ["instanceId"]);
 
   @override
-  Future<JoinHandleResultString> crateApiSimpleCreateServer({
+  Future<String> crateApiSimpleCreateServer({
     required String username,
     required bool enableDhcp,
     required String specifiedIp,
@@ -1934,14 +2015,13 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 44,
+            funcId: 46,
             port: port_,
           );
         },
         codec: SseCodec(
-          decodeSuccessData:
-              sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString,
-          decodeErrorData: null,
+          decodeSuccessData: sse_decode_String,
+          decodeErrorData: sse_decode_String,
         ),
         constMeta: kCrateApiSimpleCreateServerConstMeta,
         argValues: [
@@ -1986,7 +2066,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 45,
+            funcId: 47,
             port: port_,
           );
         },
@@ -2013,7 +2093,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 46,
+            funcId: 48,
             port: port_,
           );
         },
@@ -2044,7 +2124,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 47,
+            funcId: 49,
             port: port_,
           );
         },
@@ -2066,15 +2146,16 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       );
 
   @override
-  Future<List<String>> crateApiSimpleGetIps() {
+  Future<List<String>> crateApiSimpleGetIps({required String instanceId}) {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
           final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(instanceId, serializer);
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 48,
+            funcId: 50,
             port: port_,
           );
         },
@@ -2083,41 +2164,47 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           decodeErrorData: null,
         ),
         constMeta: kCrateApiSimpleGetIpsConstMeta,
-        argValues: [],
+        argValues: [instanceId],
         apiImpl: this,
       ),
     );
   }
 
   TaskConstMeta get kCrateApiSimpleGetIpsConstMeta =>
-      const TaskConstMeta(debugName: "get_ips", argNames: []);
+      const TaskConstMeta(debugName: "get_ips", argNames: ["instanceId"]);
 
   @override
-  Future<KVNetworkStatus> crateApiSimpleGetNetworkStatus() {
+  Future<AstralNetworkSnapshot> crateApiSimpleGetNetworkSnapshot({
+    required String instanceId,
+  }) {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
           final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(instanceId, serializer);
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 49,
+            funcId: 51,
             port: port_,
           );
         },
         codec: SseCodec(
-          decodeSuccessData: sse_decode_kv_network_status,
-          decodeErrorData: null,
+          decodeSuccessData: sse_decode_astral_network_snapshot,
+          decodeErrorData: sse_decode_String,
         ),
-        constMeta: kCrateApiSimpleGetNetworkStatusConstMeta,
-        argValues: [],
+        constMeta: kCrateApiSimpleGetNetworkSnapshotConstMeta,
+        argValues: [instanceId],
         apiImpl: this,
       ),
     );
   }
 
-  TaskConstMeta get kCrateApiSimpleGetNetworkStatusConstMeta =>
-      const TaskConstMeta(debugName: "get_network_status", argNames: []);
+  TaskConstMeta get kCrateApiSimpleGetNetworkSnapshotConstMeta =>
+      const TaskConstMeta(
+        debugName: "get_network_snapshot",
+        argNames: ["instanceId"],
+      );
 
   @override
   Future<String?> crateApiNtGetNtPath({required String dosPath}) {
@@ -2129,7 +2216,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 50,
+            funcId: 52,
             port: port_,
           );
         },
@@ -2148,35 +2235,41 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       const TaskConstMeta(debugName: "get_nt_path", argNames: ["dosPath"]);
 
   @override
-  Future<List<PeerRoutePair>> crateApiSimpleGetPeerRoutePairs() {
+  Future<AstralSecretRotationStatus>
+  crateApiSecretRotationGetSecretRotationStatus({
+    required String instanceId,
+  }) {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
           final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(instanceId, serializer);
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 51,
+            funcId: 53,
             port: port_,
           );
         },
         codec: SseCodec(
-          decodeSuccessData:
-              sse_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair,
+          decodeSuccessData: sse_decode_astral_secret_rotation_status,
           decodeErrorData: sse_decode_String,
         ),
-        constMeta: kCrateApiSimpleGetPeerRoutePairsConstMeta,
-        argValues: [],
+        constMeta: kCrateApiSecretRotationGetSecretRotationStatusConstMeta,
+        argValues: [instanceId],
         apiImpl: this,
       ),
     );
   }
 
-  TaskConstMeta get kCrateApiSimpleGetPeerRoutePairsConstMeta =>
-      const TaskConstMeta(debugName: "get_peer_route_pairs", argNames: []);
+  TaskConstMeta get kCrateApiSecretRotationGetSecretRotationStatusConstMeta =>
+      const TaskConstMeta(
+        debugName: "get_secret_rotation_status",
+        argNames: ["instanceId"],
+      );
 
   @override
-  Future<String> crateApiSimpleGetRunningInfo() {
+  Future<void> crateApiSimpleInitApp() {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
@@ -2184,87 +2277,117 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 52,
+            funcId: 54,
             port: port_,
           );
         },
         codec: SseCodec(
-          decodeSuccessData: sse_decode_String,
+          decodeSuccessData: sse_decode_unit,
           decodeErrorData: null,
         ),
-        constMeta: kCrateApiSimpleGetRunningInfoConstMeta,
+        constMeta: kCrateApiSimpleInitAppConstMeta,
         argValues: [],
         apiImpl: this,
       ),
     );
   }
 
-  TaskConstMeta get kCrateApiSimpleGetRunningInfoConstMeta =>
-      const TaskConstMeta(debugName: "get_running_info", argNames: []);
+  TaskConstMeta get kCrateApiSimpleInitAppConstMeta =>
+      const TaskConstMeta(debugName: "init_app", argNames: []);
 
   @override
-  Future<JoinHandle> crateApiSimpleHandleEvent({
-    required EventBusSubscriber events,
-  }) {
+  Future<bool> crateApiSimpleIsEasytierRunning() {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
           final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-            events,
+          pdeCallFfi(
+            generalizedFrbRustBinding,
             serializer,
+            funcId: 55,
+            port: port_,
           );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_bool,
+          decodeErrorData: null,
+        ),
+        constMeta: kCrateApiSimpleIsEasytierRunningConstMeta,
+        argValues: [],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiSimpleIsEasytierRunningConstMeta =>
+      const TaskConstMeta(debugName: "is_easytier_running", argNames: []);
+
+  @override
+  Future<bool> crateApiSimpleIsInstanceRunning({required String instanceId}) {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(instanceId, serializer);
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 53,
+            funcId: 56,
             port: port_,
           );
         },
         codec: SseCodec(
-          decodeSuccessData:
-              sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle,
+          decodeSuccessData: sse_decode_bool,
           decodeErrorData: null,
         ),
-        constMeta: kCrateApiSimpleHandleEventConstMeta,
-        argValues: [events],
+        constMeta: kCrateApiSimpleIsInstanceRunningConstMeta,
+        argValues: [instanceId],
         apiImpl: this,
       ),
     );
   }
 
-  TaskConstMeta get kCrateApiSimpleHandleEventConstMeta =>
-      const TaskConstMeta(debugName: "handle_event", argNames: ["events"]);
+  TaskConstMeta get kCrateApiSimpleIsInstanceRunningConstMeta =>
+      const TaskConstMeta(
+        debugName: "is_instance_running",
+        argNames: ["instanceId"],
+      );
 
   @override
-  Future<void> crateApiSimpleInitApp() {
+  Future<List<AstralBandwidthLimit>> crateApiBandwidthListBandwidthLimits({
+    required String instanceId,
+  }) {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
           final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(instanceId, serializer);
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 54,
+            funcId: 57,
             port: port_,
           );
         },
         codec: SseCodec(
-          decodeSuccessData: sse_decode_unit,
-          decodeErrorData: null,
+          decodeSuccessData: sse_decode_list_astral_bandwidth_limit,
+          decodeErrorData: sse_decode_String,
         ),
-        constMeta: kCrateApiSimpleInitAppConstMeta,
-        argValues: [],
+        constMeta: kCrateApiBandwidthListBandwidthLimitsConstMeta,
+        argValues: [instanceId],
         apiImpl: this,
       ),
     );
   }
 
-  TaskConstMeta get kCrateApiSimpleInitAppConstMeta =>
-      const TaskConstMeta(debugName: "init_app", argNames: []);
+  TaskConstMeta get kCrateApiBandwidthListBandwidthLimitsConstMeta =>
+      const TaskConstMeta(
+        debugName: "list_bandwidth_limits",
+        argNames: ["instanceId"],
+      );
 
   @override
-  Future<bool> crateApiSimpleIsEasytierRunning() {
+  Future<List<String>> crateApiSimpleListInstances() {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
@@ -2272,105 +2395,97 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 55,
+            funcId: 58,
             port: port_,
           );
         },
         codec: SseCodec(
-          decodeSuccessData: sse_decode_bool,
+          decodeSuccessData: sse_decode_list_String,
           decodeErrorData: null,
         ),
-        constMeta: kCrateApiSimpleIsEasytierRunningConstMeta,
+        constMeta: kCrateApiSimpleListInstancesConstMeta,
         argValues: [],
         apiImpl: this,
       ),
     );
   }
 
-  TaskConstMeta get kCrateApiSimpleIsEasytierRunningConstMeta =>
-      const TaskConstMeta(debugName: "is_easytier_running", argNames: []);
+  TaskConstMeta get kCrateApiSimpleListInstancesConstMeta =>
+      const TaskConstMeta(debugName: "list_instances", argNames: []);
 
   @override
-  Future<void> crateApiSimpleSendUdpToLocalhost({required String message}) {
+  Future<List<AstralInterface>> crateApiHopsListNetworkInterfaces() {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
           final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_String(message, serializer);
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 56,
+            funcId: 59,
             port: port_,
           );
         },
         codec: SseCodec(
-          decodeSuccessData: sse_decode_unit,
-          decodeErrorData: sse_decode_String,
+          decodeSuccessData: sse_decode_list_astral_interface,
+          decodeErrorData: sse_decode_AnyhowException,
         ),
-        constMeta: kCrateApiSimpleSendUdpToLocalhostConstMeta,
-        argValues: [message],
+        constMeta: kCrateApiHopsListNetworkInterfacesConstMeta,
+        argValues: [],
         apiImpl: this,
       ),
     );
   }
 
-  TaskConstMeta get kCrateApiSimpleSendUdpToLocalhostConstMeta =>
-      const TaskConstMeta(
-        debugName: "send_udp_to_localhost",
-        argNames: ["message"],
-      );
+  TaskConstMeta get kCrateApiHopsListNetworkInterfacesConstMeta =>
+      const TaskConstMeta(debugName: "list_network_interfaces", argNames: []);
 
   @override
-  Future<void> crateApiFirewallSetFirewallStatus({
-    required int profileIndex,
-    required bool enable,
+  Future<List<AstralPortForwardStatus>> crateApiPortForwardListPortForwards({
+    required String instanceId,
   }) {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
           final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_u_32(profileIndex, serializer);
-          sse_encode_bool(enable, serializer);
+          sse_encode_String(instanceId, serializer);
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 57,
+            funcId: 60,
             port: port_,
           );
         },
         codec: SseCodec(
-          decodeSuccessData: sse_decode_unit,
-          decodeErrorData: sse_decode_AnyhowException,
+          decodeSuccessData: sse_decode_list_astral_port_forward_status,
+          decodeErrorData: sse_decode_String,
         ),
-        constMeta: kCrateApiFirewallSetFirewallStatusConstMeta,
-        argValues: [profileIndex, enable],
+        constMeta: kCrateApiPortForwardListPortForwardsConstMeta,
+        argValues: [instanceId],
         apiImpl: this,
       ),
     );
   }
 
-  TaskConstMeta get kCrateApiFirewallSetFirewallStatusConstMeta =>
+  TaskConstMeta get kCrateApiPortForwardListPortForwardsConstMeta =>
       const TaskConstMeta(
-        debugName: "set_firewall_status",
-        argNames: ["profileIndex", "enable"],
+        debugName: "list_port_forwards",
+        argNames: ["instanceId"],
       );
 
   @override
-  Future<void> crateApiHopsSetInterfaceMetric({
+  Future<void> crateApiHopsPreferInterfaceForBroadcast({
     required String interfaceName,
-    required int metric,
   }) {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
           final serializer = SseSerializer(generalizedFrbRustBinding);
           sse_encode_String(interfaceName, serializer);
-          sse_encode_u_32(metric, serializer);
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 58,
+            funcId: 61,
             port: port_,
           );
         },
@@ -2378,30 +2493,36 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           decodeSuccessData: sse_decode_unit,
           decodeErrorData: sse_decode_AnyhowException,
         ),
-        constMeta: kCrateApiHopsSetInterfaceMetricConstMeta,
-        argValues: [interfaceName, metric],
+        constMeta: kCrateApiHopsPreferInterfaceForBroadcastConstMeta,
+        argValues: [interfaceName],
         apiImpl: this,
       ),
     );
   }
 
-  TaskConstMeta get kCrateApiHopsSetInterfaceMetricConstMeta =>
+  TaskConstMeta get kCrateApiHopsPreferInterfaceForBroadcastConstMeta =>
       const TaskConstMeta(
-        debugName: "set_interface_metric",
-        argNames: ["interfaceName", "metric"],
+        debugName: "prefer_interface_for_broadcast",
+        argNames: ["interfaceName"],
       );
 
   @override
-  Future<void> crateApiSimpleSetTunFd({required int fd}) {
+  Future<void> crateApiBandwidthRemoveBandwidthLimit({
+    required String instanceId,
+    int? peerId,
+    String? cidr,
+  }) {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
           final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_i_32(fd, serializer);
+          sse_encode_String(instanceId, serializer);
+          sse_encode_opt_box_autoadd_u_32(peerId, serializer);
+          sse_encode_opt_String(cidr, serializer);
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 59,
+            funcId: 62,
             port: port_,
           );
         },
@@ -2409,59 +2530,297 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           decodeSuccessData: sse_decode_unit,
           decodeErrorData: sse_decode_String,
         ),
-        constMeta: kCrateApiSimpleSetTunFdConstMeta,
-        argValues: [fd],
+        constMeta: kCrateApiBandwidthRemoveBandwidthLimitConstMeta,
+        argValues: [instanceId, peerId, cidr],
         apiImpl: this,
       ),
     );
   }
 
-  TaskConstMeta get kCrateApiSimpleSetTunFdConstMeta =>
-      const TaskConstMeta(debugName: "set_tun_fd", argNames: ["fd"]);
+  TaskConstMeta get kCrateApiBandwidthRemoveBandwidthLimitConstMeta =>
+      const TaskConstMeta(
+        debugName: "remove_bandwidth_limit",
+        argNames: ["instanceId", "peerId", "cidr"],
+      );
 
   @override
-  Future<Uint16List> crateApiAstralWfpToWideString({required String s}) {
+  Future<void> crateApiPortForwardRemovePortForward({
+    required String instanceId,
+    required Forward forward,
+  }) {
     return handler.executeNormal(
       NormalTask(
         callFfi: (port_) {
           final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_String(s, serializer);
+          sse_encode_String(instanceId, serializer);
+          sse_encode_box_autoadd_forward(forward, serializer);
           pdeCallFfi(
             generalizedFrbRustBinding,
             serializer,
-            funcId: 60,
+            funcId: 63,
             port: port_,
           );
         },
         codec: SseCodec(
-          decodeSuccessData: sse_decode_list_prim_u_16_strict,
-          decodeErrorData: null,
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_String,
         ),
-        constMeta: kCrateApiAstralWfpToWideStringConstMeta,
-        argValues: [s],
+        constMeta: kCrateApiPortForwardRemovePortForwardConstMeta,
+        argValues: [instanceId, forward],
         apiImpl: this,
       ),
     );
   }
 
-  TaskConstMeta get kCrateApiAstralWfpToWideStringConstMeta =>
-      const TaskConstMeta(debugName: "to_wide_string", argNames: ["s"]);
-
-  RustArcIncrementStrongCountFnType
-  get rust_arc_increment_strong_count_EventBusSubscriber =>
-      wire.rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber;
-
-  RustArcDecrementStrongCountFnType
-  get rust_arc_decrement_strong_count_EventBusSubscriber =>
-      wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber;
-
-  RustArcIncrementStrongCountFnType
-  get rust_arc_increment_strong_count_FilterRule =>
-      wire.rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule;
-
-  RustArcDecrementStrongCountFnType
-  get rust_arc_decrement_strong_count_FilterRule =>
-      wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule;
+  TaskConstMeta get kCrateApiPortForwardRemovePortForwardConstMeta =>
+      const TaskConstMeta(
+        debugName: "remove_port_forward",
+        argNames: ["instanceId", "forward"],
+      );
+
+  @override
+  Future<void> crateApiBandwidthSetBandwidthLimit({
+    required String instanceId,
+    required AstralBandwidthLimit limit,
+  }) {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(instanceId, serializer);
+          sse_encode_box_autoadd_astral_bandwidth_limit(limit, serializer);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 64,
+            port: port_,
+          );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_String,
+        ),
+        constMeta: kCrateApiBandwidthSetBandwidthLimitConstMeta,
+        argValues: [instanceId, limit],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiBandwidthSetBandwidthLimitConstMeta =>
+      const TaskConstMeta(
+        debugName: "set_bandwidth_limit",
+        argNames: ["instanceId", "limit"],
+      );
+
+  @override
+  Future<void> crateApiFirewallSetFirewallStatus({
+    required int profileIndex,
+    required bool enable,
+  }) {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_u_32(profileIndex, serializer);
+          sse_encode_bool(enable, serializer);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 65,
+            port: port_,
+          );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        ),
+        constMeta: kCrateApiFirewallSetFirewallStatusConstMeta,
+        argValues: [profileIndex, enable],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiFirewallSetFirewallStatusConstMeta =>
+      const TaskConstMeta(
+        debugName: "set_firewall_status",
+        argNames: ["profileIndex", "enable"],
+      );
+
+  @override
+  Future<void> crateApiHopsSetInterfaceMetric({
+    required String interfaceName,
+    required int metric,
+  }) {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(interfaceName, serializer);
+          sse_encode_u_32(metric, serializer);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 66,
+            port: port_,
+          );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        ),
+        constMeta: kCrateApiHopsSetInterfaceMetricConstMeta,
+        argValues: [interfaceName, metric],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiHopsSetInterfaceMetricConstMeta =>
+      const TaskConstMeta(
+        debugName: "set_interface_metric",
+        argNames: ["interfaceName", "metric"],
+      );
+
+  @override
+  Future<void> crateApiSimpleSetTunFd({
+    required String instanceId,
+    required int fd,
+  }) {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(instanceId, serializer);
+          sse_encode_i_32(fd, serializer);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 67,
+            port: port_,
+          );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_String,
+        ),
+        constMeta: kCrateApiSimpleSetTunFdConstMeta,
+        argValues: [instanceId, fd],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiSimpleSetTunFdConstMeta => const TaskConstMeta(
+    debugName: "set_tun_fd",
+    argNames: ["instanceId", "fd"],
+  );
+
+  @override
+  Future<BigInt> crateApiSecretRotationStartSecretRotation({
+    required String instanceId,
+    required String networkSecret,
+    required int gracePeriodSec,
+  }) {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(instanceId, serializer);
+          sse_encode_String(networkSecret, serializer);
+          sse_encode_u_32(gracePeriodSec, serializer);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 68,
+            port: port_,
+          );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_u_64,
+          decodeErrorData: sse_decode_String,
+        ),
+        constMeta: kCrateApiSecretRotationStartSecretRotationConstMeta,
+        argValues: [instanceId, networkSecret, gracePeriodSec],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiSecretRotationStartSecretRotationConstMeta =>
+      const TaskConstMeta(
+        debugName: "start_secret_rotation",
+        argNames: ["instanceId", "networkSecret", "gracePeriodSec"],
+      );
+
+  @override
+  Stream<AstralEvent> crateApiEventsSubscribeEvents() {
+    final sink = RustStreamSink<AstralEvent>();
+    unawaited(
+      handler.executeNormal(
+        NormalTask(
+          callFfi: (port_) {
+            final serializer = SseSerializer(generalizedFrbRustBinding);
+            sse_encode_StreamSink_astral_event_Sse(sink, serializer);
+            pdeCallFfi(
+              generalizedFrbRustBinding,
+              serializer,
+              funcId: 69,
+              port: port_,
+            );
+          },
+          codec: SseCodec(
+            decodeSuccessData: sse_decode_unit,
+            decodeErrorData: null,
+          ),
+          constMeta: kCrateApiEventsSubscribeEventsConstMeta,
+          argValues: [sink],
+          apiImpl: this,
+        ),
+      ),
+    );
+    return sink.stream;
+  }
+
+  TaskConstMeta get kCrateApiEventsSubscribeEventsConstMeta =>
+      const TaskConstMeta(debugName: "subscribe_events", argNames: ["sink"]);
+
+  @override
+  Future<Uint16List> crateApiAstralWfpToWideString({required String s}) {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(s, serializer);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 70,
+            port: port_,
+          );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_list_prim_u_16_strict,
+          decodeErrorData: null,
+        ),
+        constMeta: kCrateApiAstralWfpToWideStringConstMeta,
+        argValues: [s],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiAstralWfpToWideStringConstMeta =>
+      const TaskConstMeta(debugName: "to_wide_string", argNames: ["s"]);
+
+  RustArcIncrementStrongCountFnType
+  get rust_arc_increment_strong_count_FilterRule =>
+      wire.rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule;
+
+  RustArcDecrementStrongCountFnType
+  get rust_arc_decrement_strong_count_FilterRule =>
+      wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule;
 
   RustArcIncrementStrongCountFnType get rust_arc_increment_strong_count_Guid =>
       wire.rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerGUID;
@@ -2485,30 +2844,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   get rust_arc_decrement_strong_count_IpNetwork =>
       wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpNetwork;
 
-  RustArcIncrementStrongCountFnType
-  get rust_arc_increment_strong_count_JoinHandle =>
-      wire.rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle;
-
-  RustArcDecrementStrongCountFnType
-  get rust_arc_decrement_strong_count_JoinHandle =>
-      wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle;
-
-  RustArcIncrementStrongCountFnType
-  get rust_arc_increment_strong_count_JoinHandleResultString =>
-      wire.rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString;
-
-  RustArcDecrementStrongCountFnType
-  get rust_arc_decrement_strong_count_JoinHandleResultString =>
-      wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString;
-
-  RustArcIncrementStrongCountFnType
-  get rust_arc_increment_strong_count_PeerRoutePair =>
-      wire.rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair;
-
-  RustArcDecrementStrongCountFnType
-  get rust_arc_decrement_strong_count_PeerRoutePair =>
-      wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair;
-
   RustArcIncrementStrongCountFnType
   get rust_arc_increment_strong_count_WfpController =>
       wire.rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController;
@@ -2523,15 +2858,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return AnyhowException(raw as String);
   }
 
-  @protected
-  EventBusSubscriber
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    dynamic raw,
-  ) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return EventBusSubscriberImpl.frbInternalDcoDecode(raw as List<dynamic>);
-  }
-
   @protected
   FilterRule
   dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -2559,35 +2885,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return IpNetworkImpl.frbInternalDcoDecode(raw as List<dynamic>);
   }
 
-  @protected
-  JoinHandle
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    dynamic raw,
-  ) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return JoinHandleImpl.frbInternalDcoDecode(raw as List<dynamic>);
-  }
-
-  @protected
-  JoinHandleResultString
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    dynamic raw,
-  ) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return JoinHandleResultStringImpl.frbInternalDcoDecode(
-      raw as List<dynamic>,
-    );
-  }
-
-  @protected
-  PeerRoutePair
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    dynamic raw,
-  ) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return PeerRoutePairImpl.frbInternalDcoDecode(raw as List<dynamic>);
-  }
-
   @protected
   WfpController
   dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
@@ -2660,15 +2957,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return WfpControllerImpl.frbInternalDcoDecode(raw as List<dynamic>);
   }
 
-  @protected
-  EventBusSubscriber
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    dynamic raw,
-  ) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return EventBusSubscriberImpl.frbInternalDcoDecode(raw as List<dynamic>);
-  }
-
   @protected
   FilterRule
   dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -2706,53 +2994,278 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 
   @protected
-  JoinHandle
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
+  WfpController
+  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
     dynamic raw,
   ) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return JoinHandleImpl.frbInternalDcoDecode(raw as List<dynamic>);
+    return WfpControllerImpl.frbInternalDcoDecode(raw as List<dynamic>);
   }
 
   @protected
-  JoinHandleResultString
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
+  RustStreamSink<AstralEvent> dco_decode_StreamSink_astral_event_Sse(
     dynamic raw,
   ) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return JoinHandleResultStringImpl.frbInternalDcoDecode(
-      raw as List<dynamic>,
+    throw UnimplementedError();
+  }
+
+  @protected
+  String dco_decode_String(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return raw as String;
+  }
+
+  @protected
+  AstralBandwidthLimit dco_decode_astral_bandwidth_limit(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 4)
+      throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
+    return AstralBandwidthLimit(
+      peerId: dco_decode_opt_box_autoadd_u_32(arr[0]),
+      cidr: dco_decode_opt_String(arr[1]),
+      uploadBps: dco_decode_opt_box_autoadd_u_64(arr[2]),
+      downloadBps: dco_decode_opt_box_autoadd_u_64(arr[3]),
+    );
+  }
+
+  @protected
+  AstralConnection dco_decode_astral_connection(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 11)
+      throw Exception('unexpected arr length: expect 11 but see ${arr.length}');
+    return AstralConnection(
+      connId: dco_decode_String(arr[0]),
+      tunnelType: dco_decode_String(arr[1]),
+      localAddr: dco_decode_String(arr[2]),
+      remoteAddr: dco_decode_String(arr[3]),
+      isClient: dco_decode_bool(arr[4]),
+      latencyMs: dco_decode_f_64(arr[5]),
+      lossRate: dco_decode_f_32(arr[6]),
+      rxBytes: dco_decode_u_64(arr[7]),
+      txBytes: dco_decode_u_64(arr[8]),
+      rxPackets: dco_decode_u_64(arr[9]),
+      txPackets: dco_decode_u_64(arr[10]),
+    );
+  }
+
+  @protected
+  AstralEvent dco_decode_astral_event(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 19)
+      throw Exception('unexpected arr length: expect 19 but see ${arr.length}');
+    return AstralEvent(
+      instanceId: dco_decode_String(arr[0]),
+      timestampMs: dco_decode_i_64(arr[1]),
+      kind: dco_decode_astral_event_kind(arr[2]),
+      peerId: dco_decode_opt_box_autoadd_u_32(arr[3]),
+      conn: dco_decode_opt_box_autoadd_astral_peer_conn(arr[4]),
+      devName: dco_decode_opt_String(arr[5]),
+      url: dco_decode_opt_String(arr[6]),
+      ipVersion: dco_decode_opt_String(arr[7]),
+      localAddr: dco_decode_opt_String(arr[8]),
+      remoteAddr: dco_decode_opt_String(arr[9]),
+      portal: dco_decode_opt_String(arr[10]),
+      clientAddr: dco_decode_opt_String(arr[11]),
+      oldAddr: dco_decode_opt_String(arr[12]),
+      newAddr: dco_decode_opt_String(arr[13]),
+      addr: dco_decode_opt_String(arr[14]),
+      forward: dco_decode_opt_box_autoadd_astral_port_forward(arr[15]),
+      rotationId: dco_decode_opt_box_autoadd_u_64(arr[16]),
+      count: dco_decode_opt_box_autoadd_u_64(arr[17]),
+      error: dco_decode_opt_String(arr[18]),
+    );
+  }
+
+  @protected
+  AstralEventKind dco_decode_astral_event_kind(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return AstralEventKind.values[raw as int];
+  }
+
+  @protected
+  AstralHop dco_decode_astral_hop(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 6)
+      throw Exception('unexpected arr length: expect 6 but see ${arr.length}');
+    return AstralHop(
+      peerId: dco_decode_u_32(arr[0]),
+      hostname: dco_decode_String(arr[1]),
+      ipv4: dco_decode_opt_String(arr[2]),
+      ipv6: dco_decode_opt_String(arr[3]),
+      latencyMs: dco_decode_f_64(arr[4]),
+      lossRate: dco_decode_f_32(arr[5]),
     );
   }
 
   @protected
-  PeerRoutePair
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
+  AstralInterface dco_decode_astral_interface(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 6)
+      throw Exception('unexpected arr length: expect 6 but see ${arr.length}');
+    return AstralInterface(
+      index: dco_decode_u_32(arr[0]),
+      name: dco_decode_String(arr[1]),
+      metric: dco_decode_opt_box_autoadd_u_32(arr[2]),
+      mtu: dco_decode_u_32(arr[3]),
+      addresses: dco_decode_list_String(arr[4]),
+      up: dco_decode_bool(arr[5]),
+    );
+  }
+
+  @protected
+  AstralNetworkSnapshot dco_decode_astral_network_snapshot(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 6)
+      throw Exception('unexpected arr length: expect 6 but see ${arr.length}');
+    return AstralNetworkSnapshot(
+      instanceId: dco_decode_String(arr[0]),
+      devName: dco_decode_String(arr[1]),
+      running: dco_decode_bool(arr[2]),
+      errorMsg: dco_decode_opt_String(arr[3]),
+      listeners: dco_decode_list_String(arr[4]),
+      nodes: dco_decode_list_astral_node(arr[5]),
+    );
+  }
+
+  @protected
+  AstralNode dco_decode_astral_node(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 18)
+      throw Exception('unexpected arr length: expect 18 but see ${arr.length}');
+    return AstralNode(
+      peerId: dco_decode_u_32(arr[0]),
+      isLocal: dco_decode_bool(arr[1]),
+      hostname: dco_decode_String(arr[2]),
+      version: dco_decode_String(arr[3]),
+      ipv4: dco_decode_opt_String(arr[4]),
+      ipv6: dco_decode_opt_String(arr[5]),
+      proxyCidrs: dco_decode_list_String(arr[6]),
+      udpNatType: dco_decode_String(arr[7]),
+      tcpNatType: dco_decode_String(arr[8]),
+      nextHopPeerId: dco_decode_u_32(arr[9]),
+      cost: dco_decode_i_32(arr[10]),
+      latencyMs: dco_decode_f_64(arr[11]),
+      lossRate: dco_decode_f_32(arr[12]),
+      tunnelTypes: dco_decode_list_String(arr[13]),
+      rxBytes: dco_decode_u_64(arr[14]),
+      txBytes: dco_decode_u_64(arr[15]),
+      connections: dco_decode_list_astral_connection(arr[16]),
+      hops: dco_decode_list_astral_hop(arr[17]),
+    );
+  }
+
+  @protected
+  AstralPeerConn dco_decode_astral_peer_conn(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 8)
+      throw Exception('unexpected arr length: expect 8 but see ${arr.length}');
+    return AstralPeerConn(
+      connId: dco_decode_String(arr[0]),
+      myPeerId: dco_decode_u_32(arr[1]),
+      peerId: dco_decode_u_32(arr[2]),
+      tunnelType: dco_decode_String(arr[3]),
+      localAddr: dco_decode_String(arr[4]),
+      remoteAddr: dco_decode_String(arr[5]),
+      isClient: dco_decode_bool(arr[6]),
+      networkName: dco_decode_String(arr[7]),
+    );
+  }
+
+  @protected
+  AstralPortForward dco_decode_astral_port_forward(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 3)
+      throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+    return AstralPortForward(
+      bindAddr: dco_decode_String(arr[0]),
+      dstAddr: dco_decode_String(arr[1]),
+      proto: dco_decode_String(arr[2]),
+    );
+  }
+
+  @protected
+  AstralPortForwardStatus dco_decode_astral_port_forward_status(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 7)
+      throw Exception('unexpected arr length: expect 7 but see ${arr.length}');
+    return AstralPortForwardStatus(
+      bindAddr: dco_decode_String(arr[0]),
+      dstAddr: dco_decode_String(arr[1]),
+      proto: dco_decode_String(arr[2]),
+      bound: dco_decode_bool(arr[3]),
+      error: dco_decode_opt_String(arr[4]),
+      activeConnections: dco_decode_u_32(arr[5]),
+      totalConnections: dco_decode_u_64(arr[6]),
+    );
+  }
+
+  @protected
+  AstralSecretRotationPeer dco_decode_astral_secret_rotation_peer(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 5)
+      throw Exception('unexpected arr length: expect 5 but see ${arr.length}');
+    return AstralSecretRotationPeer(
+      peerId: dco_decode_u_32(arr[0]),
+      hostname: dco_decode_String(arr[1]),
+      prepared: dco_decode_bool(arr[2]),
+      committed: dco_decode_bool(arr[3]),
+      error: dco_decode_opt_String(arr[4]),
+    );
+  }
+
+  @protected
+  AstralSecretRotationStatus dco_decode_astral_secret_rotation_status(
     dynamic raw,
   ) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return PeerRoutePairImpl.frbInternalDcoDecode(raw as List<dynamic>);
+    final arr = raw as List<dynamic>;
+    if (arr.length != 5)
+      throw Exception('unexpected arr length: expect 5 but see ${arr.length}');
+    return AstralSecretRotationStatus(
+      rotationId: dco_decode_u_64(arr[0]),
+      initiatedLocally: dco_decode_bool(arr[1]),
+      committed: dco_decode_bool(arr[2]),
+      gracePeriodRemainingSec: dco_decode_u_32(arr[3]),
+      peers: dco_decode_list_astral_secret_rotation_peer(arr[4]),
+    );
   }
 
   @protected
-  WfpController
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+  bool dco_decode_bool(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return raw as bool;
+  }
+
+  @protected
+  AstralBandwidthLimit dco_decode_box_autoadd_astral_bandwidth_limit(
     dynamic raw,
   ) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return WfpControllerImpl.frbInternalDcoDecode(raw as List<dynamic>);
+    return dco_decode_astral_bandwidth_limit(raw);
   }
 
   @protected
-  String dco_decode_String(dynamic raw) {
+  AstralPeerConn dco_decode_box_autoadd_astral_peer_conn(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return raw as String;
+    return dco_decode_astral_peer_conn(raw);
   }
 
   @protected
-  bool dco_decode_bool(dynamic raw) {
+  AstralPortForward dco_decode_box_autoadd_astral_port_forward(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return raw as bool;
+    return dco_decode_astral_port_forward(raw);
   }
 
   @protected
@@ -2761,6 +3274,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return dco_decode_flags_c(raw);
   }
 
+  @protected
+  Forward dco_decode_box_autoadd_forward(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return dco_decode_forward(raw);
+  }
+
   @protected
   Protocol dco_decode_box_autoadd_protocol(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -2785,6 +3304,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return raw as int;
   }
 
+  @protected
+  BigInt dco_decode_box_autoadd_u_64(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return dco_decode_u_64(raw);
+  }
+
   @protected
   Direction dco_decode_direction(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -2813,33 +3338,35 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   FlagsC dco_decode_flags_c(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
     final arr = raw as List<dynamic>;
-    if (arr.length != 24)
-      throw Exception('unexpected arr length: expect 24 but see ${arr.length}');
+    if (arr.length != 26)
+      throw Exception('unexpected arr length: expect 26 but see ${arr.length}');
     return FlagsC(
       defaultProtocol: dco_decode_String(arr[0]),
       devName: dco_decode_String(arr[1]),
       enableEncryption: dco_decode_bool(arr[2]),
       enableIpv6: dco_decode_bool(arr[3]),
-      mtu: dco_decode_u_32(arr[4]),
-      latencyFirst: dco_decode_bool(arr[5]),
-      enableExitNode: dco_decode_bool(arr[6]),
-      noTun: dco_decode_bool(arr[7]),
-      useSmoltcp: dco_decode_bool(arr[8]),
-      relayNetworkWhitelist: dco_decode_String(arr[9]),
-      disableP2P: dco_decode_bool(arr[10]),
-      relayAllPeerRpc: dco_decode_bool(arr[11]),
-      disableUdpHolePunching: dco_decode_bool(arr[12]),
-      multiThread: dco_decode_bool(arr[13]),
-      dataCompressAlgo: dco_decode_i_32(arr[14]),
-      bindDevice: dco_decode_bool(arr[15]),
-      enableKcpProxy: dco_decode_bool(arr[16]),
-      disableKcpInput: dco_decode_bool(arr[17]),
-      disableRelayKcp: dco_decode_bool(arr[18]),
-      proxyForwardBySystem: dco_decode_bool(arr[19]),
-      acceptDns: dco_decode_bool(arr[20]),
-      privateMode: dco_decode_bool(arr[21]),
-      enableQuicProxy: dco_decode_bool(arr[22]),
-      disableQuicInput: dco_decode_bool(arr[23]),
+      dhcpIpv6: dco_decode_bool(arr[4]),
+      mtu: dco_decode_u_32(arr[5]),
+      latencyFirst: dco_decode_bool(arr[6]),
+      enableExitNode: dco_decode_bool(arr[7]),
+      noTun: dco_decode_bool(arr[8]),
+      useSmoltcp: dco_decode_bool(arr[9]),
+      relayNetworkWhitelist: dco_decode_String(arr[10]),
+      disableP2P: dco_decode_bool(arr[11]),
+      relayAllPeerRpc: dco_decode_bool(arr[12]),
+      disableUdpHolePunching: dco_decode_bool(arr[13]),
+      multiThread: dco_decode_bool(arr[14]),
+      dataCompressAlgo: dco_decode_i_32(arr[15]),
+      dataCompressLevel: dco_decode_i_32(arr[16]),
+      bindDevice: dco_decode_bool(arr[17]),
+      enableKcpProxy: dco_decode_bool(arr[18]),
+      disableKcpInput: dco_decode_bool(arr[19]),
+      disableRelayKcp: dco_decode_bool(arr[20]),
+      proxyForwardBySystem: dco_decode_bool(arr[21]),
+      acceptDns: dco_decode_bool(arr[22]),
+      privateMode: dco_decode_bool(arr[23]),
+      enableQuicProxy: dco_decode_bool(arr[24]),
+      disableQuicInput: dco_decode_bool(arr[25]),
     );
   }
 
@@ -2863,113 +3390,88 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 
   @protected
-  KVNetworkStatus dco_decode_kv_network_status(dynamic raw) {
+  PlatformInt64 dco_decode_i_64(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    final arr = raw as List<dynamic>;
-    if (arr.length != 2)
-      throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
-    return KVNetworkStatus(
-      totalNodes: dco_decode_usize(arr[0]),
-      nodes: dco_decode_list_kv_node_info(arr[1]),
-    );
+    return dcoDecodeI64(raw);
   }
 
   @protected
-  KVNodeConnectionStats dco_decode_kv_node_connection_stats(dynamic raw) {
+  List<FilterRule>
+  dco_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
+    dynamic raw,
+  ) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    final arr = raw as List<dynamic>;
-    if (arr.length != 5)
-      throw Exception('unexpected arr length: expect 5 but see ${arr.length}');
-    return KVNodeConnectionStats(
-      connType: dco_decode_String(arr[0]),
-      rxBytes: dco_decode_u_64(arr[1]),
-      txBytes: dco_decode_u_64(arr[2]),
-      rxPackets: dco_decode_u_64(arr[3]),
-      txPackets: dco_decode_u_64(arr[4]),
-    );
+    return (raw as List<dynamic>)
+        .map(
+          dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule,
+        )
+        .toList();
   }
 
   @protected
-  KVNodeInfo dco_decode_kv_node_info(dynamic raw) {
+  List<String> dco_decode_list_String(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    final arr = raw as List<dynamic>;
-    if (arr.length != 13)
-      throw Exception('unexpected arr length: expect 13 but see ${arr.length}');
-    return KVNodeInfo(
-      hostname: dco_decode_String(arr[0]),
-      ipv4: dco_decode_String(arr[1]),
-      latencyMs: dco_decode_f_64(arr[2]),
-      nat: dco_decode_String(arr[3]),
-      hops: dco_decode_list_node_hop_stats(arr[4]),
-      lossRate: dco_decode_f_32(arr[5]),
-      connections: dco_decode_list_kv_node_connection_stats(arr[6]),
-      tunnelProto: dco_decode_String(arr[7]),
-      connType: dco_decode_String(arr[8]),
-      rxBytes: dco_decode_u_64(arr[9]),
-      txBytes: dco_decode_u_64(arr[10]),
-      version: dco_decode_String(arr[11]),
-      cost: dco_decode_i_32(arr[12]),
-    );
+    return (raw as List<dynamic>).map(dco_decode_String).toList();
   }
 
   @protected
-  List<FilterRule>
-  dco_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
+  List<AstralBandwidthLimit> dco_decode_list_astral_bandwidth_limit(
     dynamic raw,
   ) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
     return (raw as List<dynamic>)
-        .map(
-          dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule,
-        )
+        .map(dco_decode_astral_bandwidth_limit)
         .toList();
   }
 
   @protected
-  List<PeerRoutePair>
-  dco_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    dynamic raw,
-  ) {
+  List<AstralConnection> dco_decode_list_astral_connection(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return (raw as List<dynamic>).map(dco_decode_astral_connection).toList();
+  }
+
+  @protected
+  List<AstralHop> dco_decode_list_astral_hop(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return (raw as List<dynamic>)
-        .map(
-          dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair,
-        )
-        .toList();
+    return (raw as List<dynamic>).map(dco_decode_astral_hop).toList();
   }
 
   @protected
-  List<String> dco_decode_list_String(dynamic raw) {
+  List<AstralInterface> dco_decode_list_astral_interface(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return (raw as List<dynamic>).map(dco_decode_String).toList();
+    return (raw as List<dynamic>).map(dco_decode_astral_interface).toList();
   }
 
   @protected
-  List<Forward> dco_decode_list_forward(dynamic raw) {
+  List<AstralNode> dco_decode_list_astral_node(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return (raw as List<dynamic>).map(dco_decode_forward).toList();
+    return (raw as List<dynamic>).map(dco_decode_astral_node).toList();
   }
 
   @protected
-  List<KVNodeConnectionStats> dco_decode_list_kv_node_connection_stats(
+  List<AstralPortForwardStatus> dco_decode_list_astral_port_forward_status(
     dynamic raw,
   ) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
     return (raw as List<dynamic>)
-        .map(dco_decode_kv_node_connection_stats)
+        .map(dco_decode_astral_port_forward_status)
         .toList();
   }
 
   @protected
-  List<KVNodeInfo> dco_decode_list_kv_node_info(dynamic raw) {
+  List<AstralSecretRotationPeer> dco_decode_list_astral_secret_rotation_peer(
+    dynamic raw,
+  ) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return (raw as List<dynamic>).map(dco_decode_kv_node_info).toList();
+    return (raw as List<dynamic>)
+        .map(dco_decode_astral_secret_rotation_peer)
+        .toList();
   }
 
   @protected
-  List<NodeHopStats> dco_decode_list_node_hop_stats(dynamic raw) {
+  List<Forward> dco_decode_list_forward(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return (raw as List<dynamic>).map(dco_decode_node_hop_stats).toList();
+    return (raw as List<dynamic>).map(dco_decode_forward).toList();
   }
 
   @protected
@@ -2997,23 +3499,23 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 
   @protected
-  NodeHopStats dco_decode_node_hop_stats(dynamic raw) {
+  String? dco_decode_opt_String(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    final arr = raw as List<dynamic>;
-    if (arr.length != 4)
-      throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
-    return NodeHopStats(
-      targetIp: dco_decode_String(arr[0]),
-      latencyMs: dco_decode_f_64(arr[1]),
-      packetLoss: dco_decode_f_32(arr[2]),
-      nodeName: dco_decode_String(arr[3]),
-    );
+    return raw == null ? null : dco_decode_String(raw);
   }
 
   @protected
-  String? dco_decode_opt_String(dynamic raw) {
+  AstralPeerConn? dco_decode_opt_box_autoadd_astral_peer_conn(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
-    return raw == null ? null : dco_decode_String(raw);
+    return raw == null ? null : dco_decode_box_autoadd_astral_peer_conn(raw);
+  }
+
+  @protected
+  AstralPortForward? dco_decode_opt_box_autoadd_astral_port_forward(
+    dynamic raw,
+  ) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return raw == null ? null : dco_decode_box_autoadd_astral_port_forward(raw);
   }
 
   @protected
@@ -3040,6 +3542,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return raw == null ? null : dco_decode_box_autoadd_u_32(raw);
   }
 
+  @protected
+  BigInt? dco_decode_opt_box_autoadd_u_64(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return raw == null ? null : dco_decode_box_autoadd_u_64(raw);
+  }
+
   @protected
   Protocol dco_decode_protocol(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -3109,18 +3617,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return AnyhowException(inner);
   }
 
-  @protected
-  EventBusSubscriber
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    SseDeserializer deserializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return EventBusSubscriberImpl.frbInternalSseDecode(
-      sse_decode_usize(deserializer),
-      sse_decode_i_32(deserializer),
-    );
-  }
-
   @protected
   FilterRule
   sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -3157,42 +3653,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     );
   }
 
-  @protected
-  JoinHandle
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    SseDeserializer deserializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return JoinHandleImpl.frbInternalSseDecode(
-      sse_decode_usize(deserializer),
-      sse_decode_i_32(deserializer),
-    );
-  }
-
-  @protected
-  JoinHandleResultString
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    SseDeserializer deserializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return JoinHandleResultStringImpl.frbInternalSseDecode(
-      sse_decode_usize(deserializer),
-      sse_decode_i_32(deserializer),
-    );
-  }
-
-  @protected
-  PeerRoutePair
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    SseDeserializer deserializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return PeerRoutePairImpl.frbInternalSseDecode(
-      sse_decode_usize(deserializer),
-      sse_decode_i_32(deserializer),
-    );
-  }
-
   @protected
   WfpController
   sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
@@ -3289,18 +3749,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     );
   }
 
-  @protected
-  EventBusSubscriber
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    SseDeserializer deserializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return EventBusSubscriberImpl.frbInternalSseDecode(
-      sse_decode_usize(deserializer),
-      sse_decode_i_32(deserializer),
-    );
-  }
-
   @protected
   FilterRule
   sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -3350,58 +3798,331 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 
   @protected
-  JoinHandle
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
+  WfpController
+  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
     SseDeserializer deserializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    return JoinHandleImpl.frbInternalSseDecode(
+    return WfpControllerImpl.frbInternalSseDecode(
       sse_decode_usize(deserializer),
       sse_decode_i_32(deserializer),
     );
   }
 
   @protected
-  JoinHandleResultString
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
+  RustStreamSink<AstralEvent> sse_decode_StreamSink_astral_event_Sse(
     SseDeserializer deserializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    return JoinHandleResultStringImpl.frbInternalSseDecode(
-      sse_decode_usize(deserializer),
-      sse_decode_i_32(deserializer),
+    throw UnimplementedError('Unreachable ()');
+  }
+
+  @protected
+  String sse_decode_String(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var inner = sse_decode_list_prim_u_8_strict(deserializer);
+    return utf8.decoder.convert(inner);
+  }
+
+  @protected
+  AstralBandwidthLimit sse_decode_astral_bandwidth_limit(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_peerId = sse_decode_opt_box_autoadd_u_32(deserializer);
+    var var_cidr = sse_decode_opt_String(deserializer);
+    var var_uploadBps = sse_decode_opt_box_autoadd_u_64(deserializer);
+    var var_downloadBps = sse_decode_opt_box_autoadd_u_64(deserializer);
+    return AstralBandwidthLimit(
+      peerId: var_peerId,
+      cidr: var_cidr,
+      uploadBps: var_uploadBps,
+      downloadBps: var_downloadBps,
+    );
+  }
+
+  @protected
+  AstralConnection sse_decode_astral_connection(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_connId = sse_decode_String(deserializer);
+    var var_tunnelType = sse_decode_String(deserializer);
+    var var_localAddr = sse_decode_String(deserializer);
+    var var_remoteAddr = sse_decode_String(deserializer);
+    var var_isClient = sse_decode_bool(deserializer);
+    var var_latencyMs = sse_decode_f_64(deserializer);
+    var var_lossRate = sse_decode_f_32(deserializer);
+    var var_rxBytes = sse_decode_u_64(deserializer);
+    var var_txBytes = sse_decode_u_64(deserializer);
+    var var_rxPackets = sse_decode_u_64(deserializer);
+    var var_txPackets = sse_decode_u_64(deserializer);
+    return AstralConnection(
+      connId: var_connId,
+      tunnelType: var_tunnelType,
+      localAddr: var_localAddr,
+      remoteAddr: var_remoteAddr,
+      isClient: var_isClient,
+      latencyMs: var_latencyMs,
+      lossRate: var_lossRate,
+      rxBytes: var_rxBytes,
+      txBytes: var_txBytes,
+      rxPackets: var_rxPackets,
+      txPackets: var_txPackets,
+    );
+  }
+
+  @protected
+  AstralEvent sse_decode_astral_event(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_instanceId = sse_decode_String(deserializer);
+    var var_timestampMs = sse_decode_i_64(deserializer);
+    var var_kind = sse_decode_astral_event_kind(deserializer);
+    var var_peerId = sse_decode_opt_box_autoadd_u_32(deserializer);
+    var var_conn = sse_decode_opt_box_autoadd_astral_peer_conn(deserializer);
+    var var_devName = sse_decode_opt_String(deserializer);
+    var var_url = sse_decode_opt_String(deserializer);
+    var var_ipVersion = sse_decode_opt_String(deserializer);
+    var var_localAddr = sse_decode_opt_String(deserializer);
+    var var_remoteAddr = sse_decode_opt_String(deserializer);
+    var var_portal = sse_decode_opt_String(deserializer);
+    var var_clientAddr = sse_decode_opt_String(deserializer);
+    var var_oldAddr = sse_decode_opt_String(deserializer);
+    var var_newAddr = sse_decode_opt_String(deserializer);
+    var var_addr = sse_decode_opt_String(deserializer);
+    var var_forward = sse_decode_opt_box_autoadd_astral_port_forward(
+      deserializer,
+    );
+    var var_rotationId = sse_decode_opt_box_autoadd_u_64(deserializer);
+    var var_count = sse_decode_opt_box_autoadd_u_64(deserializer);
+    var var_error = sse_decode_opt_String(deserializer);
+    return AstralEvent(
+      instanceId: var_instanceId,
+      timestampMs: var_timestampMs,
+      kind: var_kind,
+      peerId: var_peerId,
+      conn: var_conn,
+      devName: var_devName,
+      url: var_url,
+      ipVersion: var_ipVersion,
+      localAddr: var_localAddr,
+      remoteAddr: var_remoteAddr,
+      portal: var_portal,
+      clientAddr: var_clientAddr,
+      oldAddr: var_oldAddr,
+      newAddr: var_newAddr,
+      addr: var_addr,
+      forward: var_forward,
+      rotationId: var_rotationId,
+      count: var_count,
+      error: var_error,
+    );
+  }
+
+  @protected
+  AstralEventKind sse_decode_astral_event_kind(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var inner = sse_decode_i_32(deserializer);
+    return AstralEventKind.values[inner];
+  }
+
+  @protected
+  AstralHop sse_decode_astral_hop(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_peerId = sse_decode_u_32(deserializer);
+    var var_hostname = sse_decode_String(deserializer);
+    var var_ipv4 = sse_decode_opt_String(deserializer);
+    var var_ipv6 = sse_decode_opt_String(deserializer);
+    var var_latencyMs = sse_decode_f_64(deserializer);
+    var var_lossRate = sse_decode_f_32(deserializer);
+    return AstralHop(
+      peerId: var_peerId,
+      hostname: var_hostname,
+      ipv4: var_ipv4,
+      ipv6: var_ipv6,
+      latencyMs: var_latencyMs,
+      lossRate: var_lossRate,
+    );
+  }
+
+  @protected
+  AstralInterface sse_decode_astral_interface(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_index = sse_decode_u_32(deserializer);
+    var var_name = sse_decode_String(deserializer);
+    var var_metric = sse_decode_opt_box_autoadd_u_32(deserializer);
+    var var_mtu = sse_decode_u_32(deserializer);
+    var var_addresses = sse_decode_list_String(deserializer);
+    var var_up = sse_decode_bool(deserializer);
+    return AstralInterface(
+      index: var_index,
+      name: var_name,
+      metric: var_metric,
+      mtu: var_mtu,
+      addresses: var_addresses,
+      up: var_up,
     );
   }
 
   @protected
-  PeerRoutePair
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
+  AstralNetworkSnapshot sse_decode_astral_network_snapshot(
     SseDeserializer deserializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    return PeerRoutePairImpl.frbInternalSseDecode(
-      sse_decode_usize(deserializer),
-      sse_decode_i_32(deserializer),
+    var var_instanceId = sse_decode_String(deserializer);
+    var var_devName = sse_decode_String(deserializer);
+    var var_running = sse_decode_bool(deserializer);
+    var var_errorMsg = sse_decode_opt_String(deserializer);
+    var var_listeners = sse_decode_list_String(deserializer);
+    var var_nodes = sse_decode_list_astral_node(deserializer);
+    return AstralNetworkSnapshot(
+      instanceId: var_instanceId,
+      devName: var_devName,
+      running: var_running,
+      errorMsg: var_errorMsg,
+      listeners: var_listeners,
+      nodes: var_nodes,
     );
   }
 
   @protected
-  WfpController
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+  AstralNode sse_decode_astral_node(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_peerId = sse_decode_u_32(deserializer);
+    var var_isLocal = sse_decode_bool(deserializer);
+    var var_hostname = sse_decode_String(deserializer);
+    var var_version = sse_decode_String(deserializer);
+    var var_ipv4 = sse_decode_opt_String(deserializer);
+    var var_ipv6 = sse_decode_opt_String(deserializer);
+    var var_proxyCidrs = sse_decode_list_String(deserializer);
+    var var_udpNatType = sse_decode_String(deserializer);
+    var var_tcpNatType = sse_decode_String(deserializer);
+    var var_nextHopPeerId = sse_decode_u_32(deserializer);
+    var var_cost = sse_decode_i_32(deserializer);
+    var var_latencyMs = sse_decode_f_64(deserializer);
+    var var_lossRate = sse_decode_f_32(deserializer);
+    var var_tunnelTypes = sse_decode_list_String(deserializer);
+    var var_rxBytes = sse_decode_u_64(deserializer);
+    var var_txBytes = sse_decode_u_64(deserializer);
+    var var_connections = sse_decode_list_astral_connection(deserializer);
+    var var_hops = sse_decode_list_astral_hop(deserializer);
+    return AstralNode(
+      peerId: var_peerId,
+      isLocal: var_isLocal,
+      hostname: var_hostname,
+      version: var_version,
+      ipv4: var_ipv4,
+      ipv6: var_ipv6,
+      proxyCidrs: var_proxyCidrs,
+      udpNatType: var_udpNatType,
+      tcpNatType: var_tcpNatType,
+      nextHopPeerId: var_nextHopPeerId,
+      cost: var_cost,
+      latencyMs: var_latencyMs,
+      lossRate: var_lossRate,
+      tunnelTypes: var_tunnelTypes,
+      rxBytes: var_rxBytes,
+      txBytes: var_txBytes,
+      connections: var_connections,
+      hops: var_hops,
+    );
+  }
+
+  @protected
+  AstralPeerConn sse_decode_astral_peer_conn(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_connId = sse_decode_String(deserializer);
+    var var_myPeerId = sse_decode_u_32(deserializer);
+    var var_peerId = sse_decode_u_32(deserializer);
+    var var_tunnelType = sse_decode_String(deserializer);
+    var var_localAddr = sse_decode_String(deserializer);
+    var var_remoteAddr = sse_decode_String(deserializer);
+    var var_isClient = sse_decode_bool(deserializer);
+    var var_networkName = sse_decode_String(deserializer);
+    return AstralPeerConn(
+      connId: var_connId,
+      myPeerId: var_myPeerId,
+      peerId: var_peerId,
+      tunnelType: var_tunnelType,
+      localAddr: var_localAddr,
+      remoteAddr: var_remoteAddr,
+      isClient: var_isClient,
+      networkName: var_networkName,
+    );
+  }
+
+  @protected
+  AstralPortForward sse_decode_astral_port_forward(
     SseDeserializer deserializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    return WfpControllerImpl.frbInternalSseDecode(
-      sse_decode_usize(deserializer),
-      sse_decode_i_32(deserializer),
+    var var_bindAddr = sse_decode_String(deserializer);
+    var var_dstAddr = sse_decode_String(deserializer);
+    var var_proto = sse_decode_String(deserializer);
+    return AstralPortForward(
+      bindAddr: var_bindAddr,
+      dstAddr: var_dstAddr,
+      proto: var_proto,
     );
   }
 
   @protected
-  String sse_decode_String(SseDeserializer deserializer) {
+  AstralPortForwardStatus sse_decode_astral_port_forward_status(
+    SseDeserializer deserializer,
+  ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    var inner = sse_decode_list_prim_u_8_strict(deserializer);
-    return utf8.decoder.convert(inner);
+    var var_bindAddr = sse_decode_String(deserializer);
+    var var_dstAddr = sse_decode_String(deserializer);
+    var var_proto = sse_decode_String(deserializer);
+    var var_bound = sse_decode_bool(deserializer);
+    var var_error = sse_decode_opt_String(deserializer);
+    var var_activeConnections = sse_decode_u_32(deserializer);
+    var var_totalConnections = sse_decode_u_64(deserializer);
+    return AstralPortForwardStatus(
+      bindAddr: var_bindAddr,
+      dstAddr: var_dstAddr,
+      proto: var_proto,
+      bound: var_bound,
+      error: var_error,
+      activeConnections: var_activeConnections,
+      totalConnections: var_totalConnections,
+    );
+  }
+
+  @protected
+  AstralSecretRotationPeer sse_decode_astral_secret_rotation_peer(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_peerId = sse_decode_u_32(deserializer);
+    var var_hostname = sse_decode_String(deserializer);
+    var var_prepared = sse_decode_bool(deserializer);
+    var var_committed = sse_decode_bool(deserializer);
+    var var_error = sse_decode_opt_String(deserializer);
+    return AstralSecretRotationPeer(
+      peerId: var_peerId,
+      hostname: var_hostname,
+      prepared: var_prepared,
+      committed: var_committed,
+      error: var_error,
+    );
+  }
+
+  @protected
+  AstralSecretRotationStatus sse_decode_astral_secret_rotation_status(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_rotationId = sse_decode_u_64(deserializer);
+    var var_initiatedLocally = sse_decode_bool(deserializer);
+    var var_committed = sse_decode_bool(deserializer);
+    var var_gracePeriodRemainingSec = sse_decode_u_32(deserializer);
+    var var_peers = sse_decode_list_astral_secret_rotation_peer(deserializer);
+    return AstralSecretRotationStatus(
+      rotationId: var_rotationId,
+      initiatedLocally: var_initiatedLocally,
+      committed: var_committed,
+      gracePeriodRemainingSec: var_gracePeriodRemainingSec,
+      peers: var_peers,
+    );
   }
 
   @protected
@@ -3410,12 +4131,42 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return deserializer.buffer.getUint8() != 0;
   }
 
+  @protected
+  AstralBandwidthLimit sse_decode_box_autoadd_astral_bandwidth_limit(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    return (sse_decode_astral_bandwidth_limit(deserializer));
+  }
+
+  @protected
+  AstralPeerConn sse_decode_box_autoadd_astral_peer_conn(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    return (sse_decode_astral_peer_conn(deserializer));
+  }
+
+  @protected
+  AstralPortForward sse_decode_box_autoadd_astral_port_forward(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    return (sse_decode_astral_port_forward(deserializer));
+  }
+
   @protected
   FlagsC sse_decode_box_autoadd_flags_c(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     return (sse_decode_flags_c(deserializer));
   }
 
+  @protected
+  Forward sse_decode_box_autoadd_forward(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    return (sse_decode_forward(deserializer));
+  }
+
   @protected
   Protocol sse_decode_box_autoadd_protocol(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -3442,6 +4193,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return (sse_decode_u_32(deserializer));
   }
 
+  @protected
+  BigInt sse_decode_box_autoadd_u_64(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    return (sse_decode_u_64(deserializer));
+  }
+
   @protected
   Direction sse_decode_direction(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -3475,6 +4232,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     var var_devName = sse_decode_String(deserializer);
     var var_enableEncryption = sse_decode_bool(deserializer);
     var var_enableIpv6 = sse_decode_bool(deserializer);
+    var var_dhcpIpv6 = sse_decode_bool(deserializer);
     var var_mtu = sse_decode_u_32(deserializer);
     var var_latencyFirst = sse_decode_bool(deserializer);
     var var_enableExitNode = sse_decode_bool(deserializer);
@@ -3486,6 +4244,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     var var_disableUdpHolePunching = sse_decode_bool(deserializer);
     var var_multiThread = sse_decode_bool(deserializer);
     var var_dataCompressAlgo = sse_decode_i_32(deserializer);
+    var var_dataCompressLevel = sse_decode_i_32(deserializer);
     var var_bindDevice = sse_decode_bool(deserializer);
     var var_enableKcpProxy = sse_decode_bool(deserializer);
     var var_disableKcpInput = sse_decode_bool(deserializer);
@@ -3500,6 +4259,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       devName: var_devName,
       enableEncryption: var_enableEncryption,
       enableIpv6: var_enableIpv6,
+      dhcpIpv6: var_dhcpIpv6,
       mtu: var_mtu,
       latencyFirst: var_latencyFirst,
       enableExitNode: var_enableExitNode,
@@ -3511,6 +4271,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       disableUdpHolePunching: var_disableUdpHolePunching,
       multiThread: var_multiThread,
       dataCompressAlgo: var_dataCompressAlgo,
+      dataCompressLevel: var_dataCompressLevel,
       bindDevice: var_bindDevice,
       enableKcpProxy: var_enableKcpProxy,
       disableKcpInput: var_disableKcpInput,
@@ -3529,79 +4290,23 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     var var_bindAddr = sse_decode_String(deserializer);
     var var_dstAddr = sse_decode_String(deserializer);
     var var_proto = sse_decode_String(deserializer);
-    return Forward(
-      bindAddr: var_bindAddr,
-      dstAddr: var_dstAddr,
-      proto: var_proto,
-    );
-  }
-
-  @protected
-  int sse_decode_i_32(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return deserializer.buffer.getInt32();
-  }
-
-  @protected
-  KVNetworkStatus sse_decode_kv_network_status(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    var var_totalNodes = sse_decode_usize(deserializer);
-    var var_nodes = sse_decode_list_kv_node_info(deserializer);
-    return KVNetworkStatus(totalNodes: var_totalNodes, nodes: var_nodes);
+    return Forward(
+      bindAddr: var_bindAddr,
+      dstAddr: var_dstAddr,
+      proto: var_proto,
+    );
   }
 
   @protected
-  KVNodeConnectionStats sse_decode_kv_node_connection_stats(
-    SseDeserializer deserializer,
-  ) {
+  int sse_decode_i_32(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    var var_connType = sse_decode_String(deserializer);
-    var var_rxBytes = sse_decode_u_64(deserializer);
-    var var_txBytes = sse_decode_u_64(deserializer);
-    var var_rxPackets = sse_decode_u_64(deserializer);
-    var var_txPackets = sse_decode_u_64(deserializer);
-    return KVNodeConnectionStats(
-      connType: var_connType,
-      rxBytes: var_rxBytes,
-      txBytes: var_txBytes,
-      rxPackets: var_rxPackets,
-      txPackets: var_txPackets,
-    );
+    return deserializer.buffer.getInt32();
   }
 
   @protected
-  KVNodeInfo sse_decode_kv_node_info(SseDeserializer deserializer) {
+  PlatformInt64 sse_decode_i_64(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    var var_hostname = sse_decode_String(deserializer);
-    var var_ipv4 = sse_decode_String(deserializer);
-    var var_latencyMs = sse_decode_f_64(deserializer);
-    var var_nat = sse_decode_String(deserializer);
-    var var_hops = sse_decode_list_node_hop_stats(deserializer);
-    var var_lossRate = sse_decode_f_32(deserializer);
-    var var_connections = sse_decode_list_kv_node_connection_stats(
-      deserializer,
-    );
-    var var_tunnelProto = sse_decode_String(deserializer);
-    var var_connType = sse_decode_String(deserializer);
-    var var_rxBytes = sse_decode_u_64(deserializer);
-    var var_txBytes = sse_decode_u_64(deserializer);
-    var var_version = sse_decode_String(deserializer);
-    var var_cost = sse_decode_i_32(deserializer);
-    return KVNodeInfo(
-      hostname: var_hostname,
-      ipv4: var_ipv4,
-      latencyMs: var_latencyMs,
-      nat: var_nat,
-      hops: var_hops,
-      lossRate: var_lossRate,
-      connections: var_connections,
-      tunnelProto: var_tunnelProto,
-      connType: var_connType,
-      rxBytes: var_rxBytes,
-      txBytes: var_txBytes,
-      version: var_version,
-      cost: var_cost,
-    );
+    return deserializer.buffer.getPlatformInt64();
   }
 
   @protected
@@ -3624,84 +4329,119 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 
   @protected
-  List<PeerRoutePair>
-  sse_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
+  List<String> sse_decode_list_String(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    var len_ = sse_decode_i_32(deserializer);
+    var ans_ = <String>[];
+    for (var idx_ = 0; idx_ < len_; ++idx_) {
+      ans_.add(sse_decode_String(deserializer));
+    }
+    return ans_;
+  }
+
+  @protected
+  List<AstralBandwidthLimit> sse_decode_list_astral_bandwidth_limit(
     SseDeserializer deserializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
 
     var len_ = sse_decode_i_32(deserializer);
-    var ans_ = <PeerRoutePair>[];
+    var ans_ = <AstralBandwidthLimit>[];
     for (var idx_ = 0; idx_ < len_; ++idx_) {
-      ans_.add(
-        sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-          deserializer,
-        ),
-      );
+      ans_.add(sse_decode_astral_bandwidth_limit(deserializer));
     }
     return ans_;
   }
 
   @protected
-  List<String> sse_decode_list_String(SseDeserializer deserializer) {
+  List<AstralConnection> sse_decode_list_astral_connection(
+    SseDeserializer deserializer,
+  ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
 
     var len_ = sse_decode_i_32(deserializer);
-    var ans_ = <String>[];
+    var ans_ = <AstralConnection>[];
     for (var idx_ = 0; idx_ < len_; ++idx_) {
-      ans_.add(sse_decode_String(deserializer));
+      ans_.add(sse_decode_astral_connection(deserializer));
     }
     return ans_;
   }
 
   @protected
-  List<Forward> sse_decode_list_forward(SseDeserializer deserializer) {
+  List<AstralHop> sse_decode_list_astral_hop(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
 
     var len_ = sse_decode_i_32(deserializer);
-    var ans_ = <Forward>[];
+    var ans_ = <AstralHop>[];
     for (var idx_ = 0; idx_ < len_; ++idx_) {
-      ans_.add(sse_decode_forward(deserializer));
+      ans_.add(sse_decode_astral_hop(deserializer));
     }
     return ans_;
   }
 
   @protected
-  List<KVNodeConnectionStats> sse_decode_list_kv_node_connection_stats(
+  List<AstralInterface> sse_decode_list_astral_interface(
     SseDeserializer deserializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
 
     var len_ = sse_decode_i_32(deserializer);
-    var ans_ = <KVNodeConnectionStats>[];
+    var ans_ = <AstralInterface>[];
+    for (var idx_ = 0; idx_ < len_; ++idx_) {
+      ans_.add(sse_decode_astral_interface(deserializer));
+    }
+    return ans_;
+  }
+
+  @protected
+  List<AstralNode> sse_decode_list_astral_node(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    var len_ = sse_decode_i_32(deserializer);
+    var ans_ = <AstralNode>[];
     for (var idx_ = 0; idx_ < len_; ++idx_) {
-      ans_.add(sse_decode_kv_node_connection_stats(deserializer));
+      ans_.add(sse_decode_astral_node(deserializer));
     }
     return ans_;
   }
 
   @protected
-  List<KVNodeInfo> sse_decode_list_kv_node_info(SseDeserializer deserializer) {
+  List<AstralPortForwardStatus> sse_decode_list_astral_port_forward_status(
+    SseDeserializer deserializer,
+  ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
 
     var len_ = sse_decode_i_32(deserializer);
-    var ans_ = <KVNodeInfo>[];
+    var ans_ = <AstralPortForwardStatus>[];
     for (var idx_ = 0; idx_ < len_; ++idx_) {
-      ans_.add(sse_decode_kv_node_info(deserializer));
+      ans_.add(sse_decode_astral_port_forward_status(deserializer));
     }
     return ans_;
   }
 
   @protected
-  List<NodeHopStats> sse_decode_list_node_hop_stats(
+  List<AstralSecretRotationPeer> sse_decode_list_astral_secret_rotation_peer(
     SseDeserializer deserializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
 
     var len_ = sse_decode_i_32(deserializer);
-    var ans_ = <NodeHopStats>[];
+    var ans_ = <AstralSecretRotationPeer>[];
+    for (var idx_ = 0; idx_ < len_; ++idx_) {
+      ans_.add(sse_decode_astral_secret_rotation_peer(deserializer));
+    }
+    return ans_;
+  }
+
+  @protected
+  List<Forward> sse_decode_list_forward(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    var len_ = sse_decode_i_32(deserializer);
+    var ans_ = <Forward>[];
     for (var idx_ = 0; idx_ < len_; ++idx_) {
-      ans_.add(sse_decode_node_hop_stats(deserializer));
+      ans_.add(sse_decode_forward(deserializer));
     }
     return ans_;
   }
@@ -3742,26 +4482,37 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 
   @protected
-  NodeHopStats sse_decode_node_hop_stats(SseDeserializer deserializer) {
+  String? sse_decode_opt_String(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    var var_targetIp = sse_decode_String(deserializer);
-    var var_latencyMs = sse_decode_f_64(deserializer);
-    var var_packetLoss = sse_decode_f_32(deserializer);
-    var var_nodeName = sse_decode_String(deserializer);
-    return NodeHopStats(
-      targetIp: var_targetIp,
-      latencyMs: var_latencyMs,
-      packetLoss: var_packetLoss,
-      nodeName: var_nodeName,
-    );
+
+    if (sse_decode_bool(deserializer)) {
+      return (sse_decode_String(deserializer));
+    } else {
+      return null;
+    }
   }
 
   @protected
-  String? sse_decode_opt_String(SseDeserializer deserializer) {
+  AstralPeerConn? sse_decode_opt_box_autoadd_astral_peer_conn(
+    SseDeserializer deserializer,
+  ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
 
     if (sse_decode_bool(deserializer)) {
-      return (sse_decode_String(deserializer));
+      return (sse_decode_box_autoadd_astral_peer_conn(deserializer));
+    } else {
+      return null;
+    }
+  }
+
+  @protected
+  AstralPortForward? sse_decode_opt_box_autoadd_astral_port_forward(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    if (sse_decode_bool(deserializer)) {
+      return (sse_decode_box_autoadd_astral_port_forward(deserializer));
     } else {
       return null;
     }
@@ -3813,6 +4564,17 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     }
   }
 
+  @protected
+  BigInt? sse_decode_opt_box_autoadd_u_64(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    if (sse_decode_bool(deserializer)) {
+      return (sse_decode_box_autoadd_u_64(deserializer));
+    } else {
+      return null;
+    }
+  }
+
   @protected
   Protocol sse_decode_protocol(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -3880,19 +4642,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_String(self.message, serializer);
   }
 
-  @protected
-  void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    EventBusSubscriber self,
-    SseSerializer serializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as EventBusSubscriberImpl).frbInternalSseEncode(move: true),
-      serializer,
-    );
-  }
-
   @protected
   void
   sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -3934,59 +4683,59 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
 
   @protected
   void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    JoinHandle self,
+  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+    WfpController self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_usize(
-      (self as JoinHandleImpl).frbInternalSseEncode(move: true),
+      (self as WfpControllerImpl).frbInternalSseEncode(move: true),
       serializer,
     );
   }
 
   @protected
   void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    JoinHandleResultString self,
+  sse_encode_Auto_RefMut_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
+    FilterRule self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_usize(
-      (self as JoinHandleResultStringImpl).frbInternalSseEncode(move: true),
+      (self as FilterRuleImpl).frbInternalSseEncode(move: false),
       serializer,
     );
   }
 
   @protected
   void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    PeerRoutePair self,
+  sse_encode_Auto_RefMut_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpNetwork(
+    IpNetwork self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_usize(
-      (self as PeerRoutePairImpl).frbInternalSseEncode(move: true),
+      (self as IpNetworkImpl).frbInternalSseEncode(move: false),
       serializer,
     );
   }
 
   @protected
   void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+  sse_encode_Auto_RefMut_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
     WfpController self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_usize(
-      (self as WfpControllerImpl).frbInternalSseEncode(move: true),
+      (self as WfpControllerImpl).frbInternalSseEncode(move: false),
       serializer,
     );
   }
 
   @protected
   void
-  sse_encode_Auto_RefMut_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
+  sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
     FilterRule self,
     SseSerializer serializer,
   ) {
@@ -3999,7 +4748,20 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
 
   @protected
   void
-  sse_encode_Auto_RefMut_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpNetwork(
+  sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerGUID(
+    Guid self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_usize(
+      (self as GuidImpl).frbInternalSseEncode(move: false),
+      serializer,
+    );
+  }
+
+  @protected
+  void
+  sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpNetwork(
     IpNetwork self,
     SseSerializer serializer,
   ) {
@@ -4012,7 +4774,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
 
   @protected
   void
-  sse_encode_Auto_RefMut_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+  sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
     WfpController self,
     SseSerializer serializer,
   ) {
@@ -4025,183 +4787,317 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
 
   @protected
   void
-  sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
+  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
     FilterRule self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_usize(
-      (self as FilterRuleImpl).frbInternalSseEncode(move: false),
+      (self as FilterRuleImpl).frbInternalSseEncode(move: null),
+      serializer,
+    );
+  }
+
+  @protected
+  void
+  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerGUID(
+    Guid self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_usize(
+      (self as GuidImpl).frbInternalSseEncode(move: null),
+      serializer,
+    );
+  }
+
+  @protected
+  void
+  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpAddr(
+    IpAddr self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_usize(
+      (self as IpAddrImpl).frbInternalSseEncode(move: null),
+      serializer,
+    );
+  }
+
+  @protected
+  void
+  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpNetwork(
+    IpNetwork self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_usize(
+      (self as IpNetworkImpl).frbInternalSseEncode(move: null),
+      serializer,
+    );
+  }
+
+  @protected
+  void
+  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+    WfpController self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_usize(
+      (self as WfpControllerImpl).frbInternalSseEncode(move: null),
+      serializer,
+    );
+  }
+
+  @protected
+  void sse_encode_StreamSink_astral_event_Sse(
+    RustStreamSink<AstralEvent> self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_String(
+      self.setupAndSerialize(
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_astral_event,
+          decodeErrorData: sse_decode_AnyhowException,
+        ),
+      ),
       serializer,
     );
   }
 
   @protected
-  void
-  sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerGUID(
-    Guid self,
+  void sse_encode_String(String self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_list_prim_u_8_strict(utf8.encoder.convert(self), serializer);
+  }
+
+  @protected
+  void sse_encode_astral_bandwidth_limit(
+    AstralBandwidthLimit self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_opt_box_autoadd_u_32(self.peerId, serializer);
+    sse_encode_opt_String(self.cidr, serializer);
+    sse_encode_opt_box_autoadd_u_64(self.uploadBps, serializer);
+    sse_encode_opt_box_autoadd_u_64(self.downloadBps, serializer);
+  }
+
+  @protected
+  void sse_encode_astral_connection(
+    AstralConnection self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_String(self.connId, serializer);
+    sse_encode_String(self.tunnelType, serializer);
+    sse_encode_String(self.localAddr, serializer);
+    sse_encode_String(self.remoteAddr, serializer);
+    sse_encode_bool(self.isClient, serializer);
+    sse_encode_f_64(self.latencyMs, serializer);
+    sse_encode_f_32(self.lossRate, serializer);
+    sse_encode_u_64(self.rxBytes, serializer);
+    sse_encode_u_64(self.txBytes, serializer);
+    sse_encode_u_64(self.rxPackets, serializer);
+    sse_encode_u_64(self.txPackets, serializer);
+  }
+
+  @protected
+  void sse_encode_astral_event(AstralEvent self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_String(self.instanceId, serializer);
+    sse_encode_i_64(self.timestampMs, serializer);
+    sse_encode_astral_event_kind(self.kind, serializer);
+    sse_encode_opt_box_autoadd_u_32(self.peerId, serializer);
+    sse_encode_opt_box_autoadd_astral_peer_conn(self.conn, serializer);
+    sse_encode_opt_String(self.devName, serializer);
+    sse_encode_opt_String(self.url, serializer);
+    sse_encode_opt_String(self.ipVersion, serializer);
+    sse_encode_opt_String(self.localAddr, serializer);
+    sse_encode_opt_String(self.remoteAddr, serializer);
+    sse_encode_opt_String(self.portal, serializer);
+    sse_encode_opt_String(self.clientAddr, serializer);
+    sse_encode_opt_String(self.oldAddr, serializer);
+    sse_encode_opt_String(self.newAddr, serializer);
+    sse_encode_opt_String(self.addr, serializer);
+    sse_encode_opt_box_autoadd_astral_port_forward(self.forward, serializer);
+    sse_encode_opt_box_autoadd_u_64(self.rotationId, serializer);
+    sse_encode_opt_box_autoadd_u_64(self.count, serializer);
+    sse_encode_opt_String(self.error, serializer);
+  }
+
+  @protected
+  void sse_encode_astral_event_kind(
+    AstralEventKind self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as GuidImpl).frbInternalSseEncode(move: false),
-      serializer,
-    );
+    sse_encode_i_32(self.index, serializer);
   }
 
   @protected
-  void
-  sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpNetwork(
-    IpNetwork self,
-    SseSerializer serializer,
-  ) {
+  void sse_encode_astral_hop(AstralHop self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as IpNetworkImpl).frbInternalSseEncode(move: false),
-      serializer,
-    );
+    sse_encode_u_32(self.peerId, serializer);
+    sse_encode_String(self.hostname, serializer);
+    sse_encode_opt_String(self.ipv4, serializer);
+    sse_encode_opt_String(self.ipv6, serializer);
+    sse_encode_f_64(self.latencyMs, serializer);
+    sse_encode_f_32(self.lossRate, serializer);
   }
 
   @protected
-  void
-  sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
-    WfpController self,
+  void sse_encode_astral_interface(
+    AstralInterface self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as WfpControllerImpl).frbInternalSseEncode(move: false),
-      serializer,
-    );
+    sse_encode_u_32(self.index, serializer);
+    sse_encode_String(self.name, serializer);
+    sse_encode_opt_box_autoadd_u_32(self.metric, serializer);
+    sse_encode_u_32(self.mtu, serializer);
+    sse_encode_list_String(self.addresses, serializer);
+    sse_encode_bool(self.up, serializer);
   }
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    EventBusSubscriber self,
+  void sse_encode_astral_network_snapshot(
+    AstralNetworkSnapshot self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as EventBusSubscriberImpl).frbInternalSseEncode(move: null),
-      serializer,
-    );
+    sse_encode_String(self.instanceId, serializer);
+    sse_encode_String(self.devName, serializer);
+    sse_encode_bool(self.running, serializer);
+    sse_encode_opt_String(self.errorMsg, serializer);
+    sse_encode_list_String(self.listeners, serializer);
+    sse_encode_list_astral_node(self.nodes, serializer);
   }
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
-    FilterRule self,
-    SseSerializer serializer,
-  ) {
+  void sse_encode_astral_node(AstralNode self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as FilterRuleImpl).frbInternalSseEncode(move: null),
-      serializer,
-    );
+    sse_encode_u_32(self.peerId, serializer);
+    sse_encode_bool(self.isLocal, serializer);
+    sse_encode_String(self.hostname, serializer);
+    sse_encode_String(self.version, serializer);
+    sse_encode_opt_String(self.ipv4, serializer);
+    sse_encode_opt_String(self.ipv6, serializer);
+    sse_encode_list_String(self.proxyCidrs, serializer);
+    sse_encode_String(self.udpNatType, serializer);
+    sse_encode_String(self.tcpNatType, serializer);
+    sse_encode_u_32(self.nextHopPeerId, serializer);
+    sse_encode_i_32(self.cost, serializer);
+    sse_encode_f_64(self.latencyMs, serializer);
+    sse_encode_f_32(self.lossRate, serializer);
+    sse_encode_list_String(self.tunnelTypes, serializer);
+    sse_encode_u_64(self.rxBytes, serializer);
+    sse_encode_u_64(self.txBytes, serializer);
+    sse_encode_list_astral_connection(self.connections, serializer);
+    sse_encode_list_astral_hop(self.hops, serializer);
   }
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerGUID(
-    Guid self,
+  void sse_encode_astral_peer_conn(
+    AstralPeerConn self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as GuidImpl).frbInternalSseEncode(move: null),
-      serializer,
-    );
+    sse_encode_String(self.connId, serializer);
+    sse_encode_u_32(self.myPeerId, serializer);
+    sse_encode_u_32(self.peerId, serializer);
+    sse_encode_String(self.tunnelType, serializer);
+    sse_encode_String(self.localAddr, serializer);
+    sse_encode_String(self.remoteAddr, serializer);
+    sse_encode_bool(self.isClient, serializer);
+    sse_encode_String(self.networkName, serializer);
   }
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpAddr(
-    IpAddr self,
+  void sse_encode_astral_port_forward(
+    AstralPortForward self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as IpAddrImpl).frbInternalSseEncode(move: null),
-      serializer,
-    );
+    sse_encode_String(self.bindAddr, serializer);
+    sse_encode_String(self.dstAddr, serializer);
+    sse_encode_String(self.proto, serializer);
   }
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpNetwork(
-    IpNetwork self,
+  void sse_encode_astral_port_forward_status(
+    AstralPortForwardStatus self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as IpNetworkImpl).frbInternalSseEncode(move: null),
-      serializer,
-    );
+    sse_encode_String(self.bindAddr, serializer);
+    sse_encode_String(self.dstAddr, serializer);
+    sse_encode_String(self.proto, serializer);
+    sse_encode_bool(self.bound, serializer);
+    sse_encode_opt_String(self.error, serializer);
+    sse_encode_u_32(self.activeConnections, serializer);
+    sse_encode_u_64(self.totalConnections, serializer);
   }
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    JoinHandle self,
+  void sse_encode_astral_secret_rotation_peer(
+    AstralSecretRotationPeer self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as JoinHandleImpl).frbInternalSseEncode(move: null),
-      serializer,
-    );
+    sse_encode_u_32(self.peerId, serializer);
+    sse_encode_String(self.hostname, serializer);
+    sse_encode_bool(self.prepared, serializer);
+    sse_encode_bool(self.committed, serializer);
+    sse_encode_opt_String(self.error, serializer);
   }
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    JoinHandleResultString self,
+  void sse_encode_astral_secret_rotation_status(
+    AstralSecretRotationStatus self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as JoinHandleResultStringImpl).frbInternalSseEncode(move: null),
-      serializer,
-    );
+    sse_encode_u_64(self.rotationId, serializer);
+    sse_encode_bool(self.initiatedLocally, serializer);
+    sse_encode_bool(self.committed, serializer);
+    sse_encode_u_32(self.gracePeriodRemainingSec, serializer);
+    sse_encode_list_astral_secret_rotation_peer(self.peers, serializer);
   }
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    PeerRoutePair self,
-    SseSerializer serializer,
-  ) {
+  void sse_encode_bool(bool self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as PeerRoutePairImpl).frbInternalSseEncode(move: null),
-      serializer,
-    );
+    serializer.buffer.putUint8(self ? 1 : 0);
   }
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
-    WfpController self,
+  void sse_encode_box_autoadd_astral_bandwidth_limit(
+    AstralBandwidthLimit self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as WfpControllerImpl).frbInternalSseEncode(move: null),
-      serializer,
-    );
+    sse_encode_astral_bandwidth_limit(self, serializer);
   }
 
   @protected
-  void sse_encode_String(String self, SseSerializer serializer) {
+  void sse_encode_box_autoadd_astral_peer_conn(
+    AstralPeerConn self,
+    SseSerializer serializer,
+  ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_list_prim_u_8_strict(utf8.encoder.convert(self), serializer);
+    sse_encode_astral_peer_conn(self, serializer);
   }
 
   @protected
-  void sse_encode_bool(bool self, SseSerializer serializer) {
+  void sse_encode_box_autoadd_astral_port_forward(
+    AstralPortForward self,
+    SseSerializer serializer,
+  ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    serializer.buffer.putUint8(self ? 1 : 0);
+    sse_encode_astral_port_forward(self, serializer);
   }
 
   @protected
@@ -4210,6 +5106,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_flags_c(self, serializer);
   }
 
+  @protected
+  void sse_encode_box_autoadd_forward(Forward self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_forward(self, serializer);
+  }
+
   @protected
   void sse_encode_box_autoadd_protocol(
     Protocol self,
@@ -4240,6 +5142,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_u_32(self, serializer);
   }
 
+  @protected
+  void sse_encode_box_autoadd_u_64(BigInt self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_u_64(self, serializer);
+  }
+
   @protected
   void sse_encode_direction(Direction self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -4271,6 +5179,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_String(self.devName, serializer);
     sse_encode_bool(self.enableEncryption, serializer);
     sse_encode_bool(self.enableIpv6, serializer);
+    sse_encode_bool(self.dhcpIpv6, serializer);
     sse_encode_u_32(self.mtu, serializer);
     sse_encode_bool(self.latencyFirst, serializer);
     sse_encode_bool(self.enableExitNode, serializer);
@@ -4282,6 +5191,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_bool(self.disableUdpHolePunching, serializer);
     sse_encode_bool(self.multiThread, serializer);
     sse_encode_i_32(self.dataCompressAlgo, serializer);
+    sse_encode_i_32(self.dataCompressLevel, serializer);
     sse_encode_bool(self.bindDevice, serializer);
     sse_encode_bool(self.enableKcpProxy, serializer);
     sse_encode_bool(self.disableKcpInput, serializer);
@@ -4308,129 +5218,126 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 
   @protected
-  void sse_encode_kv_network_status(
-    KVNetworkStatus self,
-    SseSerializer serializer,
-  ) {
+  void sse_encode_i_64(PlatformInt64 self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(self.totalNodes, serializer);
-    sse_encode_list_kv_node_info(self.nodes, serializer);
+    serializer.buffer.putPlatformInt64(self);
   }
 
   @protected
-  void sse_encode_kv_node_connection_stats(
-    KVNodeConnectionStats self,
+  void
+  sse_encode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
+    List<FilterRule> self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_String(self.connType, serializer);
-    sse_encode_u_64(self.rxBytes, serializer);
-    sse_encode_u_64(self.txBytes, serializer);
-    sse_encode_u_64(self.rxPackets, serializer);
-    sse_encode_u_64(self.txPackets, serializer);
+    sse_encode_i_32(self.length, serializer);
+    for (final item in self) {
+      sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
+        item,
+        serializer,
+      );
+    }
   }
 
   @protected
-  void sse_encode_kv_node_info(KVNodeInfo self, SseSerializer serializer) {
+  void sse_encode_list_String(List<String> self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_String(self.hostname, serializer);
-    sse_encode_String(self.ipv4, serializer);
-    sse_encode_f_64(self.latencyMs, serializer);
-    sse_encode_String(self.nat, serializer);
-    sse_encode_list_node_hop_stats(self.hops, serializer);
-    sse_encode_f_32(self.lossRate, serializer);
-    sse_encode_list_kv_node_connection_stats(self.connections, serializer);
-    sse_encode_String(self.tunnelProto, serializer);
-    sse_encode_String(self.connType, serializer);
-    sse_encode_u_64(self.rxBytes, serializer);
-    sse_encode_u_64(self.txBytes, serializer);
-    sse_encode_String(self.version, serializer);
-    sse_encode_i_32(self.cost, serializer);
+    sse_encode_i_32(self.length, serializer);
+    for (final item in self) {
+      sse_encode_String(item, serializer);
+    }
   }
 
   @protected
-  void
-  sse_encode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
-    List<FilterRule> self,
+  void sse_encode_list_astral_bandwidth_limit(
+    List<AstralBandwidthLimit> self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_i_32(self.length, serializer);
     for (final item in self) {
-      sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
-        item,
-        serializer,
-      );
+      sse_encode_astral_bandwidth_limit(item, serializer);
     }
   }
 
   @protected
-  void
-  sse_encode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    List<PeerRoutePair> self,
+  void sse_encode_list_astral_connection(
+    List<AstralConnection> self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_i_32(self.length, serializer);
     for (final item in self) {
-      sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-        item,
-        serializer,
-      );
+      sse_encode_astral_connection(item, serializer);
     }
   }
 
   @protected
-  void sse_encode_list_String(List<String> self, SseSerializer serializer) {
+  void sse_encode_list_astral_hop(
+    List<AstralHop> self,
+    SseSerializer serializer,
+  ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_i_32(self.length, serializer);
     for (final item in self) {
-      sse_encode_String(item, serializer);
+      sse_encode_astral_hop(item, serializer);
     }
   }
 
   @protected
-  void sse_encode_list_forward(List<Forward> self, SseSerializer serializer) {
+  void sse_encode_list_astral_interface(
+    List<AstralInterface> self,
+    SseSerializer serializer,
+  ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_i_32(self.length, serializer);
     for (final item in self) {
-      sse_encode_forward(item, serializer);
+      sse_encode_astral_interface(item, serializer);
     }
   }
 
   @protected
-  void sse_encode_list_kv_node_connection_stats(
-    List<KVNodeConnectionStats> self,
+  void sse_encode_list_astral_node(
+    List<AstralNode> self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_i_32(self.length, serializer);
     for (final item in self) {
-      sse_encode_kv_node_connection_stats(item, serializer);
+      sse_encode_astral_node(item, serializer);
     }
   }
 
   @protected
-  void sse_encode_list_kv_node_info(
-    List<KVNodeInfo> self,
+  void sse_encode_list_astral_port_forward_status(
+    List<AstralPortForwardStatus> self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_i_32(self.length, serializer);
     for (final item in self) {
-      sse_encode_kv_node_info(item, serializer);
+      sse_encode_astral_port_forward_status(item, serializer);
     }
   }
 
   @protected
-  void sse_encode_list_node_hop_stats(
-    List<NodeHopStats> self,
+  void sse_encode_list_astral_secret_rotation_peer(
+    List<AstralSecretRotationPeer> self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_i_32(self.length, serializer);
     for (final item in self) {
-      sse_encode_node_hop_stats(item, serializer);
+      sse_encode_astral_secret_rotation_peer(item, serializer);
+    }
+  }
+
+  @protected
+  void sse_encode_list_forward(List<Forward> self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.length, serializer);
+    for (final item in self) {
+      sse_encode_forward(item, serializer);
     }
   }
 
@@ -4477,21 +5384,38 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 
   @protected
-  void sse_encode_node_hop_stats(NodeHopStats self, SseSerializer serializer) {
+  void sse_encode_opt_String(String? self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_String(self.targetIp, serializer);
-    sse_encode_f_64(self.latencyMs, serializer);
-    sse_encode_f_32(self.packetLoss, serializer);
-    sse_encode_String(self.nodeName, serializer);
+
+    sse_encode_bool(self != null, serializer);
+    if (self != null) {
+      sse_encode_String(self, serializer);
+    }
   }
 
   @protected
-  void sse_encode_opt_String(String? self, SseSerializer serializer) {
+  void sse_encode_opt_box_autoadd_astral_peer_conn(
+    AstralPeerConn? self,
+    SseSerializer serializer,
+  ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
 
     sse_encode_bool(self != null, serializer);
     if (self != null) {
-      sse_encode_String(self, serializer);
+      sse_encode_box_autoadd_astral_peer_conn(self, serializer);
+    }
+  }
+
+  @protected
+  void sse_encode_opt_box_autoadd_astral_port_forward(
+    AstralPortForward? self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    sse_encode_bool(self != null, serializer);
+    if (self != null) {
+      sse_encode_box_autoadd_astral_port_forward(self, serializer);
     }
   }
 
@@ -4541,6 +5465,16 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     }
   }
 
+  @protected
+  void sse_encode_opt_box_autoadd_u_64(BigInt? self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    sse_encode_bool(self != null, serializer);
+    if (self != null) {
+      sse_encode_box_autoadd_u_64(self, serializer);
+    }
+  }
+
   @protected
   void sse_encode_protocol(Protocol self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -4600,31 +5534,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 }
 
-@sealed
-class EventBusSubscriberImpl extends RustOpaque implements EventBusSubscriber {
-  // Not to be used by end users
-  EventBusSubscriberImpl.frbInternalDcoDecode(List<dynamic> wire)
-    : super.frbInternalDcoDecode(wire, _kStaticData);
-
-  // Not to be used by end users
-  EventBusSubscriberImpl.frbInternalSseDecode(
-    BigInt ptr,
-    int externalSizeOnNative,
-  ) : super.frbInternalSseDecode(ptr, externalSizeOnNative, _kStaticData);
-
-  static final _kStaticData = RustArcStaticData(
-    rustArcIncrementStrongCount:
-        RustLib.instance.api.rust_arc_increment_strong_count_EventBusSubscriber,
-    rustArcDecrementStrongCount:
-        RustLib.instance.api.rust_arc_decrement_strong_count_EventBusSubscriber,
-    rustArcDecrementStrongCountPtr:
-        RustLib
-            .instance
-            .api
-            .rust_arc_decrement_strong_count_EventBusSubscriberPtr,
-  );
-}
-
 @sealed
 class FilterRuleImpl extends RustOpaque implements FilterRule {
   // Not to be used by end users
@@ -4837,78 +5746,6 @@ class IpNetworkImpl extends RustOpaque implements IpNetwork {
       );
 }
 
-@sealed
-class JoinHandleImpl extends RustOpaque implements JoinHandle {
-  // Not to be used by end users
-  JoinHandleImpl.frbInternalDcoDecode(List<dynamic> wire)
-    : super.frbInternalDcoDecode(wire, _kStaticData);
-
-  // Not to be used by end users
-  JoinHandleImpl.frbInternalSseDecode(BigInt ptr, int externalSizeOnNative)
-    : super.frbInternalSseDecode(ptr, externalSizeOnNative, _kStaticData);
-
-  static final _kStaticData = RustArcStaticData(
-    rustArcIncrementStrongCount:
-        RustLib.instance.api.rust_arc_increment_strong_count_JoinHandle,
-    rustArcDecrementStrongCount:
-        RustLib.instance.api.rust_arc_decrement_strong_count_JoinHandle,
-    rustArcDecrementStrongCountPtr:
-        RustLib.instance.api.rust_arc_decrement_strong_count_JoinHandlePtr,
-  );
-}
-
-@sealed
-class JoinHandleResultStringImpl extends RustOpaque
-    implements JoinHandleResultString {
-  // Not to be used by end users
-  JoinHandleResultStringImpl.frbInternalDcoDecode(List<dynamic> wire)
-    : super.frbInternalDcoDecode(wire, _kStaticData);
-
-  // Not to be used by end users
-  JoinHandleResultStringImpl.frbInternalSseDecode(
-    BigInt ptr,
-    int externalSizeOnNative,
-  ) : super.frbInternalSseDecode(ptr, externalSizeOnNative, _kStaticData);
-
-  static final _kStaticData = RustArcStaticData(
-    rustArcIncrementStrongCount:
-        RustLib
-            .instance
-            .api
-            .rust_arc_increment_strong_count_JoinHandleResultString,
-    rustArcDecrementStrongCount:
-        RustLib
-            .instance
-            .api
-            .rust_arc_decrement_strong_count_JoinHandleResultString,
-    rustArcDecrementStrongCountPtr:
-        RustLib
-            .instance
-            .api
-            .rust_arc_decrement_strong_count_JoinHandleResultStringPtr,
-  );
-}
-
-@sealed
-class PeerRoutePairImpl extends RustOpaque implements PeerRoutePair {
-  // Not to be used by end users
-  PeerRoutePairImpl.frbInternalDcoDecode(List<dynamic> wire)
-    : super.frbInternalDcoDecode(wire, _kStaticData);
-
-  // Not to be used by end users
-  PeerRoutePairImpl.frbInternalSseDecode(BigInt ptr, int externalSizeOnNative)
-    : super.frbInternalSseDecode(ptr, externalSizeOnNative, _kStaticData);
-
-  static final _kStaticData = RustArcStaticData(
-    rustArcIncrementStrongCount:
-        RustLib.instance.api.rust_arc_increment_strong_count_PeerRoutePair,
-    rustArcDecrementStrongCount:
-        RustLib.instance.api.rust_arc_decrement_strong_count_PeerRoutePair,
-    rustArcDecrementStrongCountPtr:
-        RustLib.instance.api.rust_arc_decrement_strong_count_PeerRoutePairPtr,
-  );
-}
-
 @sealed
 class WfpControllerImpl extends RustOpaque implements WfpController {
   // Not to be used by end users
@@ -4,9 +4,13 @@
 // ignore_for_file: unused_import, unused_element, unnecessary_import, duplicate_ignore, invalid_use_of_internal_member, annotate_overrides, non_constant_identifier_names, curly_braces_in_flow_control_structures, prefer_const_literals_to_create_immutables, unused_field
 
 import 'api/astral_wfp.dart';
+import 'api/bandwidth.dart';
+import 'api/events.dart';
 import 'api/firewall.dart';
 import 'api/hops.dart';
 import 'api/nt.dart';
+import 'api/port_forward.dart';
+import 'api/secret_rotation.dart';
 import 'api/simple.dart';
 import 'api/utils.dart';
 import 'dart:async';
@@ -24,10 +28,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     required super.portManager,
   });
 
-  CrossPlatformFinalizerArg
-  get rust_arc_decrement_strong_count_EventBusSubscriberPtr =>
-      wire._rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriberPtr;
-
   CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_FilterRulePtr =>
       wire._rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRulePtr;
 
@@ -40,17 +40,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_IpNetworkPtr =>
       wire._rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpNetworkPtr;
 
-  CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_JoinHandlePtr =>
-      wire._rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandlePtr;
-
-  CrossPlatformFinalizerArg
-  get rust_arc_decrement_strong_count_JoinHandleResultStringPtr =>
-      wire._rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultStringPtr;
-
-  CrossPlatformFinalizerArg
-  get rust_arc_decrement_strong_count_PeerRoutePairPtr =>
-      wire._rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePairPtr;
-
   CrossPlatformFinalizerArg
   get rust_arc_decrement_strong_count_WfpControllerPtr =>
       wire._rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpControllerPtr;
@@ -58,12 +47,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   AnyhowException dco_decode_AnyhowException(dynamic raw);
 
-  @protected
-  EventBusSubscriber
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    dynamic raw,
-  );
-
   @protected
   FilterRule
   dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -82,24 +65,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     dynamic raw,
   );
 
-  @protected
-  JoinHandle
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    dynamic raw,
-  );
-
-  @protected
-  JoinHandleResultString
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    dynamic raw,
-  );
-
-  @protected
-  PeerRoutePair
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    dynamic raw,
-  );
-
   @protected
   WfpController
   dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
@@ -148,12 +113,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     dynamic raw,
   );
 
-  @protected
-  EventBusSubscriber
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    dynamic raw,
-  );
-
   @protected
   FilterRule
   dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -179,38 +138,80 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  JoinHandle
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
+  WfpController
+  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
     dynamic raw,
   );
 
   @protected
-  JoinHandleResultString
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
+  RustStreamSink<AstralEvent> dco_decode_StreamSink_astral_event_Sse(
     dynamic raw,
   );
 
   @protected
-  PeerRoutePair
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
+  String dco_decode_String(dynamic raw);
+
+  @protected
+  AstralBandwidthLimit dco_decode_astral_bandwidth_limit(dynamic raw);
+
+  @protected
+  AstralConnection dco_decode_astral_connection(dynamic raw);
+
+  @protected
+  AstralEvent dco_decode_astral_event(dynamic raw);
+
+  @protected
+  AstralEventKind dco_decode_astral_event_kind(dynamic raw);
+
+  @protected
+  AstralHop dco_decode_astral_hop(dynamic raw);
+
+  @protected
+  AstralInterface dco_decode_astral_interface(dynamic raw);
+
+  @protected
+  AstralNetworkSnapshot dco_decode_astral_network_snapshot(dynamic raw);
+
+  @protected
+  AstralNode dco_decode_astral_node(dynamic raw);
+
+  @protected
+  AstralPeerConn dco_decode_astral_peer_conn(dynamic raw);
+
+  @protected
+  AstralPortForward dco_decode_astral_port_forward(dynamic raw);
+
+  @protected
+  AstralPortForwardStatus dco_decode_astral_port_forward_status(dynamic raw);
+
+  @protected
+  AstralSecretRotationPeer dco_decode_astral_secret_rotation_peer(dynamic raw);
+
+  @protected
+  AstralSecretRotationStatus dco_decode_astral_secret_rotation_status(
     dynamic raw,
   );
 
   @protected
-  WfpController
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+  bool dco_decode_bool(dynamic raw);
+
+  @protected
+  AstralBandwidthLimit dco_decode_box_autoadd_astral_bandwidth_limit(
     dynamic raw,
   );
 
   @protected
-  String dco_decode_String(dynamic raw);
+  AstralPeerConn dco_decode_box_autoadd_astral_peer_conn(dynamic raw);
 
   @protected
-  bool dco_decode_bool(dynamic raw);
+  AstralPortForward dco_decode_box_autoadd_astral_port_forward(dynamic raw);
 
   @protected
   FlagsC dco_decode_box_autoadd_flags_c(dynamic raw);
 
+  @protected
+  Forward dco_decode_box_autoadd_forward(dynamic raw);
+
   @protected
   Protocol dco_decode_box_autoadd_protocol(dynamic raw);
 
@@ -223,6 +224,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   int dco_decode_box_autoadd_u_32(dynamic raw);
 
+  @protected
+  BigInt dco_decode_box_autoadd_u_64(dynamic raw);
+
   @protected
   Direction dco_decode_direction(dynamic raw);
 
@@ -245,13 +249,7 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   int dco_decode_i_32(dynamic raw);
 
   @protected
-  KVNetworkStatus dco_decode_kv_network_status(dynamic raw);
-
-  @protected
-  KVNodeConnectionStats dco_decode_kv_node_connection_stats(dynamic raw);
-
-  @protected
-  KVNodeInfo dco_decode_kv_node_info(dynamic raw);
+  PlatformInt64 dco_decode_i_64(dynamic raw);
 
   @protected
   List<FilterRule>
@@ -260,27 +258,37 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  List<PeerRoutePair>
-  dco_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
+  List<String> dco_decode_list_String(dynamic raw);
+
+  @protected
+  List<AstralBandwidthLimit> dco_decode_list_astral_bandwidth_limit(
     dynamic raw,
   );
 
   @protected
-  List<String> dco_decode_list_String(dynamic raw);
+  List<AstralConnection> dco_decode_list_astral_connection(dynamic raw);
 
   @protected
-  List<Forward> dco_decode_list_forward(dynamic raw);
+  List<AstralHop> dco_decode_list_astral_hop(dynamic raw);
+
+  @protected
+  List<AstralInterface> dco_decode_list_astral_interface(dynamic raw);
 
   @protected
-  List<KVNodeConnectionStats> dco_decode_list_kv_node_connection_stats(
+  List<AstralNode> dco_decode_list_astral_node(dynamic raw);
+
+  @protected
+  List<AstralPortForwardStatus> dco_decode_list_astral_port_forward_status(
     dynamic raw,
   );
 
   @protected
-  List<KVNodeInfo> dco_decode_list_kv_node_info(dynamic raw);
+  List<AstralSecretRotationPeer> dco_decode_list_astral_secret_rotation_peer(
+    dynamic raw,
+  );
 
   @protected
-  List<NodeHopStats> dco_decode_list_node_hop_stats(dynamic raw);
+  List<Forward> dco_decode_list_forward(dynamic raw);
 
   @protected
   Uint16List dco_decode_list_prim_u_16_strict(dynamic raw);
@@ -295,10 +303,15 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   List<(String, int)> dco_decode_list_record_string_u_32(dynamic raw);
 
   @protected
-  NodeHopStats dco_decode_node_hop_stats(dynamic raw);
+  String? dco_decode_opt_String(dynamic raw);
+
+  @protected
+  AstralPeerConn? dco_decode_opt_box_autoadd_astral_peer_conn(dynamic raw);
 
   @protected
-  String? dco_decode_opt_String(dynamic raw);
+  AstralPortForward? dco_decode_opt_box_autoadd_astral_port_forward(
+    dynamic raw,
+  );
 
   @protected
   Protocol? dco_decode_opt_box_autoadd_protocol(dynamic raw);
@@ -312,6 +325,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   int? dco_decode_opt_box_autoadd_u_32(dynamic raw);
 
+  @protected
+  BigInt? dco_decode_opt_box_autoadd_u_64(dynamic raw);
+
   @protected
   Protocol dco_decode_protocol(dynamic raw);
 
@@ -342,12 +358,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   AnyhowException sse_decode_AnyhowException(SseDeserializer deserializer);
 
-  @protected
-  EventBusSubscriber
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    SseDeserializer deserializer,
-  );
-
   @protected
   FilterRule
   sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -366,24 +376,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseDeserializer deserializer,
   );
 
-  @protected
-  JoinHandle
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    SseDeserializer deserializer,
-  );
-
-  @protected
-  JoinHandleResultString
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    SseDeserializer deserializer,
-  );
-
-  @protected
-  PeerRoutePair
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    SseDeserializer deserializer,
-  );
-
   @protected
   WfpController
   sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
@@ -432,12 +424,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseDeserializer deserializer,
   );
 
-  @protected
-  EventBusSubscriber
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    SseDeserializer deserializer,
-  );
-
   @protected
   FilterRule
   sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -463,38 +449,94 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  JoinHandle
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
+  WfpController
+  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
     SseDeserializer deserializer,
   );
 
   @protected
-  JoinHandleResultString
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
+  RustStreamSink<AstralEvent> sse_decode_StreamSink_astral_event_Sse(
     SseDeserializer deserializer,
   );
 
   @protected
-  PeerRoutePair
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
+  String sse_decode_String(SseDeserializer deserializer);
+
+  @protected
+  AstralBandwidthLimit sse_decode_astral_bandwidth_limit(
     SseDeserializer deserializer,
   );
 
   @protected
-  WfpController
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+  AstralConnection sse_decode_astral_connection(SseDeserializer deserializer);
+
+  @protected
+  AstralEvent sse_decode_astral_event(SseDeserializer deserializer);
+
+  @protected
+  AstralEventKind sse_decode_astral_event_kind(SseDeserializer deserializer);
+
+  @protected
+  AstralHop sse_decode_astral_hop(SseDeserializer deserializer);
+
+  @protected
+  AstralInterface sse_decode_astral_interface(SseDeserializer deserializer);
+
+  @protected
+  AstralNetworkSnapshot sse_decode_astral_network_snapshot(
     SseDeserializer deserializer,
   );
 
   @protected
-  String sse_decode_String(SseDeserializer deserializer);
+  AstralNode sse_decode_astral_node(SseDeserializer deserializer);
+
+  @protected
+  AstralPeerConn sse_decode_astral_peer_conn(SseDeserializer deserializer);
+
+  @protected
+  AstralPortForward sse_decode_astral_port_forward(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralPortForwardStatus sse_decode_astral_port_forward_status(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralSecretRotationPeer sse_decode_astral_secret_rotation_peer(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralSecretRotationStatus sse_decode_astral_secret_rotation_status(
+    SseDeserializer deserializer,
+  );
 
   @protected
   bool sse_decode_bool(SseDeserializer deserializer);
 
+  @protected
+  AstralBandwidthLimit sse_decode_box_autoadd_astral_bandwidth_limit(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralPeerConn sse_decode_box_autoadd_astral_peer_conn(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralPortForward sse_decode_box_autoadd_astral_port_forward(
+    SseDeserializer deserializer,
+  );
+
   @protected
   FlagsC sse_decode_box_autoadd_flags_c(SseDeserializer deserializer);
 
+  @protected
+  Forward sse_decode_box_autoadd_forward(SseDeserializer deserializer);
+
   @protected
   Protocol sse_decode_box_autoadd_protocol(SseDeserializer deserializer);
 
@@ -509,6 +551,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   int sse_decode_box_autoadd_u_32(SseDeserializer deserializer);
 
+  @protected
+  BigInt sse_decode_box_autoadd_u_64(SseDeserializer deserializer);
+
   @protected
   Direction sse_decode_direction(SseDeserializer deserializer);
 
@@ -531,47 +576,51 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   int sse_decode_i_32(SseDeserializer deserializer);
 
   @protected
-  KVNetworkStatus sse_decode_kv_network_status(SseDeserializer deserializer);
+  PlatformInt64 sse_decode_i_64(SseDeserializer deserializer);
 
   @protected
-  KVNodeConnectionStats sse_decode_kv_node_connection_stats(
+  List<FilterRule>
+  sse_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
     SseDeserializer deserializer,
   );
 
   @protected
-  KVNodeInfo sse_decode_kv_node_info(SseDeserializer deserializer);
+  List<String> sse_decode_list_String(SseDeserializer deserializer);
 
   @protected
-  List<FilterRule>
-  sse_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
+  List<AstralBandwidthLimit> sse_decode_list_astral_bandwidth_limit(
     SseDeserializer deserializer,
   );
 
   @protected
-  List<PeerRoutePair>
-  sse_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
+  List<AstralConnection> sse_decode_list_astral_connection(
     SseDeserializer deserializer,
   );
 
   @protected
-  List<String> sse_decode_list_String(SseDeserializer deserializer);
-
-  @protected
-  List<Forward> sse_decode_list_forward(SseDeserializer deserializer);
+  List<AstralHop> sse_decode_list_astral_hop(SseDeserializer deserializer);
 
   @protected
-  List<KVNodeConnectionStats> sse_decode_list_kv_node_connection_stats(
+  List<AstralInterface> sse_decode_list_astral_interface(
     SseDeserializer deserializer,
   );
 
   @protected
-  List<KVNodeInfo> sse_decode_list_kv_node_info(SseDeserializer deserializer);
+  List<AstralNode> sse_decode_list_astral_node(SseDeserializer deserializer);
 
   @protected
-  List<NodeHopStats> sse_decode_list_node_hop_stats(
+  List<AstralPortForwardStatus> sse_decode_list_astral_port_forward_status(
     SseDeserializer deserializer,
   );
 
+  @protected
+  List<AstralSecretRotationPeer> sse_decode_list_astral_secret_rotation_peer(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  List<Forward> sse_decode_list_forward(SseDeserializer deserializer);
+
   @protected
   Uint16List sse_decode_list_prim_u_16_strict(SseDeserializer deserializer);
 
@@ -587,10 +636,17 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  NodeHopStats sse_decode_node_hop_stats(SseDeserializer deserializer);
+  String? sse_decode_opt_String(SseDeserializer deserializer);
 
   @protected
-  String? sse_decode_opt_String(SseDeserializer deserializer);
+  AstralPeerConn? sse_decode_opt_box_autoadd_astral_peer_conn(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralPortForward? sse_decode_opt_box_autoadd_astral_port_forward(
+    SseDeserializer deserializer,
+  );
 
   @protected
   Protocol? sse_decode_opt_box_autoadd_protocol(SseDeserializer deserializer);
@@ -606,6 +662,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   int? sse_decode_opt_box_autoadd_u_32(SseDeserializer deserializer);
 
+  @protected
+  BigInt? sse_decode_opt_box_autoadd_u_64(SseDeserializer deserializer);
+
   @protected
   Protocol sse_decode_protocol(SseDeserializer deserializer);
 
@@ -639,13 +698,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
-  @protected
-  void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    EventBusSubscriber self,
-    SseSerializer serializer,
-  );
-
   @protected
   void
   sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -667,27 +719,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
-  @protected
-  void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    JoinHandle self,
-    SseSerializer serializer,
-  );
-
-  @protected
-  void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    JoinHandleResultString self,
-    SseSerializer serializer,
-  );
-
-  @protected
-  void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    PeerRoutePair self,
-    SseSerializer serializer,
-  );
-
   @protected
   void
   sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
@@ -744,13 +775,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
-  @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    EventBusSubscriber self,
-    SseSerializer serializer,
-  );
-
   @protected
   void
   sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -781,41 +805,116 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
 
   @protected
   void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    JoinHandle self,
+  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+    WfpController self,
     SseSerializer serializer,
   );
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    JoinHandleResultString self,
+  void sse_encode_StreamSink_astral_event_Sse(
+    RustStreamSink<AstralEvent> self,
     SseSerializer serializer,
   );
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    PeerRoutePair self,
+  void sse_encode_String(String self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_astral_bandwidth_limit(
+    AstralBandwidthLimit self,
     SseSerializer serializer,
   );
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
-    WfpController self,
+  void sse_encode_astral_connection(
+    AstralConnection self,
     SseSerializer serializer,
   );
 
   @protected
-  void sse_encode_String(String self, SseSerializer serializer);
+  void sse_encode_astral_event(AstralEvent self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_astral_event_kind(
+    AstralEventKind self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_hop(AstralHop self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_astral_interface(
+    AstralInterface self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_network_snapshot(
+    AstralNetworkSnapshot self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_node(AstralNode self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_astral_peer_conn(
+    AstralPeerConn self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_port_forward(
+    AstralPortForward self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_port_forward_status(
+    AstralPortForwardStatus self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_secret_rotation_peer(
+    AstralSecretRotationPeer self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_secret_rotation_status(
+    AstralSecretRotationStatus self,
+    SseSerializer serializer,
+  );
 
   @protected
   void sse_encode_bool(bool self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_box_autoadd_astral_bandwidth_limit(
+    AstralBandwidthLimit self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_box_autoadd_astral_peer_conn(
+    AstralPeerConn self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_box_autoadd_astral_port_forward(
+    AstralPortForward self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_box_autoadd_flags_c(FlagsC self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_box_autoadd_forward(Forward self, SseSerializer serializer);
+
   @protected
   void sse_encode_box_autoadd_protocol(Protocol self, SseSerializer serializer);
 
@@ -831,6 +930,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_box_autoadd_u_32(int self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_box_autoadd_u_64(BigInt self, SseSerializer serializer);
+
   @protected
   void sse_encode_direction(Direction self, SseSerializer serializer);
 
@@ -853,58 +955,63 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   void sse_encode_i_32(int self, SseSerializer serializer);
 
   @protected
-  void sse_encode_kv_network_status(
-    KVNetworkStatus self,
-    SseSerializer serializer,
-  );
+  void sse_encode_i_64(PlatformInt64 self, SseSerializer serializer);
 
   @protected
-  void sse_encode_kv_node_connection_stats(
-    KVNodeConnectionStats self,
+  void
+  sse_encode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
+    List<FilterRule> self,
     SseSerializer serializer,
   );
 
   @protected
-  void sse_encode_kv_node_info(KVNodeInfo self, SseSerializer serializer);
+  void sse_encode_list_String(List<String> self, SseSerializer serializer);
 
   @protected
-  void
-  sse_encode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
-    List<FilterRule> self,
+  void sse_encode_list_astral_bandwidth_limit(
+    List<AstralBandwidthLimit> self,
     SseSerializer serializer,
   );
 
   @protected
-  void
-  sse_encode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    List<PeerRoutePair> self,
+  void sse_encode_list_astral_connection(
+    List<AstralConnection> self,
     SseSerializer serializer,
   );
 
   @protected
-  void sse_encode_list_String(List<String> self, SseSerializer serializer);
+  void sse_encode_list_astral_hop(
+    List<AstralHop> self,
+    SseSerializer serializer,
+  );
 
   @protected
-  void sse_encode_list_forward(List<Forward> self, SseSerializer serializer);
+  void sse_encode_list_astral_interface(
+    List<AstralInterface> self,
+    SseSerializer serializer,
+  );
 
   @protected
-  void sse_encode_list_kv_node_connection_stats(
-    List<KVNodeConnectionStats> self,
+  void sse_encode_list_astral_node(
+    List<AstralNode> self,
     SseSerializer serializer,
   );
 
   @protected
-  void sse_encode_list_kv_node_info(
-    List<KVNodeInfo> self,
+  void sse_encode_list_astral_port_forward_status(
+    List<AstralPortForwardStatus> self,
     SseSerializer serializer,
   );
 
   @protected
-  void sse_encode_list_node_hop_stats(
-    List<NodeHopStats> self,
+  void sse_encode_list_astral_secret_rotation_peer(
+    List<AstralSecretRotationPeer> self,
     SseSerializer serializer,
   );
 
+  @protected
+  void sse_encode_list_forward(List<Forward> self, SseSerializer serializer);
+
   @protected
   void sse_encode_list_prim_u_16_strict(
     Uint16List self,
@@ -930,10 +1037,19 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  void sse_encode_node_hop_stats(NodeHopStats self, SseSerializer serializer);
+  void sse_encode_opt_String(String? self, SseSerializer serializer);
 
   @protected
-  void sse_encode_opt_String(String? self, SseSerializer serializer);
+  void sse_encode_opt_box_autoadd_astral_peer_conn(
+    AstralPeerConn? self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_opt_box_autoadd_astral_port_forward(
+    AstralPortForward? self,
+    SseSerializer serializer,
+  );
 
   @protected
   void sse_encode_opt_box_autoadd_protocol(
@@ -953,6 +1069,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_opt_box_autoadd_u_32(int? self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_opt_box_autoadd_u_64(BigInt? self, SseSerializer serializer);
+
   @protected
   void sse_encode_protocol(Protocol self, SseSerializer serializer);
 
@@ -998,40 +1117,6 @@ class RustLibWire implements BaseWire {
   RustLibWire(ffi.DynamicLibrary dynamicLibrary)
     : _lookup = dynamicLibrary.lookup;
 
-  void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    ffi.Pointer<ffi.Void> ptr,
-  ) {
-    return _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-      ptr,
-    );
-  }
-
-  late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriberPtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>(
-        'frbgen_astral_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber',
-      );
-  late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber =
-      _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriberPtr
-          .asFunction<void Function(ffi.Pointer<ffi.Void>)>();
-
-  void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    ffi.Pointer<ffi.Void> ptr,
-  ) {
-    return _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-      ptr,
-    );
-  }
-
-  late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriberPtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>(
-        'frbgen_astral_rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber',
-      );
-  late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber =
-      _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriberPtr
-          .asFunction<void Function(ffi.Pointer<ffi.Void>)>();
-
   void
   rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
     ffi.Pointer<ffi.Void> ptr,
@@ -1168,108 +1253,6 @@ class RustLibWire implements BaseWire {
       _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpNetworkPtr
           .asFunction<void Function(ffi.Pointer<ffi.Void>)>();
 
-  void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    ffi.Pointer<ffi.Void> ptr,
-  ) {
-    return _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-      ptr,
-    );
-  }
-
-  late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandlePtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>(
-        'frbgen_astral_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle',
-      );
-  late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle =
-      _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandlePtr
-          .asFunction<void Function(ffi.Pointer<ffi.Void>)>();
-
-  void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    ffi.Pointer<ffi.Void> ptr,
-  ) {
-    return _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-      ptr,
-    );
-  }
-
-  late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandlePtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>(
-        'frbgen_astral_rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle',
-      );
-  late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle =
-      _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandlePtr
-          .asFunction<void Function(ffi.Pointer<ffi.Void>)>();
-
-  void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    ffi.Pointer<ffi.Void> ptr,
-  ) {
-    return _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-      ptr,
-    );
-  }
-
-  late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultStringPtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>(
-        'frbgen_astral_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString',
-      );
-  late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString =
-      _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultStringPtr
-          .asFunction<void Function(ffi.Pointer<ffi.Void>)>();
-
-  void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    ffi.Pointer<ffi.Void> ptr,
-  ) {
-    return _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-      ptr,
-    );
-  }
-
-  late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultStringPtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>(
-        'frbgen_astral_rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString',
-      );
-  late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString =
-      _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultStringPtr
-          .asFunction<void Function(ffi.Pointer<ffi.Void>)>();
-
-  void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    ffi.Pointer<ffi.Void> ptr,
-  ) {
-    return _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-      ptr,
-    );
-  }
-
-  late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePairPtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>(
-        'frbgen_astral_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair',
-      );
-  late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair =
-      _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePairPtr
-          .asFunction<void Function(ffi.Pointer<ffi.Void>)>();
-
-  void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    ffi.Pointer<ffi.Void> ptr,
-  ) {
-    return _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-      ptr,
-    );
-  }
-
-  late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePairPtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>(
-        'frbgen_astral_rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair',
-      );
-  late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair =
-      _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePairPtr
-          .asFunction<void Function(ffi.Pointer<ffi.Void>)>();
-
   void
   rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
     ffi.Pointer<ffi.Void> ptr,
@@ -7,9 +7,13 @@
 // ignore_for_file: argument_type_not_assignable
 
 import 'api/astral_wfp.dart';
+import 'api/bandwidth.dart';
+import 'api/events.dart';
 import 'api/firewall.dart';
 import 'api/hops.dart';
 import 'api/nt.dart';
+import 'api/port_forward.dart';
+import 'api/secret_rotation.dart';
 import 'api/simple.dart';
 import 'api/utils.dart';
 import 'dart:async';
@@ -26,10 +30,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     required super.portManager,
   });
 
-  CrossPlatformFinalizerArg
-  get rust_arc_decrement_strong_count_EventBusSubscriberPtr =>
-      wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber;
-
   CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_FilterRulePtr =>
       wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule;
 
@@ -42,17 +42,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_IpNetworkPtr =>
       wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerIpNetwork;
 
-  CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_JoinHandlePtr =>
-      wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle;
-
-  CrossPlatformFinalizerArg
-  get rust_arc_decrement_strong_count_JoinHandleResultStringPtr =>
-      wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString;
-
-  CrossPlatformFinalizerArg
-  get rust_arc_decrement_strong_count_PeerRoutePairPtr =>
-      wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair;
-
   CrossPlatformFinalizerArg
   get rust_arc_decrement_strong_count_WfpControllerPtr =>
       wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController;
@@ -60,12 +49,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   AnyhowException dco_decode_AnyhowException(dynamic raw);
 
-  @protected
-  EventBusSubscriber
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    dynamic raw,
-  );
-
   @protected
   FilterRule
   dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -84,24 +67,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     dynamic raw,
   );
 
-  @protected
-  JoinHandle
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    dynamic raw,
-  );
-
-  @protected
-  JoinHandleResultString
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    dynamic raw,
-  );
-
-  @protected
-  PeerRoutePair
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    dynamic raw,
-  );
-
   @protected
   WfpController
   dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
@@ -150,12 +115,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     dynamic raw,
   );
 
-  @protected
-  EventBusSubscriber
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    dynamic raw,
-  );
-
   @protected
   FilterRule
   dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -181,38 +140,80 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  JoinHandle
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
+  WfpController
+  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
     dynamic raw,
   );
 
   @protected
-  JoinHandleResultString
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
+  RustStreamSink<AstralEvent> dco_decode_StreamSink_astral_event_Sse(
     dynamic raw,
   );
 
   @protected
-  PeerRoutePair
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
+  String dco_decode_String(dynamic raw);
+
+  @protected
+  AstralBandwidthLimit dco_decode_astral_bandwidth_limit(dynamic raw);
+
+  @protected
+  AstralConnection dco_decode_astral_connection(dynamic raw);
+
+  @protected
+  AstralEvent dco_decode_astral_event(dynamic raw);
+
+  @protected
+  AstralEventKind dco_decode_astral_event_kind(dynamic raw);
+
+  @protected
+  AstralHop dco_decode_astral_hop(dynamic raw);
+
+  @protected
+  AstralInterface dco_decode_astral_interface(dynamic raw);
+
+  @protected
+  AstralNetworkSnapshot dco_decode_astral_network_snapshot(dynamic raw);
+
+  @protected
+  AstralNode dco_decode_astral_node(dynamic raw);
+
+  @protected
+  AstralPeerConn dco_decode_astral_peer_conn(dynamic raw);
+
+  @protected
+  AstralPortForward dco_decode_astral_port_forward(dynamic raw);
+
+  @protected
+  AstralPortForwardStatus dco_decode_astral_port_forward_status(dynamic raw);
+
+  @protected
+  AstralSecretRotationPeer dco_decode_astral_secret_rotation_peer(dynamic raw);
+
+  @protected
+  AstralSecretRotationStatus dco_decode_astral_secret_rotation_status(
     dynamic raw,
   );
 
   @protected
-  WfpController
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+  bool dco_decode_bool(dynamic raw);
+
+  @protected
+  AstralBandwidthLimit dco_decode_box_autoadd_astral_bandwidth_limit(
     dynamic raw,
   );
 
   @protected
-  String dco_decode_String(dynamic raw);
+  AstralPeerConn dco_decode_box_autoadd_astral_peer_conn(dynamic raw);
 
   @protected
-  bool dco_decode_bool(dynamic raw);
+  AstralPortForward dco_decode_box_autoadd_astral_port_forward(dynamic raw);
 
   @protected
   FlagsC dco_decode_box_autoadd_flags_c(dynamic raw);
 
+  @protected
+  Forward dco_decode_box_autoadd_forward(dynamic raw);
+
   @protected
   Protocol dco_decode_box_autoadd_protocol(dynamic raw);
 
@@ -225,6 +226,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   int dco_decode_box_autoadd_u_32(dynamic raw);
 
+  @protected
+  BigInt dco_decode_box_autoadd_u_64(dynamic raw);
+
   @protected
   Direction dco_decode_direction(dynamic raw);
 
@@ -247,13 +251,7 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   int dco_decode_i_32(dynamic raw);
 
   @protected
-  KVNetworkStatus dco_decode_kv_network_status(dynamic raw);
-
-  @protected
-  KVNodeConnectionStats dco_decode_kv_node_connection_stats(dynamic raw);
-
-  @protected
-  KVNodeInfo dco_decode_kv_node_info(dynamic raw);
+  PlatformInt64 dco_decode_i_64(dynamic raw);
 
   @protected
   List<FilterRule>
@@ -262,27 +260,37 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  List<PeerRoutePair>
-  dco_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
+  List<String> dco_decode_list_String(dynamic raw);
+
+  @protected
+  List<AstralBandwidthLimit> dco_decode_list_astral_bandwidth_limit(
     dynamic raw,
   );
 
   @protected
-  List<String> dco_decode_list_String(dynamic raw);
+  List<AstralConnection> dco_decode_list_astral_connection(dynamic raw);
 
   @protected
-  List<Forward> dco_decode_list_forward(dynamic raw);
+  List<AstralHop> dco_decode_list_astral_hop(dynamic raw);
+
+  @protected
+  List<AstralInterface> dco_decode_list_astral_interface(dynamic raw);
+
+  @protected
+  List<AstralNode> dco_decode_list_astral_node(dynamic raw);
 
   @protected
-  List<KVNodeConnectionStats> dco_decode_list_kv_node_connection_stats(
+  List<AstralPortForwardStatus> dco_decode_list_astral_port_forward_status(
     dynamic raw,
   );
 
   @protected
-  List<KVNodeInfo> dco_decode_list_kv_node_info(dynamic raw);
+  List<AstralSecretRotationPeer> dco_decode_list_astral_secret_rotation_peer(
+    dynamic raw,
+  );
 
   @protected
-  List<NodeHopStats> dco_decode_list_node_hop_stats(dynamic raw);
+  List<Forward> dco_decode_list_forward(dynamic raw);
 
   @protected
   Uint16List dco_decode_list_prim_u_16_strict(dynamic raw);
@@ -297,10 +305,15 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   List<(String, int)> dco_decode_list_record_string_u_32(dynamic raw);
 
   @protected
-  NodeHopStats dco_decode_node_hop_stats(dynamic raw);
+  String? dco_decode_opt_String(dynamic raw);
 
   @protected
-  String? dco_decode_opt_String(dynamic raw);
+  AstralPeerConn? dco_decode_opt_box_autoadd_astral_peer_conn(dynamic raw);
+
+  @protected
+  AstralPortForward? dco_decode_opt_box_autoadd_astral_port_forward(
+    dynamic raw,
+  );
 
   @protected
   Protocol? dco_decode_opt_box_autoadd_protocol(dynamic raw);
@@ -314,6 +327,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   int? dco_decode_opt_box_autoadd_u_32(dynamic raw);
 
+  @protected
+  BigInt? dco_decode_opt_box_autoadd_u_64(dynamic raw);
+
   @protected
   Protocol dco_decode_protocol(dynamic raw);
 
@@ -344,12 +360,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   AnyhowException sse_decode_AnyhowException(SseDeserializer deserializer);
 
-  @protected
-  EventBusSubscriber
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    SseDeserializer deserializer,
-  );
-
   @protected
   FilterRule
   sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -368,24 +378,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseDeserializer deserializer,
   );
 
-  @protected
-  JoinHandle
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    SseDeserializer deserializer,
-  );
-
-  @protected
-  JoinHandleResultString
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    SseDeserializer deserializer,
-  );
-
-  @protected
-  PeerRoutePair
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    SseDeserializer deserializer,
-  );
-
   @protected
   WfpController
   sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
@@ -434,12 +426,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseDeserializer deserializer,
   );
 
-  @protected
-  EventBusSubscriber
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    SseDeserializer deserializer,
-  );
-
   @protected
   FilterRule
   sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -465,38 +451,94 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  JoinHandle
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
+  WfpController
+  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
     SseDeserializer deserializer,
   );
 
   @protected
-  JoinHandleResultString
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
+  RustStreamSink<AstralEvent> sse_decode_StreamSink_astral_event_Sse(
     SseDeserializer deserializer,
   );
 
   @protected
-  PeerRoutePair
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
+  String sse_decode_String(SseDeserializer deserializer);
+
+  @protected
+  AstralBandwidthLimit sse_decode_astral_bandwidth_limit(
     SseDeserializer deserializer,
   );
 
   @protected
-  WfpController
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+  AstralConnection sse_decode_astral_connection(SseDeserializer deserializer);
+
+  @protected
+  AstralEvent sse_decode_astral_event(SseDeserializer deserializer);
+
+  @protected
+  AstralEventKind sse_decode_astral_event_kind(SseDeserializer deserializer);
+
+  @protected
+  AstralHop sse_decode_astral_hop(SseDeserializer deserializer);
+
+  @protected
+  AstralInterface sse_decode_astral_interface(SseDeserializer deserializer);
+
+  @protected
+  AstralNetworkSnapshot sse_decode_astral_network_snapshot(
     SseDeserializer deserializer,
   );
 
   @protected
-  String sse_decode_String(SseDeserializer deserializer);
+  AstralNode sse_decode_astral_node(SseDeserializer deserializer);
+
+  @protected
+  AstralPeerConn sse_decode_astral_peer_conn(SseDeserializer deserializer);
+
+  @protected
+  AstralPortForward sse_decode_astral_port_forward(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralPortForwardStatus sse_decode_astral_port_forward_status(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralSecretRotationPeer sse_decode_astral_secret_rotation_peer(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralSecretRotationStatus sse_decode_astral_secret_rotation_status(
+    SseDeserializer deserializer,
+  );
 
   @protected
   bool sse_decode_bool(SseDeserializer deserializer);
 
+  @protected
+  AstralBandwidthLimit sse_decode_box_autoadd_astral_bandwidth_limit(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralPeerConn sse_decode_box_autoadd_astral_peer_conn(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralPortForward sse_decode_box_autoadd_astral_port_forward(
+    SseDeserializer deserializer,
+  );
+
   @protected
   FlagsC sse_decode_box_autoadd_flags_c(SseDeserializer deserializer);
 
+  @protected
+  Forward sse_decode_box_autoadd_forward(SseDeserializer deserializer);
+
   @protected
   Protocol sse_decode_box_autoadd_protocol(SseDeserializer deserializer);
 
@@ -511,6 +553,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   int sse_decode_box_autoadd_u_32(SseDeserializer deserializer);
 
+  @protected
+  BigInt sse_decode_box_autoadd_u_64(SseDeserializer deserializer);
+
   @protected
   Direction sse_decode_direction(SseDeserializer deserializer);
 
@@ -533,47 +578,51 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   int sse_decode_i_32(SseDeserializer deserializer);
 
   @protected
-  KVNetworkStatus sse_decode_kv_network_status(SseDeserializer deserializer);
+  PlatformInt64 sse_decode_i_64(SseDeserializer deserializer);
 
   @protected
-  KVNodeConnectionStats sse_decode_kv_node_connection_stats(
+  List<FilterRule>
+  sse_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
     SseDeserializer deserializer,
   );
 
   @protected
-  KVNodeInfo sse_decode_kv_node_info(SseDeserializer deserializer);
+  List<String> sse_decode_list_String(SseDeserializer deserializer);
 
   @protected
-  List<FilterRule>
-  sse_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
+  List<AstralBandwidthLimit> sse_decode_list_astral_bandwidth_limit(
     SseDeserializer deserializer,
   );
 
   @protected
-  List<PeerRoutePair>
-  sse_decode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
+  List<AstralConnection> sse_decode_list_astral_connection(
     SseDeserializer deserializer,
   );
 
   @protected
-  List<String> sse_decode_list_String(SseDeserializer deserializer);
-
-  @protected
-  List<Forward> sse_decode_list_forward(SseDeserializer deserializer);
+  List<AstralHop> sse_decode_list_astral_hop(SseDeserializer deserializer);
 
   @protected
-  List<KVNodeConnectionStats> sse_decode_list_kv_node_connection_stats(
+  List<AstralInterface> sse_decode_list_astral_interface(
     SseDeserializer deserializer,
   );
 
   @protected
-  List<KVNodeInfo> sse_decode_list_kv_node_info(SseDeserializer deserializer);
+  List<AstralNode> sse_decode_list_astral_node(SseDeserializer deserializer);
+
+  @protected
+  List<AstralPortForwardStatus> sse_decode_list_astral_port_forward_status(
+    SseDeserializer deserializer,
+  );
 
   @protected
-  List<NodeHopStats> sse_decode_list_node_hop_stats(
+  List<AstralSecretRotationPeer> sse_decode_list_astral_secret_rotation_peer(
     SseDeserializer deserializer,
   );
 
+  @protected
+  List<Forward> sse_decode_list_forward(SseDeserializer deserializer);
+
   @protected
   Uint16List sse_decode_list_prim_u_16_strict(SseDeserializer deserializer);
 
@@ -589,10 +638,17 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  NodeHopStats sse_decode_node_hop_stats(SseDeserializer deserializer);
+  String? sse_decode_opt_String(SseDeserializer deserializer);
 
   @protected
-  String? sse_decode_opt_String(SseDeserializer deserializer);
+  AstralPeerConn? sse_decode_opt_box_autoadd_astral_peer_conn(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AstralPortForward? sse_decode_opt_box_autoadd_astral_port_forward(
+    SseDeserializer deserializer,
+  );
 
   @protected
   Protocol? sse_decode_opt_box_autoadd_protocol(SseDeserializer deserializer);
@@ -608,6 +664,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   int? sse_decode_opt_box_autoadd_u_32(SseDeserializer deserializer);
 
+  @protected
+  BigInt? sse_decode_opt_box_autoadd_u_64(SseDeserializer deserializer);
+
   @protected
   Protocol sse_decode_protocol(SseDeserializer deserializer);
 
@@ -641,13 +700,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
-  @protected
-  void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    EventBusSubscriber self,
-    SseSerializer serializer,
-  );
-
   @protected
   void
   sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -669,27 +721,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
-  @protected
-  void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    JoinHandle self,
-    SseSerializer serializer,
-  );
-
-  @protected
-  void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    JoinHandleResultString self,
-    SseSerializer serializer,
-  );
-
-  @protected
-  void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    PeerRoutePair self,
-    SseSerializer serializer,
-  );
-
   @protected
   void
   sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
@@ -746,13 +777,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
-  @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    EventBusSubscriber self,
-    SseSerializer serializer,
-  );
-
   @protected
   void
   sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
@@ -783,41 +807,116 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
 
   @protected
   void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    JoinHandle self,
+  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
+    WfpController self,
     SseSerializer serializer,
   );
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    JoinHandleResultString self,
+  void sse_encode_StreamSink_astral_event_Sse(
+    RustStreamSink<AstralEvent> self,
     SseSerializer serializer,
   );
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    PeerRoutePair self,
+  void sse_encode_String(String self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_astral_bandwidth_limit(
+    AstralBandwidthLimit self,
     SseSerializer serializer,
   );
 
   @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
-    WfpController self,
+  void sse_encode_astral_connection(
+    AstralConnection self,
     SseSerializer serializer,
   );
 
   @protected
-  void sse_encode_String(String self, SseSerializer serializer);
+  void sse_encode_astral_event(AstralEvent self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_astral_event_kind(
+    AstralEventKind self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_hop(AstralHop self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_astral_interface(
+    AstralInterface self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_network_snapshot(
+    AstralNetworkSnapshot self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_node(AstralNode self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_astral_peer_conn(
+    AstralPeerConn self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_port_forward(
+    AstralPortForward self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_port_forward_status(
+    AstralPortForwardStatus self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_secret_rotation_peer(
+    AstralSecretRotationPeer self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_astral_secret_rotation_status(
+    AstralSecretRotationStatus self,
+    SseSerializer serializer,
+  );
 
   @protected
   void sse_encode_bool(bool self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_box_autoadd_astral_bandwidth_limit(
+    AstralBandwidthLimit self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_box_autoadd_astral_peer_conn(
+    AstralPeerConn self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_box_autoadd_astral_port_forward(
+    AstralPortForward self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_box_autoadd_flags_c(FlagsC self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_box_autoadd_forward(Forward self, SseSerializer serializer);
+
   @protected
   void sse_encode_box_autoadd_protocol(Protocol self, SseSerializer serializer);
 
@@ -833,6 +932,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_box_autoadd_u_32(int self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_box_autoadd_u_64(BigInt self, SseSerializer serializer);
+
   @protected
   void sse_encode_direction(Direction self, SseSerializer serializer);
 
@@ -855,58 +957,63 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   void sse_encode_i_32(int self, SseSerializer serializer);
 
   @protected
-  void sse_encode_kv_network_status(
-    KVNetworkStatus self,
-    SseSerializer serializer,
-  );
+  void sse_encode_i_64(PlatformInt64 self, SseSerializer serializer);
 
   @protected
-  void sse_encode_kv_node_connection_stats(
-    KVNodeConnectionStats self,
+  void
+  sse_encode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
+    List<FilterRule> self,
     SseSerializer serializer,
   );
 
   @protected
-  void sse_encode_kv_node_info(KVNodeInfo self, SseSerializer serializer);
+  void sse_encode_list_String(List<String> self, SseSerializer serializer);
 
   @protected
-  void
-  sse_encode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
-    List<FilterRule> self,
+  void sse_encode_list_astral_bandwidth_limit(
+    List<AstralBandwidthLimit> self,
     SseSerializer serializer,
   );
 
   @protected
-  void
-  sse_encode_list_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    List<PeerRoutePair> self,
+  void sse_encode_list_astral_connection(
+    List<AstralConnection> self,
     SseSerializer serializer,
   );
 
   @protected
-  void sse_encode_list_String(List<String> self, SseSerializer serializer);
+  void sse_encode_list_astral_hop(
+    List<AstralHop> self,
+    SseSerializer serializer,
+  );
 
   @protected
-  void sse_encode_list_forward(List<Forward> self, SseSerializer serializer);
+  void sse_encode_list_astral_interface(
+    List<AstralInterface> self,
+    SseSerializer serializer,
+  );
 
   @protected
-  void sse_encode_list_kv_node_connection_stats(
-    List<KVNodeConnectionStats> self,
+  void sse_encode_list_astral_node(
+    List<AstralNode> self,
     SseSerializer serializer,
   );
 
   @protected
-  void sse_encode_list_kv_node_info(
-    List<KVNodeInfo> self,
+  void sse_encode_list_astral_port_forward_status(
+    List<AstralPortForwardStatus> self,
     SseSerializer serializer,
   );
 
   @protected
-  void sse_encode_list_node_hop_stats(
-    List<NodeHopStats> self,
+  void sse_encode_list_astral_secret_rotation_peer(
+    List<AstralSecretRotationPeer> self,
     SseSerializer serializer,
   );
 
+  @protected
+  void sse_encode_list_forward(List<Forward> self, SseSerializer serializer);
+
   @protected
   void sse_encode_list_prim_u_16_strict(
     Uint16List self,
@@ -932,10 +1039,19 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  void sse_encode_node_hop_stats(NodeHopStats self, SseSerializer serializer);
+  void sse_encode_opt_String(String? self, SseSerializer serializer);
 
   @protected
-  void sse_encode_opt_String(String? self, SseSerializer serializer);
+  void sse_encode_opt_box_autoadd_astral_peer_conn(
+    AstralPeerConn? self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_opt_box_autoadd_astral_port_forward(
+    AstralPortForward? self,
+    SseSerializer serializer,
+  );
 
   @protected
   void sse_encode_opt_box_autoadd_protocol(
@@ -955,6 +1071,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_opt_box_autoadd_u_32(int? self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_opt_box_autoadd_u_64(BigInt? self, SseSerializer serializer);
+
   @protected
   void sse_encode_protocol(Protocol self, SseSerializer serializer);
 
@@ -991,22 +1110,6 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
 class RustLibWire implements BaseWire {
   RustLibWire.fromExternalLibrary(ExternalLibrary lib);
 
-  void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    int ptr,
-  ) => wasmModule
-      .rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-        ptr,
-      );
-
-  void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    int ptr,
-  ) => wasmModule
-      .rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-        ptr,
-      );
-
   void
   rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
     int ptr,
@@ -1071,54 +1174,6 @@ class RustLibWire implements BaseWire {
         ptr,
       );
 
-  void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    int ptr,
-  ) => wasmModule
-      .rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-        ptr,
-      );
-
-  void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    int ptr,
-  ) => wasmModule
-      .rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-        ptr,
-      );
-
-  void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    int ptr,
-  ) => wasmModule
-      .rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-        ptr,
-      );
-
-  void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    int ptr,
-  ) => wasmModule
-      .rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-        ptr,
-      );
-
-  void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    int ptr,
-  ) => wasmModule
-      .rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-        ptr,
-      );
-
-  void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    int ptr,
-  ) => wasmModule
-      .rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-        ptr,
-      );
-
   void
   rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
     int ptr,
@@ -1139,19 +1194,6 @@ class RustLibWire implements BaseWire {
 @JS('wasm_bindgen')
 external RustLibWasmModule get wasmModule;
 
-@JS()
-@anonymous
-extension type RustLibWasmModule._(JSObject _) implements JSObject {
-  external void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    int ptr,
-  );
-
-  external void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-    int ptr,
-  );
-
   external void
   rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
     int ptr,
@@ -1192,36 +1234,6 @@ extension type RustLibWasmModule._(JSObject _) implements JSObject {
     int ptr,
   );
 
-  external void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    int ptr,
-  );
-
-  external void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-    int ptr,
-  );
-
-  external void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    int ptr,
-  );
-
-  external void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-    int ptr,
-  );
-
-  external void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    int ptr,
-  );
-
-  external void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-    int ptr,
-  );
-
   external void
   rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
     int ptr,
@@ -37,7 +37,7 @@ class BaseState {
   final Signal<List<String>> customVpn = signal([]);
 
   /// 网络状态
-  final Signal<KVNetworkStatus?> netStatus = signal(null);
+  final Signal<AstralNetworkSnapshot?> netStatus = signal(null);
 
   /// 是否正在连接
   final Signal<bool> isConnecting = signal(false);
@@ -32,7 +32,7 @@ class V2BaseState {
   final Signal<bool> isConnecting = signal(false);
 
   /// 网络状态
-  final Signal<KVNetworkStatus?> netStatus = signal(null);
+  final Signal<AstralNetworkSnapshot?> netStatus = signal(null);
 
   /// 自定义VPN网段
   final Signal<List<String>> customVpn = signal([]);
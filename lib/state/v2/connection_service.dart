@@ -1,9 +1,9 @@
 import 'dart:async';
-import 'dart:convert';
 import 'dart:io';
 import 'package:astral/models/net_node.dart';
 import 'package:astral/models/room_info.dart';
 import 'package:astral/models/user_info.dart';
+import 'package:astral/src/rust/api/events.dart';
 import 'package:astral/src/rust/api/hops.dart';
 import 'package:astral/src/rust/api/simple.dart';
 import 'package:astral/state/app_state.dart';
@@ -23,7 +23,6 @@ class V2ConnectionService {
   /* ------------------------ 常量定义 ------------------------ */
   static const int connectionTimeoutSeconds = 15;
   static const int networkMonitoringIntervalSeconds = 1;
-  static const int connectionCheckIntervalSeconds = 1;
   static const String invalidIpAddress = "0.0.0.0";
   static const String defaultCidrMask = "/24";
   static const int defaultMtu = 1360;
@@ -37,6 +36,9 @@ class V2ConnectionService {
   Timer? _connectionTimer;
   Timer? _timeoutTimer;
   BuildContext? _currentContext; // 保存当前context用于显示对话框
+  String? _instanceId; // createServer 返回的实例ID
+  StreamSubscription<AstralEvent>? _eventSubscription;
+  NetNode? _connectingNode; // 正在等待连接成功的节点配置
 
   /* ------------------------ VPN状态跟踪 ------------------------ */
   int? _vpnFileDescriptor;
@@ -97,6 +99,7 @@ class V2ConnectionService {
 
     // 保存context用于后续显示对话框
     _currentContext = context;
+    _subscribeEvents();
 
     try {
       await _initializeServer(room, netNode);
@@ -112,7 +115,12 @@ class V2ConnectionService {
     _updateConnectionState(false);
     _stopVpn();
     _cancelAllTimers();
-    closeServer();
+    _connectingNode = null;
+    final instanceId = _instanceId;
+    _instanceId = null;
+    if (instanceId != null) {
+      closeServer(instanceId: instanceId);
+    }
     _clearUserInfo();
     _currentContext = null; // 清除保存的context
     _vpnDialogShown = false; // 重置对话框标记
@@ -127,6 +135,8 @@ class V2ConnectionService {
   /// 清理资源
   void dispose() {
     _cancelAllTimers();
+    _eventSubscription?.cancel();
+    _eventSubscription = null;
   }
 
   /* ------------------------ 初始化相关 ------------------------ */
@@ -143,7 +153,10 @@ class V2ConnectionService {
       debugPrint('VPN服务已启动，文件描述符: $fd');
 
       if (_isVpnServiceStarted && fd != null) {
-        setTunFd(fd: fd);
+        final instanceId = _instanceId;
+        if (instanceId != null) {
+          setTunFd(instanceId: instanceId, fd: fd);
+        }
         debugPrint('VPN文件描述符已设置: $fd');
 
         // 通知VPN状态变化（如果有context的话）
@@ -173,6 +186,33 @@ class V2ConnectionService {
     });
   }
 
+  /// 订阅 Rust 侧推送的实例事件，只订阅一次
+  void _subscribeEvents() {
+    _eventSubscription ??= subscribeEvents().listen(
+      _handleEvent,
+      onError: (e) => debugPrint('事件订阅错误: $e'),
+    );
+  }
+
+  /// 处理当前实例的事件
+  void _handleEvent(AstralEvent event) {
+    if (event.instanceId != _instanceId) {
+      return;
+    }
+
+    if (event.kind == AstralEventKind.dhcpIpv4Changed) {
+      final newAddr = event.newAddr;
+      if (newAddr != null && newAddr.isNotEmpty) {
+        AppState().v2UserState.ipv4.value = newAddr.split('/').first;
+      }
+    }
+
+    // 连接中时，任何事件都可能意味着虚拟IP已就绪
+    if (_connectingNode != null) {
+      _checkConnectionStatus();
+    }
+  }
+
   /// 获取当前context
   BuildContext? _getCurrentContext() {
     return navigatorKey?.currentContext;
@@ -230,7 +270,7 @@ class V2ConnectionService {
     print("初始化服务器");
     final serverConfig = _buildServerConfig(room, netNode);
     final username = _buildUsername();
-    await createServer(
+    _instanceId = await createServer(
       username: username,
       enableDhcp: serverConfig.enableDhcp,
       specifiedIp: serverConfig.specifiedIp,
@@ -323,6 +363,7 @@ class V2ConnectionService {
       devName: netNode.dev_name,
       enableEncryption: netNode.enable_encryption,
       enableIpv6: true,
+      dhcpIpv6: netNode.dhcp_ipv6,
       mtu: netNode.enable_encryption ? mtuWithEncryption : mtuWithoutEncryption,
       multiThread: netNode.multi_thread,
       latencyFirst: netNode.latency_first,
@@ -334,6 +375,7 @@ class V2ConnectionService {
       relayAllPeerRpc: netNode.relay_all_peer_rpc,
       disableUdpHolePunching: netNode.disable_udp_hole_punching,
       dataCompressAlgo: netNode.data_compress_algo,
+      dataCompressLevel: netNode.data_compress_level,
       bindDevice: netNode.bind_device,
       enableKcpProxy: netNode.enable_kcp_proxy,
       disableKcpInput: netNode.disable_kcp_input,
@@ -352,7 +394,9 @@ class V2ConnectionService {
   Future<void> _beginConnectionProcess(NetNode netNode) async {
     AppState().v2BaseState.setConnecting();
     _setupConnectionTimeout();
-    _startConnectionStatusCheck(netNode);
+    _connectingNode = netNode;
+    // 实例可能在订阅事件之前就已拿到IP，先主动检查一次
+    await _checkConnectionStatus();
   }
 
   /// 设置连接超时
@@ -373,25 +417,20 @@ class V2ConnectionService {
     }
   }
 
-  /// 启动连接状态检查
-  void _startConnectionStatusCheck(NetNode netNode) {
-    Timer.periodic(
-      const Duration(seconds: connectionCheckIntervalSeconds),
-      (timer) => _checkConnectionStatus(timer, netNode),
-    );
-  }
-
-  /// 检查连接状态
-  Future<void> _checkConnectionStatus(Timer timer, NetNode netNode) async {
-    if (AppState().v2BaseState.roomConnectionState.value !=
-        RoomConnectionState.connecting) {
-      timer.cancel();
+  /// 检查连接状态，由实例事件触发
+  Future<void> _checkConnectionStatus() async {
+    final netNode = _connectingNode;
+    if (netNode == null ||
+        AppState().v2BaseState.roomConnectionState.value !=
+            RoomConnectionState.connecting) {
+      _connectingNode = null;
       return;
     }
 
     final isConnected = await _checkAndUpdateConnectionStatus(netNode);
-    if (isConnected) {
-      timer.cancel();
+    // 多个事件可能同时触发检查，只处理一次连接成功
+    if (isConnected && identical(_connectingNode, netNode)) {
+      _connectingNode = null;
       // 使用保存的context或通过navigatorKey获取
       final currentContext = _currentContext ?? _getCurrentContext();
       await _handleSuccessfulConnection(netNode, context: currentContext);
@@ -401,9 +440,7 @@ class V2ConnectionService {
   /// 检查并更新连接状态
   Future<bool> _checkAndUpdateConnectionStatus(NetNode netNode) async {
     try {
-      final runningInfo = await getRunningInfo();
-      final data = jsonDecode(runningInfo) as Map<String, dynamic>;
-      final ipv4Address = _extractIpv4Address(data);
+      final ipv4Address = await _fetchLocalIpv4();
 
       if (ipv4Address != invalidIpAddress) {
         if (AppState().v2UserState.ipv4.value != ipv4Address) {
@@ -418,15 +455,25 @@ class V2ConnectionService {
     }
   }
 
-  /// 提取IPv4地址
-  String _extractIpv4Address(Map<String, dynamic> data) {
-    final virtualIpv4 = data['my_node_info']?['virtual_ipv4'];
-    if (virtualIpv4 == null || virtualIpv4.isEmpty) {
+  /// 从当前实例的网络快照中获取本机IPv4地址
+  Future<String> _fetchLocalIpv4() async {
+    final instanceId = _instanceId;
+    if (instanceId == null) {
       return invalidIpAddress;
     }
 
-    final addr = virtualIpv4['address']?['addr'] ?? 0;
-    return intToIp(addr as int);
+    final snapshot = await getNetworkSnapshot(instanceId: instanceId);
+    final localNode = snapshot.nodes.where((node) => node.isLocal).firstOrNull;
+    return localNode == null ? invalidIpAddress : _nodeIpv4(localNode);
+  }
+
+  /// 提取节点的IPv4地址，去掉前缀长度
+  String _nodeIpv4(AstralNode node) {
+    final ipv4 = node.ipv4;
+    if (ipv4 == null || ipv4.isEmpty) {
+      return invalidIpAddress;
+    }
+    return ipv4.split('/').first;
   }
 
   /// 处理成功连接
@@ -440,9 +487,7 @@ class V2ConnectionService {
     // 连接成功后立即从服务器获取最新数据（IP地址等）
     try {
       debugPrint('连接成功，立即获取服务器数据...');
-      final runningInfo = await getRunningInfo();
-      final data = jsonDecode(runningInfo) as Map<String, dynamic>;
-      final extractedIp = _extractIpv4Address(data);
+      final extractedIp = await _fetchLocalIpv4();
 
       if (_isValidIpAddress(extractedIp)) {
         // 更新IP地址到netNode和状态中
@@ -529,9 +574,7 @@ class V2ConnectionService {
     if (ipv4Addr.isEmpty || !_isValidIpAddress(ipv4Addr)) {
       try {
         debugPrint('尝试从服务器获取IP地址...');
-        final runningInfo = await getRunningInfo();
-        final data = jsonDecode(runningInfo) as Map<String, dynamic>;
-        final extractedIp = _extractIpv4Address(data);
+        final extractedIp = await _fetchLocalIpv4();
         if (_isValidIpAddress(extractedIp)) {
           ipv4Addr = extractedIp;
           netNode.ipv4 = extractedIp; // 更新netNode中的IP
@@ -738,7 +781,11 @@ class V2ConnectionService {
   /// 监控网络状态
   Future<void> _monitorNetworkStatus(Timer timer) async {
     try {
-      final status = await getNetworkStatus();
+      final instanceId = _instanceId;
+      if (instanceId == null) {
+        return;
+      }
+      final status = await getNetworkSnapshot(instanceId: instanceId);
       AppState().v2BaseState.netStatus.value = status;
 
       // 更新用户信息列表
@@ -749,7 +796,7 @@ class V2ConnectionService {
   }
 
   /// 更新用户信息列表
-  void _updateUserInfo(KVNetworkStatus status) {
+  void _updateUserInfo(AstralNetworkSnapshot status) {
     final userInfoList =
         status.nodes
             .where(_shouldIncludeNode)
@@ -760,11 +807,11 @@ class V2ConnectionService {
   }
 
   /// 判断节点是否需要展示
-  bool _shouldIncludeNode(KVNodeInfo node) {
+  bool _shouldIncludeNode(AstralNode node) {
     final hostname = node.hostname.toLowerCase();
-    final ipv4 = node.ipv4;
+    final ipv4 = _nodeIpv4(node);
 
-    if (hostname.contains('server')) {
+    if (node.isLocal || hostname.contains('server')) {
       return false;
     }
 
@@ -775,8 +822,8 @@ class V2ConnectionService {
     return true;
   }
 
-  /// 将 KVNodeInfo 转换为 UserInfo
-  UserInfo _convertNodeToUserInfo(KVNodeInfo node) {
+  /// 将 AstralNode 转换为 UserInfo
+  UserInfo _convertNodeToUserInfo(AstralNode node) {
     final version = node.version;
     String device = "unknown";
 
@@ -811,7 +858,7 @@ class V2ConnectionService {
     return UserInfo(
       name: displayName,
       avatarUrl: avatarUrl,
-      ip: node.ipv4,
+      ip: _nodeIpv4(node),
       latency: node.latencyMs.round(),
       device: device,
     );
@@ -2,7 +2,10 @@ use std::{
     collections::{HashMap, HashSet},
     net::{IpAddr, SocketAddr},
     str::FromStr as _,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
@@ -68,6 +71,7 @@ pub struct FastLookupRule {
     pub stateful: bool,
     pub rate_limit: u32,
     pub burst_limit: u32,
+    pub time_windows: Vec<TimeWindow>,
     pub rule_stats: Arc<RuleStats>,
 }
 
@@ -82,6 +86,8 @@ pub struct AclCacheKey {
     pub dst_port: u16,
     pub src_groups: Arc<Vec<String>>,
     pub dst_groups: Arc<Vec<String>>,
+    // Bumped whenever a rule time window opens or closes, so stale entries miss
+    pub time_window_generation: u64,
 }
 
 impl AclCacheKey {
@@ -95,6 +101,7 @@ impl AclCacheKey {
             dst_port: packet_info.dst_port.unwrap_or(0),
             src_groups: packet_info.src_groups.clone(),
             dst_groups: packet_info.dst_groups.clone(),
+            time_window_generation: 0,
         }
     }
 }
@@ -219,6 +226,11 @@ pub struct AclProcessor {
     // Statistics
     stats: Arc<DashMap<AclStatKey, u64>>,
 
+    // Time window tracking, only used when some rule has time windows
+    has_time_windows: bool,
+    time_window_generation: AtomicU64,
+    next_time_window_transition: AtomicU64,
+
     tasks: JoinSet<()>,
 }
 
@@ -238,6 +250,11 @@ impl AclProcessor {
         stats: Option<Arc<DashMap<AclStatKey, u64>>>,
     ) -> Self {
         let (inbound_rules, outbound_rules, forward_rules) = Self::build_rules(&acl_config);
+        let has_time_windows = inbound_rules
+            .iter()
+            .chain(outbound_rules.iter())
+            .chain(forward_rules.iter())
+            .any(|rule| !rule.time_windows.is_empty());
         let (default_inbound_action, default_outbound_action, default_forward_action) =
             Self::build_default_actions(&acl_config);
         let tasks = JoinSet::new();
@@ -264,6 +281,9 @@ impl AclProcessor {
             cache_max_size: 10000,                // Limit cache to 10k entries
             cache_cleanup_interval: Duration::from_secs(20), // Cleanup every 5 minutes
             stats: stats.unwrap_or_else(|| Arc::new(DashMap::new())),
+            has_time_windows,
+            time_window_generation: AtomicU64::new(0),
+            next_time_window_transition: AtomicU64::new(0),
             tasks,
         };
 
@@ -464,18 +484,60 @@ impl AclProcessor {
         stats
     }
 
+    /// Get the time window generation at `now`, advancing it if any window opened or closed
+    fn current_time_window_generation(&self, now: u64) -> u64 {
+        if !self.has_time_windows {
+            return 0;
+        }
+
+        let next_transition = self.next_time_window_transition.load(Ordering::Acquire);
+        if now >= next_transition {
+            let new_transition = self.next_time_window_transition_after(now);
+            // Only the thread that wins the exchange bumps the generation
+            if self
+                .next_time_window_transition
+                .compare_exchange(
+                    next_transition,
+                    new_transition,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                )
+                .is_ok()
+            {
+                self.time_window_generation.fetch_add(1, Ordering::AcqRel);
+            }
+        }
+
+        self.time_window_generation.load(Ordering::Acquire)
+    }
+
+    /// Earliest moment after `now` at which any rule time window may open or close
+    fn next_time_window_transition_after(&self, now: u64) -> u64 {
+        self.inbound_rules
+            .iter()
+            .chain(self.outbound_rules.iter())
+            .chain(self.forward_rules.iter())
+            .flat_map(|rule| rule.time_windows.iter())
+            .map(|window| time_window_next_transition(window, now))
+            .min()
+            .unwrap_or(u64::MAX)
+    }
+
     /// Process a packet through ACL rules - Now lock-free!
     pub fn process_packet(&self, packet_info: &PacketInfo, chain_type: ChainType) -> AclResult {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
         // Check cache first for performance
-        let cache_key = AclCacheKey::from_packet_info(packet_info, chain_type);
+        let mut cache_key = AclCacheKey::from_packet_info(packet_info, chain_type);
+        cache_key.time_window_generation = self.current_time_window_generation(now);
 
         // If cache hit and can skip checks, return cached result
         if let Some(mut cached) = self.rule_cache.get_mut(&cache_key) {
             // Update last access time for LRU
-            cached.last_access = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
+            cached.last_access = now;
 
             self.increment_stat(AclStatKey::CacheHits);
             return self.process_packet_with_cache_entry(packet_info, &cached);
@@ -499,10 +561,7 @@ impl AclProcessor {
         let mut cache_entry = AclCacheEntry {
             action: Action::Allow,
             matched_rule: RuleId::Default,
-            last_access: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+            last_access: now,
             conn_track_key: None,
             rate_limit_keys: vec![],
             chain_type,
@@ -512,7 +571,7 @@ impl AclProcessor {
 
         // Process rules in priority order
         for rule in rules.iter() {
-            if !rule.enabled || !self.rule_matches(rule, packet_info) {
+            if !rule.enabled || !self.rule_matches(rule, packet_info, now) {
                 continue;
             }
 
@@ -630,7 +689,7 @@ impl AclProcessor {
     }
 
     /// Check if a rule matches the packet
-    fn rule_matches(&self, rule: &FastLookupRule, packet_info: &PacketInfo) -> bool {
+    fn rule_matches(&self, rule: &FastLookupRule, packet_info: &PacketInfo, now: u64) -> bool {
         // Protocol check
         if rule.protocol != Protocol::Any && rule.protocol as i32 != packet_info.protocol as i32 {
             return false;
@@ -714,6 +773,17 @@ impl AclProcessor {
             }
         }
 
+        // Time window check
+        if !rule.time_windows.is_empty() {
+            let matches = rule
+                .time_windows
+                .iter()
+                .any(|window| time_window_contains(window, now));
+            if !matches {
+                return false;
+            }
+        }
+
         true
     }
 
@@ -841,6 +911,7 @@ impl AclProcessor {
             stateful: rule.stateful,
             rate_limit: rule.rate_limit,
             burst_limit: rule.burst_limit,
+            time_windows: rule.time_windows.clone(),
             rule_stats: Arc::new(RuleStats {
                 rule: Some(rule.clone()),
                 stat: Some(StatItem {
@@ -953,6 +1024,55 @@ fn parse_port_range(s: &str) -> Option<(u16, u16)> {
     }
 }
 
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Seconds since midnight in the window's timezone, and the local weekday (0 = Sunday)
+fn time_window_local_time(window: &TimeWindow, now: u64) -> (i64, u32) {
+    let local = now as i64 + window.timezone_offset as i64 * 60;
+    // 1970-01-01 was a Thursday
+    let day_of_week = (local.div_euclid(SECS_PER_DAY) + 4).rem_euclid(7) as u32;
+    (local.rem_euclid(SECS_PER_DAY), day_of_week)
+}
+
+/// Check whether the unix timestamp `now` falls inside the time window
+fn time_window_contains(window: &TimeWindow, now: u64) -> bool {
+    let (secs_of_day, day_of_week) = time_window_local_time(window, now);
+    let minute = (secs_of_day / 60) as u32;
+    let day_allowed =
+        |day: u32| window.days_of_week.is_empty() || window.days_of_week.contains(&day);
+
+    let (start, end) = (window.start_time, window.end_time);
+    if start == end {
+        // Whole day
+        day_allowed(day_of_week)
+    } else if start < end {
+        day_allowed(day_of_week) && minute >= start && minute < end
+    } else {
+        // Wraps past midnight, the part after midnight belongs to the previous day's window
+        (minute >= start && day_allowed(day_of_week))
+            || (minute < end && day_allowed((day_of_week + 6) % 7))
+    }
+}
+
+/// Earliest unix timestamp after `now` at which the time window may open or close
+fn time_window_next_transition(window: &TimeWindow, now: u64) -> u64 {
+    let (secs_of_day, _) = time_window_local_time(window, now);
+    // Local midnight is included since the allowed weekday may change there
+    [window.start_time, window.end_time, 0]
+        .iter()
+        .map(|minute| {
+            let target = (*minute as i64 * 60).rem_euclid(SECS_PER_DAY);
+            let delta = if target > secs_of_day {
+                target - secs_of_day
+            } else {
+                SECS_PER_DAY - secs_of_day + target
+            };
+            now + delta as u64
+        })
+        .min()
+        .unwrap()
+}
+
 // Statistics key enum for better performance
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum AclStatKey {
@@ -1105,6 +1225,7 @@ impl AclRuleBuilder {
                 stateful: true,
                 source_groups: vec![],
                 destination_groups: vec![],
+                time_windows: vec![],
             };
             inbound_chain.rules.push(tcp_rule);
             rule_priority -= 1;
@@ -1129,6 +1250,7 @@ impl AclRuleBuilder {
                 stateful: false,
                 source_groups: vec![],
                 destination_groups: vec![],
+                time_windows: vec![],
             };
             inbound_chain.rules.push(udp_rule);
         }
@@ -1630,4 +1752,198 @@ mod tests {
             Some(AclLogContext::RateLimitDrop)
         ));
     }
+
+    // 2024-01-01 00:00:00 UTC, a Monday
+    const MONDAY_MIDNIGHT_UTC: u64 = 1704067200;
+
+    fn at(day: u64, hour: u64, minute: u64) -> u64 {
+        MONDAY_MIDNIGHT_UTC + day * 86400 + hour * 3600 + minute * 60
+    }
+
+    #[test]
+    fn test_time_window_same_day() {
+        let window = TimeWindow {
+            days_of_week: vec![1],
+            start_time: 18 * 60,
+            end_time: 23 * 60,
+            timezone_offset: 0,
+        };
+
+        assert!(time_window_contains(&window, at(0, 19, 0)));
+        assert!(time_window_contains(&window, at(0, 18, 0)));
+        assert!(!time_window_contains(&window, at(0, 17, 59)));
+        assert!(!time_window_contains(&window, at(0, 23, 0)));
+        // Tuesday is not allowed
+        assert!(!time_window_contains(&window, at(1, 19, 0)));
+
+        // Empty days means every day
+        let every_day = TimeWindow {
+            days_of_week: vec![],
+            ..window
+        };
+        assert!(time_window_contains(&every_day, at(1, 19, 0)));
+    }
+
+    #[test]
+    fn test_time_window_midnight_wrap_around() {
+        // Friday 22:00 until Saturday 02:00
+        let window = TimeWindow {
+            days_of_week: vec![5],
+            start_time: 22 * 60,
+            end_time: 2 * 60,
+            timezone_offset: 0,
+        };
+
+        assert!(time_window_contains(&window, at(4, 23, 0)));
+        assert!(time_window_contains(&window, at(5, 1, 59)));
+        assert!(!time_window_contains(&window, at(5, 2, 0)));
+        // The early hours of Friday belong to Thursday's window
+        assert!(!time_window_contains(&window, at(4, 1, 0)));
+        // Saturday night is not allowed
+        assert!(!time_window_contains(&window, at(5, 23, 0)));
+    }
+
+    #[test]
+    fn test_time_window_timezone() {
+        // 09:00-17:00 in UTC+8
+        let window = TimeWindow {
+            days_of_week: vec![],
+            start_time: 9 * 60,
+            end_time: 17 * 60,
+            timezone_offset: 8 * 60,
+        };
+        assert!(time_window_contains(&window, at(0, 2, 0)));
+        assert!(!time_window_contains(&window, at(0, 10, 0)));
+
+        // Monday 03:00 UTC is still Sunday 22:00 in UTC-5
+        let sunday_evening = TimeWindow {
+            days_of_week: vec![0],
+            start_time: 20 * 60,
+            end_time: 23 * 60,
+            timezone_offset: -5 * 60,
+        };
+        assert!(time_window_contains(&sunday_evening, at(0, 3, 0)));
+        let monday_evening = TimeWindow {
+            days_of_week: vec![1],
+            ..sunday_evening
+        };
+        assert!(!time_window_contains(&monday_evening, at(0, 3, 0)));
+    }
+
+    #[test]
+    fn test_time_window_next_transition() {
+        let window = TimeWindow {
+            days_of_week: vec![],
+            start_time: 18 * 60,
+            end_time: 23 * 60,
+            timezone_offset: 0,
+        };
+        assert_eq!(
+            time_window_next_transition(&window, at(0, 19, 0)),
+            at(0, 23, 0)
+        );
+        assert_eq!(
+            time_window_next_transition(&window, at(0, 23, 0)),
+            at(1, 0, 0)
+        );
+        assert_eq!(
+            time_window_next_transition(&window, at(0, 10, 30)),
+            at(0, 18, 0)
+        );
+
+        let shifted = TimeWindow {
+            timezone_offset: 60,
+            ..window
+        };
+        assert_eq!(
+            time_window_next_transition(&shifted, at(0, 10, 30)),
+            at(0, 17, 0)
+        );
+    }
+
+    fn create_time_window_acl_config(window: TimeWindow) -> Acl {
+        let mut acl_config = Acl::default();
+        let mut acl_v1 = AclV1::default();
+        let mut chain = Chain {
+            name: "time_window_chain".to_string(),
+            chain_type: ChainType::Inbound as i32,
+            enabled: true,
+            default_action: Action::Drop as i32,
+            ..Default::default()
+        };
+        chain.rules.push(Rule {
+            name: "game_server_hours".to_string(),
+            priority: 100,
+            enabled: true,
+            action: Action::Allow as i32,
+            protocol: Protocol::Any as i32,
+            ports: vec!["80".to_string()],
+            time_windows: vec![window],
+            ..Default::default()
+        });
+        acl_v1.chains.push(chain);
+        acl_config.acl_v1 = Some(acl_v1);
+        acl_config
+    }
+
+    #[tokio::test]
+    async fn test_time_window_rule_matching() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let minute = ((now % 86400) / 60) as u32;
+
+        let open_window = TimeWindow {
+            start_time: minute,
+            end_time: (minute + 60) % 1440,
+            ..Default::default()
+        };
+        let processor = AclProcessor::new(create_time_window_acl_config(open_window));
+        let result = processor.process_packet(&create_test_packet_info(), ChainType::Inbound);
+        assert_eq!(result.action, Action::Allow);
+        assert_eq!(result.matched_rule, Some(RuleId::Priority(100)));
+
+        let closed_window = TimeWindow {
+            start_time: (minute + 60) % 1440,
+            end_time: (minute + 120) % 1440,
+            ..Default::default()
+        };
+        let processor = AclProcessor::new(create_time_window_acl_config(closed_window));
+        let result = processor.process_packet(&create_test_packet_info(), ChainType::Inbound);
+        assert_eq!(result.action, Action::Drop);
+        assert_eq!(result.matched_rule, Some(RuleId::Default));
+    }
+
+    #[tokio::test]
+    async fn test_time_window_cache_invalidation() {
+        let window = TimeWindow {
+            days_of_week: vec![],
+            start_time: 18 * 60,
+            end_time: 23 * 60,
+            timezone_offset: 0,
+        };
+        let processor = AclProcessor::new(create_time_window_acl_config(window));
+
+        let gen_open = processor.current_time_window_generation(at(0, 19, 0));
+        // Nothing changes until the window closes
+        assert_eq!(
+            processor.current_time_window_generation(at(0, 22, 59)),
+            gen_open
+        );
+        let gen_closed = processor.current_time_window_generation(at(0, 23, 0));
+        assert_ne!(gen_open, gen_closed);
+
+        let packet_info = create_test_packet_info();
+        let mut key_open = AclCacheKey::from_packet_info(&packet_info, ChainType::Inbound);
+        key_open.time_window_generation = gen_open;
+        let mut key_closed = key_open.clone();
+        key_closed.time_window_generation = gen_closed;
+        assert_ne!(key_open, key_closed);
+
+        // Rules without time windows never invalidate the cache
+        let processor = AclProcessor::new(create_test_acl_config());
+        assert_eq!(processor.current_time_window_generation(at(0, 19, 0)), 0);
+        assert_eq!(processor.current_time_window_generation(at(1, 19, 0)), 0);
+    }
 }
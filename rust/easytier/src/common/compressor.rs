@@ -9,6 +9,63 @@ use crate::tunnel::packet_def::{CompressorAlgo, CompressorTail, ZCPacket, COMPRE
 
 type Error = anyhow::Error;
 
+// lz4 block header claims the decompressed size, refuse anything larger than a packet could be
+const LZ4_MAX_DECOMPRESSED_SIZE: usize = 256 * 1024;
+
+/// Handshake feature prefix used to advertise supported compression algorithms
+pub const COMPRESS_FEATURE_PREFIX: &str = "compress:";
+
+fn compress_algo_feature_name(algo: CompressorAlgo) -> Option<&'static str> {
+    match algo {
+        CompressorAlgo::ZstdDefault => Some("zstd"),
+        CompressorAlgo::Lz4 => Some("lz4"),
+        CompressorAlgo::None => None,
+    }
+}
+
+/// Handshake features advertising every compression algorithm this node can decode
+pub fn supported_compress_features() -> Vec<String> {
+    [CompressorAlgo::ZstdDefault, CompressorAlgo::Lz4]
+        .into_iter()
+        .filter_map(compress_algo_feature_name)
+        .map(|name| format!("{}{}", COMPRESS_FEATURE_PREFIX, name))
+        .collect()
+}
+
+/// Compression algorithms a peer can decode, parsed from its handshake features.
+/// Peers that advertise nothing predate negotiation and only understand zstd.
+pub fn peer_supported_compress_algos(features: &[String]) -> Vec<CompressorAlgo> {
+    let advertised = features
+        .iter()
+        .filter_map(|f| f.strip_prefix(COMPRESS_FEATURE_PREFIX))
+        .collect::<Vec<_>>();
+    if advertised.is_empty() {
+        return vec![CompressorAlgo::ZstdDefault];
+    }
+
+    [CompressorAlgo::ZstdDefault, CompressorAlgo::Lz4]
+        .into_iter()
+        .filter(|algo| {
+            compress_algo_feature_name(*algo).is_some_and(|name| advertised.contains(&name))
+        })
+        .collect()
+}
+
+/// Pick the algorithm to use towards a peer: the preferred one if the peer supports it,
+/// otherwise zstd, otherwise no compression.
+pub fn negotiate_compress_algo(
+    preferred: CompressorAlgo,
+    peer_supported: &[CompressorAlgo],
+) -> CompressorAlgo {
+    if preferred == CompressorAlgo::None || peer_supported.contains(&preferred) {
+        preferred
+    } else if peer_supported.contains(&CompressorAlgo::ZstdDefault) {
+        CompressorAlgo::ZstdDefault
+    } else {
+        CompressorAlgo::None
+    }
+}
+
 #[async_trait::async_trait]
 pub trait Compressor {
     async fn compress(
@@ -19,7 +76,10 @@ pub trait Compressor {
     async fn decompress(&self, packet: &mut ZCPacket) -> Result<(), Error>;
 }
 
-pub struct DefaultCompressor {}
+pub struct DefaultCompressor {
+    // 0 means the zstd default level
+    zstd_level: i32,
+}
 
 impl Default for DefaultCompressor {
     fn default() -> Self {
@@ -29,7 +89,15 @@ impl Default for DefaultCompressor {
 
 impl DefaultCompressor {
     pub fn new() -> Self {
-        DefaultCompressor {}
+        Self::with_zstd_level(0)
+    }
+
+    /// Out-of-range levels are clamped to what the linked zstd supports, 0 keeps the default
+    pub fn with_zstd_level(zstd_level: i32) -> Self {
+        let range = zstd::compression_level_range();
+        DefaultCompressor {
+            zstd_level: zstd_level.clamp(*range.start(), *range.end()),
+        }
     }
 
     pub async fn compress_raw(
@@ -40,14 +108,17 @@ impl DefaultCompressor {
         match compress_algo {
             CompressorAlgo::ZstdDefault => CTX_MAP.with(|map_cell| {
                 let map = map_cell.borrow();
-                let mut ctx_entry = map.entry(compress_algo).or_default();
+                let mut ctx_entry = map
+                    .entry(self.zstd_level)
+                    .or_try_insert_with(|| bulk::Compressor::new(self.zstd_level))?;
                 ctx_entry.compress(data).with_context(|| {
                     format!(
-                        "Failed to compress data with algorithm: {:?}",
-                        compress_algo
+                        "Failed to compress data with algorithm: {:?}, level: {}",
+                        compress_algo, self.zstd_level
                     )
                 })
             }),
+            CompressorAlgo::Lz4 => Ok(lz4_flex::block::compress_prepend_size(data)),
             CompressorAlgo::None => Ok(data.to_vec()),
         }
     }
@@ -79,6 +150,13 @@ impl DefaultCompressor {
                     compress_algo
                 ))
             }),
+            CompressorAlgo::Lz4 => {
+                let (len, compressed) = lz4_flex::block::uncompressed_size(data)?;
+                if len > LZ4_MAX_DECOMPRESSED_SIZE {
+                    anyhow::bail!("lz4 decompressed size too large: {}", len);
+                }
+                Ok(lz4_flex::block::decompress(compressed, len)?)
+            }
             CompressorAlgo::None => Ok(data.to_vec()),
         }
     }
@@ -170,7 +248,8 @@ impl Compressor for DefaultCompressor {
 }
 
 thread_local! {
-    static CTX_MAP: RefCell<DashMap<CompressorAlgo, bulk::Compressor<'static>>> = RefCell::new(DashMap::new());
+    // keyed by zstd compression level
+    static CTX_MAP: RefCell<DashMap<i32, bulk::Compressor<'static>>> = RefCell::new(DashMap::new());
     static DCTX_MAP: RefCell<DashMap<CompressorAlgo, bulk::Decompressor<'static>>> = RefCell::new(DashMap::new());
 }
 
@@ -184,7 +263,7 @@ pub mod tests {
         let mut packet = ZCPacket::new_with_payload(text);
         packet.fill_peer_manager_hdr(0, 0, 0);
 
-        let compressor = DefaultCompressor {};
+        let compressor = DefaultCompressor::new();
 
         println!(
             "Uncompressed packet: {:?}, len: {}",
@@ -214,7 +293,7 @@ pub mod tests {
         let mut packet = ZCPacket::new_with_payload(text);
         packet.fill_peer_manager_hdr(0, 0, 0);
 
-        let compressor = DefaultCompressor {};
+        let compressor = DefaultCompressor::new();
 
         // short text can't be compressed
         compressor
@@ -227,4 +306,83 @@ pub mod tests {
         assert_eq!(packet.payload(), text);
         assert!(!packet.peer_manager_header().unwrap().is_compressed());
     }
+
+    #[tokio::test]
+    async fn test_lz4_and_zstd_level_compress() {
+        let text = b"abcdefgh".repeat(64);
+
+        for (compressor, algo) in [
+            (DefaultCompressor::new(), CompressorAlgo::Lz4),
+            (
+                DefaultCompressor::with_zstd_level(1),
+                CompressorAlgo::ZstdDefault,
+            ),
+            (
+                DefaultCompressor::with_zstd_level(19),
+                CompressorAlgo::ZstdDefault,
+            ),
+            // out of range, clamped to the max level
+            (
+                DefaultCompressor::with_zstd_level(1000),
+                CompressorAlgo::ZstdDefault,
+            ),
+        ] {
+            let mut packet = ZCPacket::new_with_payload(&text);
+            packet.fill_peer_manager_hdr(0, 0, 0);
+
+            compressor.compress(&mut packet, algo).await.unwrap();
+            assert!(packet.peer_manager_header().unwrap().is_compressed());
+            assert!(packet.payload_len() < text.len());
+
+            // the receiver doesn't need to know the level
+            DefaultCompressor::new()
+                .decompress(&mut packet)
+                .await
+                .unwrap();
+            assert_eq!(packet.payload(), &text[..]);
+            assert!(!packet.peer_manager_header().unwrap().is_compressed());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lz4_reject_oversized_header() {
+        let mut data = ((LZ4_MAX_DECOMPRESSED_SIZE + 1) as u32)
+            .to_le_bytes()
+            .to_vec();
+        data.extend_from_slice(&[0u8; 16]);
+        assert!(DefaultCompressor::new()
+            .decompress_raw(&data, CompressorAlgo::Lz4)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_compress_algo_negotiation() {
+        let features = supported_compress_features();
+        let all = peer_supported_compress_algos(&features);
+        assert!(all.contains(&CompressorAlgo::Lz4));
+        assert!(all.contains(&CompressorAlgo::ZstdDefault));
+        assert_eq!(
+            negotiate_compress_algo(CompressorAlgo::Lz4, &all),
+            CompressorAlgo::Lz4
+        );
+
+        // old peers advertise nothing and only decode zstd
+        let legacy = peer_supported_compress_algos(&[]);
+        assert_eq!(legacy, vec![CompressorAlgo::ZstdDefault]);
+        assert_eq!(
+            negotiate_compress_algo(CompressorAlgo::Lz4, &legacy),
+            CompressorAlgo::ZstdDefault
+        );
+
+        let lz4_only = peer_supported_compress_algos(&["compress:lz4".to_string()]);
+        assert_eq!(
+            negotiate_compress_algo(CompressorAlgo::ZstdDefault, &lz4_only),
+            CompressorAlgo::None
+        );
+        assert_eq!(
+            negotiate_compress_algo(CompressorAlgo::None, &all),
+            CompressorAlgo::None
+        );
+    }
 }
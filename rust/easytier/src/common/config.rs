@@ -1,6 +1,6 @@
 use std::{
     hash::Hasher,
-    net::{IpAddr, SocketAddr},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
     sync::{Arc, Mutex},
 };
@@ -13,7 +13,10 @@ use crate::{
     common::stun::StunInfoCollector,
     proto::{
         acl::Acl,
-        common::{CompressionAlgoPb, PortForwardConfigPb, SocketType},
+        common::{
+            BandwidthLimitPb, CompressionAlgoPb, MultipathMode, PortForwardConfigPb,
+            RouteCostPolicy, SocketType,
+        },
     },
     tunnel::generate_digest_from_str,
 };
@@ -38,6 +41,17 @@ pub fn gen_default_flags() -> Flags {
         disable_udp_hole_punching: false,
         multi_thread: true,
         data_compress_algo: CompressionAlgoPb::None.into(),
+        data_compress_level: 0,
+        multipath_mode: MultipathMode::Off.into(),
+        route_cost_policy: RouteCostPolicy::LeastLatency.into(),
+        disable_relay_data: false,
+        relay_daily_quota_mb: 0,
+        broadcast_relay_ports: "".to_string(),
+        multicast_igmp_snooping: false,
+        dhcp_ipv6: false,
+        ipv6_ula_prefix: "".to_string(),
+        disable_tcp_hole_punching: false,
+        enable_port_mapping: false,
         bind_device: true,
         enable_kcp_proxy: false,
         disable_kcp_input: false,
@@ -177,12 +191,18 @@ pub trait ConfigLoader: Send + Sync {
     fn get_vpn_portal_config(&self) -> Option<VpnPortalConfig>;
     fn set_vpn_portal_config(&self, config: VpnPortalConfig);
 
+    fn get_node_identity_config(&self) -> Option<NodeIdentityConfig>;
+    fn set_node_identity_config(&self, config: Option<NodeIdentityConfig>);
+
     fn get_flags(&self) -> Flags;
     fn set_flags(&self, flags: Flags);
 
     fn get_exit_nodes(&self) -> Vec<IpAddr>;
     fn set_exit_nodes(&self, nodes: Vec<IpAddr>);
 
+    fn get_exit_node_check_targets(&self) -> Vec<SocketAddr>;
+    fn set_exit_node_check_targets(&self, targets: Vec<SocketAddr>);
+
     fn get_routes(&self) -> Option<Vec<cidr::Ipv4Cidr>>;
     fn set_routes(&self, routes: Option<Vec<cidr::Ipv4Cidr>>);
 
@@ -192,6 +212,12 @@ pub trait ConfigLoader: Send + Sync {
     fn get_port_forwards(&self) -> Vec<PortForwardConfig>;
     fn set_port_forwards(&self, forwards: Vec<PortForwardConfig>);
 
+    fn get_bandwidth_limits(&self) -> Vec<BandwidthLimitConfig>;
+    fn set_bandwidth_limits(&self, limits: Vec<BandwidthLimitConfig>);
+
+    fn get_route_costs(&self) -> Vec<RouteCostConfig>;
+    fn set_route_costs(&self, costs: Vec<RouteCostConfig>);
+
     fn get_acl(&self) -> Option<Acl>;
     fn set_acl(&self, acl: Option<Acl>);
 
@@ -339,10 +365,155 @@ impl LoggingConfigLoader for &LoggingConfig {
     }
 }
 
+// with node identities, peers of the same network must also prove they own a key
+// trusted by the network, so knowing the network secret is not enough to join
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct NodeIdentityConfig {
+    // base64 ed25519 private key (the 32 byte seed) of this node
+    pub private_key: String,
+    // base64 public keys of the members of the network
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_public_keys: Vec<String>,
+    // base64 public key of the network ca, members signed by it are trusted as well
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_public_key: Option<String>,
+    // base64 certificate of this node signed by the network ca
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct VpnPortalConfig {
     pub client_cidr: cidr::Ipv4Cidr,
-    pub wireguard_listen: SocketAddr,
+    // the wireguard portal is only created when this is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wireguard_listen: Option<SocketAddr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub openvpn: Option<OpenVpnPortalConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadowsocks: Option<ShadowsocksPortalConfig>,
+    // when set, only these wireguard clients are accepted instead of the shared client key
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wireguard_clients: Vec<WireGuardClientConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct WireGuardClientConfig {
+    pub name: String,
+    // the fixed address of the client, inside the client cidr
+    pub address: Ipv4Addr,
+    // base64 public key of a key pair generated by the client,
+    // the key pair is derived from the network secret and the name if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    // revoked clients are kept so their address is not handed out again
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub revoked: bool,
+}
+
+impl std::str::FromStr for WireGuardClientConfig {
+    type Err = anyhow::Error;
+
+    // name:address[:public_key]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, ':');
+        let name = parts.next().unwrap_or_default();
+        if name.is_empty() {
+            anyhow::bail!("wireguard client name is empty: {}", s);
+        }
+        let address = parts
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("wireguard client address is missing: {}", s))?
+            .parse()
+            .with_context(|| format!("invalid wireguard client address: {}", s))?;
+        Ok(Self {
+            name: name.to_string(),
+            address,
+            public_key: parts.next().map(str::to_string),
+            revoked: false,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OpenVpnAuthMode {
+    // certificates derived from the network secret, clients get an address pushed from the pool
+    #[default]
+    Tls,
+    // a shared static key, clients configure their own address from the client cidr
+    StaticKey,
+}
+
+impl std::str::FromStr for OpenVpnAuthMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tls" => Ok(OpenVpnAuthMode::Tls),
+            "static-key" => Ok(OpenVpnAuthMode::StaticKey),
+            _ => Err(anyhow::anyhow!(
+                "invalid openvpn auth mode: {}, expected tls or static-key",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct OpenVpnPortalConfig {
+    // udp://0.0.0.0:1194 or tcp://0.0.0.0:1194
+    pub listen: url::Url,
+    #[serde(default)]
+    pub auth: OpenVpnAuthMode,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ShadowsocksMethod {
+    #[serde(rename = "aes-128-gcm")]
+    Aes128Gcm,
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm,
+    #[default]
+    #[serde(rename = "chacha20-ietf-poly1305")]
+    Chacha20IetfPoly1305,
+}
+
+impl ShadowsocksMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShadowsocksMethod::Aes128Gcm => "aes-128-gcm",
+            ShadowsocksMethod::Aes256Gcm => "aes-256-gcm",
+            ShadowsocksMethod::Chacha20IetfPoly1305 => "chacha20-ietf-poly1305",
+        }
+    }
+}
+
+impl std::str::FromStr for ShadowsocksMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "aes-128-gcm" => Ok(ShadowsocksMethod::Aes128Gcm),
+            "aes-256-gcm" => Ok(ShadowsocksMethod::Aes256Gcm),
+            "chacha20-ietf-poly1305" => Ok(ShadowsocksMethod::Chacha20IetfPoly1305),
+            _ => Err(anyhow::anyhow!(
+                "invalid shadowsocks method: {}, expected aes-128-gcm, aes-256-gcm or chacha20-ietf-poly1305",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ShadowsocksPortalConfig {
+    // tcp and udp are both served on this address
+    pub listen: SocketAddr,
+    #[serde(default)]
+    pub method: ShadowsocksMethod,
+    // derived from the network secret if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
@@ -380,6 +551,78 @@ impl From<PortForwardConfig> for PortForwardConfigPb {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct BandwidthLimitConfig {
+    // both unset means the whole local node
+    pub peer_id: Option<u32>,
+    pub cidr: Option<IpCidr>,
+    // bytes per second, unset means unlimited
+    pub upload_bps: Option<u64>,
+    pub download_bps: Option<u64>,
+}
+
+impl BandwidthLimitConfig {
+    pub fn same_target(&self, other: &BandwidthLimitConfig) -> bool {
+        self.peer_id == other.peer_id && self.cidr == other.cidr
+    }
+
+    pub fn target_str(&self) -> String {
+        match (self.peer_id, self.cidr) {
+            (Some(peer_id), _) => format!("peer {}", peer_id),
+            (None, Some(cidr)) => format!("cidr {}", cidr),
+            (None, None) => "node".to_string(),
+        }
+    }
+}
+
+impl TryFrom<BandwidthLimitPb> for BandwidthLimitConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(pb: BandwidthLimitPb) -> Result<Self, Self::Error> {
+        if pb.peer_id.is_some() && pb.cidr.is_some() {
+            return Err(anyhow::anyhow!(
+                "bandwidth limit can target either a peer or a cidr, not both"
+            ));
+        }
+        let cidr = pb
+            .cidr
+            .map(|c| {
+                c.parse::<IpCidr>()
+                    .with_context(|| format!("invalid bandwidth limit cidr: {}", c))
+            })
+            .transpose()?;
+        Ok(BandwidthLimitConfig {
+            peer_id: pb.peer_id,
+            cidr,
+            upload_bps: pb.upload_bps.filter(|bps| *bps > 0),
+            download_bps: pb.download_bps.filter(|bps| *bps > 0),
+        })
+    }
+}
+
+impl From<BandwidthLimitConfig> for BandwidthLimitPb {
+    fn from(val: BandwidthLimitConfig) -> Self {
+        BandwidthLimitPb {
+            peer_id: val.peer_id,
+            cidr: val.cidr.map(|c| c.to_string()),
+            upload_bps: val.upload_bps,
+            download_bps: val.download_bps,
+        }
+    }
+}
+
+// the costs are local, they are not synced to other peers. they pick the next hop of traffic
+// sent from this node, relays forward it with their own route table
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct RouteCostConfig {
+    // hostname or virtual ipv4 of the peer
+    pub peer: String,
+    // only the link between the two peers if set, otherwise every route relayed through peer
+    pub link_to: Option<String>,
+    // added to the latency of the link when routes are compared, in milliseconds
+    pub cost: u32,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 struct Config {
     netns: Option<String>,
@@ -393,6 +636,8 @@ struct Config {
     listeners: Option<Vec<url::Url>>,
     mapped_listeners: Option<Vec<url::Url>>,
     exit_nodes: Option<Vec<IpAddr>>,
+    // addresses an exit node connects to to check it reaches the internet
+    exit_node_check_targets: Option<Vec<SocketAddr>>,
 
     peer: Option<Vec<PeerConfig>>,
     proxy_network: Option<Vec<ProxyNetworkConfig>>,
@@ -402,12 +647,18 @@ struct Config {
 
     vpn_portal_config: Option<VpnPortalConfig>,
 
+    node_identity: Option<NodeIdentityConfig>,
+
     routes: Option<Vec<cidr::Ipv4Cidr>>,
 
     socks5_proxy: Option<url::Url>,
 
     port_forward: Option<Vec<PortForwardConfig>>,
 
+    bandwidth_limit: Option<Vec<BandwidthLimitConfig>>,
+
+    route_cost: Option<Vec<RouteCostConfig>>,
+
     flags: Option<serde_json::Map<String, serde_json::Value>>,
 
     #[serde(skip)]
@@ -709,6 +960,14 @@ impl ConfigLoader for TomlConfigLoader {
         self.config.lock().unwrap().vpn_portal_config = Some(config);
     }
 
+    fn get_node_identity_config(&self) -> Option<NodeIdentityConfig> {
+        self.config.lock().unwrap().node_identity.clone()
+    }
+
+    fn set_node_identity_config(&self, config: Option<NodeIdentityConfig>) {
+        self.config.lock().unwrap().node_identity = config;
+    }
+
     fn get_flags(&self) -> Flags {
         self.config
             .lock()
@@ -735,6 +994,19 @@ impl ConfigLoader for TomlConfigLoader {
         self.config.lock().unwrap().exit_nodes = Some(nodes);
     }
 
+    fn get_exit_node_check_targets(&self) -> Vec<SocketAddr> {
+        self.config
+            .lock()
+            .unwrap()
+            .exit_node_check_targets
+            .clone()
+            .unwrap_or_default()
+    }
+
+    fn set_exit_node_check_targets(&self, targets: Vec<SocketAddr>) {
+        self.config.lock().unwrap().exit_node_check_targets = Some(targets);
+    }
+
     fn get_routes(&self) -> Option<Vec<cidr::Ipv4Cidr>> {
         self.config.lock().unwrap().routes.clone()
     }
@@ -764,6 +1036,32 @@ impl ConfigLoader for TomlConfigLoader {
         self.config.lock().unwrap().port_forward = Some(forwards);
     }
 
+    fn get_bandwidth_limits(&self) -> Vec<BandwidthLimitConfig> {
+        self.config
+            .lock()
+            .unwrap()
+            .bandwidth_limit
+            .clone()
+            .unwrap_or_default()
+    }
+
+    fn set_bandwidth_limits(&self, limits: Vec<BandwidthLimitConfig>) {
+        self.config.lock().unwrap().bandwidth_limit = Some(limits);
+    }
+
+    fn get_route_costs(&self) -> Vec<RouteCostConfig> {
+        self.config
+            .lock()
+            .unwrap()
+            .route_cost
+            .clone()
+            .unwrap_or_default()
+    }
+
+    fn set_route_costs(&self, costs: Vec<RouteCostConfig>) {
+        self.config.lock().unwrap().route_cost = Some(costs);
+    }
+
     fn get_acl(&self) -> Option<Acl> {
         self.config.lock().unwrap().acl.clone()
     }
@@ -883,6 +1181,40 @@ stun_servers = [
         assert_eq!(stun_servers[2], "txt:stun.easytier.cn");
     }
 
+    #[test]
+    fn test_wireguard_client_config() {
+        let client: WireGuardClientConfig = "laptop:10.14.14.2".parse().unwrap();
+        assert_eq!(client.name, "laptop");
+        assert_eq!(client.address, Ipv4Addr::new(10, 14, 14, 2));
+        assert!(client.public_key.is_none());
+
+        let key = "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=";
+        let client: WireGuardClientConfig = format!("phone:10.14.14.3:{}", key).parse().unwrap();
+        assert_eq!(client.public_key.as_deref(), Some(key));
+
+        assert!(":10.14.14.2".parse::<WireGuardClientConfig>().is_err());
+        assert!("laptop".parse::<WireGuardClientConfig>().is_err());
+        assert!("laptop:10.14.14".parse::<WireGuardClientConfig>().is_err());
+
+        let config = TomlConfigLoader::new_from_str(
+            r#"
+[vpn_portal_config]
+client_cidr = "10.14.14.0/24"
+wireguard_listen = "0.0.0.0:11013"
+
+[[vpn_portal_config.wireguard_clients]]
+name = "laptop"
+address = "10.14.14.2"
+"#,
+        )
+        .unwrap();
+        let vpn_cfg = config.get_vpn_portal_config().unwrap();
+        assert_eq!(
+            vpn_cfg.wireguard_clients,
+            vec!["laptop:10.14.14.2".parse().unwrap()]
+        );
+    }
+
     #[tokio::test]
     async fn full_example_test() {
         let config_str = r#"
@@ -922,6 +1254,22 @@ level = "warn"
 bind_addr = "0.0.0.0:11011"
 dst_addr = "192.168.94.33:11011"
 proto = "tcp"
+
+[[bandwidth_limit]]
+upload_bps = 1048576
+
+[[bandwidth_limit]]
+cidr = "10.1.1.0/24"
+download_bps = 524288
+
+[[route_cost]]
+peer = "my-phone"
+cost = 1000
+
+[[route_cost]]
+peer = "10.144.144.20"
+link_to = "10.144.144.30"
+cost = 200
 "#;
         let ret = TomlConfigLoader::new_from_str(config_str);
         if let Err(e) = &ret {
@@ -950,6 +1298,40 @@ proto = "tcp"
             }],
             ret.get_port_forwards()
         );
+
+        assert_eq!(
+            vec![
+                BandwidthLimitConfig {
+                    peer_id: None,
+                    cidr: None,
+                    upload_bps: Some(1048576),
+                    download_bps: None,
+                },
+                BandwidthLimitConfig {
+                    peer_id: None,
+                    cidr: Some("10.1.1.0/24".parse().unwrap()),
+                    upload_bps: None,
+                    download_bps: Some(524288),
+                },
+            ],
+            ret.get_bandwidth_limits()
+        );
+
+        assert_eq!(
+            vec![
+                RouteCostConfig {
+                    peer: "my-phone".to_string(),
+                    link_to: None,
+                    cost: 1000,
+                },
+                RouteCostConfig {
+                    peer: "10.144.144.20".to_string(),
+                    link_to: Some("10.144.144.30".to_string()),
+                    cost: 200,
+                },
+            ],
+            ret.get_route_costs()
+        );
         println!("{}", ret.dump());
     }
 }
@@ -3,20 +3,26 @@ use std::net::IpAddr;
 use std::{
     hash::Hasher,
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
+#[cfg(feature = "node-identity")]
+use crate::common::config::NodeIdentityConfig;
 use crate::common::config::ProxyNetworkConfig;
 use crate::common::stats_manager::StatsManager;
 use crate::common::token_bucket::TokenBucketManager;
 use crate::peers::acl_filter::AclFilter;
+use crate::peers::bandwidth_limiter::BandwidthLimiter;
+#[cfg(feature = "node-identity")]
+use crate::peers::identity::NodeIdentity;
 use crate::proto::acl::GroupIdentity;
 use crate::proto::cli::PeerConnInfo;
-use crate::proto::common::{PeerFeatureFlag, PortForwardConfigPb};
+use crate::proto::common::{PeerFeatureFlag, PortForwardConfigPb, RouteCostPolicy};
 use crate::proto::peer_rpc::PeerGroupInfo;
 use crossbeam::atomic::AtomicCell;
 
 use super::{
-    config::{ConfigLoader, Flags},
+    config::{ConfigLoader, Flags, RouteCostConfig},
     netns::NetNS,
     network::IPCollector,
     stun::{StunInfoCollector, StunInfoCollectorTrait},
@@ -52,6 +58,11 @@ pub enum GlobalCtxEvent {
     DhcpIpv4Conflicted(Option<cidr::Ipv4Inet>),
 
     PortForwardAdded(PortForwardConfigPb),
+
+    NetworkSecretRotated(u64), // (rotation id)
+
+    ExitNodeChanged(Option<IpAddr>, Option<IpAddr>), // (old, new)
+    ExitNodeDown(IpAddr, String),                    // (exit node, error message)
 }
 
 pub type EventBus = tokio::sync::broadcast::Sender<GlobalCtxEvent>;
@@ -77,8 +88,12 @@ pub struct GlobalCtx {
     stun_info_collection: Mutex<Arc<dyn StunInfoCollectorTrait>>,
 
     running_listeners: Mutex<Vec<url::Url>>,
+    // external addrs of listeners, requested from the router by upnp / nat-pmp / pcp
+    port_mapped_listeners: Mutex<Vec<url::Url>>,
 
     enable_exit_node: bool,
+    // cached, checked for every packet sent. updated by set_flags
+    latency_first: AtomicCell<bool>,
     proxy_forward_by_system: bool,
     no_tun: bool,
 
@@ -91,6 +106,14 @@ pub struct GlobalCtx {
     stats_manager: Arc<StatsManager>,
 
     acl_filter: Arc<AclFilter>,
+
+    bandwidth_limiter: Arc<BandwidthLimiter>,
+
+    #[cfg(feature = "node-identity")]
+    cached_node_identity: Mutex<Option<(NodeIdentityConfig, Arc<NodeIdentity>)>>,
+
+    // another identity accepted from peers while the network secret is rotated
+    alternate_network_identity: Mutex<Option<(NetworkIdentity, Instant)>>,
 }
 
 impl std::fmt::Debug for GlobalCtx {
@@ -133,6 +156,8 @@ impl GlobalCtx {
         let stun_info_collector = Arc::new(stun_info_collector);
 
         let enable_exit_node = config_fs.get_flags().enable_exit_node || cfg!(target_env = "ohos");
+        let latency_first = config_fs.get_flags().latency_first;
+        Self::warn_route_costs_without_latency_first(&config_fs);
         let proxy_forward_by_system = config_fs.get_flags().proxy_forward_by_system;
         let no_tun = config_fs.get_flags().no_tun;
 
@@ -164,8 +189,10 @@ impl GlobalCtx {
             stun_info_collection: Mutex::new(stun_info_collector),
 
             running_listeners: Mutex::new(Vec::new()),
+            port_mapped_listeners: Mutex::new(Vec::new()),
 
             enable_exit_node,
+            latency_first: AtomicCell::new(latency_first),
             proxy_forward_by_system,
             no_tun,
 
@@ -177,6 +204,13 @@ impl GlobalCtx {
             stats_manager: Arc::new(StatsManager::new()),
 
             acl_filter: Arc::new(AclFilter::new()),
+
+            bandwidth_limiter: Arc::new(BandwidthLimiter::new()),
+
+            #[cfg(feature = "node-identity")]
+            cached_node_identity: Mutex::new(None),
+
+            alternate_network_identity: Mutex::new(None),
         }
     }
 
@@ -256,6 +290,47 @@ impl GlobalCtx {
         self.get_network_identity().network_name
     }
 
+    /// Also accept another network secret from peers until `valid_for` elapses,
+    /// the new secret before a rotation is committed or the previous one after.
+    pub fn set_alternate_network_identity(&self, identity: NetworkIdentity, valid_for: Duration) {
+        *self.alternate_network_identity.lock().unwrap() =
+            Some((identity, Instant::now() + valid_for));
+    }
+
+    pub fn get_alternate_network_identity(&self) -> Option<NetworkIdentity> {
+        let mut alternate = self.alternate_network_identity.lock().unwrap();
+        if matches!(alternate.as_ref(), Some((_, valid_until)) if Instant::now() >= *valid_until) {
+            alternate.take();
+        }
+        alternate.as_ref().map(|(identity, _)| identity.clone())
+    }
+
+    /// The local identity matching the one presented by a peer, either the current one or
+    /// the alternate one while a secret rotation is in progress.
+    pub fn match_network_identity(&self, identity: &NetworkIdentity) -> Option<NetworkIdentity> {
+        std::iter::once(self.get_network_identity())
+            .chain(self.get_alternate_network_identity())
+            .find(|local| local == identity)
+    }
+
+    /// The node identity used in peer handshakes, rebuilt whenever its config changes
+    /// so new allowed keys apply to the next handshakes.
+    #[cfg(feature = "node-identity")]
+    pub fn get_node_identity(&self) -> anyhow::Result<Option<Arc<NodeIdentity>>> {
+        let Some(cfg) = self.config.get_node_identity_config() else {
+            return Ok(None);
+        };
+        let mut cached = self.cached_node_identity.lock().unwrap();
+        if let Some((cached_cfg, identity)) = cached.as_ref() {
+            if *cached_cfg == cfg {
+                return Ok(Some(identity.clone()));
+            }
+        }
+        let identity = Arc::new(NodeIdentity::new(&cfg)?);
+        *cached = Some((cfg, identity.clone()));
+        Ok(Some(identity))
+    }
+
     pub fn get_ip_collector(&self) -> Arc<IPCollector> {
         self.ip_collector.lock().unwrap().as_ref().unwrap().clone()
     }
@@ -294,6 +369,14 @@ impl GlobalCtx {
         }
     }
 
+    pub fn get_port_mapped_listeners(&self) -> Vec<url::Url> {
+        self.port_mapped_listeners.lock().unwrap().clone()
+    }
+
+    pub fn set_port_mapped_listeners(&self, urls: Vec<url::Url>) {
+        *self.port_mapped_listeners.lock().unwrap() = urls;
+    }
+
     pub fn get_vpn_portal_cidr(&self) -> Option<cidr::Ipv4Cidr> {
         self.config.get_vpn_portal_config().map(|x| x.client_cidr)
     }
@@ -304,15 +387,48 @@ impl GlobalCtx {
 
     pub fn set_flags(&self, flags: Flags) {
         self.config.set_flags(flags);
+        self.latency_first
+            .store(self.config.get_flags().latency_first);
+        Self::warn_route_costs_without_latency_first(self.config.as_ref());
+    }
+
+    pub fn set_route_costs(&self, costs: Vec<RouteCostConfig>) {
+        self.config.set_route_costs(costs);
+        Self::warn_route_costs_without_latency_first(self.config.as_ref());
+    }
+
+    // route cost policies and manual route costs rank the routes of latency first routing, with
+    // least hop routing they only break ties between routes of the same hop count
+    fn warn_route_costs_without_latency_first(config: &dyn ConfigLoader) {
+        let flags = config.get_flags();
+        if flags.latency_first {
+            return;
+        }
+        if flags.route_cost_policy() != RouteCostPolicy::LeastLatency
+            || !config.get_route_costs().is_empty()
+        {
+            tracing::warn!(
+                route_cost_policy = ?flags.route_cost_policy(),
+                "latency first is off, routes use the least hops and route costs only break ties"
+            );
+        }
+    }
+
+    pub fn is_latency_first(&self) -> bool {
+        self.latency_first.load()
     }
 
     pub fn get_128_key(&self) -> [u8; 16] {
-        let mut key = [0u8; 16];
         let secret = self
             .config
             .get_network_identity()
             .network_secret
             .unwrap_or_default();
+        Self::derive_128_key(&secret)
+    }
+
+    pub fn derive_128_key(secret: &str) -> [u8; 16] {
+        let mut key = [0u8; 16];
         // fill key according to network secret
         let mut hasher = DefaultHasher::new();
         hasher.write(secret.as_bytes());
@@ -324,12 +440,16 @@ impl GlobalCtx {
     }
 
     pub fn get_256_key(&self) -> [u8; 32] {
-        let mut key = [0u8; 32];
         let secret = self
             .config
             .get_network_identity()
             .network_secret
             .unwrap_or_default();
+        Self::derive_256_key(&secret)
+    }
+
+    pub fn derive_256_key(secret: &str) -> [u8; 32] {
+        let mut key = [0u8; 32];
         // fill key according to network secret
         let mut hasher = DefaultHasher::new();
         hasher.write(secret.as_bytes());
@@ -387,6 +507,10 @@ impl GlobalCtx {
         &self.acl_filter
     }
 
+    pub fn get_bandwidth_limiter(&self) -> &Arc<BandwidthLimiter> {
+        &self.bandwidth_limiter
+    }
+
     pub fn get_acl_groups(&self, peer_id: PeerId) -> Vec<PeerGroupInfo> {
         use std::collections::HashSet;
         self.config
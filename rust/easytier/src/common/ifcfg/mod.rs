@@ -12,11 +12,24 @@ mod route;
 use std::net::{Ipv4Addr, Ipv6Addr};
 
 use async_trait::async_trait;
-use cidr::{Ipv4Inet, Ipv6Inet};
+use cidr::{IpInet, Ipv4Inet, Ipv6Inet};
 use tokio::process::Command;
 
 use super::error::Error;
 
+/// A network interface of the system, returned by `IfConfiguerTrait::list_interfaces`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceInfo {
+    pub index: u32,
+    pub name: String,
+    /// ipv4 interface metric on windows, lowest metric of the ipv4 routes over the interface
+    /// on linux. None if the interface has no such metric.
+    pub metric: Option<u32>,
+    pub mtu: u32,
+    pub addresses: Vec<IpInet>,
+    pub up: bool,
+}
+
 #[async_trait]
 pub trait IfConfiguerTrait: Send + Sync {
     async fn add_ipv4_route(
@@ -84,6 +97,14 @@ pub trait IfConfiguerTrait: Send + Sync {
     async fn set_mtu(&self, _name: &str, _mtu: u32) -> Result<(), Error> {
         Ok(())
     }
+    async fn list_interfaces(&self) -> Result<Vec<InterfaceInfo>, Error> {
+        Ok(vec![])
+    }
+    /// Sets the ipv4 interface metric on windows. linux has no interface metric, the metric of
+    /// the ipv4 routes over the interface is changed instead.
+    async fn set_interface_metric(&self, _name: &str, _metric: u32) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 fn cidr_to_subnet_mask(prefix_length: u8) -> Ipv4Addr {
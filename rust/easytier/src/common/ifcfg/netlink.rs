@@ -30,7 +30,7 @@ use nix::{
 };
 use pnet::ipnetwork::ip_mask_to_prefix;
 
-use super::{route::Route, Error, IfConfiguerTrait};
+use super::{route::Route, Error, IfConfiguerTrait, InterfaceInfo};
 
 pub(crate) fn dummy_socket() -> Result<std::net::UdpSocket, Error> {
     Ok(std::net::UdpSocket::bind("0:0")?)
@@ -595,6 +595,68 @@ impl IfConfiguerTrait for NetlinkIfConfiger {
 
         Ok(())
     }
+
+    async fn list_interfaces(&self) -> Result<Vec<InterfaceInfo>, Error> {
+        let routes = Self::list_routes()?
+            .into_iter()
+            .map(Route::from)
+            .filter(|r| r.table == RouteHeader::RT_TABLE_MAIN)
+            .collect::<Vec<_>>();
+
+        let mut names: Vec<String> = vec![];
+        for ifaddr in getifaddrs().with_context(|| "failed to call getifaddrs")? {
+            if !names.contains(&ifaddr.interface_name) {
+                names.push(ifaddr.interface_name);
+            }
+        }
+
+        let mut ret = vec![];
+        for name in names {
+            // the interface may be gone already
+            let Ok(index) = Self::get_interface_index(&name) else {
+                continue;
+            };
+            ret.push(InterfaceInfo {
+                index,
+                metric: routes
+                    .iter()
+                    .filter(|r| r.ifindex == Some(index))
+                    .filter_map(|r| r.metric)
+                    .min(),
+                mtu: Self::mtu(&name)?,
+                addresses: Self::list_addresses(&name)?,
+                up: Self::get_flags(&name)?.contains(InterfaceFlags::IFF_UP),
+                name,
+            });
+        }
+        ret.sort_by_key(|i| i.index);
+        Ok(ret)
+    }
+
+    async fn set_interface_metric(&self, name: &str, metric: u32) -> Result<(), Error> {
+        let ifidx = NetlinkIfConfiger::get_interface_index(name)?;
+
+        for msg in Self::list_routes()? {
+            let route: Route = msg.clone().into();
+            if route.ifindex != Some(ifidx)
+                || route.table != RouteHeader::RT_TABLE_MAIN
+                || route.metric == Some(metric)
+            {
+                continue;
+            }
+            // the metric is part of the route key, so add the route with the new metric
+            // before removing the old one, traffic is never left without a route.
+            let mut new_msg = msg.clone();
+            new_msg
+                .attributes
+                .retain(|attr| !matches!(attr, RouteAttribute::Priority(_)));
+            new_msg.attributes.push(RouteAttribute::Priority(metric));
+            send_netlink_req_and_wait_one_resp(RouteNetlinkMessage::NewRoute(new_msg), false)?;
+            send_netlink_req_and_wait_one_resp(RouteNetlinkMessage::DelRoute(msg), true)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -703,4 +765,47 @@ mod tests {
             .collect::<Vec<_>>();
         assert!(!routes.contains(&IpAddr::V4("10.5.5.0".parse().unwrap())));
     }
+
+    #[serial_test::serial]
+    #[tokio::test]
+    async fn interface_metric_test() {
+        let _prepare_env = PrepareEnv::new();
+        let ifcfg = NetlinkIfConfiger {};
+        ifcfg.set_link_status(DUMMY_IFACE_NAME, true).await.unwrap();
+        ifcfg
+            .add_ipv4_route(DUMMY_IFACE_NAME, "10.6.6.0".parse().unwrap(), 24, Some(100))
+            .await
+            .unwrap();
+
+        let find_dummy = |ifaces: Vec<InterfaceInfo>| {
+            ifaces
+                .into_iter()
+                .find(|i| i.name == DUMMY_IFACE_NAME)
+                .unwrap()
+        };
+        let dummy = find_dummy(ifcfg.list_interfaces().await.unwrap());
+        assert!(dummy.up);
+        assert_eq!(
+            dummy.index,
+            NetlinkIfConfiger::get_interface_index(DUMMY_IFACE_NAME).unwrap()
+        );
+        assert_eq!(dummy.metric, Some(100));
+
+        ifcfg
+            .set_interface_metric(DUMMY_IFACE_NAME, 5)
+            .await
+            .unwrap();
+        let dummy = find_dummy(ifcfg.list_interfaces().await.unwrap());
+        assert_eq!(dummy.metric, Some(5));
+
+        // the route is moved, not copied
+        let metrics = NetlinkIfConfiger::list_routes()
+            .unwrap()
+            .into_iter()
+            .map(Route::from)
+            .filter(|r| r.destination == IpAddr::V4("10.6.6.0".parse().unwrap()))
+            .map(|r| r.metric)
+            .collect::<Vec<_>>();
+        assert_eq!(metrics, vec![Some(5)]);
+    }
 }
@@ -60,6 +60,17 @@ impl InterfaceLuid {
         }
     }
 
+    /// set_ipv4_metric method sets the IPv4 interface metric and turns off the automatic metric.
+    /// Windows sends limited broadcasts (255.255.255.255) only over the interface with the lowest metric.
+    pub fn set_ipv4_metric(&self, metric: u32) -> Result<(), NETIO_STATUS> {
+        let mut row = self.get_ip_interface(AF_INET as ADDRESS_FAMILY)?;
+        row.UseAutomaticMetric = 0;
+        row.Metric = metric;
+        // https://stackoverflow.com/questions/54857292/setipinterfaceentry-returns-error-invalid-parameter
+        row.SitePrefixLength = 0;
+        self.set_ip_interface(&mut row)
+    }
+
     /// get_interface method retrieves information for the specified adapter on the local computer.
     /// https://docs.microsoft.com/en-us/windows/desktop/api/netioapi/nf-netioapi-getifentry2
     pub fn get_interface(&self) -> Result<MIB_IF_ROW2, NETIO_STATUS> {
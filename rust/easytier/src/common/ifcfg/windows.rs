@@ -1,13 +1,23 @@
-use crate::common::ifcfg::win::types::{RouteDataIpv4, RouteDataIpv6};
+use crate::common::ifcfg::win::types::{
+    convert_sockaddr_to_ipv4addr, u16_ptr_to_string, RouteDataIpv4, RouteDataIpv6,
+};
 
 use super::win::luid::InterfaceLuid;
 use async_trait::async_trait;
-use cidr::{Ipv4Inet, Ipv6Inet};
+use cidr::{IpInet, Ipv4Inet, Ipv6Inet};
 use std::{
     io,
-    net::{Ipv4Addr, Ipv6Addr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
     ptr::null_mut,
 };
+use winapi::shared::{
+    ifdef::IfOperStatusUp,
+    netioapi::{
+        FreeMibTable, GetIpInterfaceTable, GetUnicastIpAddressTable, PMIB_IPINTERFACE_TABLE,
+        PMIB_UNICASTIPADDRESS_TABLE,
+    },
+    ws2def::{AF_INET, AF_INET6, AF_UNSPEC},
+};
 use windows_sys::Win32::{
     Foundation::NO_ERROR,
     NetworkManagement::IpHelper::{GetIfEntry, SetIfEntry, MIB_IFROW},
@@ -20,7 +30,7 @@ use winreg::{
     RegKey,
 };
 
-use super::{Error, IfConfiguerTrait};
+use super::{Error, IfConfiguerTrait, InterfaceInfo};
 pub struct WindowsIfConfiger {}
 
 fn format_win_error(error: u32) -> String {
@@ -176,6 +186,80 @@ impl WindowsIfConfiger {
         }
         Ok(())
     }
+
+    fn list_unicast_addresses() -> Result<Vec<(u32, IpInet)>, Error> {
+        let mut table: PMIB_UNICASTIPADDRESS_TABLE = null_mut();
+        let ret = unsafe { GetUnicastIpAddressTable(AF_UNSPEC as _, &mut table) };
+        if ret != NO_ERROR {
+            return Err(anyhow::anyhow!(
+                "Failed to get unicast address table: {}",
+                format_win_error(ret)
+            )
+            .into());
+        }
+
+        let rows = unsafe {
+            std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize)
+        };
+        let mut addresses = vec![];
+        for row in rows {
+            let address: IpAddr = unsafe {
+                match *row.Address.si_family() as i32 {
+                    AF_INET => convert_sockaddr_to_ipv4addr(row.Address.Ipv4()).into(),
+                    AF_INET6 => Ipv6Addr::from(*row.Address.Ipv6().sin6_addr.u.Byte()).into(),
+                    _ => continue,
+                }
+            };
+            if let Ok(inet) = IpInet::new(address, row.OnLinkPrefixLength) {
+                addresses.push((row.InterfaceIndex, inet));
+            }
+        }
+        unsafe { FreeMibTable(table as _) };
+
+        Ok(addresses)
+    }
+
+    fn list_ip_interfaces() -> Result<Vec<InterfaceInfo>, Error> {
+        let addresses = Self::list_unicast_addresses()?;
+
+        let mut table: PMIB_IPINTERFACE_TABLE = null_mut();
+        let ret = unsafe { GetIpInterfaceTable(AF_INET as _, &mut table) };
+        if ret != NO_ERROR {
+            return Err(anyhow::anyhow!(
+                "Failed to get ip interface table: {}",
+                format_win_error(ret)
+            )
+            .into());
+        }
+
+        let rows = unsafe {
+            std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize)
+        };
+        let mut interfaces = vec![];
+        for row in rows {
+            let luid = InterfaceLuid::new(unsafe { row.InterfaceLuid.Value });
+            // the interface may be gone already
+            let Ok(if_row) = luid.get_interface() else {
+                continue;
+            };
+            interfaces.push(InterfaceInfo {
+                index: row.InterfaceIndex,
+                name: u16_ptr_to_string(if_row.Alias.as_ptr()),
+                metric: Some(row.Metric),
+                mtu: row.NlMtu,
+                addresses: addresses
+                    .iter()
+                    .filter(|(index, _)| *index == row.InterfaceIndex)
+                    .map(|(_, addr)| *addr)
+                    .collect(),
+                up: if_row.OperStatus == IfOperStatusUp,
+            });
+        }
+        unsafe { FreeMibTable(table as _) };
+
+        interfaces.sort_by_key(|i| i.index);
+        Ok(interfaces)
+    }
 }
 
 #[async_trait]
@@ -318,6 +402,23 @@ impl IfConfiguerTrait for WindowsIfConfiger {
         .map_err(|e| anyhow::anyhow!("Failed to delete route: {}", format_win_error(e)))?;
         Ok(())
     }
+
+    async fn list_interfaces(&self) -> Result<Vec<InterfaceInfo>, Error> {
+        Self::list_ip_interfaces()
+    }
+
+    async fn set_interface_metric(&self, name: &str, metric: u32) -> Result<(), Error> {
+        let Some(if_index) = Self::get_interface_index(name) else {
+            return Err(Error::NotFound);
+        };
+        let luid = InterfaceLuid::luid_from_index(if_index).map_err(|e| {
+            anyhow::anyhow!("Failed to get interface luid: {}", format_win_error(e))
+        })?;
+        luid.set_ipv4_metric(metric).map_err(|e| {
+            anyhow::anyhow!("Failed to set interface metric: {}", format_win_error(e))
+        })?;
+        Ok(())
+    }
 }
 
 pub struct RegistryManager;
@@ -30,6 +30,8 @@ pub enum MetricName {
     TrafficBytesRx,
     /// Traffic bytes forwarded
     TrafficBytesForwarded,
+    /// Traffic bytes of other peers relayed, counted against the relay quota
+    TrafficBytesRelayed,
     /// Traffic bytes sent to self
     TrafficBytesSelfTx,
     /// Traffic bytes received from self
@@ -57,6 +59,10 @@ pub enum MetricName {
     TrafficPacketsForeignForwardTx,
     /// Traffic packets forwarded for foreign network, forward
     TrafficPacketsForeignForwardForwarded,
+    /// Traffic packets sealed with the network key instead of a session key
+    TrafficPacketsNetworkKeyTx,
+    /// Traffic packets opened with the network key instead of a session key
+    TrafficPacketsNetworkKeyRx,
 
     /// Compression bytes before compression
     CompressionBytesRxBefore,
@@ -83,6 +89,7 @@ impl fmt::Display for MetricName {
             MetricName::TrafficBytesTx => write!(f, "traffic_bytes_tx"),
             MetricName::TrafficBytesRx => write!(f, "traffic_bytes_rx"),
             MetricName::TrafficBytesForwarded => write!(f, "traffic_bytes_forwarded"),
+            MetricName::TrafficBytesRelayed => write!(f, "traffic_bytes_relayed"),
             MetricName::TrafficBytesSelfTx => write!(f, "traffic_bytes_self_tx"),
             MetricName::TrafficBytesSelfRx => write!(f, "traffic_bytes_self_rx"),
             MetricName::TrafficBytesForeignForwardRx => {
@@ -109,6 +116,8 @@ impl fmt::Display for MetricName {
             MetricName::TrafficPacketsForeignForwardForwarded => {
                 write!(f, "traffic_packets_foreign_forward_forwarded")
             }
+            MetricName::TrafficPacketsNetworkKeyTx => write!(f, "traffic_packets_network_key_tx"),
+            MetricName::TrafficPacketsNetworkKeyRx => write!(f, "traffic_packets_network_key_rx"),
 
             MetricName::CompressionBytesRxBefore => write!(f, "compression_bytes_rx_before"),
             MetricName::CompressionBytesRxAfter => write!(f, "compression_bytes_rx_after"),
@@ -1,5 +1,5 @@
 use std::collections::BTreeSet;
-use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
@@ -9,7 +9,8 @@ use anyhow::Context;
 use chrono::Local;
 use crossbeam::atomic::AtomicCell;
 use rand::seq::IteratorRandom;
-use tokio::net::{lookup_host, UdpSocket};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{lookup_host, TcpSocket, UdpSocket};
 use tokio::sync::{broadcast, Mutex};
 use tokio::task::JoinSet;
 use tracing::{Instrument, Level};
@@ -19,6 +20,8 @@ use stun_codec::rfc5389::methods::BINDING;
 use stun_codec::{Message, MessageClass, MessageDecoder, MessageEncoder};
 
 use crate::common::error::Error;
+use crate::common::netns::NetNS;
+use crate::tunnel::common::bind_reuse_port_tcp_socket;
 
 use super::dns::resolve_txt_record;
 use super::stun_codec_ext::*;
@@ -323,6 +326,50 @@ impl StunClient {
     }
 }
 
+// send a binding request over a tcp connection made from the given socket and
+// return the mapped addr. stun over tcp needs no extra framing, the length in the
+// header tells where a message ends.
+pub async fn tcp_bind_request(
+    socket: TcpSocket,
+    stun_server: SocketAddr,
+) -> Result<SocketAddr, Error> {
+    let tid = rand::random::<u32>();
+    let bind_request = async {
+        let mut stream = socket.connect(stun_server).await?;
+
+        let message = Message::<Attribute>::new(MessageClass::Request, BINDING, u32_to_tid(tid));
+        let mut encoder = MessageEncoder::new();
+        let req = encoder
+            .encode_into_bytes(message)
+            .with_context(|| "encode stun message")?;
+        stream.write_all(&req).await?;
+
+        let mut buf = vec![0u8; 20];
+        stream.read_exact(&mut buf).await?;
+        let attr_len = u16::from_be_bytes([buf[2], buf[3]]) as usize;
+        buf.resize(20 + attr_len, 0);
+        stream.read_exact(&mut buf[20..]).await?;
+        Ok::<_, Error>(buf)
+    };
+    let buf = tokio::time::timeout(Duration::from_secs(3), bind_request).await??;
+
+    let mut decoder = MessageDecoder::<Attribute>::new();
+    let msg = decoder
+        .decode_from_bytes(&buf)
+        .with_context(|| format!("decode stun msg {:?}", buf))?
+        .map_err(|e| anyhow::anyhow!("broken stun msg: {:?}", e))?;
+    tracing::trace!(?stun_server, "recv tcp stun response, msg: {:#?}", msg);
+
+    if msg.class() != MessageClass::SuccessResponse
+        || msg.method() != BINDING
+        || tid_to_u32(&msg.transaction_id()) != tid
+    {
+        return Err(Error::Unknown);
+    }
+
+    StunClient::extrace_mapped_addr(&msg).ok_or(Error::NotFound)
+}
+
 struct StunClientBuilder {
     udp: Arc<UdpSocket>,
     task_set: JoinSet<()>,
@@ -632,6 +679,12 @@ impl UdpNatTypeDetector {
 pub trait StunInfoCollectorTrait: Send + Sync {
     fn get_stun_info(&self) -> StunInfo;
     async fn get_udp_port_mapping(&self, local_port: u16) -> Result<SocketAddr, Error>;
+    // the socket used to ask stun servers is created in net_ns
+    async fn get_tcp_port_mapping(
+        &self,
+        local_port: u16,
+        net_ns: &NetNS,
+    ) -> Result<SocketAddr, Error>;
 }
 
 pub struct StunInfoCollector {
@@ -715,6 +768,39 @@ impl StunInfoCollectorTrait for StunInfoCollector {
 
         Err(Error::NotFound)
     }
+
+    async fn get_tcp_port_mapping(
+        &self,
+        local_port: u16,
+        net_ns: &NetNS,
+    ) -> Result<SocketAddr, Error> {
+        self.start_stun_routine();
+
+        // many stun servers only serve udp, so try a few of them
+        let mut host_resolver =
+            HostResolverIter::new(self.stun_servers.read().unwrap().clone(), 2, false);
+        let mut tried = 0;
+        while let Some(server) = host_resolver.next().await {
+            let socket = {
+                let _g = net_ns.guard();
+                bind_reuse_port_tcp_socket(&SocketAddr::new(
+                    Ipv4Addr::UNSPECIFIED.into(),
+                    local_port,
+                ))?
+            };
+            match tcp_bind_request(socket, server).await {
+                Ok(mapped_addr) => return Ok(mapped_addr),
+                Err(e) => tracing::warn!(?server, ?e, "tcp stun bind request failed"),
+            }
+
+            tried += 1;
+            if tried >= 3 {
+                break;
+            }
+        }
+
+        Err(Error::NotFound)
+    }
 }
 
 impl StunInfoCollector {
@@ -905,6 +991,17 @@ impl StunInfoCollectorTrait for MockStunInfoCollector {
         }
         Ok(format!("127.0.0.1:{}", port).parse().unwrap())
     }
+
+    async fn get_tcp_port_mapping(
+        &self,
+        mut port: u16,
+        _net_ns: &NetNS,
+    ) -> Result<std::net::SocketAddr, Error> {
+        if port == 0 {
+            port = 40145;
+        }
+        Ok(format!("127.0.0.1:{}", port).parse().unwrap())
+    }
 }
 
 #[cfg(test)]
@@ -103,7 +103,11 @@ impl TokenBucket {
             return;
         }
 
-        // Add tokens without exceeding capacity
+        self.add_tokens(tokens_to_add);
+    }
+
+    /// Add tokens without exceeding capacity
+    fn add_tokens(&self, tokens_to_add: u64) {
         let mut current = self.available_tokens.load(Ordering::Relaxed);
         loop {
             let new = current
@@ -121,6 +125,11 @@ impl TokenBucket {
         }
     }
 
+    /// Return tokens taken by `try_consume` for a packet that was dropped after all
+    pub fn refund(&self, tokens: u64) {
+        self.add_tokens(tokens);
+    }
+
     /// Calculate microseconds since bucket creation
     fn elapsed_micros(&self) -> u64 {
         self.start_time.elapsed().as_micros() as u64
@@ -23,7 +23,10 @@ use crate::{
         },
         rpc_types::{self, controller::BaseController},
     },
-    tunnel::{IpVersion, TunnelConnector},
+    tunnel::{
+        proxy::{redact_proxy_credentials_str, strip_proxy_param, UpstreamProxy},
+        IpVersion, TunnelConnector,
+    },
 };
 
 use crate::{
@@ -98,6 +101,8 @@ impl ManualConnectorManager {
         ret
     }
 
+    /// Keeps the connector's remote_url, which hides upstream proxy credentials, so urls
+    /// that carry proxy credentials must be added with add_connector_by_url.
     pub fn add_connector<T>(&self, connector: T)
     where
         T: TunnelConnector + 'static,
@@ -112,17 +117,23 @@ impl ManualConnectorManager {
     }
 
     pub async fn remove_connector(&self, url: url::Url) -> Result<(), Error> {
-        tracing::info!("remove_connector: {}", url);
-        let url = url.into();
-        if !self
-            .list_connectors()
-            .await
+        tracing::info!(
+            "remove_connector: {}",
+            redact_proxy_credentials_str(url.as_str())
+        );
+        // list_connectors only shows redacted urls, so accept them as well
+        let url = url.to_string();
+        let Some(conn_url) = self
+            .data
+            .connectors
             .iter()
-            .any(|x| x.url.as_ref() == Some(&url))
-        {
+            .map(|x| x.key().clone())
+            .chain(self.data.reconnecting.iter().map(|x| x.key().clone()))
+            .find(|x| *x == url || redact_proxy_credentials_str(x) == url)
+        else {
             return Err(Error::NotFound);
-        }
-        self.data.removed_conn_urls.insert(url.to_string());
+        };
+        self.data.removed_conn_urls.insert(conn_url);
         Ok(())
     }
 
@@ -149,7 +160,7 @@ impl ManualConnectorManager {
             ret.insert(
                 0,
                 Connector {
-                    url: Some(conn_url.parse().unwrap()),
+                    url: Some(redact_proxy_credentials_str(&conn_url).parse().unwrap()),
                     status: status.into(),
                 },
             );
@@ -162,7 +173,7 @@ impl ManualConnectorManager {
             ret.insert(
                 0,
                 Connector {
-                    url: Some(conn_url.parse().unwrap()),
+                    url: Some(redact_proxy_credentials_str(&conn_url).parse().unwrap()),
                     status: ConnectorStatus::Connecting.into(),
                 },
             );
@@ -243,6 +254,12 @@ impl ManualConnectorManager {
                 }
 
                 ret = reconn_result_recv.recv() => {
+                    let ret = ret.map(|r| {
+                        r.map(|r| ReconnResult {
+                            dead_url: redact_proxy_credentials_str(&r.dead_url),
+                            ..r
+                        })
+                    });
                     tracing::warn!("reconn_tasks done, reconn result: {:?}", ret);
                 }
             }
@@ -278,15 +295,16 @@ impl ManualConnectorManager {
         let remove_later = DashSet::new();
         for it in data.removed_conn_urls.iter() {
             let url = it.key();
+            let redacted = redact_proxy_credentials_str(url);
             if data.connectors.remove(url).is_some() {
-                tracing::warn!("connector: {}, removed", url);
+                tracing::warn!("connector: {}, removed", redacted);
                 continue;
             } else if data.reconnecting.contains(url) {
-                tracing::warn!("connector: {}, reconnecting, remove later.", url);
+                tracing::warn!("connector: {}, reconnecting, remove later.", redacted);
                 remove_later.insert(url.clone());
                 continue;
             } else {
-                tracing::warn!("connector: {}, not found", url);
+                tracing::warn!("connector: {}, not found", redacted);
             }
         }
         data.removed_conn_urls.clear();
@@ -300,7 +318,11 @@ impl ManualConnectorManager {
         let all_urls: BTreeSet<String> = data.connectors.iter().map(|x| x.key().clone()).collect();
         let mut ret = BTreeSet::new();
         for url in all_urls.iter() {
-            if !data.alive_conn_urls.contains(url) {
+            // proxied tunnels report their url without the proxy param as remote addr
+            let tunnel_url = url::Url::parse(url)
+                .map(|u| strip_proxy_param(&u).to_string())
+                .unwrap_or_else(|_| url.clone());
+            if !data.alive_conn_urls.contains(url) && !data.alive_conn_urls.contains(&tunnel_url) {
                 ret.insert(url.clone());
             }
         }
@@ -325,7 +347,12 @@ impl ManualConnectorManager {
         };
 
         let (peer_id, conn_id) = pm.try_direct_connect(connector).await?;
-        tracing::info!("reconnect succ: {} {} {}", peer_id, conn_id, dead_url);
+        tracing::info!(
+            "reconnect succ: {} {} {}",
+            peer_id,
+            conn_id,
+            redact_proxy_credentials_str(&dead_url)
+        );
         Ok(ReconnResult {
             dead_url,
             peer_id,
@@ -337,19 +364,22 @@ impl ManualConnectorManager {
         data: Arc<ConnectorManagerData>,
         dead_url: String,
     ) -> Result<ReconnResult, Error> {
-        tracing::info!("reconnect: {}", dead_url);
+        let redacted_url = redact_proxy_credentials_str(&dead_url);
+        tracing::info!("reconnect: {}", redacted_url);
 
         let mut ip_versions = vec![];
         let u = url::Url::parse(&dead_url)
-            .with_context(|| format!("failed to parse connector url {:?}", dead_url))?;
-        if u.scheme() == "ring" || u.scheme() == "txt" || u.scheme() == "srv" {
+            .with_context(|| format!("failed to parse connector url {:?}", redacted_url))?;
+        // the upstream proxy resolves the destination, it may not even resolve locally
+        let proxied = UpstreamProxy::from_tunnel_url(&u)?.is_some();
+        if proxied || u.scheme() == "ring" || u.scheme() == "txt" || u.scheme() == "srv" {
             ip_versions.push(IpVersion::Both);
         } else {
             let addrs = match socket_addrs(&u, || Some(1000)).await {
                 Ok(addrs) => addrs,
                 Err(e) => {
                     data.global_ctx.issue_event(GlobalCtxEvent::ConnectError(
-                        dead_url.clone(),
+                        redacted_url.clone(),
                         format!("{:?}", IpVersion::Both),
                         format!("{:?}", e),
                     ));
@@ -359,7 +389,7 @@ impl ManualConnectorManager {
                     )));
                 }
             };
-            tracing::info!(?addrs, ?dead_url, "get ip from url done");
+            tracing::info!(?addrs, ?redacted_url, "get ip from url done");
             let mut has_ipv4 = false;
             let mut has_ipv6 = false;
             for addr in addrs {
@@ -381,16 +411,17 @@ impl ManualConnectorManager {
             "cannot get ip from url"
         )));
         for ip_version in ip_versions {
-            let use_long_timeout = dead_url.starts_with("http")
+            let use_long_timeout = proxied
+                || dead_url.starts_with("http")
                 || dead_url.starts_with("srv")
                 || dead_url.starts_with("txt");
             let ret = timeout(
-                // allow http connector to wait longer
+                // allow http and proxied connectors to wait longer
                 std::time::Duration::from_secs(if use_long_timeout { 20 } else { 2 }),
                 Self::conn_reconnect_with_ip_version(data.clone(), dead_url.clone(), ip_version),
             )
             .await;
-            tracing::info!("reconnect: {} done, ret: {:?}", dead_url, ret);
+            tracing::info!("reconnect: {} done, ret: {:?}", redacted_url, ret);
 
             match ret {
                 Ok(Ok(_)) => {
@@ -410,7 +441,7 @@ impl ManualConnectorManager {
 
             // 发送事件（只有在未 break 时才执行）
             data.global_ctx.issue_event(GlobalCtxEvent::ConnectError(
-                dead_url.clone(),
+                redacted_url.clone(),
                 format!("{:?}", ip_version),
                 format!("{:?}", reconn_ret),
             ));
@@ -448,13 +479,23 @@ impl ConnectorManageRpc for ConnectorManagerRpcService {
             self.0
                 .remove_connector(url.clone())
                 .await
-                .with_context(|| format!("remove connector failed: {:?}", url))?;
+                .with_context(|| {
+                    format!(
+                        "remove connector failed: {:?}",
+                        redact_proxy_credentials_str(url.as_str())
+                    )
+                })?;
             return Ok(ManageConnectorResponse::default());
         } else {
             self.0
                 .add_connector_by_url(url.as_str())
                 .await
-                .with_context(|| format!("add connector failed: {:?}", url))?;
+                .with_context(|| {
+                    format!(
+                        "add connector failed: {:?}",
+                        redact_proxy_credentials_str(url.as_str())
+                    )
+                })?;
         }
         Ok(ManageConnectorResponse::default())
     }
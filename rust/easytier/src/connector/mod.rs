@@ -12,13 +12,18 @@ use crate::tunnel::wireguard::{WgConfig, WgTunnelConnector};
 use crate::{
     common::{error::Error, global_ctx::ArcGlobalCtx, network::IPCollector},
     tunnel::{
-        check_scheme_and_get_socket_addr, ring::RingTunnelConnector, tcp::TcpTunnelConnector,
-        udp::UdpTunnelConnector, IpVersion, TunnelConnector,
+        check_scheme_and_get_socket_addr,
+        proxy::{redact_proxy_credentials, UpstreamProxy},
+        ring::RingTunnelConnector,
+        tcp::TcpTunnelConnector,
+        udp::UdpTunnelConnector,
+        IpVersion, TunnelConnector,
     },
 };
 
 pub mod direct;
 pub mod manual;
+pub mod tcp_hole_punch;
 pub mod udp_hole_punch;
 
 pub mod dns_connector;
@@ -58,7 +63,19 @@ pub async fn create_connector_by_url(
     ip_version: IpVersion,
 ) -> Result<Box<dyn TunnelConnector + 'static>, Error> {
     let url = url::Url::parse(url).map_err(|_| Error::InvalidUrl(url.to_owned()))?;
+    // the proxy resolves the destination itself, so neither local dns nor
+    // bind device applies to proxied connections
+    let proxied = UpstreamProxy::from_tunnel_url(&url)?.is_some();
+    if proxied && !matches!(url.scheme(), "tcp" | "ws" | "wss") {
+        return Err(Error::InvalidUrl(format!(
+            "upstream proxy is only supported for tcp, ws and wss: {}",
+            redact_proxy_credentials(&url)
+        )));
+    }
     let mut connector: Box<dyn TunnelConnector + 'static> = match url.scheme() {
+        "tcp" if proxied => Box::new(TcpTunnelConnector::new(url)),
+        #[cfg(feature = "websocket")]
+        "ws" | "wss" if proxied => Box::new(crate::tunnel::websocket::WSTunnelConnector::new(url)),
         "tcp" => {
             let dst_addr =
                 check_scheme_and_get_socket_addr::<SocketAddr>(&url, "tcp", ip_version).await?;
@@ -0,0 +1,407 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context, Error};
+use tokio::{
+    net::TcpStream,
+    task::{JoinHandle, JoinSet},
+};
+
+use crate::{
+    common::{global_ctx::ArcGlobalCtx, PeerId},
+    connector::udp_hole_punch::{handle_rpc_result, BackOff},
+    peers::{
+        peer_manager::PeerManager,
+        peer_task::{PeerTaskLauncher, PeerTaskManager},
+    },
+    proto::{
+        common::NatType,
+        peer_rpc::{
+            ExchangeTcpPunchAddrRequest, ExchangeTcpPunchAddrResponse, TcpHolePunchRpc,
+            TcpHolePunchRpcClientFactory, TcpHolePunchRpcServer,
+        },
+        rpc_types::{self, controller::BaseController},
+    },
+    tunnel::{
+        build_url_from_socket_addr, common::bind_reuse_port_tcp_socket,
+        tcp::get_tunnel_with_tcp_stream, Tunnel,
+    },
+};
+
+// how long both sides keep sending syns to each other
+const TCP_PUNCH_DURATION_MS: u64 = 10000;
+// a little longer than the first syn retransmission (1s on linux)
+const TCP_PUNCH_ATTEMPT_TIMEOUT_MS: u64 = 3000;
+const TCP_PUNCH_RETRY_INTERVAL_MS: u64 = 200;
+
+// bind an ephemeral port, then ask stun servers how the nat maps it.
+async fn alloc_tcp_punch_port(global_ctx: &ArcGlobalCtx) -> Result<(u16, SocketAddr), Error> {
+    let local_port = {
+        let _g = global_ctx.net_ns.guard();
+        let socket = bind_reuse_port_tcp_socket(&SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0))?;
+        socket.local_addr()?.port()
+    };
+
+    let mapped_addr = global_ctx
+        .get_stun_info_collector()
+        .get_tcp_port_mapping(local_port, &global_ctx.net_ns)
+        .await
+        .with_context(|| "failed to get tcp port mapping")?;
+
+    Ok((local_port, mapped_addr))
+}
+
+// keep connecting to remote_addr from local_port. the first syns are dropped by the
+// remote nat, but they open our own nat for the syns of the remote side, so once
+// both sides have sent one the connection is set up by a tcp simultaneous open.
+async fn tcp_simultaneous_open(
+    global_ctx: &ArcGlobalCtx,
+    local_port: u16,
+    remote_addr: SocketAddr,
+) -> Result<TcpStream, Error> {
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(TCP_PUNCH_DURATION_MS);
+    let bind_addr = SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), local_port);
+    let mut last_err = anyhow::anyhow!("tcp simultaneous open timeout");
+
+    while tokio::time::Instant::now() < deadline {
+        let socket = {
+            let _g = global_ctx.net_ns.guard();
+            bind_reuse_port_tcp_socket(&bind_addr)?
+        };
+
+        let attempt_timeout = deadline
+            .saturating_duration_since(tokio::time::Instant::now())
+            .min(Duration::from_millis(TCP_PUNCH_ATTEMPT_TIMEOUT_MS));
+        match tokio::time::timeout(attempt_timeout, socket.connect(remote_addr)).await {
+            Ok(Ok(stream)) => return Ok(stream),
+            Ok(Err(e)) => {
+                tracing::trace!(?e, ?remote_addr, local_port, "tcp punch connect failed");
+                last_err = e.into();
+                tokio::time::sleep(Duration::from_millis(TCP_PUNCH_RETRY_INTERVAL_MS)).await;
+            }
+            Err(_) => {
+                tracing::trace!(?remote_addr, local_port, "tcp punch connect timeout");
+            }
+        }
+    }
+
+    Err(last_err)
+}
+
+fn new_punch_tunnel(stream: TcpStream, remote_addr: SocketAddr) -> Result<Box<dyn Tunnel>, Error> {
+    let remote_url = build_url_from_socket_addr(&remote_addr.to_string(), "tcp");
+    Ok(get_tunnel_with_tcp_stream(stream, remote_url)?)
+}
+
+struct TcpHolePunchServer {
+    peer_mgr: Arc<PeerManager>,
+    tasks: std::sync::Mutex<JoinSet<()>>,
+}
+
+impl TcpHolePunchServer {
+    pub fn new(peer_mgr: Arc<PeerManager>) -> Arc<Self> {
+        Arc::new(Self {
+            peer_mgr,
+            tasks: std::sync::Mutex::new(JoinSet::new()),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl TcpHolePunchRpc for TcpHolePunchServer {
+    type Controller = BaseController;
+
+    async fn exchange_tcp_punch_addr(
+        &self,
+        _ctrl: Self::Controller,
+        input: ExchangeTcpPunchAddrRequest,
+    ) -> rpc_types::error::Result<ExchangeTcpPunchAddrResponse> {
+        let connector_addr: SocketAddr = input
+            .connector_mapped_addr
+            .ok_or(anyhow::anyhow!("connector_mapped_addr is required"))?
+            .into();
+
+        let global_ctx = self.peer_mgr.get_global_ctx();
+        let (local_port, mapped_addr) = alloc_tcp_punch_port(&global_ctx).await?;
+        tracing::info!(?connector_addr, ?mapped_addr, "tcp hole punch server start");
+
+        let peer_mgr = self.peer_mgr.clone();
+        let mut tasks = self.tasks.lock().unwrap();
+        while tasks.try_join_next().is_some() {}
+        tasks.spawn(async move {
+            let ret = tcp_simultaneous_open(&global_ctx, local_port, connector_addr)
+                .await
+                .and_then(|stream| new_punch_tunnel(stream, connector_addr));
+            let tunnel = match ret {
+                Ok(tunnel) => tunnel,
+                Err(e) => {
+                    tracing::info!(?e, ?connector_addr, "tcp hole punch server failed");
+                    return;
+                }
+            };
+
+            tracing::info!(?tunnel, "tcp hole punch server get tunnel success");
+            if let Err(e) = peer_mgr.add_tunnel_as_server(tunnel, false).await {
+                tracing::error!(?e, "failed to add tunnel as server in tcp hole punch");
+            }
+        });
+
+        Ok(ExchangeTcpPunchAddrResponse {
+            listener_mapped_addr: Some(mapped_addr.into()),
+        })
+    }
+}
+
+struct TcpHolePunchConnectorData {
+    peer_mgr: Arc<PeerManager>,
+    blacklist: Arc<timedmap::TimedMap<PeerId, ()>>,
+}
+
+impl TcpHolePunchConnectorData {
+    pub fn new(peer_mgr: Arc<PeerManager>) -> Arc<Self> {
+        Arc::new(Self {
+            peer_mgr,
+            blacklist: Arc::new(timedmap::TimedMap::new()),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn do_hole_punching(&self, dst_peer_id: PeerId) -> Result<Box<dyn Tunnel>, Error> {
+        let global_ctx = self.peer_mgr.get_global_ctx();
+        let (local_port, local_mapped_addr) = alloc_tcp_punch_port(&global_ctx).await?;
+
+        let rpc_stub = self
+            .peer_mgr
+            .get_peer_rpc_mgr()
+            .rpc_client()
+            .scoped_client::<TcpHolePunchRpcClientFactory<BaseController>>(
+                self.peer_mgr.my_peer_id(),
+                dst_peer_id,
+                global_ctx.get_network_name(),
+            );
+
+        let resp = rpc_stub
+            .exchange_tcp_punch_addr(
+                BaseController {
+                    timeout_ms: 8000,
+                    ..Default::default()
+                },
+                ExchangeTcpPunchAddrRequest {
+                    connector_mapped_addr: Some(local_mapped_addr.into()),
+                },
+            )
+            .await;
+        let resp = handle_rpc_result(resp, dst_peer_id, &self.blacklist)?;
+
+        let remote_mapped_addr: SocketAddr = resp
+            .listener_mapped_addr
+            .ok_or(anyhow::anyhow!(
+                "exchange_tcp_punch_addr response missing listener_mapped_addr"
+            ))?
+            .into();
+        tracing::debug!(
+            ?local_mapped_addr,
+            ?remote_mapped_addr,
+            "tcp hole punch got remote addr"
+        );
+
+        let stream = tcp_simultaneous_open(&global_ctx, local_port, remote_mapped_addr).await?;
+        new_punch_tunnel(stream, remote_mapped_addr)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn punch(self: Arc<Self>, dst_peer_id: PeerId) -> Result<(), Error> {
+        let mut backoff = BackOff::new(vec![1000, 2000, 4000, 8000, 16000, 32000, 64000]);
+
+        loop {
+            backoff.sleep_for_next_backoff().await;
+
+            if self.blacklist.contains(&dst_peer_id) {
+                return Ok(());
+            }
+
+            let tunnel = match self.do_hole_punching(dst_peer_id).await {
+                Ok(tunnel) => tunnel,
+                Err(e) => {
+                    tracing::info!(?e, "tcp hole punching failed");
+                    continue;
+                }
+            };
+
+            tracing::info!(?tunnel, "tcp hole punching get tunnel success");
+            match self.peer_mgr.add_client_tunnel(tunnel, false).await {
+                Ok(_) => return Ok(()),
+                Err(e) => tracing::warn!(?e, "add client tunnel failed"),
+            }
+        }
+    }
+}
+
+// udp punching is preferred, so tcp is only tried when one side did not get a
+// udp nat type, which usually means udp is blocked there.
+fn need_tcp_punch(my_nat_type: NatType, peer_nat_type: NatType) -> bool {
+    if matches!(peer_nat_type, NatType::OpenInternet | NatType::NoPat) {
+        // direct connector can reach the peer's listeners
+        return false;
+    }
+
+    [my_nat_type, peer_nat_type]
+        .iter()
+        .any(|x| matches!(x, NatType::Unknown | NatType::SymUdpFirewall))
+}
+
+#[derive(Clone)]
+struct TcpHolePunchPeerTaskLauncher {}
+
+#[async_trait::async_trait]
+impl PeerTaskLauncher for TcpHolePunchPeerTaskLauncher {
+    type Data = Arc<TcpHolePunchConnectorData>;
+    type CollectPeerItem = PeerId;
+    type TaskRet = ();
+
+    fn new_data(&self, peer_mgr: Arc<PeerManager>) -> Self::Data {
+        TcpHolePunchConnectorData::new(peer_mgr)
+    }
+
+    async fn collect_peers_need_task(&self, data: &Self::Data) -> Vec<Self::CollectPeerItem> {
+        let my_nat_type = data
+            .peer_mgr
+            .get_global_ctx()
+            .get_stun_info_collector()
+            .get_stun_info()
+            .udp_nat_type;
+        let my_nat_type = NatType::try_from(my_nat_type).unwrap_or(NatType::Unknown);
+        let my_peer_id = data.peer_mgr.my_peer_id();
+
+        data.blacklist.cleanup();
+
+        let mut peers_to_connect = Vec::new();
+        for route in data.peer_mgr.list_routes().await.iter() {
+            if route
+                .feature_flag
+                .map(|x| x.is_public_server)
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            // both sides connect at the same time, the one with smaller peer id
+            // drives it through the rpc.
+            let peer_id: PeerId = route.peer_id;
+            if peer_id <= my_peer_id {
+                continue;
+            }
+
+            if data.blacklist.contains(&peer_id) {
+                tracing::debug!(?peer_id, "peer is blacklisted, skipping");
+                continue;
+            }
+
+            let conns = data.peer_mgr.list_peer_conns(peer_id).await;
+            if conns.is_some() && !conns.unwrap().is_empty() {
+                continue;
+            }
+
+            let peer_nat_type = route
+                .stun_info
+                .as_ref()
+                .map(|x| x.udp_nat_type)
+                .unwrap_or(0);
+            let peer_nat_type = NatType::try_from(peer_nat_type).unwrap_or(NatType::Unknown);
+            if !need_tcp_punch(my_nat_type, peer_nat_type) {
+                continue;
+            }
+
+            tracing::info!(
+                ?peer_id,
+                ?peer_nat_type,
+                ?my_nat_type,
+                "found peer to do tcp hole punching"
+            );
+            peers_to_connect.push(peer_id);
+        }
+
+        peers_to_connect
+    }
+
+    async fn launch_task(
+        &self,
+        data: &Self::Data,
+        item: Self::CollectPeerItem,
+    ) -> JoinHandle<Result<Self::TaskRet, Error>> {
+        tokio::spawn(data.clone().punch(item))
+    }
+
+    fn loop_interval_ms(&self) -> u64 {
+        5000
+    }
+}
+
+pub struct TcpHolePunchConnector {
+    server: Arc<TcpHolePunchServer>,
+    client: PeerTaskManager<TcpHolePunchPeerTaskLauncher>,
+    peer_mgr: Arc<PeerManager>,
+}
+
+impl TcpHolePunchConnector {
+    pub fn new(peer_mgr: Arc<PeerManager>) -> Self {
+        Self {
+            server: TcpHolePunchServer::new(peer_mgr.clone()),
+            client: PeerTaskManager::new(TcpHolePunchPeerTaskLauncher {}, peer_mgr.clone()),
+            peer_mgr,
+        }
+    }
+
+    pub async fn run_as_client(&mut self) -> Result<(), Error> {
+        self.client.start();
+        Ok(())
+    }
+
+    pub async fn run_as_server(&mut self) -> Result<(), Error> {
+        self.peer_mgr
+            .get_peer_rpc_mgr()
+            .rpc_server()
+            .registry()
+            .register(
+                TcpHolePunchRpcServer::new(self.server.clone()),
+                &self.peer_mgr.get_global_ctx().get_network_name(),
+            );
+
+        Ok(())
+    }
+
+    pub async fn run(&mut self) -> Result<(), Error> {
+        let global_ctx = self.peer_mgr.get_global_ctx();
+
+        if global_ctx.get_flags().disable_p2p {
+            return Ok(());
+        }
+        if global_ctx.get_flags().disable_tcp_hole_punching {
+            return Ok(());
+        }
+
+        self.run_as_client().await?;
+        self.run_as_server().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::proto::common::NatType;
+
+    use super::need_tcp_punch;
+
+    #[test]
+    fn test_need_tcp_punch() {
+        assert!(need_tcp_punch(NatType::Unknown, NatType::PortRestricted));
+        assert!(need_tcp_punch(NatType::FullCone, NatType::SymUdpFirewall));
+        assert!(need_tcp_punch(NatType::Unknown, NatType::Unknown));
+        assert!(!need_tcp_punch(NatType::Unknown, NatType::OpenInternet));
+        assert!(!need_tcp_punch(NatType::PortRestricted, NatType::Symmetric));
+    }
+}
@@ -29,21 +29,25 @@ use easytier::{
     proto::{
         cli::{
             list_peer_route_pair, AclManageRpc, AclManageRpcClientFactory, AddPortForwardRequest,
-            ConnectorManageRpc, ConnectorManageRpcClientFactory, DumpRouteRequest,
-            GetAclStatsRequest, GetLoggerConfigRequest, GetPrometheusStatsRequest, GetStatsRequest,
-            GetVpnPortalInfoRequest, GetWhitelistRequest, ListConnectorRequest,
+            BandwidthLimitManageRpc, BandwidthLimitManageRpcClientFactory, ConnectorManageRpc,
+            ConnectorManageRpcClientFactory, DumpRouteRequest, GetAclStatsRequest,
+            GetLoggerConfigRequest, GetPrometheusStatsRequest, GetSecretRotationStatusRequest,
+            GetStatsRequest, GetVpnPortalInfoRequest, GetWhitelistRequest,
+            ListBandwidthLimitRequest, ListConnectorRequest, ListExitNodeRequest,
             ListForeignNetworkRequest, ListGlobalForeignNetworkRequest, ListMappedListenerRequest,
             ListPeerRequest, ListPeerResponse, ListPortForwardRequest, ListRouteRequest,
             ListRouteResponse, LogLevel, LoggerRpc, LoggerRpcClientFactory,
             ManageMappedListenerRequest, MappedListenerManageAction, MappedListenerManageRpc,
             MappedListenerManageRpcClientFactory, NodeInfo, PeerManageRpc,
             PeerManageRpcClientFactory, PortForwardManageRpc, PortForwardManageRpcClientFactory,
-            RemovePortForwardRequest, SetLoggerConfigRequest, SetWhitelistRequest,
-            ShowNodeInfoRequest, StatsRpc, StatsRpcClientFactory, TcpProxyEntryState,
-            TcpProxyEntryTransportType, TcpProxyRpc, TcpProxyRpcClientFactory, VpnPortalRpc,
-            VpnPortalRpcClientFactory,
+            RemoveBandwidthLimitRequest, RemovePortForwardRequest, RevokeVpnPortalClientRequest,
+            SecretRotationManageRpc, SecretRotationManageRpcClientFactory,
+            SetBandwidthLimitRequest, SetLoggerConfigRequest, SetWhitelistRequest,
+            ShowNodeInfoRequest, StartSecretRotationRequest, StatsRpc, StatsRpcClientFactory,
+            TcpProxyEntryState, TcpProxyEntryTransportType, TcpProxyRpc, TcpProxyRpcClientFactory,
+            VpnPortalRpc, VpnPortalRpcClientFactory,
         },
-        common::{NatType, SocketType},
+        common::{BandwidthLimitPb, NatType, SocketType},
         peer_rpc::{GetGlobalPeerMapRequest, PeerCenterRpc, PeerCenterRpcClientFactory},
         rpc_impl::standalone::StandAloneClient,
         rpc_types::controller::BaseController,
@@ -91,8 +95,8 @@ enum SubCommand {
     Route(RouteArgs),
     #[command(about = "show global peers info")]
     PeerCenter,
-    #[command(about = "show vpn portal (wireguard) info")]
-    VpnPortal,
+    #[command(about = "show vpn portal info and manage its clients")]
+    VpnPortal(VpnPortalArgs),
     #[command(about = "inspect self easytier-core status")]
     Node(NodeArgs),
     #[command(about = "manage easytier-core as a system service")]
@@ -105,10 +109,19 @@ enum SubCommand {
     PortForward(PortForwardArgs),
     #[command(about = "manage TCP/UDP whitelist")]
     Whitelist(WhitelistArgs),
+    #[command(about = "manage upload/download bandwidth limits")]
+    Bandwidth(BandwidthArgs),
+    #[command(about = "rotate the network secret of all members")]
+    Secret(SecretArgs),
+    #[command(about = "show health of the exit nodes and the one in use")]
+    ExitNode,
     #[command(about = "show statistics information")]
     Stats(StatsArgs),
     #[command(about = "manage logger configuration")]
     Logger(LoggerArgs),
+    #[cfg(feature = "node-identity")]
+    #[command(about = "generate node identity keys and certificates")]
+    Identity(IdentityArgs),
     #[command(about = t!("core_clap.generate_completions").to_string())]
     GenAutocomplete { shell: Shell },
 }
@@ -206,6 +219,59 @@ enum AclSubCommand {
     Stats,
 }
 
+#[derive(Args, Debug)]
+struct VpnPortalArgs {
+    #[command(subcommand)]
+    sub_command: Option<VpnPortalSubCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+enum VpnPortalSubCommand {
+    /// Show client configs and connected clients
+    Show,
+    /// Revoke a named client, its connections are closed
+    Revoke {
+        #[arg(help = "Client name")]
+        name: String,
+        #[arg(
+            long,
+            default_value = "wireguard",
+            help = "Portal the client belongs to"
+        )]
+        vpn_type: String,
+    },
+}
+
+#[cfg(feature = "node-identity")]
+#[derive(Args, Debug)]
+struct IdentityArgs {
+    #[command(subcommand)]
+    sub_command: IdentitySubCommand,
+}
+
+#[cfg(feature = "node-identity")]
+#[derive(Subcommand, Debug)]
+enum IdentitySubCommand {
+    /// Generate a new private key, for a node or the network ca
+    GenKey,
+    /// Print the public key of a private key
+    Pubkey {
+        #[arg(help = "Base64 private key")]
+        private_key: String,
+    },
+    /// Sign the public key of a node with the private key of the network ca
+    SignCert {
+        #[arg(long, help = "Base64 private key of the network ca")]
+        ca_key: String,
+        #[arg(long, help = "Base64 public key of the node")]
+        public_key: String,
+        #[arg(long, help = "Network name the certificate is valid in")]
+        network_name: String,
+        #[arg(long, help = "Days the certificate is valid, never expires if not set")]
+        valid_days: Option<u64>,
+    },
+}
+
 #[derive(Args, Debug)]
 struct PortForwardArgs {
     #[command(subcommand)]
@@ -262,6 +328,58 @@ enum WhitelistSubCommand {
     Show,
 }
 
+#[derive(Args, Debug)]
+struct BandwidthArgs {
+    #[command(subcommand)]
+    sub_command: Option<BandwidthSubCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+enum BandwidthSubCommand {
+    /// Set bandwidth limit of the whole node, a peer or a cidr
+    Set {
+        #[arg(long, help = "limit traffic with this peer id")]
+        peer_id: Option<u32>,
+        #[arg(long, help = "limit traffic with this cidr (e.g., 10.1.1.0/24)")]
+        cidr: Option<String>,
+        #[arg(long, help = "upload limit in bytes per second, 0 means unlimited")]
+        upload: Option<u64>,
+        #[arg(long, help = "download limit in bytes per second, 0 means unlimited")]
+        download: Option<u64>,
+    },
+    /// Remove bandwidth limit of the whole node, a peer or a cidr
+    Remove {
+        #[arg(long, help = "peer id of the limit to remove")]
+        peer_id: Option<u32>,
+        #[arg(long, help = "cidr of the limit to remove")]
+        cidr: Option<String>,
+    },
+    /// List bandwidth limits
+    List,
+}
+
+#[derive(Args, Debug)]
+struct SecretArgs {
+    #[command(subcommand)]
+    sub_command: Option<SecretSubCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+enum SecretSubCommand {
+    /// Switch every member of the network to a new secret
+    Rotate {
+        #[arg(help = "the new network secret")]
+        network_secret: String,
+        #[arg(
+            long,
+            help = "seconds the previous secret is still accepted (at least 30), 0 means the default (600)"
+        )]
+        grace_period: Option<u32>,
+    },
+    /// Show the progress of the last rotation
+    Status,
+}
+
 #[derive(Args, Debug)]
 struct StatsArgs {
     #[command(subcommand)]
@@ -452,6 +570,30 @@ impl CommandHandler<'_> {
             .with_context(|| "failed to get port forward manager client")?)
     }
 
+    async fn get_bandwidth_limit_manager_client(
+        &self,
+    ) -> Result<Box<dyn BandwidthLimitManageRpc<Controller = BaseController>>, Error> {
+        Ok(self
+            .client
+            .lock()
+            .await
+            .scoped_client::<BandwidthLimitManageRpcClientFactory<BaseController>>("".to_string())
+            .await
+            .with_context(|| "failed to get bandwidth limit manager client")?)
+    }
+
+    async fn get_secret_rotation_manager_client(
+        &self,
+    ) -> Result<Box<dyn SecretRotationManageRpc<Controller = BaseController>>, Error> {
+        Ok(self
+            .client
+            .lock()
+            .await
+            .scoped_client::<SecretRotationManageRpcClientFactory<BaseController>>("".to_string())
+            .await
+            .with_context(|| "failed to get secret rotation manager client")?)
+    }
+
     async fn get_stats_client(
         &self,
     ) -> Result<Box<dyn StatsRpc<Controller = BaseController>>, Error> {
@@ -529,6 +671,7 @@ impl CommandHandler<'_> {
             tunnel_proto: String,
             #[tabled(rename = "NAT")]
             nat_type: String,
+            relay: String,
             #[tabled(skip)]
             id: String,
             version: String,
@@ -561,6 +704,11 @@ impl CommandHandler<'_> {
                         .join(",")
                         .to_string(),
                     nat_type: p.get_udp_nat_type(),
+                    relay: if route.feature_flag.is_some_and(|f| f.no_relay_data) {
+                        "off".to_string()
+                    } else {
+                        "on".to_string()
+                    },
                     id: route.peer_id.to_string(),
                     version: if route.version.is_empty() {
                         "unknown".to_string()
@@ -590,6 +738,20 @@ impl CommandHandler<'_> {
                     } else {
                         "Unknown".to_string()
                     },
+                    relay: {
+                        let used = format_size(p.relay_bytes_today, humansize::DECIMAL);
+                        if p.relay_daily_quota > 0 {
+                            format!(
+                                "{}/{}",
+                                used,
+                                format_size(p.relay_daily_quota, humansize::DECIMAL)
+                            )
+                        } else if p.feature_flag.is_some_and(|f| f.no_relay_data) {
+                            "off".to_string()
+                        } else {
+                            used
+                        }
+                    },
                     id: p.peer_id.to_string(),
                     version: p.version,
                 }
@@ -1043,6 +1205,93 @@ impl CommandHandler<'_> {
         Ok(())
     }
 
+    async fn handle_vpn_portal_show(&self) -> Result<(), Error> {
+        let vpn_portal_client = self.get_vpn_portal_client().await?;
+        let resp = vpn_portal_client
+            .get_vpn_portal_info(
+                BaseController::default(),
+                GetVpnPortalInfoRequest::default(),
+            )
+            .await?;
+        // older nodes only report a single portal
+        let infos = if resp.vpn_portal_infos.is_empty() {
+            vec![resp.vpn_portal_info.unwrap_or_default()]
+        } else {
+            resp.vpn_portal_infos
+        };
+
+        if *self.output_format == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&infos)?);
+            return Ok(());
+        }
+
+        #[derive(tabled::Tabled, serde::Serialize)]
+        struct VpnPortalClientTableItem {
+            name: String,
+            address: String,
+            endpoint: String,
+            rx_bytes: String,
+            tx_bytes: String,
+            last_handshake: String,
+        }
+
+        for info in infos {
+            println!("portal_name: {}", info.vpn_type);
+            println!(
+                r#"
+############### client_config_start ###############
+{}
+############### client_config_end ###############
+"#,
+                info.client_config
+            );
+            println!("connected_clients:\n{:#?}", info.connected_clients);
+            if info.clients.is_empty() {
+                continue;
+            }
+
+            let items = info
+                .clients
+                .into_iter()
+                .map(|c| VpnPortalClientTableItem {
+                    name: c.name,
+                    address: c.address,
+                    endpoint: c.endpoint,
+                    rx_bytes: format_size(c.rx_bytes, humansize::DECIMAL),
+                    tx_bytes: format_size(c.tx_bytes, humansize::DECIMAL),
+                    last_handshake: chrono::DateTime::<chrono::Utc>::from_timestamp(
+                        c.last_handshake,
+                        0,
+                    )
+                    .filter(|_| c.last_handshake > 0)
+                    .map(|t| {
+                        t.with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M:%S")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| "never".to_string()),
+                })
+                .collect::<Vec<_>>();
+            print_output(&items, self.output_format)?;
+        }
+        Ok(())
+    }
+
+    async fn handle_vpn_portal_revoke(&self, name: &str, vpn_type: &str) -> Result<(), Error> {
+        let client = self.get_vpn_portal_client().await?;
+        client
+            .revoke_vpn_portal_client(
+                BaseController::default(),
+                RevokeVpnPortalClientRequest {
+                    vpn_type: vpn_type.to_string(),
+                    client_name: name.to_string(),
+                },
+            )
+            .await?;
+        println!("client {} of {} portal revoked", name, vpn_type);
+        Ok(())
+    }
+
     async fn handle_port_forward_list(&self) -> Result<(), Error> {
         let client = self.get_port_forward_manager_client().await?;
         let request = ListPortForwardRequest::default();
@@ -1060,24 +1309,40 @@ impl CommandHandler<'_> {
             protocol: String,
             bind_addr: String,
             dst_addr: String,
+            status: String,
+            connections: String,
         }
 
         let items: Vec<PortForwardTableItem> = response
             .cfgs
             .into_iter()
-            .map(|rule| PortForwardTableItem {
-                protocol: format!(
-                    "{:?}",
-                    SocketType::try_from(rule.socket_type).unwrap_or(SocketType::Tcp)
-                ),
-                bind_addr: rule
-                    .bind_addr
-                    .map(|addr| addr.to_string())
-                    .unwrap_or_default(),
-                dst_addr: rule
-                    .dst_addr
-                    .map(|addr| addr.to_string())
-                    .unwrap_or_default(),
+            .enumerate()
+            .map(|(idx, rule)| {
+                let status = response.statuses.get(idx);
+                PortForwardTableItem {
+                    protocol: format!(
+                        "{:?}",
+                        SocketType::try_from(rule.socket_type).unwrap_or(SocketType::Tcp)
+                    ),
+                    bind_addr: rule
+                        .bind_addr
+                        .map(|addr| addr.to_string())
+                        .unwrap_or_default(),
+                    dst_addr: rule
+                        .dst_addr
+                        .map(|addr| addr.to_string())
+                        .unwrap_or_default(),
+                    status: match status {
+                        Some(s) if s.bound => "bound".to_string(),
+                        Some(s) => {
+                            format!("failed: {}", s.error.as_deref().unwrap_or("not running"))
+                        }
+                        None => "unknown".to_string(),
+                    },
+                    connections: status
+                        .map(|s| format!("{}/{}", s.active_connections, s.total_connections))
+                        .unwrap_or_default(),
+                }
             })
             .collect();
 
@@ -1196,6 +1461,226 @@ impl CommandHandler<'_> {
         Ok(())
     }
 
+    async fn handle_bandwidth_set(
+        &self,
+        peer_id: Option<u32>,
+        cidr: Option<String>,
+        upload: Option<u64>,
+        download: Option<u64>,
+    ) -> Result<(), Error> {
+        let client = self.get_bandwidth_limit_manager_client().await?;
+        let request = SetBandwidthLimitRequest {
+            limit: Some(BandwidthLimitPb {
+                peer_id,
+                cidr,
+                upload_bps: upload,
+                download_bps: download,
+            }),
+        };
+        client
+            .set_bandwidth_limit(BaseController::default(), request)
+            .await?;
+        println!("Bandwidth limit updated");
+        Ok(())
+    }
+
+    async fn handle_bandwidth_remove(
+        &self,
+        peer_id: Option<u32>,
+        cidr: Option<String>,
+    ) -> Result<(), Error> {
+        let client = self.get_bandwidth_limit_manager_client().await?;
+        let request = RemoveBandwidthLimitRequest {
+            limit: Some(BandwidthLimitPb {
+                peer_id,
+                cidr,
+                ..Default::default()
+            }),
+        };
+        client
+            .remove_bandwidth_limit(BaseController::default(), request)
+            .await?;
+        println!("Bandwidth limit removed");
+        Ok(())
+    }
+
+    async fn handle_bandwidth_list(&self) -> Result<(), Error> {
+        let client = self.get_bandwidth_limit_manager_client().await?;
+        let response = client
+            .list_bandwidth_limit(
+                BaseController::default(),
+                ListBandwidthLimitRequest::default(),
+            )
+            .await?;
+
+        if self.verbose || *self.output_format == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+            return Ok(());
+        }
+
+        #[derive(tabled::Tabled, serde::Serialize)]
+        struct BandwidthLimitTableItem {
+            target: String,
+            upload: String,
+            download: String,
+        }
+
+        let fmt_bps = |bps: Option<u64>| match bps {
+            Some(bps) if bps > 0 => format!("{}/s", format_size(bps, humansize::DECIMAL)),
+            _ => "unlimited".to_string(),
+        };
+
+        let items: Vec<BandwidthLimitTableItem> = response
+            .limits
+            .into_iter()
+            .map(|limit| BandwidthLimitTableItem {
+                target: match (limit.peer_id, limit.cidr.as_ref()) {
+                    (Some(peer_id), _) => format!("peer {}", peer_id),
+                    (None, Some(cidr)) => format!("cidr {}", cidr),
+                    (None, None) => "node".to_string(),
+                },
+                upload: fmt_bps(limit.upload_bps),
+                download: fmt_bps(limit.download_bps),
+            })
+            .collect();
+
+        print_output(&items, self.output_format)?;
+        Ok(())
+    }
+
+    async fn handle_secret_rotate(
+        &self,
+        network_secret: String,
+        grace_period: Option<u32>,
+    ) -> Result<(), Error> {
+        let client = self.get_secret_rotation_manager_client().await?;
+        let request = StartSecretRotationRequest {
+            network_secret,
+            grace_period_sec: grace_period.unwrap_or_default(),
+        };
+        let response = client
+            .start_secret_rotation(BaseController::default(), request)
+            .await?;
+        println!(
+            "Secret rotation {} started, check progress with `secret status`",
+            response.rotation_id
+        );
+        Ok(())
+    }
+
+    async fn handle_secret_status(&self) -> Result<(), Error> {
+        let client = self.get_secret_rotation_manager_client().await?;
+        let response = client
+            .get_secret_rotation_status(
+                BaseController::default(),
+                GetSecretRotationStatusRequest::default(),
+            )
+            .await?;
+
+        if self.verbose || *self.output_format == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+            return Ok(());
+        }
+
+        if response.rotation_id == 0 {
+            println!("No secret rotation");
+            return Ok(());
+        }
+        println!(
+            "Rotation {} ({}), {}, previous secret accepted for {}s",
+            response.rotation_id,
+            if response.initiated_locally {
+                "started here"
+            } else {
+                "received"
+            },
+            if response.committed {
+                "committed"
+            } else {
+                "prepared"
+            },
+            response.grace_period_remaining_sec
+        );
+
+        #[derive(tabled::Tabled, serde::Serialize)]
+        struct SecretRotationTableItem {
+            peer_id: u32,
+            hostname: String,
+            prepared: bool,
+            committed: bool,
+            error: String,
+        }
+
+        let items: Vec<SecretRotationTableItem> = response
+            .peers
+            .into_iter()
+            .map(|p| SecretRotationTableItem {
+                peer_id: p.peer_id,
+                hostname: p.hostname,
+                prepared: p.prepared,
+                committed: p.committed,
+                error: p.error.unwrap_or_default(),
+            })
+            .collect();
+
+        print_output(&items, self.output_format)?;
+        Ok(())
+    }
+
+    async fn handle_exit_node_list(&self) -> Result<(), Error> {
+        let client = self.get_peer_manager_client().await?;
+        let response = client
+            .list_exit_node(BaseController::default(), ListExitNodeRequest::default())
+            .await?;
+
+        if self.verbose || *self.output_format == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&response.exit_nodes)?);
+            return Ok(());
+        }
+
+        if response.exit_nodes.is_empty() {
+            println!("No exit node configured");
+            return Ok(());
+        }
+
+        #[derive(tabled::Tabled, serde::Serialize)]
+        struct ExitNodeTableItem {
+            addr: String,
+            peer_id: String,
+            active: bool,
+            healthy: bool,
+            latency_ms: String,
+            internet: String,
+            error: String,
+        }
+
+        let items: Vec<ExitNodeTableItem> = response
+            .exit_nodes
+            .into_iter()
+            .map(|n| ExitNodeTableItem {
+                addr: n.addr,
+                peer_id: n.peer_id.map(|p| p.to_string()).unwrap_or("-".to_string()),
+                active: n.active,
+                healthy: n.healthy,
+                latency_ms: n
+                    .latency_ms
+                    .map(|l| l.to_string())
+                    .unwrap_or("-".to_string()),
+                internet: match (n.ipv4_reachable, n.ipv6_reachable) {
+                    (true, true) => "ipv4,ipv6",
+                    (true, false) => "ipv4",
+                    (false, true) => "ipv6",
+                    (false, false) => "-",
+                }
+                .to_string(),
+                error: n.error.unwrap_or_default(),
+            })
+            .collect();
+
+        print_output(&items, self.output_format)?;
+        Ok(())
+    }
+
     async fn handle_logger_get(&self) -> Result<(), Error> {
         let client = self.get_logger_client().await?;
         let request = GetLoggerConfigRequest {};
@@ -1780,27 +2265,14 @@ async fn main() -> Result<(), Error> {
 
             print_output(&table_rows, &cli.output_format)?;
         }
-        SubCommand::VpnPortal => {
-            let vpn_portal_client = handler.get_vpn_portal_client().await?;
-            let resp = vpn_portal_client
-                .get_vpn_portal_info(
-                    BaseController::default(),
-                    GetVpnPortalInfoRequest::default(),
-                )
-                .await?
-                .vpn_portal_info
-                .unwrap_or_default();
-            println!("portal_name: {}", resp.vpn_type);
-            println!(
-                r#"
-############### client_config_start ###############
-{}
-############### client_config_end ###############
-"#,
-                resp.client_config
-            );
-            println!("connected_clients:\n{:#?}", resp.connected_clients);
-        }
+        SubCommand::VpnPortal(vpn_portal_args) => match &vpn_portal_args.sub_command {
+            Some(VpnPortalSubCommand::Revoke { name, vpn_type }) => {
+                handler.handle_vpn_portal_revoke(name, vpn_type).await?;
+            }
+            Some(VpnPortalSubCommand::Show) | None => {
+                handler.handle_vpn_portal_show().await?;
+            }
+        },
         SubCommand::Node(sub_cmd) => {
             let client = handler.get_peer_manager_client().await?;
             let node_info = client
@@ -2026,6 +2498,42 @@ async fn main() -> Result<(), Error> {
                 handler.handle_whitelist_show().await?;
             }
         },
+        SubCommand::Bandwidth(bandwidth_args) => match &bandwidth_args.sub_command {
+            Some(BandwidthSubCommand::Set {
+                peer_id,
+                cidr,
+                upload,
+                download,
+            }) => {
+                handler
+                    .handle_bandwidth_set(*peer_id, cidr.clone(), *upload, *download)
+                    .await?;
+            }
+            Some(BandwidthSubCommand::Remove { peer_id, cidr }) => {
+                handler
+                    .handle_bandwidth_remove(*peer_id, cidr.clone())
+                    .await?;
+            }
+            Some(BandwidthSubCommand::List) | None => {
+                handler.handle_bandwidth_list().await?;
+            }
+        },
+        SubCommand::ExitNode => {
+            handler.handle_exit_node_list().await?;
+        }
+        SubCommand::Secret(secret_args) => match &secret_args.sub_command {
+            Some(SecretSubCommand::Rotate {
+                network_secret,
+                grace_period,
+            }) => {
+                handler
+                    .handle_secret_rotate(network_secret.clone(), *grace_period)
+                    .await?;
+            }
+            Some(SecretSubCommand::Status) | None => {
+                handler.handle_secret_status().await?;
+            }
+        },
         SubCommand::Stats(stats_args) => match &stats_args.sub_command {
             Some(StatsSubCommand::Show) | None => {
                 let client = handler.get_stats_client().await?;
@@ -2097,6 +2605,34 @@ async fn main() -> Result<(), Error> {
                 handler.handle_logger_set(level).await?;
             }
         },
+        #[cfg(feature = "node-identity")]
+        SubCommand::Identity(identity_args) => match identity_args.sub_command {
+            IdentitySubCommand::GenKey => {
+                let private_key = peers::identity::generate_private_key();
+                println!("private key: {}", private_key);
+                println!(
+                    "public key: {}",
+                    peers::identity::public_key_of(&private_key)?
+                );
+            }
+            IdentitySubCommand::Pubkey { private_key } => {
+                println!("{}", peers::identity::public_key_of(&private_key)?);
+            }
+            IdentitySubCommand::SignCert {
+                ca_key,
+                public_key,
+                network_name,
+                valid_days,
+            } => {
+                let cert = peers::identity::sign_certificate(
+                    &ca_key,
+                    &public_key,
+                    &network_name,
+                    valid_days.map(|days| Duration::from_secs(days * 24 * 3600)),
+                )?;
+                println!("{}", cert);
+            }
+        },
         SubCommand::GenAutocomplete { shell } => {
             let mut cmd = Cli::command();
             easytier::print_completions(shell, &mut cmd, "easytier-cli");
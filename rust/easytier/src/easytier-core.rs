@@ -18,8 +18,9 @@ use easytier::{
     common::{
         config::{
             get_avaliable_encrypt_methods, ConfigLoader, ConsoleLoggerConfig, FileLoggerConfig,
-            LoggingConfigLoader, NetworkIdentity, PeerConfig, PortForwardConfig, TomlConfigLoader,
-            VpnPortalConfig,
+            LoggingConfigLoader, NetworkIdentity, NodeIdentityConfig, OpenVpnAuthMode,
+            OpenVpnPortalConfig, PeerConfig, PortForwardConfig, ShadowsocksMethod,
+            ShadowsocksPortalConfig, TomlConfigLoader, VpnPortalConfig, WireGuardClientConfig,
         },
         constants::EASYTIER_VERSION,
         global_ctx::GlobalCtx,
@@ -29,7 +30,7 @@ use easytier::{
     connector::create_connector_by_url,
     instance_manager::NetworkInstanceManager,
     launcher::{add_proxy_network_to_config, ConfigSource},
-    proto::common::{CompressionAlgoPb, NatType},
+    proto::common::{CompressionAlgoPb, MultipathMode, NatType, RouteCostPolicy},
     tunnel::{IpVersion, PROTO_PORT_OFFSET},
     utils::{init_logger, setup_panic_handler},
     web_client,
@@ -270,6 +271,75 @@ struct NetworkOptions {
     )]
     vpn_portal: Option<String>,
 
+    #[arg(
+        long,
+        env = "ET_OPENVPN_PORTAL",
+        help = t!("core_clap.openvpn_portal").to_string()
+    )]
+    openvpn_portal: Option<url::Url>,
+
+    #[arg(
+        long,
+        env = "ET_OPENVPN_PORTAL_AUTH",
+        help = t!("core_clap.openvpn_portal_auth").to_string(),
+        default_value = "tls"
+    )]
+    openvpn_portal_auth: OpenVpnAuthMode,
+
+    #[arg(
+        long,
+        env = "ET_SHADOWSOCKS_PORTAL",
+        help = t!("core_clap.shadowsocks_portal").to_string()
+    )]
+    shadowsocks_portal: Option<SocketAddr>,
+
+    #[arg(
+        long,
+        env = "ET_SHADOWSOCKS_PORTAL_METHOD",
+        help = t!("core_clap.shadowsocks_portal_method").to_string(),
+        default_value = "chacha20-ietf-poly1305"
+    )]
+    shadowsocks_portal_method: ShadowsocksMethod,
+
+    #[arg(
+        long,
+        env = "ET_WIREGUARD_PORTAL_CLIENTS",
+        value_delimiter = ',',
+        help = t!("core_clap.wireguard_portal_clients").to_string(),
+        num_args = 0..
+    )]
+    wireguard_portal_clients: Vec<WireGuardClientConfig>,
+
+    #[arg(
+        long,
+        env = "ET_NODE_PRIVATE_KEY",
+        help = t!("core_clap.node_private_key").to_string()
+    )]
+    node_private_key: Option<String>,
+
+    #[arg(
+        long,
+        env = "ET_NODE_ALLOWED_KEYS",
+        value_delimiter = ',',
+        help = t!("core_clap.node_allowed_keys").to_string(),
+        num_args = 0..
+    )]
+    node_allowed_keys: Vec<String>,
+
+    #[arg(
+        long,
+        env = "ET_NODE_CA_KEY",
+        help = t!("core_clap.node_ca_key").to_string()
+    )]
+    node_ca_key: Option<String>,
+
+    #[arg(
+        long,
+        env = "ET_NODE_CERTIFICATE",
+        help = t!("core_clap.node_certificate").to_string()
+    )]
+    node_certificate: Option<String>,
+
     #[arg(
         long,
         env = "ET_DEFAULT_PROTOCOL",
@@ -361,6 +431,15 @@ struct NetworkOptions {
     )]
     enable_exit_node: Option<bool>,
 
+    #[arg(
+        long,
+        env = "ET_EXIT_NODE_CHECK_TARGETS",
+        value_delimiter = ',',
+        help = t!("core_clap.exit_node_check_targets").to_string(),
+        num_args = 0..
+    )]
+    exit_node_check_targets: Vec<SocketAddr>,
+
     #[arg(
         long,
         env = "ET_PROXY_FORWARD_BY_SYSTEM",
@@ -427,6 +506,24 @@ struct NetworkOptions {
     )]
     disable_udp_hole_punching: Option<bool>,
 
+    #[arg(
+        long,
+        env = "ET_DISABLE_TCP_HOLE_PUNCHING",
+        help = t!("core_clap.disable_tcp_hole_punching").to_string(),
+        num_args = 0..=1,
+        default_missing_value = "true"
+    )]
+    disable_tcp_hole_punching: Option<bool>,
+
+    #[arg(
+        long,
+        env = "ET_ENABLE_PORT_MAPPING",
+        help = t!("core_clap.enable_port_mapping").to_string(),
+        num_args = 0..=1,
+        default_missing_value = "true"
+    )]
+    enable_port_mapping: Option<bool>,
+
     #[arg(
         long,
         env = "ET_DISABLE_SYM_HOLE_PUNCHING",
@@ -460,6 +557,27 @@ struct NetworkOptions {
     )]
     compression: Option<String>,
 
+    #[arg(
+        long,
+        env = "ET_COMPRESSION_LEVEL",
+        help = t!("core_clap.compression_level").to_string(),
+    )]
+    compression_level: Option<i32>,
+
+    #[arg(
+        long,
+        env = "ET_MULTIPATH",
+        help = t!("core_clap.multipath").to_string(),
+    )]
+    multipath: Option<String>,
+
+    #[arg(
+        long,
+        env = "ET_ROUTE_COST_POLICY",
+        help = t!("core_clap.route_cost_policy").to_string(),
+    )]
+    route_cost_policy: Option<String>,
+
     #[arg(
         long,
         env = "ET_BIND_DEVICE",
@@ -558,6 +676,54 @@ struct NetworkOptions {
     )]
     disable_relay_kcp: Option<bool>,
 
+    #[arg(
+        long,
+        env = "ET_DISABLE_RELAY_DATA",
+        help = t!("core_clap.disable_relay_data").to_string(),
+        num_args = 0..=1,
+        default_missing_value = "true"
+    )]
+    disable_relay_data: Option<bool>,
+
+    #[arg(
+        long,
+        env = "ET_RELAY_DAILY_QUOTA_MB",
+        help = t!("core_clap.relay_daily_quota_mb").to_string(),
+    )]
+    relay_daily_quota_mb: Option<u64>,
+
+    #[arg(
+        long,
+        env = "ET_BROADCAST_RELAY_PORTS",
+        help = t!("core_clap.broadcast_relay_ports").to_string(),
+    )]
+    broadcast_relay_ports: Option<String>,
+
+    #[arg(
+        long,
+        env = "ET_MULTICAST_IGMP_SNOOPING",
+        help = t!("core_clap.multicast_igmp_snooping").to_string(),
+        num_args = 0..=1,
+        default_missing_value = "true"
+    )]
+    multicast_igmp_snooping: Option<bool>,
+
+    #[arg(
+        long,
+        env = "ET_DHCP_IPV6",
+        help = t!("core_clap.dhcp_ipv6").to_string(),
+        num_args = 0..=1,
+        default_missing_value = "true"
+    )]
+    dhcp_ipv6: Option<bool>,
+
+    #[arg(
+        long,
+        env = "ET_IPV6_ULA_PREFIX",
+        help = t!("core_clap.ipv6_ula_prefix").to_string(),
+    )]
+    ipv6_ula_prefix: Option<String>,
+
     #[arg(
         long,
         env = "ET_ENABLE_RELAY_FOREIGN_NETWORK_KCP",
@@ -792,7 +958,9 @@ impl NetworkOptions {
         } else if let Some(r) = cfg.get_rpc_portal() {
             r
         } else {
-            Cli::parse_rpc_portal("0".into())?
+            // easytier-cli connects to 15888 by default, prefer it when it is free
+            let port = easytier::utils::find_free_tcp_port(15888..15900).unwrap_or(0);
+            Cli::parse_rpc_portal(port.to_string())?
         };
         cfg.set_rpc_portal(rpc_portal);
 
@@ -832,10 +1000,49 @@ impl NetworkOptions {
                 format!("failed to parse vpn portal client cidr: {}", url.path())
             })?;
             let wireguard_listen: SocketAddr = format!("{}:{}", host, port).parse().unwrap();
+            let openvpn = self
+                .openvpn_portal
+                .as_ref()
+                .map(|listen| OpenVpnPortalConfig {
+                    listen: listen.clone(),
+                    auth: self.openvpn_portal_auth,
+                });
+            let shadowsocks = self
+                .shadowsocks_portal
+                .map(|listen| ShadowsocksPortalConfig {
+                    listen,
+                    method: self.shadowsocks_portal_method,
+                    password: None,
+                });
             cfg.set_vpn_portal_config(VpnPortalConfig {
-                wireguard_listen,
+                wireguard_listen: Some(wireguard_listen),
                 client_cidr,
+                openvpn,
+                shadowsocks,
+                wireguard_clients: self.wireguard_portal_clients.clone(),
             });
+        } else if self.openvpn_portal.is_some() {
+            anyhow::bail!("--openvpn-portal requires --vpn-portal to define the client cidr");
+        } else if self.shadowsocks_portal.is_some() {
+            anyhow::bail!("--shadowsocks-portal requires --vpn-portal to define the client cidr");
+        } else if !self.wireguard_portal_clients.is_empty() {
+            anyhow::bail!(
+                "--wireguard-portal-clients requires --vpn-portal to define the client cidr"
+            );
+        }
+
+        if let Some(private_key) = self.node_private_key.as_ref() {
+            cfg.set_node_identity_config(Some(NodeIdentityConfig {
+                private_key: private_key.clone(),
+                allowed_public_keys: self.node_allowed_keys.clone(),
+                ca_public_key: self.node_ca_key.clone(),
+                certificate: self.node_certificate.clone(),
+            }));
+        } else if !self.node_allowed_keys.is_empty()
+            || self.node_ca_key.is_some()
+            || self.node_certificate.is_some()
+        {
+            anyhow::bail!("node identity options require --node-private-key");
         }
 
         if let Some(manual_routes) = self.manual_routes.as_ref() {
@@ -925,19 +1132,49 @@ impl NetworkOptions {
         f.disable_udp_hole_punching = self
             .disable_udp_hole_punching
             .unwrap_or(f.disable_udp_hole_punching);
+        f.disable_tcp_hole_punching = self
+            .disable_tcp_hole_punching
+            .unwrap_or(f.disable_tcp_hole_punching);
+        f.enable_port_mapping = self.enable_port_mapping.unwrap_or(f.enable_port_mapping);
         f.relay_all_peer_rpc = self.relay_all_peer_rpc.unwrap_or(f.relay_all_peer_rpc);
         f.multi_thread = self.multi_thread.unwrap_or(f.multi_thread);
         if let Some(compression) = &self.compression {
             f.data_compress_algo = match compression.as_str() {
                 "none" => CompressionAlgoPb::None,
                 "zstd" => CompressionAlgoPb::Zstd,
+                "lz4" => CompressionAlgoPb::Lz4,
                 _ => panic!(
-                    "unknown compression algorithm: {}, supported: none, zstd",
+                    "unknown compression algorithm: {}, supported: none, zstd, lz4",
                     compression
                 ),
             }
             .into();
         }
+        f.data_compress_level = self.compression_level.unwrap_or(f.data_compress_level);
+        if let Some(multipath) = &self.multipath {
+            f.multipath_mode = match multipath.as_str() {
+                "off" => MultipathMode::Off,
+                "stripe" => MultipathMode::Stripe,
+                "duplicate" => MultipathMode::Duplicate,
+                _ => panic!(
+                    "unknown multipath mode: {}, supported: off, stripe, duplicate",
+                    multipath
+                ),
+            }
+            .into();
+        }
+        if let Some(policy) = &self.route_cost_policy {
+            f.route_cost_policy = match policy.as_str() {
+                "latency" => RouteCostPolicy::LeastLatency,
+                "loss" => RouteCostPolicy::LeastLoss,
+                "direct" => RouteCostPolicy::PreferDirect,
+                _ => panic!(
+                    "unknown route cost policy: {}, supported: latency, loss, direct",
+                    policy
+                ),
+            }
+            .into();
+        }
         f.bind_device = self.bind_device.unwrap_or(f.bind_device);
         f.enable_kcp_proxy = self.enable_kcp_proxy.unwrap_or(f.enable_kcp_proxy);
         f.disable_kcp_input = self.disable_kcp_input.unwrap_or(f.disable_kcp_input);
@@ -950,6 +1187,26 @@ impl NetworkOptions {
             .unwrap_or(f.foreign_relay_bps_limit);
         f.multi_thread_count = self.multi_thread_count.unwrap_or(f.multi_thread_count);
         f.disable_relay_kcp = self.disable_relay_kcp.unwrap_or(f.disable_relay_kcp);
+        f.disable_relay_data = self.disable_relay_data.unwrap_or(f.disable_relay_data);
+        f.relay_daily_quota_mb = self.relay_daily_quota_mb.unwrap_or(f.relay_daily_quota_mb);
+        if let Some(ports) = &self.broadcast_relay_ports {
+            easytier::peers::broadcast_relay::parse_port_ranges(ports)
+                .with_context(|| format!("failed to parse broadcast relay ports: {}", ports))?;
+            f.broadcast_relay_ports = ports.clone();
+        }
+        f.multicast_igmp_snooping = self
+            .multicast_igmp_snooping
+            .unwrap_or(f.multicast_igmp_snooping);
+        f.dhcp_ipv6 = self.dhcp_ipv6.unwrap_or(f.dhcp_ipv6);
+        if let Some(prefix) = &self.ipv6_ula_prefix {
+            let cidr: cidr::Ipv6Cidr = prefix
+                .parse()
+                .with_context(|| format!("failed to parse ipv6 prefix: {}", prefix))?;
+            if cidr.network_length() > 64 {
+                anyhow::bail!("ipv6 prefix must be /64 or shorter: {}", prefix);
+            }
+            f.ipv6_ula_prefix = prefix.clone();
+        }
         f.enable_relay_foreign_network_kcp = self
             .enable_relay_foreign_network_kcp
             .unwrap_or(f.enable_relay_foreign_network_kcp);
@@ -960,6 +1217,10 @@ impl NetworkOptions {
             cfg.set_exit_nodes(self.exit_nodes.clone());
         }
 
+        if !self.exit_node_check_targets.is_empty() {
+            cfg.set_exit_node_check_targets(self.exit_node_check_targets.clone());
+        }
+
         let mut old_tcp_whitelist = cfg.get_tcp_whitelist();
         old_tcp_whitelist.extend(self.tcp_whitelist.clone());
         cfg.set_tcp_whitelist(old_tcp_whitelist);
@@ -274,7 +274,7 @@ impl IcmpProxy {
         }
 
         let peer_manager = self.peer_manager.clone();
-        let is_latency_first = self.global_ctx.get_flags().latency_first;
+        let is_latency_first = self.global_ctx.is_latency_first();
         self.tasks.lock().await.spawn(
             async move {
                 while let Some(mut msg) = receiver.recv().await {
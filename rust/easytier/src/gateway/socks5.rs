@@ -1,7 +1,7 @@
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
         Arc, Weak,
     },
     time::{Duration, Instant},
@@ -49,11 +49,12 @@ use tokio::{
 use crate::{
     common::{error::Error, global_ctx::GlobalCtx},
     peers::{peer_manager::PeerManager, PeerPacketFilter},
+    proto::cli::PortForwardStatus,
 };
 
 use super::tcp_proxy::NatDstConnector as _;
 
-enum SocksUdpSocket {
+pub(crate) enum SocksUdpSocket {
     UdpSocket(Arc<tokio::net::UdpSocket>),
     SmolUdpSocket(super::tokio_smoltcp::UdpSocket),
 }
@@ -74,7 +75,7 @@ impl SocksUdpSocket {
     }
 }
 
-enum SocksTcpStream {
+pub(crate) enum SocksTcpStream {
     Tcp(tokio::net::TcpStream),
     SmolTcp(super::tokio_smoltcp::TcpStream),
     Kcp(KcpStream),
@@ -240,7 +241,7 @@ impl AsyncTcpConnector for Socks5KcpConnector {
     }
 }
 
-fn bind_tcp_socket(addr: SocketAddr, net_ns: NetNS) -> Result<TcpListener, Error> {
+pub(crate) fn bind_tcp_socket(addr: SocketAddr, net_ns: NetNS) -> Result<TcpListener, Error> {
     let _g = net_ns.guard();
     let socket2_socket = socket2::Socket::new(
         socket2::Domain::for_address(addr),
@@ -259,7 +260,7 @@ fn bind_tcp_socket(addr: SocketAddr, net_ns: NetNS) -> Result<TcpListener, Error
     Ok(socket.listen(1024)?)
 }
 
-fn bind_udp_socket(addr: SocketAddr, net_ns: NetNS) -> Result<UdpSocket, Error> {
+pub(crate) fn bind_udp_socket(addr: SocketAddr, net_ns: NetNS) -> Result<UdpSocket, Error> {
     let _g = net_ns.guard();
     let socket2_socket = socket2::Socket::new(
         socket2::Domain::for_address(addr),
@@ -397,9 +398,17 @@ struct UdpClientInfo {
     entry_key: Socks5Entry,
 }
 
+#[derive(Debug, Default)]
+struct PortForwardStats {
+    active_conns: AtomicU32,
+    total_conns: AtomicU64,
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 struct UdpClientKey {
     client_addr: SocketAddr,
+    // forwards on different bind addresses may share the destination
+    bind_addr: SocketAddr,
     dst_addr: SocketAddr,
 }
 
@@ -423,6 +432,9 @@ pub struct Socks5Server {
 
     socks5_enabled: Arc<AtomicBool>,
     cancel_tokens: Arc<DashMap<PortForwardConfig, DropGuard>>,
+    port_forward_stats: Arc<DashMap<PortForwardConfig, Arc<PortForwardStats>>>,
+    // bind errors of port forwards in config that are not running
+    port_forward_errors: Arc<DashMap<PortForwardConfig, String>>,
     port_forward_list_change_notifier: Arc<Notify>,
 }
 
@@ -537,6 +549,8 @@ impl Socks5Server {
 
             socks5_enabled: Arc::new(AtomicBool::new(false)),
             cancel_tokens: Arc::new(DashMap::new()),
+            port_forward_stats: Arc::new(DashMap::new()),
+            port_forward_errors: Arc::new(DashMap::new()),
             port_forward_list_change_notifier: Arc::new(Notify::new()),
         })
     }
@@ -640,9 +654,10 @@ impl Socks5Server {
         Ok(())
     }
 
+    /// Start port forwards in `cfgs` and stop the others. A forward failing to bind does not
+    /// stop the rest, its error is reported by `list_port_forward_status`.
     pub async fn reload_port_forwards(&self, cfgs: &Vec<PortForwardConfig>) -> Result<(), Error> {
-        // remove entries not in new cfg
-        self.cancel_tokens.retain(|k, _| {
+        let in_cfgs = |k: &PortForwardConfig| {
             cfgs.iter().any(|cfg| {
                 if cfg.dst_addr.ip().is_unspecified() {
                     k.bind_addr == cfg.bind_addr && k.proto == cfg.proto
@@ -650,22 +665,75 @@ impl Socks5Server {
                     k == cfg
                 }
             })
-        });
+        };
+        // remove entries not in new cfg
+        self.cancel_tokens.retain(|k, _| in_cfgs(k));
+        self.port_forward_stats.retain(|k, _| in_cfgs(k));
+        self.port_forward_errors.retain(|k, _| in_cfgs(k));
         // add new ones
         for cfg in cfgs {
             if !self.cancel_tokens.contains_key(cfg) {
-                self.add_port_forward(cfg.clone()).await?;
+                if let Err(e) = self.add_port_forward(cfg.clone()).await {
+                    tracing::error!(?cfg, ?e, "failed to add port forward");
+                }
             }
         }
         self.port_forward_list_change_notifier.notify_one();
         Ok(())
     }
 
+    pub fn list_port_forward_status(&self, cfgs: &[PortForwardConfig]) -> Vec<PortForwardStatus> {
+        cfgs.iter()
+            .map(|cfg| {
+                let (active_connections, total_connections) =
+                    if cfg.proto.eq_ignore_ascii_case("udp") {
+                        // udp has no connection, count client sessions instead
+                        let active = self
+                            .udp_client_map
+                            .iter()
+                            .filter(|e| {
+                                e.key().bind_addr == cfg.bind_addr
+                                    && e.key().dst_addr == cfg.dst_addr
+                            })
+                            .count() as u32;
+                        let total = self
+                            .port_forward_stats
+                            .get(cfg)
+                            .map(|s| s.total_conns.load(Ordering::Relaxed))
+                            .unwrap_or_default();
+                        (active, total)
+                    } else {
+                        self.port_forward_stats
+                            .get(cfg)
+                            .map(|s| {
+                                (
+                                    s.active_conns.load(Ordering::Relaxed),
+                                    s.total_conns.load(Ordering::Relaxed),
+                                )
+                            })
+                            .unwrap_or_default()
+                    };
+                PortForwardStatus {
+                    cfg: Some(cfg.clone().into()),
+                    bound: self.cancel_tokens.contains_key(cfg),
+                    error: self.port_forward_errors.get(cfg).map(|e| e.value().clone()),
+                    active_connections,
+                    total_connections,
+                }
+            })
+            .collect()
+    }
+
     async fn handle_port_forward_connection(
         mut incoming_socket: tokio::net::TcpStream,
         connector: Box<dyn AsyncTcpConnector<S = SocksTcpStream> + Send>,
         dst_addr: SocketAddr,
+        stats: Arc<PortForwardStats>,
     ) {
+        crate::defer! {
+            stats.active_conns.fetch_sub(1, Ordering::Relaxed);
+        }
+
         let outgoing_socket = match connector.tcp_connect(dst_addr, 10).await {
             Ok(socket) => socket,
             Err(e) => {
@@ -689,21 +757,20 @@ impl Socks5Server {
     }
 
     pub async fn add_port_forward(&self, cfg: PortForwardConfig) -> Result<(), Error> {
-        match cfg.proto.to_lowercase().as_str() {
-            "tcp" => {
-                self.add_tcp_port_forward(&cfg).await?;
-            }
-            "udp" => {
-                self.add_udp_port_forward(&cfg).await?;
-            }
-            _ => {
-                return Err(anyhow::anyhow!(
-                    "unsupported protocol: {}, only support udp / tcp",
-                    cfg.proto
-                )
-                .into());
-            }
+        let ret = match cfg.proto.to_lowercase().as_str() {
+            "tcp" => self.add_tcp_port_forward(&cfg).await,
+            "udp" => self.add_udp_port_forward(&cfg).await,
+            _ => Err(anyhow::anyhow!(
+                "unsupported protocol: {}, only support udp / tcp",
+                cfg.proto
+            )
+            .into()),
+        };
+        if let Err(e) = ret {
+            self.port_forward_errors.insert(cfg, e.to_string());
+            return Err(e);
         }
+        self.port_forward_errors.remove(&cfg);
         self.global_ctx
             .issue_event(GlobalCtxEvent::PortForwardAdded(cfg.clone().into()));
         Ok(())
@@ -711,6 +778,8 @@ impl Socks5Server {
 
     pub fn remove_port_forward(&self, cfg: PortForwardConfig) {
         let _ = self.cancel_tokens.remove(&cfg);
+        let _ = self.port_forward_stats.remove(&cfg);
+        let _ = self.port_forward_errors.remove(&cfg);
     }
 
     pub async fn add_tcp_port_forward(&self, cfg: &PortForwardConfig) -> Result<(), Error> {
@@ -726,6 +795,8 @@ impl Socks5Server {
         let cancel_token = CancellationToken::new();
         self.cancel_tokens
             .insert(cfg.clone(), cancel_token.clone().drop_guard());
+        let stats = Arc::new(PortForwardStats::default());
+        self.port_forward_stats.insert(cfg.clone(), stats.clone());
 
         self.tasks.lock().unwrap().spawn(async move {
             loop {
@@ -780,6 +851,8 @@ impl Socks5Server {
                         }),
                     };
 
+                stats.total_conns.fetch_add(1, Ordering::Relaxed);
+                stats.active_conns.fetch_add(1, Ordering::Relaxed);
                 forward_tasks
                     .lock()
                     .unwrap()
@@ -787,6 +860,7 @@ impl Socks5Server {
                         incoming_socket,
                         connector,
                         dst_addr,
+                        stats.clone(),
                     ));
             }
         });
@@ -807,6 +881,8 @@ impl Socks5Server {
         let cancel_token = CancellationToken::new();
         self.cancel_tokens
             .insert(cfg.clone(), cancel_token.clone().drop_guard());
+        let stats = Arc::new(PortForwardStats::default());
+        self.port_forward_stats.insert(cfg.clone(), stats.clone());
 
         self.tasks.lock().unwrap().spawn(async move {
             loop {
@@ -837,6 +913,7 @@ impl Socks5Server {
 
                 let udp_client_key = UdpClientKey {
                     client_addr: addr,
+                    bind_addr,
                     dst_addr,
                 };
 
@@ -875,6 +952,7 @@ impl Socks5Server {
                             entry_key,
                         });
                         udp_client_map.insert(udp_client_key.clone(), client_info.clone());
+                        stats.total_conns.fetch_add(1, Ordering::Relaxed);
                         client_info
                     }
                 };
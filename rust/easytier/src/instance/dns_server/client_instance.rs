@@ -66,6 +66,7 @@ impl MagicDnsClientInstance {
             routes.push(Route {
                 hostname: ctx.get_hostname(),
                 ipv4_addr: ctx.get_ipv4().map(Into::into),
+                ipv6_addr: ctx.get_ipv6().map(Into::into),
                 ..Default::default()
             });
             let req = UpdateDnsRecordRequest {
@@ -2,7 +2,7 @@ use hickory_proto::rr;
 use hickory_proto::rr::RData;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -99,6 +99,10 @@ impl TryFrom<&Record> for rr::Record {
                 let addr: Ipv4Addr = value.value.parse()?;
                 record.set_data(RData::A(rr::rdata::a::A(addr)));
             }
+            RecordType::AAAA => {
+                let addr: Ipv6Addr = value.value.parse()?;
+                record.set_data(RData::AAAA(rr::rdata::aaaa::AAAA(addr)));
+            }
             RecordType::SOA => {
                 let soa = value.value.split_whitespace().collect::<Vec<_>>();
                 if soa.len() != 7 {
@@ -190,4 +194,21 @@ ttl = "61s"
 
         Ok(())
     }
+
+    #[test]
+    fn test_aaaa_record() -> anyhow::Result<()> {
+        let record = RecordBuilder::default()
+            .rr_type(RecordType::AAAA)
+            .name("node.et.net.".to_string())
+            .value("fd00::1:1234:5678".to_string())
+            .ttl(Duration::from_secs(1))
+            .build()?;
+        let rr_record: rr::Record = (&record).try_into()?;
+        assert_eq!(rr_record.record_type(), RecordType::AAAA);
+
+        let mut record = record;
+        record.value = "10.0.0.1".to_string();
+        assert!(rr::Record::try_from(&record).is_err());
+        Ok(())
+    }
 }
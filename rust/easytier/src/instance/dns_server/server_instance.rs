@@ -38,8 +38,9 @@ use crate::{
         common::{TunnelInfo, Void},
         magic_dns::{
             dns_record::{self},
-            DnsRecord, DnsRecordA, DnsRecordList, GetDnsRecordResponse, HandshakeRequest,
-            HandshakeResponse, MagicDnsServerRpc, MagicDnsServerRpcServer, UpdateDnsRecordRequest,
+            DnsRecord, DnsRecordA, DnsRecordAaaa, DnsRecordList, GetDnsRecordResponse,
+            HandshakeRequest, HandshakeResponse, MagicDnsServerRpc, MagicDnsServerRpcServer,
+            UpdateDnsRecordRequest,
         },
         rpc_impl::standalone::{RpcServerHook, StandAloneServer},
         rpc_types::controller::{BaseController, Controller},
@@ -81,24 +82,33 @@ impl MagicDnsServerInstanceData {
                 continue;
             }
 
-            let Some(ipv4_addr) = route.ipv4_addr.unwrap_or_default().address else {
-                continue;
-            };
-
-            let record = RecordBuilder::default()
-                .rr_type(RecordType::A)
-                .name(format!("{}.{}", route.hostname, zone))
-                .value(ipv4_addr.to_string())
-                .ttl(Duration::from_secs(1))
-                .build()?;
+            let ipv4_addr = route
+                .ipv4_addr
+                .unwrap_or_default()
+                .address
+                .map(|addr| (RecordType::A, Ipv4Addr::from(addr).to_string()));
+            let ipv6_addr = route
+                .ipv6_addr
+                .unwrap_or_default()
+                .address
+                .map(|addr| (RecordType::AAAA, std::net::Ipv6Addr::from(addr).to_string()));
+
+            for (rr_type, value) in ipv4_addr.into_iter().chain(ipv6_addr) {
+                let record = RecordBuilder::default()
+                    .rr_type(rr_type)
+                    .name(format!("{}.{}", route.hostname, zone))
+                    .value(value)
+                    .ttl(Duration::from_secs(1))
+                    .build()?;
+
+                // check record name valid for dns
+                if let Err(e) = record.name() {
+                    tracing::error!("Invalid subdomain label: {}", e);
+                    continue;
+                }
 
-            // check record name valid for dns
-            if let Err(e) = record.name() {
-                tracing::error!("Invalid subdomain label: {}", e);
-                continue;
+                records.push(record);
             }
-
-            records.push(record);
         }
 
         let soa_record = RecordBuilder::default()
@@ -199,6 +209,15 @@ impl MagicDnsServerRpc for MagicDnsServerInstanceData {
                         ttl: 1,
                     })),
                 });
+                if let Some(ipv6_addr) = route.ipv6_addr {
+                    dns_records.records.push(DnsRecord {
+                        record: Some(dns_record::Record::Aaaa(DnsRecordAaaa {
+                            name: format!("{}.{}", route.hostname, zone),
+                            value: ipv6_addr.address,
+                            ttl: 1,
+                        })),
+                    });
+                }
             }
             ret.insert(zone.clone(), dns_records);
         }
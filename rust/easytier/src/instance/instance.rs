@@ -1,12 +1,12 @@
 use std::any::Any;
 use std::collections::HashSet;
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Weak};
 use std::time::Duration;
 
 use anyhow::Context;
-use cidr::{IpCidr, Ipv4Inet};
+use cidr::{IpCidr, Ipv4Inet, Ipv6Cidr, Ipv6Inet};
 
 use futures::FutureExt;
 use tokio::sync::{oneshot, Notify};
@@ -14,13 +14,14 @@ use tokio::{sync::Mutex, task::JoinSet};
 use tokio_util::sync::CancellationToken;
 
 use crate::common::acl_processor::AclRuleBuilder;
-use crate::common::config::ConfigLoader;
+use crate::common::config::{ConfigLoader, PortForwardConfig};
 use crate::common::error::Error;
 use crate::common::global_ctx::{ArcGlobalCtx, GlobalCtx, GlobalCtxEvent};
 use crate::common::scoped_task::ScopedTask;
 use crate::common::PeerId;
 use crate::connector::direct::DirectConnectorManager;
 use crate::connector::manual::{ConnectorManagerRpcService, ManualConnectorManager};
+use crate::connector::tcp_hole_punch::TcpHolePunchConnector;
 use crate::connector::udp_hole_punch::UdpHolePunchConnector;
 use crate::gateway::icmp_proxy::IcmpProxy;
 use crate::gateway::kcp_proxy::{KcpProxyDst, KcpProxyDstRpcService, KcpProxySrc};
@@ -41,7 +42,10 @@ use crate::proto::cli::{
     MappedListenerManageAction, MappedListenerManageRpc, MetricSnapshot, PortForwardManageRpc,
     RemovePortForwardRequest, RemovePortForwardResponse, StatsRpc,
 };
-use crate::proto::cli::{GetVpnPortalInfoRequest, GetVpnPortalInfoResponse, VpnPortalInfo};
+use crate::proto::cli::{
+    GetVpnPortalInfoRequest, GetVpnPortalInfoResponse, RevokeVpnPortalClientRequest,
+    RevokeVpnPortalClientResponse, VpnPortalInfo,
+};
 use crate::proto::common::{PortForwardConfigPb, TunnelInfo};
 use crate::proto::peer_rpc::PeerCenterRpcServer;
 use crate::proto::rpc_impl::standalone::{RpcServerHook, StandAloneServer};
@@ -53,10 +57,45 @@ use crate::vpn_portal::{self, VpnPortal};
 use super::dns_server::runner::DnsRunner;
 use super::dns_server::MAGIC_DNS_FAKE_IP;
 use super::listeners::ListenerManager;
+use super::port_mapping::PortMappingManager;
 
 #[cfg(feature = "socks5")]
 use crate::gateway::socks5::Socks5Server;
 
+/// The ipv6 ula prefix (RFC 4193) of a network. The global id is derived from the network
+/// name, so all members of a network agree on it without any coordination.
+pub fn ula_prefix_of_network(network_name: &str) -> Ipv6Cidr {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(network_name.as_bytes());
+    let mut octets = [0u8; 16];
+    octets[0] = 0xfd;
+    octets[1..6].copy_from_slice(&digest[..5]);
+    Ipv6Cidr::new(Ipv6Addr::from(octets), 64).unwrap()
+}
+
+/// The dhcp ipv6 address of a node. The interface id is hashed from the machine id and the
+/// instance name, so a node keeps its address across restarts while the peer id changes, and
+/// 64 bits of hash make a collision between the members of a network unlikely.
+pub fn dhcp_ipv6_of_node(prefix: &Ipv6Cidr, machine_id: &uuid::Uuid, inst_name: &str) -> Ipv6Inet {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(machine_id.as_bytes());
+    hasher.update(inst_name.as_bytes());
+    let digest = hasher.finalize();
+    let interface_id = u64::from_be_bytes(digest[..8].try_into().unwrap()) as u128;
+
+    let host_mask = u128::MAX
+        .checked_shr(prefix.network_length() as u32)
+        .unwrap_or(0);
+    let mut host = interface_id & host_mask;
+    // keep away from the subnet router anycast address
+    if host == 0 {
+        host = 1;
+    }
+    let addr = u128::from(prefix.first_address()) | host;
+    Ipv6Inet::new(Ipv6Addr::from(addr), prefix.network_length()).unwrap()
+}
+
 #[derive(Clone)]
 struct IpProxy {
     tcp_proxy: Arc<TcpProxy<NatDstTcpConnector>>,
@@ -239,6 +278,8 @@ pub struct Instance {
     conn_manager: Arc<ManualConnectorManager>,
     direct_conn_manager: Arc<DirectConnectorManager>,
     udp_hole_puncher: Arc<Mutex<UdpHolePunchConnector>>,
+    tcp_hole_puncher: Arc<Mutex<TcpHolePunchConnector>>,
+    port_mapping_manager: PortMappingManager,
 
     ip_proxy: Option<IpProxy>,
 
@@ -250,7 +291,8 @@ pub struct Instance {
 
     peer_center: Arc<PeerCenterInstance>,
 
-    vpn_portal: Arc<Mutex<Box<dyn VpnPortal>>>,
+    // the first portal is always wireguard (or the null portal), others run alongside it
+    vpn_portals: Vec<Arc<Mutex<Box<dyn VpnPortal>>>>,
 
     #[cfg(feature = "socks5")]
     socks5_server: Arc<Socks5Server>,
@@ -296,13 +338,34 @@ impl Instance {
         direct_conn_manager.run();
 
         let udp_hole_puncher = UdpHolePunchConnector::new(peer_manager.clone());
+        let tcp_hole_puncher = TcpHolePunchConnector::new(peer_manager.clone());
+        let port_mapping_manager = PortMappingManager::new(global_ctx.clone());
 
         let peer_center = Arc::new(PeerCenterInstance::new(peer_manager.clone()));
 
+        // only create the portals the config asks for
+        #[allow(unused_variables)]
+        let vpn_portal_cfg = global_ctx.config.get_vpn_portal_config();
+        #[allow(unused_mut)]
+        let mut vpn_portals: Vec<Box<dyn VpnPortal>> = Vec::new();
         #[cfg(feature = "wireguard")]
-        let vpn_portal_inst = vpn_portal::wireguard::WireGuard::default();
-        #[cfg(not(feature = "wireguard"))]
-        let vpn_portal_inst = vpn_portal::NullVpnPortal;
+        if vpn_portal_cfg
+            .as_ref()
+            .is_some_and(|c| c.wireguard_listen.is_some())
+        {
+            vpn_portals.push(Box::new(vpn_portal::wireguard::WireGuard::default()));
+        }
+        #[cfg(feature = "openvpn")]
+        if vpn_portal_cfg.as_ref().is_some_and(|c| c.openvpn.is_some()) {
+            vpn_portals.push(Box::new(vpn_portal::openvpn::OpenVpn::default()));
+        }
+        #[cfg(feature = "shadowsocks")]
+        if vpn_portal_cfg
+            .as_ref()
+            .is_some_and(|c| c.shadowsocks.is_some())
+        {
+            vpn_portals.push(Box::new(vpn_portal::shadowsocks::Shadowsocks::default()));
+        }
 
         #[cfg(feature = "socks5")]
         let socks5_server = Socks5Server::new(global_ctx.clone(), peer_manager.clone(), None);
@@ -325,6 +388,8 @@ impl Instance {
             conn_manager,
             direct_conn_manager: Arc::new(direct_conn_manager),
             udp_hole_puncher: Arc::new(Mutex::new(udp_hole_puncher)),
+            tcp_hole_puncher: Arc::new(Mutex::new(tcp_hole_puncher)),
+            port_mapping_manager,
 
             ip_proxy: None,
             kcp_proxy_src: None,
@@ -335,7 +400,10 @@ impl Instance {
 
             peer_center,
 
-            vpn_portal: Arc::new(Mutex::new(Box::new(vpn_portal_inst))),
+            vpn_portals: vpn_portals
+                .into_iter()
+                .map(|p| Arc::new(Mutex::new(p)))
+                .collect(),
 
             #[cfg(feature = "socks5")]
             socks5_server,
@@ -536,6 +604,31 @@ impl Instance {
         });
     }
 
+    fn assign_dhcp_ipv6(&self) -> Result<(), Error> {
+        let flags = self.global_ctx.get_flags();
+        let prefix = if flags.ipv6_ula_prefix.is_empty() {
+            ula_prefix_of_network(&self.global_ctx.get_network_name())
+        } else {
+            let prefix: Ipv6Cidr = flags
+                .ipv6_ula_prefix
+                .parse()
+                .with_context(|| format!("invalid ipv6 prefix: {}", flags.ipv6_ula_prefix))?;
+            if prefix.network_length() > 64 {
+                return Err(anyhow::anyhow!("ipv6 prefix longer than /64: {}", prefix).into());
+            }
+            prefix
+        };
+
+        let ip = dhcp_ipv6_of_node(
+            &prefix,
+            &crate::common::get_machine_id(),
+            &self.global_ctx.inst_name,
+        );
+        tracing::info!(?ip, "dhcp ipv6 assigned");
+        self.global_ctx.set_ipv6(Some(ip));
+        Ok(())
+    }
+
     fn check_for_static_ip(&self, first_round_output: oneshot::Sender<Result<(), Error>>) {
         let ipv4_addr = self.global_ctx.get_ipv4();
         let ipv6_addr = self.global_ctx.get_ipv6();
@@ -630,6 +723,10 @@ impl Instance {
 
         Self::clear_nic_ctx(self.nic_ctx.clone(), self.peer_packet_receiver.clone()).await;
 
+        if self.global_ctx.get_flags().dhcp_ipv6 && self.global_ctx.get_ipv6().is_none() {
+            self.assign_dhcp_ipv6()?;
+        }
+
         if !self.global_ctx.config.get_flags().no_tun {
             #[cfg(not(any(target_os = "android", target_env = "ohos")))]
             {
@@ -673,6 +770,9 @@ impl Instance {
         self.global_ctx
             .get_acl_filter()
             .reload_rules(AclRuleBuilder::build(&self.global_ctx)?.as_ref());
+        self.global_ctx
+            .get_bandwidth_limiter()
+            .reload_limits(&self.global_ctx.config.get_bandwidth_limits());
 
         // run after tun device created, so listener can bind to tun device, which may be required by win 10
         self.ip_proxy = Some(IpProxy::new(
@@ -682,6 +782,8 @@ impl Instance {
         self.run_ip_proxy().await?;
 
         self.udp_hole_puncher.lock().await.run().await?;
+        self.tcp_hole_puncher.lock().await.run().await?;
+        self.port_mapping_manager.run();
 
         self.peer_center.init().await;
         let route_calc = self.peer_center.get_cost_calculator();
@@ -722,11 +824,13 @@ impl Instance {
         if self.global_ctx.get_vpn_portal_cidr().is_none() {
             return Err(anyhow::anyhow!("vpn portal cidr not set.").into());
         }
-        self.vpn_portal
-            .lock()
-            .await
-            .start(self.get_global_ctx(), self.get_peer_manager())
-            .await?;
+        for vpn_portal in self.vpn_portals.iter() {
+            vpn_portal
+                .lock()
+                .await
+                .start(self.get_global_ctx(), self.get_peer_manager())
+                .await?;
+        }
         Ok(())
     }
 
@@ -762,7 +866,7 @@ impl Instance {
         #[derive(Clone)]
         struct VpnPortalRpcService {
             peer_mgr: Weak<PeerManager>,
-            vpn_portal: Weak<Mutex<Box<dyn VpnPortal>>>,
+            vpn_portals: Vec<Weak<Mutex<Box<dyn VpnPortal>>>>,
         }
 
         #[async_trait::async_trait]
@@ -774,30 +878,59 @@ impl Instance {
                 _: BaseController,
                 _request: GetVpnPortalInfoRequest,
             ) -> Result<GetVpnPortalInfoResponse, rpc_types::error::Error> {
-                let Some(vpn_portal) = self.vpn_portal.upgrade() else {
-                    return Err(anyhow::anyhow!("vpn portal not available").into());
-                };
-
                 let Some(peer_mgr) = self.peer_mgr.upgrade() else {
                     return Err(anyhow::anyhow!("peer manager not available").into());
                 };
 
-                let vpn_portal = vpn_portal.lock().await;
-                let ret = GetVpnPortalInfoResponse {
-                    vpn_portal_info: Some(VpnPortalInfo {
+                let mut infos = Vec::with_capacity(self.vpn_portals.len());
+                for vpn_portal in self.vpn_portals.iter() {
+                    let Some(vpn_portal) = vpn_portal.upgrade() else {
+                        return Err(anyhow::anyhow!("vpn portal not available").into());
+                    };
+                    let vpn_portal = vpn_portal.lock().await;
+                    infos.push(VpnPortalInfo {
                         vpn_type: vpn_portal.name(),
-                        client_config: vpn_portal.dump_client_config(peer_mgr).await,
+                        client_config: vpn_portal.dump_client_config(peer_mgr.clone()).await,
                         connected_clients: vpn_portal.list_clients().await,
-                    }),
+                        clients: vpn_portal.list_client_infos().await,
+                    });
+                }
+
+                let ret = GetVpnPortalInfoResponse {
+                    vpn_portal_info: infos.first().cloned(),
+                    vpn_portal_infos: infos,
                 };
 
                 Ok(ret)
             }
+
+            async fn revoke_vpn_portal_client(
+                &self,
+                _: BaseController,
+                request: RevokeVpnPortalClientRequest,
+            ) -> Result<RevokeVpnPortalClientResponse, rpc_types::error::Error> {
+                let vpn_type = if request.vpn_type.is_empty() {
+                    "wireguard"
+                } else {
+                    request.vpn_type.as_str()
+                };
+                for vpn_portal in self.vpn_portals.iter() {
+                    let Some(vpn_portal) = vpn_portal.upgrade() else {
+                        return Err(anyhow::anyhow!("vpn portal not available").into());
+                    };
+                    let vpn_portal = vpn_portal.lock().await;
+                    if vpn_portal.name() == vpn_type {
+                        vpn_portal.revoke_client(&request.client_name).await?;
+                        return Ok(RevokeVpnPortalClientResponse {});
+                    }
+                }
+                Err(anyhow::anyhow!("vpn portal not found: {}", vpn_type).into())
+            }
         }
 
         VpnPortalRpcService {
             peer_mgr: Arc::downgrade(&self.peer_manager),
-            vpn_portal: Arc::downgrade(&self.vpn_portal),
+            vpn_portals: self.vpn_portals.iter().map(Arc::downgrade).collect(),
         }
     }
 
@@ -818,12 +951,18 @@ impl Instance {
             ) -> Result<ListMappedListenerResponse, rpc_types::error::Error> {
                 let mut ret = ListMappedListenerResponse::default();
                 let urls = self.0.config.get_mapped_listeners();
-                let mapped_listeners: Vec<MappedListener> = urls
+                let mut mapped_listeners: Vec<MappedListener> = urls
                     .into_iter()
                     .map(|u| MappedListener {
                         url: Some(u.into()),
+                        is_port_mapping: false,
                     })
                     .collect();
+                let port_mapped = self.0.get_port_mapped_listeners();
+                mapped_listeners.extend(port_mapped.into_iter().map(|u| MappedListener {
+                    url: Some(u.into()),
+                    is_port_mapping: true,
+                }));
                 ret.mappedlisteners = mapped_listeners;
                 Ok(ret)
             }
@@ -872,18 +1011,41 @@ impl Instance {
                 let Some(socks5_server) = self.socks5_server.upgrade() else {
                     return Err(anyhow::anyhow!("socks5 server not available").into());
                 };
-                if let Some(cfg) = request.cfg {
-                    tracing::info!("Port forward rule added: {:?}", cfg);
-                    let mut current_forwards = self.global_ctx.config.get_port_forwards();
-                    current_forwards.push(cfg.into());
-                    self.global_ctx
-                        .config
-                        .set_port_forwards(current_forwards.clone());
-                    socks5_server
-                        .reload_port_forwards(&current_forwards)
-                        .await
-                        .with_context(|| "Failed to reload port forwards")?;
+                let Some(cfg) = request.cfg else {
+                    return Err(anyhow::anyhow!("port forward config is empty").into());
+                };
+                let Some(bind_addr) = cfg.bind_addr else {
+                    return Err(anyhow::anyhow!("port forward bind addr is empty").into());
+                };
+                let Some(dst_addr) = cfg.dst_addr else {
+                    return Err(anyhow::anyhow!("port forward dst addr is empty").into());
+                };
+                if bind_addr.port == 0 || dst_addr.port == 0 {
+                    return Err(anyhow::anyhow!("port forward port must not be 0").into());
+                }
+
+                let cfg: PortForwardConfig = cfg.into();
+                let mut current_forwards = self.global_ctx.config.get_port_forwards();
+                if current_forwards
+                    .iter()
+                    .any(|e| e.bind_addr == cfg.bind_addr && e.proto == cfg.proto)
+                {
+                    return Err(anyhow::anyhow!(
+                        "port forward already exists on {} {}",
+                        cfg.proto,
+                        cfg.bind_addr
+                    )
+                    .into());
                 }
+
+                // only save the rule once it is running, so a bad bind addr is reported to caller
+                socks5_server
+                    .add_port_forward(cfg.clone())
+                    .await
+                    .with_context(|| format!("Failed to add port forward {:?}", cfg))?;
+                tracing::info!("Port forward rule added: {:?}", cfg);
+                current_forwards.push(cfg);
+                self.global_ctx.config.set_port_forwards(current_forwards);
                 Ok(AddPortForwardResponse {})
             }
 
@@ -919,8 +1081,13 @@ impl Instance {
                 _request: ListPortForwardRequest,
             ) -> Result<ListPortForwardResponse, rpc_types::error::Error> {
                 let forwards = self.global_ctx.config.get_port_forwards();
+                let statuses = self
+                    .socks5_server
+                    .upgrade()
+                    .map(|s| s.list_port_forward_status(&forwards))
+                    .unwrap_or_default();
                 let cfgs: Vec<PortForwardConfigPb> = forwards.into_iter().map(Into::into).collect();
-                Ok(ListPortForwardResponse { cfgs })
+                Ok(ListPortForwardResponse { cfgs, statuses })
             }
         }
 
@@ -1007,7 +1174,13 @@ impl Instance {
         s.registry()
             .register(PeerManageRpcServer::new(peer_mgr_rpc_service.clone()), "");
         s.registry()
-            .register(AclManageRpcServer::new(peer_mgr_rpc_service), "");
+            .register(AclManageRpcServer::new(peer_mgr_rpc_service.clone()), "");
+        s.registry().register(
+            BandwidthLimitManageRpcServer::new(peer_mgr_rpc_service.clone()),
+            "",
+        );
+        s.registry()
+            .register(SecretRotationManageRpcServer::new(peer_mgr_rpc_service), "");
         s.registry().register(
             ConnectorManageRpcServer::new(ConnectorManagerRpcService(conn_manager)),
             "",
@@ -1071,15 +1244,30 @@ impl Instance {
         )));
 
         let _g = self.global_ctx.net_ns.guard();
-        Ok(s.serve().await.with_context(|| "rpc server start failed")?)
+        s.serve().await.with_context(|| "rpc server start failed")?;
+
+        // port 0 lets the os pick a free port, write it back so get_rpc_portal reports it
+        if let Some(mut portal) = self.global_ctx.config.get_rpc_portal() {
+            if portal.port() == 0 {
+                if let Some(port) = s.local_url().and_then(|u| u.port()) {
+                    portal.set_port(port);
+                    self.global_ctx.config.set_rpc_portal(portal);
+                }
+            }
+        }
+        Ok(())
     }
 
     pub fn get_global_ctx(&self) -> ArcGlobalCtx {
         self.global_ctx.clone()
     }
 
-    pub fn get_vpn_portal_inst(&self) -> Arc<Mutex<Box<dyn VpnPortal>>> {
-        self.vpn_portal.clone()
+    pub fn get_vpn_portal_inst(&self) -> Option<Arc<Mutex<Box<dyn VpnPortal>>>> {
+        self.vpn_portals.first().cloned()
+    }
+
+    pub fn get_vpn_portal_insts(&self) -> Vec<Arc<Mutex<Box<dyn VpnPortal>>>> {
+        self.vpn_portals.clone()
     }
 
     pub fn get_nic_ctx(&self) -> ArcNicCtx {
@@ -1174,6 +1362,36 @@ mod tests {
         instance::instance::InstanceRpcServerHook, proto::rpc_impl::standalone::RpcServerHook,
     };
 
+    #[test]
+    fn test_dhcp_ipv6() {
+        use super::{dhcp_ipv6_of_node, ula_prefix_of_network};
+
+        let prefix = ula_prefix_of_network("net1");
+        assert_eq!(prefix, ula_prefix_of_network("net1"));
+        assert_ne!(prefix, ula_prefix_of_network("net2"));
+        assert_eq!(prefix.first_address().octets()[0], 0xfd);
+        assert_eq!(prefix.network_length(), 64);
+
+        // the same machine and instance always get the same address
+        let machine_id = uuid::Uuid::from_u128(0x1234);
+        let ip = dhcp_ipv6_of_node(&prefix, &machine_id, "inst1");
+        assert_eq!(ip, dhcp_ipv6_of_node(&prefix, &machine_id, "inst1"));
+        assert!(prefix.contains(&ip.address()));
+        assert_eq!(ip.network_length(), 64);
+        assert_ne!(u128::from(ip.address()) & u64::MAX as u128, 0);
+        assert_ne!(ip, dhcp_ipv6_of_node(&prefix, &machine_id, "inst2"));
+        assert_ne!(
+            ip,
+            dhcp_ipv6_of_node(&prefix, &uuid::Uuid::from_u128(0x1235), "inst1")
+        );
+
+        // a shorter prefix keeps its network bits
+        let prefix: cidr::Ipv6Cidr = "fd00:1:2::/48".parse().unwrap();
+        let ip = dhcp_ipv6_of_node(&prefix, &machine_id, "inst1");
+        assert!(prefix.contains(&ip.address()));
+        assert_eq!(ip.network_length(), 48);
+    }
+
     #[tokio::test]
     async fn test_rpc_portal_whitelist() {
         use cidr::IpCidr;
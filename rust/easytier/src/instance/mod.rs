@@ -4,6 +4,8 @@ pub mod instance;
 
 pub mod listeners;
 
+pub mod port_mapping;
+
 #[cfg(feature = "tun")]
 pub mod virtual_nic;
 
@@ -0,0 +1,596 @@
+// ask the router in front of us to forward the ports of our listeners, so peers
+// on the internet can connect to us directly. pcp, nat-pmp and upnp igd are
+// tried in this order, the first one which answers is used until it fails.
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::{sync::Mutex, task::JoinSet};
+
+use crate::common::{global_ctx::ArcGlobalCtx, netns::NetNS};
+
+pub mod natpmp;
+pub mod pcp;
+pub mod upnp;
+
+use natpmp::{NatPmpClient, NAT_PMP_PORT};
+use pcp::{PcpClient, PCP_PORT};
+use upnp::{UpnpClient, SSDP_MULTICAST_ADDR};
+
+const MAPPING_LIFETIME: Duration = Duration::from_secs(3600);
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+const REDISCOVER_INTERVAL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MappingProtocol {
+    Tcp,
+    Udp,
+}
+
+impl MappingProtocol {
+    pub fn of_listener(url: &url::Url) -> Option<Self> {
+        match url.scheme() {
+            "tcp" | "ws" | "wss" => Some(Self::Tcp),
+            "udp" | "quic" | "wg" => Some(Self::Udp),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MappedPort {
+    pub external_addr: SocketAddr,
+    pub lifetime: Duration,
+}
+
+#[async_trait::async_trait]
+pub trait PortMappingClient: Send + Sync + Debug {
+    fn method(&self) -> &'static str;
+
+    // the returned lifetime may be shorter than the requested one
+    async fn add_mapping(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        lifetime: Duration,
+    ) -> anyhow::Result<MappedPort>;
+
+    async fn remove_mapping(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+    ) -> anyhow::Result<()>;
+}
+
+#[cfg(target_os = "linux")]
+fn default_gateway_from_os(net_ns: &NetNS) -> Option<Ipv4Addr> {
+    // /proc/net follows the netns of the main thread, not the one of the caller
+    let route = net_ns.run(|| {
+        std::fs::read_to_string("/proc/thread-self/net/route")
+            .or_else(|_| std::fs::read_to_string("/proc/net/route"))
+    });
+
+    // Iface Destination Gateway Flags ..., addresses are hex in host byte order
+    route.ok()?.lines().skip(1).find_map(|line| {
+        let cols = line.split_whitespace().collect::<Vec<_>>();
+        if cols.len() < 3 || cols[1] != "00000000" {
+            return None;
+        }
+        let gateway = u32::from_str_radix(cols[2], 16).ok()?;
+        Some(Ipv4Addr::from(gateway.to_ne_bytes())).filter(|ip| !ip.is_unspecified())
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn default_gateway_from_os(_net_ns: &NetNS) -> Option<Ipv4Addr> {
+    use windows_sys::Win32::{
+        NetworkManagement::IpHelper::{GetBestRoute2, MIB_IPFORWARD_ROW2},
+        Networking::WinSock::{AF_INET, SOCKADDR_INET},
+    };
+
+    unsafe {
+        // the best route to any internet address is the default route
+        let mut dst: SOCKADDR_INET = std::mem::zeroed();
+        dst.Ipv4.sin_family = AF_INET;
+        dst.Ipv4.sin_addr.S_un.S_addr = u32::from_ne_bytes([8, 8, 8, 8]);
+        let mut row: MIB_IPFORWARD_ROW2 = std::mem::zeroed();
+        let mut src: SOCKADDR_INET = std::mem::zeroed();
+        let ret = GetBestRoute2(
+            std::ptr::null(),
+            0,
+            std::ptr::null(),
+            &dst,
+            0,
+            &mut row,
+            &mut src,
+        );
+        if ret != 0 || row.NextHop.si_family != AF_INET {
+            return None;
+        }
+        // on-link routes (vpn adapters) have no next hop
+        Some(Ipv4Addr::from(
+            row.NextHop.Ipv4.sin_addr.S_un.S_addr.to_ne_bytes(),
+        ))
+        .filter(|ip| !ip.is_unspecified())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn default_gateway_from_os(_net_ns: &NetNS) -> Option<Ipv4Addr> {
+    use nix::libc;
+    use std::{
+        io::{Read as _, Write as _},
+        mem::size_of,
+        os::fd::FromRawFd as _,
+    };
+
+    // RTM_GET of 0.0.0.0/0 on a routing socket, like `route -n get default`
+    let hdr_len = size_of::<libc::rt_msghdr>();
+    let sa_len = size_of::<libc::sockaddr_in>();
+    let seq = 1;
+    let mut hdr: libc::rt_msghdr = unsafe { std::mem::zeroed() };
+    hdr.rtm_msglen = (hdr_len + 2 * sa_len) as u16;
+    hdr.rtm_version = libc::RTM_VERSION as u8;
+    hdr.rtm_type = libc::RTM_GET as u8;
+    hdr.rtm_addrs = libc::RTA_DST | libc::RTA_NETMASK;
+    hdr.rtm_seq = seq;
+    let mut sa: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+    sa.sin_len = sa_len as u8;
+    sa.sin_family = libc::AF_INET as u8;
+
+    let mut req = vec![0u8; hdr_len + 2 * sa_len];
+    unsafe {
+        std::ptr::write_unaligned(req.as_mut_ptr() as *mut libc::rt_msghdr, hdr);
+        std::ptr::write_unaligned(req.as_mut_ptr().add(hdr_len) as *mut libc::sockaddr_in, sa);
+        std::ptr::write_unaligned(
+            req.as_mut_ptr().add(hdr_len + sa_len) as *mut libc::sockaddr_in,
+            sa,
+        );
+    }
+
+    let fd = unsafe { libc::socket(libc::PF_ROUTE, libc::SOCK_RAW, libc::AF_INET) };
+    if fd < 0 {
+        return None;
+    }
+    let mut sock = unsafe { std::fs::File::from_raw_fd(fd) };
+    // never block forever if the kernel does not answer
+    let timeout = libc::timeval {
+        tv_sec: GATEWAY_LOOKUP_TIMEOUT.as_secs() as libc::time_t,
+        tv_usec: 0,
+    };
+    let ret = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_RCVTIMEO,
+            &timeout as *const _ as *const libc::c_void,
+            size_of::<libc::timeval>() as libc::socklen_t,
+        )
+    };
+    if ret != 0 {
+        return None;
+    }
+    sock.write_all(&req).ok()?;
+
+    let pid = std::process::id() as libc::pid_t;
+    let mut buf = vec![0u8; 2048];
+    // the socket also gets the route changes of the whole system
+    for _ in 0..16 {
+        let n = sock.read(&mut buf).ok()?;
+        if n < hdr_len {
+            return None;
+        }
+        let reply = unsafe { std::ptr::read_unaligned(buf.as_ptr() as *const libc::rt_msghdr) };
+        if reply.rtm_pid != pid || reply.rtm_seq != seq {
+            continue;
+        }
+        if reply.rtm_errno != 0 || reply.rtm_flags & libc::RTF_GATEWAY == 0 {
+            return None;
+        }
+
+        // the addresses follow in the order of their RTA_ bits, padded to 4 bytes
+        let mut off = hdr_len;
+        for bit in 0..libc::RTAX_MAX {
+            if reply.rtm_addrs & (1 << bit) == 0 {
+                continue;
+            }
+            let len = *buf.get(off)? as usize;
+            if 1 << bit == libc::RTA_GATEWAY {
+                if *buf.get(off + 1)? != libc::AF_INET as u8 {
+                    return None;
+                }
+                let ip: [u8; 4] = buf.get(off + 4..off + 8)?.try_into().ok()?;
+                return Some(Ipv4Addr::from(ip)).filter(|ip| !ip.is_unspecified());
+            }
+            off += if len == 0 { 4 } else { (len + 3) & !3 };
+        }
+        return None;
+    }
+    None
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn default_gateway_from_os(_net_ns: &NetNS) -> Option<Ipv4Addr> {
+    None
+}
+
+const GATEWAY_LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub async fn default_gateway_v4(net_ns: &NetNS) -> anyhow::Result<Ipv4Addr> {
+    // the lookups are blocking syscalls, keep them off the runtime threads
+    let net_ns = net_ns.clone();
+    let lookup = tokio::task::spawn_blocking(move || default_gateway_from_os(&net_ns));
+    tokio::time::timeout(GATEWAY_LOOKUP_TIMEOUT, lookup)
+        .await
+        .map_err(|_| anyhow::anyhow!("default gateway lookup timed out"))??
+        .ok_or_else(|| anyhow::anyhow!("no ipv4 default gateway found"))
+}
+
+pub async fn discover_client(net_ns: &NetNS) -> anyhow::Result<Arc<dyn PortMappingClient>> {
+    // pcp and nat-pmp only talk to the gateway, upnp finds the router by multicast
+    match default_gateway_v4(net_ns).await {
+        Ok(gateway) => {
+            let pcp = PcpClient::new(SocketAddr::new(gateway.into(), PCP_PORT), net_ns.clone());
+            match pcp.announce().await {
+                Ok(_) => return Ok(Arc::new(pcp)),
+                Err(e) => tracing::debug!(?e, ?gateway, "pcp not available"),
+            }
+
+            let nat_pmp = NatPmpClient::new(
+                SocketAddr::new(gateway.into(), NAT_PMP_PORT),
+                net_ns.clone(),
+            );
+            match nat_pmp.external_ip().await {
+                Ok(_) => return Ok(Arc::new(nat_pmp)),
+                Err(e) => tracing::debug!(?e, ?gateway, "nat-pmp not available"),
+            }
+        }
+        Err(e) => tracing::debug!(?e, "skip pcp and nat-pmp"),
+    }
+
+    Ok(Arc::new(
+        UpnpClient::discover(net_ns, SSDP_MULTICAST_ADDR).await?,
+    ))
+}
+
+// only the listeners bound to an address behind the router can be mapped
+fn listener_internal_port(url: &url::Url) -> Option<(MappingProtocol, u16)> {
+    let protocol = MappingProtocol::of_listener(url)?;
+    let ip = url.host_str()?.parse::<Ipv4Addr>().ok()?;
+    if !ip.is_unspecified() && !ip.is_private() {
+        return None;
+    }
+    let port = url.port_or_known_default().filter(|p| *p > 0)?;
+    Some((protocol, port))
+}
+
+// routers behind a carrier grade nat hand out addresses nobody can connect to
+fn is_publishable(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || (a == 100 && (b & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => !(ip.is_loopback() || ip.is_unspecified()),
+    }
+}
+
+#[derive(Debug)]
+struct MappingEntry {
+    protocol: MappingProtocol,
+    internal_port: u16,
+    mapped: MappedPort,
+    renew_at: Instant,
+}
+
+impl MappingEntry {
+    fn external_url(&self, listener: &url::Url) -> Option<url::Url> {
+        let ip = self.mapped.external_addr.ip();
+        if !is_publishable(&ip) {
+            return None;
+        }
+        let mut url = listener.clone();
+        url.set_ip_host(ip).ok()?;
+        url.set_port(Some(self.mapped.external_addr.port())).ok()?;
+        Some(url)
+    }
+}
+
+#[derive(Debug, Default)]
+struct PortMappingState {
+    client: Option<Arc<dyn PortMappingClient>>,
+    entries: HashMap<url::Url, MappingEntry>,
+    last_discover: Option<Instant>,
+}
+
+struct PortMappingManagerData {
+    global_ctx: ArcGlobalCtx,
+    state: Mutex<PortMappingState>,
+}
+
+impl PortMappingManagerData {
+    async fn remove_entries(
+        client: &Arc<dyn PortMappingClient>,
+        entries: impl IntoIterator<Item = (url::Url, MappingEntry)>,
+    ) {
+        for (url, entry) in entries {
+            let ret = client
+                .remove_mapping(
+                    entry.protocol,
+                    entry.internal_port,
+                    entry.mapped.external_addr.port(),
+                )
+                .await;
+            tracing::info!(?ret, %url, ?entry, "remove port mapping");
+        }
+    }
+
+    async fn refresh(&self) {
+        let flags = self.global_ctx.get_flags();
+        let candidates = if !flags.enable_port_mapping || flags.disable_p2p {
+            vec![]
+        } else {
+            self.global_ctx
+                .get_running_listeners()
+                .into_iter()
+                .filter_map(|url| listener_internal_port(&url).map(|p| (url, p)))
+                .collect::<Vec<_>>()
+        };
+
+        let mut state = self.state.lock().await;
+
+        let stale_urls = state
+            .entries
+            .keys()
+            .filter(|url| !candidates.iter().any(|(c, _)| c == *url))
+            .cloned()
+            .collect::<Vec<_>>();
+        let stale = stale_urls
+            .into_iter()
+            .filter_map(|url| state.entries.remove_entry(&url))
+            .collect::<Vec<_>>();
+        if let Some(client) = state.client.clone() {
+            Self::remove_entries(&client, stale).await;
+        }
+
+        if candidates.is_empty() {
+            self.global_ctx.set_port_mapped_listeners(vec![]);
+            return;
+        }
+
+        if state.client.is_none()
+            && state
+                .last_discover
+                .map(|t| t.elapsed() > REDISCOVER_INTERVAL)
+                .unwrap_or(true)
+        {
+            state.last_discover = Some(Instant::now());
+            match discover_client(&self.global_ctx.net_ns).await {
+                Ok(client) => {
+                    tracing::info!(method = client.method(), "port mapping client found");
+                    state.client = Some(client);
+                }
+                Err(e) => tracing::info!(?e, "no port mapping protocol available"),
+            }
+        }
+        let Some(client) = state.client.clone() else {
+            return;
+        };
+
+        let mut all_failed = true;
+        for (url, (protocol, internal_port)) in candidates {
+            if let Some(entry) = state.entries.get(&url) {
+                if entry.renew_at > Instant::now() {
+                    all_failed = false;
+                    continue;
+                }
+            }
+
+            match client
+                .add_mapping(protocol, internal_port, MAPPING_LIFETIME)
+                .await
+            {
+                Ok(mapped) => {
+                    tracing::info!(method = client.method(), %url, ?mapped, "port mapped");
+                    all_failed = false;
+                    state.entries.insert(
+                        url,
+                        MappingEntry {
+                            protocol,
+                            internal_port,
+                            mapped,
+                            renew_at: Instant::now() + mapped.lifetime / 2,
+                        },
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!(?e, method = client.method(), %url, "port mapping failed");
+                    state.entries.remove(&url);
+                }
+            }
+        }
+
+        // the router may have been replaced or rebooted without the protocol
+        if all_failed {
+            state.client = None;
+        }
+
+        self.global_ctx.set_port_mapped_listeners(
+            state
+                .entries
+                .iter()
+                .filter_map(|(url, entry)| entry.external_url(url))
+                .collect(),
+        );
+    }
+}
+
+pub struct PortMappingManager {
+    data: Arc<PortMappingManagerData>,
+    tasks: JoinSet<()>,
+}
+
+impl PortMappingManager {
+    pub fn new(global_ctx: ArcGlobalCtx) -> Self {
+        Self {
+            data: Arc::new(PortMappingManagerData {
+                global_ctx,
+                state: Mutex::new(PortMappingState::default()),
+            }),
+            tasks: JoinSet::new(),
+        }
+    }
+
+    pub fn run(&mut self) {
+        let data = self.data.clone();
+        self.tasks.spawn(async move {
+            loop {
+                data.refresh().await;
+                tokio::time::sleep(REFRESH_INTERVAL).await;
+            }
+        });
+    }
+}
+
+impl Drop for PortMappingManager {
+    fn drop(&mut self) {
+        self.tasks.abort_all();
+
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let Ok(mut state) = self.data.state.try_lock() else {
+            return;
+        };
+        let Some(client) = state.client.clone() else {
+            return;
+        };
+        let entries = std::mem::take(&mut state.entries);
+        if !entries.is_empty() {
+            handle.spawn(async move {
+                PortMappingManagerData::remove_entries(&client, entries).await;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex as StdMutex;
+
+    use crate::common::global_ctx::tests::get_mock_global_ctx;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct MockClient {
+        removed: StdMutex<Vec<(MappingProtocol, u16)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl PortMappingClient for MockClient {
+        fn method(&self) -> &'static str {
+            "mock"
+        }
+
+        async fn add_mapping(
+            &self,
+            _protocol: MappingProtocol,
+            internal_port: u16,
+            lifetime: Duration,
+        ) -> anyhow::Result<MappedPort> {
+            Ok(MappedPort {
+                external_addr: SocketAddr::new([203, 0, 113, 9].into(), internal_port + 1000),
+                lifetime,
+            })
+        }
+
+        async fn remove_mapping(
+            &self,
+            protocol: MappingProtocol,
+            internal_port: u16,
+            _external_port: u16,
+        ) -> anyhow::Result<()> {
+            self.removed.lock().unwrap().push((protocol, internal_port));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn listener_port_filter() {
+        let port = |s: &str| listener_internal_port(&s.parse().unwrap());
+        assert_eq!(
+            port("tcp://0.0.0.0:11010"),
+            Some((MappingProtocol::Tcp, 11010))
+        );
+        assert_eq!(
+            port("wg://192.168.1.2:11011"),
+            Some((MappingProtocol::Udp, 11011))
+        );
+        assert_eq!(port("ws://0.0.0.0/"), Some((MappingProtocol::Tcp, 80)));
+        assert_eq!(port("udp://0.0.0.0:0"), None);
+        assert_eq!(port("tcp://1.2.3.4:11010"), None);
+        assert_eq!(port("tcp://[::]:11010"), None);
+        assert_eq!(port("ring://0.0.0.0:11010"), None);
+
+        assert!(is_publishable(&"1.2.3.4".parse().unwrap()));
+        assert!(!is_publishable(&"100.64.3.4".parse().unwrap()));
+        assert!(!is_publishable(&"192.168.3.4".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn publish_and_remove_mappings() {
+        let global_ctx = get_mock_global_ctx();
+        let mut flags = global_ctx.get_flags();
+        flags.enable_port_mapping = true;
+        global_ctx.set_flags(flags);
+        global_ctx.add_running_listener("tcp://0.0.0.0:11010".parse().unwrap());
+        global_ctx.add_running_listener("udp://0.0.0.0:11010".parse().unwrap());
+        global_ctx.add_running_listener("tcp://8.8.8.8:11012".parse().unwrap());
+
+        let client = Arc::new(MockClient::default());
+        let state = PortMappingState {
+            client: Some(client.clone()),
+            entries: HashMap::new(),
+            last_discover: Some(Instant::now()),
+        };
+        let data = PortMappingManagerData {
+            global_ctx: global_ctx.clone(),
+            state: Mutex::new(state),
+        };
+
+        data.refresh().await;
+        let mut mapped = global_ctx.get_port_mapped_listeners();
+        mapped.sort();
+        assert_eq!(
+            mapped,
+            vec![
+                "tcp://203.0.113.9:12010".parse::<url::Url>().unwrap(),
+                "udp://203.0.113.9:12010".parse().unwrap(),
+            ]
+        );
+
+        let mut flags = global_ctx.get_flags();
+        flags.enable_port_mapping = false;
+        global_ctx.set_flags(flags);
+        data.refresh().await;
+        assert!(global_ctx.get_port_mapped_listeners().is_empty());
+
+        let mut removed = client.removed.lock().unwrap().clone();
+        removed.sort_by_key(|(p, _)| *p == MappingProtocol::Udp);
+        assert_eq!(
+            removed,
+            vec![(MappingProtocol::Tcp, 11010), (MappingProtocol::Udp, 11010)]
+        );
+    }
+}
@@ -0,0 +1,210 @@
+// nat-pmp client, see rfc 6886
+
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+use anyhow::Context;
+use tokio::net::UdpSocket;
+
+use crate::common::netns::NetNS;
+
+use super::{MappedPort, MappingProtocol, PortMappingClient};
+
+pub const NAT_PMP_PORT: u16 = 5351;
+
+const OP_EXTERNAL_ADDRESS: u8 = 0;
+const OP_MAP_UDP: u8 = 1;
+const OP_MAP_TCP: u8 = 2;
+
+#[derive(Debug)]
+pub struct NatPmpClient {
+    gateway: SocketAddr,
+    net_ns: NetNS,
+}
+
+impl NatPmpClient {
+    pub fn new(gateway: SocketAddr, net_ns: NetNS) -> Self {
+        Self { gateway, net_ns }
+    }
+
+    async fn request(&self, req: &[u8], resp_len: usize) -> anyhow::Result<Vec<u8>> {
+        let socket = {
+            let _g = self.net_ns.guard();
+            UdpSocket::bind("0.0.0.0:0").await?
+        };
+        socket.connect(self.gateway).await?;
+
+        // the rfc retransmits from 250ms and doubles the wait each time, we give up
+        // much earlier than it does since the router is on the lan.
+        let mut wait = Duration::from_millis(250);
+        let mut buf = [0u8; 64];
+        for _ in 0..4 {
+            socket.send(req).await?;
+            let len = match tokio::time::timeout(wait, socket.recv(&mut buf)).await {
+                Ok(ret) => ret.with_context(|| format!("nat-pmp request to {}", self.gateway))?,
+                Err(_) => {
+                    wait *= 2;
+                    continue;
+                }
+            };
+
+            if len < resp_len || buf[0] != 0 || buf[1] != (req[1] | 0x80) {
+                tracing::debug!(resp = ?&buf[..len], "ignore unexpected nat-pmp response");
+                continue;
+            }
+
+            let result_code = u16::from_be_bytes([buf[2], buf[3]]);
+            if result_code != 0 {
+                anyhow::bail!("nat-pmp request failed, result code: {}", result_code);
+            }
+            return Ok(buf[..len].to_vec());
+        }
+
+        anyhow::bail!("nat-pmp gateway {} not responding", self.gateway)
+    }
+
+    pub async fn external_ip(&self) -> anyhow::Result<Ipv4Addr> {
+        let resp = self.request(&[0, OP_EXTERNAL_ADDRESS], 12).await?;
+        Ok(Ipv4Addr::new(resp[8], resp[9], resp[10], resp[11]))
+    }
+
+    async fn map(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: u32,
+    ) -> anyhow::Result<(u16, u32)> {
+        let op = match protocol {
+            MappingProtocol::Udp => OP_MAP_UDP,
+            MappingProtocol::Tcp => OP_MAP_TCP,
+        };
+        let mut req = vec![0, op, 0, 0];
+        req.extend_from_slice(&internal_port.to_be_bytes());
+        req.extend_from_slice(&external_port.to_be_bytes());
+        req.extend_from_slice(&lifetime.to_be_bytes());
+
+        let resp = self.request(&req, 16).await?;
+        let mapped_port = u16::from_be_bytes([resp[10], resp[11]]);
+        let lifetime = u32::from_be_bytes([resp[12], resp[13], resp[14], resp[15]]);
+        Ok((mapped_port, lifetime))
+    }
+}
+
+#[async_trait::async_trait]
+impl PortMappingClient for NatPmpClient {
+    fn method(&self) -> &'static str {
+        "nat-pmp"
+    }
+
+    async fn add_mapping(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        lifetime: Duration,
+    ) -> anyhow::Result<MappedPort> {
+        let external_ip = self.external_ip().await?;
+        let (external_port, lifetime) = self
+            .map(
+                protocol,
+                internal_port,
+                internal_port,
+                lifetime.as_secs() as u32,
+            )
+            .await?;
+
+        Ok(MappedPort {
+            external_addr: SocketAddr::new(external_ip.into(), external_port),
+            lifetime: Duration::from_secs(lifetime as u64),
+        })
+    }
+
+    async fn remove_mapping(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        _external_port: u16,
+    ) -> anyhow::Result<()> {
+        // deletion has both the lifetime and the suggested external port set to 0
+        self.map(protocol, internal_port, 0, 0).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    // answers like a router with external ip 203.0.113.7, maps every internal port
+    // to internal port + 1000 and records the requests.
+    async fn run_fake_nat_pmp_server() -> (SocketAddr, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let requests_clone = requests.clone();
+
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            while let Ok((len, from)) = socket.recv_from(&mut buf).await {
+                let req = buf[..len].to_vec();
+                requests_clone.lock().unwrap().push(req.clone());
+
+                let mut resp = vec![0, req[1] | 0x80, 0, 0, 0, 0, 0, 42];
+                if req[1] == OP_EXTERNAL_ADDRESS {
+                    resp.extend_from_slice(&[203, 0, 113, 7]);
+                } else {
+                    let internal_port = u16::from_be_bytes([req[4], req[5]]);
+                    let lifetime = u32::from_be_bytes([req[8], req[9], req[10], req[11]]);
+                    resp.extend_from_slice(&internal_port.to_be_bytes());
+                    let external_port = if lifetime == 0 {
+                        0
+                    } else {
+                        internal_port + 1000
+                    };
+                    resp.extend_from_slice(&external_port.to_be_bytes());
+                    resp.extend_from_slice(&lifetime.to_be_bytes());
+                }
+                let _ = socket.send_to(&resp, from).await;
+            }
+        });
+
+        (addr, requests)
+    }
+
+    #[tokio::test]
+    async fn nat_pmp_map_and_remove() {
+        let (addr, requests) = run_fake_nat_pmp_server().await;
+        let client = NatPmpClient::new(addr, NetNS::new(None));
+
+        let mapped = client
+            .add_mapping(MappingProtocol::Tcp, 11010, Duration::from_secs(3600))
+            .await
+            .unwrap();
+        assert_eq!(mapped.external_addr, "203.0.113.7:12010".parse().unwrap());
+        assert_eq!(mapped.lifetime, Duration::from_secs(3600));
+
+        client
+            .remove_mapping(MappingProtocol::Tcp, 11010, 12010)
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[1][1], OP_MAP_TCP);
+        assert_eq!(&requests[2][4..], &[0x2b, 0x02, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn nat_pmp_no_gateway() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        drop(socket);
+
+        let client = NatPmpClient::new(addr, NetNS::new(None));
+        assert!(client.external_ip().await.is_err());
+    }
+}
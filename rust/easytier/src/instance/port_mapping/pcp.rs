@@ -0,0 +1,242 @@
+// pcp client, see rfc 6887. only the MAP opcode for ipv4 is used.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Mutex,
+    time::Duration,
+};
+
+use anyhow::Context;
+use tokio::net::UdpSocket;
+
+use crate::common::netns::NetNS;
+
+use super::{MappedPort, MappingProtocol, PortMappingClient};
+
+pub const PCP_PORT: u16 = 5351;
+
+const PCP_VERSION: u8 = 2;
+const OPCODE_ANNOUNCE: u8 = 0;
+const OPCODE_MAP: u8 = 1;
+const HEADER_LEN: usize = 24;
+const MAP_LEN: usize = 36;
+
+#[derive(Debug)]
+pub struct PcpClient {
+    gateway: SocketAddr,
+    net_ns: NetNS,
+    // a mapping can only be renewed or deleted with the nonce that created it
+    nonces: Mutex<HashMap<(MappingProtocol, u16), [u8; 12]>>,
+}
+
+fn protocol_number(protocol: MappingProtocol) -> u8 {
+    match protocol {
+        MappingProtocol::Tcp => 6,
+        MappingProtocol::Udp => 17,
+    }
+}
+
+impl PcpClient {
+    pub fn new(gateway: SocketAddr, net_ns: NetNS) -> Self {
+        Self {
+            gateway,
+            net_ns,
+            nonces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // the request header carries the client ip, which is only known after the
+    // socket is connected. returns the granted lifetime and the opcode payload.
+    async fn request(
+        &self,
+        opcode: u8,
+        lifetime: u32,
+        payload: &[u8],
+    ) -> anyhow::Result<(u32, Vec<u8>)> {
+        let socket = {
+            let _g = self.net_ns.guard();
+            UdpSocket::bind("0.0.0.0:0").await?
+        };
+        socket.connect(self.gateway).await?;
+        let IpAddr::V4(client_ip) = socket.local_addr()?.ip() else {
+            anyhow::bail!("pcp client ip is not ipv4");
+        };
+
+        let mut req = vec![PCP_VERSION, opcode, 0, 0];
+        req.extend_from_slice(&lifetime.to_be_bytes());
+        req.extend_from_slice(&client_ip.to_ipv6_mapped().octets());
+        req.extend_from_slice(payload);
+
+        let mut wait = Duration::from_millis(250);
+        let mut buf = [0u8; 1100];
+        for _ in 0..4 {
+            socket.send(&req).await?;
+            let len = match tokio::time::timeout(wait, socket.recv(&mut buf)).await {
+                Ok(ret) => ret.with_context(|| format!("pcp request to {}", self.gateway))?,
+                Err(_) => {
+                    wait *= 2;
+                    continue;
+                }
+            };
+
+            // a nat-pmp only server answers with version 0
+            if len < HEADER_LEN + payload.len()
+                || buf[0] != PCP_VERSION
+                || buf[1] != (opcode | 0x80)
+            {
+                anyhow::bail!("unexpected pcp response: {:?}", &buf[..len.min(4)]);
+            }
+
+            let result_code = buf[3];
+            if result_code != 0 {
+                anyhow::bail!("pcp request failed, result code: {}", result_code);
+            }
+            let lifetime = u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]);
+            return Ok((lifetime, buf[HEADER_LEN..len].to_vec()));
+        }
+
+        anyhow::bail!("pcp server {} not responding", self.gateway)
+    }
+
+    pub async fn announce(&self) -> anyhow::Result<()> {
+        self.request(OPCODE_ANNOUNCE, 0, &[]).await?;
+        Ok(())
+    }
+
+    async fn map(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        lifetime: u32,
+    ) -> anyhow::Result<(SocketAddr, u32)> {
+        let nonce = *self
+            .nonces
+            .lock()
+            .unwrap()
+            .entry((protocol, internal_port))
+            .or_insert_with(rand::random);
+
+        let mut payload = Vec::with_capacity(MAP_LEN);
+        payload.extend_from_slice(&nonce);
+        payload.extend_from_slice(&[protocol_number(protocol), 0, 0, 0]);
+        payload.extend_from_slice(&internal_port.to_be_bytes());
+        payload.extend_from_slice(&internal_port.to_be_bytes());
+        payload.extend_from_slice(&Ipv4Addr::UNSPECIFIED.to_ipv6_mapped().octets());
+
+        let (lifetime, resp) = self.request(OPCODE_MAP, lifetime, &payload).await?;
+        if resp[..12] != nonce {
+            anyhow::bail!("pcp map response nonce mismatch");
+        }
+
+        let external_port = u16::from_be_bytes([resp[18], resp[19]]);
+        let external_ip = Ipv6Addr::from(<[u8; 16]>::try_from(&resp[20..36]).unwrap());
+        let external_ip = external_ip
+            .to_ipv4_mapped()
+            .map(IpAddr::V4)
+            .unwrap_or(IpAddr::V6(external_ip));
+        Ok((SocketAddr::new(external_ip, external_port), lifetime))
+    }
+}
+
+#[async_trait::async_trait]
+impl PortMappingClient for PcpClient {
+    fn method(&self) -> &'static str {
+        "pcp"
+    }
+
+    async fn add_mapping(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        lifetime: Duration,
+    ) -> anyhow::Result<MappedPort> {
+        let (external_addr, lifetime) = self
+            .map(protocol, internal_port, lifetime.as_secs() as u32)
+            .await?;
+        Ok(MappedPort {
+            external_addr,
+            lifetime: Duration::from_secs(lifetime as u64),
+        })
+    }
+
+    async fn remove_mapping(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        _external_port: u16,
+    ) -> anyhow::Result<()> {
+        self.map(protocol, internal_port, 0).await?;
+        self.nonces
+            .lock()
+            .unwrap()
+            .remove(&(protocol, internal_port));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    // maps every internal port to internal port + 1000 on 203.0.113.8 and records
+    // the requests.
+    async fn run_fake_pcp_server() -> (SocketAddr, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let requests_clone = requests.clone();
+
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1100];
+            while let Ok((len, from)) = socket.recv_from(&mut buf).await {
+                let req = buf[..len].to_vec();
+                requests_clone.lock().unwrap().push(req.clone());
+
+                let mut resp = vec![PCP_VERSION, req[1] | 0x80, 0, 0];
+                resp.extend_from_slice(&req[4..8]);
+                resp.extend_from_slice(&[0u8; 16]);
+                if req[1] == OPCODE_MAP {
+                    let internal_port = u16::from_be_bytes([req[40], req[41]]);
+                    resp.extend_from_slice(&req[24..42]);
+                    resp.extend_from_slice(&(internal_port + 1000).to_be_bytes());
+                    resp.extend_from_slice(
+                        &Ipv4Addr::new(203, 0, 113, 8).to_ipv6_mapped().octets(),
+                    );
+                }
+                let _ = socket.send_to(&resp, from).await;
+            }
+        });
+
+        (addr, requests)
+    }
+
+    #[tokio::test]
+    async fn pcp_map_and_remove() {
+        let (addr, requests) = run_fake_pcp_server().await;
+        let client = PcpClient::new(addr, NetNS::new(None));
+        client.announce().await.unwrap();
+
+        let mapped = client
+            .add_mapping(MappingProtocol::Udp, 11010, Duration::from_secs(7200))
+            .await
+            .unwrap();
+        assert_eq!(mapped.external_addr, "203.0.113.8:12010".parse().unwrap());
+        assert_eq!(mapped.lifetime, Duration::from_secs(7200));
+
+        client
+            .remove_mapping(MappingProtocol::Udp, 11010, 12010)
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[1].len(), HEADER_LEN + MAP_LEN);
+        assert_eq!(requests[1][36], 17);
+        // the deletion reuses the nonce of the mapping and asks for lifetime 0
+        assert_eq!(requests[1][24..36], requests[2][24..36]);
+        assert_eq!(&requests[2][4..8], &[0, 0, 0, 0]);
+    }
+}
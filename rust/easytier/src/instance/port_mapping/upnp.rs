@@ -0,0 +1,543 @@
+// upnp igd client. only the few soap actions needed for port mapping are
+// implemented, with a tiny http client so no http stack is pulled in.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4},
+    sync::Mutex,
+    time::Duration,
+};
+
+use anyhow::Context;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+};
+
+use crate::common::netns::NetNS;
+
+use super::{MappedPort, MappingProtocol, PortMappingClient};
+
+pub const SSDP_MULTICAST_ADDR: SocketAddr =
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900));
+
+const SEARCH_TARGET: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+const SERVICE_TYPES: [&str; 3] = [
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+const ERR_CONFLICT_IN_MAPPING_ENTRY: u16 = 718;
+const ERR_ONLY_PERMANENT_LEASES_SUPPORTED: u16 = 725;
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, thiserror::Error)]
+#[error("upnp error {code}: {description}")]
+struct UpnpError {
+    code: u16,
+    description: String,
+}
+
+// returns the text of the first <tag>...</tag> in xml
+fn xml_tag<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    Some(xml[start..end].trim())
+}
+
+fn decode_chunked(mut body: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut ret = Vec::new();
+    loop {
+        let line_end = body
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .context("bad chunk size line")?;
+        let size_str = std::str::from_utf8(&body[..line_end])?;
+        let size = usize::from_str_radix(size_str.split(';').next().unwrap().trim(), 16)?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Ok(ret);
+        }
+        if body.len() < size {
+            anyhow::bail!("chunk truncated");
+        }
+        ret.extend_from_slice(&body[..size]);
+        body = body.get(size + 2..).unwrap_or_default();
+    }
+}
+
+struct HttpResponse {
+    status: u16,
+    body: String,
+    local_ip: IpAddr,
+}
+
+async fn http_request(
+    net_ns: &NetNS,
+    url: &url::Url,
+    method: &str,
+    headers: &[(&str, String)],
+    body: &str,
+) -> anyhow::Result<HttpResponse> {
+    let host = url.host_str().context("url has no host")?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let mut stream = {
+        let _g = net_ns.guard();
+        tokio::time::timeout(HTTP_TIMEOUT, TcpStream::connect((host, port))).await??
+    };
+    let local_ip = stream.local_addr()?.ip();
+
+    let mut req = format!(
+        "{} {} HTTP/1.1\r\nHost: {}:{}\r\nConnection: close\r\nContent-Length: {}\r\n",
+        method,
+        &url[url::Position::BeforePath..],
+        host,
+        port,
+        body.len()
+    );
+    for (k, v) in headers {
+        req.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    req.push_str("\r\n");
+    req.push_str(body);
+
+    let mut resp = Vec::new();
+    tokio::time::timeout(HTTP_TIMEOUT, async {
+        stream.write_all(req.as_bytes()).await?;
+        stream.read_to_end(&mut resp).await
+    })
+    .await??;
+
+    let header_end = resp
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .context("http response without header end")?;
+    let header = std::str::from_utf8(&resp[..header_end])?;
+    let mut lines = header.lines();
+    let status = lines
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|s| s.parse().ok())
+        .context("bad http status line")?;
+    let chunked = lines.any(|l| {
+        let l = l.to_ascii_lowercase();
+        l.starts_with("transfer-encoding:") && l.contains("chunked")
+    });
+
+    let body = &resp[header_end + 4..];
+    let body = if chunked {
+        decode_chunked(body)?
+    } else {
+        body.to_vec()
+    };
+
+    Ok(HttpResponse {
+        status,
+        body: String::from_utf8_lossy(&body).into_owned(),
+        local_ip,
+    })
+}
+
+#[derive(Debug)]
+pub struct UpnpClient {
+    control_url: url::Url,
+    service_type: &'static str,
+    local_ip: IpAddr,
+    net_ns: NetNS,
+    // external ports assigned when the internal one was taken, tried first on renewal
+    external_ports: Mutex<HashMap<(MappingProtocol, u16), u16>>,
+}
+
+impl UpnpClient {
+    pub async fn discover(net_ns: &NetNS, ssdp_addr: SocketAddr) -> anyhow::Result<Self> {
+        let socket = {
+            let _g = net_ns.guard();
+            UdpSocket::bind("0.0.0.0:0").await?
+        };
+        let search = format!(
+            "M-SEARCH * HTTP/1.1\r\nHOST: {}\r\nST: {}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\n\r\n",
+            SSDP_MULTICAST_ADDR, SEARCH_TARGET
+        );
+        socket.send_to(search.as_bytes(), ssdp_addr).await?;
+
+        let mut buf = [0u8; 1500];
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(3);
+        while let Ok(ret) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+            let (len, from) = ret?;
+            let resp = String::from_utf8_lossy(&buf[..len]);
+            let Some(location) = resp.lines().find_map(|l| {
+                let (k, v) = l.split_once(':')?;
+                k.eq_ignore_ascii_case("location").then_some(v.trim())
+            }) else {
+                continue;
+            };
+
+            let Ok(location) = location.parse() else {
+                continue;
+            };
+            match Self::from_location(net_ns, &location).await {
+                Ok(client) => return Ok(client),
+                Err(e) => tracing::debug!(?e, ?from, %location, "not a usable igd"),
+            }
+        }
+
+        anyhow::bail!("no upnp igd found")
+    }
+
+    pub async fn from_location(net_ns: &NetNS, location: &url::Url) -> anyhow::Result<Self> {
+        let resp = http_request(net_ns, location, "GET", &[], "").await?;
+        if resp.status != 200 {
+            anyhow::bail!("get igd description failed, status: {}", resp.status);
+        }
+
+        // the description nests services of all devices, look for a wan
+        // connection service in any of them
+        for service in resp.body.split("<service>").skip(1) {
+            let Some(service_type) = xml_tag(service, "serviceType")
+                .and_then(|t| SERVICE_TYPES.into_iter().find(|s| *s == t))
+            else {
+                continue;
+            };
+            let Some(control_url) = xml_tag(service, "controlURL") else {
+                continue;
+            };
+
+            return Ok(Self {
+                control_url: location.join(control_url)?,
+                service_type,
+                local_ip: resp.local_ip,
+                net_ns: net_ns.clone(),
+                external_ports: Mutex::new(HashMap::new()),
+            });
+        }
+
+        anyhow::bail!("no wan connection service in igd description")
+    }
+
+    async fn soap_call(&self, action: &str, args: &[(&str, String)]) -> anyhow::Result<String> {
+        let args = args
+            .iter()
+            .map(|(k, v)| format!("<{}>{}</{}>", k, v, k))
+            .collect::<String>();
+        let body = format!(
+            "<?xml version=\"1.0\"?>\r\n\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+             s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+             <s:Body><u:{} xmlns:u=\"{}\">{}</u:{}></s:Body></s:Envelope>",
+            action, self.service_type, args, action
+        );
+        let headers = [
+            ("Content-Type", "text/xml; charset=\"utf-8\"".to_owned()),
+            (
+                "SOAPAction",
+                format!("\"{}#{}\"", self.service_type, action),
+            ),
+        ];
+
+        let resp = http_request(&self.net_ns, &self.control_url, "POST", &headers, &body).await?;
+        if resp.status == 200 {
+            return Ok(resp.body);
+        }
+
+        match xml_tag(&resp.body, "errorCode").and_then(|c| c.parse().ok()) {
+            Some(code) => Err(UpnpError {
+                code,
+                description: xml_tag(&resp.body, "errorDescription")
+                    .unwrap_or_default()
+                    .to_owned(),
+            }
+            .into()),
+            None => anyhow::bail!("upnp {} failed, status: {}", action, resp.status),
+        }
+    }
+
+    pub async fn external_ip(&self) -> anyhow::Result<IpAddr> {
+        let resp = self.soap_call("GetExternalIPAddress", &[]).await?;
+        Ok(xml_tag(&resp, "NewExternalIPAddress")
+            .context("no external ip in response")?
+            .parse()?)
+    }
+
+    async fn add_port_mapping(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lease: u32,
+    ) -> anyhow::Result<()> {
+        let protocol = match protocol {
+            MappingProtocol::Tcp => "TCP",
+            MappingProtocol::Udp => "UDP",
+        };
+        self.soap_call(
+            "AddPortMapping",
+            &[
+                ("NewRemoteHost", String::new()),
+                ("NewExternalPort", external_port.to_string()),
+                ("NewProtocol", protocol.to_owned()),
+                ("NewInternalPort", internal_port.to_string()),
+                ("NewInternalClient", self.local_ip.to_string()),
+                ("NewEnabled", "1".to_owned()),
+                ("NewPortMappingDescription", "easytier".to_owned()),
+                ("NewLeaseDuration", lease.to_string()),
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+fn upnp_error_code(e: &anyhow::Error) -> Option<u16> {
+    e.downcast_ref::<UpnpError>().map(|e| e.code)
+}
+
+#[async_trait::async_trait]
+impl PortMappingClient for UpnpClient {
+    fn method(&self) -> &'static str {
+        "upnp"
+    }
+
+    async fn add_mapping(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        lifetime: Duration,
+    ) -> anyhow::Result<MappedPort> {
+        let external_ip = self.external_ip().await?;
+
+        let key = (protocol, internal_port);
+        let mut external_port = self
+            .external_ports
+            .lock()
+            .unwrap()
+            .get(&key)
+            .copied()
+            .unwrap_or(internal_port);
+        let mut lease = lifetime.as_secs() as u32;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let Err(e) = self
+                .add_port_mapping(protocol, internal_port, external_port, lease)
+                .await
+            else {
+                break;
+            };
+
+            match upnp_error_code(&e) {
+                // old igds only take permanent mappings, which we still renew
+                // and remove ourselves
+                Some(ERR_ONLY_PERMANENT_LEASES_SUPPORTED) if lease != 0 => lease = 0,
+                Some(ERR_CONFLICT_IN_MAPPING_ENTRY) if attempts < 4 => {
+                    external_port = rand::random::<u16>().max(1024)
+                }
+                _ => return Err(e),
+            }
+        }
+
+        self.external_ports
+            .lock()
+            .unwrap()
+            .insert(key, external_port);
+        Ok(MappedPort {
+            external_addr: SocketAddr::new(external_ip, external_port),
+            lifetime,
+        })
+    }
+
+    async fn remove_mapping(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+    ) -> anyhow::Result<()> {
+        self.external_ports
+            .lock()
+            .unwrap()
+            .remove(&(protocol, internal_port));
+        let protocol = match protocol {
+            MappingProtocol::Tcp => "TCP",
+            MappingProtocol::Udp => "UDP",
+        };
+        self.soap_call(
+            "DeletePortMapping",
+            &[
+                ("NewRemoteHost", String::new()),
+                ("NewExternalPort", external_port.to_string()),
+                ("NewProtocol", protocol.to_owned()),
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    const DESCRIPTION: &str = "<?xml version=\"1.0\"?><root><device>\
+        <serviceList><service><serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>\
+        <controlURL>/l3f</controlURL></service></serviceList><deviceList><device><serviceList>\
+        <service><serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>\
+        <controlURL>/ctl/IPConn</controlURL></service></serviceList></device></deviceList>\
+        </device></root>";
+
+    async fn read_http_request(stream: &mut TcpStream) -> String {
+        let mut req = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let len = stream.read(&mut buf).await.unwrap();
+            req.extend_from_slice(&buf[..len]);
+            let s = String::from_utf8_lossy(&req);
+            if len == 0 {
+                return s.into_owned();
+            }
+            if let Some((header, body)) = s.split_once("\r\n\r\n") {
+                let content_len = header
+                    .lines()
+                    .find_map(|l| l.strip_prefix("Content-Length: "))
+                    .map(|l| l.parse::<usize>().unwrap())
+                    .unwrap_or(0);
+                if body.len() >= content_len {
+                    return s.into_owned();
+                }
+            }
+        }
+    }
+
+    // an igd with external ip 203.0.113.10 which only supports permanent leases,
+    // the soap actions received are recorded.
+    async fn run_fake_igd() -> (url::Url, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let actions = Arc::new(Mutex::new(Vec::new()));
+        let actions_clone = actions.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let req = read_http_request(&mut stream).await;
+                let resp = if req.starts_with("GET /desc.xml ") {
+                    // served chunked like many routers do
+                    let (a, b) = DESCRIPTION.split_at(100);
+                    format!(
+                        "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{}\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+                        a.len(), a, b.len(), b
+                    )
+                } else {
+                    assert!(req.starts_with("POST /ctl/IPConn "));
+                    let action = req
+                        .lines()
+                        .find_map(|l| l.strip_prefix("SOAPAction: "))
+                        .and_then(|a| a.trim_matches('"').split_once('#'))
+                        .unwrap()
+                        .1
+                        .to_owned();
+                    actions_clone.lock().unwrap().push(action.clone());
+
+                    let (status, body) = match action.as_str() {
+                        "GetExternalIPAddress" => (
+                            "200 OK",
+                            "<NewExternalIPAddress>203.0.113.10</NewExternalIPAddress>",
+                        ),
+                        "AddPortMapping"
+                            if !req.contains("<NewLeaseDuration>0</NewLeaseDuration>") =>
+                        {
+                            (
+                                "500 Internal Server Error",
+                                "<UPnPError><errorCode>725</errorCode>\
+                                 <errorDescription>OnlyPermanentLeasesSupported</errorDescription>\
+                                 </UPnPError>",
+                            )
+                        }
+                        _ => ("200 OK", ""),
+                    };
+                    format!(
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    )
+                };
+                let _ = stream.write_all(resp.as_bytes()).await;
+            }
+        });
+
+        (
+            format!("http://{}/desc.xml", addr).parse().unwrap(),
+            actions,
+        )
+    }
+
+    async fn run_fake_ssdp_responder(location: url::Url) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            while let Ok((len, from)) = socket.recv_from(&mut buf).await {
+                let req = String::from_utf8_lossy(&buf[..len]);
+                assert!(req.starts_with("M-SEARCH * HTTP/1.1\r\n"));
+                assert!(req.contains(SEARCH_TARGET));
+                let resp = format!(
+                    "HTTP/1.1 200 OK\r\nST: {}\r\nLOCATION: {}\r\n\r\n",
+                    SEARCH_TARGET, location
+                );
+                let _ = socket.send_to(resp.as_bytes(), from).await;
+            }
+        });
+        addr
+    }
+
+    #[test]
+    fn parse_xml_and_chunked() {
+        assert_eq!(xml_tag("<a><b> x </b></a>", "b"), Some("x"));
+        assert_eq!(xml_tag("<a><b>x</a>", "b"), None);
+        assert_eq!(
+            decode_chunked(b"3\r\nabc\r\n2;ext=1\r\nde\r\n0\r\n\r\n").unwrap(),
+            b"abcde"
+        );
+        assert!(decode_chunked(b"5\r\nab").is_err());
+    }
+
+    #[tokio::test]
+    async fn upnp_discover_map_and_remove() {
+        let (location, actions) = run_fake_igd().await;
+        let ssdp_addr = run_fake_ssdp_responder(location).await;
+
+        let client = UpnpClient::discover(&NetNS::new(None), ssdp_addr)
+            .await
+            .unwrap();
+        assert_eq!(
+            client.service_type,
+            "urn:schemas-upnp-org:service:WANIPConnection:1"
+        );
+        assert_eq!(client.control_url.path(), "/ctl/IPConn");
+
+        let mapped = client
+            .add_mapping(MappingProtocol::Tcp, 11010, Duration::from_secs(3600))
+            .await
+            .unwrap();
+        assert_eq!(mapped.external_addr, "203.0.113.10:11010".parse().unwrap());
+
+        client
+            .remove_mapping(MappingProtocol::Tcp, 11010, 11010)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *actions.lock().unwrap(),
+            vec![
+                "GetExternalIPAddress",
+                "AddPortMapping",
+                "AddPortMapping",
+                "DeletePortMapping"
+            ]
+        );
+    }
+}
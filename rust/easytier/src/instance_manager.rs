@@ -150,6 +150,18 @@ impl NetworkInstanceManager {
             .map(|instance| instance.value().get_inst_name())
     }
 
+    pub fn get_rpc_portal(&self, instance_id: &uuid::Uuid) -> Option<std::net::SocketAddr> {
+        self.instance_map
+            .get(instance_id)
+            .and_then(|instance| instance.value().get_rpc_portal())
+    }
+
+    pub fn subscribe_event(&self, instance_id: &uuid::Uuid) -> Option<EventBusSubscriber> {
+        self.instance_map
+            .get(instance_id)
+            .and_then(|instance| instance.value().subscribe_event())
+    }
+
     pub fn set_tun_fd(&self, instance_id: &uuid::Uuid, fd: i32) -> Result<(), anyhow::Error> {
         let mut instance = self
             .instance_map
@@ -319,6 +331,27 @@ fn handle_event(
                             ),
                         );
                     }
+
+                    GlobalCtxEvent::NetworkSecretRotated(rotation_id) => {
+                        print_event(
+                            instance_id,
+                            format!("network secret rotated. rotation id: {}", rotation_id),
+                        );
+                    }
+
+                    GlobalCtxEvent::ExitNodeChanged(old, new) => {
+                        print_event(
+                            instance_id,
+                            format!("exit node changed. old: {:?}, new: {:?}", old, new),
+                        );
+                    }
+
+                    GlobalCtxEvent::ExitNodeDown(addr, error) => {
+                        print_event(
+                            instance_id,
+                            format!("exit node down. addr: {}, error: {}", addr, error),
+                        );
+                    }
                 }
             } else {
                 events = events.resubscribe();
@@ -177,6 +177,8 @@ impl EasyTierLauncher {
 
                     let node_info = MyNodeInfo {
                         virtual_ipv4: global_ctx_c.get_ipv4().map(|ip| ip.into()),
+                        virtual_ipv6: global_ctx_c.get_ipv6().map(|ip| ip.into()),
+                        peer_id: peer_mgr_c.my_peer_id(),
                         hostname: global_ctx_c.get_hostname(),
                         version: EASYTIER_VERSION.to_string(),
                         ips: Some(global_ctx_c.get_ip_collector().collect_ip_addrs().await),
@@ -186,13 +188,16 @@ impl EasyTierLauncher {
                             .into_iter()
                             .map(Into::into)
                             .collect(),
-                        vpn_portal_cfg: Some(
-                            vpn_portal
-                                .lock()
-                                .await
-                                .dump_client_config(peer_mgr_c.clone())
-                                .await,
-                        ),
+                        vpn_portal_cfg: match vpn_portal.as_ref() {
+                            Some(vpn_portal) => Some(
+                                vpn_portal
+                                    .lock()
+                                    .await
+                                    .dump_client_config(peer_mgr_c.clone())
+                                    .await,
+                            ),
+                            None => None,
+                        },
                     };
                     *data_c.my_node_info.write().unwrap() = node_info.clone();
                     *data_c.routes.write().unwrap() = peer_mgr_c.list_routes().await;
@@ -220,21 +225,6 @@ impl EasyTierLauncher {
         Ok(())
     }
 
-    fn select_proper_rpc_port(cfg: &TomlConfigLoader) {
-        let Some(mut f) = cfg.get_rpc_portal() else {
-            return;
-        };
-
-        if f.port() == 0 {
-            let Some(port) = crate::utils::find_free_tcp_port(15888..15900) else {
-                tracing::warn!("No free port found for RPC portal, skipping setting RPC portal");
-                return;
-            };
-            f.set_port(port);
-            cfg.set_rpc_portal(f);
-        }
-    }
-
     pub fn start<F>(&mut self, cfg_generator: F)
     where
         F: FnOnce() -> Result<TomlConfigLoader, anyhow::Error> + Send + Sync,
@@ -250,8 +240,6 @@ impl EasyTierLauncher {
 
         self.running_cfg = cfg.dump();
 
-        Self::select_proper_rpc_port(&cfg);
-
         let stop_flag = self.stop_flag.clone();
 
         let instance_alive = self.instance_alive.clone();
@@ -418,6 +406,10 @@ impl NetworkInstance {
         self.config.get_inst_name()
     }
 
+    pub fn get_rpc_portal(&self) -> Option<SocketAddr> {
+        self.config.get_rpc_portal()
+    }
+
     pub fn set_tun_fd(&mut self, tun_fd: i32) {
         if let Some(launcher) = self.launcher.as_ref() {
             launcher.data.tun_fd.write().unwrap().replace(tun_fd);
@@ -630,17 +622,22 @@ impl NetworkConfig {
                 client_cidr: cidr
                     .parse()
                     .with_context(|| format!("failed to parse vpn portal client cidr: {}", cidr))?,
-                wireguard_listen: format!(
-                    "0.0.0.0:{}",
-                    self.vpn_portal_listen_port.unwrap_or_default()
-                )
-                .parse()
-                .with_context(|| {
+                wireguard_listen: Some(
                     format!(
-                        "failed to parse vpn portal wireguard listen port. {:?}",
-                        self.vpn_portal_listen_port
+                        "0.0.0.0:{}",
+                        self.vpn_portal_listen_port.unwrap_or_default()
                     )
-                })?,
+                    .parse()
+                    .with_context(|| {
+                        format!(
+                            "failed to parse vpn portal wireguard listen port. {:?}",
+                            self.vpn_portal_listen_port
+                        )
+                    })?,
+                ),
+                openvpn: None,
+                shadowsocks: None,
+                wireguard_clients: vec![],
             });
         }
 
@@ -877,7 +874,9 @@ impl NetworkConfig {
             result.vpn_portal_client_network_addr = Some(cidr.first_address().to_string());
             result.vpn_portal_client_network_len = Some(cidr.network_length() as i32);
 
-            result.vpn_portal_listen_port = Some(vpn_config.wireguard_listen.port() as i32);
+            result.vpn_portal_listen_port = vpn_config
+                .wireguard_listen
+                .map(|listen| listen.port() as i32);
         }
 
         if let Some(routes) = config.get_routes() {
@@ -1093,7 +1092,10 @@ mod tests {
                 let vpn_port = rng.gen_range(10000..60000);
                 config.set_vpn_portal_config(crate::common::config::VpnPortalConfig {
                     client_cidr: vpn_network.parse().unwrap(),
-                    wireguard_listen: format!("0.0.0.0:{}", vpn_port).parse().unwrap(),
+                    wireguard_listen: Some(format!("0.0.0.0:{}", vpn_port).parse().unwrap()),
+                    openvpn: None,
+                    shadowsocks: None,
+                    wireguard_clients: vec![],
                 });
             }
 
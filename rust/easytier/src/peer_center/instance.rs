@@ -365,21 +365,30 @@ impl PeerCenterInstance {
         }
 
         impl RouteCostCalculatorImpl {
-            fn directed_cost(&self, src: PeerId, dst: PeerId) -> Option<i32> {
+            fn directed_info(&self, src: PeerId, dst: PeerId) -> Option<&DirectConnectedPeerInfo> {
                 self.global_peer_map_clone
                     .map
                     .get(&src)
                     .and_then(|src_peer_info| src_peer_info.direct_peers.get(&dst))
-                    .map(|info| info.latency_ms)
+            }
+
+            fn link_info(&self, src: PeerId, dst: PeerId) -> Option<&DirectConnectedPeerInfo> {
+                self.directed_info(src, dst)
+                    .or_else(|| self.directed_info(dst, src))
             }
         }
 
         impl RouteCostCalculatorInterface for RouteCostCalculatorImpl {
             fn calculate_cost(&self, src: PeerId, dst: PeerId) -> i32 {
-                if let Some(cost) = self.directed_cost(src, dst) {
-                    return cost;
-                }
-                self.directed_cost(dst, src).unwrap_or(500)
+                self.link_info(src, dst)
+                    .map(|info| info.latency_ms)
+                    .unwrap_or(500)
+            }
+
+            fn calculate_loss_percent(&self, src: PeerId, dst: PeerId) -> u32 {
+                self.link_info(src, dst)
+                    .map(|info| info.loss_percent)
+                    .unwrap_or(0)
             }
 
             fn begin_update(&mut self) {
@@ -454,10 +463,17 @@ impl PeerCenterPeerManagerTrait for PeerMapWithPeerRpcManager {
                     continue;
                 };
 
+                let min_loss = conns
+                    .iter()
+                    .map(|conn| (conn.loss_rate * 100.0).round() as u32)
+                    .min()
+                    .unwrap_or(0);
+
                 ret.direct_peers.insert(
                     peer,
                     DirectConnectedPeerInfo {
                         latency_ms: std::cmp::max(1, (min_lat as u32 / 1000) as i32),
+                        loss_percent: min_loss,
                     },
                 );
             }
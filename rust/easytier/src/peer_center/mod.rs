@@ -38,8 +38,16 @@ impl From<Vec<PeerInfo>> for PeerInfoForGlobalMap {
                 continue;
             };
 
+            let min_loss = peer
+                .conns
+                .iter()
+                .map(|conn| (conn.loss_rate * 100.0).round() as u32)
+                .min()
+                .unwrap_or(0);
+
             let dp_info = DirectConnectedPeerInfo {
                 latency_ms: std::cmp::max(1, (min_lat as u32 / 1000) as i32),
+                loss_percent: min_loss,
             };
 
             // sort conn info so hash result is stable
@@ -0,0 +1,330 @@
+use std::{
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use arc_swap::ArcSwap;
+use cidr::IpCidr;
+use pnet::packet::{ipv4::Ipv4Packet, ipv6::Ipv6Packet};
+
+use crate::{
+    common::{config::BandwidthLimitConfig, token_bucket::TokenBucket, PeerId},
+    proto::common::LimiterConfig,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficDirection {
+    Upload,
+    Download,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LimitTarget {
+    Node,
+    Peer(PeerId),
+    Cidr(IpCidr),
+}
+
+impl LimitTarget {
+    fn matches(&self, peer_id: PeerId, ip: Option<IpAddr>) -> bool {
+        match self {
+            LimitTarget::Node => true,
+            LimitTarget::Peer(p) => *p == peer_id,
+            LimitTarget::Cidr(cidr) => ip.is_some_and(|ip| cidr.contains(&ip)),
+        }
+    }
+}
+
+struct LimitRule {
+    target: LimitTarget,
+    upload: Option<Arc<TokenBucket>>,
+    download: Option<Arc<TokenBucket>>,
+}
+
+impl LimitRule {
+    fn new(cfg: &BandwidthLimitConfig) -> Self {
+        let target = match (cfg.peer_id, cfg.cidr) {
+            (Some(peer_id), _) => LimitTarget::Peer(peer_id),
+            (None, Some(cidr)) => LimitTarget::Cidr(cidr),
+            (None, None) => LimitTarget::Node,
+        };
+        let new_bucket = |bps: Option<u64>| {
+            bps.filter(|bps| *bps > 0).map(|bps| {
+                TokenBucket::new_from_cfg(
+                    LimiterConfig {
+                        burst_rate: None,
+                        bps: Some(bps),
+                        fill_duration_ms: None,
+                    }
+                    .into(),
+                )
+            })
+        };
+        LimitRule {
+            target,
+            upload: new_bucket(cfg.upload_bps),
+            download: new_bucket(cfg.download_bps),
+        }
+    }
+
+    fn bucket(&self, direction: TrafficDirection) -> Option<&Arc<TokenBucket>> {
+        match direction {
+            TrafficDirection::Upload => self.upload.as_ref(),
+            TrafficDirection::Download => self.download.as_ref(),
+        }
+    }
+}
+
+/// Take `len` tokens from every bucket or from none of them, so a packet dropped by one
+/// limit does not use up the budget of the others.
+fn try_consume_all<'a>(buckets: impl IntoIterator<Item = &'a Arc<TokenBucket>>, len: u64) -> bool {
+    let mut consumed: Vec<&Arc<TokenBucket>> = vec![];
+    for bucket in buckets {
+        if !bucket.try_consume(len) {
+            consumed.iter().for_each(|b| b.refund(len));
+            return false;
+        }
+        consumed.push(bucket);
+    }
+    true
+}
+
+/// Shapes data traffic of the local node, per destination peer and per proxied cidr.
+/// Packets that would exceed any matching limit are dropped, like the foreign relay limiter.
+pub struct BandwidthLimiter {
+    rules: ArcSwap<Vec<LimitRule>>,
+    enabled: AtomicBool,
+}
+
+impl Default for BandwidthLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BandwidthLimiter {
+    pub fn new() -> Self {
+        Self {
+            rules: ArcSwap::from_pointee(Vec::new()),
+            enabled: AtomicBool::new(false),
+        }
+    }
+
+    /// Replace all limits, token buckets start full again.
+    /// Must be called inside a tokio runtime because buckets refill in background tasks.
+    pub fn reload_limits(&self, limits: &[BandwidthLimitConfig]) {
+        let rules = limits
+            .iter()
+            .map(LimitRule::new)
+            .filter(|r| r.upload.is_some() || r.download.is_some())
+            .collect::<Vec<_>>();
+        let enabled = !rules.is_empty();
+        self.rules.store(Arc::new(rules));
+        self.enabled.store(enabled, Ordering::Relaxed);
+        tracing::info!(?limits, "bandwidth limits reloaded");
+    }
+
+    /// Return false if the packet exceeds a limit and should be dropped.
+    /// `ip` is the destination ip for uploads and the source ip for downloads.
+    pub fn try_consume(
+        &self,
+        direction: TrafficDirection,
+        peer_id: PeerId,
+        ip: Option<IpAddr>,
+        len: u64,
+    ) -> bool {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let rules = self.rules.load();
+        try_consume_all(
+            rules
+                .iter()
+                .filter(|r| r.target.matches(peer_id, ip))
+                .filter_map(|r| r.bucket(direction)),
+            len,
+        )
+    }
+
+    /// Like `try_consume` for one packet sent to several peers. Node and cidr limits are
+    /// charged once for the packet, peer limits once per destination. Destinations over
+    /// their peer limit are removed, returns false if the packet should be dropped.
+    pub fn try_consume_multi(
+        &self,
+        direction: TrafficDirection,
+        peer_ids: &mut Vec<PeerId>,
+        ip: Option<IpAddr>,
+        len: u64,
+    ) -> bool {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let rules = self.rules.load();
+        let peer_buckets = |peer_id: PeerId| {
+            rules
+                .iter()
+                .filter(move |r| r.target == LimitTarget::Peer(peer_id))
+                .filter_map(move |r| r.bucket(direction))
+        };
+        peer_ids.retain(|peer_id| try_consume_all(peer_buckets(*peer_id), len));
+        if peer_ids.is_empty() {
+            return false;
+        }
+
+        let packet_buckets = rules
+            .iter()
+            .filter(|r| !matches!(r.target, LimitTarget::Peer(_)) && r.target.matches(0, ip))
+            .filter_map(|r| r.bucket(direction));
+        if try_consume_all(packet_buckets, len) {
+            return true;
+        }
+        for peer_id in peer_ids.iter() {
+            peer_buckets(*peer_id).for_each(|b| b.refund(len));
+        }
+        false
+    }
+
+    /// Like `try_consume`, taking the ip from the header of an ip packet payload.
+    pub fn try_consume_ip_packet(
+        &self,
+        direction: TrafficDirection,
+        peer_id: PeerId,
+        payload: &[u8],
+    ) -> bool {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let ip = match payload.first().map(|b| b >> 4) {
+            Some(4) => Ipv4Packet::new(payload).map(|p| match direction {
+                TrafficDirection::Upload => IpAddr::V4(p.get_destination()),
+                TrafficDirection::Download => IpAddr::V4(p.get_source()),
+            }),
+            Some(6) => Ipv6Packet::new(payload).map(|p| match direction {
+                TrafficDirection::Upload => IpAddr::V6(p.get_destination()),
+                TrafficDirection::Download => IpAddr::V6(p.get_source()),
+            }),
+            _ => None,
+        };
+        self.try_consume(direction, peer_id, ip, payload.len() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(
+        peer_id: Option<PeerId>,
+        cidr: Option<&str>,
+        upload_bps: Option<u64>,
+        download_bps: Option<u64>,
+    ) -> BandwidthLimitConfig {
+        BandwidthLimitConfig {
+            peer_id,
+            cidr: cidr.map(|c| c.parse().unwrap()),
+            upload_bps,
+            download_bps,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disabled_limiter_passes_everything() {
+        let limiter = BandwidthLimiter::new();
+        assert!(limiter.try_consume(TrafficDirection::Upload, 1, None, u64::MAX));
+
+        limiter.reload_limits(&[limit(None, None, None, None)]);
+        assert!(limiter.try_consume(TrafficDirection::Download, 1, None, u64::MAX));
+    }
+
+    #[tokio::test]
+    async fn test_node_limit_per_direction() {
+        let limiter = BandwidthLimiter::new();
+        limiter.reload_limits(&[limit(None, None, Some(10_000), None)]);
+
+        // bucket starts full with one second of traffic
+        assert!(limiter.try_consume(TrafficDirection::Upload, 1, None, 10_000));
+        assert!(!limiter.try_consume(TrafficDirection::Upload, 2, None, 1_000));
+        // download is not limited
+        assert!(limiter.try_consume(TrafficDirection::Download, 1, None, 100_000));
+    }
+
+    #[tokio::test]
+    async fn test_peer_and_cidr_targets() {
+        let limiter = BandwidthLimiter::new();
+        limiter.reload_limits(&[
+            limit(Some(1), None, Some(10_000), None),
+            limit(None, Some("10.1.1.0/24"), None, Some(10_000)),
+        ]);
+
+        assert!(limiter.try_consume(TrafficDirection::Upload, 1, None, 10_000));
+        assert!(!limiter.try_consume(TrafficDirection::Upload, 1, None, 1_000));
+        assert!(limiter.try_consume(TrafficDirection::Upload, 2, None, 10_000));
+
+        let in_cidr = Some("10.1.1.5".parse().unwrap());
+        let out_cidr = Some("10.1.2.5".parse().unwrap());
+        assert!(limiter.try_consume(TrafficDirection::Download, 3, in_cidr, 10_000));
+        assert!(!limiter.try_consume(TrafficDirection::Download, 3, in_cidr, 1_000));
+        assert!(limiter.try_consume(TrafficDirection::Download, 3, out_cidr, 10_000));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_packet_keeps_other_budgets() {
+        let limiter = BandwidthLimiter::new();
+        limiter.reload_limits(&[
+            limit(None, None, Some(20_000), None),
+            limit(Some(1), None, Some(10_000), None),
+        ]);
+
+        assert!(limiter.try_consume(TrafficDirection::Upload, 1, None, 10_000));
+        // dropped by the peer limit, the node bucket is refunded
+        for _ in 0..10 {
+            assert!(!limiter.try_consume(TrafficDirection::Upload, 1, None, 5_000));
+        }
+        assert!(limiter.try_consume(TrafficDirection::Upload, 2, None, 10_000));
+    }
+
+    #[tokio::test]
+    async fn test_multi_destination_charges_node_once() {
+        let limiter = BandwidthLimiter::new();
+        limiter.reload_limits(&[
+            limit(None, None, Some(10_000), None),
+            limit(Some(3), None, Some(10_000), None),
+        ]);
+
+        let mut peers = vec![1, 2, 3];
+        assert!(limiter.try_consume_multi(TrafficDirection::Upload, &mut peers, None, 5_000));
+        assert_eq!(peers, vec![1, 2, 3]);
+        let mut peers = vec![1, 2, 3];
+        assert!(limiter.try_consume_multi(TrafficDirection::Upload, &mut peers, None, 5_000));
+
+        // the node bucket is empty now
+        let mut peers = vec![1, 2];
+        assert!(!limiter.try_consume_multi(TrafficDirection::Upload, &mut peers, None, 5_000));
+    }
+
+    #[tokio::test]
+    async fn test_ip_packet_direction() {
+        let limiter = BandwidthLimiter::new();
+        limiter.reload_limits(&[limit(None, Some("10.1.1.0/24"), Some(10_000), Some(10_000))]);
+
+        let mut buf = vec![0u8; 20];
+        buf[0] = 0x45;
+        buf[12..16].copy_from_slice(&[10, 1, 1, 2]); // src
+        buf[16..20].copy_from_slice(&[10, 2, 2, 2]); // dst
+
+        // only the source is inside the cidr, so only downloads are counted
+        for _ in 0..1000 {
+            assert!(limiter.try_consume_ip_packet(TrafficDirection::Upload, 1, &buf));
+        }
+        for _ in 0..500 {
+            assert!(limiter.try_consume_ip_packet(TrafficDirection::Download, 1, &buf));
+        }
+        assert!(!limiter.try_consume_ip_packet(TrafficDirection::Download, 1, &buf));
+    }
+}
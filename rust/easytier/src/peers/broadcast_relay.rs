@@ -0,0 +1,542 @@
+// relays lan broadcast and multicast between the members, mostly used by the discovery of
+// legacy games (server browsers, lan lobbies).
+//
+// packets sent from the nic to a broadcast or multicast address are flooded to all peers by
+// default. this layer limits them to the configured udp ports, drops packets of this node that
+// come back through a relay (a member bridging the network back into itself) or relayed copies
+// of a packet that already arrived, and, with igmp snooping enabled, only sends multicast to
+// the peers that joined the group. only peers that snoop themselves query their nic for groups,
+// so peers without the igmp_snooping feature flag still get every multicast packet. igmp itself
+// is always flooded, so the group table of every member stays complete.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash as _, Hasher as _},
+    net::Ipv4Addr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use pnet::packet::{
+    ip::IpNextHeaderProtocols,
+    ipv4::{self, Ipv4Packet, MutableIpv4Packet},
+    udp::UdpPacket,
+    Packet as _,
+};
+
+use crate::{
+    common::{global_ctx::ArcGlobalCtx, PeerId},
+    tunnel::packet_def::{PacketType, ZCPacket},
+};
+
+use super::PeerPacketFilter;
+
+// copies arriving further apart than this are delivered again
+const DEDUP_WINDOW: Duration = Duration::from_millis(200);
+// stale hashes are only swept once the table grows past this
+const DEDUP_TABLE_SWEEP_LEN: usize = 4096;
+
+/// How often a general query is injected into the local nic, see RFC 3376 8.2.
+pub const IGMP_QUERY_INTERVAL: Duration = Duration::from_secs(125);
+// robustness variable * query interval + query response interval, RFC 3376 8.4
+const IGMP_MEMBERSHIP_TIMEOUT: Duration = Duration::from_secs(2 * 125 + 10);
+
+const IGMP_V1_REPORT: u8 = 0x12;
+const IGMP_V2_REPORT: u8 = 0x16;
+const IGMP_V2_LEAVE: u8 = 0x17;
+const IGMP_V3_REPORT: u8 = 0x22;
+
+// igmpv3 group record types, RFC 3376 4.2.12
+const MODE_IS_INCLUDE: u8 = 1;
+const MODE_IS_EXCLUDE: u8 = 2;
+const CHANGE_TO_INCLUDE_MODE: u8 = 3;
+const CHANGE_TO_EXCLUDE_MODE: u8 = 4;
+const ALLOW_NEW_SOURCES: u8 = 5;
+
+/// Parses a port list like "27015,6112-6119", empty means all ports.
+pub fn parse_port_ranges(s: &str) -> anyhow::Result<Vec<(u16, u16)>> {
+    let mut ret = vec![];
+    for item in s.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+        let (start, end) = match item.split_once('-') {
+            Some((start, end)) => (start.trim().parse::<u16>()?, end.trim().parse::<u16>()?),
+            None => {
+                let port = item.parse::<u16>()?;
+                (port, port)
+            }
+        };
+        if start > end {
+            anyhow::bail!("invalid port range: {}", item);
+        }
+        ret.push((start, end));
+    }
+    Ok(ret)
+}
+
+fn is_link_local_multicast(addr: &Ipv4Addr) -> bool {
+    // 224.0.0.0/24 is never routed and always flooded, RFC 4541 2.1.2
+    addr.octets()[..3] == [224, 0, 0]
+}
+
+fn packet_hash(ipv4: &Ipv4Packet) -> u64 {
+    // the header is left out, ttl and checksum change when the packet is forwarded
+    let mut hasher = DefaultHasher::new();
+    ipv4.get_source().hash(&mut hasher);
+    ipv4.get_destination().hash(&mut hasher);
+    ipv4.get_next_level_protocol().0.hash(&mut hasher);
+    ipv4.payload().hash(&mut hasher);
+    hasher.finish()
+}
+
+enum IgmpMembership {
+    Join(Ipv4Addr),
+    Leave(Ipv4Addr),
+}
+
+fn parse_igmp_report(igmp: &[u8]) -> Vec<IgmpMembership> {
+    let group_at = |off: usize| -> Option<Ipv4Addr> {
+        let b: [u8; 4] = igmp.get(off..off + 4)?.try_into().ok()?;
+        Some(Ipv4Addr::from(b))
+    };
+
+    let mut ret = vec![];
+    match igmp.first() {
+        Some(&IGMP_V1_REPORT) | Some(&IGMP_V2_REPORT) => {
+            ret.extend(group_at(4).map(IgmpMembership::Join));
+        }
+        Some(&IGMP_V2_LEAVE) => {
+            ret.extend(group_at(4).map(IgmpMembership::Leave));
+        }
+        Some(&IGMP_V3_REPORT) => {
+            let Some(count) = igmp.get(6..8) else {
+                return ret;
+            };
+            let count = u16::from_be_bytes([count[0], count[1]]);
+            let mut off = 8;
+            for _ in 0..count {
+                let Some(record) = igmp.get(off..off + 8) else {
+                    break;
+                };
+                let record_type = record[0];
+                let aux_len = record[1] as usize * 4;
+                let sources = u16::from_be_bytes([record[2], record[3]]) as usize;
+                let group = Ipv4Addr::new(record[4], record[5], record[6], record[7]);
+                off += 8 + sources * 4 + aux_len;
+
+                match record_type {
+                    MODE_IS_EXCLUDE | CHANGE_TO_EXCLUDE_MODE => {
+                        ret.push(IgmpMembership::Join(group))
+                    }
+                    // include mode without sources means the group is left
+                    MODE_IS_INCLUDE | CHANGE_TO_INCLUDE_MODE if sources == 0 => {
+                        ret.push(IgmpMembership::Leave(group))
+                    }
+                    MODE_IS_INCLUDE | CHANGE_TO_INCLUDE_MODE | ALLOW_NEW_SOURCES => {
+                        ret.push(IgmpMembership::Join(group))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    ret
+}
+
+/// Builds an igmpv3 general query to 224.0.0.1, the local host answers it with reports of
+/// all groups it joined on the nic.
+pub fn build_igmp_general_query() -> Vec<u8> {
+    // 20 bytes header + 4 bytes router alert option + 12 bytes query
+    let mut buf = vec![0u8; 36];
+    buf[24] = 0x11;
+    // max response time 10s, in 1/10 seconds
+    buf[25] = 100;
+    // QRV = 2
+    buf[32] = 2;
+    buf[33] = IGMP_QUERY_INTERVAL.as_secs() as u8;
+    let csum = pnet::packet::util::checksum(&buf[24..], 1);
+    buf[26..28].copy_from_slice(&csum.to_be_bytes());
+
+    let mut ip = MutableIpv4Packet::new(&mut buf).unwrap();
+    ip.set_version(4);
+    ip.set_header_length(6);
+    ip.set_total_length(36);
+    ip.set_ttl(1);
+    ip.set_next_level_protocol(IpNextHeaderProtocols::Igmp);
+    ip.set_source(Ipv4Addr::UNSPECIFIED);
+    ip.set_destination(Ipv4Addr::new(224, 0, 0, 1));
+    ip.set_options_raw(&[0x94, 0x04, 0x00, 0x00]);
+    let csum = ipv4::checksum(&ip.to_immutable());
+    ip.set_checksum(csum);
+    buf
+}
+
+pub struct BroadcastRelay {
+    global_ctx: ArcGlobalCtx,
+    allowed_ports: Vec<(u16, u16)>,
+    igmp_snooping: bool,
+
+    // hash of broadcast and multicast packets recently sent from the nic
+    sent: DashMap<u64, Instant>,
+    // hash of broadcast and multicast packets recently received from peers
+    received: DashMap<u64, Instant>,
+    // group -> member peer -> last report
+    groups: Mutex<HashMap<Ipv4Addr, HashMap<PeerId, Instant>>>,
+}
+
+impl BroadcastRelay {
+    pub fn new(global_ctx: ArcGlobalCtx) -> Self {
+        let flags = global_ctx.get_flags();
+        let allowed_ports = parse_port_ranges(&flags.broadcast_relay_ports).unwrap_or_else(|e| {
+            tracing::warn!(
+                ports = %flags.broadcast_relay_ports,
+                ?e,
+                "invalid broadcast relay ports, relay all ports"
+            );
+            vec![]
+        });
+        if flags.multicast_igmp_snooping {
+            let mut f = global_ctx.get_feature_flags();
+            f.igmp_snooping = true;
+            global_ctx.set_feature_flags(f);
+        }
+        BroadcastRelay {
+            global_ctx,
+            allowed_ports,
+            igmp_snooping: flags.multicast_igmp_snooping,
+            sent: DashMap::new(),
+            received: DashMap::new(),
+            groups: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn igmp_snooping_enabled(&self) -> bool {
+        self.igmp_snooping
+    }
+
+    /// Whether filter_outgoing limits packets to this address to the members of the group.
+    pub fn is_snooped_group(&self, addr: &Ipv4Addr) -> bool {
+        self.igmp_snooping && addr.is_multicast() && !is_link_local_multicast(addr)
+    }
+
+    /// Whether packets to this address are flooded to all peers.
+    pub fn is_broadcast_dst(&self, addr: &Ipv4Addr) -> bool {
+        if addr.is_broadcast() || addr.is_multicast() {
+            return true;
+        }
+        let network_length = self
+            .global_ctx
+            .get_ipv4()
+            .map(|x| x.network_length())
+            .unwrap_or(24);
+        cidr::Ipv4Inet::new(*addr, network_length)
+            .map(|x| x.last_address() == *addr)
+            .unwrap_or(false)
+    }
+
+    fn is_port_allowed(&self, port: u16) -> bool {
+        self.allowed_ports.is_empty()
+            || self
+                .allowed_ports
+                .iter()
+                .any(|(start, end)| (*start..=*end).contains(&port))
+    }
+
+    fn seen_recently(table: &DashMap<u64, Instant>, hash: u64) -> bool {
+        table
+            .get(&hash)
+            .is_some_and(|last| last.elapsed() < DEDUP_WINDOW)
+    }
+
+    fn record(table: &DashMap<u64, Instant>, hash: u64) {
+        let now = Instant::now();
+        if table.len() > DEDUP_TABLE_SWEEP_LEN {
+            table.retain(|_, t| now.duration_since(*t) < DEDUP_WINDOW);
+        }
+        table.insert(hash, now);
+    }
+
+    fn update_membership(&self, peer_id: PeerId, igmp: &[u8]) {
+        let mut groups = self.groups.lock().unwrap();
+        for m in parse_igmp_report(igmp) {
+            match m {
+                IgmpMembership::Join(group) => {
+                    tracing::trace!(?group, ?peer_id, "peer joined multicast group");
+                    groups
+                        .entry(group)
+                        .or_default()
+                        .insert(peer_id, Instant::now());
+                }
+                IgmpMembership::Leave(group) => {
+                    tracing::trace!(?group, ?peer_id, "peer left multicast group");
+                    if let Some(members) = groups.get_mut(&group) {
+                        members.remove(&peer_id);
+                        if members.is_empty() {
+                            groups.remove(&group);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Peers that reported membership of the group recently.
+    pub fn multicast_members(&self, group: &Ipv4Addr) -> Vec<PeerId> {
+        let mut groups = self.groups.lock().unwrap();
+        let Some(members) = groups.get_mut(group) else {
+            return vec![];
+        };
+        members.retain(|_, t| t.elapsed() < IGMP_MEMBERSHIP_TIMEOUT);
+        let ret = members.keys().copied().collect::<Vec<_>>();
+        if ret.is_empty() {
+            groups.remove(group);
+        }
+        ret
+    }
+
+    /// Limits the peers a broadcast or multicast ipv4 packet from the nic is sent to, returns
+    /// false if the packet is dropped. snooping_peers are the peers with the igmp_snooping
+    /// feature flag, only they are left out of groups they didn't join.
+    pub fn filter_outgoing(
+        &self,
+        ip_packet: &[u8],
+        dst_peers: &mut Vec<PeerId>,
+        snooping_peers: &[PeerId],
+    ) -> bool {
+        let Some(ipv4) = Ipv4Packet::new(ip_packet) else {
+            return true;
+        };
+        let proto = ipv4.get_next_level_protocol();
+        if proto == IpNextHeaderProtocols::Igmp {
+            return true;
+        }
+
+        if !self.allowed_ports.is_empty() {
+            let port = (proto == IpNextHeaderProtocols::Udp)
+                .then(|| UdpPacket::new(ipv4.payload()))
+                .flatten()
+                .map(|udp| udp.get_destination());
+            if !port.is_some_and(|p| self.is_port_allowed(p)) {
+                tracing::trace!(?ipv4, "broadcast port not allowed, drop");
+                return false;
+            }
+        }
+
+        let dst = ipv4.get_destination();
+        if self.is_snooped_group(&dst) {
+            let members = self.multicast_members(&dst);
+            dst_peers.retain(|p| members.contains(p) || !snooping_peers.contains(p));
+        }
+
+        if dst_peers.is_empty() {
+            return false;
+        }
+        // the app may repeat a packet at once, only copies coming back are dropped
+        Self::record(&self.sent, packet_hash(&ipv4));
+        true
+    }
+
+    /// Records igmp reports of peers and drops broadcast packets of this node that came back
+    /// or relayed copies of packets that already arrived, returns false if the packet is
+    /// dropped.
+    fn filter_incoming(&self, from_peer_id: PeerId, relayed: bool, ip_packet: &[u8]) -> bool {
+        let Some(ipv4) = Ipv4Packet::new(ip_packet) else {
+            return true;
+        };
+        if ipv4.get_version() != 4 || !self.is_broadcast_dst(&ipv4.get_destination()) {
+            return true;
+        }
+
+        if ipv4.get_next_level_protocol() == IpNextHeaderProtocols::Igmp {
+            if self.igmp_snooping {
+                self.update_membership(from_peer_id, ipv4.payload());
+            }
+            return true;
+        }
+
+        let hash = packet_hash(&ipv4);
+        if Self::seen_recently(&self.sent, hash) {
+            tracing::trace!(?ipv4, ?from_peer_id, "drop looped broadcast packet");
+            return false;
+        }
+        if relayed && Self::seen_recently(&self.received, hash) {
+            tracing::trace!(?ipv4, ?from_peer_id, "drop duplicated broadcast packet");
+            return false;
+        }
+        Self::record(&self.received, hash);
+        true
+    }
+}
+
+#[async_trait::async_trait]
+impl PeerPacketFilter for BroadcastRelay {
+    async fn try_process_packet_from_peer(&self, packet: ZCPacket) -> Option<ZCPacket> {
+        let hdr = packet.peer_manager_header().unwrap();
+        if hdr.packet_type != PacketType::Data as u8 {
+            return Some(packet);
+        }
+        let from_peer_id = hdr.from_peer_id.get();
+        // every relay on the way counts the packet once more
+        let relayed = hdr.forward_counter > 1;
+        if self.filter_incoming(from_peer_id, relayed, packet.payload()) {
+            Some(packet)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use pnet::packet::{
+        ip::IpNextHeaderProtocols,
+        ipv4::{Ipv4Packet, MutableIpv4Packet},
+        udp::MutableUdpPacket,
+        Packet as _,
+    };
+
+    use crate::common::global_ctx::tests::get_mock_global_ctx;
+
+    use super::*;
+
+    fn udp_packet(dst: Ipv4Addr, port: u16, payload: &[u8]) -> Vec<u8> {
+        let mut buf = vec![0u8; 28 + payload.len()];
+        let mut ip = MutableIpv4Packet::new(&mut buf).unwrap();
+        ip.set_version(4);
+        ip.set_header_length(5);
+        ip.set_total_length((28 + payload.len()) as u16);
+        ip.set_ttl(64);
+        ip.set_next_level_protocol(IpNextHeaderProtocols::Udp);
+        ip.set_source(Ipv4Addr::new(10, 126, 126, 1));
+        ip.set_destination(dst);
+        let mut udp = MutableUdpPacket::new(&mut buf[20..]).unwrap();
+        udp.set_source(port);
+        udp.set_destination(port);
+        udp.set_length((8 + payload.len()) as u16);
+        udp.set_payload(payload);
+        buf
+    }
+
+    fn igmp_packet(igmp: &[u8]) -> Vec<u8> {
+        let mut buf = vec![0u8; 20 + igmp.len()];
+        let mut ip = MutableIpv4Packet::new(&mut buf).unwrap();
+        ip.set_version(4);
+        ip.set_header_length(5);
+        ip.set_total_length((20 + igmp.len()) as u16);
+        ip.set_ttl(1);
+        ip.set_next_level_protocol(IpNextHeaderProtocols::Igmp);
+        ip.set_source(Ipv4Addr::new(10, 126, 126, 2));
+        ip.set_destination(Ipv4Addr::new(224, 0, 0, 22));
+        ip.set_payload(igmp);
+        buf
+    }
+
+    fn relay(ports: &str, snooping: bool) -> BroadcastRelay {
+        let global_ctx = get_mock_global_ctx();
+        let mut flags = global_ctx.get_flags();
+        flags.broadcast_relay_ports = ports.to_string();
+        flags.multicast_igmp_snooping = snooping;
+        global_ctx.set_flags(flags);
+        BroadcastRelay::new(global_ctx)
+    }
+
+    #[test]
+    fn port_ranges() {
+        assert_eq!(
+            parse_port_ranges("27015, 6112-6119").unwrap(),
+            vec![(27015, 27015), (6112, 6119)]
+        );
+        assert!(parse_port_ranges("").unwrap().is_empty());
+        assert!(parse_port_ranges("6119-6112").is_err());
+        assert!(parse_port_ranges("abc").is_err());
+    }
+
+    #[tokio::test]
+    async fn port_allowlist_and_dedup() {
+        let relay = relay("27015,6112-6119", false);
+        let mut peers = vec![1, 2];
+
+        let allowed = udp_packet(Ipv4Addr::BROADCAST, 6113, b"hello");
+        assert!(relay.filter_outgoing(&allowed, &mut peers, &[]));
+        assert_eq!(peers, vec![1, 2]);
+        // apps repeat discovery packets right away, they are all sent
+        assert!(relay.filter_outgoing(&allowed, &mut peers, &[]));
+        let denied = udp_packet(Ipv4Addr::BROADCAST, 5353, b"hello");
+        assert!(!relay.filter_outgoing(&denied, &mut peers, &[]));
+
+        // our own packet coming back through a bridging member
+        assert!(!relay.filter_incoming(2, true, &allowed));
+
+        let incoming = udp_packet(Ipv4Addr::BROADCAST, 27015, b"discover");
+        assert!(relay.filter_incoming(1, false, &incoming));
+        // repeated by the sender itself
+        assert!(relay.filter_incoming(1, false, &incoming));
+        // the same packet relayed through another path
+        assert!(!relay.filter_incoming(2, true, &incoming));
+
+        // unicast is left alone
+        let unicast = udp_packet(Ipv4Addr::new(10, 126, 126, 3), 27015, b"discover");
+        assert!(relay.filter_incoming(1, true, &unicast));
+        assert!(relay.filter_incoming(1, true, &unicast));
+    }
+
+    #[tokio::test]
+    async fn igmp_snooping() {
+        let relay = relay("", true);
+        assert!(relay.global_ctx.get_feature_flags().igmp_snooping);
+        let group = Ipv4Addr::new(239, 1, 2, 3);
+
+        // v2 report from peer 1
+        let mut report = vec![IGMP_V2_REPORT, 0, 0, 0];
+        report.extend_from_slice(&group.octets());
+        assert!(relay.filter_incoming(1, false, &igmp_packet(&report)));
+
+        // v3 report from peer 2: change to exclude {} for the group
+        let mut report = vec![IGMP_V3_REPORT, 0, 0, 0, 0, 0, 0, 1];
+        report.extend_from_slice(&[CHANGE_TO_EXCLUDE_MODE, 0, 0, 0]);
+        report.extend_from_slice(&group.octets());
+        assert!(relay.filter_incoming(2, false, &igmp_packet(&report)));
+
+        let mut members = relay.multicast_members(&group);
+        members.sort();
+        assert_eq!(members, vec![1, 2]);
+
+        let mut peers = vec![1, 2, 3, 4];
+        let packet = udp_packet(group, 5000, b"game");
+        // peer 4 doesn't snoop, so its groups are unknown
+        assert!(relay.filter_outgoing(&packet, &mut peers, &[1, 2, 3]));
+        assert_eq!(peers, vec![1, 2, 4]);
+
+        // peer 1 leaves
+        let mut leave = vec![IGMP_V2_LEAVE, 0, 0, 0];
+        leave.extend_from_slice(&group.octets());
+        assert!(relay.filter_incoming(1, false, &igmp_packet(&leave)));
+        assert_eq!(relay.multicast_members(&group), vec![2]);
+
+        // nobody joined this group
+        let mut peers = vec![1, 2, 3];
+        let packet = udp_packet(Ipv4Addr::new(239, 9, 9, 9), 5000, b"game");
+        assert!(!relay.filter_outgoing(&packet, &mut peers, &[1, 2, 3]));
+
+        // link local groups are flooded
+        let mut peers = vec![1, 2, 3];
+        let packet = udp_packet(Ipv4Addr::new(224, 0, 0, 251), 5353, b"mdns");
+        assert!(relay.filter_outgoing(&packet, &mut peers, &[1, 2, 3]));
+        assert_eq!(peers.len(), 3);
+    }
+
+    #[test]
+    fn general_query() {
+        let query = build_igmp_general_query();
+        let ip = Ipv4Packet::new(&query).unwrap();
+        assert_eq!(ip.get_destination(), Ipv4Addr::new(224, 0, 0, 1));
+        assert_eq!(ip.get_checksum(), pnet::packet::ipv4::checksum(&ip));
+        assert_eq!(ip.payload()[0], 0x11);
+        assert_eq!(
+            pnet::packet::util::checksum(ip.payload(), 1),
+            u16::from_be_bytes([ip.payload()[2], ip.payload()[3]])
+        );
+    }
+}
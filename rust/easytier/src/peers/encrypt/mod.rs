@@ -1,6 +1,15 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use crate::{common::config::EncryptionAlgorithm, tunnel::packet_def::ZCPacket};
+use arc_swap::{ArcSwap, ArcSwapOption};
+use dashmap::DashSet;
+
+use crate::{
+    common::{config::EncryptionAlgorithm, PeerId},
+    tunnel::packet_def::ZCPacket,
+};
 
 #[cfg(feature = "wireguard")]
 pub mod ring_aes_gcm;
@@ -16,6 +25,9 @@ pub mod openssl_cipher;
 
 pub mod xor_cipher;
 
+#[cfg(feature = "pfs")]
+pub mod session;
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("packet is too short. len: {0}")]
@@ -50,6 +62,85 @@ impl Encryptor for NullCipher {
     }
 }
 
+/// The encryptor of the network key, replaced in place when the network secret is rotated.
+/// While a rotation is in progress another key is accepted as well: the new one before this
+/// node switches to it, the previous one for the grace period after.
+/// Ciphers without a tag (xor) can't tell the keys apart, so they only use the current key.
+pub struct RotatingEncryptor {
+    current: ArcSwap<Arc<dyn Encryptor>>,
+    alternate: ArcSwapOption<(Arc<dyn Encryptor>, Instant)>,
+    // peers known to still use the alternate key, packets to them are sealed with it
+    alternate_peers: DashSet<PeerId>,
+}
+
+impl RotatingEncryptor {
+    pub fn new(encryptor: Arc<dyn Encryptor>) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(encryptor),
+            alternate: ArcSwapOption::empty(),
+            alternate_peers: DashSet::new(),
+        }
+    }
+
+    /// Also accept packets sealed with another key until `valid_for` elapses.
+    pub fn set_alternate(&self, encryptor: Arc<dyn Encryptor>, valid_for: Duration) {
+        self.alternate_peers.clear();
+        self.alternate
+            .store(Some(Arc::new((encryptor, Instant::now() + valid_for))));
+    }
+
+    /// Seal with a new key, the current one is still accepted for the grace period.
+    pub fn rotate(&self, encryptor: Arc<dyn Encryptor>, grace_period: Duration) {
+        let previous = self.current.swap(Arc::new(encryptor));
+        self.set_alternate((*previous).clone(), grace_period);
+    }
+
+    pub fn set_peer_on_alternate(&self, peer_id: PeerId, on_alternate: bool) {
+        if on_alternate {
+            self.alternate_peers.insert(peer_id);
+        } else {
+            self.alternate_peers.remove(&peer_id);
+        }
+    }
+
+    fn valid_alternate(&self) -> Option<Arc<dyn Encryptor>> {
+        self.alternate
+            .load()
+            .as_deref()
+            .filter(|(_, valid_until)| Instant::now() < *valid_until)
+            .map(|(encryptor, _)| encryptor.clone())
+    }
+}
+
+impl Encryptor for RotatingEncryptor {
+    fn encrypt(&self, zc_packet: &mut ZCPacket) -> Result<(), Error> {
+        if !self.alternate_peers.is_empty() {
+            let to_peer_id = zc_packet
+                .peer_manager_header()
+                .map(|hdr| hdr.to_peer_id.get());
+            if to_peer_id.is_some_and(|peer_id| self.alternate_peers.contains(&peer_id)) {
+                if let Some(alternate) = self.valid_alternate() {
+                    return alternate.encrypt(zc_packet);
+                }
+            }
+        }
+        self.current.load().encrypt(zc_packet)
+    }
+
+    fn decrypt(&self, zc_packet: &mut ZCPacket) -> Result<(), Error> {
+        let Some(alternate) = self.valid_alternate() else {
+            return self.current.load().decrypt(zc_packet);
+        };
+        // a failed decryption may leave the payload modified
+        let backup = zc_packet.clone();
+        if self.current.load().decrypt(zc_packet).is_ok() {
+            return Ok(());
+        }
+        *zc_packet = backup;
+        alternate.decrypt(zc_packet)
+    }
+}
+
 /// Create an encryptor based on the algorithm name
 pub fn create_encryptor(
     algorithm: &str,
@@ -116,3 +207,54 @@ pub fn create_encryptor(
         }
     }
 }
+
+#[cfg(all(test, feature = "wireguard"))]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        peers::encrypt::{ring_aes_gcm::AesGcmCipher, Encryptor, RotatingEncryptor},
+        tunnel::packet_def::ZCPacket,
+    };
+
+    fn encrypted_packet(cipher: &dyn Encryptor, to_peer_id: u32) -> ZCPacket {
+        let mut packet = ZCPacket::new_with_payload(b"1234567");
+        packet.fill_peer_manager_hdr(0, to_peer_id, 0);
+        cipher.encrypt(&mut packet).unwrap();
+        packet
+    }
+
+    #[tokio::test]
+    async fn test_rotating_encryptor() {
+        let old_key = Arc::new(AesGcmCipher::new_128([1u8; 16]));
+        let new_key = Arc::new(AesGcmCipher::new_128([2u8; 16]));
+        let cipher = RotatingEncryptor::new(old_key.clone());
+
+        // prepared: still seals with the old key, accepts both
+        cipher.set_alternate(new_key.clone(), Duration::from_secs(10));
+        let mut packet = encrypted_packet(&cipher, 1);
+        old_key.decrypt(&mut packet).unwrap();
+        for key in [&old_key, &new_key] {
+            let mut packet = encrypted_packet(key.as_ref(), 1);
+            cipher.decrypt(&mut packet).unwrap();
+            assert_eq!(packet.payload(), b"1234567");
+        }
+
+        // committed: seals with the new key, except for peers still on the old one
+        cipher.rotate(new_key.clone(), Duration::from_millis(200));
+        cipher.set_peer_on_alternate(2, true);
+        let mut packet = encrypted_packet(&cipher, 1);
+        new_key.decrypt(&mut packet).unwrap();
+        let mut packet = encrypted_packet(&cipher, 2);
+        old_key.decrypt(&mut packet).unwrap();
+        let mut packet = encrypted_packet(old_key.as_ref(), 2);
+        cipher.decrypt(&mut packet).unwrap();
+
+        // the old key is dropped after the grace period
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let mut packet = encrypted_packet(old_key.as_ref(), 2);
+        assert!(cipher.decrypt(&mut packet).is_err());
+        let mut packet = encrypted_packet(&cipher, 2);
+        new_key.decrypt(&mut packet).unwrap();
+    }
+}
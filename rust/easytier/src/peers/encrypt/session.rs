@@ -0,0 +1,447 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use ring::{
+    agreement::{self, EphemeralPrivateKey, UnparsedPublicKey, X25519},
+    hkdf,
+    rand::SystemRandom,
+};
+
+use crate::{
+    common::{
+        config::EncryptionAlgorithm,
+        global_ctx::GlobalCtx,
+        stats_manager::{CounterHandle, LabelSet, LabelType, MetricName},
+        PeerId,
+    },
+    tunnel::packet_def::ZCPacket,
+};
+
+use super::{create_encryptor, Encryptor, Error};
+
+/// Session keys are ratcheted forward this often, a leaked key only opens one interval.
+pub const REKEY_INTERVAL: Duration = Duration::from_secs(600);
+
+const SESSION_INFO: &[u8] = b"easytier session";
+const CHAIN_INFO: &[u8] = b"easytier session chain";
+const KEY_INFO: &[u8] = b"easytier session key";
+
+/// Session keys only work with ciphers that can tell a wrong key (aead), with xor every
+/// packet would decrypt with any key.
+pub fn supports_session_keys(algorithm: &str) -> bool {
+    !matches!(
+        EncryptionAlgorithm::try_from(algorithm),
+        Ok(EncryptionAlgorithm::Xor)
+    )
+}
+
+/// The ephemeral X25519 key of one side of a handshake, sent in `session_public_key`.
+pub struct SessionKeyExchange {
+    private_key: EphemeralPrivateKey,
+    public_key: Vec<u8>,
+}
+
+impl SessionKeyExchange {
+    pub fn new() -> anyhow::Result<Self> {
+        let private_key = EphemeralPrivateKey::generate(&X25519, &SystemRandom::new())
+            .map_err(|_| anyhow::anyhow!("failed to generate x25519 key"))?;
+        let public_key = private_key
+            .compute_public_key()
+            .map_err(|_| anyhow::anyhow!("failed to compute x25519 public key"))?
+            .as_ref()
+            .to_vec();
+        Ok(Self {
+            private_key,
+            public_key,
+        })
+    }
+
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    /// The network secret is mixed in so a man in the middle without the secret can't
+    /// complete the exchange, recorded traffic stays sealed even if the secret leaks later.
+    pub fn derive(
+        self,
+        peer_public_key: &[u8],
+        network_secret: &str,
+        algorithm: &str,
+        is_client: bool,
+    ) -> anyhow::Result<Arc<Session>> {
+        let (client_key, server_key) = if is_client {
+            (self.public_key.as_slice(), peer_public_key)
+        } else {
+            (peer_public_key, self.public_key.as_slice())
+        };
+        let salt = hkdf::Salt::new(
+            hkdf::HKDF_SHA256,
+            &GlobalCtx::derive_256_key(network_secret),
+        );
+        let chain_key = agreement::agree_ephemeral(
+            self.private_key,
+            &UnparsedPublicKey::new(&X25519, peer_public_key),
+            |shared| {
+                let mut chain_key = [0u8; 32];
+                salt.extract(shared)
+                    .expand(&[SESSION_INFO, client_key, server_key], hkdf::HKDF_SHA256)
+                    .and_then(|okm| okm.fill(&mut chain_key))
+                    .map(|_| chain_key)
+            },
+        )
+        .map_err(|_| anyhow::anyhow!("x25519 key agreement failed"))?
+        .map_err(|_| anyhow::anyhow!("session key derivation failed"))?;
+
+        Ok(Arc::new(Session::new(
+            algorithm.to_string(),
+            chain_key,
+            // the responder knows the initiator has the key once the handshake is sent
+            !is_client,
+        )))
+    }
+}
+
+fn expand(key: &[u8; 32], info: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, key)
+        .expand(&[info], hkdf::HKDF_SHA256)
+        .and_then(|okm| okm.fill(&mut out))
+        .expect("hkdf output length is valid for sha256");
+    out
+}
+
+struct EpochKeys {
+    epoch: u64,
+    previous: Option<Arc<dyn Encryptor>>,
+    current: Arc<dyn Encryptor>,
+    next: Arc<dyn Encryptor>,
+    // chain key of the epoch after `next`, the older ones are forgotten
+    chain_key: [u8; 32],
+}
+
+/// The keys of one peer connection. Both sides derive the key of an epoch from the one
+/// before, the epoch is counted from the end of the handshake on each side, so the
+/// neighbouring epochs are accepted too.
+pub struct Session {
+    algorithm: String,
+    established: Instant,
+    // the peer has the session too, so packets to it may be sealed with it
+    confirmed: AtomicBool,
+    keys: Mutex<EpochKeys>,
+}
+
+impl Session {
+    fn new(algorithm: String, chain_key: [u8; 32], confirmed: bool) -> Self {
+        let current = Self::epoch_encryptor(&algorithm, &chain_key);
+        let chain_key = expand(&chain_key, CHAIN_INFO);
+        let next = Self::epoch_encryptor(&algorithm, &chain_key);
+        let chain_key = expand(&chain_key, CHAIN_INFO);
+        Self {
+            algorithm,
+            established: Instant::now(),
+            confirmed: AtomicBool::new(confirmed),
+            keys: Mutex::new(EpochKeys {
+                epoch: 0,
+                previous: None,
+                current,
+                next,
+                chain_key,
+            }),
+        }
+    }
+
+    fn epoch_encryptor(algorithm: &str, chain_key: &[u8; 32]) -> Arc<dyn Encryptor> {
+        let key_256 = expand(chain_key, KEY_INFO);
+        let mut key_128 = [0u8; 16];
+        key_128.copy_from_slice(&key_256[..16]);
+        create_encryptor(algorithm, key_128, key_256)
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed.load(Ordering::Relaxed)
+    }
+
+    // a failed decryption may leave the payload modified, it is restored from the backup
+    // before the next key is tried, and on failure
+    fn decrypt_with_backup(&self, zc_packet: &mut ZCPacket, backup: &[u8]) -> bool {
+        let (previous, current, next) = self.keys();
+        for key in std::iter::once(current).chain(Some(next)).chain(previous) {
+            if key.decrypt(zc_packet).is_ok() {
+                self.confirmed.store(true, Ordering::Relaxed);
+                return true;
+            }
+            zc_packet.mut_payload().copy_from_slice(backup);
+        }
+        false
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn keys(
+        &self,
+    ) -> (
+        Option<Arc<dyn Encryptor>>,
+        Arc<dyn Encryptor>,
+        Arc<dyn Encryptor>,
+    ) {
+        let epoch = self.established.elapsed().as_secs() / REKEY_INTERVAL.as_secs();
+        let mut guard = self.keys.lock().unwrap();
+        let keys = &mut *guard;
+        while keys.epoch < epoch {
+            let next = Self::epoch_encryptor(&self.algorithm, &keys.chain_key);
+            keys.previous = Some(std::mem::replace(
+                &mut keys.current,
+                std::mem::replace(&mut keys.next, next),
+            ));
+            keys.chain_key = expand(&keys.chain_key, CHAIN_INFO);
+            keys.epoch += 1;
+        }
+        (
+            keys.previous.clone(),
+            keys.current.clone(),
+            keys.next.clone(),
+        )
+    }
+}
+
+impl Encryptor for Session {
+    fn encrypt(&self, zc_packet: &mut ZCPacket) -> Result<(), Error> {
+        self.keys().1.encrypt(zc_packet)
+    }
+
+    fn decrypt(&self, zc_packet: &mut ZCPacket) -> Result<(), Error> {
+        if !zc_packet
+            .peer_manager_header()
+            .is_some_and(|hdr| hdr.is_encrypted())
+        {
+            return Ok(());
+        }
+        let backup = zc_packet.payload().to_vec();
+        if self.decrypt_with_backup(zc_packet, &backup) {
+            Ok(())
+        } else {
+            Err(Error::DecryptionFailed)
+        }
+    }
+}
+
+/// Seals the traffic with a peer with the key of a connection to it when both sides did the
+/// key exchange, and with the network key otherwise (old peers, peers that are not directly
+/// connected). The sessions are owned by their connection and go away with it.
+pub struct SessionEncryptor {
+    inner: Arc<dyn Encryptor>,
+    sessions: DashMap<PeerId, Vec<Weak<Session>>>,
+
+    // packets that were not protected by a session key, mostly those of relayed peers
+    network_key_tx: CounterHandle,
+    network_key_rx: CounterHandle,
+}
+
+impl SessionEncryptor {
+    pub fn new(inner: Arc<dyn Encryptor>, global_ctx: &GlobalCtx) -> Self {
+        let label_set =
+            LabelSet::new().with_label_type(LabelType::NetworkName(global_ctx.get_network_name()));
+        let stats_mgr = global_ctx.stats_manager();
+        Self {
+            inner,
+            sessions: DashMap::new(),
+            network_key_tx: stats_mgr
+                .get_counter(MetricName::TrafficPacketsNetworkKeyTx, label_set.clone()),
+            network_key_rx: stats_mgr
+                .get_counter(MetricName::TrafficPacketsNetworkKeyRx, label_set),
+        }
+    }
+
+    pub fn add_session(&self, peer_id: PeerId, session: &Arc<Session>) {
+        self.sessions.retain(|_, sessions| {
+            sessions.retain(|s| s.strong_count() > 0);
+            !sessions.is_empty()
+        });
+        self.sessions
+            .entry(peer_id)
+            .or_default()
+            .push(Arc::downgrade(session));
+    }
+
+    // the oldest connection is used, a newer one may not be known to the peer yet
+    fn session_to(&self, peer_id: PeerId) -> Option<Arc<Session>> {
+        self.sessions
+            .get(&peer_id)?
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|s| s.is_confirmed())
+    }
+
+    fn sessions_from(&self, peer_id: PeerId) -> Vec<Arc<Session>> {
+        self.sessions
+            .get(&peer_id)
+            .map(|s| s.iter().filter_map(Weak::upgrade).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Encryptor for SessionEncryptor {
+    fn encrypt(&self, zc_packet: &mut ZCPacket) -> Result<(), Error> {
+        let to_peer_id = zc_packet
+            .peer_manager_header()
+            .map(|hdr| hdr.to_peer_id.get());
+        match to_peer_id.and_then(|peer_id| self.session_to(peer_id)) {
+            Some(session) => session.encrypt(zc_packet),
+            None => {
+                self.network_key_tx.inc();
+                self.inner.encrypt(zc_packet)
+            }
+        }
+    }
+
+    fn decrypt(&self, zc_packet: &mut ZCPacket) -> Result<(), Error> {
+        let Some(hdr) = zc_packet.peer_manager_header() else {
+            return self.inner.decrypt(zc_packet);
+        };
+        if !hdr.is_encrypted() {
+            return Ok(());
+        }
+        let from_peer_id = hdr.from_peer_id.get();
+        let sessions = self.sessions_from(from_peer_id);
+        if sessions.is_empty() {
+            self.network_key_rx.inc();
+            return self.inner.decrypt(zc_packet);
+        }
+
+        // only copied when the peer has sessions, the copy is shared by all their keys
+        let backup = zc_packet.payload().to_vec();
+        for session in sessions {
+            if session.decrypt_with_backup(zc_packet, &backup) {
+                return Ok(());
+            }
+        }
+        // the peer did not confirm the session yet, or sent through a relay
+        self.network_key_rx.inc();
+        let ret = self.inner.decrypt(zc_packet);
+        if ret.is_ok() {
+            tracing::debug!(
+                ?from_peer_id,
+                "packet of a peer with session keys opened with the network key"
+            );
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        common::{
+            global_ctx::tests::get_mock_global_ctx,
+            stats_manager::{LabelSet, LabelType, MetricName},
+        },
+        peers::encrypt::{Encryptor, NullCipher},
+        tunnel::packet_def::ZCPacket,
+    };
+
+    use super::{SessionEncryptor, SessionKeyExchange};
+
+    fn packet(from_peer_id: u32, to_peer_id: u32) -> ZCPacket {
+        let mut packet = ZCPacket::new_with_payload(b"1234567");
+        packet.fill_peer_manager_hdr(from_peer_id, to_peer_id, 0);
+        packet
+    }
+
+    #[test]
+    fn test_session_key_exchange() {
+        let client = SessionKeyExchange::new().unwrap();
+        let server = SessionKeyExchange::new().unwrap();
+        let (client_pub, server_pub) = (client.public_key().to_vec(), server.public_key().to_vec());
+
+        let client_session = client
+            .derive(&server_pub, "secret", "aes-gcm", true)
+            .unwrap();
+        let server_session = server
+            .derive(&client_pub, "secret", "aes-gcm", false)
+            .unwrap();
+        assert!(!client_session.is_confirmed());
+        assert!(server_session.is_confirmed());
+
+        let mut p = packet(2, 1);
+        server_session.encrypt(&mut p).unwrap();
+        assert_ne!(p.payload(), b"1234567");
+        client_session.decrypt(&mut p).unwrap();
+        assert_eq!(p.payload(), b"1234567");
+        assert!(client_session.is_confirmed());
+
+        // a different secret derives different keys
+        let client = SessionKeyExchange::new().unwrap();
+        let server = SessionKeyExchange::new().unwrap();
+        let client_pub = client.public_key().to_vec();
+        let client_session = client
+            .derive(server.public_key(), "secret", "aes-gcm", true)
+            .unwrap();
+        let server_session = server
+            .derive(&client_pub, "other", "aes-gcm", false)
+            .unwrap();
+        let mut p = packet(2, 1);
+        server_session.encrypt(&mut p).unwrap();
+        assert!(client_session.decrypt(&mut p).is_err());
+    }
+
+    #[test]
+    fn test_session_encryptor() {
+        let client = SessionKeyExchange::new().unwrap();
+        let server = SessionKeyExchange::new().unwrap();
+        let client_pub = client.public_key().to_vec();
+        let client_session = client
+            .derive(server.public_key(), "secret", "aes-gcm", true)
+            .unwrap();
+        let server_session = server
+            .derive(&client_pub, "secret", "aes-gcm", false)
+            .unwrap();
+
+        let client_ctx = get_mock_global_ctx();
+        let client_enc = SessionEncryptor::new(Arc::new(NullCipher), &client_ctx);
+        client_enc.add_session(2, &client_session);
+        let server_enc = SessionEncryptor::new(Arc::new(NullCipher), &get_mock_global_ctx());
+        server_enc.add_session(1, &server_session);
+        let network_key_tx = || {
+            let label_set = LabelSet::new()
+                .with_label_type(LabelType::NetworkName(client_ctx.get_network_name()));
+            client_ctx
+                .stats_manager()
+                .get_metric(MetricName::TrafficPacketsNetworkKeyTx, &label_set)
+                .map(|x| x.value)
+                .unwrap_or(0)
+        };
+
+        // not confirmed yet, the client falls back to the network key
+        let mut p = packet(1, 2);
+        client_enc.encrypt(&mut p).unwrap();
+        assert!(!p.peer_manager_header().unwrap().is_encrypted());
+        assert_eq!(network_key_tx(), 1);
+        server_enc.decrypt(&mut p).unwrap();
+
+        let mut p = packet(2, 1);
+        server_enc.encrypt(&mut p).unwrap();
+        assert!(p.peer_manager_header().unwrap().is_encrypted());
+        client_enc.decrypt(&mut p).unwrap();
+        assert_eq!(p.payload(), b"1234567");
+
+        let mut p = packet(1, 2);
+        client_enc.encrypt(&mut p).unwrap();
+        assert!(p.peer_manager_header().unwrap().is_encrypted());
+        assert_eq!(network_key_tx(), 1);
+        server_enc.decrypt(&mut p).unwrap();
+        assert_eq!(p.payload(), b"1234567");
+
+        // the session goes away with its connection
+        drop(server_session);
+        let mut p = packet(2, 1);
+        server_enc.encrypt(&mut p).unwrap();
+        assert!(!p.peer_manager_header().unwrap().is_encrypted());
+    }
+}
@@ -0,0 +1,570 @@
+// picks the exit node traffic to the internet goes through.
+//
+// every configured exit node is probed over peer rpc, the probe tells whether the node forwards
+// traffic at all and whether it reaches the internet itself, its round trip time is used as the
+// latency of the node. the fastest healthy node is used and kept until it goes down or another
+// one is clearly faster. traffic of an address family the active node can't reach goes to the
+// fastest healthy node that reaches it. if no node is known to be healthy (not probed yet, or nodes that don't
+// answer the probe) the first reachable node in config order is used like before.
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use crossbeam::atomic::AtomicCell;
+use tokio::task::JoinSet;
+
+use crate::{
+    common::{
+        global_ctx::{ArcGlobalCtx, GlobalCtxEvent},
+        PeerId,
+    },
+    proto::{
+        cli::ExitNodeStatus,
+        peer_rpc::{
+            ExitNodeRpc, ExitNodeRpcClientFactory, ExitNodeRpcServer, ProbeExitNodeRequest,
+            ProbeExitNodeResponse,
+        },
+        rpc_types::{self, controller::BaseController},
+    },
+};
+
+use super::{peer_map::PeerMap, peer_rpc::PeerRpcManager};
+
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+const PROBE_TIMEOUT_MS: i32 = 2000;
+// a node is only marked down after this many failed probes in a row
+const FAILURES_BEFORE_DOWN: u32 = 2;
+// another node has to be this much faster before traffic is moved to it
+const SWITCH_MIN_GAIN_MS: u32 = 20;
+const SWITCH_MIN_GAIN_PERCENT: u32 = 30;
+
+// the exit node side checks whether it reaches the internet in the background this often,
+// probes are answered from the last result
+const INTERNET_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const INTERNET_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+// used when exit_node_check_targets is not configured. anycast resolvers of several operators,
+// so the check does not depend on a single provider being reachable from the exit node's region
+const DEFAULT_INTERNET_CHECK_TARGETS: [&str; 6] = [
+    "1.1.1.1:443",
+    "9.9.9.9:443",
+    "223.5.5.5:443",
+    "[2606:4700:4700::1111]:443",
+    "[2620:fe::fe]:443",
+    "[2400:3200::1]:443",
+];
+
+#[derive(Debug, Clone, Default)]
+struct ExitNodeHealth {
+    addr: Option<IpAddr>,
+    peer_id: Option<PeerId>,
+    healthy: bool,
+    latency_ms: Option<u32>,
+    ipv4_reachable: bool,
+    ipv6_reachable: bool,
+    failures: u32,
+    error: Option<String>,
+}
+
+impl ExitNodeHealth {
+    fn record_success(&mut self, resp: &ProbeExitNodeResponse, rtt_ms: u32) {
+        let error = if !resp.exit_node_enabled {
+            Some("not an exit node")
+        } else if !resp.ipv4_reachable && !resp.ipv6_reachable {
+            Some("exit node can't reach the internet")
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            self.record_failure(error.to_string());
+            return;
+        }
+
+        // smooth the latency, a single slow probe should not move the traffic
+        self.latency_ms = Some(match self.latency_ms {
+            Some(prev) => (prev * 7 + rtt_ms * 3) / 10,
+            None => rtt_ms,
+        });
+        self.ipv4_reachable = resp.ipv4_reachable;
+        self.ipv6_reachable = resp.ipv6_reachable;
+        self.healthy = true;
+        self.failures = 0;
+        self.error = None;
+    }
+
+    fn record_failure(&mut self, error: String) {
+        self.failures += 1;
+        if self.failures >= FAILURES_BEFORE_DOWN {
+            self.healthy = false;
+            self.latency_ms = None;
+        }
+        self.error = Some(error);
+    }
+}
+
+// the index of the exit node to use, current is kept unless it is down or another node is
+// clearly faster
+fn choose_exit_node(current: Option<usize>, nodes: &[ExitNodeHealth]) -> Option<usize> {
+    let best = nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| n.healthy && n.peer_id.is_some())
+        .min_by_key(|(idx, n)| (n.latency_ms.unwrap_or(u32::MAX), *idx))
+        .map(|(idx, _)| idx)?;
+
+    let current = current.filter(|c| nodes[*c].healthy && nodes[*c].peer_id.is_some());
+    let Some(current) = current else {
+        return Some(best);
+    };
+    let cur_latency = nodes[current].latency_ms.unwrap_or(u32::MAX) as u64;
+    let best_latency = nodes[best].latency_ms.unwrap_or(u32::MAX) as u64;
+    if best_latency + SWITCH_MIN_GAIN_MS as u64 <= cur_latency
+        && best_latency * 100 <= cur_latency * (100 - SWITCH_MIN_GAIN_PERCENT) as u64
+    {
+        Some(best)
+    } else {
+        Some(current)
+    }
+}
+
+fn reaches(node: &ExitNodeHealth, ipv6: bool) -> bool {
+    node.healthy
+        && node.peer_id.is_some()
+        && if ipv6 {
+            node.ipv6_reachable
+        } else {
+            node.ipv4_reachable
+        }
+}
+
+// the node used for one address family, the active one if it reaches that family, otherwise
+// the fastest healthy node that does
+fn choose_exit_node_for_family(
+    active: Option<usize>,
+    nodes: &[ExitNodeHealth],
+    ipv6: bool,
+) -> Option<usize> {
+    if let Some(active) = active.filter(|idx| reaches(&nodes[*idx], ipv6)) {
+        return Some(active);
+    }
+    nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| reaches(n, ipv6))
+        .min_by_key(|(idx, n)| (n.latency_ms.unwrap_or(u32::MAX), *idx))
+        .map(|(idx, _)| idx)
+}
+
+// (ipv4 targets, ipv6 targets)
+fn internet_check_targets(global_ctx: &ArcGlobalCtx) -> (Vec<SocketAddr>, Vec<SocketAddr>) {
+    let mut targets = global_ctx.config.get_exit_node_check_targets();
+    if targets.is_empty() {
+        targets = DEFAULT_INTERNET_CHECK_TARGETS
+            .iter()
+            .filter_map(|t| t.parse().ok())
+            .collect();
+    }
+    targets.into_iter().partition(|t| t.is_ipv4())
+}
+
+async fn can_reach_any(targets: Vec<SocketAddr>) -> bool {
+    let mut tasks = JoinSet::new();
+    for addr in targets {
+        tasks.spawn(async move {
+            matches!(
+                tokio::time::timeout(INTERNET_CHECK_TIMEOUT, tokio::net::TcpStream::connect(addr))
+                    .await,
+                Ok(Ok(_))
+            )
+        });
+    }
+    while let Some(ret) = tasks.join_next().await {
+        if ret.unwrap_or(false) {
+            return true;
+        }
+    }
+    false
+}
+
+pub struct ExitNodeManager {
+    my_peer_id: PeerId,
+    global_ctx: ArcGlobalCtx,
+    peer_map: Weak<PeerMap>,
+    peer_rpc_mgr: Weak<PeerRpcManager>,
+
+    exit_nodes: Vec<IpAddr>,
+    nodes: Mutex<Vec<ExitNodeHealth>>,
+    active: AtomicCell<Option<usize>>,
+    // the peer used for ipv4 and ipv6 traffic, only valid while a node is active
+    active_peer_id_v4: AtomicCell<Option<PeerId>>,
+    active_peer_id_v6: AtomicCell<Option<PeerId>>,
+
+    // exit node side, (ipv4 reachable, ipv6 reachable) of the last check
+    internet_check: AtomicCell<Option<(bool, bool)>>,
+}
+
+impl ExitNodeManager {
+    pub fn new(
+        my_peer_id: PeerId,
+        global_ctx: ArcGlobalCtx,
+        peer_map: Weak<PeerMap>,
+        peer_rpc_mgr: Weak<PeerRpcManager>,
+    ) -> Arc<Self> {
+        let exit_nodes = global_ctx.config.get_exit_nodes();
+        let nodes = exit_nodes
+            .iter()
+            .map(|addr| ExitNodeHealth {
+                addr: Some(*addr),
+                ..Default::default()
+            })
+            .collect();
+        Arc::new(ExitNodeManager {
+            my_peer_id,
+            global_ctx,
+            peer_map,
+            peer_rpc_mgr,
+            exit_nodes,
+            nodes: Mutex::new(nodes),
+            active: AtomicCell::new(None),
+            active_peer_id_v4: AtomicCell::new(None),
+            active_peer_id_v6: AtomicCell::new(None),
+            internet_check: AtomicCell::new(None),
+        })
+    }
+
+    pub fn register_rpc_server(self: &Arc<Self>, peer_rpc_mgr: &PeerRpcManager) {
+        peer_rpc_mgr.rpc_server().registry().register(
+            ExitNodeRpcServer::new(ExitNodeRpcService(Arc::downgrade(self))),
+            &self.global_ctx.get_network_name(),
+        );
+    }
+
+    async fn resolve_peer_id(peer_map: &PeerMap, addr: &IpAddr) -> Option<PeerId> {
+        match addr {
+            IpAddr::V4(addr) => peer_map.get_peer_id_by_ipv4(addr).await,
+            IpAddr::V6(addr) => peer_map.get_peer_id_by_ipv6(addr).await,
+        }
+    }
+
+    /// The peer traffic to the internet address `dst` is sent to.
+    pub async fn get_exit_peer_id(&self, peer_map: &PeerMap, dst: &IpAddr) -> Option<PeerId> {
+        if self.active.load().is_some() {
+            return match dst {
+                IpAddr::V4(_) => self.active_peer_id_v4.load(),
+                IpAddr::V6(_) => self.active_peer_id_v6.load(),
+            };
+        }
+        // no node is known to be healthy, use the config order
+        for addr in &self.exit_nodes {
+            if let Some(peer_id) = Self::resolve_peer_id(peer_map, addr).await {
+                return Some(peer_id);
+            }
+        }
+        None
+    }
+
+    async fn probe(&self, peer_id: PeerId) -> anyhow::Result<(ProbeExitNodeResponse, u32)> {
+        let peer_rpc_mgr = self
+            .peer_rpc_mgr
+            .upgrade()
+            .ok_or_else(|| anyhow!("peer manager is stopped"))?;
+        let stub = peer_rpc_mgr
+            .rpc_client()
+            .scoped_client::<ExitNodeRpcClientFactory<BaseController>>(
+                self.my_peer_id,
+                peer_id,
+                self.global_ctx.get_network_name(),
+            );
+        let ctrl = BaseController {
+            timeout_ms: PROBE_TIMEOUT_MS,
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let resp = stub
+            .probe_exit_node(ctrl, ProbeExitNodeRequest::default())
+            .await?;
+        Ok((resp, start.elapsed().as_millis() as u32))
+    }
+
+    /// Probes all exit nodes once and switches the active one if needed.
+    pub async fn probe_all(&self) {
+        let Some(peer_map) = self.peer_map.upgrade() else {
+            return;
+        };
+
+        let mut peer_ids = Vec::with_capacity(self.exit_nodes.len());
+        for addr in &self.exit_nodes {
+            peer_ids.push(Self::resolve_peer_id(&peer_map, addr).await);
+        }
+        drop(peer_map);
+
+        let probes = futures::future::join_all(peer_ids.iter().map(|peer_id| async move {
+            match peer_id {
+                Some(peer_id) => Some(self.probe(*peer_id).await),
+                None => None,
+            }
+        }))
+        .await;
+
+        let mut events = vec![];
+        {
+            let mut nodes = self.nodes.lock().unwrap();
+            for ((node, peer_id), probe) in nodes.iter_mut().zip(peer_ids).zip(probes) {
+                let was_healthy = node.healthy;
+                node.peer_id = peer_id;
+                match probe {
+                    Some(Ok((resp, rtt_ms))) => node.record_success(&resp, rtt_ms),
+                    Some(Err(e)) => node.record_failure(format!("{:#}", e)),
+                    None => node.record_failure("no route to exit node".to_string()),
+                }
+                if was_healthy && !node.healthy {
+                    let addr = node.addr.unwrap();
+                    let error = node.error.clone().unwrap_or_default();
+                    tracing::warn!(?addr, ?error, "exit node is down");
+                    events.push(GlobalCtxEvent::ExitNodeDown(addr, error));
+                }
+            }
+
+            let old = self.active.load();
+            let new = choose_exit_node(old, &nodes);
+            let peer_id_of = |ipv6| {
+                choose_exit_node_for_family(new, &nodes, ipv6).and_then(|idx| nodes[idx].peer_id)
+            };
+            self.active_peer_id_v4.store(peer_id_of(false));
+            self.active_peer_id_v6.store(peer_id_of(true));
+            self.active.store(new);
+            if old != new {
+                let old_addr = old.and_then(|idx| nodes[idx].addr);
+                let new_addr = new.and_then(|idx| nodes[idx].addr);
+                tracing::info!(?old_addr, ?new_addr, "exit node changed");
+                events.push(GlobalCtxEvent::ExitNodeChanged(old_addr, new_addr));
+            }
+        }
+
+        for event in events {
+            self.global_ctx.issue_event(event);
+        }
+    }
+
+    pub async fn run_probe_routine(self: &Arc<Self>, tasks: &mut JoinSet<()>) {
+        if self.exit_nodes.is_empty() {
+            return;
+        }
+        let this = Arc::downgrade(self);
+        tasks.spawn(async move {
+            loop {
+                let Some(this) = this.upgrade() else {
+                    return;
+                };
+                this.probe_all().await;
+                drop(this);
+                tokio::time::sleep(PROBE_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Keeps the answer to probes of other nodes up to date, the check takes up to
+    /// INTERNET_CHECK_TIMEOUT so it is not done while a probe waits.
+    pub async fn run_internet_check_routine(self: &Arc<Self>, tasks: &mut JoinSet<()>) {
+        let this = Arc::downgrade(self);
+        tasks.spawn(async move {
+            loop {
+                let Some(this) = this.upgrade() else {
+                    return;
+                };
+                let result = if this.global_ctx.enable_exit_node() {
+                    let (v4_targets, v6_targets) = internet_check_targets(&this.global_ctx);
+                    let (v4, v6) =
+                        tokio::join!(can_reach_any(v4_targets), can_reach_any(v6_targets));
+                    Some((v4, v6))
+                } else {
+                    None
+                };
+                this.internet_check.store(result);
+                drop(this);
+                tokio::time::sleep(INTERNET_CHECK_INTERVAL).await;
+            }
+        });
+    }
+
+    pub fn list_status(&self) -> Vec<ExitNodeStatus> {
+        let active = self.active.load();
+        let nodes = self.nodes.lock().unwrap();
+        nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, n)| ExitNodeStatus {
+                addr: n.addr.map(|a| a.to_string()).unwrap_or_default(),
+                peer_id: n.peer_id,
+                healthy: n.healthy,
+                active: active == Some(idx),
+                latency_ms: n.latency_ms,
+                ipv4_reachable: n.ipv4_reachable,
+                ipv6_reachable: n.ipv6_reachable,
+                error: n.error.clone(),
+            })
+            .collect()
+    }
+
+    fn handle_probe(&self) -> ProbeExitNodeResponse {
+        if !self.global_ctx.enable_exit_node() {
+            return ProbeExitNodeResponse::default();
+        }
+
+        // not checked yet, the prober tolerates a failed probe
+        let (ipv4_reachable, ipv6_reachable) = self.internet_check.load().unwrap_or_default();
+        ProbeExitNodeResponse {
+            exit_node_enabled: true,
+            ipv4_reachable,
+            ipv6_reachable,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ExitNodeRpcService(Weak<ExitNodeManager>);
+
+#[async_trait::async_trait]
+impl ExitNodeRpc for ExitNodeRpcService {
+    type Controller = BaseController;
+
+    async fn probe_exit_node(
+        &self,
+        _: BaseController,
+        _req: ProbeExitNodeRequest,
+    ) -> rpc_types::error::Result<ProbeExitNodeResponse> {
+        let mgr = self
+            .0
+            .upgrade()
+            .ok_or_else(|| anyhow!("peer manager is stopped"))?;
+        Ok(mgr.handle_probe())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        common::global_ctx::tests::get_mock_global_ctx,
+        peers::{
+            create_packet_recv_chan,
+            peer_manager::{PeerManager, RouteAlgoType},
+            tests::{connect_peer_manager, create_mock_peer_manager, wait_route_appear},
+        },
+    };
+
+    use super::*;
+
+    fn healthy(peer_id: PeerId, latency_ms: u32) -> ExitNodeHealth {
+        ExitNodeHealth {
+            peer_id: Some(peer_id),
+            healthy: true,
+            latency_ms: Some(latency_ms),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn choose_exit_node_by_health_and_latency() {
+        let mut nodes = vec![healthy(1, 100), healthy(2, 80), ExitNodeHealth::default()];
+        assert_eq!(choose_exit_node(None, &nodes), Some(1));
+        // not faster enough to move the traffic
+        assert_eq!(choose_exit_node(Some(0), &nodes), Some(0));
+
+        nodes[1].latency_ms = Some(40);
+        assert_eq!(choose_exit_node(Some(0), &nodes), Some(1));
+
+        // failover
+        nodes[1].healthy = false;
+        assert_eq!(choose_exit_node(Some(1), &nodes), Some(0));
+        nodes[0].healthy = false;
+        assert_eq!(choose_exit_node(Some(0), &nodes), None);
+    }
+
+    #[test]
+    fn choose_exit_node_by_address_family() {
+        let mut nodes = vec![healthy(1, 10), healthy(2, 50)];
+        nodes[0].ipv4_reachable = true;
+        nodes[1].ipv4_reachable = true;
+        nodes[1].ipv6_reachable = true;
+        assert_eq!(choose_exit_node_for_family(Some(0), &nodes, false), Some(0));
+        // the active node has no ipv6, ipv6 traffic goes to the node that has
+        assert_eq!(choose_exit_node_for_family(Some(0), &nodes, true), Some(1));
+
+        nodes[1].healthy = false;
+        assert_eq!(choose_exit_node_for_family(Some(0), &nodes, true), None);
+    }
+
+    #[test]
+    fn exit_node_goes_down_after_failures() {
+        let mut node = healthy(1, 10);
+        node.record_failure("timeout".to_string());
+        assert!(node.healthy);
+        node.record_failure("timeout".to_string());
+        assert!(!node.healthy);
+
+        let resp = ProbeExitNodeResponse {
+            exit_node_enabled: true,
+            ipv4_reachable: true,
+            ipv6_reachable: false,
+        };
+        node.record_success(&resp, 30);
+        assert!(node.healthy);
+        assert_eq!(node.latency_ms, Some(30));
+        assert_eq!(node.error, None);
+    }
+
+    #[test]
+    fn internet_check_targets_from_config() {
+        let global_ctx = get_mock_global_ctx();
+        let (v4, v6) = internet_check_targets(&global_ctx);
+        assert_eq!(v4.len() + v6.len(), DEFAULT_INTERNET_CHECK_TARGETS.len());
+        assert!(!v4.is_empty() && !v6.is_empty());
+
+        global_ctx
+            .config
+            .set_exit_node_check_targets(vec!["192.0.2.1:80".parse().unwrap()]);
+        let (v4, v6) = internet_check_targets(&global_ctx);
+        assert_eq!(v4, vec!["192.0.2.1:80".parse::<SocketAddr>().unwrap()]);
+        assert!(v6.is_empty());
+    }
+
+    #[tokio::test]
+    async fn probe_peer_that_is_not_exit_node() {
+        let peer_mgr_b = create_mock_peer_manager().await;
+        let exit_ip = "10.144.144.2".parse().unwrap();
+        peer_mgr_b
+            .get_global_ctx()
+            .set_ipv4(Some("10.144.144.2/24".parse().unwrap()));
+
+        let global_ctx = get_mock_global_ctx();
+        global_ctx.config.set_exit_nodes(vec![exit_ip]);
+        let (s, _r) = create_packet_recv_chan();
+        let peer_mgr_a = Arc::new(PeerManager::new(RouteAlgoType::Ospf, global_ctx, s));
+        peer_mgr_a.run().await.unwrap();
+
+        connect_peer_manager(peer_mgr_a.clone(), peer_mgr_b.clone()).await;
+        wait_route_appear(peer_mgr_a.clone(), peer_mgr_b.clone())
+            .await
+            .unwrap();
+
+        let exit_nodes = peer_mgr_a.get_exit_node_manager();
+        exit_nodes.probe_all().await;
+        let status = exit_nodes.list_status();
+        assert_eq!(status.len(), 1);
+        assert_eq!(status[0].peer_id, Some(peer_mgr_b.my_peer_id()));
+        assert_eq!(status[0].error.as_deref(), Some("not an exit node"));
+        assert!(!status[0].active);
+
+        // nothing is known to be healthy, the first reachable exit node is still used
+        let (dst_peers, is_exit_node) = peer_mgr_a
+            .get_msg_dst_peer(&"8.8.8.8".parse().unwrap())
+            .await;
+        assert_eq!(dst_peers, vec![peer_mgr_b.my_peer_id()]);
+        assert!(is_exit_node);
+    }
+}
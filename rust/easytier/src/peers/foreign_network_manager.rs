@@ -49,6 +49,7 @@ use super::{
     peer_rpc_service::DirectConnectorManagerRpcServer,
     recv_packet_from_chan,
     route_trait::NextHopPolicy,
+    secret_rotation::DEFAULT_GRACE_PERIOD,
     PacketRecvChan, PacketRecvChanReceiver, PUBLIC_SERVER_HOSTNAME_PREFIX,
 };
 
@@ -155,6 +156,32 @@ impl ForeignNetworkEntry {
         }
     }
 
+    // members rotating the network secret come back with the digest of the new secret. a
+    // member that already joined with the current digest vouches for the new one, both are
+    // accepted for the grace period so members that did not rotate yet can still join.
+    fn accept_network_identity(&self, identity: &NetworkIdentity, peer_id: PeerId) -> bool {
+        if self.global_ctx.match_network_identity(identity).is_some() {
+            return true;
+        }
+        if identity.network_name != self.network.network_name || !self.peer_map.has_peer(peer_id) {
+            return false;
+        }
+
+        let previous = self.global_ctx.get_network_identity();
+        tracing::info!(
+            ?peer_id,
+            network = ?identity,
+            ?previous,
+            "member switched network secret, accepting both during the grace period"
+        );
+        self.global_ctx
+            .config
+            .set_network_identity(identity.clone());
+        self.global_ctx
+            .set_alternate_network_identity(previous, DEFAULT_GRACE_PERIOD);
+        true
+    }
+
     fn build_foreign_global_ctx(
         network: &NetworkIdentity,
         global_ctx: ArcGlobalCtx,
@@ -590,7 +617,8 @@ impl ForeignNetworkManager {
 
         let _g = entry.lock.lock().await;
 
-        if entry.network != peer_conn.get_network_identity()
+        if !entry
+            .accept_network_identity(&peer_conn.get_network_identity(), peer_conn.get_peer_id())
             || entry.my_peer_id != peer_conn.get_my_peer_id()
         {
             if new_added {
@@ -0,0 +1,667 @@
+// node identities, an optional second factor on top of the network secret.
+// every node owns an ed25519 key pair, a peer of the same network is accepted only if
+// its public key is in the allowlist or it presents a certificate signed by the network ca.
+//
+// the handshake takes three messages when both sides use identities:
+// 1. client -> server: key, certificate and a random nonce
+// 2. server -> client: the same, plus a signature over both nonces
+// 3. client -> server: a signature over both nonces
+// so neither side can be impersonated by replaying an old handshake.
+//
+// the handshake only covers directly connected peers. members that are reached through
+// relays, e.g. when they meet on a public server of another network which checks nothing,
+// answer a challenge over peer rpc instead (PeerIdentityVerifier), no data is sent to or
+// accepted from them until they did.
+
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::anyhow;
+use base64::{prelude::BASE64_STANDARD, Engine as _};
+use dashmap::{DashMap, DashSet};
+use prost::Message as _;
+use rand::RngCore as _;
+use ring::signature::{Ed25519KeyPair, KeyPair as _, UnparsedPublicKey, ED25519};
+use tokio::task::JoinSet;
+
+use crate::{
+    common::{config::NodeIdentityConfig, global_ctx::ArcGlobalCtx, PeerId},
+    proto::{
+        peer_rpc::{
+            HandshakeRequest, NodeCertificate, NodeIdentityRpc, NodeIdentityRpcClientFactory,
+            NodeIdentityRpcServer, ProveNodeIdentityRequest, ProveNodeIdentityResponse,
+        },
+        rpc_types::{self, controller::BaseController},
+    },
+};
+
+use super::{peer_map::PeerMap, peer_rpc::PeerRpcManager};
+
+const PUBLIC_KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 32;
+const CERT_CONTEXT: &[u8] = b"easytier-node-certificate";
+const HANDSHAKE_CONTEXT: &[u8] = b"easytier-node-handshake";
+const PROOF_CONTEXT: &[u8] = b"easytier-node-proof";
+
+const VERIFY_INTERVAL: Duration = Duration::from_secs(1);
+const VERIFY_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+const VERIFY_TIMEOUT_MS: i32 = 3000;
+
+fn decode_key(key: &str, what: &str) -> anyhow::Result<[u8; PUBLIC_KEY_LEN]> {
+    BASE64_STANDARD
+        .decode(key.trim())
+        .ok()
+        .and_then(|key| key.try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("invalid {}: {}", what, key))
+}
+
+fn key_pair_from_seed(private_key: &str) -> anyhow::Result<Ed25519KeyPair> {
+    let seed = decode_key(private_key, "node private key")?;
+    Ed25519KeyPair::from_seed_unchecked(&seed)
+        .map_err(|e| anyhow::anyhow!("invalid node private key: {}", e))
+}
+
+/// A new random private key, base64 encoded.
+pub fn generate_private_key() -> String {
+    let mut seed = [0u8; PUBLIC_KEY_LEN];
+    rand::thread_rng().fill_bytes(&mut seed);
+    BASE64_STANDARD.encode(seed)
+}
+
+/// The base64 public key of a base64 private key.
+pub fn public_key_of(private_key: &str) -> anyhow::Result<String> {
+    let key_pair = key_pair_from_seed(private_key)?;
+    Ok(BASE64_STANDARD.encode(key_pair.public_key().as_ref()))
+}
+
+fn certificate_message(cert: &NodeCertificate) -> Vec<u8> {
+    let unsigned = NodeCertificate {
+        signature: vec![],
+        ..cert.clone()
+    };
+    let mut msg = CERT_CONTEXT.to_vec();
+    msg.extend_from_slice(&unsigned.encode_to_vec());
+    msg
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Sign the public key of a member with the private key of the network ca,
+/// returns the base64 certificate to put into the config of the member.
+pub fn sign_certificate(
+    ca_private_key: &str,
+    public_key: &str,
+    network_name: &str,
+    valid_for: Option<Duration>,
+) -> anyhow::Result<String> {
+    let ca = key_pair_from_seed(ca_private_key)?;
+    let mut cert = NodeCertificate {
+        public_key: decode_key(public_key, "node public key")?.to_vec(),
+        network_name: network_name.to_string(),
+        not_after: valid_for
+            .map(|d| unix_now() + d.as_secs() as i64)
+            .unwrap_or_default(),
+        signature: vec![],
+    };
+    cert.signature = ca.sign(&certificate_message(&cert)).as_ref().to_vec();
+    Ok(BASE64_STANDARD.encode(cert.encode_to_vec()))
+}
+
+/// The key pair of this node and the keys it trusts, built from the config.
+pub struct NodeIdentity {
+    key_pair: Ed25519KeyPair,
+    certificate: Option<NodeCertificate>,
+    allowed_public_keys: HashSet<[u8; PUBLIC_KEY_LEN]>,
+    ca_public_key: Option<[u8; PUBLIC_KEY_LEN]>,
+}
+
+impl std::fmt::Debug for NodeIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeIdentity")
+            .field("public_key", &BASE64_STANDARD.encode(self.public_key()))
+            .field("allowed_public_keys", &self.allowed_public_keys.len())
+            .field("has_ca", &self.ca_public_key.is_some())
+            .finish()
+    }
+}
+
+impl NodeIdentity {
+    pub fn new(cfg: &NodeIdentityConfig) -> anyhow::Result<Self> {
+        let key_pair = key_pair_from_seed(&cfg.private_key)?;
+        let allowed_public_keys = cfg
+            .allowed_public_keys
+            .iter()
+            .map(|key| decode_key(key, "allowed public key"))
+            .collect::<Result<HashSet<_>, _>>()?;
+        let ca_public_key = cfg
+            .ca_public_key
+            .as_ref()
+            .map(|key| decode_key(key, "ca public key"))
+            .transpose()?;
+        if allowed_public_keys.is_empty() && ca_public_key.is_none() {
+            anyhow::bail!("node identity needs allowed public keys or a ca public key");
+        }
+
+        let certificate = cfg
+            .certificate
+            .as_ref()
+            .map(|cert| {
+                let cert = BASE64_STANDARD
+                    .decode(cert.trim())
+                    .map_err(anyhow::Error::from)
+                    .and_then(|cert| Ok(NodeCertificate::decode(cert.as_slice())?))
+                    .map_err(|e| anyhow::anyhow!("invalid node certificate: {}", e))?;
+                if cert.public_key != key_pair.public_key().as_ref() {
+                    anyhow::bail!("node certificate is not issued for the node private key");
+                }
+                Ok(cert)
+            })
+            .transpose()?;
+
+        Ok(Self {
+            key_pair,
+            certificate,
+            allowed_public_keys,
+            ca_public_key,
+        })
+    }
+
+    pub fn public_key(&self) -> &[u8] {
+        self.key_pair.public_key().as_ref()
+    }
+
+    /// Check that a key is a member of the network, by the allowlist or its certificate.
+    fn check_member(
+        &self,
+        network_name: &str,
+        public_key: &[u8],
+        certificate: Option<&NodeCertificate>,
+    ) -> anyhow::Result<()> {
+        let Ok(key) = <[u8; PUBLIC_KEY_LEN]>::try_from(public_key) else {
+            anyhow::bail!("peer did not present a node identity");
+        };
+        if self.allowed_public_keys.contains(&key) {
+            return Ok(());
+        }
+
+        let (Some(ca_public_key), Some(cert)) = (self.ca_public_key, certificate) else {
+            anyhow::bail!(
+                "node identity {} is not allowed in this network",
+                BASE64_STANDARD.encode(key)
+            );
+        };
+        if cert.public_key != key || cert.network_name != network_name {
+            anyhow::bail!("node certificate is issued for another key or network");
+        }
+        if cert.not_after != 0 && cert.not_after < unix_now() {
+            anyhow::bail!("node certificate expired at {}", cert.not_after);
+        }
+        UnparsedPublicKey::new(&ED25519, ca_public_key)
+            .verify(&certificate_message(cert), &cert.signature)
+            .map_err(|_| anyhow::anyhow!("node certificate is not signed by the network ca"))
+    }
+}
+
+fn proof_message(network_name: &str, challenger: PeerId, prover: PeerId, nonce: &[u8]) -> Vec<u8> {
+    let mut msg = PROOF_CONTEXT.to_vec();
+    msg.extend_from_slice(network_name.as_bytes());
+    msg.extend_from_slice(&challenger.to_be_bytes());
+    msg.extend_from_slice(&prover.to_be_bytes());
+    msg.extend_from_slice(nonce);
+    msg
+}
+
+impl NodeIdentity {
+    /// Answer the challenge of a peer that is not directly connected.
+    fn prove(
+        &self,
+        network_name: &str,
+        my_peer_id: PeerId,
+        req: &ProveNodeIdentityRequest,
+    ) -> ProveNodeIdentityResponse {
+        let msg = proof_message(network_name, req.peer_id, my_peer_id, &req.nonce);
+        ProveNodeIdentityResponse {
+            public_key: self.public_key().to_vec(),
+            certificate: self.certificate.clone(),
+            signature: self.key_pair.sign(&msg).as_ref().to_vec(),
+        }
+    }
+
+    /// Check the answer of `prover` to our challenge.
+    fn verify_proof(
+        &self,
+        network_name: &str,
+        my_peer_id: PeerId,
+        prover: PeerId,
+        nonce: &[u8],
+        rsp: &ProveNodeIdentityResponse,
+    ) -> anyhow::Result<()> {
+        self.check_member(network_name, &rsp.public_key, rsp.certificate.as_ref())?;
+        let msg = proof_message(network_name, my_peer_id, prover, nonce);
+        UnparsedPublicKey::new(&ED25519, &rsp.public_key)
+            .verify(&msg, &rsp.signature)
+            .map_err(|_| anyhow!("invalid node identity proof"))
+    }
+}
+
+fn handshake_transcript(
+    signer_is_client: bool,
+    network_name: &str,
+    client: &HandshakeRequest,
+    server: &HandshakeRequest,
+) -> Vec<u8> {
+    let mut msg = HANDSHAKE_CONTEXT.to_vec();
+    msg.push(signer_is_client as u8);
+    msg.extend_from_slice(network_name.as_bytes());
+    for side in [client, server] {
+        msg.extend_from_slice(&side.my_peer_id.to_be_bytes());
+        msg.extend_from_slice(&side.identity_public_key);
+        msg.extend_from_slice(&side.identity_nonce);
+    }
+    msg
+}
+
+/// The identity state of one handshake.
+pub struct HandshakeIdentity {
+    identity: std::sync::Arc<NodeIdentity>,
+    nonce: [u8; NONCE_LEN],
+}
+
+impl HandshakeIdentity {
+    pub fn new(identity: std::sync::Arc<NodeIdentity>) -> Self {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        Self { identity, nonce }
+    }
+
+    /// Fill the identity of this node into a handshake message, signed if the handshake
+    /// of the peer is already known.
+    pub fn fill(
+        &self,
+        req: &mut HandshakeRequest,
+        peer: Option<&HandshakeRequest>,
+        is_client: bool,
+    ) {
+        req.identity_public_key = self.identity.public_key().to_vec();
+        req.identity_certificate = self.identity.certificate.clone();
+        req.identity_nonce = self.nonce.to_vec();
+
+        if let Some(peer) = peer {
+            let (client, server) = if is_client {
+                (&*req, peer)
+            } else {
+                (peer, &*req)
+            };
+            let msg = handshake_transcript(is_client, &req.network_name, client, server);
+            req.identity_signature = self.identity.key_pair.sign(&msg).as_ref().to_vec();
+        }
+    }
+
+    /// Check that the peer presents an identity trusted by this node, before anything is signed.
+    pub fn check_peer(&self, peer: &HandshakeRequest) -> anyhow::Result<()> {
+        self.identity.check_member(
+            &peer.network_name,
+            &peer.identity_public_key,
+            peer.identity_certificate.as_ref(),
+        )?;
+        if peer.identity_nonce.len() != NONCE_LEN {
+            anyhow::bail!("invalid node identity nonce");
+        }
+        Ok(())
+    }
+
+    /// Verify the signed handshake message of the peer.
+    pub fn verify(
+        &self,
+        my_peer_id: PeerId,
+        peer: &HandshakeRequest,
+        is_client: bool,
+    ) -> anyhow::Result<()> {
+        self.check_peer(peer)?;
+
+        // the peer signs what it received from us, so rebuild our part from our own state
+        let mine = HandshakeRequest {
+            my_peer_id,
+            identity_public_key: self.identity.public_key().to_vec(),
+            identity_nonce: self.nonce.to_vec(),
+            ..Default::default()
+        };
+        let (client, server) = if is_client {
+            (&mine, peer)
+        } else {
+            (peer, &mine)
+        };
+        let msg = handshake_transcript(!is_client, &peer.network_name, client, server);
+        UnparsedPublicKey::new(&ED25519, &peer.identity_public_key)
+            .verify(&msg, &peer.identity_signature)
+            .map_err(|_| anyhow::anyhow!("invalid node identity signature"))
+    }
+}
+
+/// Tracks which peers proved their node identity. Directly connected peers did so in the
+/// handshake, the others are challenged over peer rpc once they show up in the routes.
+pub struct PeerIdentityVerifier {
+    my_peer_id: PeerId,
+    global_ctx: ArcGlobalCtx,
+    peer_map: Weak<PeerMap>,
+    peer_rpc_mgr: Weak<PeerRpcManager>,
+
+    // refreshed by the verify routine, so the data path does not rebuild the identity
+    enabled: AtomicBool,
+    verified: DashSet<PeerId>,
+    // peers that failed the challenge, retried after VERIFY_RETRY_INTERVAL
+    failed: DashMap<PeerId, Instant>,
+}
+
+impl PeerIdentityVerifier {
+    pub fn new(
+        my_peer_id: PeerId,
+        global_ctx: ArcGlobalCtx,
+        peer_map: Weak<PeerMap>,
+        peer_rpc_mgr: Weak<PeerRpcManager>,
+    ) -> Arc<Self> {
+        let enabled = global_ctx.config.get_node_identity_config().is_some();
+        Arc::new(PeerIdentityVerifier {
+            my_peer_id,
+            global_ctx,
+            peer_map,
+            peer_rpc_mgr,
+            enabled: AtomicBool::new(enabled),
+            verified: DashSet::new(),
+            failed: DashMap::new(),
+        })
+    }
+
+    pub fn register_rpc_server(self: &Arc<Self>, peer_rpc_mgr: &PeerRpcManager) {
+        peer_rpc_mgr.rpc_server().registry().register(
+            NodeIdentityRpcServer::new(NodeIdentityRpcService(Arc::downgrade(self))),
+            &self.global_ctx.get_network_name(),
+        );
+    }
+
+    /// Whether data may be exchanged with the peer.
+    pub fn is_verified(&self, peer_id: PeerId) -> bool {
+        if !self.enabled.load(Ordering::Relaxed) || self.verified.contains(&peer_id) {
+            return true;
+        }
+        // same network peers are only added once their handshake checked the identity
+        self.peer_map
+            .upgrade()
+            .map(|peer_map| peer_map.has_peer(peer_id))
+            .unwrap_or(false)
+    }
+
+    async fn challenge(&self, identity: &NodeIdentity, peer_id: PeerId) -> anyhow::Result<()> {
+        let peer_rpc_mgr = self
+            .peer_rpc_mgr
+            .upgrade()
+            .ok_or_else(|| anyhow!("peer manager is stopped"))?;
+        let network_name = self.global_ctx.get_network_name();
+        let stub = peer_rpc_mgr
+            .rpc_client()
+            .scoped_client::<NodeIdentityRpcClientFactory<BaseController>>(
+                self.my_peer_id,
+                peer_id,
+                network_name.clone(),
+            );
+        let ctrl = BaseController {
+            timeout_ms: VERIFY_TIMEOUT_MS,
+            ..Default::default()
+        };
+
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let rsp = stub
+            .prove_node_identity(
+                ctrl,
+                ProveNodeIdentityRequest {
+                    peer_id: self.my_peer_id,
+                    nonce: nonce.to_vec(),
+                },
+            )
+            .await?;
+        identity.verify_proof(&network_name, self.my_peer_id, peer_id, &nonce, &rsp)
+    }
+
+    /// Challenges the peers in the routes that are not verified yet.
+    pub async fn verify_all(&self) {
+        let identity = match self.global_ctx.get_node_identity() {
+            Ok(Some(identity)) => identity,
+            Ok(None) => {
+                self.enabled.store(false, Ordering::Relaxed);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(?e, "invalid node identity, peers can't be verified");
+                return;
+            }
+        };
+        self.enabled.store(true, Ordering::Relaxed);
+        let Some(peer_map) = self.peer_map.upgrade() else {
+            return;
+        };
+
+        let routes = peer_map.list_route_infos().await;
+        self.verified
+            .retain(|peer_id| routes.iter().any(|r| r.peer_id == *peer_id));
+        self.failed
+            .retain(|_, failed_at| failed_at.elapsed() < VERIFY_RETRY_INTERVAL);
+        let pending = routes
+            .iter()
+            .map(|r| r.peer_id)
+            .filter(|peer_id| {
+                *peer_id != self.my_peer_id
+                    && !peer_map.has_peer(*peer_id)
+                    && !self.verified.contains(peer_id)
+                    && !self.failed.contains_key(peer_id)
+            })
+            .collect::<Vec<_>>();
+        drop(peer_map);
+
+        futures::future::join_all(pending.into_iter().map(|peer_id| {
+            let identity = identity.clone();
+            async move {
+                match self.challenge(&identity, peer_id).await {
+                    Ok(()) => {
+                        tracing::info!(?peer_id, "relayed peer proved its node identity");
+                        self.verified.insert(peer_id);
+                    }
+                    Err(e) => {
+                        tracing::warn!(?peer_id, ?e, "relayed peer failed node identity check");
+                        self.failed.insert(peer_id, Instant::now());
+                    }
+                }
+            }
+        }))
+        .await;
+    }
+
+    pub async fn run_verify_routine(self: &Arc<Self>, tasks: &mut JoinSet<()>) {
+        let this = Arc::downgrade(self);
+        tasks.spawn(async move {
+            loop {
+                let Some(this) = this.upgrade() else {
+                    return;
+                };
+                this.verify_all().await;
+                drop(this);
+                tokio::time::sleep(VERIFY_INTERVAL).await;
+            }
+        });
+    }
+
+    fn handle_prove(
+        &self,
+        req: ProveNodeIdentityRequest,
+    ) -> anyhow::Result<ProveNodeIdentityResponse> {
+        let identity = self
+            .global_ctx
+            .get_node_identity()?
+            .ok_or_else(|| anyhow!("node identity is not configured"))?;
+        if req.nonce.len() != NONCE_LEN {
+            anyhow::bail!("invalid node identity nonce");
+        }
+        Ok(identity.prove(&self.global_ctx.get_network_name(), self.my_peer_id, &req))
+    }
+}
+
+#[derive(Clone)]
+struct NodeIdentityRpcService(Weak<PeerIdentityVerifier>);
+
+#[async_trait::async_trait]
+impl NodeIdentityRpc for NodeIdentityRpcService {
+    type Controller = BaseController;
+
+    async fn prove_node_identity(
+        &self,
+        _: BaseController,
+        req: ProveNodeIdentityRequest,
+    ) -> rpc_types::error::Result<ProveNodeIdentityResponse> {
+        let verifier = self
+            .0
+            .upgrade()
+            .ok_or_else(|| anyhow!("peer manager is stopped"))?;
+        Ok(verifier.handle_prove(req)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity_cfg(private_key: &str) -> NodeIdentityConfig {
+        NodeIdentityConfig {
+            private_key: private_key.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_allowlist_and_certificate() {
+        let node_a = generate_private_key();
+        let node_b = generate_private_key();
+        let ca = generate_private_key();
+        let key_a = public_key_of(&node_a).unwrap();
+        let key_b = public_key_of(&node_b).unwrap();
+
+        // an identity without anything to trust is a config error
+        assert!(NodeIdentity::new(&identity_cfg(&node_a)).is_err());
+
+        let identity = NodeIdentity::new(&NodeIdentityConfig {
+            allowed_public_keys: vec![key_a.clone()],
+            ca_public_key: Some(public_key_of(&ca).unwrap()),
+            ..identity_cfg(&node_a)
+        })
+        .unwrap();
+        let raw_a = BASE64_STANDARD.decode(&key_a).unwrap();
+        let raw_b = BASE64_STANDARD.decode(&key_b).unwrap();
+        assert!(identity.check_member("net", &raw_a, None).is_ok());
+        assert!(identity.check_member("net", &raw_b, None).is_err());
+        assert!(identity.check_member("net", &[], None).is_err());
+
+        let decode = |cert: String| {
+            NodeCertificate::decode(BASE64_STANDARD.decode(cert).unwrap().as_slice()).unwrap()
+        };
+        let cert = decode(sign_certificate(&ca, &key_b, "net", None).unwrap());
+        assert!(identity.check_member("net", &raw_b, Some(&cert)).is_ok());
+        assert!(identity.check_member("other", &raw_b, Some(&cert)).is_err());
+
+        let mut forged = cert.clone();
+        forged.not_after = 1;
+        assert!(identity.check_member("net", &raw_b, Some(&forged)).is_err());
+
+        let other_ca = generate_private_key();
+        let cert = decode(sign_certificate(&other_ca, &key_b, "net", None).unwrap());
+        assert!(identity.check_member("net", &raw_b, Some(&cert)).is_err());
+
+        // a certificate issued for another key can not be used
+        let cert = sign_certificate(&ca, &key_b, "net", None).unwrap();
+        assert!(NodeIdentity::new(&NodeIdentityConfig {
+            allowed_public_keys: vec![key_a.clone()],
+            certificate: Some(cert),
+            ..identity_cfg(&node_a)
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_handshake_signature() {
+        let node_a = generate_private_key();
+        let node_b = generate_private_key();
+        let cfg = |private_key: &str| NodeIdentityConfig {
+            allowed_public_keys: vec![
+                public_key_of(&node_a).unwrap(),
+                public_key_of(&node_b).unwrap(),
+            ],
+            ..identity_cfg(private_key)
+        };
+        let client = HandshakeIdentity::new(NodeIdentity::new(&cfg(&node_a)).unwrap().into());
+        let server = HandshakeIdentity::new(NodeIdentity::new(&cfg(&node_b)).unwrap().into());
+        let new_req = |peer_id| HandshakeRequest {
+            my_peer_id: peer_id,
+            network_name: "net".to_string(),
+            ..Default::default()
+        };
+
+        let mut hello = new_req(1);
+        client.fill(&mut hello, None, true);
+        assert!(hello.identity_signature.is_empty());
+
+        let mut rsp = new_req(2);
+        server.fill(&mut rsp, Some(&hello), false);
+        assert!(client.verify(1, &rsp, true).is_ok());
+
+        let mut proof = new_req(1);
+        client.fill(&mut proof, Some(&rsp), true);
+        assert!(server.verify(2, &proof, false).is_ok());
+
+        // a proof made for another server nonce is rejected
+        let other_server = HandshakeIdentity::new(NodeIdentity::new(&cfg(&node_b)).unwrap().into());
+        assert!(other_server.verify(2, &proof, false).is_err());
+
+        // the server signature does not verify as a client one
+        assert!(server.verify(2, &rsp, false).is_err());
+    }
+    #[test]
+    fn test_relayed_peer_proof() {
+        let node_a = generate_private_key();
+        let node_b = generate_private_key();
+        let stranger = generate_private_key();
+        let cfg = |private_key: &str| NodeIdentityConfig {
+            allowed_public_keys: vec![
+                public_key_of(&node_a).unwrap(),
+                public_key_of(&node_b).unwrap(),
+            ],
+            ..identity_cfg(private_key)
+        };
+        let a = NodeIdentity::new(&cfg(&node_a)).unwrap();
+        let b = NodeIdentity::new(&cfg(&node_b)).unwrap();
+        let req = ProveNodeIdentityRequest {
+            peer_id: 1,
+            nonce: vec![7; NONCE_LEN],
+        };
+
+        let rsp = b.prove("net", 2, &req);
+        assert!(a.verify_proof("net", 1, 2, &req.nonce, &rsp).is_ok());
+        // the proof is bound to the nonce and both peer ids
+        assert!(a.verify_proof("net", 1, 2, &[8; NONCE_LEN], &rsp).is_err());
+        assert!(a.verify_proof("net", 1, 3, &req.nonce, &rsp).is_err());
+        assert!(a.verify_proof("net", 4, 2, &req.nonce, &rsp).is_err());
+
+        // a key that is not a member can't prove anything
+        let stranger = NodeIdentity::new(&cfg(&stranger)).unwrap();
+        let rsp = stranger.prove("net", 2, &req);
+        assert!(a.verify_proof("net", 1, 2, &req.nonce, &rsp).is_err());
+    }
+}
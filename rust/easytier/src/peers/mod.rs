@@ -1,6 +1,10 @@
 mod graph_algo;
 
 pub mod acl_filter;
+pub mod bandwidth_limiter;
+pub mod broadcast_relay;
+pub mod exit_node;
+pub mod multipath;
 pub mod peer;
 // pub mod peer_conn;
 pub mod peer_conn;
@@ -10,14 +14,19 @@ pub mod peer_map;
 pub mod peer_ospf_route;
 pub mod peer_rpc;
 pub mod peer_rpc_service;
+pub mod relay_quota;
 pub mod route_trait;
 pub mod rpc_service;
+pub mod secret_rotation;
 
 pub mod foreign_network_client;
 pub mod foreign_network_manager;
 
 pub mod encrypt;
 
+#[cfg(feature = "node-identity")]
+pub mod identity;
+
 pub mod peer_task;
 
 #[cfg(test)]
@@ -0,0 +1,364 @@
+// use several connections to the same peer together, see MultipathMode.
+//
+// striping reorders packets, which tcp inside the tunnel handles poorly on paths with very
+// different latency, so it is best suited for udp heavy traffic. duplication only copies small
+// packets (game input, voip), bulk traffic still takes the best connection.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash as _, Hasher as _},
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use rand::Rng as _;
+
+use crate::{
+    common::PeerId,
+    proto::common::MultipathMode,
+    tunnel::packet_def::{PacketType, ZCPacket},
+};
+
+use super::peer_conn::PeerConn;
+
+/// Advertised in the handshake by peers that drop duplicated packets.
+pub const MULTIPATH_DEDUP_FEATURE: &str = "multipath-dedup";
+
+/// Larger packets are not duplicated.
+pub const DUPLICATE_MAX_PAYLOAD_LEN: usize = 512;
+
+// copies arriving further apart than this are delivered twice, which the apps tolerate
+const DEDUP_WINDOW: Duration = Duration::from_secs(2);
+// used until the first pong of a connection is received
+const UNMEASURED_LATENCY_MS: f64 = 100.0;
+
+/// What the scheduler needs to know about a connection.
+pub trait MultipathConn {
+    fn latency_us(&self) -> u64;
+    fn loss_rate(&self) -> f32;
+    fn supports_multipath_dedup(&self) -> bool;
+    fn is_closed(&self) -> bool;
+}
+
+impl MultipathConn for PeerConn {
+    fn latency_us(&self) -> u64 {
+        self.get_stats().latency_us
+    }
+
+    fn loss_rate(&self) -> f32 {
+        self.get_loss_rate()
+    }
+
+    fn supports_multipath_dedup(&self) -> bool {
+        PeerConn::supports_multipath_dedup(self)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.get_close_notifier().is_closed()
+    }
+}
+
+fn conn_weight(conn: &impl MultipathConn) -> f64 {
+    let latency_ms = match conn.latency_us() {
+        0 => UNMEASURED_LATENCY_MS,
+        latency_us => (latency_us as f64 / 1000.0).max(1.0),
+    };
+    let loss = (conn.loss_rate() as f64).clamp(0.0, 0.99);
+    (1.0 - loss) / latency_ms
+}
+
+/// Picks the connections a data packet to a peer is sent over.
+pub struct MultipathScheduler {
+    mode: MultipathMode,
+    next_seq: AtomicU8,
+}
+
+impl MultipathScheduler {
+    pub fn new(mode: MultipathMode) -> Self {
+        Self {
+            mode,
+            next_seq: AtomicU8::new(0),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.mode != MultipathMode::Off
+    }
+
+    /// Returns no connection if the packet should take the default one, two if it was
+    /// marked as duplicated.
+    pub fn schedule<C: MultipathConn>(
+        &self,
+        msg: &mut ZCPacket,
+        peer_id: PeerId,
+        mut conns: Vec<Arc<C>>,
+    ) -> Vec<Arc<C>> {
+        let Some(hdr) = msg.peer_manager_header() else {
+            return vec![];
+        };
+        // closed conns are only removed from the peer a bit later
+        conns.retain(|conn| !conn.is_closed());
+        if conns.len() < 2 || hdr.packet_type != PacketType::Data as u8 {
+            return vec![];
+        }
+        // relays would forward both copies
+        let to_peer = hdr.to_peer_id.get() == peer_id;
+
+        let mut weighted = conns
+            .into_iter()
+            .map(|conn| (conn_weight(conn.as_ref()), conn))
+            .collect::<Vec<_>>();
+
+        match self.mode {
+            MultipathMode::Off => vec![],
+
+            MultipathMode::Stripe => {
+                let total: f64 = weighted.iter().map(|(w, _)| w).sum();
+                let mut point = rand::thread_rng().gen_range(0.0..total);
+                for (weight, conn) in weighted.iter() {
+                    if point < *weight {
+                        return vec![conn.clone()];
+                    }
+                    point -= weight;
+                }
+                weighted
+                    .pop()
+                    .map(|(_, conn)| vec![conn])
+                    .unwrap_or_default()
+            }
+
+            MultipathMode::Duplicate => {
+                // the peer would deliver every copy
+                weighted.retain(|(_, conn)| conn.supports_multipath_dedup());
+                if !to_peer || weighted.len() < 2 || msg.payload_len() > DUPLICATE_MAX_PAYLOAD_LEN {
+                    return vec![];
+                }
+                weighted.sort_by(|a, b| b.0.total_cmp(&a.0));
+                let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+                msg.mut_peer_manager_header()
+                    .unwrap()
+                    .set_multipath_seq(seq);
+                weighted.into_iter().take(2).map(|(_, conn)| conn).collect()
+            }
+        }
+    }
+}
+
+/// Drops the second copy of duplicated packets, shared by the connections of one peer.
+/// The copies are identical, so a packet is a copy if the same sequence was seen with the
+/// same content shortly before.
+pub struct MultipathDedup {
+    seen: Mutex<Vec<Option<(u64, Instant)>>>,
+}
+
+impl Default for MultipathDedup {
+    fn default() -> Self {
+        Self {
+            seen: Mutex::new(vec![None; u8::MAX as usize + 1]),
+        }
+    }
+}
+
+impl MultipathDedup {
+    pub fn is_duplicate(&self, packet: &ZCPacket) -> bool {
+        let Some(seq) = packet
+            .peer_manager_header()
+            .and_then(|hdr| hdr.multipath_seq())
+        else {
+            return false;
+        };
+        let mut hasher = DefaultHasher::new();
+        packet.payload().hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let mut seen = self.seen.lock().unwrap();
+        let slot = &mut seen[seq as usize];
+        if slot.is_some_and(|(h, t)| h == hash && t.elapsed() < DEDUP_WINDOW) {
+            // only two copies are sent, the next packet with this sequence is a new one
+            *slot = None;
+            return true;
+        }
+        *slot = Some((hash, Instant::now()));
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use crate::{
+        proto::common::MultipathMode,
+        tunnel::packet_def::{PacketType, ZCPacket},
+    };
+
+    use super::{MultipathConn, MultipathDedup, MultipathScheduler, DUPLICATE_MAX_PAYLOAD_LEN};
+
+    struct MockConn {
+        latency_us: u64,
+        loss_rate: f32,
+        dedup: bool,
+        closed: AtomicBool,
+    }
+
+    impl MultipathConn for MockConn {
+        fn latency_us(&self) -> u64 {
+            self.latency_us
+        }
+
+        fn loss_rate(&self) -> f32 {
+            self.loss_rate
+        }
+
+        fn supports_multipath_dedup(&self) -> bool {
+            self.dedup
+        }
+
+        fn is_closed(&self) -> bool {
+            self.closed.load(Ordering::Relaxed)
+        }
+    }
+
+    fn conn(latency_ms: u64, loss_rate: f32, dedup: bool) -> Arc<MockConn> {
+        Arc::new(MockConn {
+            latency_us: latency_ms * 1000,
+            loss_rate,
+            dedup,
+            closed: AtomicBool::new(false),
+        })
+    }
+
+    fn packet(payload: &[u8], seq: Option<u8>) -> ZCPacket {
+        let mut packet = ZCPacket::new_with_payload(payload);
+        packet.fill_peer_manager_hdr(1, 2, PacketType::Data as u8);
+        if let Some(seq) = seq {
+            packet
+                .mut_peer_manager_header()
+                .unwrap()
+                .set_multipath_seq(seq);
+        }
+        packet
+    }
+
+    #[test]
+    fn test_multipath_dedup() {
+        let dedup = MultipathDedup::default();
+
+        assert!(!dedup.is_duplicate(&packet(b"abc", Some(1))));
+        assert!(dedup.is_duplicate(&packet(b"abc", Some(1))));
+        // a third packet with the sequence is a new one after the sequence wrapped
+        assert!(!dedup.is_duplicate(&packet(b"abc", Some(1))));
+
+        // same sequence but different content
+        assert!(!dedup.is_duplicate(&packet(b"def", Some(2))));
+        assert!(!dedup.is_duplicate(&packet(b"xyz", Some(2))));
+
+        // packets that were not duplicated are never dropped
+        assert!(!dedup.is_duplicate(&packet(b"abc", None)));
+        assert!(!dedup.is_duplicate(&packet(b"abc", None)));
+    }
+
+    // how often each conn is picked by the stripe scheduler
+    fn stripe_counts(conns: &[Arc<MockConn>], rounds: usize) -> Vec<usize> {
+        let scheduler = MultipathScheduler::new(MultipathMode::Stripe);
+        let mut counts = vec![0; conns.len()];
+        for _ in 0..rounds {
+            let picked = scheduler.schedule(&mut packet(b"abc", None), 2, conns.to_vec());
+            assert_eq!(picked.len(), 1);
+            let idx = conns
+                .iter()
+                .position(|c| Arc::ptr_eq(c, &picked[0]))
+                .unwrap();
+            counts[idx] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_stripe_weighted_by_latency_and_loss() {
+        // weights 1/10 and 1/40, so the fast conn takes about 80% of the packets
+        let counts = stripe_counts(&[conn(10, 0.0, true), conn(40, 0.0, true)], 10000);
+        assert!((7500..8500).contains(&counts[0]), "{:?}", counts);
+
+        // half of the packets are lost on the first conn, the weights become even
+        let counts = stripe_counts(&[conn(10, 0.5, true), conn(20, 0.0, true)], 10000);
+        assert!((4500..5500).contains(&counts[0]), "{:?}", counts);
+
+        // unmeasured conns are treated as 100ms
+        let counts = stripe_counts(&[conn(0, 0.0, true), conn(25, 0.0, true)], 10000);
+        assert!((1500..2500).contains(&counts[0]), "{:?}", counts);
+    }
+
+    #[test]
+    fn test_duplicate_picks_two_best_dedup_conns() {
+        let scheduler = MultipathScheduler::new(MultipathMode::Duplicate);
+        let slow = conn(50, 0.0, true);
+        let fast = conn(10, 0.0, true);
+        let lossy = conn(10, 0.9, true);
+        let no_dedup = conn(1, 0.0, false);
+        let conns = vec![slow.clone(), lossy.clone(), no_dedup.clone(), fast.clone()];
+
+        let mut msg = packet(b"abc", None);
+        let picked = scheduler.schedule(&mut msg, 2, conns.clone());
+        assert_eq!(picked.len(), 2);
+        assert!(Arc::ptr_eq(&picked[0], &fast));
+        assert!(Arc::ptr_eq(&picked[1], &slow));
+        let seq = msg.peer_manager_header().unwrap().multipath_seq();
+        assert!(seq.is_some());
+
+        // every duplicated packet gets the next sequence
+        let mut msg = packet(b"abc", None);
+        scheduler.schedule(&mut msg, 2, conns.clone());
+        assert_eq!(
+            msg.peer_manager_header().unwrap().multipath_seq(),
+            seq.map(|s| s.wrapping_add(1))
+        );
+
+        // large packets take the default conn
+        let mut msg = packet(&[0u8; DUPLICATE_MAX_PAYLOAD_LEN + 1], None);
+        assert!(scheduler.schedule(&mut msg, 2, conns.clone()).is_empty());
+        assert!(msg.peer_manager_header().unwrap().multipath_seq().is_none());
+
+        // relayed packets are not duplicated, the relay would forward both copies
+        assert!(scheduler
+            .schedule(&mut packet(b"abc", None), 3, conns.clone())
+            .is_empty());
+
+        // only one conn can drop the copy
+        assert!(scheduler
+            .schedule(&mut packet(b"abc", None), 2, vec![fast, no_dedup])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_schedule_skips_closed_conns() {
+        let a = conn(10, 0.0, true);
+        let b = conn(10, 0.0, true);
+        let c = conn(10, 0.0, true);
+        b.closed.store(true, Ordering::Relaxed);
+        let conns = vec![a.clone(), b.clone(), c.clone()];
+
+        let counts = stripe_counts(&conns, 1000);
+        assert_eq!(counts[1], 0);
+        assert!(counts[0] > 0 && counts[2] > 0);
+
+        let scheduler = MultipathScheduler::new(MultipathMode::Duplicate);
+        let picked = scheduler.schedule(&mut packet(b"abc", None), 2, conns.clone());
+        assert!(picked.iter().all(|p| !Arc::ptr_eq(p, &b)));
+
+        // a single conn left, the packet falls back to the default conn
+        c.closed.store(true, Ordering::Relaxed);
+        for mode in [MultipathMode::Stripe, MultipathMode::Duplicate] {
+            let scheduler = MultipathScheduler::new(mode);
+            assert!(scheduler
+                .schedule(&mut packet(b"abc", None), 2, conns.clone())
+                .is_empty());
+        }
+    }
+}
@@ -8,6 +8,7 @@ use tokio::{select, sync::mpsc};
 use tracing::Instrument;
 
 use super::{
+    multipath::{MultipathDedup, MultipathScheduler},
     peer_conn::{PeerConn, PeerConnId},
     PacketRecvChan,
 };
@@ -38,6 +39,9 @@ pub struct Peer {
 
     default_conn_id: Arc<AtomicCell<PeerConnId>>,
     default_conn_id_clear_task: ScopedTask<()>,
+
+    multipath_scheduler: MultipathScheduler,
+    multipath_dedup: Arc<MultipathDedup>,
 }
 
 impl Peer {
@@ -103,6 +107,8 @@ impl Peer {
             }
         }));
 
+        let multipath_scheduler = MultipathScheduler::new(global_ctx.get_flags().multipath_mode());
+
         Peer {
             peer_node_id,
             conns: conns.clone(),
@@ -115,6 +121,9 @@ impl Peer {
             shutdown_notifier,
             default_conn_id,
             default_conn_id_clear_task,
+
+            multipath_scheduler,
+            multipath_dedup: Arc::new(MultipathDedup::default()),
         }
     }
 
@@ -122,6 +131,7 @@ impl Peer {
         let close_notifier = conn.get_close_notifier();
         let conn_info = conn.get_conn_info();
 
+        conn.set_multipath_dedup(self.multipath_dedup.clone());
         conn.start_recv_loop(self.packet_recv_chan.clone()).await;
         conn.start_pingpong();
         self.conns.insert(conn.get_conn_id(), Arc::new(conn));
@@ -162,7 +172,21 @@ impl Peer {
             .map(|conn| conn.clone())
     }
 
-    pub async fn send_msg(&self, msg: ZCPacket) -> Result<(), Error> {
+    pub async fn send_msg(&self, mut msg: ZCPacket) -> Result<(), Error> {
+        if self.multipath_scheduler.is_enabled() && self.conns.len() > 1 {
+            let conns = self.conns.iter().map(|conn| conn.clone()).collect();
+            let mut conns = self
+                .multipath_scheduler
+                .schedule(&mut msg, self.peer_node_id, conns);
+            if let Some(conn) = conns.pop() {
+                for dup in conns {
+                    let _ = dup.send_msg(msg.clone()).await;
+                }
+                conn.send_msg(msg).await?;
+                return Ok(());
+            }
+        }
+
         let Some(conn) = self.select_conn().await else {
             return Err(Error::PeerNoConnectionError(self.peer_node_id));
         };
@@ -230,12 +254,18 @@ impl Drop for Peer {
 #[cfg(test)]
 mod tests {
 
+    use std::time::Duration;
+
     use tokio::time::timeout;
 
     use crate::{
         common::{global_ctx::tests::get_mock_global_ctx, new_peer_id},
         peers::{create_packet_recv_chan, peer_conn::PeerConn},
-        tunnel::ring::create_ring_tunnel_pair,
+        proto::common::MultipathMode,
+        tunnel::{
+            packet_def::{PacketType, ZCPacket},
+            ring::create_ring_tunnel_pair,
+        },
     };
 
     use super::Peer;
@@ -287,4 +317,88 @@ mod tests {
         println!("wait for close handler");
         close_handler.await.unwrap().unwrap();
     }
+
+    #[tokio::test]
+    async fn send_msg_duplicated_over_two_conns() {
+        let global_ctx = get_mock_global_ctx();
+        let mut flags = global_ctx.get_flags();
+        flags.multipath_mode = MultipathMode::Duplicate.into();
+        global_ctx.set_flags(flags);
+
+        let (local_packet_send, _local_packet_recv) = create_packet_recv_chan();
+        let (remote_packet_send, mut remote_packet_recv) = create_packet_recv_chan();
+        let local_peer = Peer::new(new_peer_id(), local_packet_send, global_ctx.clone());
+        let remote_peer = Peer::new(new_peer_id(), remote_packet_send, global_ctx.clone());
+
+        let mut local_conn_ids = vec![];
+        for _ in 0..2 {
+            let (local_tunnel, remote_tunnel) = create_ring_tunnel_pair();
+            let mut local_peer_conn =
+                PeerConn::new(local_peer.peer_node_id, global_ctx.clone(), local_tunnel);
+            let mut remote_peer_conn =
+                PeerConn::new(remote_peer.peer_node_id, global_ctx.clone(), remote_tunnel);
+            let (a, b) = tokio::join!(
+                local_peer_conn.do_handshake_as_client(),
+                remote_peer_conn.do_handshake_as_server()
+            );
+            a.unwrap();
+            b.unwrap();
+            local_conn_ids.push(local_peer_conn.get_conn_id());
+            local_peer.add_peer_conn(local_peer_conn).await;
+            remote_peer.add_peer_conn(remote_peer_conn).await;
+        }
+
+        let new_msg = |payload: &[u8]| {
+            let mut msg = ZCPacket::new_with_payload(payload);
+            msg.fill_peer_manager_hdr(
+                local_peer.peer_node_id,
+                remote_peer.peer_node_id,
+                PacketType::Data as u8,
+            );
+            msg
+        };
+
+        // both conns carry a copy, the remote peer delivers it once
+        local_peer.send_msg(new_msg(b"first")).await.unwrap();
+        let recv = timeout(Duration::from_secs(5), remote_packet_recv.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recv.payload(), b"first");
+        assert!(recv
+            .peer_manager_header()
+            .unwrap()
+            .multipath_seq()
+            .is_some());
+        assert!(
+            timeout(Duration::from_millis(500), remote_packet_recv.recv())
+                .await
+                .is_err()
+        );
+
+        // one path dies, the other still delivers
+        local_peer
+            .close_peer_conn(&local_conn_ids[0])
+            .await
+            .unwrap();
+        timeout(Duration::from_secs(5), async {
+            while local_peer.list_peer_conns().await.len() > 1 {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        local_peer.send_msg(new_msg(b"second")).await.unwrap();
+        let recv = timeout(Duration::from_secs(5), remote_packet_recv.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recv.payload(), b"second");
+        assert!(recv
+            .peer_manager_header()
+            .unwrap()
+            .multipath_seq()
+            .is_none());
+    }
 }
@@ -9,6 +9,7 @@ use std::{
 };
 
 use arc_swap::ArcSwapOption;
+use base64::{prelude::BASE64_STANDARD, Engine as _};
 use futures::{StreamExt, TryFutureExt};
 
 use prost::Message;
@@ -24,6 +25,7 @@ use zerocopy::AsBytes;
 
 use crate::{
     common::{
+        compressor::supported_compress_features,
         config::{NetworkIdentity, NetworkSecretDigest},
         defer,
         error::Error,
@@ -45,13 +47,41 @@ use crate::{
     },
 };
 
-use super::{peer_conn_ping::PeerConnPinger, PacketRecvChan};
+#[cfg(feature = "pfs")]
+use super::encrypt::session::{supports_session_keys, Session, SessionKeyExchange};
+#[cfg(feature = "node-identity")]
+use super::identity::HandshakeIdentity;
+use super::{
+    multipath::{MultipathDedup, MULTIPATH_DEDUP_FEATURE},
+    peer_conn_ping::PeerConnPinger,
+    PacketRecvChan,
+};
 
 pub type PeerConnId = uuid::Uuid;
 
 const MAGIC: u32 = 0xd1e1a5e1;
 const VERSION: u32 = 1;
 
+// stands in for the node identity of a handshake when built without the node-identity feature,
+// there is never one since configuring an identity fails the handshake.
+#[cfg(not(feature = "node-identity"))]
+enum HandshakeIdentity {}
+
+#[cfg(not(feature = "node-identity"))]
+impl HandshakeIdentity {
+    fn fill(&self, _: &mut HandshakeRequest, _: Option<&HandshakeRequest>, _: bool) {
+        match *self {}
+    }
+
+    fn check_peer(&self, _: &HandshakeRequest) -> anyhow::Result<()> {
+        match *self {}
+    }
+
+    fn verify(&self, _: PeerId, _: &HandshakeRequest, _: bool) -> anyhow::Result<()> {
+        match *self {}
+    }
+}
+
 pub struct PeerConnCloseNotify {
     conn_id: PeerConnId,
     sender: Arc<std::sync::Mutex<Option<broadcast::Sender<()>>>>,
@@ -122,6 +152,13 @@ pub struct PeerConn {
     loss_rate_stats: Arc<AtomicU32>,
 
     counters: ArcSwapOption<PeerConnCounter>,
+
+    // shared by the conns of a peer to drop the copies of duplicated packets
+    multipath_dedup: Option<Arc<MultipathDedup>>,
+
+    // keys negotiated in the handshake, owned by the connection so they are dropped with it
+    #[cfg(feature = "pfs")]
+    session: Option<Arc<Session>>,
 }
 
 impl Debug for PeerConn {
@@ -177,6 +214,11 @@ impl PeerConn {
             loss_rate_stats: Arc::new(AtomicU32::new(0)),
 
             counters: ArcSwapOption::new(None),
+
+            multipath_dedup: None,
+
+            #[cfg(feature = "pfs")]
+            session: None,
         }
     }
 
@@ -260,19 +302,20 @@ impl PeerConn {
         .await?
     }
 
-    async fn send_handshake(&mut self, send_secret_digest: bool) -> Result<(), Error> {
-        let network = self.global_ctx.get_network_identity();
+    fn new_handshake_request(&self, network: Option<&NetworkIdentity>) -> HandshakeRequest {
+        let mut features = supported_compress_features();
+        features.push(MULTIPATH_DEDUP_FEATURE.to_string());
         let mut req = HandshakeRequest {
             magic: MAGIC,
             my_peer_id: self.my_peer_id,
             version: VERSION,
-            features: Vec::new(),
-            network_name: network.network_name.clone(),
+            features,
+            network_name: self.global_ctx.get_network_name(),
             ..Default::default()
         };
 
         // only send network secret digest if the network is the same
-        if send_secret_digest {
+        if let Some(network) = network {
             req.network_secret_digrest
                 .extend_from_slice(&network.network_secret_digest.unwrap_or_default());
         } else {
@@ -281,6 +324,10 @@ impl PeerConn {
                 .extend_from_slice(&[0u8; std::mem::size_of::<NetworkSecretDigest>()]);
         }
 
+        req
+    }
+
+    async fn send_handshake(&mut self, req: &HandshakeRequest) -> Result<(), Error> {
         let hs_req = req.encode_to_vec();
         let mut zc_packet = ZCPacket::new_with_payload(hs_req.as_bytes());
         zc_packet.fill_peer_manager_hdr(
@@ -300,6 +347,111 @@ impl PeerConn {
         Ok(())
     }
 
+    #[cfg(feature = "node-identity")]
+    fn new_handshake_identity(&self) -> Result<Option<HandshakeIdentity>, Error> {
+        Ok(self
+            .global_ctx
+            .get_node_identity()?
+            .map(HandshakeIdentity::new))
+    }
+
+    #[cfg(not(feature = "node-identity"))]
+    fn new_handshake_identity(&self) -> Result<Option<HandshakeIdentity>, Error> {
+        if self.global_ctx.config.get_node_identity_config().is_some() {
+            return Err(anyhow::anyhow!(
+                "node identity is configured but easytier is built without the node-identity feature"
+            )
+            .into());
+        }
+        Ok(None)
+    }
+
+    #[cfg(feature = "pfs")]
+    fn new_session_key_exchange(&self) -> Option<SessionKeyExchange> {
+        let flags = self.global_ctx.get_flags();
+        if !flags.enable_encryption || !supports_session_keys(&flags.encryption_algorithm) {
+            return None;
+        }
+        SessionKeyExchange::new()
+            .inspect_err(|e| tracing::warn!(?e, "session key exchange unavailable"))
+            .ok()
+    }
+
+    #[cfg(feature = "pfs")]
+    fn derive_session(
+        &self,
+        key_exchange: Option<SessionKeyExchange>,
+        peer: &HandshakeRequest,
+        network: &NetworkIdentity,
+        is_client: bool,
+    ) -> Result<Option<Arc<Session>>, Error> {
+        let Some(key_exchange) = key_exchange.filter(|_| !peer.session_public_key.is_empty())
+        else {
+            return Ok(None);
+        };
+        let session = key_exchange.derive(
+            &peer.session_public_key,
+            network.network_secret.as_deref().unwrap_or_default(),
+            &self.global_ctx.get_flags().encryption_algorithm,
+            is_client,
+        )?;
+        Ok(Some(session))
+    }
+
+    // peers of the same network prove their node identity, peers of other networks
+    // (e.g. clients of a public server) are left to the foreign network manager.
+    async fn send_handshake_as_server(&mut self) -> Result<(), Error> {
+        let peer = self.info.clone().unwrap();
+        // answer with the secret the peer knows, it may still use the previous one
+        // while a secret rotation is in its grace period.
+        let network = self
+            .global_ctx
+            .match_network_identity(&self.get_network_identity());
+        let mut rsp = self.new_handshake_request(network.as_ref());
+
+        // only peers that know the secret get session keys, old peers send no key
+        #[cfg(feature = "pfs")]
+        if let Some(network) = network
+            .as_ref()
+            .filter(|_| !peer.session_public_key.is_empty())
+        {
+            let key_exchange = self.new_session_key_exchange();
+            if let Some(key_exchange) = &key_exchange {
+                rsp.session_public_key = key_exchange.public_key().to_vec();
+            }
+            self.session = self.derive_session(key_exchange, &peer, network, false)?;
+        }
+
+        let identity = if peer.network_name == rsp.network_name {
+            self.new_handshake_identity()?
+        } else {
+            None
+        };
+        let Some(identity) = identity else {
+            return self.send_handshake(&rsp).await;
+        };
+
+        identity
+            .check_peer(&peer)
+            .map_err(|e| e.context("peer rejected by node identity"))?;
+        identity.fill(&mut rsp, Some(&peer), false);
+        self.send_handshake(&rsp).await?;
+
+        let proof = self.wait_handshake_loop().await?;
+        if proof.my_peer_id != peer.my_peer_id
+            || proof.identity_public_key != peer.identity_public_key
+            || proof.identity_nonce != peer.identity_nonce
+        {
+            return Err(Error::WaitRespError(
+                "node identity proof does not match the handshake".to_owned(),
+            ));
+        }
+        identity
+            .verify(self.my_peer_id, &proof, false)
+            .map_err(|e| e.context("peer rejected by node identity"))?;
+        Ok(())
+    }
+
     #[tracing::instrument(skip(handshake_recved))]
     pub async fn do_handshake_as_server_ext<Fn>(
         &mut self,
@@ -316,8 +468,7 @@ impl PeerConn {
         self.info = Some(rsp);
         self.is_client = Some(false);
 
-        let send_digest = self.get_network_identity() == self.global_ctx.get_network_identity();
-        self.send_handshake(send_digest).await?;
+        self.send_handshake_as_server().await?;
 
         if self.get_peer_id() == self.my_peer_id {
             Err(Error::WaitRespError("peer id conflict".to_owned()))
@@ -333,8 +484,7 @@ impl PeerConn {
         self.info = Some(rsp);
         self.is_client = Some(false);
 
-        let send_digest = self.get_network_identity() == self.global_ctx.get_network_identity();
-        self.send_handshake(send_digest).await?;
+        self.send_handshake_as_server().await?;
 
         if self.get_peer_id() == self.my_peer_id {
             Err(Error::WaitRespError(
@@ -347,10 +497,37 @@ impl PeerConn {
 
     #[tracing::instrument]
     pub async fn do_handshake_as_client(&mut self) -> Result<(), Error> {
-        self.send_handshake(true).await?;
+        let identity = self.new_handshake_identity()?;
+        let network = self.global_ctx.get_network_identity();
+        let mut req = self.new_handshake_request(Some(&network));
+        if let Some(identity) = &identity {
+            identity.fill(&mut req, None, true);
+        }
+        #[cfg(feature = "pfs")]
+        let key_exchange = self.new_session_key_exchange();
+        #[cfg(feature = "pfs")]
+        if let Some(key_exchange) = &key_exchange {
+            req.session_public_key = key_exchange.public_key().to_vec();
+        }
+        self.send_handshake(&req).await?;
         tracing::info!("waiting for handshake request from server");
         let rsp = self.wait_handshake_loop().await?;
         tracing::info!("handshake response: {:?}", rsp);
+
+        if let Some(identity) = identity.filter(|_| rsp.network_name == req.network_name) {
+            identity
+                .verify(self.my_peer_id, &rsp, true)
+                .map_err(|e| e.context("server rejected by node identity"))?;
+            let mut proof = self.new_handshake_request(Some(&network));
+            identity.fill(&mut proof, Some(&rsp), true);
+            self.send_handshake(&proof).await?;
+        }
+
+        #[cfg(feature = "pfs")]
+        if rsp.network_name == req.network_name {
+            self.session = self.derive_session(key_exchange, &rsp, &network, true)?;
+        }
+
         self.info = Some(rsp);
         self.is_client = Some(true);
 
@@ -367,12 +544,23 @@ impl PeerConn {
         self.info.is_some()
     }
 
+    pub fn set_multipath_dedup(&mut self, dedup: Arc<MultipathDedup>) {
+        self.multipath_dedup = Some(dedup);
+    }
+
+    pub fn supports_multipath_dedup(&self) -> bool {
+        self.info
+            .as_ref()
+            .is_some_and(|info| info.features.iter().any(|f| f == MULTIPATH_DEDUP_FEATURE))
+    }
+
     pub async fn start_recv_loop(&mut self, packet_recv_chan: PacketRecvChan) {
         let mut stream = self.recv.lock().await.take().unwrap();
         let sink = self.sink.clone();
         let sender = packet_recv_chan.clone();
         let close_event_notifier = self.close_event_notifier.clone();
         let ctrl_sender = self.ctrl_resp_sender.clone();
+        let multipath_dedup = self.multipath_dedup.clone();
         let conn_info_for_instrument = self.get_conn_info();
 
         let stats_mgr = self.global_ctx.stats_manager();
@@ -424,6 +612,11 @@ impl PeerConn {
                         if let Err(e) = ctrl_sender.send(zc_packet) {
                             tracing::error!(?e, "peer conn send ctrl resp error");
                         }
+                    } else if multipath_dedup
+                        .as_ref()
+                        .is_some_and(|dedup| dedup.is_duplicate(&zc_packet))
+                    {
+                        continue;
                     } else if sender.send(zc_packet).await.is_err() {
                         break;
                     }
@@ -493,6 +686,15 @@ impl PeerConn {
         ret
     }
 
+    #[cfg(feature = "pfs")]
+    pub fn get_session(&self) -> Option<Arc<Session>> {
+        self.session.clone()
+    }
+
+    pub fn get_loss_rate(&self) -> f32 {
+        (f64::from(self.loss_rate_stats.load(Ordering::Relaxed)) / 100.0) as f32
+    }
+
     pub fn get_close_notifier(&self) -> Arc<PeerConnCloseNotify> {
         self.close_event_notifier.clone()
     }
@@ -518,10 +720,19 @@ impl PeerConn {
             features: info.features.clone(),
             tunnel: self.tunnel_info.clone(),
             stats: Some(self.get_stats()),
-            loss_rate: (f64::from(self.loss_rate_stats.load(Ordering::Relaxed)) / 100.0) as f32,
+            loss_rate: self.get_loss_rate(),
             is_client: self.is_client.unwrap_or_default(),
             network_name: info.network_name.clone(),
             is_closed: self.close_event_notifier.is_closed(),
+            identity_public_key: if info.identity_public_key.is_empty() {
+                String::new()
+            } else {
+                BASE64_STANDARD.encode(&info.identity_public_key)
+            },
+            #[cfg(feature = "pfs")]
+            forward_secrecy: self.session.is_some(),
+            #[cfg(not(feature = "pfs"))]
+            forward_secrecy: false,
         }
     }
 
@@ -612,6 +823,46 @@ mod tests {
         assert_eq!(c_peer.get_network_identity(), NetworkIdentity::default());
     }
 
+    #[cfg(feature = "pfs")]
+    #[tokio::test]
+    async fn peer_conn_handshake_session_keys() {
+        use crate::peers::encrypt::Encryptor as _;
+
+        for server_encryption in [true, false] {
+            let (c, s) = create_ring_tunnel_pair();
+            let c_ctx = get_mock_global_ctx();
+            let s_ctx = get_mock_global_ctx();
+            let mut flags = s_ctx.get_flags();
+            flags.enable_encryption = server_encryption;
+            s_ctx.set_flags(flags);
+
+            let mut c_peer = PeerConn::new(new_peer_id(), c_ctx, Box::new(c));
+            let mut s_peer = PeerConn::new(new_peer_id(), s_ctx, Box::new(s));
+            let (c_ret, s_ret) = tokio::join!(
+                c_peer.do_handshake_as_client(),
+                s_peer.do_handshake_as_server()
+            );
+            c_ret.unwrap();
+            s_ret.unwrap();
+
+            // a peer that doesn't take part leaves both sides on the network key
+            let (Some(c_session), Some(s_session)) = (c_peer.get_session(), s_peer.get_session())
+            else {
+                assert!(!server_encryption);
+                assert!(c_peer.get_session().is_none() && s_peer.get_session().is_none());
+                continue;
+            };
+            assert!(server_encryption);
+            assert!(c_peer.get_conn_info().forward_secrecy);
+
+            let mut packet = ZCPacket::new_with_payload(b"1234567");
+            packet.fill_peer_manager_hdr(s_peer.my_peer_id, c_peer.my_peer_id, 0);
+            s_session.encrypt(&mut packet).unwrap();
+            c_session.decrypt(&mut packet).unwrap();
+            assert_eq!(packet.payload(), b"1234567");
+        }
+    }
+
     async fn peer_conn_pingpong_test_common(
         drop_start: u32,
         drop_end: u32,
@@ -695,4 +946,86 @@ mod tests {
             .unwrap_err();
         let _ = tokio::join!(j);
     }
+
+    #[cfg(feature = "node-identity")]
+    fn global_ctx_with_identity(private_key: &str, allowed: &[&str]) -> ArcGlobalCtx {
+        use crate::common::config::NodeIdentityConfig;
+        use crate::peers::identity::public_key_of;
+
+        let global_ctx = get_mock_global_ctx();
+        global_ctx
+            .config
+            .set_node_identity_config(Some(NodeIdentityConfig {
+                private_key: private_key.to_string(),
+                allowed_public_keys: allowed.iter().map(|k| public_key_of(k).unwrap()).collect(),
+                ..Default::default()
+            }));
+        global_ctx
+    }
+
+    #[cfg(feature = "node-identity")]
+    async fn handshake(
+        c_ctx: ArcGlobalCtx,
+        s_ctx: ArcGlobalCtx,
+    ) -> (PeerConn, Result<(), Error>, PeerConn, Result<(), Error>) {
+        let (c, s) = create_ring_tunnel_pair();
+        let mut c_peer = PeerConn::new(new_peer_id(), c_ctx, Box::new(c));
+        let mut s_peer = PeerConn::new(new_peer_id(), s_ctx, Box::new(s));
+        let (c_ret, s_ret) = tokio::join!(
+            c_peer.do_handshake_as_client(),
+            s_peer.do_handshake_as_server()
+        );
+        (c_peer, c_ret, s_peer, s_ret)
+    }
+
+    #[cfg(feature = "node-identity")]
+    #[tokio::test]
+    async fn peer_conn_handshake_node_identity() {
+        use crate::peers::identity::{generate_private_key, public_key_of};
+
+        let key_a = generate_private_key();
+        let key_b = generate_private_key();
+        let key_c = generate_private_key();
+
+        let (c_peer, c_ret, s_peer, s_ret) = handshake(
+            global_ctx_with_identity(&key_a, &[&key_a, &key_b]),
+            global_ctx_with_identity(&key_b, &[&key_a, &key_b]),
+        )
+        .await;
+        c_ret.unwrap();
+        s_ret.unwrap();
+        assert_eq!(
+            c_peer.get_conn_info().identity_public_key,
+            public_key_of(&key_b).unwrap()
+        );
+        assert_eq!(
+            s_peer.get_conn_info().identity_public_key,
+            public_key_of(&key_a).unwrap()
+        );
+
+        // a node whose key is not allowed is rejected by the server
+        let (_, _, _, s_ret) = handshake(
+            global_ctx_with_identity(&key_c, &[&key_a, &key_b]),
+            global_ctx_with_identity(&key_b, &[&key_a, &key_b]),
+        )
+        .await;
+        assert!(s_ret.is_err());
+
+        // a server whose key is not allowed is rejected by the client
+        let (_, c_ret, _, _) = handshake(
+            global_ctx_with_identity(&key_a, &[&key_a, &key_b]),
+            global_ctx_with_identity(&key_c, &[&key_a]),
+        )
+        .await;
+        assert!(c_ret.is_err());
+
+        // knowing the network secret alone is not enough
+        let (_, c_ret, _, s_ret) = handshake(
+            get_mock_global_ctx(),
+            global_ctx_with_identity(&key_b, &[&key_a, &key_b]),
+        )
+        .await;
+        assert!(s_ret.is_err());
+        assert!(c_ret.is_err());
+    }
 }
@@ -20,7 +20,10 @@ use tokio::{
 
 use crate::{
     common::{
-        compressor::{Compressor as _, DefaultCompressor},
+        compressor::{
+            negotiate_compress_algo, peer_supported_compress_algos, Compressor as _,
+            DefaultCompressor,
+        },
         constants::EASYTIER_VERSION,
         error::Error,
         global_ctx::{ArcGlobalCtx, NetworkIdentity},
@@ -29,6 +32,7 @@ use crate::{
         PeerId,
     },
     peers::{
+        bandwidth_limiter::TrafficDirection,
         peer_conn::PeerConn,
         peer_rpc::PeerRpcManagerTransport,
         recv_packet_from_chan,
@@ -51,16 +55,24 @@ use crate::{
     },
 };
 
+#[cfg(feature = "pfs")]
+use super::encrypt::session::SessionEncryptor;
+#[cfg(feature = "node-identity")]
+use super::identity::PeerIdentityVerifier;
 use super::{
+    broadcast_relay::{build_igmp_general_query, BroadcastRelay, IGMP_QUERY_INTERVAL},
     create_packet_recv_chan,
-    encrypt::{Encryptor, NullCipher},
+    encrypt::{Encryptor, NullCipher, RotatingEncryptor},
+    exit_node::ExitNodeManager,
     foreign_network_client::ForeignNetworkClient,
     foreign_network_manager::{ForeignNetworkManager, GlobalForeignNetworkAccessor},
     peer_conn::PeerConnId,
     peer_map::PeerMap,
     peer_ospf_route::PeerRoute,
     peer_rpc::PeerRpcManager,
+    relay_quota::{RelayQuota, RESET_CHECK_INTERVAL},
     route_trait::{ArcRoute, Route},
+    secret_rotation::SecretRotationManager,
     BoxNicPacketFilter, BoxPeerPacketFilter, PacketRecvChan, PacketRecvChanReceiver,
 };
 
@@ -150,9 +162,19 @@ pub struct PeerManager {
     foreign_network_client: Arc<ForeignNetworkClient>,
 
     encryptor: Arc<dyn Encryptor + 'static>,
+    #[cfg(feature = "pfs")]
+    session_encryptor: Arc<SessionEncryptor>,
+    secret_rotation: Arc<SecretRotationManager>,
+    relay_quota: Arc<RelayQuota>,
+    broadcast_relay: Arc<BroadcastRelay>,
     data_compress_algo: CompressorAlgo,
+    data_compress_level: i32,
+    // algo negotiated with each directly connected peer, derived from handshake features
+    peer_compress_algos: Arc<DashMap<PeerId, CompressorAlgo>>,
 
-    exit_nodes: Vec<IpAddr>,
+    exit_nodes: Arc<ExitNodeManager>,
+    #[cfg(feature = "node-identity")]
+    identity_verifier: Arc<PeerIdentityVerifier>,
 
     reserved_my_peer_id_map: DashMap<String, PeerId>,
 
@@ -198,6 +220,14 @@ impl PeerManager {
             // disable_encryption = true 时使用 NullCipher
             Arc::new(NullCipher)
         };
+        // the key can be replaced at runtime by a secret rotation
+        let rotating_encryptor = Arc::new(RotatingEncryptor::new(encryptor));
+        let encryptor: Arc<dyn Encryptor> = rotating_encryptor.clone();
+        // directly connected peers use the keys negotiated on their connections instead
+        #[cfg(feature = "pfs")]
+        let session_encryptor = Arc::new(SessionEncryptor::new(encryptor, &global_ctx));
+        #[cfg(feature = "pfs")]
+        let encryptor: Arc<dyn Encryptor> = session_encryptor.clone();
 
         if global_ctx
             .check_network_in_whitelist(&global_ctx.get_network_name())
@@ -224,6 +254,14 @@ impl PeerManager {
             global_ctx.stats_manager().clone(),
         ));
 
+        let secret_rotation = SecretRotationManager::new(
+            my_peer_id,
+            global_ctx.clone(),
+            Arc::downgrade(&peers),
+            Arc::downgrade(&peer_rpc_mgr),
+            rotating_encryptor,
+        );
+
         let route_algo_inst = match route_algo {
             RouteAlgoType::Ospf => RouteAlgoInst::Ospf(PeerRoute::new(
                 my_peer_id,
@@ -251,8 +289,25 @@ impl PeerManager {
             .data_compress_algo()
             .try_into()
             .expect("invalid data compress algo, maybe some features not enabled");
+        let data_compress_level = global_ctx.get_flags().data_compress_level;
+
+        let exit_nodes = ExitNodeManager::new(
+            my_peer_id,
+            global_ctx.clone(),
+            Arc::downgrade(&peers),
+            Arc::downgrade(&peer_rpc_mgr),
+        );
+
+        #[cfg(feature = "node-identity")]
+        let identity_verifier = PeerIdentityVerifier::new(
+            my_peer_id,
+            global_ctx.clone(),
+            Arc::downgrade(&peers),
+            Arc::downgrade(&peer_rpc_mgr),
+        );
 
-        let exit_nodes = global_ctx.config.get_exit_nodes();
+        let relay_quota = Arc::new(RelayQuota::new(global_ctx.clone()));
+        let broadcast_relay = Arc::new(BroadcastRelay::new(global_ctx.clone()));
 
         let stats_manager = global_ctx.stats_manager();
         let self_tx_counters = SelfTxCounters {
@@ -302,9 +357,18 @@ impl PeerManager {
             foreign_network_client,
 
             encryptor,
+            #[cfg(feature = "pfs")]
+            session_encryptor,
+            secret_rotation,
+            relay_quota,
+            broadcast_relay,
             data_compress_algo,
+            data_compress_level,
+            peer_compress_algos: Arc::new(DashMap::new()),
 
             exit_nodes,
+            #[cfg(feature = "node-identity")]
+            identity_verifier,
 
             reserved_my_peer_id_map: DashMap::new(),
 
@@ -348,12 +412,24 @@ impl PeerManager {
     }
 
     async fn add_new_peer_conn(&self, peer_conn: PeerConn) -> Result<(), Error> {
-        if self.global_ctx.get_network_identity() != peer_conn.get_network_identity() {
+        let Some(identity) = self
+            .global_ctx
+            .match_network_identity(&peer_conn.get_network_identity())
+        else {
             return Err(Error::SecretKeyError(
                 "network identity not match".to_string(),
             ));
+        };
+        let peer_id = peer_conn.get_peer_id();
+        #[cfg(feature = "pfs")]
+        if let Some(session) = peer_conn.get_session() {
+            self.session_encryptor.add_session(peer_id, &session);
         }
         self.peers.add_new_peer_conn(peer_conn).await;
+        if identity != self.global_ctx.get_network_identity() {
+            // the peer is on the other secret of a rotation, no-op if it's ahead of us
+            self.secret_rotation.on_peer_with_previous_secret(peer_id);
+        }
         Ok(())
     }
 
@@ -631,10 +707,16 @@ impl PeerManager {
         let foreign_mgr = self.foreign_network_manager.clone();
         let encryptor = self.encryptor.clone();
         let compress_algo = self.data_compress_algo;
+        let compress_level = self.data_compress_level;
+        let peer_compress_algos = self.peer_compress_algos.clone();
         let acl_filter = self.global_ctx.get_acl_filter().clone();
+        let bandwidth_limiter = self.global_ctx.get_bandwidth_limiter().clone();
         let global_ctx = self.global_ctx.clone();
         let stats_mgr = self.global_ctx.stats_manager().clone();
         let route = self.get_route();
+        let relay_quota = self.relay_quota.clone();
+        #[cfg(feature = "node-identity")]
+        let identity_verifier = self.identity_verifier.clone();
 
         let label_set =
             LabelSet::new().with_label_type(LabelType::NetworkName(global_ctx.get_network_name()));
@@ -676,6 +758,7 @@ impl PeerManager {
                 tracing::trace!(?hdr, "peer recv a packet...");
                 let from_peer_id = hdr.from_peer_id.get();
                 let to_peer_id = hdr.to_peer_id.get();
+                let hdr_packet_type = hdr.packet_type;
                 if to_peer_id != my_peer_id {
                     if hdr.forward_counter > 7 {
                         tracing::warn!(?hdr, "forward counter exceed, drop packet");
@@ -696,15 +779,33 @@ impl PeerManager {
                             || hdr.packet_type == PacketType::KcpSrc as u8
                             || hdr.packet_type == PacketType::KcpDst as u8
                         {
-                            let _ =
-                                Self::try_compress_and_encrypt(compress_algo, &encryptor, &mut ret)
-                                    .await;
+                            let compress_algo = Self::get_peer_compress_algo(
+                                &peers,
+                                &peer_compress_algos,
+                                compress_algo,
+                                to_peer_id,
+                            )
+                            .await;
+                            if let Err(e) = Self::try_compress_and_encrypt(
+                                compress_algo,
+                                compress_level,
+                                &encryptor,
+                                &mut ret,
+                            )
+                            .await
+                            {
+                                tracing::warn!(?e, ?to_peer_id, "compress and encrypt failed");
+                            }
                         }
 
                         compress_tx_bytes_after.add(ret.buf_len() as u64);
                         self_tx_bytes.add(ret.buf_len() as u64);
                         self_tx_packets.inc();
                     } else {
+                        if !relay_quota.try_consume(hdr_packet_type, buf_len as u64) {
+                            tracing::trace!(?hdr, "relay not allowed, drop packet");
+                            continue;
+                        }
                         forward_tx_bytes.add(buf_len as u64);
                         forward_tx_packets.inc();
                     }
@@ -725,7 +826,7 @@ impl PeerManager {
                     self_rx_packets.inc();
                     compress_rx_bytes_before.add(buf_len as u64);
 
-                    let compressor = DefaultCompressor {};
+                    let compressor = DefaultCompressor::new();
                     if let Err(e) = compressor.decompress(&mut ret).await {
                         tracing::error!(?e, "decompress failed");
                         continue;
@@ -733,6 +834,25 @@ impl PeerManager {
 
                     compress_rx_bytes_after.add(ret.buf_len() as u64);
 
+                    #[cfg(feature = "node-identity")]
+                    if hdr_packet_type == PacketType::Data as u8
+                        && !identity_verifier.is_verified(from_peer_id)
+                    {
+                        tracing::trace!(?from_peer_id, "node identity not verified, drop");
+                        continue;
+                    }
+
+                    if hdr_packet_type == PacketType::Data as u8
+                        && !bandwidth_limiter.try_consume_ip_packet(
+                            TrafficDirection::Download,
+                            from_peer_id,
+                            ret.payload(),
+                        )
+                    {
+                        tracing::trace!(?from_peer_id, "exceed download bandwidth limit, drop");
+                        continue;
+                    }
+
                     if !acl_filter.process_packet_with_acl(
                         &ret,
                         true,
@@ -804,6 +924,10 @@ impl PeerManager {
         }))
         .await;
 
+        // dedup broadcast packets and learn multicast members before they reach the nic
+        self.add_packet_process_pipeline(Box::new(self.broadcast_relay.clone()))
+            .await;
+
         // for peer rpc packet
         struct PeerRpcPacketProcessor {
             peer_rpc_tspt_sender: UnboundedSender<ZCPacket>,
@@ -1045,16 +1169,7 @@ impl PeerManager {
     pub async fn get_msg_dst_peer(&self, ipv4_addr: &Ipv4Addr) -> (Vec<PeerId>, bool) {
         let mut is_exit_node = false;
         let mut dst_peers = vec![];
-        let network_length = self
-            .global_ctx
-            .get_ipv4()
-            .map(|x| x.network_length())
-            .unwrap_or(24);
-        let ipv4_inet = cidr::Ipv4Inet::new(*ipv4_addr, network_length).unwrap();
-        if ipv4_addr.is_broadcast()
-            || ipv4_addr.is_multicast()
-            || *ipv4_addr == ipv4_inet.last_address()
-        {
+        if self.broadcast_relay.is_broadcast_dst(ipv4_addr) {
             dst_peers.extend(self.peers.list_routes().await.iter().filter_map(|x| {
                 if *x.key() != self.my_peer_id {
                     Some(*x.key())
@@ -1068,15 +1183,13 @@ impl PeerManager {
             .global_ctx
             .is_ip_in_same_network(&std::net::IpAddr::V4(*ipv4_addr))
         {
-            for exit_node in &self.exit_nodes {
-                let IpAddr::V4(exit_node) = exit_node else {
-                    continue;
-                };
-                if let Some(peer_id) = self.peers.get_peer_id_by_ipv4(exit_node).await {
-                    dst_peers.push(peer_id);
-                    is_exit_node = true;
-                    break;
-                }
+            if let Some(peer_id) = self
+                .exit_nodes
+                .get_exit_peer_id(&self.peers, &IpAddr::V4(*ipv4_addr))
+                .await
+            {
+                dst_peers.push(peer_id);
+                is_exit_node = true;
             }
         }
         #[cfg(target_env = "ohos")]
@@ -1109,27 +1222,59 @@ impl PeerManager {
             dst_peers.push(peer_id);
         } else if !ipv6_addr.is_unicast_link_local() {
             // NOTE: never route link local address to exit node.
-            for exit_node in &self.exit_nodes {
-                let IpAddr::V6(exit_node) = exit_node else {
-                    continue;
-                };
-                if let Some(peer_id) = self.peers.get_peer_id_by_ipv6(exit_node).await {
-                    dst_peers.push(peer_id);
-                    is_exit_node = true;
-                    break;
-                }
+            if let Some(peer_id) = self
+                .exit_nodes
+                .get_exit_peer_id(&self.peers, &IpAddr::V6(*ipv6_addr))
+                .await
+            {
+                dst_peers.push(peer_id);
+                is_exit_node = true;
             }
         }
 
         (dst_peers, is_exit_node)
     }
 
+    /// Resolve the compression algo to use towards `peer_id`. Only directly connected peers
+    /// advertise their supported algos, others fall back to zstd which every version decodes.
+    async fn get_peer_compress_algo(
+        peers: &PeerMap,
+        peer_compress_algos: &DashMap<PeerId, CompressorAlgo>,
+        preferred: CompressorAlgo,
+        peer_id: PeerId,
+    ) -> CompressorAlgo {
+        if matches!(
+            preferred,
+            CompressorAlgo::None | CompressorAlgo::ZstdDefault
+        ) {
+            return preferred;
+        }
+
+        if let Some(algo) = peer_compress_algos.get(&peer_id) {
+            return *algo;
+        }
+
+        let Some(conn) = peers
+            .list_peer_conns(peer_id)
+            .await
+            .and_then(|conns| conns.into_iter().next())
+        else {
+            return negotiate_compress_algo(preferred, &[CompressorAlgo::ZstdDefault]);
+        };
+
+        let algo =
+            negotiate_compress_algo(preferred, &peer_supported_compress_algos(&conn.features));
+        peer_compress_algos.insert(peer_id, algo);
+        algo
+    }
+
     pub async fn try_compress_and_encrypt(
         compress_algo: CompressorAlgo,
+        compress_level: i32,
         encryptor: &Arc<dyn Encryptor + 'static>,
         msg: &mut ZCPacket,
     ) -> Result<(), Error> {
-        let compressor = DefaultCompressor {};
+        let compressor = DefaultCompressor::with_zstd_level(compress_level);
         compressor
             .compress(msg, compress_algo)
             .await
@@ -1162,27 +1307,93 @@ impl PeerManager {
             .await;
         }
 
-        let (dst_peers, is_exit_node) = match ip_addr {
+        let (mut dst_peers, is_exit_node) = match ip_addr {
             IpAddr::V4(ipv4_addr) => self.get_msg_dst_peer(&ipv4_addr).await,
             IpAddr::V6(ipv6_addr) => self.get_msg_dst_peer_ipv6(&ipv6_addr).await,
         };
 
+        if let IpAddr::V4(ipv4_addr) = ip_addr {
+            if self.broadcast_relay.is_broadcast_dst(&ipv4_addr) {
+                let mut snooping_peers = vec![];
+                if self.broadcast_relay.is_snooped_group(&ipv4_addr) {
+                    for peer_id in dst_peers.iter() {
+                        let snooping = self
+                            .peers
+                            .get_route_peer_info(*peer_id)
+                            .await
+                            .and_then(|x| x.feature_flag.map(|x| x.igmp_snooping))
+                            .unwrap_or(false);
+                        if snooping {
+                            snooping_peers.push(*peer_id);
+                        }
+                    }
+                }
+                if !self.broadcast_relay.filter_outgoing(
+                    msg.payload(),
+                    &mut dst_peers,
+                    &snooping_peers,
+                ) {
+                    return Ok(());
+                }
+            }
+        }
+
+        #[cfg(feature = "node-identity")]
+        dst_peers.retain(|peer_id| self.identity_verifier.is_verified(*peer_id));
+
         if dst_peers.is_empty() {
             tracing::info!("no peer id for ip: {}", ip_addr);
             return Ok(());
         }
 
+        if !self.global_ctx.get_bandwidth_limiter().try_consume_multi(
+            TrafficDirection::Upload,
+            &mut dst_peers,
+            Some(ip_addr),
+            msg.payload().len() as u64,
+        ) {
+            tracing::trace!(?ip_addr, "exceed upload bandwidth limit, drop");
+            return Ok(());
+        }
+
         self.self_tx_counters
             .compress_tx_bytes_before
             .add(msg.buf_len() as u64);
 
-        Self::try_compress_and_encrypt(self.data_compress_algo, &self.encryptor, &mut msg).await?;
+        // the packet is compressed once for all destinations, so fall back to zstd (or no
+        // compression if some peer can't decode anything) when they don't agree on an algo
+        let mut compress_algo = None;
+        for peer_id in dst_peers.iter() {
+            let algo = Self::get_peer_compress_algo(
+                &self.peers,
+                &self.peer_compress_algos,
+                self.data_compress_algo,
+                *peer_id,
+            )
+            .await;
+            compress_algo = match compress_algo {
+                None => Some(algo),
+                Some(prev) if prev == algo => Some(prev),
+                Some(CompressorAlgo::None) => Some(CompressorAlgo::None),
+                Some(_) if algo == CompressorAlgo::None => Some(CompressorAlgo::None),
+                Some(_) => Some(CompressorAlgo::ZstdDefault),
+            };
+        }
+        let compress_algo = compress_algo.unwrap_or(self.data_compress_algo);
+
+        Self::try_compress_and_encrypt(
+            compress_algo,
+            self.data_compress_level,
+            &self.encryptor,
+            &mut msg,
+        )
+        .await?;
 
         self.self_tx_counters
             .compress_tx_bytes_after
             .add(msg.buf_len() as u64);
 
-        let is_latency_first = self.global_ctx.get_flags().latency_first;
+        let is_latency_first = self.global_ctx.is_latency_first();
         msg.mut_peer_manager_header()
             .unwrap()
             .set_latency_first(is_latency_first)
@@ -1228,14 +1439,49 @@ impl PeerManager {
 
     async fn run_clean_peer_without_conn_routine(&self) {
         let peer_map = self.peers.clone();
+        let peer_compress_algos = self.peer_compress_algos.clone();
         self.tasks.lock().await.spawn(async move {
             loop {
                 peer_map.clean_peer_without_conn().await;
+                // a reconnected peer may run a different version, negotiate again
+                peer_compress_algos.retain(|peer_id, _| peer_map.has_peer(*peer_id));
                 tokio::time::sleep(std::time::Duration::from_secs(3)).await;
             }
         });
     }
 
+    async fn run_relay_quota_reset_routine(&self) {
+        let relay_quota = self.relay_quota.clone();
+        self.tasks.lock().await.spawn(async move {
+            loop {
+                // no packet is relayed to trigger the check once the quota is exhausted
+                relay_quota.check_day();
+                tokio::time::sleep(RESET_CHECK_INTERVAL).await;
+            }
+        });
+    }
+
+    // the kernel only reports the multicast groups it joined when asked by a querier, so
+    // query the local nic like a router on the lan would
+    async fn run_igmp_querier_routine(&self) {
+        if !self.broadcast_relay.igmp_snooping_enabled() {
+            return;
+        }
+        let my_peer_id = self.my_peer_id;
+        let nic_channel = self.nic_channel.clone();
+        self.tasks.lock().await.spawn(async move {
+            let query = build_igmp_general_query();
+            loop {
+                let mut packet = ZCPacket::new_with_payload(&query);
+                packet.fill_peer_manager_hdr(my_peer_id, my_peer_id, PacketType::Data as u8);
+                if nic_channel.send(packet).await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(IGMP_QUERY_INTERVAL).await;
+            }
+        });
+    }
+
     async fn run_foriegn_network(&self) {
         self.peer_rpc_tspt
             .foreign_peers
@@ -1254,9 +1500,26 @@ impl PeerManager {
 
         self.init_packet_process_pipeline().await;
         self.peer_rpc_mgr.run();
+        self.secret_rotation.register_rpc_server(&self.peer_rpc_mgr);
+        self.exit_nodes.register_rpc_server(&self.peer_rpc_mgr);
+        #[cfg(feature = "node-identity")]
+        self.identity_verifier
+            .register_rpc_server(&self.peer_rpc_mgr);
 
         self.start_peer_recv().await;
         self.run_clean_peer_without_conn_routine().await;
+        self.run_relay_quota_reset_routine().await;
+        self.run_igmp_querier_routine().await;
+        self.exit_nodes
+            .run_probe_routine(&mut *self.tasks.lock().await)
+            .await;
+        self.exit_nodes
+            .run_internet_check_routine(&mut *self.tasks.lock().await)
+            .await;
+        #[cfg(feature = "node-identity")]
+        self.identity_verifier
+            .run_verify_routine(&mut *self.tasks.lock().await)
+            .await;
 
         self.run_foriegn_network().await;
 
@@ -1271,6 +1534,18 @@ impl PeerManager {
         self.peer_rpc_mgr.clone()
     }
 
+    pub fn get_secret_rotation(&self) -> Arc<SecretRotationManager> {
+        self.secret_rotation.clone()
+    }
+
+    pub fn get_exit_node_manager(&self) -> Arc<ExitNodeManager> {
+        self.exit_nodes.clone()
+    }
+
+    pub fn get_relay_quota(&self) -> Arc<RelayQuota> {
+        self.relay_quota.clone()
+    }
+
     pub fn my_node_id(&self) -> uuid::Uuid {
         self.global_ctx.get_id()
     }
@@ -1329,6 +1604,8 @@ impl PeerManager {
             version: EASYTIER_VERSION.to_string(),
             feature_flag: Some(self.global_ctx.get_feature_flags()),
             ip_list: Some(self.global_ctx.get_ip_collector().collect_ip_addrs().await),
+            relay_bytes_today: self.relay_quota.used_today(),
+            relay_daily_quota: self.relay_quota.daily_quota(),
         }
     }
 
@@ -1390,7 +1667,7 @@ impl PeerManager {
             return false;
         }
 
-        let next_hop_policy = Self::get_next_hop_policy(self.global_ctx.get_flags().latency_first);
+        let next_hop_policy = Self::get_next_hop_policy(self.global_ctx.is_latency_first());
         // check relay node allow relay kcp.
         let Some(next_hop_id) = route
             .get_next_hop_with_policy(dst_peer_id, next_hop_policy)
@@ -1427,7 +1704,10 @@ mod tests {
     use std::{fmt::Debug, sync::Arc, time::Duration};
 
     use crate::{
-        common::{config::Flags, global_ctx::tests::get_mock_global_ctx},
+        common::{
+            config::{Flags, RouteCostConfig},
+            global_ctx::tests::get_mock_global_ctx,
+        },
         connector::{
             create_connector_by_url, direct::PeerManagerForDirectConnector,
             udp_hole_punch::tests::create_mock_peer_manager_with_mock_stun,
@@ -1447,6 +1727,7 @@ mod tests {
         tunnel::{
             common::tests::wait_for_condition,
             filter::{tests::DropSendTunnelFilter, TunnelWithFilter},
+            packet_def::ZCPacket,
             ring::create_ring_tunnel_pair,
             TunnelConnector, TunnelListener,
         },
@@ -1690,6 +1971,133 @@ mod tests {
         assert_eq!(ret, Some(peer_mgr_b.my_peer_id));
     }
 
+    #[tokio::test]
+    async fn test_manual_route_cost_data_path() {
+        // a->b->c
+        // a->d->e->c
+        let peer_mgr_a = create_mock_peer_manager_with_mock_stun(NatType::Unknown).await;
+        let peer_mgr_b = create_mock_peer_manager_with_mock_stun(NatType::Unknown).await;
+        let peer_mgr_d = create_mock_peer_manager_with_mock_stun(NatType::Unknown).await;
+        let peer_mgr_e = create_mock_peer_manager_with_mock_stun(NatType::Unknown).await;
+        let (s, mut c_recv) = create_packet_recv_chan();
+        let peer_mgr_c = Arc::new(PeerManager::new(
+            RouteAlgoType::Ospf,
+            get_mock_global_ctx(),
+            s,
+        ));
+        peer_mgr_c.run().await.unwrap();
+
+        peer_mgr_b
+            .get_global_ctx()
+            .set_hostname("peer-b".to_string());
+        peer_mgr_c
+            .get_global_ctx()
+            .set_ipv4(Some("10.144.144.3/24".parse().unwrap()));
+
+        connect_peer_manager(peer_mgr_a.clone(), peer_mgr_b.clone()).await;
+        connect_peer_manager(peer_mgr_b.clone(), peer_mgr_c.clone()).await;
+        connect_peer_manager(peer_mgr_a.clone(), peer_mgr_d.clone()).await;
+        connect_peer_manager(peer_mgr_d.clone(), peer_mgr_e.clone()).await;
+        connect_peer_manager(peer_mgr_e.clone(), peer_mgr_c.clone()).await;
+
+        wait_route_appear_with_cost(peer_mgr_a.clone(), peer_mgr_c.my_peer_id, Some(2))
+            .await
+            .unwrap();
+
+        // send data to c by its virtual ip until it arrives after the given number of hops
+        async fn wait_hops(
+            peer_mgr: &PeerManager,
+            recv: &mut crate::peers::PacketRecvChanReceiver,
+            hops: u8,
+        ) {
+            for _ in 0..50 {
+                let _ = peer_mgr
+                    .send_msg_by_ip(
+                        ZCPacket::new_with_payload(b"hello"),
+                        "10.144.144.3".parse().unwrap(),
+                    )
+                    .await;
+                let Ok(Some(packet)) =
+                    tokio::time::timeout(Duration::from_millis(100), recv.recv()).await
+                else {
+                    continue;
+                };
+                if packet.peer_manager_header().unwrap().forward_counter == hops {
+                    return;
+                }
+            }
+            panic!("data to c never took {} hops", hops);
+        }
+
+        // least hop routing relays through b
+        wait_hops(&peer_mgr_a, &mut c_recv, 2).await;
+
+        // a manual cost alone does not turn on latency first
+        peer_mgr_a
+            .get_global_ctx()
+            .set_route_costs(vec![RouteCostConfig {
+                peer: "peer-b".to_string(),
+                link_to: None,
+                cost: 1000,
+            }]);
+        assert!(!peer_mgr_a.get_global_ctx().is_latency_first());
+
+        let mut flags = peer_mgr_a.get_global_ctx().get_flags();
+        flags.latency_first = true;
+        peer_mgr_a.get_global_ctx().set_flags(flags);
+        assert!(peer_mgr_a.get_global_ctx().is_latency_first());
+        wait_hops(&peer_mgr_a, &mut c_recv, 3).await;
+    }
+
+    #[tokio::test]
+    async fn test_no_relay_data() {
+        let peer_mgr_a = create_mock_peer_manager_with_mock_stun(NatType::Unknown).await;
+        let peer_mgr_b = create_mock_peer_manager_with_mock_stun(NatType::Unknown).await;
+        let peer_mgr_c = create_mock_peer_manager_with_mock_stun(NatType::Unknown).await;
+
+        connect_peer_manager(peer_mgr_a.clone(), peer_mgr_b.clone()).await;
+        connect_peer_manager(peer_mgr_b.clone(), peer_mgr_c.clone()).await;
+
+        wait_route_appear_with_cost(peer_mgr_a.clone(), peer_mgr_c.my_peer_id, Some(2))
+            .await
+            .unwrap();
+
+        // unlike avoid_relay_data, b is not used even if it is the only path
+        peer_mgr_b
+            .get_global_ctx()
+            .set_feature_flags(PeerFeatureFlag {
+                no_relay_data: true,
+                ..Default::default()
+            });
+        wait_for_condition(
+            || async {
+                peer_mgr_a
+                    .get_route()
+                    .get_next_hop(peer_mgr_c.my_peer_id)
+                    .await
+                    .is_none()
+            },
+            Duration::from_secs(5),
+        )
+        .await;
+
+        // b itself still reaches both of them
+        assert_eq!(
+            peer_mgr_b
+                .get_route()
+                .get_next_hop(peer_mgr_c.my_peer_id)
+                .await,
+            Some(peer_mgr_c.my_peer_id)
+        );
+        assert_eq!(
+            peer_mgr_a
+                .get_route()
+                .get_next_hop(peer_mgr_b.my_peer_id)
+                .await,
+            Some(peer_mgr_b.my_peer_id)
+        );
+    }
+
     #[tokio::test]
     async fn test_client_inbound_blackhole() {
         let peer_mgr_a = create_mock_peer_manager_with_mock_stun(NatType::Unknown).await;
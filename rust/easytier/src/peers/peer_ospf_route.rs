@@ -30,13 +30,16 @@ use tokio::{
 
 use crate::{
     common::{
-        config::NetworkIdentity, constants::EASYTIER_VERSION, global_ctx::ArcGlobalCtx,
-        stun::StunInfoCollectorTrait, PeerId,
+        config::{NetworkIdentity, RouteCostConfig},
+        constants::EASYTIER_VERSION,
+        global_ctx::ArcGlobalCtx,
+        stun::StunInfoCollectorTrait,
+        PeerId,
     },
     peers::route_trait::{Route, RouteInterfaceBox},
     proto::{
         acl::GroupIdentity,
-        common::{Ipv4Inet, NatType, StunInfo},
+        common::{Ipv4Inet, NatType, RouteCostPolicy, StunInfo},
         peer_rpc::{
             route_foreign_network_infos, route_foreign_network_summary,
             ForeignNetworkRouteInfoEntry, ForeignNetworkRouteInfoKey, OspfRouteRpc,
@@ -67,6 +70,10 @@ static UPDATE_PEER_INFO_PERIOD: Duration = Duration::from_secs(3600);
 static REMOVE_DEAD_PEER_INFO_AFTER: Duration = Duration::from_secs(3660);
 // the cost (latency between two peers) is i32, i32::MAX is large enough.
 static AVOID_RELAY_COST: usize = i32::MAX as usize;
+// one percent of loss outweighs the latency of any sane route.
+static LOSS_PERCENT_COST: usize = 10_000;
+// a relayed route has to be this much faster than the direct link to be used.
+static RELAY_HOP_COST: usize = 1000;
 
 type Version = u32;
 
@@ -372,6 +379,14 @@ impl SyncedRouteInfo {
             .unwrap_or_default()
     }
 
+    fn get_no_relay_data(&self, peer_id: PeerId) -> bool {
+        self.peer_infos
+            .get(&peer_id)
+            .and_then(|x| x.value().feature_flag)
+            .map(|x| x.no_relay_data)
+            .unwrap_or_default()
+    }
+
     fn check_duplicate_peer_id(
         &self,
         my_peer_id: PeerId,
@@ -709,7 +724,88 @@ impl SyncedRouteInfo {
     }
 }
 
-type PeerGraph = Graph<PeerId, usize, Directed>;
+// routes are compared by cost, latency is only summed up to be shown with the route.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+struct LinkCost {
+    cost: usize,
+    latency: usize,
+}
+
+impl std::ops::Add for LinkCost {
+    type Output = LinkCost;
+
+    fn add(self, rhs: LinkCost) -> LinkCost {
+        LinkCost {
+            cost: self.cost + rhs.cost,
+            latency: self.latency + rhs.latency,
+        }
+    }
+}
+
+// cost weights from the config, resolved to the peers currently in the network. only this node
+// knows them, so they decide the first hop and every relay on the way routes by its own costs.
+#[derive(Debug, Default)]
+struct ManualRouteCosts {
+    // added to every link out of the peer, so routes relayed through it
+    relay: HashMap<PeerId, usize>,
+    link: HashMap<(PeerId, PeerId), usize>,
+}
+
+impl ManualRouteCosts {
+    fn resolve(configs: &[RouteCostConfig], synced_info: &SyncedRouteInfo) -> Self {
+        let mut ret = ManualRouteCosts::default();
+        if configs.is_empty() {
+            return ret;
+        }
+
+        let match_peers = |name: &str| -> Vec<PeerId> {
+            let ipv4 = name.parse::<Ipv4Addr>().ok();
+            synced_info
+                .peer_infos
+                .iter()
+                .filter(|info| {
+                    info.hostname.as_deref() == Some(name)
+                        || (ipv4.is_some() && info.ipv4_addr.map(Ipv4Addr::from) == ipv4)
+                })
+                .map(|info| *info.key())
+                .collect()
+        };
+
+        for config in configs {
+            let cost = config.cost as usize;
+            let peers = match_peers(&config.peer);
+            match &config.link_to {
+                None => {
+                    for peer_id in peers {
+                        *ret.relay.entry(peer_id).or_default() += cost;
+                    }
+                }
+                Some(link_to) => {
+                    let other_peers = match_peers(link_to);
+                    for peer_id in peers.iter() {
+                        for other_peer_id in other_peers.iter() {
+                            *ret.link.entry((*peer_id, *other_peer_id)).or_default() += cost;
+                            *ret.link.entry((*other_peer_id, *peer_id)).or_default() += cost;
+                        }
+                    }
+                }
+            }
+        }
+        ret
+    }
+
+    fn cost(&self, my_peer_id: PeerId, src: PeerId, dst: PeerId) -> usize {
+        // routes starting at a peer are not relayed through it
+        let relay_cost = if src == my_peer_id {
+            0
+        } else {
+            self.relay.get(&src).copied().unwrap_or(0)
+        };
+        relay_cost + self.link.get(&(src, dst)).copied().unwrap_or(0)
+    }
+}
+
+type PeerGraph = Graph<PeerId, LinkCost, Directed>;
 type PeerIdToNodexIdxMap = DashMap<PeerId, NodeIndex>;
 #[derive(Debug, Clone, Copy)]
 struct NextHopInfo {
@@ -774,7 +870,9 @@ impl RouteTable {
     fn build_peer_graph_from_synced_info<T: RouteCostCalculatorInterface>(
         my_peer_id: PeerId,
         synced_info: &SyncedRouteInfo,
+        cost_policy: &NextHopPolicy,
         cost_calc: &T,
+        manual_costs: &ManualRouteCosts,
     ) -> (PeerGraph, NodeIndex) {
         let mut graph: PeerGraph = PeerGraph::new();
 
@@ -803,6 +901,12 @@ impl RouteTable {
         for item in peer_id_to_node_index.iter() {
             let src_peer_id = item.key();
             let src_node_idx = item.value();
+
+            // a peer that never relays data only keeps the links its own routes start with.
+            if *src_peer_id != my_peer_id && synced_info.get_no_relay_data(*src_peer_id) {
+                continue;
+            }
+
             let connected_peers: BTreeSet<_> = synced_info
                 .get_connected_peers(*src_peer_id)
                 .unwrap_or_default();
@@ -815,12 +919,21 @@ impl RouteTable {
                     continue;
                 };
 
-                let mut cost = cost_calc.calculate_cost(*src_peer_id, *dst_peer_id) as usize;
+                let latency = cost_calc.calculate_cost(*src_peer_id, *dst_peer_id) as usize;
+                let mut cost = match cost_policy {
+                    NextHopPolicy::LeastHop | NextHopPolicy::LeastCost => latency,
+                    NextHopPolicy::LeastLoss => {
+                        let loss = cost_calc.calculate_loss_percent(*src_peer_id, *dst_peer_id);
+                        latency + loss as usize * LOSS_PERCENT_COST
+                    }
+                    NextHopPolicy::PreferDirect => latency + RELAY_HOP_COST,
+                };
+                cost += manual_costs.cost(my_peer_id, *src_peer_id, *dst_peer_id);
                 if peer_avoid_relay_data {
                     cost += AVOID_RELAY_COST;
                 }
 
-                graph.add_edge(*src_node_idx, *dst_node_idx, cost);
+                graph.add_edge(*src_node_idx, *dst_node_idx, LinkCost { cost, latency });
             }
         }
 
@@ -857,8 +970,8 @@ impl RouteTable {
         start_node: &NodeIndex,
         version: Version,
     ) {
-        let normalize_edge_cost = |e: petgraph::graph::EdgeReference<usize>| {
-            if *e.weight() >= AVOID_RELAY_COST {
+        let normalize_edge_cost = |e: petgraph::graph::EdgeReference<LinkCost>| {
+            if e.weight().cost >= AVOID_RELAY_COST {
                 AVOID_RELAY_COST + 1
             } else {
                 1
@@ -905,7 +1018,7 @@ impl RouteTable {
         for (dst, (next_hop, path_len)) in next_hops.iter() {
             let info = NextHopInfo {
                 next_hop_peer_id: *graph.node_weight(*next_hop).unwrap(),
-                path_latency: (*costs.get(dst).unwrap() % AVOID_RELAY_COST) as i32,
+                path_latency: costs.get(dst).unwrap().latency as i32,
                 path_len: { *path_len },
                 version,
             };
@@ -928,20 +1041,28 @@ impl RouteTable {
         my_peer_id: PeerId,
         synced_info: &SyncedRouteInfo,
         policy: NextHopPolicy,
+        // weighs the links, least hop routing uses it to pick among routes of the same length
+        cost_policy: NextHopPolicy,
         cost_calc: &T,
+        manual_costs: &ManualRouteCosts,
     ) {
         let version = synced_info.version.get();
 
         // build next hop map
-        let (graph, start_node) =
-            Self::build_peer_graph_from_synced_info(my_peer_id, synced_info, cost_calc);
+        let (graph, start_node) = Self::build_peer_graph_from_synced_info(
+            my_peer_id,
+            synced_info,
+            &cost_policy,
+            cost_calc,
+            manual_costs,
+        );
 
         if graph.node_count() == 0 {
             tracing::warn!("no peer in graph, cannot build next hop map");
             return;
         }
 
-        if matches!(policy, NextHopPolicy::LeastHop) {
+        if policy.is_least_hop() {
             self.gen_next_hop_map_with_least_hop(&graph, &start_node, version);
         } else {
             self.gen_next_hop_map_with_least_cost(&graph, &start_node, version);
@@ -1213,6 +1334,8 @@ struct PeerRouteServiceImpl {
     synced_route_info: SyncedRouteInfo,
     cached_local_conn_map: std::sync::Mutex<RouteConnBitmap>,
     cached_local_conn_map_version: AtomicVersion,
+    // cost policy and manual costs the route tables were last built with
+    applied_route_cost_config: std::sync::Mutex<(RouteCostPolicy, Vec<RouteCostConfig>)>,
 
     last_update_my_foreign_network: AtomicCell<Option<std::time::Instant>>,
 
@@ -1270,6 +1393,10 @@ impl PeerRouteServiceImpl {
             },
             cached_local_conn_map: std::sync::Mutex::new(RouteConnBitmap::new()),
             cached_local_conn_map_version: AtomicVersion::new(),
+            applied_route_cost_config: std::sync::Mutex::new((
+                RouteCostPolicy::LeastLatency,
+                Vec::new(),
+            )),
 
             last_update_my_foreign_network: AtomicCell::new(None),
 
@@ -1370,19 +1497,27 @@ impl PeerRouteServiceImpl {
             .begin_update();
 
         let calc_locked = self.cost_calculator.read().unwrap();
+        let policy = self.global_ctx.get_flags().route_cost_policy();
+        let route_costs = self.global_ctx.config.get_route_costs();
+        let manual_costs = ManualRouteCosts::resolve(&route_costs, &self.synced_route_info);
+        *self.applied_route_cost_config.lock().unwrap() = (policy, route_costs);
 
         self.route_table.build_from_synced_info(
             self.my_peer_id,
             &self.synced_route_info,
             NextHopPolicy::LeastHop,
+            policy.into(),
             calc_locked.as_ref().unwrap(),
+            &manual_costs,
         );
 
         self.route_table_with_cost.build_from_synced_info(
             self.my_peer_id,
             &self.synced_route_info,
-            NextHopPolicy::LeastCost,
+            policy.into(),
+            policy.into(),
             calc_locked.as_ref().unwrap(),
+            &manual_costs,
         );
 
         drop(calc_locked);
@@ -1439,6 +1574,12 @@ impl PeerRouteServiceImpl {
             .unwrap_or(false)
     }
 
+    fn route_cost_config_changed(&self) -> bool {
+        let applied = self.applied_route_cost_config.lock().unwrap();
+        applied.0 != self.global_ctx.get_flags().route_cost_policy()
+            || applied.1 != self.global_ctx.config.get_route_costs()
+    }
+
     fn update_route_table_and_cached_local_conn_bitmap(&self) {
         self.update_peer_info_last_update();
 
@@ -2258,7 +2399,9 @@ impl PeerRoute {
                 session_mgr.sync_now("update_my_infos");
             }
 
-            if service_impl.cost_calculator_need_update() {
+            if service_impl.cost_calculator_need_update()
+                || service_impl.route_cost_config_changed()
+            {
                 tracing::debug!("cost_calculator_need_update");
                 service_impl.synced_route_info.version.inc();
                 service_impl.update_route_table();
@@ -2351,10 +2494,11 @@ impl Route for PeerRoute {
         dst_peer_id: PeerId,
         policy: NextHopPolicy,
     ) -> Option<PeerId> {
-        let route_table = if matches!(policy, NextHopPolicy::LeastCost) {
-            &self.service_impl.route_table_with_cost
-        } else {
+        // the cost table is built with the route cost policy from the flags
+        let route_table = if policy.is_least_hop() {
             &self.service_impl.route_table
+        } else {
+            &self.service_impl.route_table_with_cost
         };
         route_table
             .get_next_hop(dst_peer_id)
@@ -2523,7 +2667,7 @@ mod tests {
     use prost_reflect::{DynamicMessage, ReflectMessage};
 
     use crate::{
-        common::{global_ctx::tests::get_mock_global_ctx, PeerId},
+        common::{config::RouteCostConfig, global_ctx::tests::get_mock_global_ctx, PeerId},
         connector::udp_hole_punch::tests::replace_stun_info_collector,
         peers::{
             create_packet_recv_chan,
@@ -2533,7 +2677,7 @@ mod tests {
             tests::{connect_peer_manager, create_mock_peer_manager},
         },
         proto::{
-            common::NatType,
+            common::{NatType, RouteCostPolicy},
             peer_rpc::{RoutePeerInfo, RoutePeerInfos, SyncRouteInfoRequest},
         },
         tunnel::common::tests::wait_for_condition,
@@ -2964,6 +3108,117 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_route_cost_policy() {
+        let p_a = create_mock_pmgr().await;
+        let p_b = create_mock_pmgr().await;
+        let p_c = create_mock_pmgr().await;
+        let p_d = create_mock_pmgr().await;
+        p_c.get_global_ctx().set_hostname("peer-c".to_string());
+        connect_peer_manager(p_a.clone(), p_b.clone()).await;
+        connect_peer_manager(p_a.clone(), p_c.clone()).await;
+        connect_peer_manager(p_d.clone(), p_b.clone()).await;
+        connect_peer_manager(p_d.clone(), p_c.clone()).await;
+        connect_peer_manager(p_b.clone(), p_c.clone()).await;
+
+        let _r_a = create_mock_route(p_a.clone()).await;
+        let _r_b = create_mock_route(p_b.clone()).await;
+        let _r_c = create_mock_route(p_c.clone()).await;
+        let r_d = create_mock_route(p_d.clone()).await;
+
+        wait_for_condition(
+            || async { (r_d.get_next_hop(p_a.my_peer_id()).await).is_some() },
+            Duration::from_secs(5),
+        )
+        .await;
+
+        struct TestCostCalculator {
+            p_a_peer_id: PeerId,
+            p_b_peer_id: PeerId,
+            p_c_peer_id: PeerId,
+            p_d_peer_id: PeerId,
+        }
+
+        impl RouteCostCalculatorInterface for TestCostCalculator {
+            fn calculate_cost(&self, src: PeerId, dst: PeerId) -> i32 {
+                if src == self.p_d_peer_id && dst == self.p_b_peer_id {
+                    return 100;
+                }
+                if src == self.p_c_peer_id && dst == self.p_a_peer_id {
+                    return 101;
+                }
+                if src == self.p_c_peer_id && dst == self.p_b_peer_id {
+                    return 2;
+                }
+                1
+            }
+
+            fn calculate_loss_percent(&self, src: PeerId, dst: PeerId) -> u32 {
+                if src == self.p_d_peer_id && dst == self.p_c_peer_id {
+                    return 5;
+                }
+                0
+            }
+
+            // rebuild the route table after the flags of the test changed
+            fn need_update(&self) -> bool {
+                true
+            }
+        }
+
+        r_d.set_route_cost_fn(Box::new(TestCostCalculator {
+            p_a_peer_id: p_a.my_peer_id(),
+            p_b_peer_id: p_b.my_peer_id(),
+            p_c_peer_id: p_c.my_peer_id(),
+            p_d_peer_id: p_d.my_peer_id(),
+        }))
+        .await;
+
+        let set_policy = |policy: RouteCostPolicy| {
+            let mut flags = p_d.get_global_ctx().get_flags();
+            flags.route_cost_policy = policy.into();
+            p_d.get_global_ctx().set_flags(flags);
+        };
+        let wait_next_hop = |dst: PeerId, next_hop: PeerId| {
+            let r_d = r_d.clone();
+            async move {
+                wait_for_condition(
+                    || async {
+                        r_d.get_next_hop_with_policy(dst, NextHopPolicy::LeastCost)
+                            .await
+                            == Some(next_hop)
+                    },
+                    Duration::from_secs(5),
+                )
+                .await;
+            }
+        };
+
+        // least latency takes the lossy link to p_c
+        wait_next_hop(p_a.my_peer_id(), p_c.my_peer_id()).await;
+        wait_next_hop(p_b.my_peer_id(), p_c.my_peer_id()).await;
+
+        set_policy(RouteCostPolicy::LeastLoss);
+        wait_next_hop(p_a.my_peer_id(), p_b.my_peer_id()).await;
+
+        // the direct link to p_b is used although relaying through p_c is faster
+        set_policy(RouteCostPolicy::PreferDirect);
+        wait_next_hop(p_b.my_peer_id(), p_b.my_peer_id()).await;
+
+        set_policy(RouteCostPolicy::LeastLatency);
+        wait_next_hop(p_b.my_peer_id(), p_c.my_peer_id()).await;
+
+        // avoid relaying through p_c, but still reach it directly
+        p_d.get_global_ctx().set_route_costs(vec![RouteCostConfig {
+            peer: "peer-c".to_string(),
+            link_to: None,
+            cost: 1000,
+        }]);
+        wait_next_hop(p_a.my_peer_id(), p_b.my_peer_id()).await;
+        wait_next_hop(p_b.my_peer_id(), p_b.my_peer_id()).await;
+        wait_next_hop(p_c.my_peer_id(), p_c.my_peer_id()).await;
+    }
+
     #[tokio::test]
     async fn test_raw_peer_info() {
         let mut req = SyncRouteInfoRequest::default();
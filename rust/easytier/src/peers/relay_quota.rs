@@ -0,0 +1,191 @@
+// limits how much data of other peers this node relays, for members on metered links.
+//
+// other peers learn from the no_relay_data feature flag that they have to route around this
+// node, packets that still arrive (routes not converged yet) are dropped. avoid_relay_data is
+// set as well, so peers that don't know no_relay_data yet at least prefer other routes. rpc
+// and handshake packets are always relayed, so hole punching through this node keeps working.
+//
+// the usage of the day is only kept in memory, a restart starts with the full quota again.
+
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    common::{
+        global_ctx::ArcGlobalCtx,
+        stats_manager::{CounterHandle, LabelSet, LabelType, MetricName},
+    },
+    tunnel::packet_def::PacketType,
+};
+
+const SECS_PER_DAY: u64 = 24 * 3600;
+
+/// How often an exhausted quota checks whether the day is over.
+pub const RESET_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// days are counted in utc, so the quota of all members resets at the same time
+fn current_day() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / SECS_PER_DAY)
+        .unwrap_or(0)
+}
+
+fn is_relay_data_packet(packet_type: u8) -> bool {
+    packet_type == PacketType::Data as u8
+        || packet_type == PacketType::KcpSrc as u8
+        || packet_type == PacketType::KcpDst as u8
+}
+
+pub struct RelayQuota {
+    global_ctx: ArcGlobalCtx,
+    disabled: bool,
+    // avoid_relay_data as set without the quota, restored when relaying is allowed again
+    configured_avoid_relay: bool,
+    // bytes per day, 0 means unlimited
+    daily_quota: u64,
+
+    day: AtomicU64,
+    used_today: AtomicU64,
+    exhausted: AtomicBool,
+
+    relayed_bytes: CounterHandle,
+}
+
+impl RelayQuota {
+    pub fn new(global_ctx: ArcGlobalCtx) -> Self {
+        let flags = global_ctx.get_flags();
+        let relayed_bytes = global_ctx.stats_manager().get_counter(
+            MetricName::TrafficBytesRelayed,
+            LabelSet::new().with_label_type(LabelType::NetworkName(global_ctx.get_network_name())),
+        );
+        let ret = RelayQuota {
+            configured_avoid_relay: global_ctx.get_feature_flags().avoid_relay_data,
+            global_ctx,
+            disabled: flags.disable_relay_data,
+            daily_quota: flags.relay_daily_quota_mb.saturating_mul(1024 * 1024),
+            day: AtomicU64::new(current_day()),
+            used_today: AtomicU64::new(0),
+            exhausted: AtomicBool::new(false),
+            relayed_bytes,
+        };
+        if ret.disabled {
+            ret.set_relay_allowed(false);
+        }
+        ret
+    }
+
+    fn set_relay_allowed(&self, allowed: bool) {
+        let mut f = self.global_ctx.get_feature_flags();
+        f.no_relay_data = !allowed;
+        f.avoid_relay_data = !allowed || self.configured_avoid_relay;
+        self.global_ctx.set_feature_flags(f);
+    }
+
+    /// Starts a new quota when the day changed.
+    pub fn check_day(&self) {
+        let today = current_day();
+        if self.day.swap(today, Ordering::Relaxed) == today {
+            return;
+        }
+        self.used_today.store(0, Ordering::Relaxed);
+        if self.exhausted.swap(false, Ordering::Relaxed) {
+            tracing::info!("new day, relaying data for other peers again");
+            self.set_relay_allowed(true);
+        }
+    }
+
+    /// Counts a packet relayed for other peers, returns false if it has to be dropped.
+    pub fn try_consume(&self, packet_type: u8, bytes: u64) -> bool {
+        if !is_relay_data_packet(packet_type) {
+            return true;
+        }
+        if self.disabled {
+            return false;
+        }
+        if self.daily_quota > 0 {
+            self.check_day();
+            if self.exhausted.load(Ordering::Relaxed) {
+                return false;
+            }
+        }
+
+        let used = self.used_today.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.relayed_bytes.add(bytes);
+
+        if self.daily_quota > 0
+            && used >= self.daily_quota
+            && !self.exhausted.swap(true, Ordering::Relaxed)
+        {
+            tracing::warn!(
+                used,
+                daily_quota = self.daily_quota,
+                "relay quota exhausted, stop relaying data for other peers"
+            );
+            self.set_relay_allowed(false);
+        }
+        true
+    }
+
+    /// Bytes relayed since the start of the day, or since the start of the process.
+    pub fn used_today(&self) -> u64 {
+        self.used_today.load(Ordering::Relaxed)
+    }
+
+    pub fn daily_quota(&self) -> u64 {
+        self.daily_quota
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{common::global_ctx::tests::get_mock_global_ctx, tunnel::packet_def::PacketType};
+
+    use super::RelayQuota;
+
+    #[tokio::test]
+    async fn relay_quota_exhausted() {
+        let global_ctx = get_mock_global_ctx();
+        let mut flags = global_ctx.get_flags();
+        flags.relay_daily_quota_mb = 1;
+        global_ctx.set_flags(flags);
+
+        let quota = RelayQuota::new(global_ctx.clone());
+        let data = PacketType::Data as u8;
+        assert!(quota.try_consume(data, 1024 * 1024 - 1));
+        assert!(!global_ctx.get_feature_flags().no_relay_data);
+
+        // the packet reaching the quota still goes through
+        assert!(quota.try_consume(data, 1));
+        assert!(global_ctx.get_feature_flags().no_relay_data);
+        assert!(global_ctx.get_feature_flags().avoid_relay_data);
+        assert!(!quota.try_consume(data, 1));
+
+        // control packets are not limited
+        assert!(quota.try_consume(PacketType::RpcReq as u8, 100));
+        assert_eq!(quota.used_today(), 1024 * 1024);
+
+        // a new day restores relaying
+        quota.day.store(0, std::sync::atomic::Ordering::Relaxed);
+        quota.check_day();
+        assert!(!global_ctx.get_feature_flags().no_relay_data);
+        assert!(!global_ctx.get_feature_flags().avoid_relay_data);
+        assert!(quota.try_consume(data, 1));
+    }
+
+    #[tokio::test]
+    async fn relay_disabled() {
+        let global_ctx = get_mock_global_ctx();
+        let mut flags = global_ctx.get_flags();
+        flags.disable_relay_data = true;
+        global_ctx.set_flags(flags);
+
+        let quota = RelayQuota::new(global_ctx.clone());
+        assert!(global_ctx.get_feature_flags().no_relay_data);
+        assert!(global_ctx.get_feature_flags().avoid_relay_data);
+        assert!(!quota.try_consume(PacketType::Data as u8, 1));
+        assert!(quota.try_consume(PacketType::RpcResp as u8, 1));
+    }
+}
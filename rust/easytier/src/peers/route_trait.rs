@@ -7,9 +7,12 @@ use dashmap::DashMap;
 
 use crate::{
     common::{global_ctx::NetworkIdentity, PeerId},
-    proto::peer_rpc::{
-        ForeignNetworkRouteInfoEntry, ForeignNetworkRouteInfoKey, RouteForeignNetworkInfos,
-        RouteForeignNetworkSummary, RoutePeerInfo,
+    proto::{
+        common::RouteCostPolicy,
+        peer_rpc::{
+            ForeignNetworkRouteInfoEntry, ForeignNetworkRouteInfoKey, RouteForeignNetworkInfos,
+            RouteForeignNetworkSummary, RoutePeerInfo,
+        },
     },
 };
 
@@ -18,6 +21,26 @@ pub enum NextHopPolicy {
     #[default]
     LeastHop,
     LeastCost,
+    // loss of a link weighs more than its latency
+    LeastLoss,
+    // every relay hop adds a fixed penalty, so direct links win unless much slower
+    PreferDirect,
+}
+
+impl NextHopPolicy {
+    pub fn is_least_hop(&self) -> bool {
+        matches!(self, NextHopPolicy::LeastHop)
+    }
+}
+
+impl From<RouteCostPolicy> for NextHopPolicy {
+    fn from(policy: RouteCostPolicy) -> Self {
+        match policy {
+            RouteCostPolicy::LeastLatency => NextHopPolicy::LeastCost,
+            RouteCostPolicy::LeastLoss => NextHopPolicy::LeastLoss,
+            RouteCostPolicy::PreferDirect => NextHopPolicy::PreferDirect,
+        }
+    }
 }
 
 pub type ForeignNetworkRouteInfoMap =
@@ -43,6 +66,10 @@ pub trait RouteCostCalculatorInterface: Send + Sync {
         1
     }
 
+    fn calculate_loss_percent(&self, _src: PeerId, _dst: PeerId) -> u32 {
+        0
+    }
+
     fn need_update(&self) -> bool {
         false
     }
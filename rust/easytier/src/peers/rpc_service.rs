@@ -1,21 +1,26 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use crate::{
-    common::acl_processor::AclRuleBuilder,
+    common::{acl_processor::AclRuleBuilder, config::BandwidthLimitConfig},
     proto::{
         cli::{
-            AclManageRpc, DumpRouteRequest, DumpRouteResponse, GetAclStatsRequest,
-            GetAclStatsResponse, GetWhitelistRequest, GetWhitelistResponse,
-            ListForeignNetworkRequest, ListForeignNetworkResponse, ListGlobalForeignNetworkRequest,
-            ListGlobalForeignNetworkResponse, ListPeerRequest, ListPeerResponse, ListRouteRequest,
-            ListRouteResponse, PeerInfo, PeerManageRpc, SetWhitelistRequest, SetWhitelistResponse,
-            ShowNodeInfoRequest, ShowNodeInfoResponse,
+            AclManageRpc, BandwidthLimitManageRpc, DumpRouteRequest, DumpRouteResponse,
+            GetAclStatsRequest, GetAclStatsResponse, GetSecretRotationStatusRequest,
+            GetSecretRotationStatusResponse, GetWhitelistRequest, GetWhitelistResponse,
+            ListBandwidthLimitRequest, ListBandwidthLimitResponse, ListExitNodeRequest,
+            ListExitNodeResponse, ListForeignNetworkRequest, ListForeignNetworkResponse,
+            ListGlobalForeignNetworkRequest, ListGlobalForeignNetworkResponse, ListPeerRequest,
+            ListPeerResponse, ListRouteRequest, ListRouteResponse, PeerInfo, PeerManageRpc,
+            RemoveBandwidthLimitRequest, RemoveBandwidthLimitResponse, SecretRotationManageRpc,
+            SetBandwidthLimitRequest, SetBandwidthLimitResponse, SetWhitelistRequest,
+            SetWhitelistResponse, ShowNodeInfoRequest, ShowNodeInfoResponse,
+            StartSecretRotationRequest, StartSecretRotationResponse,
         },
         rpc_types::{self, controller::BaseController},
     },
 };
 
-use super::peer_manager::PeerManager;
+use super::{peer_manager::PeerManager, secret_rotation::DEFAULT_GRACE_PERIOD};
 
 #[derive(Clone)]
 pub struct PeerManagerRpcService {
@@ -142,6 +147,16 @@ impl PeerManageRpc for PeerManagerRpcService {
             node_info: Some(self.peer_manager.get_my_info().await),
         })
     }
+
+    async fn list_exit_node(
+        &self,
+        _: BaseController,
+        _request: ListExitNodeRequest,
+    ) -> Result<ListExitNodeResponse, rpc_types::error::Error> {
+        Ok(ListExitNodeResponse {
+            exit_nodes: self.peer_manager.get_exit_node_manager().list_status(),
+        })
+    }
 }
 
 #[async_trait::async_trait]
@@ -204,3 +219,100 @@ impl AclManageRpc for PeerManagerRpcService {
         })
     }
 }
+
+#[async_trait::async_trait]
+impl BandwidthLimitManageRpc for PeerManagerRpcService {
+    type Controller = BaseController;
+
+    async fn set_bandwidth_limit(
+        &self,
+        _: BaseController,
+        request: SetBandwidthLimitRequest,
+    ) -> Result<SetBandwidthLimitResponse, rpc_types::error::Error> {
+        let limit: BandwidthLimitConfig = request
+            .limit
+            .ok_or(anyhow::anyhow!("bandwidth limit is empty"))?
+            .try_into()?;
+        tracing::info!("Setting bandwidth limit: {:?}", limit);
+
+        let global_ctx = self.peer_manager.get_global_ctx();
+        let mut limits = global_ctx.config.get_bandwidth_limits();
+        limits.retain(|l| !l.same_target(&limit));
+        limits.push(limit);
+        global_ctx.config.set_bandwidth_limits(limits.clone());
+        global_ctx.get_bandwidth_limiter().reload_limits(&limits);
+
+        Ok(SetBandwidthLimitResponse {})
+    }
+
+    async fn remove_bandwidth_limit(
+        &self,
+        _: BaseController,
+        request: RemoveBandwidthLimitRequest,
+    ) -> Result<RemoveBandwidthLimitResponse, rpc_types::error::Error> {
+        let limit: BandwidthLimitConfig = request
+            .limit
+            .ok_or(anyhow::anyhow!("bandwidth limit is empty"))?
+            .try_into()?;
+        tracing::info!("Removing bandwidth limit of {}", limit.target_str());
+
+        let global_ctx = self.peer_manager.get_global_ctx();
+        let mut limits = global_ctx.config.get_bandwidth_limits();
+        limits.retain(|l| !l.same_target(&limit));
+        global_ctx.config.set_bandwidth_limits(limits.clone());
+        global_ctx.get_bandwidth_limiter().reload_limits(&limits);
+
+        Ok(RemoveBandwidthLimitResponse {})
+    }
+
+    async fn list_bandwidth_limit(
+        &self,
+        _: BaseController,
+        _request: ListBandwidthLimitRequest,
+    ) -> Result<ListBandwidthLimitResponse, rpc_types::error::Error> {
+        let limits = self
+            .peer_manager
+            .get_global_ctx()
+            .config
+            .get_bandwidth_limits()
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        Ok(ListBandwidthLimitResponse { limits })
+    }
+}
+
+#[async_trait::async_trait]
+impl SecretRotationManageRpc for PeerManagerRpcService {
+    type Controller = BaseController;
+
+    async fn start_secret_rotation(
+        &self,
+        _: BaseController,
+        request: StartSecretRotationRequest,
+    ) -> Result<StartSecretRotationResponse, rpc_types::error::Error> {
+        let grace_period = match request.grace_period_sec {
+            0 => DEFAULT_GRACE_PERIOD,
+            secs => Duration::from_secs(secs as u64),
+        };
+        tracing::info!(
+            "Starting network secret rotation, grace period {:?}",
+            grace_period
+        );
+
+        let rotation_id = self
+            .peer_manager
+            .get_secret_rotation()
+            .start(request.network_secret, grace_period)
+            .await?;
+        Ok(StartSecretRotationResponse { rotation_id })
+    }
+
+    async fn get_secret_rotation_status(
+        &self,
+        _: BaseController,
+        _request: GetSecretRotationStatusRequest,
+    ) -> Result<GetSecretRotationStatusResponse, rpc_types::error::Error> {
+        Ok(self.peer_manager.get_secret_rotation().get_status().await)
+    }
+}
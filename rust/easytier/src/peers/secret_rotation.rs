@@ -0,0 +1,687 @@
+// live rotation of the network secret. the node where the rotation is started pushes the new
+// secret to every member over peer rpc, see RotateNetworkSecretRequest for the two phases.
+//
+// during the grace period both secrets are accepted in the handshake and for decryption, so
+// members that missed the push can still connect and are told about the new secret then.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail};
+use tokio::task::JoinSet;
+
+use crate::{
+    common::{
+        global_ctx::{ArcGlobalCtx, GlobalCtx, GlobalCtxEvent, NetworkIdentity},
+        PeerId,
+    },
+    proto::{
+        cli::{GetSecretRotationStatusResponse, SecretRotationPeerStatus},
+        peer_rpc::{
+            RotateNetworkSecretRequest, RotateNetworkSecretResponse, SecretRotationRpc,
+            SecretRotationRpcClientFactory, SecretRotationRpcServer,
+        },
+        rpc_types::{
+            self,
+            controller::{BaseController, Controller},
+        },
+    },
+};
+
+use super::{
+    encrypt::{create_encryptor, Encryptor, RotatingEncryptor},
+    peer_map::PeerMap,
+    peer_rpc::PeerRpcManager,
+};
+
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(600);
+// members need some time to receive the commit before the previous secret stops working
+pub const MIN_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+const PUSH_RETRIES: usize = 3;
+const PUSH_TIMEOUT_MS: i32 = 3000;
+// rotation ids are wall clock millis, allow this much clock skew between members
+const MAX_ROTATION_ID_SKEW: Duration = Duration::from_secs(600);
+
+struct Rotation {
+    rotation_id: u64,
+    network_secret: String,
+    grace_period: Duration,
+    initiated_locally: bool,
+    committed: bool,
+    // the other secret is not accepted anymore after this
+    valid_until: Instant,
+    // members the new secret was pushed to, only filled on the node that pushes
+    peers: BTreeMap<PeerId, SecretRotationPeerStatus>,
+}
+
+pub struct SecretRotationManager {
+    my_peer_id: PeerId,
+    global_ctx: ArcGlobalCtx,
+    peer_map: Weak<PeerMap>,
+    peer_rpc_mgr: Weak<PeerRpcManager>,
+    encryptor: Arc<RotatingEncryptor>,
+
+    rotation: Mutex<Option<Rotation>>,
+    tasks: Mutex<JoinSet<()>>,
+}
+
+impl SecretRotationManager {
+    pub fn new(
+        my_peer_id: PeerId,
+        global_ctx: ArcGlobalCtx,
+        peer_map: Weak<PeerMap>,
+        peer_rpc_mgr: Weak<PeerRpcManager>,
+        encryptor: Arc<RotatingEncryptor>,
+    ) -> Arc<Self> {
+        Arc::new(SecretRotationManager {
+            my_peer_id,
+            global_ctx,
+            peer_map,
+            peer_rpc_mgr,
+            encryptor,
+            rotation: Mutex::new(None),
+            tasks: Mutex::new(JoinSet::new()),
+        })
+    }
+
+    pub fn register_rpc_server(self: &Arc<Self>, peer_rpc_mgr: &PeerRpcManager) {
+        peer_rpc_mgr.rpc_server().registry().register(
+            SecretRotationRpcServer::new(SecretRotationRpcService(Arc::downgrade(self))),
+            &self.global_ctx.get_network_name(),
+        );
+    }
+
+    // the push is sealed with the network secret, so any member of the network may send it,
+    // including members that are only reachable through a relay
+    async fn is_authorized(&self, from_peer_id: PeerId) -> bool {
+        if from_peer_id == self.my_peer_id {
+            return false;
+        }
+        let Some(peer_map) = self.peer_map.upgrade() else {
+            return false;
+        };
+        peer_map
+            .get_route_peer_info(from_peer_id)
+            .await
+            .is_some_and(|info| !info.feature_flag.is_some_and(|f| f.is_public_server))
+    }
+
+    fn now_millis() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    }
+
+    fn new_encryptor(&self, network_secret: &str) -> Option<Arc<dyn Encryptor>> {
+        let flags = self.global_ctx.get_flags();
+        if !flags.enable_encryption {
+            return None;
+        }
+        Some(create_encryptor(
+            &flags.encryption_algorithm,
+            GlobalCtx::derive_128_key(network_secret),
+            GlobalCtx::derive_256_key(network_secret),
+        ))
+    }
+
+    // accept the new secret, but keep using the current one
+    fn prepare(&self, rotation: &Rotation) {
+        let network_name = self.global_ctx.get_network_name();
+        self.global_ctx.set_alternate_network_identity(
+            NetworkIdentity::new(network_name, rotation.network_secret.clone()),
+            rotation.grace_period,
+        );
+        if let Some(encryptor) = self.new_encryptor(&rotation.network_secret) {
+            self.encryptor
+                .set_alternate(encryptor, rotation.grace_period);
+        }
+    }
+
+    // switch to the new secret, the previous one is accepted for the grace period
+    fn commit(&self, rotation: &mut Rotation) {
+        let previous = self.global_ctx.get_network_identity();
+        self.global_ctx
+            .config
+            .set_network_identity(NetworkIdentity::new(
+                previous.network_name.clone(),
+                rotation.network_secret.clone(),
+            ));
+        self.global_ctx
+            .set_alternate_network_identity(previous, rotation.grace_period);
+        if let Some(encryptor) = self.new_encryptor(&rotation.network_secret) {
+            self.encryptor.rotate(encryptor, rotation.grace_period);
+        }
+
+        rotation.committed = true;
+        rotation.valid_until = Instant::now() + rotation.grace_period;
+        tracing::info!(rotation_id = rotation.rotation_id, "network secret rotated");
+        self.global_ctx
+            .issue_event(GlobalCtxEvent::NetworkSecretRotated(rotation.rotation_id));
+    }
+
+    async fn list_members(&self) -> Vec<(PeerId, String)> {
+        let Some(peer_map) = self.peer_map.upgrade() else {
+            return vec![];
+        };
+        peer_map
+            .list_route_infos()
+            .await
+            .into_iter()
+            .filter(|r| r.peer_id != self.my_peer_id)
+            .filter(|r| !r.feature_flag.map(|f| f.is_public_server).unwrap_or(false))
+            .map(|r| (r.peer_id, r.hostname))
+            .collect()
+    }
+
+    pub async fn start(
+        self: &Arc<Self>,
+        network_secret: String,
+        grace_period: Duration,
+    ) -> anyhow::Result<u64> {
+        if !self.global_ctx.get_flags().enable_encryption {
+            bail!("encryption is disabled, the new secret would be sent in plain text");
+        }
+        if network_secret.is_empty() {
+            bail!("network secret is empty");
+        }
+        if grace_period < MIN_GRACE_PERIOD {
+            bail!(
+                "grace period {:?} is shorter than the minimum {:?}",
+                grace_period,
+                MIN_GRACE_PERIOD
+            );
+        }
+        if self
+            .global_ctx
+            .get_network_identity()
+            .network_secret
+            .as_ref()
+            == Some(&network_secret)
+        {
+            bail!("network secret is the same as the current one");
+        }
+
+        let members = self.list_members().await;
+
+        let mut guard = self.rotation.lock().unwrap();
+        let mut rotation_id = Self::now_millis();
+        if let Some(r) = guard.as_ref() {
+            if !r.committed && Instant::now() < r.valid_until {
+                bail!("rotation {} is still in progress", r.rotation_id);
+            }
+            let next_id = r
+                .rotation_id
+                .checked_add(1)
+                .ok_or_else(|| anyhow!("rotation id {} overflows", r.rotation_id))?;
+            rotation_id = rotation_id.max(next_id);
+        }
+
+        let rotation = Rotation {
+            rotation_id,
+            network_secret,
+            grace_period,
+            initiated_locally: true,
+            committed: false,
+            valid_until: Instant::now() + grace_period,
+            peers: members
+                .into_iter()
+                .map(|(peer_id, hostname)| {
+                    (
+                        peer_id,
+                        SecretRotationPeerStatus {
+                            peer_id,
+                            hostname,
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+        };
+        self.prepare(&rotation);
+        *guard = Some(rotation);
+        drop(guard);
+
+        let this = self.clone();
+        self.spawn(async move { this.run_rotation(rotation_id).await });
+        Ok(rotation_id)
+    }
+
+    fn spawn(&self, fut: impl std::future::Future<Output = ()> + Send + 'static) {
+        let mut tasks = self.tasks.lock().unwrap();
+        while tasks.try_join_next().is_some() {}
+        tasks.spawn(fut);
+    }
+
+    fn peers_of(&self, rotation_id: u64) -> Vec<PeerId> {
+        self.rotation
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|r| r.rotation_id == rotation_id)
+            .map(|r| r.peers.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    async fn run_rotation(self: Arc<Self>, rotation_id: u64) {
+        let peers = self.peers_of(rotation_id);
+        futures::future::join_all(peers.iter().map(|p| self.push(*p, rotation_id, false))).await;
+
+        {
+            let mut guard = self.rotation.lock().unwrap();
+            let Some(rotation) = guard.as_mut().filter(|r| r.rotation_id == rotation_id) else {
+                return;
+            };
+            self.commit(rotation);
+            // members that did not prepare can't read the commit sealed with the new secret
+            for status in rotation.peers.values().filter(|s| !s.prepared) {
+                self.encryptor.set_peer_on_alternate(status.peer_id, true);
+            }
+        }
+
+        futures::future::join_all(peers.iter().map(|p| self.push(*p, rotation_id, true))).await;
+    }
+
+    async fn push(&self, peer_id: PeerId, rotation_id: u64, commit: bool) {
+        let req = {
+            let guard = self.rotation.lock().unwrap();
+            let Some(rotation) = guard.as_ref().filter(|r| r.rotation_id == rotation_id) else {
+                return;
+            };
+            RotateNetworkSecretRequest {
+                rotation_id,
+                network_secret: rotation.network_secret.clone(),
+                grace_period_sec: rotation.grace_period.as_secs() as u32,
+                commit,
+            }
+        };
+
+        let mut ret = Err(anyhow!("not sent"));
+        for _ in 0..PUSH_RETRIES {
+            ret = self.call_peer(peer_id, req.clone()).await;
+            if ret.is_ok() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        if let Err(e) = &ret {
+            tracing::warn!(
+                ?peer_id,
+                rotation_id,
+                commit,
+                ?e,
+                "push secret rotation failed"
+            );
+        }
+
+        if commit && ret.is_ok() {
+            self.encryptor.set_peer_on_alternate(peer_id, false);
+        }
+
+        let mut guard = self.rotation.lock().unwrap();
+        let Some(rotation) = guard.as_mut().filter(|r| r.rotation_id == rotation_id) else {
+            return;
+        };
+        let status = rotation
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| SecretRotationPeerStatus {
+                peer_id,
+                ..Default::default()
+            });
+        match ret {
+            Ok(()) => {
+                status.prepared = true;
+                status.committed |= commit;
+                status.error = None;
+            }
+            Err(e) => status.error = Some(format!("{:#}", e)),
+        }
+    }
+
+    async fn call_peer(
+        &self,
+        peer_id: PeerId,
+        req: RotateNetworkSecretRequest,
+    ) -> anyhow::Result<()> {
+        let peer_rpc_mgr = self
+            .peer_rpc_mgr
+            .upgrade()
+            .ok_or_else(|| anyhow!("peer manager is stopped"))?;
+        let stub = peer_rpc_mgr
+            .rpc_client()
+            .scoped_client::<SecretRotationRpcClientFactory<BaseController>>(
+                self.my_peer_id,
+                peer_id,
+                self.global_ctx.get_network_name(),
+            );
+        let ctrl = BaseController {
+            timeout_ms: PUSH_TIMEOUT_MS,
+            ..Default::default()
+        };
+        stub.rotate_network_secret(ctrl, req).await?;
+        Ok(())
+    }
+
+    async fn handle_request(
+        &self,
+        from_peer_id: PeerId,
+        req: RotateNetworkSecretRequest,
+    ) -> anyhow::Result<()> {
+        if !self.is_authorized(from_peer_id).await {
+            bail!(
+                "peer {} is not allowed to rotate the network secret",
+                from_peer_id
+            );
+        }
+        // without encryption the secret came over the wire in plain text, don't adopt it
+        if !self.global_ctx.get_flags().enable_encryption {
+            bail!("encryption is disabled, refusing a secret sent in plain text");
+        }
+        if req.network_secret.is_empty() {
+            bail!("network secret is empty");
+        }
+        let grace_period = Duration::from_secs(req.grace_period_sec as u64);
+        if grace_period < MIN_GRACE_PERIOD {
+            bail!(
+                "grace period {:?} is shorter than the minimum {:?}",
+                grace_period,
+                MIN_GRACE_PERIOD
+            );
+        }
+        // a far future id would make every later rotation look stale
+        let max_rotation_id =
+            Self::now_millis().saturating_add(MAX_ROTATION_ID_SKEW.as_millis() as u64);
+        if req.rotation_id > max_rotation_id {
+            bail!("rotation id {} is too far in the future", req.rotation_id);
+        }
+
+        let mut guard = self.rotation.lock().unwrap();
+        match guard.as_mut() {
+            Some(r) if r.rotation_id > req.rotation_id => {
+                bail!("a newer rotation {} is known", r.rotation_id);
+            }
+            Some(r) if r.rotation_id == req.rotation_id => {
+                if req.commit && !r.committed {
+                    self.commit(r);
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let mut rotation = Rotation {
+            rotation_id: req.rotation_id,
+            network_secret: req.network_secret,
+            grace_period,
+            initiated_locally: false,
+            committed: false,
+            valid_until: Instant::now() + grace_period,
+            peers: BTreeMap::new(),
+        };
+        self.prepare(&rotation);
+        if req.commit {
+            self.commit(&mut rotation);
+        }
+        *guard = Some(rotation);
+        Ok(())
+    }
+
+    /// called when a member connects with the secret we rotated away from, it missed the rotation
+    /// so traffic to it is sealed with the previous secret until it acks the commit.
+    pub fn on_peer_with_previous_secret(self: &Arc<Self>, peer_id: PeerId) {
+        let rotation_id = {
+            let mut guard = self.rotation.lock().unwrap();
+            let Some(rotation) = guard.as_mut().filter(|r| r.committed) else {
+                return;
+            };
+            rotation
+                .peers
+                .entry(peer_id)
+                .or_insert_with(|| SecretRotationPeerStatus {
+                    peer_id,
+                    ..Default::default()
+                });
+            rotation.rotation_id
+        };
+
+        self.encryptor.set_peer_on_alternate(peer_id, true);
+        let this = self.clone();
+        self.spawn(async move { this.push(peer_id, rotation_id, true).await });
+    }
+
+    pub async fn get_status(&self) -> GetSecretRotationStatusResponse {
+        let hostnames: BTreeMap<PeerId, String> = self.list_members().await.into_iter().collect();
+        let guard = self.rotation.lock().unwrap();
+        let Some(rotation) = guard.as_ref() else {
+            return GetSecretRotationStatusResponse::default();
+        };
+        GetSecretRotationStatusResponse {
+            rotation_id: rotation.rotation_id,
+            initiated_locally: rotation.initiated_locally,
+            grace_period_remaining_sec: rotation
+                .valid_until
+                .saturating_duration_since(Instant::now())
+                .as_secs() as u32,
+            peers: rotation
+                .peers
+                .values()
+                .map(|s| {
+                    let mut s = s.clone();
+                    if s.hostname.is_empty() {
+                        s.hostname = hostnames.get(&s.peer_id).cloned().unwrap_or_default();
+                    }
+                    s
+                })
+                .collect(),
+            committed: rotation.committed,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct SecretRotationRpcService(Weak<SecretRotationManager>);
+
+#[async_trait::async_trait]
+impl SecretRotationRpc for SecretRotationRpcService {
+    type Controller = BaseController;
+
+    async fn rotate_network_secret(
+        &self,
+        ctrl: BaseController,
+        req: RotateNetworkSecretRequest,
+    ) -> rpc_types::error::Result<RotateNetworkSecretResponse> {
+        let mgr = self
+            .0
+            .upgrade()
+            .ok_or_else(|| anyhow!("peer manager is stopped"))?;
+        let from_peer_id = ctrl
+            .get_from_peer_id()
+            .ok_or_else(|| anyhow!("unknown caller"))?;
+        mgr.handle_request(from_peer_id, req).await?;
+        Ok(RotateNetworkSecretResponse {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        peers::tests::{connect_peer_manager, create_mock_peer_manager, wait_route_appear},
+        tunnel::common::tests::wait_for_condition,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn rotate_secret_between_two_peers() {
+        let previous = NetworkIdentity::default();
+        let peer_mgr_a = create_mock_peer_manager().await;
+        let peer_mgr_b = create_mock_peer_manager().await;
+        connect_peer_manager(peer_mgr_a.clone(), peer_mgr_b.clone()).await;
+        wait_route_appear(peer_mgr_a.clone(), peer_mgr_b.clone())
+            .await
+            .unwrap();
+
+        let rotation = peer_mgr_a.get_secret_rotation();
+        let rotation_id = rotation
+            .start("new".to_string(), Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        wait_for_condition(
+            || async {
+                let status = rotation.get_status().await;
+                status.committed && status.peers.iter().all(|p| p.committed)
+            },
+            Duration::from_secs(10),
+        )
+        .await;
+
+        for peer_mgr in [&peer_mgr_a, &peer_mgr_b] {
+            let ctx = peer_mgr.get_global_ctx();
+            assert_eq!(
+                ctx.get_network_identity().network_secret,
+                Some("new".to_string())
+            );
+            assert!(ctx.match_network_identity(&previous).is_some());
+        }
+
+        let status = peer_mgr_b.get_secret_rotation().get_status().await;
+        assert_eq!(status.rotation_id, rotation_id);
+        assert!(!status.initiated_locally);
+        assert!(status.committed);
+
+        // traffic still flows with the new secret
+        wait_route_appear(peer_mgr_a.clone(), peer_mgr_b.clone())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn rotate_secret_to_relayed_peer() {
+        let peer_mgr_a = create_mock_peer_manager().await;
+        let peer_mgr_b = create_mock_peer_manager().await;
+        let peer_mgr_c = create_mock_peer_manager().await;
+        connect_peer_manager(peer_mgr_a.clone(), peer_mgr_b.clone()).await;
+        connect_peer_manager(peer_mgr_b.clone(), peer_mgr_c.clone()).await;
+        wait_route_appear(peer_mgr_a.clone(), peer_mgr_c.clone())
+            .await
+            .unwrap();
+        // a only reaches c through b
+        assert!(!peer_mgr_a.get_peer_map().has_peer(peer_mgr_c.my_peer_id()));
+
+        let rotation = peer_mgr_a.get_secret_rotation();
+        rotation
+            .start("new".to_string(), Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        wait_for_condition(
+            || async {
+                let status = rotation.get_status().await;
+                status.committed
+                    && status.peers.len() == 2
+                    && status.peers.iter().all(|p| p.committed)
+            },
+            Duration::from_secs(10),
+        )
+        .await;
+
+        for peer_mgr in [&peer_mgr_b, &peer_mgr_c] {
+            assert_eq!(
+                peer_mgr
+                    .get_global_ctx()
+                    .get_network_identity()
+                    .network_secret,
+                Some("new".to_string())
+            );
+        }
+
+        wait_route_appear(peer_mgr_a.clone(), peer_mgr_c.clone())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reject_push_with_short_grace_period() {
+        let peer_mgr = create_mock_peer_manager().await;
+        let peer_mgr_b = create_mock_peer_manager().await;
+        connect_peer_manager(peer_mgr.clone(), peer_mgr_b.clone()).await;
+        wait_route_appear(peer_mgr.clone(), peer_mgr_b.clone())
+            .await
+            .unwrap();
+
+        let rotation = peer_mgr.get_secret_rotation();
+        let ret = rotation
+            .handle_request(
+                peer_mgr_b.my_peer_id(),
+                RotateNetworkSecretRequest {
+                    rotation_id: 1,
+                    network_secret: "new".to_string(),
+                    grace_period_sec: 0,
+                    commit: true,
+                },
+            )
+            .await;
+        assert!(ret.is_err());
+        assert_ne!(
+            peer_mgr
+                .get_global_ctx()
+                .get_network_identity()
+                .network_secret,
+            Some("new".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn reject_push_from_unknown_peer_or_future_id() {
+        let peer_mgr = create_mock_peer_manager().await;
+        let peer_mgr_b = create_mock_peer_manager().await;
+        connect_peer_manager(peer_mgr.clone(), peer_mgr_b.clone()).await;
+        wait_route_appear(peer_mgr.clone(), peer_mgr_b.clone())
+            .await
+            .unwrap();
+
+        let rotation = peer_mgr.get_secret_rotation();
+        let req = RotateNetworkSecretRequest {
+            rotation_id: 1,
+            network_secret: "new".to_string(),
+            grace_period_sec: 60,
+            commit: true,
+        };
+
+        // not a member of the network
+        let unknown_peer_id = peer_mgr_b.my_peer_id().wrapping_add(1);
+        assert!(rotation
+            .handle_request(unknown_peer_id, req.clone())
+            .await
+            .is_err());
+
+        // would block every later rotation
+        let future_req = RotateNetworkSecretRequest {
+            rotation_id: u64::MAX,
+            ..req.clone()
+        };
+        assert!(rotation
+            .handle_request(peer_mgr_b.my_peer_id(), future_req)
+            .await
+            .is_err());
+        assert_eq!(rotation.get_status().await.rotation_id, 0);
+
+        rotation
+            .handle_request(peer_mgr_b.my_peer_id(), req)
+            .await
+            .unwrap();
+        assert_eq!(
+            peer_mgr
+                .get_global_ctx()
+                .get_network_identity()
+                .network_secret,
+            Some("new".to_string())
+        );
+    }
+}
@@ -39,7 +39,7 @@ impl Display for Rule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "[name: '{}', prio: {}, action: {:?}, enabled: {}, proto: {:?}, ports: {:?}, src_ports: {:?}, src_ips: {:?}, dst_ips: {:?}, stateful: {}, rate: {}, burst: {}]",
+            "[name: '{}', prio: {}, action: {:?}, enabled: {}, proto: {:?}, ports: {:?}, src_ports: {:?}, src_ips: {:?}, dst_ips: {:?}, stateful: {}, rate: {}, burst: {}, time_windows: {:?}]",
             self.name,
             self.priority,
             Action::try_from(self.action).unwrap_or(Action::Noop),
@@ -51,7 +51,30 @@ impl Display for Rule {
             self.destination_ips,
             self.stateful,
             self.rate_limit,
-            self.burst_limit
+            self.burst_limit,
+            self.time_windows
+                .iter()
+                .map(|w| w.to_string())
+                .collect::<Vec<_>>()
+        )
+    }
+}
+
+impl Display for TimeWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.timezone_offset < 0 { '-' } else { '+' };
+        let offset = self.timezone_offset.unsigned_abs();
+        write!(
+            f,
+            "[days: {:?}, {:02}:{:02}-{:02}:{:02}, UTC{}{:02}:{:02}]",
+            self.days_of_week,
+            self.start_time / 60,
+            self.start_time % 60,
+            self.end_time / 60,
+            self.end_time % 60,
+            sign,
+            offset / 60,
+            offset % 60
         )
     }
 }
@@ -283,6 +283,7 @@ impl TryFrom<CompressionAlgoPb> for CompressorAlgo {
     fn try_from(value: CompressionAlgoPb) -> Result<Self, Self::Error> {
         match value {
             CompressionAlgoPb::Zstd => Ok(CompressorAlgo::ZstdDefault),
+            CompressionAlgoPb::Lz4 => Ok(CompressorAlgo::Lz4),
             CompressionAlgoPb::None => Ok(CompressorAlgo::None),
             _ => Err(anyhow::anyhow!("Invalid CompressionAlgoPb")),
         }
@@ -295,6 +296,7 @@ impl TryFrom<CompressorAlgo> for CompressionAlgoPb {
     fn try_from(value: CompressorAlgo) -> Result<Self, Self::Error> {
         match value {
             CompressorAlgo::ZstdDefault => Ok(CompressionAlgoPb::Zstd),
+            CompressorAlgo::Lz4 => Ok(CompressionAlgoPb::Lz4),
             CompressorAlgo::None => Ok(CompressionAlgoPb::None),
         }
     }
@@ -36,6 +36,7 @@ impl RpcServerHook for DefaultHook {}
 pub struct StandAloneServer<L> {
     registry: Arc<ServiceRegistry>,
     listener: Option<L>,
+    local_url: Option<url::Url>,
     inflight_server: Arc<AtomicU32>,
     tasks: JoinSet<()>,
     hook: Option<Arc<dyn RpcServerHook>>,
@@ -46,6 +47,7 @@ impl<L: TunnelListener + 'static> StandAloneServer<L> {
         StandAloneServer {
             registry: Arc::new(ServiceRegistry::new()),
             listener: Some(listener),
+            local_url: None,
             inflight_server: Arc::new(AtomicU32::new(0)),
             tasks: JoinSet::new(),
 
@@ -106,6 +108,7 @@ impl<L: TunnelListener + 'static> StandAloneServer<L> {
             .listen()
             .await
             .with_context(|| "failed to listen")?;
+        self.local_url = Some(listener.local_url());
 
         let registry = self.registry.clone();
 
@@ -132,6 +135,11 @@ impl<L: TunnelListener + 'static> StandAloneServer<L> {
         Ok(())
     }
 
+    // the listened url, with the real port when the listener was asked for port 0
+    pub fn local_url(&self) -> Option<url::Url> {
+        self.local_url.clone()
+    }
+
     pub fn inflight_server(&self) -> u32 {
         self.inflight_server
             .load(std::sync::atomic::Ordering::Relaxed)
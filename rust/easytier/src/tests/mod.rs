@@ -1,6 +1,9 @@
 #[cfg(target_os = "linux")]
 mod three_node;
 
+#[cfg(target_os = "linux")]
+mod tcp_hole_punch;
+
 mod ipv6_test;
 
 use crate::common::PeerId;
@@ -0,0 +1,192 @@
+// two peers behind nat routers which drop udp and unsolicited tcp syns, they can
+// only reach each other directly through tcp hole punching.
+//
+//              tp_pub 10.200.0.1 (relay peer + tcp stun server)
+//                         | br_tpunch
+//            +------------+------------+
+//   tp_r1 10.200.0.11            tp_r2 10.200.0.12
+//            |                         |
+//   tp_c1 192.168.11.2           tp_c2 192.168.12.2
+
+use std::time::Duration;
+
+use stun_codec::{
+    rfc5389::{attributes::XorMappedAddress, methods::BINDING},
+    Message, MessageClass, MessageEncoder, TransactionId,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+use bytecodec::EncodeExt;
+
+use super::{three_node::drop_insts, *};
+
+use crate::{
+    common::{
+        config::{ConfigLoader, TomlConfigLoader},
+        netns::NetNS,
+        scoped_task::ScopedTask,
+        stun_codec_ext::Attribute,
+    },
+    instance::instance::Instance,
+    tunnel::{common::tests::wait_for_condition, tcp::TcpTunnelConnector},
+};
+
+fn run_cmd(cmd: &str) {
+    let args = cmd.split_whitespace().collect::<Vec<_>>();
+    let ret = std::process::Command::new(args[0])
+        .args(&args[1..])
+        .output()
+        .unwrap();
+    tracing::info!(?cmd, ?ret, "run cmd");
+}
+
+fn ns_exec(net_ns: &str, cmd: &str) {
+    run_cmd(&format!("ip netns exec {} {}", net_ns, cmd));
+}
+
+// put client behind router. the router masquerades the lan, drops all forwarded
+// udp, and drops tcp syns to itself like a home router without port forwarding.
+fn prepare_nat(router: &str, client: &str, lan_prefix: &str) {
+    run_cmd(&format!("ip netns add {}", client));
+    ns_exec(client, "ip link set lo up");
+
+    let router_lan = get_host_veth_name(client);
+    let client_wan = get_guest_veth_name(client);
+    run_cmd(&format!(
+        "ip link add {} type veth peer name {}",
+        router_lan, client_wan
+    ));
+    run_cmd(&format!("ip link set {} netns {}", router_lan, router));
+    run_cmd(&format!("ip link set {} netns {}", client_wan, client));
+
+    ns_exec(
+        router,
+        &format!("ip addr add {}.1/24 dev {}", lan_prefix, router_lan),
+    );
+    ns_exec(router, &format!("ip link set {} up", router_lan));
+    ns_exec(
+        client,
+        &format!("ip addr add {}.2/24 dev {}", lan_prefix, client_wan),
+    );
+    ns_exec(client, &format!("ip link set {} up", client_wan));
+    ns_exec(
+        client,
+        &format!("ip route add default via {}.1", lan_prefix),
+    );
+
+    let router_wan = get_guest_veth_name(router);
+    ns_exec(router, "sysctl -w net.ipv4.ip_forward=1");
+    ns_exec(
+        router,
+        &format!(
+            "iptables -t nat -A POSTROUTING -o {} -j MASQUERADE",
+            router_wan
+        ),
+    );
+    ns_exec(router, "iptables -A FORWARD -p udp -j DROP");
+    ns_exec(
+        router,
+        &format!("iptables -A INPUT -i {} -p tcp --syn -j DROP", router_wan),
+    );
+}
+
+fn prepare_nat_namespaces() {
+    for ns in ["tp_c1", "tp_c2", "tp_r1", "tp_r2", "tp_pub"] {
+        del_netns(ns);
+    }
+
+    create_netns("tp_pub", "10.200.0.1/24", "fd20::1/64");
+    create_netns("tp_r1", "10.200.0.11/24", "fd20::11/64");
+    create_netns("tp_r2", "10.200.0.12/24", "fd20::12/64");
+
+    prepare_bridge("br_tpunch");
+    for ns in ["tp_pub", "tp_r1", "tp_r2"] {
+        add_ns_to_bridge("br_tpunch", ns);
+    }
+
+    prepare_nat("tp_r1", "tp_c1", "192.168.11");
+    prepare_nat("tp_r2", "tp_c2", "192.168.12");
+}
+
+// answers binding requests over tcp with the source addr of the connection
+async fn run_tcp_stun_server(listener: TcpListener) {
+    while let Ok((mut stream, addr)) = listener.accept().await {
+        tokio::spawn(async move {
+            let mut header = [0u8; 20];
+            stream.read_exact(&mut header).await?;
+            let mut attrs = vec![0u8; u16::from_be_bytes([header[2], header[3]]) as usize];
+            stream.read_exact(&mut attrs).await?;
+
+            let tid = TransactionId::new(header[8..20].try_into().unwrap());
+            let mut msg = Message::<Attribute>::new(MessageClass::SuccessResponse, BINDING, tid);
+            msg.add_attribute(Attribute::XorMappedAddress(XorMappedAddress::new(addr)));
+            let resp = MessageEncoder::new().encode_into_bytes(msg).unwrap();
+            stream.write_all(&resp).await
+        });
+    }
+}
+
+fn get_punch_inst_config(inst_name: &str, ns: &str, ipv4: &str) -> TomlConfigLoader {
+    let config = TomlConfigLoader::default();
+    config.set_inst_name(inst_name.to_owned());
+    config.set_netns(Some(ns.to_owned()));
+    config.set_ipv4(Some(ipv4.parse().unwrap()));
+    config.set_listeners(vec!["tcp://0.0.0.0:11010".parse().unwrap()]);
+    config.set_stun_servers(Some(vec!["10.200.0.1:3478".to_owned()]));
+    config
+}
+
+#[tokio::test]
+#[serial_test::serial]
+pub async fn tcp_hole_punch_through_nat() {
+    prepare_nat_namespaces();
+
+    let stun_listener = NetNS::new(Some("tp_pub".into())).run(|| {
+        let listener = std::net::TcpListener::bind("10.200.0.1:3478").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        listener
+    });
+    let _stun_task: ScopedTask<()> = tokio::spawn(run_tcp_stun_server(
+        TcpListener::from_std(stun_listener).unwrap(),
+    ))
+    .into();
+
+    let mut inst_pub = Instance::new(get_punch_inst_config("pub", "tp_pub", "10.144.145.1"));
+    let mut inst_c1 = Instance::new(get_punch_inst_config("c1", "tp_c1", "10.144.145.2"));
+    let mut inst_c2 = Instance::new(get_punch_inst_config("c2", "tp_c2", "10.144.145.3"));
+    inst_pub.run().await.unwrap();
+    inst_c1.run().await.unwrap();
+    inst_c2.run().await.unwrap();
+
+    for inst in [&inst_c1, &inst_c2] {
+        inst.get_conn_manager()
+            .add_connector(TcpTunnelConnector::new(
+                "tcp://10.200.0.1:11010".parse().unwrap(),
+            ));
+    }
+
+    let c2_peer_id = inst_c2.peer_id();
+    let c1_peer_mgr = inst_c1.get_peer_manager();
+    wait_for_condition(
+        || async {
+            c1_peer_mgr
+                .list_peer_conns(c2_peer_id)
+                .await
+                .map(|conns| !conns.is_empty())
+                .unwrap_or(false)
+        },
+        Duration::from_secs(60),
+    )
+    .await;
+
+    let conns = c1_peer_mgr.list_peer_conns(c2_peer_id).await.unwrap();
+    let tunnel = conns[0].tunnel.as_ref().unwrap();
+    assert_eq!(tunnel.tunnel_type, "tcp");
+    tracing::info!(?tunnel, "punched conn");
+
+    drop(c1_peer_mgr);
+    drop_insts(vec![inst_pub, inst_c1, inst_c2]).await;
+}
@@ -31,7 +31,7 @@ use crate::{
 use crate::{
     common::config::VpnPortalConfig,
     tunnel::wireguard::{WgConfig, WgTunnelConnector},
-    vpn_portal::wireguard::get_wg_config_for_portal,
+    vpn_portal::wireguard::{get_wg_config_for_portal, get_wg_config_for_portal_client},
 };
 
 pub fn prepare_linux_namespaces() {
@@ -935,7 +935,23 @@ fn run_wireguard_client(
 #[tokio::test]
 #[serial_test::serial]
 pub async fn wireguard_vpn_portal(#[values(true, false)] test_v6: bool) {
-    let mut insts = init_three_node("tcp").await;
+    let insts = init_three_node_ex(
+        "tcp",
+        |cfg| {
+            if cfg.get_inst_name() == "inst3" {
+                cfg.set_vpn_portal_config(VpnPortalConfig {
+                    wireguard_listen: Some("0.0.0.0:22121".parse().unwrap()),
+                    client_cidr: "10.14.14.0/24".parse().unwrap(),
+                    openvpn: None,
+                    shadowsocks: None,
+                    wireguard_clients: vec![],
+                });
+            }
+            cfg
+        },
+        false,
+    )
+    .await;
 
     if test_v6 {
         ping6_test("net_d", "fd12::3", None).await;
@@ -943,17 +959,6 @@ pub async fn wireguard_vpn_portal(#[values(true, false)] test_v6: bool) {
         ping_test("net_d", "10.1.2.3", None).await;
     }
 
-    let net_ns = NetNS::new(Some("net_d".into()));
-    let _g = net_ns.guard();
-    insts[2]
-        .get_global_ctx()
-        .config
-        .set_vpn_portal_config(VpnPortalConfig {
-            wireguard_listen: "0.0.0.0:22121".parse().unwrap(),
-            client_cidr: "10.14.14.0/24".parse().unwrap(),
-        });
-    insts[2].run_vpn_portal().await.unwrap();
-
     let dst_socket_addr = if test_v6 {
         "[fd12::3]:22121".parse().unwrap()
     } else {
@@ -994,6 +999,100 @@ pub async fn wireguard_vpn_portal(#[values(true, false)] test_v6: bool) {
     drop_insts(insts).await;
 }
 
+#[cfg(feature = "wireguard")]
+#[tokio::test]
+#[serial_test::serial]
+pub async fn wireguard_vpn_portal_named_client() {
+    let insts = init_three_node_ex(
+        "tcp",
+        |cfg| {
+            if cfg.get_inst_name() == "inst3" {
+                cfg.set_vpn_portal_config(VpnPortalConfig {
+                    wireguard_listen: Some("0.0.0.0:22121".parse().unwrap()),
+                    client_cidr: "10.14.14.0/24".parse().unwrap(),
+                    openvpn: None,
+                    shadowsocks: None,
+                    wireguard_clients: vec![
+                        "laptop:10.14.14.5".parse().unwrap(),
+                        "phone:10.14.14.6".parse().unwrap(),
+                    ],
+                });
+            }
+            cfg
+        },
+        false,
+    )
+    .await;
+    let vpn_portal = insts[2].get_vpn_portal_inst().unwrap();
+
+    let net_ns = NetNS::new(Some("net_d".into()));
+    let _g = net_ns.guard();
+    let nid = insts[2].get_global_ctx().get_network_identity();
+    let wg_cfg = get_wg_config_for_portal_client(&nid, "laptop");
+    run_wireguard_client(
+        "10.1.2.3:22121".parse().unwrap(),
+        Key::try_from(wg_cfg.my_public_key()).unwrap(),
+        Key::try_from(wg_cfg.peer_secret_key()).unwrap(),
+        vec!["10.14.14.0/24".to_string(), "10.144.144.0/24".to_string()],
+        "10.14.14.5".to_string(),
+    )
+    .unwrap();
+
+    wait_for_condition(
+        || async { ping_test("net_d", "10.144.144.1", None).await },
+        Duration::from_secs(5),
+    )
+    .await;
+
+    let infos = vpn_portal.lock().await.list_client_infos().await;
+    assert_eq!(infos.len(), 2);
+    let laptop = &infos[0];
+    assert_eq!(laptop.name, "laptop");
+    assert_eq!(laptop.address, "10.14.14.5");
+    assert!(!laptop.endpoint.is_empty());
+    assert!(laptop.rx_bytes > 0 && laptop.tx_bytes > 0);
+    assert!(laptop.last_handshake > 0);
+    let phone = &infos[1];
+    assert!(phone.endpoint.is_empty());
+    assert_eq!(phone.last_handshake, 0);
+
+    vpn_portal
+        .lock()
+        .await
+        .revoke_client("laptop")
+        .await
+        .unwrap();
+    wait_for_condition(
+        || async { !ping_test("net_d", "10.144.144.1", None).await },
+        Duration::from_secs(5),
+    )
+    .await;
+    let infos = vpn_portal.lock().await.list_client_infos().await;
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].name, "phone");
+    assert!(
+        insts[2]
+            .get_global_ctx()
+            .config
+            .get_vpn_portal_config()
+            .unwrap()
+            .wireguard_clients[0]
+            .revoked
+    );
+    assert!(vpn_portal
+        .lock()
+        .await
+        .revoke_client("laptop")
+        .await
+        .is_err());
+
+    // the revoked key fails its next handshake
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    assert!(!ping_test("net_d", "10.144.144.3", None).await);
+
+    drop_insts(insts).await;
+}
+
 #[cfg(feature = "wireguard")]
 #[rstest::rstest]
 #[tokio::test]
@@ -1055,6 +1154,337 @@ pub async fn socks5_vpn_portal(#[values("10.144.144.1", "10.144.144.3")] dst_add
     drop_insts(_insts).await;
 }
 
+// a minimal chacha20-ietf-poly1305 shadowsocks client written from the spec, so the portal is not
+// only checked against its own crypto module
+#[cfg(feature = "shadowsocks")]
+struct SsTestCipher {
+    key: ring::aead::LessSafeKey,
+    counter: u64,
+}
+
+#[cfg(feature = "shadowsocks")]
+impl SsTestCipher {
+    // EVP_BytesToKey(md5, "easytier-test"), computed with python hashlib
+    const MASTER_KEY: [u8; 32] = [
+        0xc6, 0x1b, 0xe9, 0x96, 0x99, 0x32, 0x47, 0x21, 0x98, 0x82, 0xce, 0xd3, 0x0b, 0x7d, 0x1e,
+        0x2b, 0xa9, 0xe2, 0x63, 0x33, 0xf2, 0x0d, 0x79, 0x4d, 0xd7, 0x9e, 0x3f, 0x0b, 0x5b, 0xd0,
+        0x0d, 0x11,
+    ];
+    const SALT_LEN: usize = 32;
+    const TAG_LEN: usize = 16;
+
+    fn new(salt: &[u8]) -> Self {
+        use ring::{aead, hkdf};
+        let okm = hkdf::Salt::new(hkdf::HKDF_SHA1_FOR_LEGACY_USE_ONLY, salt)
+            .extract(&Self::MASTER_KEY)
+            .expand(&[b"ss-subkey"], &aead::CHACHA20_POLY1305)
+            .unwrap();
+        Self {
+            key: aead::LessSafeKey::new(aead::UnboundKey::from(okm)),
+            counter: 0,
+        }
+    }
+
+    fn nonce(&mut self) -> ring::aead::Nonce {
+        let mut nonce = [0u8; 12];
+        nonce[..8].copy_from_slice(&self.counter.to_le_bytes());
+        self.counter += 1;
+        ring::aead::Nonce::assume_unique_for_key(nonce)
+    }
+
+    fn seal(&mut self, data: &[u8]) -> Vec<u8> {
+        let nonce = self.nonce();
+        let mut buf = data.to_vec();
+        self.key
+            .seal_in_place_append_tag(nonce, ring::aead::Aad::empty(), &mut buf)
+            .unwrap();
+        buf
+    }
+
+    fn open(&mut self, data: &[u8]) -> Vec<u8> {
+        let nonce = self.nonce();
+        let mut buf = data.to_vec();
+        let len = self
+            .key
+            .open_in_place(nonce, ring::aead::Aad::empty(), &mut buf)
+            .unwrap()
+            .len();
+        buf.truncate(len);
+        buf
+    }
+
+    fn random_salt() -> Vec<u8> {
+        let mut salt = vec![0u8; Self::SALT_LEN];
+        rand::thread_rng().fill(&mut salt[..]);
+        salt
+    }
+
+    // salt, then the sealed length and payload of a single chunk
+    fn tcp_request(payload: &[u8]) -> Vec<u8> {
+        let salt = Self::random_salt();
+        let mut cipher = Self::new(&salt);
+        let mut out = salt;
+        out.extend(cipher.seal(&(payload.len() as u16).to_be_bytes()));
+        out.extend(cipher.seal(payload));
+        out
+    }
+
+    fn udp_packet(payload: &[u8]) -> Vec<u8> {
+        let salt = Self::random_salt();
+        let body = Self::new(&salt).seal(payload);
+        [salt, body].concat()
+    }
+
+    fn open_udp_packet(packet: &[u8]) -> Vec<u8> {
+        let (salt, body) = packet.split_at(Self::SALT_LEN);
+        Self::new(salt).open(body)
+    }
+
+    // socks5 style address, atyp 1 for ipv4
+    fn target_addr(addr: SocketAddr) -> Vec<u8> {
+        let SocketAddr::V4(addr) = addr else {
+            panic!("only ipv4 targets are used in the test");
+        };
+        let mut out = vec![1u8];
+        out.extend_from_slice(&addr.ip().octets());
+        out.extend_from_slice(&addr.port().to_be_bytes());
+        out
+    }
+}
+
+#[cfg(feature = "shadowsocks")]
+#[rstest::rstest]
+#[tokio::test]
+#[serial_test::serial]
+pub async fn shadowsocks_vpn_portal(#[values("10.144.144.1", "10.144.144.3")] dst_addr: &str) {
+    use crate::common::config::{ShadowsocksMethod, ShadowsocksPortalConfig, VpnPortalConfig};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    let method = ShadowsocksMethod::Chacha20IetfPoly1305;
+    let insts = init_three_node_ex(
+        "tcp",
+        |cfg| {
+            if cfg.get_inst_name() == "inst3" {
+                cfg.set_vpn_portal_config(VpnPortalConfig {
+                    wireguard_listen: None,
+                    client_cidr: "10.14.14.0/24".parse().unwrap(),
+                    openvpn: None,
+                    shadowsocks: Some(ShadowsocksPortalConfig {
+                        listen: "0.0.0.0:28388".parse().unwrap(),
+                        method,
+                        password: Some("easytier-test".to_string()),
+                    }),
+                    wireguard_clients: vec![],
+                });
+            }
+            cfg
+        },
+        false,
+    )
+    .await;
+
+    // replies from the portal stack are routed back to inst3 through the ring tunnel
+    wait_proxy_route_appear(
+        &insts[0].get_peer_manager(),
+        "10.144.144.3/24",
+        insts[2].peer_id(),
+        "10.14.14.0/24",
+    )
+    .await;
+
+    // echo servers on the target node
+    let dst_ns = if dst_addr == "10.144.144.1" {
+        "net_a"
+    } else {
+        "net_c"
+    };
+    let net_ns = NetNS::new(Some(dst_ns.into()));
+    let (listener, udp_echo) = {
+        let _g = net_ns.guard();
+        (
+            TcpListener::bind("0.0.0.0:22222").await.unwrap(),
+            UdpSocket::bind("0.0.0.0:22223").await.unwrap(),
+        )
+    };
+    let mut echo_tasks = JoinSet::new();
+    echo_tasks.spawn(async move {
+        let (mut st, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 1024];
+        st.read_exact(&mut buf).await.unwrap();
+        st.write_all(&buf).await.unwrap();
+        // keep the connection open until the client has read the echo
+        let _ = st.read(&mut buf).await;
+    });
+    echo_tasks.spawn(async move {
+        let mut buf = vec![0u8; 1024];
+        let (len, from) = udp_echo.recv_from(&mut buf).await.unwrap();
+        udp_echo.send_to(&buf[..len], from).await.unwrap();
+    });
+
+    let net_ns = NetNS::new(Some("net_d".into()));
+    let _g = net_ns.guard();
+
+    // tcp, the target address is sent with the first chunk
+    let mut data = vec![0u8; 1024];
+    rand::thread_rng().fill(&mut data[..]);
+    let mut stream = TcpStream::connect("10.1.2.3:28388").await.unwrap();
+    let mut first = SsTestCipher::target_addr(format!("{}:22222", dst_addr).parse().unwrap());
+    first.extend_from_slice(&data);
+    stream
+        .write_all(&SsTestCipher::tcp_request(&first))
+        .await
+        .unwrap();
+
+    let mut salt = vec![0u8; SsTestCipher::SALT_LEN];
+    stream.read_exact(&mut salt).await.unwrap();
+    let mut cipher = SsTestCipher::new(&salt);
+    let mut echoed = vec![];
+    while echoed.len() < data.len() {
+        let mut len = [0u8; 2 + SsTestCipher::TAG_LEN];
+        stream.read_exact(&mut len).await.unwrap();
+        let len = cipher.open(&len);
+        let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+        let mut chunk = vec![0u8; len + SsTestCipher::TAG_LEN];
+        stream.read_exact(&mut chunk).await.unwrap();
+        echoed.extend(cipher.open(&chunk));
+    }
+    assert_eq!(echoed, data);
+
+    // no wireguard listen address is set, so only the shadowsocks portal exists
+    let portals = insts[2].get_vpn_portal_insts();
+    assert_eq!(portals.len(), 1);
+    let clients = portals[0].lock().await.list_clients().await;
+    assert_eq!(clients, vec!["10.1.2.4 (tcp: 1, udp: 0)".to_string()]);
+    drop(stream);
+
+    // udp, replies carry the address they came from
+    let target: SocketAddr = format!("{}:22223", dst_addr).parse().unwrap();
+    let socket = UdpSocket::bind("0.0.0.0:0").await.unwrap();
+    let mut payload = SsTestCipher::target_addr(target);
+    payload.extend_from_slice(b"hello shadowsocks");
+    socket
+        .send_to(&SsTestCipher::udp_packet(&payload), "10.1.2.3:28388")
+        .await
+        .unwrap();
+
+    let mut buf = vec![0u8; 2048];
+    let (len, _) = tokio::time::timeout(Duration::from_secs(5), socket.recv_from(&mut buf))
+        .await
+        .unwrap()
+        .unwrap();
+    let reply = SsTestCipher::open_udp_packet(&buf[..len]);
+    assert_eq!(reply, payload);
+
+    // destinations outside of the virtual network are refused
+    let mut stream = TcpStream::connect("10.1.2.3:28388").await.unwrap();
+    stream
+        .write_all(&SsTestCipher::tcp_request(&SsTestCipher::target_addr(
+            "1.1.1.1:80".parse().unwrap(),
+        )))
+        .await
+        .unwrap();
+    let mut buf = [0u8; 1];
+    let ret = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+        .await
+        .unwrap();
+    assert!(matches!(ret, Ok(0) | Err(_)));
+
+    while let Some(ret) = echo_tasks.join_next().await {
+        ret.unwrap();
+    }
+    drop_insts(insts).await;
+}
+
+#[cfg(feature = "openvpn")]
+#[rstest::rstest]
+#[tokio::test]
+#[serial_test::serial]
+pub async fn openvpn_vpn_portal(#[values("udp", "tcp")] proto: &str) {
+    use crate::common::config::{OpenVpnAuthMode, OpenVpnPortalConfig, VpnPortalConfig};
+
+    // runs the stock openvpn client against the portal, skipped where it is not installed
+    if std::process::Command::new("openvpn")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        println!("openvpn client not found, skipping openvpn_vpn_portal");
+        return;
+    }
+
+    let insts = init_three_node_ex(
+        "tcp",
+        |cfg| {
+            if cfg.get_inst_name() == "inst3" {
+                cfg.set_vpn_portal_config(VpnPortalConfig {
+                    wireguard_listen: Some("0.0.0.0:22121".parse().unwrap()),
+                    client_cidr: "10.14.14.0/24".parse().unwrap(),
+                    openvpn: Some(OpenVpnPortalConfig {
+                        listen: format!("{}://0.0.0.0:1194", proto).parse().unwrap(),
+                        auth: OpenVpnAuthMode::Tls,
+                    }),
+                    shadowsocks: None,
+                    wireguard_clients: vec![],
+                });
+            }
+            cfg
+        },
+        false,
+    )
+    .await;
+
+    wait_proxy_route_appear(
+        &insts[0].get_peer_manager(),
+        "10.144.144.3/24",
+        insts[2].peer_id(),
+        "10.14.14.0/24",
+    )
+    .await;
+
+    let portal = insts[2].get_vpn_portal_insts()[1].clone();
+    let client_cfg = portal
+        .lock()
+        .await
+        .dump_client_config(insts[2].get_peer_manager())
+        .await
+        .replace("remote 0.0.0.0 ", "remote 10.1.2.3 ");
+    let cfg_path = std::env::temp_dir().join(format!("easytier-openvpn-{}.ovpn", proto));
+    std::fs::write(&cfg_path, client_cfg).unwrap();
+
+    let mut client = {
+        let _g = NetNS::new(Some(ROOT_NETNS_NAME.to_owned())).guard();
+        tokio::process::Command::new("ip")
+            .args(["netns", "exec", "net_d", "openvpn", "--config"])
+            .arg(&cfg_path)
+            .args(["--dev", "tun-ovpn"])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap()
+    };
+
+    // the client only gets its address after the tls handshake, key exchange and push reply
+    wait_for_condition(
+        || async { ping_test("net_d", "10.144.144.1", None).await },
+        Duration::from_secs(30),
+    )
+    .await;
+    wait_for_condition(
+        || async { ping_test("net_d", "10.144.144.3", None).await },
+        Duration::from_secs(5),
+    )
+    .await;
+    assert_eq!(portal.lock().await.list_clients().await.len(), 1);
+
+    client.kill().await.unwrap();
+    let _ = std::fs::remove_file(&cfg_path);
+    drop_insts(insts).await;
+}
+
 #[tokio::test]
 #[serial_test::serial]
 pub async fn foreign_network_functional_cluster() {
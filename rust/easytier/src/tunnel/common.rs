@@ -10,6 +10,7 @@ use futures::{stream::FuturesUnordered, Future, Sink, Stream};
 use network_interface::NetworkInterfaceConfig as _;
 use pin_project_lite::pin_project;
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpSocket;
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use tokio_stream::StreamExt;
@@ -439,6 +440,20 @@ pub(crate) fn setup_sokcet2(
     )
 }
 
+// the port of the returned socket can be shared with other sockets bound the same
+// way, so a port observed by a stun server can be reused for tcp hole punching.
+pub(crate) fn bind_reuse_port_tcp_socket(bind_addr: &SocketAddr) -> Result<TcpSocket, TunnelError> {
+    let socket2_socket = socket2::Socket::new(
+        socket2::Domain::for_address(*bind_addr),
+        socket2::Type::STREAM,
+        Some(socket2::Protocol::TCP),
+    )?;
+    #[cfg(all(unix, not(target_os = "solaris"), not(target_os = "illumos")))]
+    socket2_socket.set_reuse_port(true)?;
+    setup_sokcet2_ext(&socket2_socket, bind_addr, None)?;
+    Ok(TcpSocket::from_std_stream(socket2_socket.into()))
+}
+
 pub fn reserve_buf(buf: &mut BytesMut, min_size: usize, max_size: usize) {
     if buf.capacity() < min_size {
         buf.reserve(max_size);
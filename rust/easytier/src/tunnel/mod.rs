@@ -38,6 +38,11 @@ pub mod websocket;
 #[cfg(any(feature = "quic", feature = "websocket"))]
 pub mod insecure_tls;
 
+#[cfg(any(feature = "quic", feature = "websocket"))]
+pub mod tls;
+
+pub mod proxy;
+
 #[derive(thiserror::Error, Debug)]
 pub enum TunnelError {
     #[error("io error")]
@@ -148,6 +153,23 @@ pub fn build_url_from_socket_addr(addr: &String, scheme: &str) -> url::Url {
     }
 }
 
+// returns the url without the given query params, which carry local settings that
+// must not leave this node.
+pub fn remove_query_params(url: &url::Url, keys: &[&str]) -> url::Url {
+    let mut ret = url.clone();
+    let pairs = url
+        .query_pairs()
+        .filter(|(k, _)| !keys.contains(&k.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect::<Vec<_>>();
+    if pairs.is_empty() {
+        ret.set_query(None);
+    } else {
+        ret.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    ret
+}
+
 impl std::fmt::Debug for dyn Tunnel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Tunnel")
@@ -82,6 +82,7 @@ bitflags::bitflags! {
         const NO_PROXY = 0b0000_1000;
         const COMPRESSED = 0b0001_0000;
         const KCP_SRC_MODIFIED = 0b0010_0000;
+        const MULTIPATH_DUPLICATED = 0b0100_0000;
 
         const _ = !0;
     }
@@ -95,7 +96,8 @@ pub struct PeerManagerHeader {
     pub packet_type: u8,
     pub flags: u8,
     pub forward_counter: u8,
-    reserved: u8,
+    // sequence of a packet sent over several connections, only valid with MULTIPATH_DUPLICATED
+    multipath_seq: u8,
     pub len: U32<DefaultEndian>,
 }
 pub const PEER_MANAGER_HEADER_SIZE: usize = std::mem::size_of::<PeerManagerHeader>();
@@ -117,6 +119,21 @@ impl PeerManagerHeader {
         self.flags = flags.bits();
     }
 
+    /// The sequence the receiver drops copies of the packet by, if it was duplicated.
+    pub fn multipath_seq(&self) -> Option<u8> {
+        PeerManagerHeaderFlags::from_bits(self.flags)
+            .unwrap()
+            .contains(PeerManagerHeaderFlags::MULTIPATH_DUPLICATED)
+            .then_some(self.multipath_seq)
+    }
+
+    pub fn set_multipath_seq(&mut self, seq: u8) {
+        let mut flags = PeerManagerHeaderFlags::from_bits(self.flags).unwrap();
+        flags.insert(PeerManagerHeaderFlags::MULTIPATH_DUPLICATED);
+        self.flags = flags.bits();
+        self.multipath_seq = seq;
+    }
+
     pub fn is_latency_first(&self) -> bool {
         PeerManagerHeaderFlags::from_bits(self.flags)
             .unwrap()
@@ -257,6 +274,7 @@ pub const AES_GCM_ENCRYPTION_RESERVED: usize = std::mem::size_of::<AesGcmTail>()
 pub enum CompressorAlgo {
     None = 0,
     ZstdDefault = 1,
+    Lz4 = 2,
 }
 
 #[repr(C, packed)]
@@ -270,6 +288,7 @@ impl CompressorTail {
     pub fn get_algo(&self) -> Option<CompressorAlgo> {
         match self.algo {
             1 => Some(CompressorAlgo::ZstdDefault),
+            2 => Some(CompressorAlgo::Lz4),
             _ => None,
         }
     }
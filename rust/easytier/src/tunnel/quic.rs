@@ -11,6 +11,7 @@ use crate::tunnel::{
     TunnelInfo,
 };
 use anyhow::Context;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
 
 use quinn::{
     congestion::BbrConfig, crypto::rustls::QuicClientConfig, udp::RecvMeta, AsyncUdpSocket,
@@ -20,11 +21,16 @@ use quinn::{
 use super::{
     check_scheme_and_get_socket_addr,
     insecure_tls::{get_insecure_tls_cert, get_insecure_tls_client_config},
+    tls::{load_server_cert, strip_tls_params, TlsVerifyMode},
     IpVersion, Tunnel, TunnelConnector, TunnelError, TunnelListener,
 };
 
 pub fn configure_client() -> ClientConfig {
-    let client_crypto = QuicClientConfig::try_from(get_insecure_tls_client_config()).unwrap();
+    configure_client_with_tls(get_insecure_tls_client_config())
+}
+
+pub fn configure_client_with_tls(tls_config: rustls::ClientConfig) -> ClientConfig {
+    let client_crypto = QuicClientConfig::try_from(tls_config).unwrap();
     let mut client_config = ClientConfig::new(Arc::new(client_crypto));
 
     // // Create a new TransportConfig and set BBR
@@ -88,7 +94,16 @@ impl AsyncUdpSocket for NoGroAsyncUdpSocket {
 /// - server certificate serialized into DER format
 #[allow(unused)]
 pub fn make_server_endpoint(bind_addr: SocketAddr) -> Result<(Endpoint, Vec<u8>), Box<dyn Error>> {
-    let (server_config, server_cert) = configure_server()?;
+    let (certs, key) = get_insecure_tls_cert();
+    make_server_endpoint_with_cert(bind_addr, certs, key)
+}
+
+pub fn make_server_endpoint_with_cert(
+    bind_addr: SocketAddr,
+    certs: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+) -> Result<(Endpoint, Vec<u8>), Box<dyn Error>> {
+    let (server_config, server_cert) = configure_server_with_cert(certs, key)?;
 
     let socket2_socket = socket2::Socket::new(
         socket2::Domain::for_address(bind_addr),
@@ -117,7 +132,13 @@ pub fn make_server_endpoint(bind_addr: SocketAddr) -> Result<(Endpoint, Vec<u8>)
 /// Returns default server configuration along with its certificate.
 pub fn configure_server() -> Result<(ServerConfig, Vec<u8>), Box<dyn Error>> {
     let (certs, key) = get_insecure_tls_cert();
+    configure_server_with_cert(certs, key)
+}
 
+pub fn configure_server_with_cert(
+    certs: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+) -> Result<(ServerConfig, Vec<u8>), Box<dyn Error>> {
     let mut server_config = ServerConfig::with_single_cert(certs.clone(), key)?;
     let transport_config = Arc::get_mut(&mut server_config.transport).unwrap();
     transport_config.max_concurrent_uni_streams(10_u8.into());
@@ -193,7 +214,8 @@ impl TunnelListener for QUICTunnelListener {
         let addr =
             check_scheme_and_get_socket_addr::<SocketAddr>(&self.addr, "quic", IpVersion::Both)
                 .await?;
-        let (endpoint, server_cert) = make_server_endpoint(addr)
+        let (certs, key) = load_server_cert(&self.addr)?;
+        let (endpoint, server_cert) = make_server_endpoint_with_cert(addr, certs, key)
             .map_err(|e| anyhow::anyhow!("make server endpoint error: {:?}", e))?;
         self.endpoint = Some(endpoint);
         self.server_cert = Some(server_cert);
@@ -218,7 +240,7 @@ impl TunnelListener for QUICTunnelListener {
     }
 
     fn local_url(&self) -> url::Url {
-        self.addr.clone()
+        strip_tls_params(&self.addr)
     }
 }
 
@@ -250,13 +272,14 @@ impl TunnelConnector for QUICTunnelConnector {
             "[::]:0"
         };
 
+        let tls_verify = TlsVerifyMode::from_url(&self.addr)?;
         let mut endpoint = Endpoint::client(local_addr.parse().unwrap())?;
-        endpoint.set_default_client_config(configure_client());
+        endpoint.set_default_client_config(configure_client_with_tls(tls_verify.client_config()?));
 
         // connect to server
         let connection = endpoint
-            .connect(addr, "localhost")
-            .unwrap()
+            .connect(addr, &tls_verify.server_name(&self.addr))
+            .with_context(|| "create quic connection failed")?
             .await
             .with_context(|| "connect failed")?;
         tracing::info!("[client] connected: addr={}", connection.remote_address());
@@ -299,6 +322,7 @@ impl TunnelConnector for QUICTunnelConnector {
 mod tests {
     use crate::tunnel::{
         common::tests::{_tunnel_bench, _tunnel_pingpong},
+        tls::tests::gen_test_cert_files,
         IpVersion,
     };
 
@@ -340,6 +364,52 @@ mod tests {
         _tunnel_pingpong(listener, connector).await;
     }
 
+    #[tokio::test]
+    async fn quic_cert_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let (cert, key, fingerprint) = gen_test_cert_files(dir.path());
+
+        let listen_url = |port: u16| {
+            format!("quic://0.0.0.0:{}?cert={}&key={}", port, cert, key)
+                .parse()
+                .unwrap()
+        };
+
+        let listener = QUICTunnelListener::new(listen_url(21013));
+        let connector = QUICTunnelConnector::new(
+            format!("quic://127.0.0.1:21013?fingerprint={}", fingerprint)
+                .parse()
+                .unwrap(),
+        );
+        _tunnel_pingpong(listener, connector).await;
+
+        let listener = QUICTunnelListener::new(listen_url(21014));
+        let mut connector = QUICTunnelConnector::new(
+            format!("quic://localhost:21014?ca={}", cert)
+                .parse()
+                .unwrap(),
+        );
+        connector.set_ip_version(IpVersion::V4);
+        _tunnel_pingpong(listener, connector).await;
+    }
+
+    #[tokio::test]
+    async fn quic_fingerprint_mismatch() {
+        let mut listener = QUICTunnelListener::new("quic://0.0.0.0:21015".parse().unwrap());
+        listener.listen().await.unwrap();
+        assert_eq!(listener.local_url().query(), None);
+        let _accept_task = tokio::spawn(async move {
+            let _ = listener.accept().await;
+        });
+
+        let mut connector = QUICTunnelConnector::new(
+            format!("quic://127.0.0.1:21015?fingerprint={}", "00".repeat(32))
+                .parse()
+                .unwrap(),
+        );
+        assert!(connector.connect().await.is_err());
+    }
+
     #[tokio::test]
     async fn test_alloc_port() {
         // v4
@@ -10,6 +10,7 @@ use crate::tunnel::common::setup_sokcet2;
 use super::{
     check_scheme_and_get_socket_addr,
     common::{wait_for_connect_futures, FramedReader, FramedWriter, TunnelWrapper},
+    proxy::{redact_proxy_credentials, strip_proxy_param, UpstreamProxy},
     IpVersion, Tunnel, TunnelError, TunnelListener,
 };
 
@@ -107,7 +108,7 @@ impl TunnelListener for TcpTunnelListener {
     }
 }
 
-fn get_tunnel_with_tcp_stream(
+pub(crate) fn get_tunnel_with_tcp_stream(
     stream: TcpStream,
     remote_url: url::Url,
 ) -> Result<Box<dyn Tunnel>, super::TunnelError> {
@@ -190,6 +191,11 @@ impl TcpTunnelConnector {
 #[async_trait]
 impl super::TunnelConnector for TcpTunnelConnector {
     async fn connect(&mut self) -> Result<Box<dyn Tunnel>, super::TunnelError> {
+        if let Some(proxy) = UpstreamProxy::from_tunnel_url(&self.addr)? {
+            let stream = proxy.connect(&self.addr).await?;
+            return get_tunnel_with_tcp_stream(stream, strip_proxy_param(&self.addr));
+        }
+
         let addr =
             check_scheme_and_get_socket_addr::<SocketAddr>(&self.addr, "tcp", self.ip_version)
                 .await?;
@@ -201,7 +207,7 @@ impl super::TunnelConnector for TcpTunnelConnector {
     }
 
     fn remote_url(&self) -> url::Url {
-        self.addr.clone()
+        redact_proxy_credentials(&self.addr)
     }
 
     fn set_bind_addrs(&mut self, addrs: Vec<SocketAddr>) {
@@ -217,6 +223,10 @@ impl super::TunnelConnector for TcpTunnelConnector {
 mod tests {
     use crate::tunnel::{
         common::tests::{_tunnel_bench, _tunnel_pingpong},
+        proxy::{
+            tests::{run_fake_http_proxy, run_fake_socks5_proxy},
+            PROXY_PARAM,
+        },
         TunnelConnector,
     };
 
@@ -244,6 +254,23 @@ mod tests {
         _tunnel_pingpong(listener, connector).await
     }
 
+    #[tokio::test]
+    async fn tcp_pingpong_through_proxy() {
+        let proxy = run_fake_http_proxy(false).await;
+        let mut url: url::Url = "tcp://127.0.0.1:31021".parse().unwrap();
+        url.query_pairs_mut()
+            .append_pair(PROXY_PARAM, proxy.as_str());
+        let listener = TcpTunnelListener::new("tcp://0.0.0.0:31021".parse().unwrap());
+        _tunnel_pingpong(listener, TcpTunnelConnector::new(url)).await;
+
+        let proxy = run_fake_socks5_proxy().await;
+        let mut url: url::Url = "tcp://localhost:31022".parse().unwrap();
+        url.query_pairs_mut()
+            .append_pair(PROXY_PARAM, proxy.as_str());
+        let listener = TcpTunnelListener::new("tcp://0.0.0.0:31022".parse().unwrap());
+        _tunnel_pingpong(listener, TcpTunnelConnector::new(url)).await;
+    }
+
     #[tokio::test]
     #[should_panic]
     async fn tcp_bench_with_bind_fail() {
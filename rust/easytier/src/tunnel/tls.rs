@@ -0,0 +1,376 @@
+// tls settings of wss:// and quic:// tunnels, carried in the query of the url.
+//
+// connector, only one of them may be given:
+//   tls_verify=insecure|webpki|system  insecure (the default) accepts any cert
+//   fingerprint=<hex sha256 of the server cert>  ':' separators are allowed
+//   ca=<path of a pem file with the trusted ca certs>
+//
+// listener:
+//   cert=<path of a pem cert chain>&key=<path of a pem private key>
+//   a self-signed cert is generated when they are not given.
+
+use std::{io::BufReader, path::PathBuf, sync::Arc};
+
+use anyhow::Context;
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime},
+    DigitallySignedStruct, RootCertStore,
+};
+use sha2::{Digest, Sha256};
+
+use super::{
+    insecure_tls::{get_insecure_tls_cert, get_insecure_tls_client_config, init_crypto_provider},
+    remove_query_params, TunnelError,
+};
+
+const TLS_PARAMS: [&str; 5] = ["tls_verify", "fingerprint", "ca", "cert", "key"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TlsVerifyMode {
+    Insecure,
+    WebPki,
+    System,
+    Fingerprint([u8; 32]),
+    CaFile(PathBuf),
+}
+
+fn query_param(url: &url::Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.into_owned())
+}
+
+pub fn cert_fingerprint(cert: &CertificateDer<'_>) -> String {
+    Sha256::digest(cert.as_ref())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn parse_fingerprint(s: &str) -> Option<[u8; 32]> {
+    let hex = s.replace(':', "");
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut ret = [0u8; 32];
+    for (i, b) in ret.iter_mut().enumerate() {
+        *b = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(ret)
+}
+
+// the tls params must not be sent to the server or published to other peers
+pub fn strip_tls_params(url: &url::Url) -> url::Url {
+    remove_query_params(url, &TLS_PARAMS)
+}
+
+impl TlsVerifyMode {
+    pub fn from_url(url: &url::Url) -> Result<Self, TunnelError> {
+        let mut modes = vec![];
+        if let Some(v) = query_param(url, "tls_verify") {
+            modes.push(match v.as_str() {
+                "insecure" => Self::Insecure,
+                "webpki" => Self::WebPki,
+                "system" => Self::System,
+                _ => {
+                    return Err(TunnelError::InvalidAddr(format!(
+                        "invalid tls_verify: {}",
+                        v
+                    )))
+                }
+            });
+        }
+        if let Some(v) = query_param(url, "fingerprint") {
+            let fp = parse_fingerprint(&v)
+                .ok_or_else(|| TunnelError::InvalidAddr(format!("invalid fingerprint: {}", v)))?;
+            modes.push(Self::Fingerprint(fp));
+        }
+        if let Some(v) = query_param(url, "ca") {
+            modes.push(Self::CaFile(v.into()));
+        }
+
+        match modes.len() {
+            0 => Ok(Self::Insecure),
+            1 => Ok(modes.pop().unwrap()),
+            _ => Err(TunnelError::InvalidAddr(format!(
+                "only one of tls_verify, fingerprint and ca can be set: {}",
+                url
+            ))),
+        }
+    }
+
+    // without a verified name the server is addressed as localhost, so an ip
+    // in the sni does not get the connection blocked.
+    pub fn server_name(&self, url: &url::Url) -> String {
+        match self {
+            Self::WebPki | Self::System | Self::CaFile(_) => match url.host() {
+                Some(url::Host::Ipv6(ip)) => ip.to_string(),
+                Some(host) => host.to_string(),
+                None => "localhost".to_owned(),
+            },
+            Self::Insecure | Self::Fingerprint(_) => url.domain().unwrap_or("localhost").to_owned(),
+        }
+    }
+
+    fn root_store(&self) -> Result<RootCertStore, TunnelError> {
+        let mut roots = RootCertStore::empty();
+        match self {
+            Self::WebPki => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+            Self::System => {
+                let certs = rustls_native_certs::load_native_certs();
+                if !certs.errors.is_empty() {
+                    tracing::warn!(errors = ?certs.errors, "failed to load some system certs");
+                }
+                roots.add_parsable_certificates(certs.certs);
+            }
+            Self::CaFile(path) => {
+                let mut reader = BufReader::new(
+                    std::fs::File::open(path)
+                        .with_context(|| format!("open ca file {:?} failed", path))?,
+                );
+                for cert in rustls_pemfile::certs(&mut reader) {
+                    roots
+                        .add(cert.with_context(|| format!("read ca file {:?} failed", path))?)
+                        .with_context(|| format!("invalid ca cert in {:?}", path))?;
+                }
+            }
+            Self::Insecure | Self::Fingerprint(_) => {}
+        }
+
+        if roots.is_empty() {
+            return Err(anyhow::anyhow!("no trusted root cert for {:?}", self).into());
+        }
+        Ok(roots)
+    }
+
+    pub fn client_config(&self) -> Result<rustls::ClientConfig, TunnelError> {
+        init_crypto_provider();
+        let mut config = match self {
+            Self::Insecure => return Ok(get_insecure_tls_client_config()),
+            Self::Fingerprint(fp) => {
+                let provider = rustls::crypto::CryptoProvider::get_default().unwrap();
+                rustls::ClientConfig::builder()
+                    .dangerous()
+                    .with_custom_certificate_verifier(Arc::new(FingerprintVerification {
+                        fingerprint: *fp,
+                        provider: provider.clone(),
+                    }))
+                    .with_no_client_auth()
+            }
+            _ => rustls::ClientConfig::builder()
+                .with_root_certificates(self.root_store()?)
+                .with_no_client_auth(),
+        };
+        config.enable_sni = true;
+        config.enable_early_data = false;
+        Ok(config)
+    }
+}
+
+// accepts exactly the pinned cert, the name in it is not checked because the
+// pin is stronger than any ca signature.
+#[derive(Debug)]
+struct FingerprintVerification {
+    fingerprint: [u8; 32],
+    provider: Arc<rustls::crypto::CryptoProvider>,
+}
+
+impl ServerCertVerifier for FingerprintVerification {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if Sha256::digest(end_entity.as_ref()).as_slice() == self.fingerprint {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(format!(
+                "server cert fingerprint {} does not match the pinned one",
+                cert_fingerprint(end_entity)
+            )))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+// loads the cert and key given in the listener url, or generates a self-signed one
+pub fn load_server_cert(
+    url: &url::Url,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), TunnelError> {
+    let (cert_path, key_path) = match (query_param(url, "cert"), query_param(url, "key")) {
+        (None, None) => return Ok(get_insecure_tls_cert()),
+        (Some(cert), Some(key)) => (cert, key),
+        _ => {
+            return Err(TunnelError::InvalidAddr(format!(
+                "cert and key must be set together: {}",
+                url
+            )))
+        }
+    };
+
+    let mut reader = BufReader::new(
+        std::fs::File::open(&cert_path)
+            .with_context(|| format!("open cert file {} failed", cert_path))?,
+    );
+    let certs = rustls_pemfile::certs(&mut reader)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("read cert file {} failed", cert_path))?;
+    if certs.is_empty() {
+        return Err(anyhow::anyhow!("no cert in {}", cert_path).into());
+    }
+
+    let mut reader = BufReader::new(
+        std::fs::File::open(&key_path)
+            .with_context(|| format!("open key file {} failed", key_path))?,
+    );
+    let key = rustls_pemfile::private_key(&mut reader)
+        .with_context(|| format!("read key file {} failed", key_path))?
+        .with_context(|| format!("no private key in {}", key_path))?;
+
+    tracing::info!(
+        %url,
+        fingerprint = %cert_fingerprint(&certs[0]),
+        "tls cert loaded, peers can pin it with the fingerprint"
+    );
+    Ok((certs, key))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    // writes a self-signed cert for localhost into dir, returns the cert path,
+    // the key path and the fingerprint of the cert.
+    pub fn gen_test_cert_files(dir: &Path) -> (String, String, String) {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        std::fs::write(&cert_path, cert.serialize_pem().unwrap()).unwrap();
+        std::fs::write(&key_path, cert.serialize_private_key_pem()).unwrap();
+        let fingerprint = cert_fingerprint(&cert.serialize_der().unwrap().into());
+        (
+            cert_path.to_str().unwrap().to_owned(),
+            key_path.to_str().unwrap().to_owned(),
+            fingerprint,
+        )
+    }
+
+    #[test]
+    fn verify_mode_from_url() {
+        let mode = |s: &str| TlsVerifyMode::from_url(&s.parse().unwrap());
+        assert_eq!(mode("wss://1.2.3.4:443").unwrap(), TlsVerifyMode::Insecure);
+        assert_eq!(
+            mode("quic://a.com:443?tls_verify=webpki").unwrap(),
+            TlsVerifyMode::WebPki
+        );
+        assert_eq!(
+            mode("quic://a.com:443?ca=/etc/ca.pem").unwrap(),
+            TlsVerifyMode::CaFile("/etc/ca.pem".into())
+        );
+
+        let fp = "AB:".repeat(31) + "AB";
+        assert_eq!(
+            mode(&format!("wss://a.com/?fingerprint={}", fp)).unwrap(),
+            TlsVerifyMode::Fingerprint([0xab; 32])
+        );
+
+        assert!(mode("wss://a.com/?tls_verify=maybe").is_err());
+        assert!(mode("wss://a.com/?fingerprint=abcd").is_err());
+        assert!(mode("wss://a.com/?tls_verify=webpki&ca=/etc/ca.pem").is_err());
+    }
+
+    #[test]
+    fn server_name_and_strip() {
+        let url: url::Url = "wss://1.2.3.4:8443/path?fingerprint=00&x=1&cert=/a"
+            .parse()
+            .unwrap();
+        assert_eq!(TlsVerifyMode::Insecure.server_name(&url), "localhost");
+        assert_eq!(TlsVerifyMode::WebPki.server_name(&url), "1.2.3.4");
+        assert_eq!(
+            strip_tls_params(&url).as_str(),
+            "wss://1.2.3.4:8443/path?x=1"
+        );
+
+        let url: url::Url = "quic://[::1]:443?ca=/etc/ca.pem".parse().unwrap();
+        assert_eq!(TlsVerifyMode::System.server_name(&url), "::1");
+        assert_eq!(strip_tls_params(&url).as_str(), "quic://[::1]:443");
+    }
+
+    #[test]
+    fn load_cert_from_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (cert, key, fingerprint) = gen_test_cert_files(dir.path());
+
+        let url = format!("wss://0.0.0.0:11012?cert={}&key={}", cert, key)
+            .parse()
+            .unwrap();
+        let (certs, _) = load_server_cert(&url).unwrap();
+        assert_eq!(cert_fingerprint(&certs[0]), fingerprint);
+
+        let url = format!("wss://0.0.0.0:11012?cert={}", cert)
+            .parse()
+            .unwrap();
+        assert!(load_server_cert(&url).is_err());
+    }
+
+    #[test]
+    fn pinned_fingerprint() {
+        let (certs, _) = get_insecure_tls_cert();
+        let fp = parse_fingerprint(&cert_fingerprint(&certs[0])).unwrap();
+
+        init_crypto_provider();
+        let provider = rustls::crypto::CryptoProvider::get_default().unwrap();
+        let verifier = FingerprintVerification {
+            fingerprint: fp,
+            provider: provider.clone(),
+        };
+        let name = ServerName::try_from("localhost").unwrap();
+        assert!(verifier
+            .verify_server_cert(&certs[0], &[], &name, &[], UnixTime::now())
+            .is_ok());
+
+        let (other, _) = get_insecure_tls_cert();
+        assert!(verifier
+            .verify_server_cert(&other[0], &[], &name, &[], UnixTime::now())
+            .is_err());
+    }
+}
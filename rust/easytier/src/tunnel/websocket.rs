@@ -12,12 +12,13 @@ use tokio_websockets::{ClientBuilder, Limits, MaybeTlsStream, Message};
 use zerocopy::AsBytes;
 
 use super::TunnelInfo;
-use crate::tunnel::insecure_tls::get_insecure_tls_client_config;
 
 use super::{
     common::{setup_sokcet2, wait_for_connect_futures, TunnelWrapper},
-    insecure_tls::{get_insecure_tls_cert, init_crypto_provider},
+    insecure_tls::init_crypto_provider,
     packet_def::{ZCPacket, ZCPacketType},
+    proxy::{redact_proxy_credentials, strip_proxy_param, UpstreamProxy},
+    tls::{load_server_cert, strip_tls_params, TlsVerifyMode},
     FromUrl, IpVersion, Tunnel, TunnelConnector, TunnelError, TunnelListener,
 };
 
@@ -63,6 +64,7 @@ async fn map_from_ws_message(
 pub struct WSTunnelListener {
     addr: url::Url,
     listener: Option<TcpListener>,
+    tls_config: Option<Arc<rustls::ServerConfig>>,
 }
 
 impl WSTunnelListener {
@@ -70,6 +72,7 @@ impl WSTunnelListener {
         WSTunnelListener {
             addr,
             listener: None,
+            tls_config: None,
         }
     }
 
@@ -88,14 +91,8 @@ impl WSTunnelListener {
 
         let server_bulder = tokio_websockets::ServerBuilder::new().limits(Limits::unlimited());
 
-        let ret: Box<dyn Tunnel> = if is_wss(&self.addr)? {
-            init_crypto_provider();
-            let (certs, key) = get_insecure_tls_cert();
-            let config = rustls::ServerConfig::builder()
-                .with_no_client_auth()
-                .with_single_cert(certs, key)
-                .with_context(|| "Failed to create server config")?;
-            let acceptor = TlsAcceptor::from(Arc::new(config));
+        let ret: Box<dyn Tunnel> = if let Some(config) = self.tls_config.clone() {
+            let acceptor = TlsAcceptor::from(config);
 
             let stream = acceptor.accept(stream).await?;
             let (write, read) = server_bulder.accept(stream).await?.split();
@@ -134,6 +131,16 @@ impl TunnelListener for WSTunnelListener {
             .set_port(Some(socket.local_addr()?.port()))
             .unwrap();
 
+        if is_wss(&self.addr)? {
+            init_crypto_provider();
+            let (certs, key) = load_server_cert(&self.addr)?;
+            let config = rustls::ServerConfig::builder()
+                .with_no_client_auth()
+                .with_single_cert(certs, key)
+                .with_context(|| "Failed to create server config")?;
+            self.tls_config = Some(Arc::new(config));
+        }
+
         self.listener = Some(socket.listen(1024)?);
         Ok(())
     }
@@ -155,7 +162,7 @@ impl TunnelListener for WSTunnelListener {
     }
 
     fn local_url(&self) -> url::Url {
-        self.addr.clone()
+        strip_tls_params(&self.addr)
     }
 }
 
@@ -181,10 +188,16 @@ impl WSTunnelConnector {
         ip_version: IpVersion,
         tcp_socket: TcpSocket,
     ) -> Result<Box<dyn Tunnel>, TunnelError> {
-        let is_wss = is_wss(&addr)?;
         let socket_addr = SocketAddr::from_url(addr.clone(), ip_version).await?;
         let stream = tcp_socket.connect(socket_addr).await?;
+        Self::connect_with_stream(addr, stream).await
+    }
 
+    async fn connect_with_stream(
+        addr: url::Url,
+        stream: TcpStream,
+    ) -> Result<Box<dyn Tunnel>, TunnelError> {
+        let is_wss = is_wss(&addr)?;
         let info = TunnelInfo {
             tunnel_type: addr.scheme().to_owned(),
             local_addr: Some(
@@ -194,19 +207,16 @@ impl WSTunnelConnector {
                 )
                 .into(),
             ),
-            remote_addr: Some(addr.clone().into()),
+            remote_addr: Some(strip_proxy_param(&addr).into()),
         };
 
-        let c = ClientBuilder::from_uri(http::Uri::try_from(addr.to_string()).unwrap());
+        let c = ClientBuilder::from_uri(
+            http::Uri::try_from(strip_proxy_param(&strip_tls_params(&addr)).to_string()).unwrap(),
+        );
         let stream: MaybeTlsStream<TcpStream> = if is_wss {
-            init_crypto_provider();
-            let tls_conn =
-                tokio_rustls::TlsConnector::from(Arc::new(get_insecure_tls_client_config()));
-            // Modify SNI logic: use "localhost" as SNI for url without domain to avoid IP blocking.
-            let sni = match addr.domain() {
-                None => "localhost".to_string(),
-                Some(domain) => domain.to_string(),
-            };
+            let tls_verify = TlsVerifyMode::from_url(&addr)?;
+            let tls_conn = tokio_rustls::TlsConnector::from(Arc::new(tls_verify.client_config()?));
+            let sni = tls_verify.server_name(&addr);
             let server_name = rustls::pki_types::ServerName::try_from(sni)
                 .map_err(|_| TunnelError::InvalidProtocol("Invalid SNI".to_string()))?;
             let stream = tls_conn.connect(server_name, stream).await?;
@@ -269,6 +279,11 @@ impl WSTunnelConnector {
 #[async_trait::async_trait]
 impl TunnelConnector for WSTunnelConnector {
     async fn connect(&mut self) -> Result<Box<dyn Tunnel>, super::TunnelError> {
+        if let Some(proxy) = UpstreamProxy::from_tunnel_url(&self.addr)? {
+            let stream = proxy.connect(&self.addr).await?;
+            return Self::connect_with_stream(self.addr.clone(), stream).await;
+        }
+
         let addr = SocketAddr::from_url(self.addr.clone(), self.ip_version).await?;
         if self.bind_addrs.is_empty() || addr.is_ipv6() {
             self.connect_with_default_bind(addr).await
@@ -278,7 +293,7 @@ impl TunnelConnector for WSTunnelConnector {
     }
 
     fn remote_url(&self) -> url::Url {
-        self.addr.clone()
+        redact_proxy_credentials(&self.addr)
     }
 
     fn set_ip_version(&mut self, ip_version: IpVersion) {
@@ -293,6 +308,8 @@ impl TunnelConnector for WSTunnelConnector {
 #[cfg(test)]
 pub mod tests {
     use crate::tunnel::common::tests::_tunnel_pingpong;
+    use crate::tunnel::proxy::{tests::run_fake_socks5_proxy, PROXY_PARAM};
+    use crate::tunnel::tls::tests::gen_test_cert_files;
     use crate::tunnel::websocket::{WSTunnelConnector, WSTunnelListener};
     use crate::tunnel::{TunnelConnector, TunnelListener};
 
@@ -345,4 +362,55 @@ pub mod tests {
 
         j.abort();
     }
+
+    #[tokio::test]
+    async fn wss_cert_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let (cert, key, fingerprint) = gen_test_cert_files(dir.path());
+
+        let listener = WSTunnelListener::new(
+            format!("wss://0.0.0.0:25559?cert={}&key={}", cert, key)
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(listener.local_url().as_str(), "wss://0.0.0.0:25559/");
+        let connector = WSTunnelConnector::new(
+            format!("wss://127.0.0.1:25559?fingerprint={}", fingerprint)
+                .parse()
+                .unwrap(),
+        );
+        _tunnel_pingpong(listener, connector).await;
+
+        let mut listener = WSTunnelListener::new("wss://0.0.0.0:25560".parse().unwrap());
+        listener.listen().await.unwrap();
+        let j = tokio::spawn(async move {
+            let _ = listener.accept().await;
+        });
+
+        // the generated cert is neither pinned nor signed by a trusted ca
+        for verify in [
+            format!("fingerprint={}", fingerprint),
+            format!("ca={}", cert),
+        ] {
+            let mut connector = WSTunnelConnector::new(
+                format!("wss://127.0.0.1:25560?{}", verify).parse().unwrap(),
+            );
+            connector.connect().await.unwrap_err();
+        }
+
+        j.abort();
+    }
+
+    #[rstest::rstest]
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn ws_pingpong_through_proxy(#[values("ws", "wss")] proto: &str) {
+        let listener = WSTunnelListener::new(format!("{}://0.0.0.0:25561", proto).parse().unwrap());
+        let proxy = run_fake_socks5_proxy().await;
+        let mut url: url::Url = format!("{}://127.0.0.1:25561", proto).parse().unwrap();
+        url.query_pairs_mut()
+            .append_pair(PROXY_PARAM, proxy.as_str());
+        let connector = WSTunnelConnector::new(url);
+        _tunnel_pingpong(listener, connector).await
+    }
 }
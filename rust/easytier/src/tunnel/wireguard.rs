@@ -10,7 +10,7 @@ use anyhow::Context;
 use async_recursion::async_recursion;
 use async_trait::async_trait;
 use boringtun::{
-    noise::{errors::WireGuardError, Tunn, TunnResult},
+    noise::{errors::WireGuardError, handshake::parse_handshake_anon, Packet, Tunn, TunnResult},
     x25519::{PublicKey, StaticSecret},
 };
 use bytes::BytesMut;
@@ -107,6 +107,26 @@ impl WgConfig {
     }
 }
 
+/// Decides whether a peer may connect to a listener, by the static public key of its handshake.
+pub type WgPeerAuthorizer = Arc<dyn Fn(&[u8; 32]) -> bool + Send + Sync>;
+
+/// A peer accepted by a listener, valid as long as its tunnel is alive.
+#[derive(Clone)]
+pub struct WgPeerHandle {
+    public_key: PublicKey,
+    tunn: Arc<Mutex<Tunn>>,
+}
+
+impl WgPeerHandle {
+    pub fn public_key(&self) -> &[u8; 32] {
+        self.public_key.as_bytes()
+    }
+
+    pub async fn time_since_last_handshake(&self) -> Option<Duration> {
+        self.tunn.lock().await.stats().0
+    }
+}
+
 #[derive(Clone)]
 struct WgPeerData {
     udp: Arc<UdpSocket>, // only for send
@@ -444,6 +464,13 @@ impl WgPeer {
     fn udp_socket(&self) -> Arc<UdpSocket> {
         self.udp.clone()
     }
+
+    fn handle(&self) -> Option<WgPeerHandle> {
+        Some(WgPeerHandle {
+            public_key: self.config.peer_public_key,
+            tunn: self.data.as_ref()?.tunn.clone(),
+        })
+    }
 }
 
 type ConnSender = tokio::sync::mpsc::UnboundedSender<Box<dyn Tunnel>>;
@@ -458,6 +485,7 @@ pub struct WgTunnelListener {
     conn_send: Option<ConnSender>,
 
     wg_peer_map: Arc<DashMap<SocketAddr, Arc<WgPeer>>>,
+    authorizer: Option<WgPeerAuthorizer>,
 
     tasks: JoinSet<()>,
 }
@@ -474,18 +502,50 @@ impl WgTunnelListener {
             conn_send: Some(conn_send),
 
             wg_peer_map: Arc::new(DashMap::new()),
+            authorizer: None,
 
             tasks: JoinSet::new(),
         }
     }
 
+    /// Accept any peer the authorizer allows instead of the single peer key of the config,
+    /// must be called before listen.
+    pub fn set_peer_authorizer(&mut self, authorizer: WgPeerAuthorizer) {
+        self.authorizer = Some(authorizer);
+    }
+
+    /// The peer behind a tunnel returned by accept, looked up by the remote address of the tunnel.
+    pub fn get_peer_handle(&self, endpoint: &SocketAddr) -> Option<WgPeerHandle> {
+        self.wg_peer_map.get(endpoint)?.handle()
+    }
+
     fn get_udp_socket(&self) -> Arc<UdpSocket> {
         self.udp.as_ref().unwrap().clone()
     }
 
+    /// Returns the config to talk to the sender of a handshake initiation, if it is authorized.
+    fn authorize_handshake(
+        config: &WgConfig,
+        authorizer: &WgPeerAuthorizer,
+        packet: &[u8],
+    ) -> Option<WgConfig> {
+        let Ok(Packet::HandshakeInit(init)) = Tunn::parse_incoming_packet(packet) else {
+            return None;
+        };
+        let half =
+            parse_handshake_anon(&config.my_secret_key, &config.my_public_key, &init).ok()?;
+        if !authorizer(&half.peer_static_public) {
+            return None;
+        }
+        let mut config = config.clone();
+        config.peer_public_key = PublicKey::from(half.peer_static_public);
+        Some(config)
+    }
+
     async fn handle_udp_incoming(
         socket: Arc<UdpSocket>,
         config: WgConfig,
+        authorizer: Option<WgPeerAuthorizer>,
         conn_sender: ConnSender,
         peer_map: Arc<DashMap<SocketAddr, Arc<WgPeer>>>,
     ) {
@@ -512,8 +572,19 @@ impl WgTunnelListener {
             tracing::trace!(?n, ?addr, "Received bytes from peer");
 
             if !peer_map.contains_key(&addr) {
+                let config = match &authorizer {
+                    Some(authorizer) => {
+                        let Some(config) = Self::authorize_handshake(&config, authorizer, data)
+                        else {
+                            tracing::debug!(?addr, "Dropping packet from unauthorized peer");
+                            continue;
+                        };
+                        config
+                    }
+                    None => config.clone(),
+                };
                 tracing::info!("New peer: {}", addr);
-                let mut wg = WgPeer::new(socket.clone(), config.clone(), addr);
+                let mut wg = WgPeer::new(socket.clone(), config, addr);
                 let (stream, sink) = wg.start_and_get_tunnel().split();
                 let tunnel = Box::new(TunnelWrapper::new(
                     stream,
@@ -532,10 +603,11 @@ impl WgTunnelListener {
                         ),
                     }),
                 ));
+                // insert before sending, so the peer handle can be looked up once the tunnel is accepted
+                peer_map.insert(addr, Arc::new(wg));
                 if let Err(e) = conn_sender.send(tunnel) {
                     tracing::error!("Failed to send tunnel to conn_sender: {}", e);
                 }
-                peer_map.insert(addr, Arc::new(wg));
             }
 
             let peer = peer_map.get(&addr).unwrap().clone();
@@ -571,6 +643,7 @@ impl TunnelListener for WgTunnelListener {
         self.tasks.spawn(Self::handle_udp_incoming(
             self.get_udp_socket(),
             self.config.clone(),
+            self.authorizer.clone(),
             self.conn_send.take().unwrap(),
             self.wg_peer_map.clone(),
         ));
@@ -878,6 +951,40 @@ pub mod tests {
         assert_eq!(0, listener.wg_peer_map.len());
     }
 
+    #[tokio::test]
+    async fn wg_peer_authorizer() {
+        let (server_cfg, client_cfg) = create_wg_config();
+        let allowed = *client_cfg.my_public_key.as_bytes();
+        let mut listener =
+            WgTunnelListener::new("wg://127.0.0.1:5594".parse().unwrap(), server_cfg);
+        listener.set_peer_authorizer(Arc::new(move |key| *key == allowed));
+        listener.listen().await.unwrap();
+
+        let mut connector =
+            WgTunnelConnector::new("wg://127.0.0.1:5594".parse().unwrap(), client_cfg.clone());
+        let _t = connector.connect().await.unwrap();
+        let accepted = listener.accept().await.unwrap();
+        let remote: url::Url = accepted.info().unwrap().remote_addr.unwrap().into();
+        let handle = listener
+            .get_peer_handle(&remote.socket_addrs(|| None).unwrap()[0])
+            .unwrap();
+        assert_eq!(handle.public_key(), &allowed);
+        assert!(handle.time_since_last_handshake().await.is_some());
+
+        // same server key, but a key pair the authorizer does not know
+        let other_secret = x25519::StaticSecret::random_from_rng(rand::thread_rng());
+        let other_cfg = WgConfig {
+            my_public_key: x25519::PublicKey::from(&other_secret),
+            my_secret_key: other_secret,
+            ..client_cfg
+        };
+        let mut connector =
+            WgTunnelConnector::new("wg://127.0.0.1:5594".parse().unwrap(), other_cfg);
+        let ret = tokio::time::timeout(Duration::from_secs(2), connector.connect()).await;
+        assert!(ret.is_err());
+        assert_eq!(1, listener.wg_peer_map.len());
+    }
+
     #[tokio::test]
     async fn bind_same_port() {
         let (server_cfg, _client_cfg) = create_wg_config();
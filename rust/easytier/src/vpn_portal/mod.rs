@@ -2,13 +2,20 @@
 // without installing easytier.
 // these vpn client include:
 // 1. wireguard
-// 2. openvpn (TODO)
-// 3. shadowsocks (TODO)
+// 2. openvpn
+// 3. shadowsocks
 
 use std::sync::Arc;
 
-use crate::{common::global_ctx::ArcGlobalCtx, peers::peer_manager::PeerManager};
+use crate::{
+    common::global_ctx::ArcGlobalCtx, peers::peer_manager::PeerManager,
+    proto::cli::VpnPortalClientInfo,
+};
 
+#[cfg(feature = "openvpn")]
+pub mod openvpn;
+#[cfg(feature = "shadowsocks")]
+pub mod shadowsocks;
 #[cfg(feature = "wireguard")]
 pub mod wireguard;
 
@@ -22,6 +29,15 @@ pub trait VpnPortal: Send + Sync {
     async fn dump_client_config(&self, peer_mgr: Arc<PeerManager>) -> String;
     fn name(&self) -> String;
     async fn list_clients(&self) -> Vec<String>;
+
+    // named clients with their own credentials, empty if the portal has none
+    async fn list_client_infos(&self) -> Vec<VpnPortalClientInfo> {
+        vec![]
+    }
+
+    async fn revoke_client(&self, name: &str) -> anyhow::Result<()> {
+        anyhow::bail!("{} portal has no named client: {}", self.name(), name)
+    }
 }
 
 pub struct NullVpnPortal;
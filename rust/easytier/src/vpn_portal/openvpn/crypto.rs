@@ -0,0 +1,665 @@
+use std::sync::Arc;
+
+use aes::Aes256;
+use anyhow::Context;
+use cbc::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use sha2::{Digest, Sha256};
+
+type Aes256CbcEnc = cbc::Encryptor<Aes256>;
+type Aes256CbcDec = cbc::Decryptor<Aes256>;
+type HmacSha256 = Hmac<Sha256>;
+
+pub(crate) const KEY_MATERIAL_LEN: usize = 256;
+pub(crate) const EKM_LABEL: &[u8] = b"EXPORTER-OpenVPN-datakeys";
+
+const CBC_IV_LEN: usize = 16;
+const HMAC_LEN: usize = 32;
+const AEAD_TAG_LEN: usize = 16;
+const PACKET_ID_LEN: usize = 4;
+
+// pkcs8 v1 header of an ed25519 private key, followed by the 32 bytes seed
+const ED25519_PKCS8_PREFIX: [u8; 16] = [
+    0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04, 0x20,
+];
+
+pub(crate) const SERVER_CERT_NAME: &str = "easytier-openvpn-portal";
+
+/// Two keys of 64 bytes cipher key + 64 bytes hmac key, the layout of
+/// `--secret` files and of the keys exported from the tls session.
+#[derive(Clone)]
+pub(crate) struct KeyMaterial([u8; KEY_MATERIAL_LEN]);
+
+impl KeyMaterial {
+    pub fn derive(seed: &str, label: &str) -> Self {
+        let mut out = [0u8; KEY_MATERIAL_LEN];
+        for (i, chunk) in out.chunks_mut(32).enumerate() {
+            let mut hasher = Sha256::new();
+            hasher.update(label.as_bytes());
+            hasher.update([i as u8]);
+            hasher.update(seed.as_bytes());
+            chunk.copy_from_slice(&hasher.finalize());
+        }
+        Self(out)
+    }
+
+    pub fn from_bytes(bytes: [u8; KEY_MATERIAL_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    fn cipher_key(&self, idx: usize) -> &[u8] {
+        &self.0[idx * 128..idx * 128 + 64]
+    }
+
+    fn hmac_key(&self, idx: usize) -> &[u8] {
+        &self.0[idx * 128 + 64..idx * 128 + 128]
+    }
+
+    /// Content of a `--secret` file in the format written by `openvpn --genkey`.
+    pub fn to_static_key_file(&self) -> String {
+        let mut ret = String::from(
+            "#\n# 2048 bit OpenVPN static key\n#\n-----BEGIN OpenVPN Static key V1-----\n",
+        );
+        for line in self.0.chunks(16) {
+            for b in line {
+                ret.push_str(&format!("{:02x}", b));
+            }
+            ret.push('\n');
+        }
+        ret.push_str("-----END OpenVPN Static key V1-----\n");
+        ret
+    }
+}
+
+/// Sliding window replay protection over packet ids, like openvpn's `--replay-window 64`.
+#[derive(Debug, Default)]
+pub(crate) struct ReplayWindow {
+    max: u32,
+    bitmap: u64,
+}
+
+impl ReplayWindow {
+    pub fn check_and_update(&mut self, packet_id: u32) -> bool {
+        if packet_id == 0 {
+            return false;
+        }
+        if packet_id > self.max {
+            let shift = packet_id - self.max;
+            self.bitmap = if shift >= 64 { 0 } else { self.bitmap << shift };
+            self.bitmap |= 1;
+            self.max = packet_id;
+            return true;
+        }
+        let offset = self.max - packet_id;
+        if offset >= 64 {
+            return false;
+        }
+        let bit = 1u64 << offset;
+        if self.bitmap & bit != 0 {
+            return false;
+        }
+        self.bitmap |= bit;
+        true
+    }
+}
+
+pub(crate) trait DataCipher: Send {
+    /// Encrypt an ip packet into a full data channel packet starting with `header`.
+    fn encrypt(&mut self, header: &[u8], payload: &[u8]) -> Vec<u8>;
+    /// Decrypt a full data channel packet whose first `header_len` bytes are the opcode header.
+    fn decrypt(&mut self, header_len: usize, packet: &[u8]) -> anyhow::Result<Vec<u8>>;
+}
+
+/// AES-256-CBC with HMAC-SHA256, used in static key mode. The plaintext starts
+/// with the long form packet id (id + timestamp).
+pub(crate) struct CbcHmacCipher {
+    enc_key: [u8; 32],
+    enc_hmac: [u8; 32],
+    dec_key: [u8; 32],
+    dec_hmac: [u8; 32],
+    send_packet_id: u32,
+    // the start of our packet id sequence, sent with every packet
+    send_time: u32,
+    recv_time: u32,
+    replay: ReplayWindow,
+}
+
+impl CbcHmacCipher {
+    pub fn new(keys: &KeyMaterial, out_key: usize, in_key: usize) -> Self {
+        let copy = |src: &[u8]| {
+            let mut dst = [0u8; 32];
+            dst.copy_from_slice(&src[..32]);
+            dst
+        };
+        Self {
+            enc_key: copy(keys.cipher_key(out_key)),
+            enc_hmac: copy(keys.hmac_key(out_key)),
+            dec_key: copy(keys.cipher_key(in_key)),
+            dec_hmac: copy(keys.hmac_key(in_key)),
+            send_packet_id: 0,
+            send_time: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as u32)
+                .unwrap_or_default(),
+            recv_time: 0,
+            replay: ReplayWindow::default(),
+        }
+    }
+
+    // returns the iv and cipher text of a packet whose hmac matches
+    fn verify_hmac<'a>(&self, header_len: usize, packet: &'a [u8]) -> anyhow::Result<&'a [u8]> {
+        let body = packet
+            .get(header_len..)
+            .filter(|b| b.len() >= HMAC_LEN + CBC_IV_LEN + 16)
+            .ok_or_else(|| anyhow::anyhow!("data packet too short"))?;
+        let (mac, rest) = body.split_at(HMAC_LEN);
+        let mut verifier = HmacSha256::new_from_slice(&self.dec_hmac).unwrap();
+        verifier.update(rest);
+        verifier
+            .verify_slice(mac)
+            .map_err(|_| anyhow::anyhow!("data packet hmac mismatch"))?;
+        Ok(rest)
+    }
+
+    /// Whether the packet was sealed with our key, without touching the replay state.
+    pub fn is_authentic(&self, header_len: usize, packet: &[u8]) -> bool {
+        self.verify_hmac(header_len, packet).is_ok()
+    }
+}
+
+impl DataCipher for CbcHmacCipher {
+    fn encrypt(&mut self, header: &[u8], payload: &[u8]) -> Vec<u8> {
+        self.send_packet_id = self.send_packet_id.wrapping_add(1);
+
+        let mut plain = Vec::with_capacity(PACKET_ID_LEN * 2 + payload.len());
+        plain.extend_from_slice(&self.send_packet_id.to_be_bytes());
+        plain.extend_from_slice(&self.send_time.to_be_bytes());
+        plain.extend_from_slice(payload);
+
+        let mut iv = [0u8; CBC_IV_LEN];
+        rand::thread_rng().fill_bytes(&mut iv);
+        let cipher_text = Aes256CbcEnc::new(&self.enc_key.into(), &iv.into())
+            .encrypt_padded_vec_mut::<Pkcs7>(&plain);
+
+        let mut mac = HmacSha256::new_from_slice(&self.enc_hmac).unwrap();
+        mac.update(&iv);
+        mac.update(&cipher_text);
+        let mac = mac.finalize().into_bytes();
+
+        let mut ret = Vec::with_capacity(header.len() + HMAC_LEN + CBC_IV_LEN + cipher_text.len());
+        ret.extend_from_slice(header);
+        ret.extend_from_slice(&mac);
+        ret.extend_from_slice(&iv);
+        ret.extend_from_slice(&cipher_text);
+        ret
+    }
+
+    fn decrypt(&mut self, header_len: usize, packet: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let rest = self.verify_hmac(header_len, packet)?;
+        let (iv, cipher_text) = rest.split_at(CBC_IV_LEN);
+        let iv: [u8; CBC_IV_LEN] = iv.try_into().unwrap();
+        let plain = Aes256CbcDec::new(&self.dec_key.into(), &iv.into())
+            .decrypt_padded_vec_mut::<Pkcs7>(cipher_text)
+            .map_err(|_| anyhow::anyhow!("data packet padding invalid"))?;
+        if plain.len() < PACKET_ID_LEN * 2 {
+            anyhow::bail!("data packet missing packet id");
+        }
+
+        let packet_id = u32::from_be_bytes(plain[0..4].try_into().unwrap());
+        let time = u32::from_be_bytes(plain[4..8].try_into().unwrap());
+        // the peer restarted its packet id sequence, e.g. after a reconnect
+        if time > self.recv_time {
+            self.recv_time = time;
+            self.replay = ReplayWindow::default();
+        } else if time < self.recv_time {
+            anyhow::bail!("data packet timestamp too old");
+        }
+        if !self.replay.check_and_update(packet_id) {
+            anyhow::bail!("data packet replayed: {}", packet_id);
+        }
+
+        Ok(plain[PACKET_ID_LEN * 2..].to_vec())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AeadAlgo {
+    Aes256Gcm,
+    Aes128Gcm,
+    Chacha20Poly1305,
+}
+
+impl AeadAlgo {
+    // in our order of preference
+    pub const ALL: [AeadAlgo; 3] = [
+        AeadAlgo::Aes256Gcm,
+        AeadAlgo::Chacha20Poly1305,
+        AeadAlgo::Aes128Gcm,
+    ];
+
+    pub fn openvpn_name(&self) -> &'static str {
+        match self {
+            AeadAlgo::Aes256Gcm => "AES-256-GCM",
+            AeadAlgo::Aes128Gcm => "AES-128-GCM",
+            AeadAlgo::Chacha20Poly1305 => "CHACHA20-POLY1305",
+        }
+    }
+
+    fn ring_algo(&self) -> &'static aead::Algorithm {
+        match self {
+            AeadAlgo::Aes256Gcm => &aead::AES_256_GCM,
+            AeadAlgo::Aes128Gcm => &aead::AES_128_GCM,
+            AeadAlgo::Chacha20Poly1305 => &aead::CHACHA20_POLY1305,
+        }
+    }
+
+    /// Pick our most preferred cipher from the colon separated `IV_CIPHERS` of a client.
+    pub fn negotiate(client_ciphers: &str) -> Option<AeadAlgo> {
+        Self::ALL.into_iter().find(|algo| {
+            client_ciphers
+                .split(':')
+                .any(|c| c.eq_ignore_ascii_case(algo.openvpn_name()))
+        })
+    }
+}
+
+/// AEAD data channel of tls mode. The nonce is the packet id followed by an
+/// implicit iv taken from the hmac part of the key material.
+pub(crate) struct AeadCipher {
+    enc: LessSafeKey,
+    enc_implicit_iv: [u8; 8],
+    dec: LessSafeKey,
+    dec_implicit_iv: [u8; 8],
+    send_packet_id: u32,
+    replay: ReplayWindow,
+}
+
+impl AeadCipher {
+    pub fn new(algo: AeadAlgo, keys: &KeyMaterial, out_key: usize, in_key: usize) -> Self {
+        let ring_algo = algo.ring_algo();
+        let new_key = |idx: usize| {
+            LessSafeKey::new(
+                UnboundKey::new(ring_algo, &keys.cipher_key(idx)[..ring_algo.key_len()]).unwrap(),
+            )
+        };
+        let implicit_iv = |idx: usize| {
+            let mut iv = [0u8; 8];
+            iv.copy_from_slice(&keys.hmac_key(idx)[..8]);
+            iv
+        };
+        Self {
+            enc: new_key(out_key),
+            enc_implicit_iv: implicit_iv(out_key),
+            dec: new_key(in_key),
+            dec_implicit_iv: implicit_iv(in_key),
+            send_packet_id: 0,
+            replay: ReplayWindow::default(),
+        }
+    }
+
+    fn nonce(packet_id: &[u8], implicit_iv: &[u8; 8]) -> Nonce {
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        nonce[..PACKET_ID_LEN].copy_from_slice(packet_id);
+        nonce[PACKET_ID_LEN..].copy_from_slice(implicit_iv);
+        Nonce::assume_unique_for_key(nonce)
+    }
+
+    // P_DATA_V2 authenticates the opcode and peer id, P_DATA_V1 only the packet id
+    fn aad(header: &[u8], packet_id: &[u8]) -> Vec<u8> {
+        let mut aad = Vec::with_capacity(header.len() + PACKET_ID_LEN);
+        if header.len() > 1 {
+            aad.extend_from_slice(header);
+        }
+        aad.extend_from_slice(packet_id);
+        aad
+    }
+}
+
+impl DataCipher for AeadCipher {
+    fn encrypt(&mut self, header: &[u8], payload: &[u8]) -> Vec<u8> {
+        self.send_packet_id = self.send_packet_id.wrapping_add(1);
+        let packet_id = self.send_packet_id.to_be_bytes();
+
+        let mut buf = payload.to_vec();
+        let tag = self
+            .enc
+            .seal_in_place_separate_tag(
+                Self::nonce(&packet_id, &self.enc_implicit_iv),
+                Aad::from(Self::aad(header, &packet_id)),
+                &mut buf,
+            )
+            .unwrap();
+
+        let mut ret =
+            Vec::with_capacity(header.len() + PACKET_ID_LEN + AEAD_TAG_LEN + payload.len());
+        ret.extend_from_slice(header);
+        ret.extend_from_slice(&packet_id);
+        ret.extend_from_slice(tag.as_ref());
+        ret.extend_from_slice(&buf);
+        ret
+    }
+
+    fn decrypt(&mut self, header_len: usize, packet: &[u8]) -> anyhow::Result<Vec<u8>> {
+        if packet.len() < header_len + PACKET_ID_LEN + AEAD_TAG_LEN {
+            anyhow::bail!("data packet too short");
+        }
+        let (header, body) = packet.split_at(header_len);
+        let (packet_id, body) = body.split_at(PACKET_ID_LEN);
+        let (tag, cipher_text) = body.split_at(AEAD_TAG_LEN);
+
+        // ring expects the tag after the cipher text
+        let mut buf = Vec::with_capacity(body.len());
+        buf.extend_from_slice(cipher_text);
+        buf.extend_from_slice(tag);
+        let plain_len = self
+            .dec
+            .open_in_place(
+                Self::nonce(packet_id, &self.dec_implicit_iv),
+                Aad::from(Self::aad(header, packet_id)),
+                &mut buf,
+            )
+            .map_err(|_| anyhow::anyhow!("data packet authentication failed"))?
+            .len();
+        buf.truncate(plain_len);
+
+        let packet_id = u32::from_be_bytes(packet_id.try_into().unwrap());
+        if !self.replay.check_and_update(packet_id) {
+            anyhow::bail!("data packet replayed: {}", packet_id);
+        }
+        Ok(buf)
+    }
+}
+
+struct DerivedCert {
+    cert: rcgen::Certificate,
+    key_der: Vec<u8>,
+}
+
+fn derive_cert(
+    seed: &str,
+    label: &str,
+    names: Vec<String>,
+    customize: impl FnOnce(&mut rcgen::CertificateParams),
+) -> anyhow::Result<DerivedCert> {
+    let mut hasher = Sha256::new();
+    hasher.update(label.as_bytes());
+    hasher.update(seed.as_bytes());
+    let mut key_der = ED25519_PKCS8_PREFIX.to_vec();
+    key_der.extend_from_slice(&hasher.finalize());
+
+    let mut params = rcgen::CertificateParams::new(names);
+    params.alg = &rcgen::PKCS_ED25519;
+    params.key_pair = Some(rcgen::KeyPair::from_der(&key_der)?);
+    params.distinguished_name = rcgen::DistinguishedName::new();
+    params
+        .distinguished_name
+        .push(rcgen::DnType::CommonName, label);
+    customize(&mut params);
+
+    Ok(DerivedCert {
+        cert: rcgen::Certificate::from_params(params)?,
+        key_der,
+    })
+}
+
+fn to_pem(tag: &str, der: &[u8]) -> String {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    let encoded = BASE64_STANDARD.encode(der);
+    let mut ret = format!("-----BEGIN {}-----\n", tag);
+    for line in encoded.as_bytes().chunks(64) {
+        ret.push_str(std::str::from_utf8(line).unwrap());
+        ret.push('\n');
+    }
+    ret.push_str(&format!("-----END {}-----\n", tag));
+    ret
+}
+
+/// A ca, a server and a client certificate derived from the network secret, so
+/// exported client configs keep working across restarts. Ed25519 keys are used
+/// because they can be derived from a seed and sign deterministically.
+pub(crate) struct TlsIdentity {
+    pub ca_der: Vec<u8>,
+    pub server_cert_der: Vec<u8>,
+    server_key_der: Vec<u8>,
+    pub client_cert_der: Vec<u8>,
+    pub client_key_der: Vec<u8>,
+}
+
+impl TlsIdentity {
+    pub fn derive(seed: &str) -> anyhow::Result<Self> {
+        let ca = derive_cert(seed, "easytier-openvpn-ca", vec![], |p| {
+            p.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+            p.key_usages = vec![
+                rcgen::KeyUsagePurpose::KeyCertSign,
+                rcgen::KeyUsagePurpose::CrlSign,
+                rcgen::KeyUsagePurpose::DigitalSignature,
+            ];
+        })?;
+        let server = derive_cert(
+            seed,
+            "easytier-openvpn-server",
+            vec![SERVER_CERT_NAME.to_string()],
+            |p| {
+                p.key_usages = vec![rcgen::KeyUsagePurpose::DigitalSignature];
+                p.extended_key_usages = vec![rcgen::ExtendedKeyUsagePurpose::ServerAuth];
+            },
+        )?;
+        let client = derive_cert(seed, "easytier-openvpn-client", vec![], |p| {
+            p.key_usages = vec![rcgen::KeyUsagePurpose::DigitalSignature];
+            p.extended_key_usages = vec![rcgen::ExtendedKeyUsagePurpose::ClientAuth];
+        })?;
+
+        Ok(Self {
+            ca_der: ca.cert.serialize_der()?,
+            server_cert_der: server.cert.serialize_der_with_signer(&ca.cert)?,
+            server_key_der: server.key_der,
+            client_cert_der: client.cert.serialize_der_with_signer(&ca.cert)?,
+            client_key_der: client.key_der,
+        })
+    }
+
+    pub fn ca_pem(&self) -> String {
+        to_pem("CERTIFICATE", &self.ca_der)
+    }
+
+    pub fn client_cert_pem(&self) -> String {
+        to_pem("CERTIFICATE", &self.client_cert_der)
+    }
+
+    pub fn client_key_pem(&self) -> String {
+        to_pem("PRIVATE KEY", &self.client_key_der)
+    }
+
+    /// Server side tls config, clients must present a certificate signed by our ca.
+    pub fn server_config(&self) -> anyhow::Result<Arc<rustls::ServerConfig>> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(CertificateDer::from(self.ca_der.clone()))?;
+        let verifier = rustls::server::WebPkiClientVerifier::builder_with_provider(
+            Arc::new(roots),
+            provider.clone(),
+        )
+        .build()
+        .with_context(|| "failed to build openvpn client verifier")?;
+
+        let config = rustls::ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()?
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(
+                vec![
+                    CertificateDer::from(self.server_cert_der.clone()),
+                    CertificateDer::from(self.ca_der.clone()),
+                ],
+                PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(self.server_key_der.clone())),
+            )?;
+        Ok(Arc::new(config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_window() {
+        let mut w = ReplayWindow::default();
+        assert!(!w.check_and_update(0));
+        assert!(w.check_and_update(1));
+        assert!(!w.check_and_update(1));
+        assert!(w.check_and_update(3));
+        assert!(w.check_and_update(2));
+        assert!(!w.check_and_update(2));
+        assert!(w.check_and_update(100));
+        assert!(!w.check_and_update(30));
+        assert!(w.check_and_update(99));
+    }
+
+    #[test]
+    fn test_static_key_file_format() {
+        let keys = KeyMaterial::derive("net", "label");
+        let file = keys.to_static_key_file();
+        let hex_lines = file
+            .lines()
+            .filter(|l| !l.starts_with('#') && !l.starts_with("-----"))
+            .collect::<Vec<_>>();
+        assert_eq!(hex_lines.len(), 16);
+        assert!(hex_lines.iter().all(|l| l.len() == 32));
+        assert_eq!(
+            file,
+            KeyMaterial::derive("net", "label").to_static_key_file()
+        );
+        assert_ne!(
+            file,
+            KeyMaterial::derive("net2", "label").to_static_key_file()
+        );
+    }
+
+    #[test]
+    fn test_cbc_hmac_roundtrip() {
+        let keys = KeyMaterial::derive("net", "static");
+        // server uses key direction 0 and client key direction 1
+        let mut server = CbcHmacCipher::new(&keys, 0, 1);
+        let mut client = CbcHmacCipher::new(&keys, 1, 0);
+
+        let payload = b"hello openvpn".to_vec();
+        let pkt = client.encrypt(&[], &payload);
+        assert!(server.is_authentic(0, &pkt));
+        assert_eq!(server.decrypt(0, &pkt).unwrap(), payload);
+        // replayed
+        assert!(server.decrypt(0, &pkt).is_err());
+
+        let pkt = server.encrypt(&[], &payload);
+        assert_eq!(client.decrypt(0, &pkt).unwrap(), payload);
+
+        // a packet reflected back to its sender is rejected
+        let pkt = server.encrypt(&[], &payload);
+        assert!(server.decrypt(0, &pkt).is_err());
+
+        let mut pkt = client.encrypt(&[], &payload);
+        let last = pkt.len() - 1;
+        pkt[last] ^= 1;
+        assert!(!server.is_authentic(0, &pkt));
+        assert!(server.decrypt(0, &pkt).is_err());
+        assert!(!server.is_authentic(0, &[0u8; 128]));
+    }
+
+    #[test]
+    fn test_aead_roundtrip() {
+        let keys = KeyMaterial::derive("net", "ekm");
+        for algo in AeadAlgo::ALL {
+            let mut server = AeadCipher::new(algo, &keys, 1, 0);
+            let mut client = AeadCipher::new(algo, &keys, 0, 1);
+
+            let header = [(9 << 3) as u8, 0, 0, 1];
+            let payload = vec![0x45u8; 100];
+            let pkt = client.encrypt(&header, &payload);
+            assert_eq!(&pkt[..4], &header);
+            assert_eq!(server.decrypt(4, &pkt).unwrap(), payload);
+            assert!(server.decrypt(4, &pkt).is_err());
+
+            // the peer id is authenticated in P_DATA_V2
+            let mut pkt = client.encrypt(&header, &payload);
+            pkt[3] = 2;
+            assert!(server.decrypt(4, &pkt).is_err());
+
+            let pkt = server.encrypt(&[6 << 3], &payload);
+            assert_eq!(client.decrypt(1, &pkt).unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn test_negotiate_cipher() {
+        assert_eq!(
+            AeadAlgo::negotiate("AES-128-GCM:CHACHA20-POLY1305"),
+            Some(AeadAlgo::Chacha20Poly1305)
+        );
+        assert_eq!(
+            AeadAlgo::negotiate("aes-256-gcm:AES-128-GCM"),
+            Some(AeadAlgo::Aes256Gcm)
+        );
+        assert_eq!(AeadAlgo::negotiate("BF-CBC"), None);
+    }
+
+    #[test]
+    fn test_tls_identity_handshake() {
+        let identity = TlsIdentity::derive("net").unwrap();
+        assert_eq!(
+            identity.client_cert_der,
+            TlsIdentity::derive("net").unwrap().client_cert_der
+        );
+
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut roots = rustls::RootCertStore::empty();
+        roots
+            .add(CertificateDer::from(identity.ca_der.clone()))
+            .unwrap();
+        let client_config = rustls::ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_client_auth_cert(
+                vec![CertificateDer::from(identity.client_cert_der.clone())],
+                PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(identity.client_key_der.clone())),
+            )
+            .unwrap();
+
+        let mut client = rustls::ClientConnection::new(
+            Arc::new(client_config),
+            SERVER_CERT_NAME.try_into().unwrap(),
+        )
+        .unwrap();
+        let mut server = rustls::ServerConnection::new(identity.server_config().unwrap()).unwrap();
+
+        for _ in 0..10 {
+            let mut buf = Vec::new();
+            while client.wants_write() {
+                client.write_tls(&mut buf).unwrap();
+            }
+            server.read_tls(&mut buf.as_slice()).unwrap();
+            server.process_new_packets().unwrap();
+
+            let mut buf = Vec::new();
+            while server.wants_write() {
+                server.write_tls(&mut buf).unwrap();
+            }
+            client.read_tls(&mut buf.as_slice()).unwrap();
+            client.process_new_packets().unwrap();
+
+            if !client.is_handshaking() && !server.is_handshaking() {
+                break;
+            }
+        }
+        assert!(!server.is_handshaking());
+
+        let client_keys = client
+            .export_keying_material([0u8; KEY_MATERIAL_LEN], EKM_LABEL, None)
+            .unwrap();
+        let server_keys = server
+            .export_keying_material([0u8; KEY_MATERIAL_LEN], EKM_LABEL, None)
+            .unwrap();
+        assert_eq!(client_keys, server_keys);
+    }
+}
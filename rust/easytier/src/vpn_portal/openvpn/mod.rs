@@ -0,0 +1,594 @@
+// an openvpn compatible server for stock openvpn clients.
+// supports udp and tcp transports, and two auth modes:
+// 1. tls: a ca, server and client certificate derived from the network secret. clients
+//    get an address pushed from the portal cidr and negotiate an aead data cipher.
+//    keys are exported from the tls session (tls-ekm), which needs openvpn 2.6 or later.
+// 2. static-key: a shared `--secret` key derived from the network secret. clients
+//    configure their own address and the portal learns it from their traffic.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{atomic::AtomicU32, Arc, Weak},
+};
+
+use anyhow::Context;
+use bytes::{Bytes, BytesMut};
+use cidr::{Ipv4Cidr, Ipv4Inet};
+use dashmap::{mapref::entry::Entry, DashMap};
+use pnet::packet::ipv4::Ipv4Packet;
+use tokio::{
+    io::AsyncReadExt,
+    net::{TcpListener, TcpStream, UdpSocket},
+    sync::mpsc,
+    task::JoinSet,
+};
+use tracing::Level;
+
+use crate::{
+    common::{
+        config::{OpenVpnAuthMode, OpenVpnPortalConfig},
+        global_ctx::{ArcGlobalCtx, GlobalCtxEvent},
+        join_joinset_background,
+    },
+    peers::{peer_manager::PeerManager, PeerPacketFilter},
+    tunnel::packet_def::{PacketType, ZCPacket},
+};
+
+use self::{
+    crypto::{KeyMaterial, TlsIdentity},
+    session::Link,
+};
+
+use super::VpnPortal;
+
+mod crypto;
+mod packet;
+mod session;
+
+const DEFAULT_PORT: u16 = 1194;
+// udp sessions are opened before the client is authenticated in tls mode, bound their number
+const MAX_UDP_SESSIONS: usize = 1024;
+
+struct ClientEntry {
+    endpoint: String,
+    sink: mpsc::Sender<Bytes>,
+}
+
+type ClientTable = Arc<DashMap<Ipv4Addr, Arc<ClientEntry>>>;
+
+/// Removes the client from the table when its session ends, unless another
+/// session has taken over the address in the meantime.
+struct ClientRegistration {
+    global_ctx: ArcGlobalCtx,
+    portal: String,
+    clients: ClientTable,
+    ip: Ipv4Addr,
+    entry: Arc<ClientEntry>,
+}
+
+impl ClientRegistration {
+    fn new(ctx: &PortalCtx, ip: Ipv4Addr, entry: Arc<ClientEntry>) -> Self {
+        let portal = ctx.listen.to_string();
+        ctx.global_ctx
+            .issue_event(GlobalCtxEvent::VpnPortalClientConnected(
+                portal.clone(),
+                entry.endpoint.clone(),
+            ));
+        Self {
+            global_ctx: ctx.global_ctx.clone(),
+            portal,
+            clients: ctx.clients.clone(),
+            ip,
+            entry,
+        }
+    }
+}
+
+impl Drop for ClientRegistration {
+    fn drop(&mut self) {
+        self.clients
+            .remove_if(&self.ip, |_, e| Arc::ptr_eq(e, &self.entry));
+        self.global_ctx
+            .issue_event(GlobalCtxEvent::VpnPortalClientDisconnected(
+                self.portal.clone(),
+                self.entry.endpoint.clone(),
+            ));
+    }
+}
+
+enum PortalAuth {
+    StaticKey(KeyMaterial),
+    Tls {
+        identity: TlsIdentity,
+        server_config: Arc<rustls::ServerConfig>,
+    },
+}
+
+/// State shared by the listeners and all client sessions of a portal.
+struct PortalCtx {
+    global_ctx: ArcGlobalCtx,
+    peer_mgr: Arc<PeerManager>,
+    listen: url::Url,
+    client_cidr: Ipv4Cidr,
+    // the address of the portal inside the client cidr, used as route gateway
+    gateway: Ipv4Addr,
+    auth: PortalAuth,
+    clients: ClientTable,
+    next_peer_id: AtomicU32,
+}
+
+impl PortalCtx {
+    fn is_client_address(&self, ip: Ipv4Addr) -> bool {
+        self.client_cidr.contains(&ip)
+            && ip != self.client_cidr.first_address()
+            && ip != self.client_cidr.last_address()
+            && ip != self.gateway
+    }
+
+    /// Register a client with the address it configured itself, replacing any
+    /// previous client of that address.
+    fn register_client(&self, ip: Ipv4Addr, entry: Arc<ClientEntry>) -> ClientRegistration {
+        self.clients.insert(ip, entry.clone());
+        ClientRegistration::new(self, ip, entry)
+    }
+
+    /// Assign a free address, counting down from below the gateway so manually
+    /// configured wireguard clients at the start of the cidr are unlikely to collide.
+    fn allocate_client(&self, entry: Arc<ClientEntry>) -> Option<ClientRegistration> {
+        let first = u32::from(self.client_cidr.first_address());
+        let gateway = u32::from(self.gateway);
+        for ip in (first + 1..gateway).rev().map(Ipv4Addr::from) {
+            if let Entry::Vacant(v) = self.clients.entry(ip) {
+                v.insert(entry.clone());
+                return Some(ClientRegistration::new(self, ip, entry));
+            }
+        }
+        None
+    }
+
+    /// Networks reachable through the portal: the virtual network and all proxied cidrs.
+    async fn list_client_routes(&self) -> Vec<Ipv4Cidr> {
+        let routes = self.peer_mgr.list_routes().await;
+        let mut cidrs = routes
+            .iter()
+            .flat_map(|x| {
+                x.proxy_cidrs
+                    .iter()
+                    .filter_map(|c| c.parse::<Ipv4Cidr>().ok())
+            })
+            .collect::<Vec<_>>();
+        if let Some(ipv4) = routes
+            .iter()
+            .filter_map(|x| x.ipv4_addr)
+            .chain(self.global_ctx.get_ipv4().into_iter().map(Into::into))
+            .next()
+        {
+            cidrs.push(Ipv4Inet::from(ipv4).network());
+        }
+
+        let mut ret = Vec::with_capacity(cidrs.len());
+        for cidr in cidrs {
+            if !ret.contains(&cidr) {
+                ret.push(cidr);
+            }
+        }
+        ret
+    }
+
+    async fn send_to_mesh(&self, packet: &[u8], dst: Ipv4Addr) {
+        let _ = self
+            .peer_mgr
+            .send_msg_by_ip(ZCPacket::new_with_payload(packet), IpAddr::V4(dst))
+            .await;
+    }
+}
+
+fn parse_listen_addr(listen: &url::Url) -> anyhow::Result<SocketAddr> {
+    let host = listen
+        .host_str()
+        .ok_or_else(|| anyhow::anyhow!("openvpn portal url missing host: {}", listen))?;
+    format!("{}:{}", host, listen.port().unwrap_or(DEFAULT_PORT))
+        .parse()
+        .with_context(|| format!("invalid openvpn portal listen address: {}", listen))
+}
+
+struct OpenVpnImpl {
+    ctx: Arc<PortalCtx>,
+    auth_mode: OpenVpnAuthMode,
+    tasks: Arc<std::sync::Mutex<JoinSet<()>>>,
+}
+
+impl OpenVpnImpl {
+    fn new(
+        global_ctx: ArcGlobalCtx,
+        peer_mgr: Arc<PeerManager>,
+        cfg: OpenVpnPortalConfig,
+        client_cidr: Ipv4Cidr,
+    ) -> anyhow::Result<Self> {
+        if client_cidr.network_length() > 30 {
+            anyhow::bail!(
+                "vpn portal client cidr {} is too small for openvpn clients",
+                client_cidr
+            );
+        }
+        let gateway = Ipv4Addr::from(u32::from(client_cidr.last_address()) - 1);
+
+        let nid = global_ctx.get_network_identity();
+        let key_seed = format!(
+            "{}{}",
+            nid.network_name,
+            nid.network_secret.as_ref().unwrap_or(&"".to_string())
+        );
+        let auth = match cfg.auth {
+            OpenVpnAuthMode::StaticKey => {
+                PortalAuth::StaticKey(KeyMaterial::derive(&key_seed, "easytier-openvpn-static"))
+            }
+            OpenVpnAuthMode::Tls => {
+                let identity = TlsIdentity::derive(&key_seed)?;
+                let server_config = identity.server_config()?;
+                PortalAuth::Tls {
+                    identity,
+                    server_config,
+                }
+            }
+        };
+
+        Ok(Self {
+            ctx: Arc::new(PortalCtx {
+                global_ctx,
+                peer_mgr,
+                listen: cfg.listen,
+                client_cidr,
+                gateway,
+                auth,
+                clients: Arc::new(DashMap::new()),
+                next_peer_id: AtomicU32::new(0),
+            }),
+            auth_mode: cfg.auth,
+            tasks: Arc::new(std::sync::Mutex::new(JoinSet::new())),
+        })
+    }
+
+    async fn run_udp_listener(
+        ctx: Arc<PortalCtx>,
+        socket: Arc<UdpSocket>,
+        tasks: Weak<std::sync::Mutex<JoinSet<()>>>,
+    ) {
+        let mut sessions: HashMap<SocketAddr, mpsc::Sender<BytesMut>> = HashMap::new();
+        let mut buf = vec![0u8; 65536];
+        loop {
+            let (len, remote) = match socket.recv_from(&mut buf).await {
+                Ok(ret) => ret,
+                Err(err) => {
+                    tracing::error!(?err, "openvpn portal udp recv failed");
+                    break;
+                }
+            };
+            let packet = BytesMut::from(&buf[..len]);
+
+            if let Some(tx) = sessions.get(&remote).filter(|tx| !tx.is_closed()) {
+                if let Err(err) = tx.try_send(packet) {
+                    tracing::trace!(?err, ?remote, "openvpn session queue full, dropping");
+                }
+                continue;
+            }
+
+            if !session::accepts_new_session(&ctx, &packet) {
+                continue;
+            }
+            let Some(tasks) = tasks.upgrade() else {
+                break;
+            };
+            sessions.retain(|_, tx| !tx.is_closed());
+            if sessions.len() >= MAX_UDP_SESSIONS {
+                tracing::debug!(?remote, "too many openvpn udp sessions, dropping");
+                continue;
+            }
+
+            let (tx, rx) = mpsc::channel(256);
+            let _ = tx.try_send(packet);
+            sessions.insert(remote, tx);
+            let link = Link::Udp {
+                socket: socket.clone(),
+                remote,
+            };
+            tasks.lock().unwrap().spawn(session::run_session(
+                ctx.clone(),
+                link,
+                format!("udp://{}", remote),
+                rx,
+            ));
+        }
+    }
+
+    async fn handle_tcp_conn(ctx: Arc<PortalCtx>, stream: TcpStream, remote: SocketAddr) {
+        let _ = stream.set_nodelay(true);
+        let (mut reader, writer) = stream.into_split();
+        let (tx, rx) = mpsc::channel(256);
+
+        // each packet is prefixed with its length on stream transports
+        let read_loop = async move {
+            loop {
+                let len = reader.read_u16().await? as usize;
+                let mut buf = BytesMut::zeroed(len);
+                reader.read_exact(&mut buf).await?;
+                if tx.send(buf).await.is_err() {
+                    break;
+                }
+            }
+            Ok::<_, std::io::Error>(())
+        };
+
+        tokio::select! {
+            ret = read_loop => {
+                tracing::debug!(?ret, ?remote, "openvpn tcp client read loop exited");
+            }
+            _ = session::run_session(ctx, Link::Tcp(writer), format!("tcp://{}", remote), rx) => {}
+        }
+    }
+
+    async fn run_tcp_listener(
+        ctx: Arc<PortalCtx>,
+        listener: TcpListener,
+        tasks: Weak<std::sync::Mutex<JoinSet<()>>>,
+    ) {
+        loop {
+            let (stream, remote) = match listener.accept().await {
+                Ok(ret) => ret,
+                Err(err) => {
+                    tracing::error!(?err, "openvpn portal tcp accept failed");
+                    continue;
+                }
+            };
+            let Some(tasks) = tasks.upgrade() else {
+                break;
+            };
+            tasks
+                .lock()
+                .unwrap()
+                .spawn(Self::handle_tcp_conn(ctx.clone(), stream, remote));
+        }
+    }
+
+    async fn start_listener(&self) -> anyhow::Result<()> {
+        let listen = &self.ctx.listen;
+        let addr = parse_listen_addr(listen)?;
+        let tasks = Arc::downgrade(&self.tasks);
+
+        match listen.scheme() {
+            "udp" => {
+                let socket = {
+                    let _g = self.ctx.global_ctx.net_ns.guard();
+                    UdpSocket::bind(addr)
+                        .await
+                        .with_context(|| format!("failed to bind openvpn portal on {}", listen))?
+                };
+                self.tasks.lock().unwrap().spawn(Self::run_udp_listener(
+                    self.ctx.clone(),
+                    Arc::new(socket),
+                    tasks,
+                ));
+            }
+            "tcp" => {
+                let listener = {
+                    let _g = self.ctx.global_ctx.net_ns.guard();
+                    TcpListener::bind(addr)
+                        .await
+                        .with_context(|| format!("failed to bind openvpn portal on {}", listen))?
+                };
+                self.tasks.lock().unwrap().spawn(Self::run_tcp_listener(
+                    self.ctx.clone(),
+                    listener,
+                    tasks,
+                ));
+            }
+            scheme => anyhow::bail!("unsupported openvpn portal protocol: {}", scheme),
+        }
+
+        Ok(())
+    }
+
+    async fn start_pipeline_processor(&self) {
+        struct PeerPacketFilterForOpenVpnPortal {
+            clients: ClientTable,
+        }
+
+        #[async_trait::async_trait]
+        impl PeerPacketFilter for PeerPacketFilterForOpenVpnPortal {
+            async fn try_process_packet_from_peer(&self, packet: ZCPacket) -> Option<ZCPacket> {
+                let hdr = packet.peer_manager_header().unwrap();
+                if hdr.packet_type != PacketType::Data as u8 {
+                    return Some(packet);
+                };
+
+                let ipv4 = Ipv4Packet::new(packet.payload())?;
+                if ipv4.get_version() != 4 {
+                    return Some(packet);
+                }
+
+                let Some(entry) = self.clients.get(&ipv4.get_destination()).map(|f| f.clone())
+                else {
+                    return Some(packet);
+                };
+
+                let payload_offset = packet.packet_type().get_packet_offsets().payload_offset;
+                let payload = packet.inner().split_off(payload_offset).freeze();
+                if let Err(e) = entry.sink.try_send(payload) {
+                    tracing::debug!(?e, "Failed to send packet to openvpn client");
+                }
+
+                None
+            }
+        }
+
+        self.ctx
+            .peer_mgr
+            .add_packet_process_pipeline(Box::new(PeerPacketFilterForOpenVpnPortal {
+                clients: self.ctx.clients.clone(),
+            }))
+            .await;
+    }
+
+    #[tracing::instrument(skip(self), err(level = Level::WARN))]
+    async fn start(&self) -> anyhow::Result<()> {
+        tracing::info!("OpenVPN VPN Portal Starting");
+
+        self.start_listener().await?;
+        join_joinset_background(self.tasks.clone(), "openvpn".to_string());
+        self.start_pipeline_processor().await;
+
+        self.ctx
+            .global_ctx
+            .issue_event(GlobalCtxEvent::VpnPortalStarted(
+                self.ctx.listen.to_string(),
+            ));
+
+        Ok(())
+    }
+
+    async fn dump_client_config(&self) -> String {
+        let ctx = &self.ctx;
+        let (proto, port) = match parse_listen_addr(&ctx.listen) {
+            Ok(addr) if ctx.listen.scheme() == "tcp" => ("tcp-client", addr.port()),
+            Ok(addr) => ("udp", addr.port()),
+            Err(e) => return format!("ERROR: {}", e),
+        };
+        let host = ctx.listen.host_str().unwrap_or_default();
+
+        let mut cfg = String::from("# EasyTier OpenVPN portal client config\n");
+        match &ctx.auth {
+            PortalAuth::Tls { identity, .. } => {
+                cfg.push_str(&format!(
+                    r#"client
+dev tun
+proto {proto}
+remote {host} {port} # should be the public ip(or domain) of the vpn server
+nobind
+remote-cert-tls server
+data-ciphers AES-256-GCM:CHACHA20-POLY1305:AES-128-GCM
+# the portal does not renegotiate keys
+reneg-sec 0
+<ca>
+{ca}</ca>
+<cert>
+{cert}</cert>
+<key>
+{key}</key>
+"#,
+                    ca = identity.ca_pem(),
+                    cert = identity.client_cert_pem(),
+                    key = identity.client_key_pem(),
+                ));
+            }
+            PortalAuth::StaticKey(keys) => {
+                let mask = ctx.client_cidr.mask();
+                let address = Ipv4Addr::from(u32::from(ctx.gateway) - 1);
+                cfg.push_str(&format!(
+                    r#"dev tun
+proto {proto}
+remote {host} {port} # should be the public ip(or domain) of the vpn server
+nobind
+topology subnet
+ifconfig {address} {mask} # should assign a different ip from {cidr} to each client manually
+route-gateway {gateway}
+"#,
+                    cidr = ctx.client_cidr,
+                    gateway = ctx.gateway,
+                ));
+                for route in ctx.list_client_routes().await {
+                    cfg.push_str(&format!(
+                        "route {} {}\n",
+                        route.first_address(),
+                        route.mask()
+                    ));
+                }
+                cfg.push_str(&format!(
+                    r#"cipher AES-256-CBC
+auth SHA256
+keepalive 10 60
+# openvpn 2.7 and later refuse static keys without this option
+setenv opt allow-deprecated-insecure-static-crypto
+key-direction 1
+<secret>
+{secret}</secret>
+"#,
+                    secret = keys.to_static_key_file(),
+                ));
+            }
+        }
+        cfg
+    }
+}
+
+#[derive(Default)]
+pub struct OpenVpn {
+    inner: Option<OpenVpnImpl>,
+}
+
+#[async_trait::async_trait]
+impl VpnPortal for OpenVpn {
+    async fn start(
+        &mut self,
+        global_ctx: ArcGlobalCtx,
+        peer_mgr: Arc<PeerManager>,
+    ) -> anyhow::Result<()> {
+        assert!(self.inner.is_none());
+
+        let Some(vpn_cfg) = global_ctx.config.get_vpn_portal_config() else {
+            anyhow::bail!("vpn cfg is not set for openvpn vpn portal");
+        };
+        let Some(openvpn_cfg) = vpn_cfg.openvpn else {
+            anyhow::bail!("openvpn is not enabled in vpn portal config");
+        };
+
+        let inner = OpenVpnImpl::new(global_ctx, peer_mgr, openvpn_cfg, vpn_cfg.client_cidr)?;
+        inner.start().await?;
+        self.inner = Some(inner);
+        Ok(())
+    }
+
+    async fn dump_client_config(&self, _peer_mgr: Arc<PeerManager>) -> String {
+        match &self.inner {
+            Some(inner) => inner.dump_client_config().await,
+            None => "ERROR: OpenVPN VPN Portal Not Started".to_string(),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self.inner.as_ref().map(|i| i.auth_mode) {
+            Some(OpenVpnAuthMode::StaticKey) => "openvpn (static-key)".to_string(),
+            _ => "openvpn".to_string(),
+        }
+    }
+
+    async fn list_clients(&self) -> Vec<String> {
+        self.inner
+            .as_ref()
+            .map(|o| {
+                o.ctx
+                    .clients
+                    .iter()
+                    .map(|x| x.value().endpoint.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listen_addr() {
+        assert_eq!(
+            parse_listen_addr(&"udp://0.0.0.0".parse().unwrap()).unwrap(),
+            "0.0.0.0:1194".parse().unwrap()
+        );
+        assert_eq!(
+            parse_listen_addr(&"tcp://[::]:443".parse().unwrap()).unwrap(),
+            "[::]:443".parse().unwrap()
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use bytes::{Buf, BufMut};
+
+pub(crate) const P_CONTROL_SOFT_RESET_V1: u8 = 3;
+pub(crate) const P_CONTROL_V1: u8 = 4;
+pub(crate) const P_ACK_V1: u8 = 5;
+pub(crate) const P_DATA_V1: u8 = 6;
+pub(crate) const P_CONTROL_HARD_RESET_CLIENT_V2: u8 = 7;
+pub(crate) const P_CONTROL_HARD_RESET_SERVER_V2: u8 = 8;
+pub(crate) const P_DATA_V2: u8 = 9;
+
+pub(crate) const KEY_METHOD_2: u8 = 2;
+const KEY_METHOD_MASK: u8 = 0x0f;
+const KEY_SOURCE_CLIENT_LEN: usize = 48 + 32 + 32;
+pub(crate) const KEY_SOURCE_SERVER_LEN: usize = 32 + 32;
+
+// the payload of data channel keepalive packets
+pub(crate) const PING_STRING: [u8; 16] = [
+    0x2a, 0x18, 0x7b, 0xf3, 0x64, 0x1e, 0xb4, 0xcb, 0x07, 0xed, 0x2d, 0x0a, 0x98, 0x1f, 0xc7, 0x48,
+];
+// the prefix of options consistency check messages on the data channel
+pub(crate) const OCC_STRING: [u8; 16] = [
+    0x28, 0x7f, 0x34, 0x6b, 0xd4, 0xef, 0x7a, 0x81, 0x2d, 0x56, 0xb8, 0xd3, 0xaf, 0xc5, 0x45, 0x9c,
+];
+pub(crate) const OCC_EXIT: u8 = 6;
+
+// client capabilities announced in IV_PROTO of the peer info
+pub(crate) const IV_PROTO_DATA_V2: u32 = 1 << 1;
+pub(crate) const IV_PROTO_TLS_KEY_EXPORT: u32 = 1 << 3;
+
+pub(crate) fn opcode(first_byte: u8) -> u8 {
+    first_byte >> 3
+}
+
+pub(crate) fn key_id(first_byte: u8) -> u8 {
+    first_byte & 0x07
+}
+
+pub(crate) fn op_byte(opcode: u8, key_id: u8) -> u8 {
+    (opcode << 3) | (key_id & 0x07)
+}
+
+/// A packet of the reliable control channel, without tls-auth or tls-crypt wrapping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ControlPacket {
+    pub opcode: u8,
+    pub key_id: u8,
+    pub session_id: u64,
+    pub acks: Vec<u32>,
+    pub remote_session_id: Option<u64>,
+    // none for P_ACK_V1
+    pub packet_id: Option<u32>,
+    pub payload: Vec<u8>,
+}
+
+impl ControlPacket {
+    pub fn parse(mut buf: &[u8]) -> Option<Self> {
+        if buf.remaining() < 1 + 8 + 1 {
+            return None;
+        }
+        let first = buf.get_u8();
+        let opcode = opcode(first);
+        let session_id = buf.get_u64();
+
+        let ack_len = buf.get_u8() as usize;
+        if buf.remaining() < ack_len * 4 {
+            return None;
+        }
+        let acks = (0..ack_len).map(|_| buf.get_u32()).collect::<Vec<_>>();
+        let remote_session_id = if ack_len > 0 {
+            if buf.remaining() < 8 {
+                return None;
+            }
+            Some(buf.get_u64())
+        } else {
+            None
+        };
+
+        let packet_id = if opcode == P_ACK_V1 {
+            None
+        } else {
+            if buf.remaining() < 4 {
+                return None;
+            }
+            Some(buf.get_u32())
+        };
+
+        Some(Self {
+            opcode,
+            key_id: key_id(first),
+            session_id,
+            acks,
+            remote_session_id,
+            packet_id,
+            payload: buf.to_vec(),
+        })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf =
+            Vec::with_capacity(1 + 8 + 1 + self.acks.len() * 4 + 8 + 4 + self.payload.len());
+        buf.put_u8(op_byte(self.opcode, self.key_id));
+        buf.put_u64(self.session_id);
+        buf.put_u8(self.acks.len() as u8);
+        for ack in &self.acks {
+            buf.put_u32(*ack);
+        }
+        if !self.acks.is_empty() {
+            buf.put_u64(self.remote_session_id.unwrap_or_default());
+        }
+        if let Some(packet_id) = self.packet_id {
+            buf.put_u32(packet_id);
+        }
+        buf.put_slice(&self.payload);
+        buf
+    }
+}
+
+/// The key method 2 message a client sends once the tls handshake is done.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct ClientKeyMethod {
+    pub options: String,
+    pub username: String,
+    pub password: String,
+    pub peer_info: String,
+}
+
+impl ClientKeyMethod {
+    pub fn parse(mut buf: &[u8]) -> anyhow::Result<Self> {
+        if buf.remaining() < 4 + 1 + KEY_SOURCE_CLIENT_LEN {
+            anyhow::bail!("key method message too short");
+        }
+        if buf.get_u32() != 0 {
+            anyhow::bail!("key method message must start with zero");
+        }
+        let method = buf.get_u8() & KEY_METHOD_MASK;
+        if method != KEY_METHOD_2 {
+            anyhow::bail!("unsupported key method: {}", method);
+        }
+        // the key source is only used by the legacy prf, keys are exported from tls instead
+        buf.advance(KEY_SOURCE_CLIENT_LEN);
+
+        let mut ret = ClientKeyMethod {
+            options: read_string(&mut buf)?,
+            ..Default::default()
+        };
+        if buf.has_remaining() {
+            ret.username = read_string(&mut buf)?;
+        }
+        if buf.has_remaining() {
+            ret.password = read_string(&mut buf)?;
+        }
+        if buf.has_remaining() {
+            ret.peer_info = read_string(&mut buf)?;
+        }
+        Ok(ret)
+    }
+
+    /// Look up a `KEY=VALUE` line of the peer info.
+    pub fn peer_info_value(&self, key: &str) -> Option<&str> {
+        self.peer_info.lines().find_map(|line| {
+            line.split_once('=')
+                .filter(|(k, _)| *k == key)
+                .map(|(_, v)| v.trim())
+        })
+    }
+
+    pub fn iv_proto(&self) -> u32 {
+        self.peer_info_value("IV_PROTO")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+pub(crate) fn encode_server_key_method(
+    options: &str,
+    random: &[u8; KEY_SOURCE_SERVER_LEN],
+) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(4 + 1 + KEY_SOURCE_SERVER_LEN + 2 + options.len() + 1);
+    buf.put_u32(0);
+    buf.put_u8(KEY_METHOD_2);
+    buf.put_slice(random);
+    write_string(&mut buf, options);
+    buf
+}
+
+fn read_string(buf: &mut &[u8]) -> anyhow::Result<String> {
+    if buf.remaining() < 2 {
+        anyhow::bail!("string length missing");
+    }
+    let len = buf.get_u16() as usize;
+    if buf.remaining() < len {
+        anyhow::bail!("string truncated");
+    }
+    let s = &buf[..len];
+    buf.advance(len);
+    let s = s.strip_suffix(&[0]).unwrap_or(s);
+    Ok(String::from_utf8_lossy(s).into_owned())
+}
+
+fn write_string(buf: &mut Vec<u8>, s: &str) {
+    buf.put_u16(s.len() as u16 + 1);
+    buf.put_slice(s.as_bytes());
+    buf.put_u8(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control_packet_roundtrip() {
+        let pkt = ControlPacket {
+            opcode: P_CONTROL_V1,
+            key_id: 0,
+            session_id: 0x1122334455667788,
+            acks: vec![1, 2],
+            remote_session_id: Some(0x99),
+            packet_id: Some(3),
+            payload: b"tls".to_vec(),
+        };
+        let buf = pkt.encode();
+        assert_eq!(buf[0], P_CONTROL_V1 << 3);
+        assert_eq!(ControlPacket::parse(&buf).unwrap(), pkt);
+
+        let ack = ControlPacket {
+            opcode: P_ACK_V1,
+            key_id: 1,
+            session_id: 1,
+            acks: vec![7],
+            remote_session_id: Some(2),
+            packet_id: None,
+            payload: vec![],
+        };
+        assert_eq!(ControlPacket::parse(&ack.encode()).unwrap(), ack);
+
+        let reset = ControlPacket {
+            opcode: P_CONTROL_HARD_RESET_CLIENT_V2,
+            key_id: 0,
+            session_id: 5,
+            acks: vec![],
+            remote_session_id: None,
+            packet_id: Some(0),
+            payload: vec![],
+        };
+        let buf = reset.encode();
+        assert_eq!(buf.len(), 1 + 8 + 1 + 4);
+        assert_eq!(ControlPacket::parse(&buf).unwrap(), reset);
+
+        assert!(ControlPacket::parse(&buf[..buf.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn test_parse_client_key_method() {
+        let mut buf = Vec::new();
+        buf.put_u32(0);
+        buf.put_u8(KEY_METHOD_2);
+        buf.put_slice(&[0u8; KEY_SOURCE_CLIENT_LEN]);
+        write_string(&mut buf, "V4,dev-type tun");
+        buf.put_u16(0);
+        buf.put_u16(0);
+        write_string(
+            &mut buf,
+            "IV_VER=2.6.12\nIV_PROTO=990\nIV_CIPHERS=AES-256-GCM\n",
+        );
+
+        let km = ClientKeyMethod::parse(&buf).unwrap();
+        assert_eq!(km.options, "V4,dev-type tun");
+        assert_eq!(km.username, "");
+        assert_eq!(km.peer_info_value("IV_VER"), Some("2.6.12"));
+        assert_eq!(km.peer_info_value("IV_CIPHERS"), Some("AES-256-GCM"));
+        assert_eq!(km.iv_proto(), 990);
+        assert_ne!(km.iv_proto() & IV_PROTO_TLS_KEY_EXPORT, 0);
+
+        buf[4] = 1;
+        assert!(ClientKeyMethod::parse(&buf).is_err());
+        assert!(ClientKeyMethod::parse(&buf[..20]).is_err());
+    }
+
+    #[test]
+    fn test_server_key_method_layout() {
+        let buf = encode_server_key_method("V4", &[1u8; KEY_SOURCE_SERVER_LEN]);
+        assert_eq!(&buf[..5], &[0, 0, 0, 0, KEY_METHOD_2]);
+        assert_eq!(&buf[5 + KEY_SOURCE_SERVER_LEN..], &[0, 3, b'V', b'4', 0]);
+    }
+}
@@ -0,0 +1,808 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    io::{Read, Write},
+    net::{Ipv4Addr, SocketAddr},
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
+
+use bytes::{Bytes, BytesMut};
+use pnet::packet::{ipv4::Ipv4Packet, Packet};
+use rand::RngCore;
+use tokio::{
+    io::AsyncWriteExt,
+    net::{tcp::OwnedWriteHalf, UdpSocket},
+    sync::mpsc,
+    time::Instant,
+};
+
+use super::{
+    crypto::{
+        AeadAlgo, AeadCipher, CbcHmacCipher, DataCipher, KeyMaterial, EKM_LABEL, KEY_MATERIAL_LEN,
+    },
+    packet::{
+        encode_server_key_method, op_byte, opcode, ClientKeyMethod, ControlPacket,
+        IV_PROTO_DATA_V2, IV_PROTO_TLS_KEY_EXPORT, KEY_SOURCE_SERVER_LEN, OCC_EXIT, OCC_STRING,
+        PING_STRING, P_ACK_V1, P_CONTROL_HARD_RESET_CLIENT_V2, P_CONTROL_HARD_RESET_SERVER_V2,
+        P_CONTROL_SOFT_RESET_V1, P_CONTROL_V1, P_DATA_V1, P_DATA_V2,
+    },
+    ClientEntry, ClientRegistration, PortalAuth, PortalCtx,
+};
+
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+const IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(60);
+const RETRANSMIT_INTERVAL: Duration = Duration::from_secs(2);
+
+// reliable layer limits, well below the windows of openvpn clients
+const SEND_WINDOW: usize = 4;
+const RECV_WINDOW: u32 = 8;
+const MAX_ACKS_PER_PACKET: usize = 4;
+const CONTROL_PAYLOAD_MAX: usize = 1100;
+const PUSH_REPLY_MAX: usize = 1000;
+
+pub(super) enum Link {
+    Udp {
+        socket: Arc<UdpSocket>,
+        remote: SocketAddr,
+    },
+    Tcp(OwnedWriteHalf),
+}
+
+impl Link {
+    async fn send(&mut self, packet: &[u8]) -> std::io::Result<()> {
+        match self {
+            Link::Udp { socket, remote } => socket.send_to(packet, *remote).await.map(|_| ()),
+            Link::Tcp(writer) => {
+                let mut frame = Vec::with_capacity(2 + packet.len());
+                frame.extend_from_slice(&(packet.len() as u16).to_be_bytes());
+                frame.extend_from_slice(packet);
+                writer.write_all(&frame).await
+            }
+        }
+    }
+
+    fn is_udp(&self) -> bool {
+        matches!(self, Link::Udp { .. })
+    }
+
+    // the proto name used in the options consistency string
+    fn occ_proto(&self) -> &'static str {
+        match self {
+            Link::Udp { .. } => "UDPv4",
+            Link::Tcp(_) => "TCPv4_SERVER",
+        }
+    }
+}
+
+/// Whether a packet from an unknown udp address may open a new session.
+pub(super) fn accepts_new_session(ctx: &PortalCtx, packet: &[u8]) -> bool {
+    match &ctx.auth {
+        // check the hmac before a session is spawned for a spoofed source
+        PortalAuth::StaticKey(keys) => CbcHmacCipher::new(keys, 0, 1).is_authentic(0, packet),
+        PortalAuth::Tls { .. } => packet
+            .first()
+            .is_some_and(|b| opcode(*b) == P_CONTROL_HARD_RESET_CLIENT_V2),
+    }
+}
+
+pub(super) async fn run_session(
+    ctx: Arc<PortalCtx>,
+    link: Link,
+    endpoint: String,
+    incoming: mpsc::Receiver<BytesMut>,
+) {
+    let ret = match &ctx.auth {
+        PortalAuth::StaticKey(keys) => {
+            let cipher = CbcHmacCipher::new(keys, 0, 1);
+            StaticKeySession::new(ctx.clone(), link, endpoint.clone(), cipher)
+                .run(incoming)
+                .await
+        }
+        PortalAuth::Tls { server_config, .. } => {
+            TlsSession::run(
+                ctx.clone(),
+                link,
+                endpoint.clone(),
+                server_config.clone(),
+                incoming,
+            )
+            .await
+        }
+    };
+    tracing::info!(?ret, %endpoint, "openvpn client session ended");
+}
+
+enum DataPayload<'a> {
+    Ip(Ipv4Packet<'a>),
+    Keepalive,
+    Exit,
+    Unknown,
+}
+
+fn classify_payload(payload: &[u8]) -> DataPayload<'_> {
+    if payload == PING_STRING {
+        return DataPayload::Keepalive;
+    }
+    if let Some(occ) = payload.strip_prefix(&OCC_STRING) {
+        if occ.first() == Some(&OCC_EXIT) {
+            return DataPayload::Exit;
+        }
+        return DataPayload::Keepalive;
+    }
+    match Ipv4Packet::new(payload) {
+        Some(ipv4) if ipv4.get_version() == 4 => DataPayload::Ip(ipv4),
+        _ => DataPayload::Unknown,
+    }
+}
+
+async fn forward_to_mesh(ctx: &PortalCtx, client_ip: Ipv4Addr, ipv4: &Ipv4Packet<'_>) {
+    if ipv4.get_source() != client_ip {
+        tracing::trace!(src = ?ipv4.get_source(), ?client_ip, "dropping spoofed packet from openvpn client");
+        return;
+    }
+    ctx.send_to_mesh(ipv4.packet(), ipv4.get_destination())
+        .await;
+}
+
+struct StaticKeySession {
+    ctx: Arc<PortalCtx>,
+    link: Link,
+    endpoint: String,
+    cipher: CbcHmacCipher,
+    authenticated: bool,
+    registration: Option<ClientRegistration>,
+}
+
+impl StaticKeySession {
+    fn new(ctx: Arc<PortalCtx>, link: Link, endpoint: String, cipher: CbcHmacCipher) -> Self {
+        Self {
+            ctx,
+            link,
+            endpoint,
+            cipher,
+            authenticated: false,
+            registration: None,
+        }
+    }
+
+    async fn run(mut self, mut incoming: mpsc::Receiver<BytesMut>) -> anyhow::Result<()> {
+        let (sink, mut from_mesh) = mpsc::channel::<Bytes>(128);
+        let mut last_recv = Instant::now();
+        let mut ticker = tokio::time::interval(PING_INTERVAL);
+
+        loop {
+            tokio::select! {
+                packet = incoming.recv() => {
+                    let Some(packet) = packet else {
+                        return Ok(());
+                    };
+                    let payload = match self.cipher.decrypt(0, &packet) {
+                        Ok(payload) => payload,
+                        Err(e) if !self.authenticated => {
+                            return Err(e.context("static key client failed to authenticate"));
+                        }
+                        Err(e) => {
+                            tracing::debug!(?e, "dropping invalid openvpn data packet");
+                            continue;
+                        }
+                    };
+                    self.authenticated = true;
+                    last_recv = Instant::now();
+
+                    match classify_payload(&payload) {
+                        DataPayload::Ip(ipv4) => {
+                            // static key clients configure their address themselves
+                            if self.registration.is_none() {
+                                let src = ipv4.get_source();
+                                if !self.ctx.is_client_address(src) {
+                                    tracing::debug!(?src, "openvpn client address outside of portal cidr");
+                                    continue;
+                                }
+                                let entry = Arc::new(ClientEntry {
+                                    endpoint: self.endpoint.clone(),
+                                    sink: sink.clone(),
+                                });
+                                self.registration = Some(self.ctx.register_client(src, entry));
+                            }
+                            let client_ip = self.registration.as_ref().unwrap().ip;
+                            forward_to_mesh(&self.ctx, client_ip, &ipv4).await;
+                        }
+                        DataPayload::Exit => return Ok(()),
+                        DataPayload::Keepalive | DataPayload::Unknown => {}
+                    }
+                }
+                packet = from_mesh.recv() => {
+                    let Some(packet) = packet else {
+                        continue;
+                    };
+                    let out = self.cipher.encrypt(&[], &packet);
+                    self.link.send(&out).await?;
+                }
+                _ = ticker.tick() => {
+                    if last_recv.elapsed() > IDLE_TIMEOUT {
+                        anyhow::bail!("openvpn client timed out");
+                    }
+                    if self.authenticated {
+                        let out = self.cipher.encrypt(&[], &PING_STRING);
+                        self.link.send(&out).await?;
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct InFlight {
+    opcode: u8,
+    payload: Vec<u8>,
+    last_sent: Instant,
+}
+
+/// A tls mode client: the reliable control channel carrying the tls session,
+/// the key method exchange and push messages, and the aead data channel.
+struct TlsSession {
+    ctx: Arc<PortalCtx>,
+    link: Link,
+    endpoint: String,
+    tls: rustls::ServerConnection,
+
+    key_id: u8,
+    local_session_id: u64,
+    remote_session_id: u64,
+
+    send_next_id: u32,
+    send_queue: VecDeque<(u8, Vec<u8>)>,
+    in_flight: BTreeMap<u32, InFlight>,
+    recv_next_id: u32,
+    recv_buffer: BTreeMap<u32, Vec<u8>>,
+    pending_acks: VecDeque<u32>,
+
+    plaintext: Vec<u8>,
+    key_method: Option<ClientKeyMethod>,
+    push_reply: Option<Vec<String>>,
+    auth_failed: bool,
+
+    cipher: Option<AeadCipher>,
+    data_header: Vec<u8>,
+    sink: mpsc::Sender<Bytes>,
+    registration: Option<ClientRegistration>,
+
+    started: Instant,
+    last_recv: Instant,
+    last_ping: Instant,
+}
+
+impl TlsSession {
+    async fn run(
+        ctx: Arc<PortalCtx>,
+        link: Link,
+        endpoint: String,
+        server_config: Arc<rustls::ServerConfig>,
+        mut incoming: mpsc::Receiver<BytesMut>,
+    ) -> anyhow::Result<()> {
+        let Some(first) = incoming.recv().await else {
+            return Ok(());
+        };
+        let (reset, reset_id) = parse_hard_reset(&first)?;
+
+        let (sink, mut from_mesh) = mpsc::channel(128);
+        let now = Instant::now();
+        let mut session = TlsSession {
+            ctx,
+            link,
+            endpoint,
+            tls: rustls::ServerConnection::new(server_config)?,
+            key_id: reset.key_id,
+            local_session_id: rand::random(),
+            remote_session_id: reset.session_id,
+            send_next_id: 0,
+            send_queue: VecDeque::from([(P_CONTROL_HARD_RESET_SERVER_V2, vec![])]),
+            in_flight: BTreeMap::new(),
+            recv_next_id: next_packet_id(reset_id)?,
+            recv_buffer: BTreeMap::new(),
+            pending_acks: VecDeque::from([reset_id]),
+            plaintext: Vec::new(),
+            key_method: None,
+            push_reply: None,
+            auth_failed: false,
+            cipher: None,
+            data_header: vec![],
+            sink,
+            registration: None,
+            started: now,
+            last_recv: now,
+            last_ping: now,
+        };
+        session.flush_control().await?;
+
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            tokio::select! {
+                packet = incoming.recv() => {
+                    let Some(packet) = packet else {
+                        return Ok(());
+                    };
+                    session.handle_packet(&packet).await?;
+                }
+                packet = from_mesh.recv() => {
+                    let Some(packet) = packet else {
+                        continue;
+                    };
+                    session.send_data(&packet).await?;
+                }
+                _ = ticker.tick() => {
+                    session.on_tick().await?;
+                }
+            }
+        }
+    }
+
+    async fn handle_packet(&mut self, packet: &[u8]) -> anyhow::Result<()> {
+        let Some(first) = packet.first() else {
+            return Ok(());
+        };
+        match opcode(*first) {
+            P_DATA_V1 | P_DATA_V2 => self.handle_data(packet).await,
+            P_CONTROL_HARD_RESET_CLIENT_V2 | P_CONTROL_V1 | P_ACK_V1 => {
+                self.handle_control(packet).await
+            }
+            P_CONTROL_SOFT_RESET_V1 => {
+                // the client reconnects with a hard reset after its ping-restart
+                anyhow::bail!("openvpn client requested key renegotiation, which is not supported")
+            }
+            op => {
+                tracing::debug!(op, "ignoring unsupported openvpn packet");
+                Ok(())
+            }
+        }
+    }
+
+    async fn handle_control(&mut self, packet: &[u8]) -> anyhow::Result<()> {
+        let Some(pkt) = ControlPacket::parse(packet) else {
+            tracing::debug!("dropping malformed openvpn control packet");
+            return Ok(());
+        };
+        if pkt.session_id != self.remote_session_id {
+            if pkt.opcode == P_CONTROL_HARD_RESET_CLIENT_V2 {
+                anyhow::bail!("openvpn client restarted its session");
+            }
+            return Ok(());
+        }
+        self.last_recv = Instant::now();
+
+        if pkt.remote_session_id == Some(self.local_session_id) {
+            for ack in &pkt.acks {
+                self.in_flight.remove(ack);
+            }
+        }
+
+        if let Some(id) = pkt.packet_id {
+            if !self.pending_acks.contains(&id) {
+                self.pending_acks.push_back(id);
+            }
+            if pkt.opcode == P_CONTROL_V1
+                && id >= self.recv_next_id
+                && id - self.recv_next_id < RECV_WINDOW
+            {
+                self.recv_buffer.insert(id, pkt.payload);
+            }
+        }
+
+        let mut tls_input = Vec::new();
+        while let Some(payload) = self.recv_buffer.remove(&self.recv_next_id) {
+            self.recv_next_id = next_packet_id(self.recv_next_id)?;
+            tls_input.extend_from_slice(&payload);
+        }
+
+        let ret = if tls_input.is_empty() {
+            Ok(())
+        } else {
+            self.feed_tls(&tls_input).await
+        };
+        // flush even on errors so the client receives our tls alert
+        self.flush_tls();
+        self.flush_control().await?;
+        ret
+    }
+
+    async fn feed_tls(&mut self, mut input: &[u8]) -> anyhow::Result<()> {
+        while !input.is_empty() {
+            if self.tls.read_tls(&mut input)? == 0 {
+                break;
+            }
+            self.tls.process_new_packets()?;
+        }
+
+        let mut buf = [0u8; 4096];
+        loop {
+            match self.tls.reader().read(&mut buf) {
+                Ok(0) => anyhow::bail!("openvpn client closed the tls session"),
+                Ok(n) => self.plaintext.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        self.handle_plaintext().await
+    }
+
+    async fn handle_plaintext(&mut self) -> anyhow::Result<()> {
+        if self.plaintext.is_empty() || self.tls.is_handshaking() {
+            return Ok(());
+        }
+
+        if self.key_method.is_none() {
+            let key_method = ClientKeyMethod::parse(&self.plaintext)?;
+            self.plaintext.clear();
+            tracing::info!(
+                endpoint = %self.endpoint,
+                peer_info = ?key_method.peer_info,
+                "openvpn client finished tls handshake"
+            );
+
+            let mut random = [0u8; KEY_SOURCE_SERVER_LEN];
+            rand::thread_rng().fill_bytes(&mut random);
+            let options = format!(
+                "V4,dev-type tun,link-mtu 1549,tun-mtu 1500,proto {},cipher AES-256-GCM,auth [null-digest],keysize 256,key-method 2,tls-server",
+                self.link.occ_proto()
+            );
+            self.tls
+                .writer()
+                .write_all(&encode_server_key_method(&options, &random))?;
+            self.key_method = Some(key_method);
+            return Ok(());
+        }
+
+        // later messages are nul terminated strings
+        while let Some(pos) = self.plaintext.iter().position(|b| *b == 0) {
+            let msg = self.plaintext.drain(..=pos).collect::<Vec<_>>();
+            let msg = String::from_utf8_lossy(&msg[..pos]).trim().to_string();
+            match msg.as_str() {
+                "PUSH_REQUEST" => self.handle_push_request().await?,
+                "EXIT" => anyhow::bail!("openvpn client exited"),
+                _ => tracing::debug!(?msg, "ignoring openvpn control message"),
+            }
+        }
+        Ok(())
+    }
+
+    fn send_control_message(&mut self, msg: &str) -> anyhow::Result<()> {
+        let mut buf = msg.as_bytes().to_vec();
+        buf.push(0);
+        self.tls.writer().write_all(&buf)?;
+        Ok(())
+    }
+
+    async fn handle_push_request(&mut self) -> anyhow::Result<()> {
+        if self.push_reply.is_none() {
+            let reply = match self.prepare_push_reply().await {
+                Ok(reply) => reply,
+                Err(e) => {
+                    tracing::warn!(?e, endpoint = %self.endpoint, "rejecting openvpn client");
+                    self.auth_failed = true;
+                    vec![format!("AUTH_FAILED,{}", e)]
+                }
+            };
+            self.push_reply = Some(reply);
+        }
+        for msg in self.push_reply.clone().unwrap_or_default() {
+            self.send_control_message(&msg)?;
+        }
+        Ok(())
+    }
+
+    /// Assign an address, derive the data channel keys and build the options pushed to the client.
+    async fn prepare_push_reply(&mut self) -> anyhow::Result<Vec<String>> {
+        let key_method = self.key_method.as_ref().unwrap();
+        let iv_proto = key_method.iv_proto();
+        if iv_proto & IV_PROTO_TLS_KEY_EXPORT == 0 {
+            anyhow::bail!("openvpn 2.6 or later is required");
+        }
+        let algo = AeadAlgo::negotiate(
+            key_method
+                .peer_info_value("IV_CIPHERS")
+                .unwrap_or("AES-256-GCM:AES-128-GCM"),
+        )
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "no common data cipher, AES-256-GCM, CHACHA20-POLY1305 or AES-128-GCM is required"
+            )
+        })?;
+
+        let entry = Arc::new(ClientEntry {
+            endpoint: self.endpoint.clone(),
+            sink: self.sink.clone(),
+        });
+        let registration = self
+            .ctx
+            .allocate_client(entry)
+            .ok_or_else(|| anyhow::anyhow!("no free address left in {}", self.ctx.client_cidr))?;
+
+        let keys = self
+            .tls
+            .export_keying_material([0u8; KEY_MATERIAL_LEN], EKM_LABEL, None)?;
+        // the client sends with the first key and receives with the second
+        self.cipher = Some(AeadCipher::new(algo, &KeyMaterial::from_bytes(keys), 1, 0));
+
+        let mut options = vec![
+            format!("route-gateway {}", self.ctx.gateway),
+            "topology subnet".to_string(),
+            format!("ping {}", PING_INTERVAL.as_secs()),
+            format!("ping-restart {}", IDLE_TIMEOUT.as_secs() / 2),
+            format!(
+                "ifconfig {} {}",
+                registration.ip,
+                self.ctx.client_cidr.mask()
+            ),
+        ];
+        for route in self.ctx.list_client_routes().await {
+            options.push(format!("route {} {}", route.first_address(), route.mask()));
+        }
+        if iv_proto & IV_PROTO_DATA_V2 != 0 {
+            // 0xffffff is reserved for clients without a peer id
+            let peer_id = self.ctx.next_peer_id.fetch_add(1, Ordering::Relaxed) % 0xffffff;
+            self.data_header = vec![
+                op_byte(P_DATA_V2, self.key_id),
+                (peer_id >> 16) as u8,
+                (peer_id >> 8) as u8,
+                peer_id as u8,
+            ];
+            options.push(format!("peer-id {}", peer_id));
+        } else {
+            self.data_header = vec![op_byte(P_DATA_V1, self.key_id)];
+        }
+        options.push(format!("cipher {}", algo.openvpn_name()));
+        options.push("key-derivation tls-ekm".to_string());
+
+        tracing::info!(
+            endpoint = %self.endpoint,
+            ip = ?registration.ip,
+            cipher = algo.openvpn_name(),
+            "openvpn client connected"
+        );
+        self.registration = Some(registration);
+
+        Ok(build_push_replies(&options))
+    }
+
+    fn flush_tls(&mut self) {
+        let mut out = Vec::new();
+        while self.tls.wants_write() {
+            if self.tls.write_tls(&mut out).is_err() {
+                break;
+            }
+        }
+        for chunk in out.chunks(CONTROL_PAYLOAD_MAX) {
+            self.send_queue.push_back((P_CONTROL_V1, chunk.to_vec()));
+        }
+    }
+
+    async fn flush_control(&mut self) -> anyhow::Result<()> {
+        while self.in_flight.len() < SEND_WINDOW {
+            let Some((opcode, payload)) = self.send_queue.pop_front() else {
+                break;
+            };
+            let id = self.send_next_id;
+            self.send_next_id = next_packet_id(id)?;
+            self.in_flight.insert(
+                id,
+                InFlight {
+                    opcode,
+                    payload,
+                    last_sent: Instant::now(),
+                },
+            );
+            self.send_control(opcode, Some(id)).await?;
+        }
+        while !self.pending_acks.is_empty() {
+            self.send_control(P_ACK_V1, None).await?;
+        }
+        Ok(())
+    }
+
+    /// Send a reliable packet from the in flight window, or an ack only packet
+    /// when `packet_id` is none. Pending acks are piggybacked.
+    async fn send_control(&mut self, opcode: u8, packet_id: Option<u32>) -> anyhow::Result<()> {
+        let ack_count = self.pending_acks.len().min(MAX_ACKS_PER_PACKET);
+        let acks = self.pending_acks.drain(..ack_count).collect::<Vec<_>>();
+        let payload = packet_id
+            .and_then(|id| self.in_flight.get(&id))
+            .map(|f| f.payload.clone())
+            .unwrap_or_default();
+        let pkt = ControlPacket {
+            opcode,
+            key_id: self.key_id,
+            session_id: self.local_session_id,
+            remote_session_id: (!acks.is_empty()).then_some(self.remote_session_id),
+            acks,
+            packet_id,
+            payload,
+        };
+        self.link.send(&pkt.encode()).await?;
+        Ok(())
+    }
+
+    async fn handle_data(&mut self, packet: &[u8]) -> anyhow::Result<()> {
+        let Some(cipher) = self.cipher.as_mut() else {
+            return Ok(());
+        };
+        let header_len = if opcode(packet[0]) == P_DATA_V2 { 4 } else { 1 };
+        let payload = match cipher.decrypt(header_len, packet) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::debug!(?e, "dropping invalid openvpn data packet");
+                return Ok(());
+            }
+        };
+        self.last_recv = Instant::now();
+
+        match classify_payload(&payload) {
+            DataPayload::Ip(ipv4) => {
+                if let Some(registration) = &self.registration {
+                    forward_to_mesh(&self.ctx, registration.ip, &ipv4).await;
+                }
+            }
+            DataPayload::Exit => anyhow::bail!("openvpn client exited"),
+            DataPayload::Keepalive | DataPayload::Unknown => {}
+        }
+        Ok(())
+    }
+
+    async fn send_data(&mut self, payload: &[u8]) -> anyhow::Result<()> {
+        let Some(cipher) = self.cipher.as_mut() else {
+            return Ok(());
+        };
+        let packet = cipher.encrypt(&self.data_header, payload);
+        self.link.send(&packet).await?;
+        Ok(())
+    }
+
+    async fn on_tick(&mut self) -> anyhow::Result<()> {
+        let now = Instant::now();
+        if self.cipher.is_none() && now - self.started > HANDSHAKE_TIMEOUT {
+            anyhow::bail!("openvpn client handshake timed out");
+        }
+        if now - self.last_recv > IDLE_TIMEOUT {
+            anyhow::bail!("openvpn client timed out");
+        }
+        if self.auth_failed && self.in_flight.is_empty() && self.send_queue.is_empty() {
+            anyhow::bail!("openvpn client was rejected");
+        }
+
+        // stream transports never lose packets
+        if self.link.is_udp() {
+            let due = self
+                .in_flight
+                .iter_mut()
+                .filter(|(_, f)| now - f.last_sent >= RETRANSMIT_INTERVAL)
+                .map(|(id, f)| {
+                    f.last_sent = now;
+                    (*id, f.opcode)
+                })
+                .collect::<Vec<_>>();
+            for (id, opcode) in due {
+                self.send_control(opcode, Some(id)).await?;
+            }
+        }
+
+        if self.cipher.is_some() && now - self.last_ping >= PING_INTERVAL {
+            self.last_ping = now;
+            self.send_data(&PING_STRING).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse the hard reset that opens a tls session, returning it with its packet id.
+fn parse_hard_reset(packet: &[u8]) -> anyhow::Result<(ControlPacket, u32)> {
+    let Some((reset, id)) = ControlPacket::parse(packet)
+        .filter(|p| p.opcode == P_CONTROL_HARD_RESET_CLIENT_V2)
+        .and_then(|p| p.packet_id.map(|id| (p, id)))
+    else {
+        anyhow::bail!("openvpn client did not start with a hard reset");
+    };
+    Ok((reset, id))
+}
+
+// packet ids must not wrap within a session, openvpn renegotiates long before that
+fn next_packet_id(id: u32) -> anyhow::Result<u32> {
+    id.checked_add(1)
+        .ok_or_else(|| anyhow::anyhow!("openvpn control packet id overflow"))
+}
+
+/// Split pushed options into messages the client can buffer, chained with push-continuation.
+fn build_push_replies(options: &[String]) -> Vec<String> {
+    const HEAD: &str = "PUSH_REPLY";
+    let mut replies = vec![];
+    let mut cur = HEAD.to_string();
+    for opt in options {
+        if cur.len() + opt.len() + 1 > PUSH_REPLY_MAX && cur != HEAD {
+            replies.push(std::mem::replace(&mut cur, HEAD.to_string()));
+        }
+        cur.push(',');
+        cur.push_str(opt);
+    }
+    replies.push(cur);
+
+    if replies.len() > 1 {
+        let last = replies.len() - 1;
+        for (i, reply) in replies.iter_mut().enumerate() {
+            reply.push_str(if i == last {
+                ",push-continuation 1"
+            } else {
+                ",push-continuation 2"
+            });
+        }
+    }
+    replies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_push_replies() {
+        let options = vec!["topology subnet".to_string(), "ping 10".to_string()];
+        assert_eq!(
+            build_push_replies(&options),
+            vec!["PUSH_REPLY,topology subnet,ping 10".to_string()]
+        );
+
+        let options = (0..100)
+            .map(|i| format!("route 10.{}.0.0 255.255.0.0", i))
+            .collect::<Vec<_>>();
+        let replies = build_push_replies(&options);
+        assert!(replies.len() > 1);
+        assert!(replies.iter().all(|r| r.starts_with("PUSH_REPLY,route ")));
+        assert!(replies.iter().all(|r| r.len() <= PUSH_REPLY_MAX + 25));
+        assert!(replies.last().unwrap().ends_with(",push-continuation 1"));
+        assert!(replies[0].ends_with(",push-continuation 2"));
+        let total = replies
+            .iter()
+            .map(|r| r.matches(",route ").count())
+            .sum::<usize>();
+        assert_eq!(total, 100);
+    }
+
+    #[test]
+    fn test_classify_payload() {
+        assert!(matches!(
+            classify_payload(&PING_STRING),
+            DataPayload::Keepalive
+        ));
+
+        let mut exit = OCC_STRING.to_vec();
+        exit.push(OCC_EXIT);
+        assert!(matches!(classify_payload(&exit), DataPayload::Exit));
+
+        let mut ipv4 = vec![0u8; 20];
+        ipv4[0] = 0x45;
+        assert!(matches!(classify_payload(&ipv4), DataPayload::Ip(_)));
+        ipv4[0] = 0x60;
+        assert!(matches!(classify_payload(&ipv4), DataPayload::Unknown));
+    }
+
+    #[test]
+    fn test_hard_reset_packet_id() {
+        let mut reset = ControlPacket {
+            opcode: P_CONTROL_HARD_RESET_CLIENT_V2,
+            key_id: 0,
+            session_id: 5,
+            acks: vec![],
+            remote_session_id: None,
+            packet_id: Some(7),
+            payload: vec![],
+        };
+        let (_, id) = parse_hard_reset(&reset.encode()).unwrap();
+        assert_eq!(next_packet_id(id).unwrap(), 8);
+
+        reset.packet_id = Some(u32::MAX);
+        let (_, id) = parse_hard_reset(&reset.encode()).unwrap();
+        assert!(next_packet_id(id).is_err());
+
+        reset.opcode = P_CONTROL_V1;
+        assert!(parse_hard_reset(&reset.encode()).is_err());
+    }
+}
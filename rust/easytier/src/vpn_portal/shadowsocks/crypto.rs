@@ -0,0 +1,387 @@
+use std::collections::HashSet;
+
+use md5::{Digest, Md5};
+use rand::RngCore;
+use ring::{
+    aead::{self, Aad, LessSafeKey, Nonce, UnboundKey},
+    hkdf,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::common::config::ShadowsocksMethod;
+
+pub(crate) const TAG_LEN: usize = 16;
+// the length prefix of a tcp chunk has 14 usable bits
+pub(crate) const MAX_CHUNK_LEN: usize = 0x3fff;
+const SUBKEY_INFO: &[u8] = b"ss-subkey";
+
+struct HkdfLen(usize);
+
+impl hkdf::KeyType for HkdfLen {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+/// The key derived from the password, shared by all tcp streams and udp packets.
+/// Every stream or packet starts with a random salt that derives its own subkey.
+pub(crate) struct MasterKey {
+    method: ShadowsocksMethod,
+    key: Vec<u8>,
+}
+
+impl MasterKey {
+    pub fn new(method: ShadowsocksMethod, password: &str) -> Self {
+        let key_len = match method {
+            ShadowsocksMethod::Aes128Gcm => 16,
+            ShadowsocksMethod::Aes256Gcm | ShadowsocksMethod::Chacha20IetfPoly1305 => 32,
+        };
+        Self {
+            method,
+            key: evp_bytes_to_key(password, key_len),
+        }
+    }
+
+    pub fn salt_len(&self) -> usize {
+        self.key.len()
+    }
+
+    pub fn new_salt(&self) -> Vec<u8> {
+        let mut salt = vec![0u8; self.salt_len()];
+        rand::thread_rng().fill_bytes(&mut salt);
+        salt
+    }
+
+    pub fn cipher(&self, salt: &[u8]) -> AeadCipher {
+        let mut subkey = vec![0u8; self.key.len()];
+        hkdf::Salt::new(hkdf::HKDF_SHA1_FOR_LEGACY_USE_ONLY, salt)
+            .extract(&self.key)
+            .expand(&[SUBKEY_INFO], HkdfLen(subkey.len()))
+            .and_then(|okm| okm.fill(&mut subkey))
+            .expect("hkdf output length is valid for sha1");
+
+        let algo = match self.method {
+            ShadowsocksMethod::Aes128Gcm => &aead::AES_128_GCM,
+            ShadowsocksMethod::Aes256Gcm => &aead::AES_256_GCM,
+            ShadowsocksMethod::Chacha20IetfPoly1305 => &aead::CHACHA20_POLY1305,
+        };
+        AeadCipher {
+            key: LessSafeKey::new(
+                UnboundKey::new(algo, &subkey).expect("subkey length matches the cipher"),
+            ),
+            nonce: [0u8; aead::NONCE_LEN],
+        }
+    }
+
+    /// A udp packet: salt, then the payload sealed with a zero nonce.
+    pub fn encrypt_packet(&self, payload: &[u8]) -> Vec<u8> {
+        let salt = self.new_salt();
+        let mut body = payload.to_vec();
+        self.cipher(&salt).seal(&mut body);
+
+        let mut ret = salt;
+        ret.extend_from_slice(&body);
+        ret
+    }
+
+    pub fn decrypt_packet(&self, packet: &[u8]) -> anyhow::Result<Vec<u8>> {
+        if packet.len() < self.salt_len() + TAG_LEN {
+            anyhow::bail!("shadowsocks packet too short: {}", packet.len());
+        }
+        let (salt, body) = packet.split_at(self.salt_len());
+        let mut body = body.to_vec();
+        let len = self.cipher(salt).open(&mut body)?;
+        body.truncate(len);
+        Ok(body)
+    }
+}
+
+/// OpenSSL's EVP_BytesToKey with md5 and no salt, how shadowsocks turns a password into a key.
+fn evp_bytes_to_key(password: &str, key_len: usize) -> Vec<u8> {
+    let mut key = Vec::with_capacity(key_len + 16);
+    let mut prev = vec![];
+    while key.len() < key_len {
+        let mut hasher = Md5::new();
+        hasher.update(&prev);
+        hasher.update(password.as_bytes());
+        prev = hasher.finalize().to_vec();
+        key.extend_from_slice(&prev);
+    }
+    key.truncate(key_len);
+    key
+}
+
+/// The subkey of one direction of a stream, with the little endian nonce
+/// counter incremented after every seal or open.
+pub(crate) struct AeadCipher {
+    key: LessSafeKey,
+    nonce: [u8; aead::NONCE_LEN],
+}
+
+impl AeadCipher {
+    fn next_nonce(&mut self) -> Nonce {
+        let nonce = Nonce::assume_unique_for_key(self.nonce);
+        for b in self.nonce.iter_mut() {
+            *b = b.wrapping_add(1);
+            if *b != 0 {
+                break;
+            }
+        }
+        nonce
+    }
+
+    /// Encrypt `buf` in place and append the tag.
+    pub fn seal(&mut self, buf: &mut Vec<u8>) {
+        let nonce = self.next_nonce();
+        self.key
+            .seal_in_place_append_tag(nonce, Aad::empty(), buf)
+            .expect("shadowsocks payload is within aead limits");
+    }
+
+    /// Decrypt `buf`, the ciphertext followed by the tag, in place and return the plaintext length.
+    pub fn open(&mut self, buf: &mut [u8]) -> anyhow::Result<usize> {
+        let nonce = self.next_nonce();
+        self.key
+            .open_in_place(nonce, Aad::empty(), buf)
+            .map(|plain| plain.len())
+            .map_err(|_| anyhow::anyhow!("shadowsocks decryption failed"))
+    }
+}
+
+/// Reads the chunks of a tcp stream whose salt has already been consumed.
+pub(crate) struct AeadReader<R> {
+    inner: R,
+    cipher: AeadCipher,
+}
+
+impl<R: AsyncRead + Unpin> AeadReader<R> {
+    pub fn new(inner: R, cipher: AeadCipher) -> Self {
+        Self { inner, cipher }
+    }
+
+    /// Returns none when the stream is closed at a chunk boundary.
+    pub async fn read_chunk(&mut self) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut len = [0u8; 2 + TAG_LEN];
+        match self.inner.read_exact(&mut len).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        self.cipher.open(&mut len)?;
+        let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+        if len > MAX_CHUNK_LEN {
+            anyhow::bail!("invalid shadowsocks chunk length: {}", len);
+        }
+
+        let mut payload = vec![0u8; len + TAG_LEN];
+        self.inner.read_exact(&mut payload).await?;
+        self.cipher.open(&mut payload)?;
+        payload.truncate(len);
+        Ok(Some(payload))
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Writes the chunks of a tcp stream, prefixed by a fresh salt.
+pub(crate) struct AeadWriter<W> {
+    inner: W,
+    cipher: AeadCipher,
+    pending_salt: Option<Vec<u8>>,
+}
+
+impl<W: AsyncWrite + Unpin> AeadWriter<W> {
+    pub fn new(inner: W, key: &MasterKey) -> Self {
+        let salt = key.new_salt();
+        Self {
+            inner,
+            cipher: key.cipher(&salt),
+            pending_salt: Some(salt),
+        }
+    }
+
+    pub async fn write_chunk(&mut self, data: &[u8]) -> std::io::Result<()> {
+        let mut out = self.pending_salt.take().unwrap_or_default();
+        for part in data.chunks(MAX_CHUNK_LEN) {
+            let mut len = (part.len() as u16).to_be_bytes().to_vec();
+            self.cipher.seal(&mut len);
+            out.extend_from_slice(&len);
+
+            let mut payload = part.to_vec();
+            self.cipher.seal(&mut payload);
+            out.extend_from_slice(&payload);
+        }
+        self.inner.write_all(&out).await
+    }
+
+    pub async fn shutdown(&mut self) -> std::io::Result<()> {
+        self.inner.shutdown().await
+    }
+}
+
+/// Remembers the salts of recent tcp streams so replayed streams are rejected.
+/// Two generations are kept, the older one is dropped when the newer one is full.
+pub(crate) struct SaltFilter {
+    current: HashSet<Vec<u8>>,
+    previous: HashSet<Vec<u8>>,
+    capacity: usize,
+}
+
+impl SaltFilter {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            current: HashSet::new(),
+            previous: HashSet::new(),
+            capacity,
+        }
+    }
+
+    /// Returns false if the salt has been seen before.
+    pub fn check_and_insert(&mut self, salt: &[u8]) -> bool {
+        if self.current.contains(salt) || self.previous.contains(salt) {
+            return false;
+        }
+        if self.current.len() >= self.capacity {
+            self.previous = std::mem::take(&mut self.current);
+        }
+        self.current.insert(salt.to_vec());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evp_bytes_to_key() {
+        // md5("foobar"), then md5(md5("foobar") || "foobar")
+        let key = evp_bytes_to_key("foobar", 32);
+        assert_eq!(
+            key[..16],
+            [
+                0x38, 0x58, 0xf6, 0x22, 0x30, 0xac, 0x3c, 0x91, 0x5f, 0x30, 0x0c, 0x66, 0x43, 0x12,
+                0xc6, 0x3f
+            ]
+        );
+        assert_eq!(key.len(), 32);
+        assert_eq!(evp_bytes_to_key("foobar", 16), key[..16]);
+    }
+
+    #[test]
+    fn test_packet_roundtrip() {
+        for method in [
+            ShadowsocksMethod::Aes128Gcm,
+            ShadowsocksMethod::Aes256Gcm,
+            ShadowsocksMethod::Chacha20IetfPoly1305,
+        ] {
+            let key = MasterKey::new(method, "password");
+            let packet = key.encrypt_packet(b"hello");
+            assert_eq!(packet.len(), key.salt_len() + 5 + TAG_LEN);
+            assert_eq!(key.decrypt_packet(&packet).unwrap(), b"hello");
+
+            let mut tampered = packet.clone();
+            *tampered.last_mut().unwrap() ^= 1;
+            assert!(key.decrypt_packet(&tampered).is_err());
+
+            let other = MasterKey::new(method, "other");
+            assert!(other.decrypt_packet(&packet).is_err());
+        }
+    }
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    // generated with the aead ciphers of openssl (python cryptography), password "easytier",
+    // salt 00 01 02 .. and payload "known answer". tcp is the length chunk and the payload chunk.
+    #[tokio::test]
+    async fn test_known_answer() {
+        let vectors = [
+            (
+                ShadowsocksMethod::Aes128Gcm,
+                "000102030405060708090a0b0c0d0e0fec057c635edff27e309cff428f21d901e0e1718df2d1ef38b68f0548",
+                "000102030405060708090a0b0c0d0e0f876723a285e7f55a8936a20e995737866a4dcdc914731b942dc2622322be3d34bafaff0691810c60ea6dceeaa775",
+            ),
+            (
+                ShadowsocksMethod::Aes256Gcm,
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1fa31a36cb9a745819d6b85f1fce9861b79544b7c66aa8c1a0c2eff2ca",
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1fc878a174c4e377e5a59ac3b0f7ea781704022c7817019113f70b1a2f29d75659fe818f40cbad256b7ab4a9aec926",
+            ),
+            (
+                ShadowsocksMethod::Chacha20IetfPoly1305,
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f8c99f9d0f3b81c7698be7d6893f099e30f24b38e08fa6fe42f3e14a0",
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1fe7fb9af4f1913948474e6f2d9b93a5efcd530209ec1f1c709317bf24ca75bdd5f70a6d38a32eee63030b3710b80f",
+            ),
+        ];
+        let payload = b"known answer";
+        for (method, udp, tcp) in vectors {
+            let key = MasterKey::new(method, "easytier");
+            let salt = (0..key.salt_len() as u8).collect::<Vec<_>>();
+
+            let udp = unhex(udp);
+            let mut body = payload.to_vec();
+            key.cipher(&salt).seal(&mut body);
+            assert_eq!([salt.clone(), body].concat(), udp);
+            assert_eq!(key.decrypt_packet(&udp).unwrap(), payload);
+
+            let tcp = unhex(tcp);
+            let mut cipher = key.cipher(&salt);
+            let mut len = (payload.len() as u16).to_be_bytes().to_vec();
+            cipher.seal(&mut len);
+            let mut body = payload.to_vec();
+            cipher.seal(&mut body);
+            assert_eq!([salt.clone(), len, body].concat(), tcp);
+
+            let mut reader = AeadReader::new(&tcp[salt.len()..], key.cipher(&salt));
+            assert_eq!(reader.read_chunk().await.unwrap().unwrap(), payload);
+            assert!(reader.read_chunk().await.unwrap().is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_roundtrip() {
+        let key = MasterKey::new(ShadowsocksMethod::Chacha20IetfPoly1305, "password");
+        let (client, server) = tokio::io::duplex(1024 * 1024);
+
+        let big = vec![7u8; MAX_CHUNK_LEN * 2 + 10];
+        let mut writer = AeadWriter::new(client, &key);
+        writer.write_chunk(b"first").await.unwrap();
+        writer.write_chunk(&big).await.unwrap();
+        writer.shutdown().await.unwrap();
+        drop(writer);
+
+        let mut server = server;
+        let mut salt = vec![0u8; key.salt_len()];
+        server.read_exact(&mut salt).await.unwrap();
+        let mut reader = AeadReader::new(server, key.cipher(&salt));
+        assert_eq!(reader.read_chunk().await.unwrap().unwrap(), b"first");
+
+        let mut received = vec![];
+        while let Some(chunk) = reader.read_chunk().await.unwrap() {
+            assert!(chunk.len() <= MAX_CHUNK_LEN);
+            received.extend_from_slice(&chunk);
+        }
+        assert_eq!(received, big);
+    }
+
+    #[test]
+    fn test_salt_filter() {
+        let mut filter = SaltFilter::new(2);
+        assert!(filter.check_and_insert(b"a"));
+        assert!(!filter.check_and_insert(b"a"));
+        assert!(filter.check_and_insert(b"b"));
+        // rotates the generations, a and b are still remembered
+        assert!(filter.check_and_insert(b"c"));
+        assert!(!filter.check_and_insert(b"a"));
+        assert!(filter.check_and_insert(b"d"));
+        assert!(filter.check_and_insert(b"e"));
+        // a and b have been forgotten by now
+        assert!(filter.check_and_insert(b"a"));
+    }
+}
@@ -0,0 +1,563 @@
+// a shadowsocks (aead) server for clients that only have a shadowsocks app.
+// tcp streams and udp packets are relayed to virtual ips and proxied subnets, other
+// destinations are refused. connections into the virtual network are made from a
+// userspace tcp/ip stack that owns the first host address of the portal cidr, which
+// is routed to this node, so no tun device is needed.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use anyhow::Context;
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use cidr::{Ipv4Cidr, Ipv4Inet};
+use dashmap::DashMap;
+use pnet::packet::ipv4::Ipv4Packet;
+use sha2::{Digest, Sha256};
+use tokio::{
+    net::{TcpListener, TcpSocket},
+    sync::mpsc,
+    task::JoinSet,
+    time::timeout,
+};
+use tracing::Level;
+
+use crate::{
+    common::{
+        config::{NetworkIdentity, ShadowsocksPortalConfig},
+        global_ctx::{ArcGlobalCtx, GlobalCtxEvent},
+        join_joinset_background,
+    },
+    gateway::{
+        fast_socks5::util::target_addr::TargetAddr,
+        socks5::{bind_tcp_socket, bind_udp_socket, SocksTcpStream},
+        tokio_smoltcp::{channel_device, BufferSize, Net, NetConfig},
+    },
+    peers::{peer_manager::PeerManager, PeerPacketFilter},
+    tunnel::packet_def::{PacketType, ZCPacket},
+};
+
+use self::crypto::{MasterKey, SaltFilter};
+
+use super::VpnPortal;
+
+pub(crate) mod crypto;
+mod relay;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const SALT_FILTER_CAPACITY: usize = 10000;
+
+/// Where the portal connects to for a target address.
+#[derive(Debug, Clone, Copy)]
+enum Outbound {
+    // this node or one of its own proxied subnets, reached with kernel sockets
+    Local(SocketAddr),
+    // another node of the virtual network, reached through the userspace stack
+    Mesh(SocketAddr),
+}
+
+impl Outbound {
+    fn addr(&self) -> SocketAddr {
+        match self {
+            Outbound::Local(addr) | Outbound::Mesh(addr) => *addr,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ConnKind {
+    Tcp,
+    Udp,
+}
+
+#[derive(Debug, Default)]
+struct ClientConns {
+    tcp: u32,
+    udp: u32,
+}
+
+type ClientTable = Arc<DashMap<IpAddr, ClientConns>>;
+
+/// Counts a tcp connection or udp association of a client. A client is
+/// connected while it has any of them.
+struct ClientGuard {
+    global_ctx: ArcGlobalCtx,
+    portal: String,
+    clients: ClientTable,
+    ip: IpAddr,
+    kind: ConnKind,
+}
+
+impl ClientGuard {
+    fn new(ctx: &PortalCtx, ip: IpAddr, kind: ConnKind) -> Self {
+        let mut is_new = false;
+        {
+            let mut conns = ctx.clients.entry(ip).or_insert_with(|| {
+                is_new = true;
+                ClientConns::default()
+            });
+            match kind {
+                ConnKind::Tcp => conns.tcp += 1,
+                ConnKind::Udp => conns.udp += 1,
+            }
+        }
+        if is_new {
+            ctx.global_ctx
+                .issue_event(GlobalCtxEvent::VpnPortalClientConnected(
+                    ctx.portal.clone(),
+                    ip.to_string(),
+                ));
+        }
+        Self {
+            global_ctx: ctx.global_ctx.clone(),
+            portal: ctx.portal.clone(),
+            clients: ctx.clients.clone(),
+            ip,
+            kind,
+        }
+    }
+}
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        if let Some(mut conns) = self.clients.get_mut(&self.ip) {
+            match self.kind {
+                ConnKind::Tcp => conns.tcp -= 1,
+                ConnKind::Udp => conns.udp -= 1,
+            }
+        }
+        let removed = self
+            .clients
+            .remove_if(&self.ip, |_, c| c.tcp == 0 && c.udp == 0)
+            .is_some();
+        if removed {
+            self.global_ctx
+                .issue_event(GlobalCtxEvent::VpnPortalClientDisconnected(
+                    self.portal.clone(),
+                    self.ip.to_string(),
+                ));
+        }
+    }
+}
+
+/// State shared by the listeners and all client connections of a portal.
+struct PortalCtx {
+    global_ctx: ArcGlobalCtx,
+    peer_mgr: Arc<PeerManager>,
+    portal: String,
+    key: MasterKey,
+    net: Net,
+    clients: ClientTable,
+    salt_filter: std::sync::Mutex<SaltFilter>,
+}
+
+impl PortalCtx {
+    /// Resolve the target and check it is inside the virtual network.
+    async fn route_target(&self, target: TargetAddr) -> anyhow::Result<Outbound> {
+        let addr = match target.resolve_dns().await? {
+            TargetAddr::Ip(SocketAddr::V4(addr)) => addr,
+            target => anyhow::bail!("{} is not an ipv4 address", target),
+        };
+        let ip = *addr.ip();
+
+        if self
+            .global_ctx
+            .get_ipv4()
+            .is_some_and(|ipv4| ipv4.address() == ip)
+        {
+            return Ok(Outbound::Local(SocketAddr::new(
+                Ipv4Addr::LOCALHOST.into(),
+                addr.port(),
+            )));
+        }
+        if self
+            .global_ctx
+            .config
+            .get_proxy_cidrs()
+            .iter()
+            .any(|c| c.cidr.contains(&ip))
+        {
+            return Ok(Outbound::Local(addr.into()));
+        }
+        if self.is_mesh_address(ip).await {
+            return Ok(Outbound::Mesh(addr.into()));
+        }
+        anyhow::bail!("{} is outside of the virtual network", ip)
+    }
+
+    async fn is_mesh_address(&self, ip: Ipv4Addr) -> bool {
+        if self
+            .global_ctx
+            .get_ipv4()
+            .is_some_and(|ipv4| ipv4.network().contains(&ip))
+        {
+            return true;
+        }
+        self.peer_mgr.list_routes().await.iter().any(|route| {
+            route
+                .ipv4_addr
+                .is_some_and(|ipv4| Ipv4Inet::from(ipv4).address() == ip)
+                || route
+                    .proxy_cidrs
+                    .iter()
+                    .filter_map(|c| c.parse::<Ipv4Cidr>().ok())
+                    .any(|c| c.contains(&ip))
+        })
+    }
+
+    async fn connect_tcp(&self, outbound: Outbound) -> anyhow::Result<SocksTcpStream> {
+        Ok(match outbound {
+            Outbound::Local(addr) => {
+                let socket = {
+                    let _g = self.global_ctx.net_ns.guard();
+                    TcpSocket::new_v4()?
+                };
+                SocksTcpStream::Tcp(timeout(CONNECT_TIMEOUT, socket.connect(addr)).await??)
+            }
+            Outbound::Mesh(addr) => SocksTcpStream::SmolTcp(
+                timeout(
+                    CONNECT_TIMEOUT,
+                    self.net.tcp_connect(addr, self.net.get_port()),
+                )
+                .await??,
+            ),
+        })
+    }
+}
+
+/// The password used when none is configured, so exported client configs stay
+/// valid across restarts.
+fn derive_password(nid: &NetworkIdentity) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"easytier-shadowsocks");
+    hasher.update(nid.network_name.as_bytes());
+    hasher.update(nid.network_secret.as_deref().unwrap_or_default().as_bytes());
+    BASE64_URL_SAFE_NO_PAD.encode(&hasher.finalize()[..16])
+}
+
+// percent encode everything but unreserved characters, for the name shown by clients
+fn encode_uri_component(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// A SIP002 uri, understood by most shadowsocks clients and qr code importers.
+fn build_uri(cfg: &ShadowsocksPortalConfig, password: &str, name: &str) -> String {
+    let userinfo = BASE64_URL_SAFE_NO_PAD.encode(format!("{}:{}", cfg.method.as_str(), password));
+    format!(
+        "ss://{}@{}#{}",
+        userinfo,
+        cfg.listen,
+        encode_uri_component(name)
+    )
+}
+
+type StackSink = mpsc::Sender<std::io::Result<Vec<u8>>>;
+
+struct ShadowsocksImpl {
+    ctx: Arc<PortalCtx>,
+    cfg: ShadowsocksPortalConfig,
+    password: String,
+    stack_addr: Ipv4Addr,
+    stack_sink: StackSink,
+    tasks: Arc<std::sync::Mutex<JoinSet<()>>>,
+}
+
+impl ShadowsocksImpl {
+    fn new(
+        global_ctx: ArcGlobalCtx,
+        peer_mgr: Arc<PeerManager>,
+        cfg: ShadowsocksPortalConfig,
+        client_cidr: Ipv4Cidr,
+    ) -> anyhow::Result<Self> {
+        if client_cidr.network_length() > 30 {
+            anyhow::bail!(
+                "vpn portal client cidr {} is too small for the shadowsocks portal",
+                client_cidr
+            );
+        }
+        let stack_addr = Ipv4Addr::from(u32::from(client_cidr.first_address()) + 1);
+        let password = cfg
+            .password
+            .clone()
+            .unwrap_or_else(|| derive_password(&global_ctx.get_network_identity()));
+
+        let mut tasks = JoinSet::new();
+        let (net, stack_sink) = Self::create_stack(
+            peer_mgr.clone(),
+            Ipv4Inet::new(stack_addr, client_cidr.network_length())?,
+            &mut tasks,
+        );
+
+        Ok(Self {
+            ctx: Arc::new(PortalCtx {
+                global_ctx,
+                peer_mgr,
+                portal: format!("shadowsocks://{}", cfg.listen),
+                key: MasterKey::new(cfg.method, &password),
+                net,
+                clients: Arc::new(DashMap::new()),
+                salt_filter: std::sync::Mutex::new(SaltFilter::new(SALT_FILTER_CAPACITY)),
+            }),
+            cfg,
+            password,
+            stack_addr,
+            stack_sink,
+            tasks: Arc::new(std::sync::Mutex::new(tasks)),
+        })
+    }
+
+    /// A userspace stack whose packets are sent into the virtual network, the
+    /// returned sink feeds it the packets addressed to the stack.
+    fn create_stack(
+        peer_mgr: Arc<PeerManager>,
+        stack_addr: Ipv4Inet,
+        tasks: &mut JoinSet<()>,
+    ) -> (Net, StackSink) {
+        let mut cap = smoltcp::phy::DeviceCapabilities::default();
+        cap.max_transmission_unit = 1284; // 1284 - 20 can be divided by 8 (fragment offset unit)
+        cap.medium = smoltcp::phy::Medium::Ip;
+        let (dev, stack_sink, mut stack_stream) = channel_device::ChannelDevice::new(cap);
+
+        tasks.spawn(async move {
+            while let Some(data) = stack_stream.recv().await {
+                let Some(ipv4) = Ipv4Packet::new(&data) else {
+                    continue;
+                };
+                let dst = ipv4.get_destination();
+                if let Err(e) = peer_mgr
+                    .send_msg_by_ip(ZCPacket::new_with_payload(&data), IpAddr::V4(dst))
+                    .await
+                {
+                    tracing::trace!(?e, ?dst, "shadowsocks portal failed to send to peer");
+                }
+            }
+        });
+
+        let net = Net::new(
+            dev,
+            NetConfig::new(
+                smoltcp::iface::Config::new(smoltcp::wire::HardwareAddress::Ip),
+                format!("{}/{}", stack_addr.address(), stack_addr.network_length())
+                    .parse()
+                    .unwrap(),
+                vec![format!("{}", stack_addr.address()).parse().unwrap()],
+                Some(BufferSize {
+                    tcp_rx_size: 1024 * 128,
+                    tcp_tx_size: 1024 * 128,
+                    ..Default::default()
+                }),
+            ),
+        );
+        (net, stack_sink)
+    }
+
+    async fn run_tcp_listener(
+        ctx: Arc<PortalCtx>,
+        listener: TcpListener,
+        tasks: Weak<std::sync::Mutex<JoinSet<()>>>,
+    ) {
+        loop {
+            let (stream, client) = match listener.accept().await {
+                Ok(ret) => ret,
+                Err(err) => {
+                    tracing::error!(?err, "shadowsocks portal tcp accept failed");
+                    continue;
+                }
+            };
+            let Some(tasks) = tasks.upgrade() else {
+                break;
+            };
+            tasks
+                .lock()
+                .unwrap()
+                .spawn(relay::handle_tcp_client(ctx.clone(), stream, client));
+        }
+    }
+
+    async fn start_pipeline_processor(&self) {
+        struct PeerPacketFilterForShadowsocksPortal {
+            stack_addr: Ipv4Addr,
+            stack_sink: StackSink,
+        }
+
+        #[async_trait::async_trait]
+        impl PeerPacketFilter for PeerPacketFilterForShadowsocksPortal {
+            async fn try_process_packet_from_peer(&self, packet: ZCPacket) -> Option<ZCPacket> {
+                let hdr = packet.peer_manager_header().unwrap();
+                if hdr.packet_type != PacketType::Data as u8 {
+                    return Some(packet);
+                };
+
+                let ipv4 = Ipv4Packet::new(packet.payload())?;
+                if ipv4.get_version() != 4 || ipv4.get_destination() != self.stack_addr {
+                    return Some(packet);
+                }
+
+                if let Err(e) = self.stack_sink.try_send(Ok(packet.payload().to_vec())) {
+                    tracing::debug!(?e, "Failed to send packet to shadowsocks portal stack");
+                }
+
+                None
+            }
+        }
+
+        self.ctx
+            .peer_mgr
+            .add_packet_process_pipeline(Box::new(PeerPacketFilterForShadowsocksPortal {
+                stack_addr: self.stack_addr,
+                stack_sink: self.stack_sink.clone(),
+            }))
+            .await;
+    }
+
+    #[tracing::instrument(skip(self), err(level = Level::WARN))]
+    async fn start(&self) -> anyhow::Result<()> {
+        tracing::info!("Shadowsocks VPN Portal Starting");
+
+        let listen = self.cfg.listen;
+        let net_ns = self.ctx.global_ctx.net_ns.clone();
+        let listener = bind_tcp_socket(listen, net_ns.clone())
+            .with_context(|| format!("failed to bind shadowsocks portal tcp on {}", listen))?;
+        let socket = bind_udp_socket(listen, net_ns)
+            .with_context(|| format!("failed to bind shadowsocks portal udp on {}", listen))?;
+
+        {
+            let mut tasks = self.tasks.lock().unwrap();
+            tasks.spawn(Self::run_tcp_listener(
+                self.ctx.clone(),
+                listener,
+                Arc::downgrade(&self.tasks),
+            ));
+            tasks.spawn(relay::run_udp_relay(self.ctx.clone(), Arc::new(socket)));
+        }
+        join_joinset_background(self.tasks.clone(), "shadowsocks".to_string());
+        self.start_pipeline_processor().await;
+
+        self.ctx
+            .global_ctx
+            .issue_event(GlobalCtxEvent::VpnPortalStarted(self.ctx.portal.clone()));
+
+        Ok(())
+    }
+
+    fn dump_client_config(&self) -> String {
+        let name = format!(
+            "EasyTier-{}",
+            self.ctx.global_ctx.get_network_identity().network_name
+        );
+        format!(
+            r#"{uri}
+# the address in the uri should be the public ip(or domain) of the vpn server
+# virtual ips and proxied subnets are reachable, {stack_addr} is used by the portal itself
+"#,
+            uri = build_uri(&self.cfg, &self.password, &name),
+            stack_addr = self.stack_addr,
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct Shadowsocks {
+    inner: Option<ShadowsocksImpl>,
+}
+
+#[async_trait::async_trait]
+impl VpnPortal for Shadowsocks {
+    async fn start(
+        &mut self,
+        global_ctx: ArcGlobalCtx,
+        peer_mgr: Arc<PeerManager>,
+    ) -> anyhow::Result<()> {
+        assert!(self.inner.is_none());
+
+        let Some(vpn_cfg) = global_ctx.config.get_vpn_portal_config() else {
+            anyhow::bail!("vpn cfg is not set for shadowsocks vpn portal");
+        };
+        let Some(ss_cfg) = vpn_cfg.shadowsocks else {
+            anyhow::bail!("shadowsocks is not enabled in vpn portal config");
+        };
+
+        let inner = ShadowsocksImpl::new(global_ctx, peer_mgr, ss_cfg, vpn_cfg.client_cidr)?;
+        inner.start().await?;
+        self.inner = Some(inner);
+        Ok(())
+    }
+
+    async fn dump_client_config(&self, _peer_mgr: Arc<PeerManager>) -> String {
+        match &self.inner {
+            Some(inner) => inner.dump_client_config(),
+            None => "ERROR: Shadowsocks VPN Portal Not Started".to_string(),
+        }
+    }
+
+    fn name(&self) -> String {
+        "shadowsocks".to_string()
+    }
+
+    async fn list_clients(&self) -> Vec<String> {
+        self.inner
+            .as_ref()
+            .map(|s| {
+                s.ctx
+                    .clients
+                    .iter()
+                    .map(|x| format!("{} (tcp: {}, udp: {})", x.key(), x.tcp, x.udp))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::config::ShadowsocksMethod;
+
+    use super::*;
+
+    #[test]
+    fn test_build_uri() {
+        let cfg = ShadowsocksPortalConfig {
+            listen: "1.2.3.4:8388".parse().unwrap(),
+            method: ShadowsocksMethod::Aes256Gcm,
+            password: None,
+        };
+        let uri = build_uri(&cfg, "pass", "EasyTier-my net");
+        assert_eq!(
+            uri,
+            format!(
+                "ss://{}@1.2.3.4:8388#EasyTier-my%20net",
+                BASE64_URL_SAFE_NO_PAD.encode("aes-256-gcm:pass")
+            )
+        );
+
+        let cfg = ShadowsocksPortalConfig {
+            listen: "[::1]:8388".parse().unwrap(),
+            ..cfg
+        };
+        assert!(build_uri(&cfg, "pass", "x").contains("@[::1]:8388#x"));
+    }
+
+    #[test]
+    fn test_derive_password() {
+        let nid = NetworkIdentity::new("net".to_string(), "secret".to_string());
+        let password = derive_password(&nid);
+        assert_eq!(password.len(), 22);
+        assert_eq!(password, derive_password(&nid));
+        assert_ne!(
+            password,
+            derive_password(&NetworkIdentity::new(
+                "net".to_string(),
+                "other".to_string()
+            ))
+        );
+    }
+}
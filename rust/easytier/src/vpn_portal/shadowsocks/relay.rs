@@ -0,0 +1,297 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+    task::JoinSet,
+    time::timeout,
+};
+
+use crate::gateway::{
+    fast_socks5::util::target_addr::{read_address, TargetAddr},
+    socks5::{bind_udp_socket, SocksUdpSocket},
+};
+
+use super::{
+    crypto::{AeadReader, AeadWriter, MAX_CHUNK_LEN},
+    ClientGuard, ConnKind, Outbound, PortalCtx,
+};
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+// how long a client failing to authenticate is read from before the connection is closed,
+// closing right away would tell probes that they hit a shadowsocks server
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(60);
+const UDP_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+const UDP_BUF_LEN: usize = 65536;
+
+/// Split the target address off the first payload of a stream or packet.
+pub(super) async fn parse_target(buf: &[u8]) -> anyhow::Result<(TargetAddr, &[u8])> {
+    let Some((&atyp, mut rest)) = buf.split_first() else {
+        anyhow::bail!("missing shadowsocks target address");
+    };
+    let target = read_address(&mut rest, atyp).await?;
+    Ok((target, rest))
+}
+
+async fn drain<R: AsyncRead + Unpin>(mut reader: R) {
+    let _ = timeout(
+        DRAIN_TIMEOUT,
+        tokio::io::copy(&mut reader, &mut tokio::io::sink()),
+    )
+    .await;
+}
+
+pub(super) async fn handle_tcp_client(ctx: Arc<PortalCtx>, stream: TcpStream, client: SocketAddr) {
+    let _ = stream.set_nodelay(true);
+    if let Err(e) = serve_tcp_client(ctx, stream, client).await {
+        tracing::debug!(?e, ?client, "shadowsocks tcp connection closed with error");
+    }
+}
+
+async fn serve_tcp_client(
+    ctx: Arc<PortalCtx>,
+    stream: TcpStream,
+    client: SocketAddr,
+) -> anyhow::Result<()> {
+    let (mut reader, writer) = stream.into_split();
+
+    let mut salt = vec![0u8; ctx.key.salt_len()];
+    timeout(HANDSHAKE_TIMEOUT, reader.read_exact(&mut salt)).await??;
+    if !ctx.salt_filter.lock().unwrap().check_and_insert(&salt) {
+        drain(reader).await;
+        anyhow::bail!("replayed shadowsocks stream");
+    }
+
+    let mut reader = AeadReader::new(reader, ctx.key.cipher(&salt));
+    let first = match timeout(HANDSHAKE_TIMEOUT, reader.read_chunk()).await? {
+        Ok(Some(first)) => first,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            drain(reader.into_inner()).await;
+            return Err(e.context("shadowsocks client failed to authenticate"));
+        }
+    };
+    let (target, early_data) = parse_target(&first).await?;
+    let outbound = ctx.route_target(target.clone()).await?;
+
+    let _guard = ClientGuard::new(&ctx, client.ip(), ConnKind::Tcp);
+    tracing::info!(?client, %target, ?outbound, "shadowsocks client connecting");
+    let remote = ctx
+        .connect_tcp(outbound)
+        .await
+        .with_context(|| format!("failed to connect to {}", target))?;
+    let (mut remote_reader, mut remote_writer) = tokio::io::split(remote);
+    remote_writer.write_all(early_data).await?;
+
+    let mut writer = AeadWriter::new(writer, &ctx.key);
+    let upload = async {
+        while let Some(chunk) = reader.read_chunk().await? {
+            remote_writer.write_all(&chunk).await?;
+        }
+        remote_writer.shutdown().await?;
+        Ok::<_, anyhow::Error>(())
+    };
+    let download = async {
+        let mut buf = vec![0u8; MAX_CHUNK_LEN];
+        loop {
+            let n = remote_reader.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            writer.write_chunk(&buf[..n]).await?;
+        }
+        writer.shutdown().await?;
+        Ok::<_, anyhow::Error>(())
+    };
+    tokio::try_join!(upload, download)?;
+    Ok(())
+}
+
+/// The outbound sockets of one udp client, replies are relayed back to it
+/// with the source address prepended.
+struct UdpAssociation {
+    ctx: Arc<PortalCtx>,
+    relay: Arc<UdpSocket>,
+    client: SocketAddr,
+    local: Option<Arc<SocksUdpSocket>>,
+    mesh: Option<Arc<SocksUdpSocket>>,
+    targets: HashMap<TargetAddr, Outbound>,
+    last_active: Instant,
+    tasks: JoinSet<()>,
+    _guard: ClientGuard,
+}
+
+impl UdpAssociation {
+    fn new(ctx: Arc<PortalCtx>, relay: Arc<UdpSocket>, client: SocketAddr) -> Self {
+        let guard = ClientGuard::new(&ctx, client.ip(), ConnKind::Udp);
+        Self {
+            ctx,
+            relay,
+            client,
+            local: None,
+            mesh: None,
+            targets: HashMap::new(),
+            last_active: Instant::now(),
+            tasks: JoinSet::new(),
+            _guard: guard,
+        }
+    }
+
+    async fn route(&mut self, target: TargetAddr) -> anyhow::Result<Outbound> {
+        if let Some(outbound) = self.targets.get(&target) {
+            return Ok(*outbound);
+        }
+        let outbound = self.ctx.route_target(target.clone()).await?;
+        self.targets.insert(target, outbound);
+        Ok(outbound)
+    }
+
+    async fn socket(&mut self, outbound: Outbound) -> anyhow::Result<Arc<SocksUdpSocket>> {
+        let slot = match outbound {
+            Outbound::Local(_) => &mut self.local,
+            Outbound::Mesh(_) => &mut self.mesh,
+        };
+        if let Some(socket) = slot.as_ref() {
+            return Ok(socket.clone());
+        }
+
+        let socket = Arc::new(match outbound {
+            Outbound::Local(_) => SocksUdpSocket::UdpSocket(Arc::new(bind_udp_socket(
+                "0.0.0.0:0".parse().unwrap(),
+                self.ctx.global_ctx.net_ns.clone(),
+            )?)),
+            Outbound::Mesh(_) => SocksUdpSocket::SmolUdpSocket(
+                self.ctx.net.udp_bind("0.0.0.0:0".parse().unwrap()).await?,
+            ),
+        });
+        *slot = Some(socket.clone());
+
+        let ctx = self.ctx.clone();
+        let relay = self.relay.clone();
+        let client = self.client;
+        let replies = socket.clone();
+        self.tasks.spawn(async move {
+            let mut buf = vec![0u8; UDP_BUF_LEN];
+            loop {
+                let (len, from) = match replies.recv_from(&mut buf).await {
+                    Ok(ret) => ret,
+                    Err(e) => {
+                        tracing::debug!(?e, "shadowsocks udp outbound recv failed");
+                        break;
+                    }
+                };
+                let Ok(mut payload) = TargetAddr::Ip(ctx.reply_source(from)).to_be_bytes() else {
+                    continue;
+                };
+                payload.extend_from_slice(&buf[..len]);
+                let packet = ctx.key.encrypt_packet(&payload);
+                if let Err(e) = relay.send_to(&packet, client).await {
+                    tracing::debug!(?e, ?client, "shadowsocks udp reply send failed");
+                }
+            }
+        });
+
+        Ok(socket)
+    }
+
+    async fn send(&mut self, target: TargetAddr, data: &[u8]) -> anyhow::Result<()> {
+        self.last_active = Instant::now();
+        let outbound = self.route(target).await?;
+        let socket = self.socket(outbound).await?;
+        socket.send_to(data, outbound.addr()).await?;
+        Ok(())
+    }
+}
+
+pub(super) async fn run_udp_relay(ctx: Arc<PortalCtx>, relay: Arc<UdpSocket>) {
+    let mut associations: HashMap<SocketAddr, UdpAssociation> = HashMap::new();
+    let mut buf = vec![0u8; UDP_BUF_LEN];
+    let mut cleanup = tokio::time::interval(Duration::from_secs(30));
+
+    loop {
+        let (len, client) = tokio::select! {
+            ret = relay.recv_from(&mut buf) => match ret {
+                Ok(ret) => ret,
+                Err(e) => {
+                    tracing::error!(?e, "shadowsocks udp relay recv failed");
+                    continue;
+                }
+            },
+            _ = cleanup.tick() => {
+                associations.retain(|_, a| a.last_active.elapsed() < UDP_IDLE_TIMEOUT);
+                continue;
+            }
+        };
+
+        let payload = match ctx.key.decrypt_packet(&buf[..len]) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::trace!(?e, ?client, "dropping invalid shadowsocks udp packet");
+                continue;
+            }
+        };
+        let (target, data) = match parse_target(&payload).await {
+            Ok(ret) => ret,
+            Err(e) => {
+                tracing::trace!(
+                    ?e,
+                    ?client,
+                    "dropping shadowsocks udp packet without target"
+                );
+                continue;
+            }
+        };
+
+        let association = associations
+            .entry(client)
+            .or_insert_with(|| UdpAssociation::new(ctx.clone(), relay.clone(), client));
+        if let Err(e) = association.send(target, data).await {
+            tracing::debug!(?e, ?client, "shadowsocks udp relay send failed");
+        }
+    }
+}
+
+impl PortalCtx {
+    /// The address replies appear to come from, loopback replies come from this node's virtual ip.
+    fn reply_source(&self, from: SocketAddr) -> SocketAddr {
+        match self.global_ctx.get_ipv4() {
+            Some(ipv4) if from.ip().is_loopback() => {
+                SocketAddr::new(IpAddr::V4(ipv4.address()), from.port())
+            }
+            _ => from,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_parse_target() {
+        let mut buf = TargetAddr::Ip("10.144.144.1:80".parse().unwrap())
+            .to_be_bytes()
+            .unwrap();
+        buf.extend_from_slice(b"GET /");
+        let (target, rest) = parse_target(&buf).await.unwrap();
+        assert_eq!(target, TargetAddr::Ip("10.144.144.1:80".parse().unwrap()));
+        assert_eq!(rest, b"GET /");
+
+        let buf = TargetAddr::Domain("example.com".to_string(), 443)
+            .to_be_bytes()
+            .unwrap();
+        let (target, rest) = parse_target(&buf).await.unwrap();
+        assert_eq!(target, TargetAddr::Domain("example.com".to_string(), 443));
+        assert!(rest.is_empty());
+
+        assert!(parse_target(&[]).await.is_err());
+        assert!(parse_target(&buf[..5]).await.is_err());
+        assert!(parse_target(&[9, 1, 2, 3]).await.is_err());
+    }
+}
@@ -1,6 +1,10 @@
 use std::{
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::Context;
@@ -10,19 +14,21 @@ use dashmap::DashMap;
 use futures::StreamExt;
 use pnet::packet::ipv4::Ipv4Packet;
 use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
 use tracing::Level;
 
 use crate::{
     common::{
-        config::NetworkIdentity,
+        config::{NetworkIdentity, VpnPortalConfig, WireGuardClientConfig},
         global_ctx::{ArcGlobalCtx, GlobalCtxEvent},
         join_joinset_background,
     },
     peers::{peer_manager::PeerManager, PeerPacketFilter},
+    proto::cli::VpnPortalClientInfo,
     tunnel::{
         mpsc::{MpscTunnel, MpscTunnelSender},
         packet_def::{PacketType, ZCPacket, ZCPacketType},
-        wireguard::{WgConfig, WgTunnelListener},
+        wireguard::{WgConfig, WgPeerHandle, WgTunnelListener},
         Tunnel, TunnelListener,
     },
 };
@@ -30,19 +36,149 @@ use crate::{
 use super::VpnPortal;
 
 type WgPeerIpTable = Arc<DashMap<Ipv4Addr, Arc<ClientEntry>>>;
+type WgClientTable = Arc<DashMap<String, Arc<WgClient>>>;
 
-pub(crate) fn get_wg_config_for_portal(nid: &NetworkIdentity) -> WgConfig {
-    let key_seed = format!(
+fn get_portal_key_seed(nid: &NetworkIdentity) -> String {
+    format!(
         "{}{}",
         nid.network_name,
         nid.network_secret.as_ref().unwrap_or(&"".to_string())
-    );
+    )
+}
+
+pub(crate) fn get_wg_config_for_portal(nid: &NetworkIdentity) -> WgConfig {
+    let key_seed = get_portal_key_seed(nid);
     WgConfig::new_for_portal(&key_seed, &key_seed)
 }
 
+// the server key is the same as the shared one, only the client key pair depends on the name
+pub(crate) fn get_wg_config_for_portal_client(nid: &NetworkIdentity, name: &str) -> WgConfig {
+    let key_seed = get_portal_key_seed(nid);
+    WgConfig::new_for_portal(&key_seed, &format!("{}{}", key_seed, name))
+}
+
+/// A named client with its own key pair and a fixed address.
+struct WgClient {
+    cfg: WireGuardClientConfig,
+    public_key: [u8; 32],
+    // unknown if the client generated its own key pair
+    private_key: Option<[u8; 32]>,
+
+    rx_bytes: AtomicU64,
+    tx_bytes: AtomicU64,
+    last_handshake: std::sync::Mutex<Option<SystemTime>>,
+
+    revoked: CancellationToken,
+}
+
+impl WgClient {
+    fn new(nid: &NetworkIdentity, cfg: WireGuardClientConfig) -> anyhow::Result<Self> {
+        let (public_key, private_key) = match &cfg.public_key {
+            Some(key) => {
+                let key = BASE64_STANDARD
+                    .decode(key)
+                    .ok()
+                    .and_then(|key| <[u8; 32]>::try_from(key).ok())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("invalid public key of wireguard client {}", cfg.name)
+                    })?;
+                (key, None)
+            }
+            None => {
+                let wg_cfg = get_wg_config_for_portal_client(nid, &cfg.name);
+                (
+                    wg_cfg.peer_public_key().try_into().unwrap(),
+                    Some(wg_cfg.peer_secret_key().try_into().unwrap()),
+                )
+            }
+        };
+
+        Ok(Self {
+            cfg,
+            public_key,
+            private_key,
+            rx_bytes: AtomicU64::new(0),
+            tx_bytes: AtomicU64::new(0),
+            last_handshake: std::sync::Mutex::new(None),
+            revoked: CancellationToken::new(),
+        })
+    }
+
+    async fn update_last_handshake(&self, peer: &WgPeerHandle) {
+        let Some(elapsed) = peer.time_since_last_handshake().await else {
+            return;
+        };
+        *self.last_handshake.lock().unwrap() = SystemTime::now().checked_sub(elapsed);
+    }
+
+    fn to_info(&self, endpoint_addr: Option<&url::Url>) -> VpnPortalClientInfo {
+        VpnPortalClientInfo {
+            name: self.cfg.name.clone(),
+            address: self.cfg.address.to_string(),
+            public_key: BASE64_STANDARD.encode(self.public_key),
+            endpoint: endpoint_addr.map(|x| x.to_string()).unwrap_or_default(),
+            rx_bytes: self.rx_bytes.load(Ordering::Relaxed),
+            tx_bytes: self.tx_bytes.load(Ordering::Relaxed),
+            last_handshake: self
+                .last_handshake
+                .lock()
+                .unwrap()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn load_wg_clients(
+    nid: &NetworkIdentity,
+    vpn_cfg: &VpnPortalConfig,
+) -> anyhow::Result<WgClientTable> {
+    let clients: WgClientTable = Arc::new(DashMap::new());
+    for (i, cfg) in vpn_cfg.wireguard_clients.iter().enumerate() {
+        if cfg.name.is_empty() {
+            anyhow::bail!("wireguard client name must not be empty");
+        }
+        if !vpn_cfg.client_cidr.contains(&cfg.address) {
+            anyhow::bail!(
+                "address {} of wireguard client {} is not in the client cidr {}",
+                cfg.address,
+                cfg.name,
+                vpn_cfg.client_cidr
+            );
+        }
+        if let Some(other) = vpn_cfg.wireguard_clients[..i]
+            .iter()
+            .find(|x| x.name == cfg.name || x.address == cfg.address)
+        {
+            anyhow::bail!(
+                "wireguard client {} conflicts with {}, names and addresses must be unique",
+                cfg.name,
+                other.name
+            );
+        }
+        if cfg.revoked {
+            continue;
+        }
+
+        let client = WgClient::new(nid, cfg.clone())?;
+        if clients.iter().any(|x| x.public_key == client.public_key) {
+            anyhow::bail!(
+                "wireguard client {} reuses the key of another client",
+                cfg.name
+            );
+        }
+        clients.insert(cfg.name.clone(), Arc::new(client));
+    }
+    Ok(clients)
+}
+
 struct ClientEntry {
     endpoint_addr: Option<url::Url>,
     sink: MpscTunnelSender,
+    // set for named clients
+    client: Option<Arc<WgClient>>,
+    peer: Option<WgPeerHandle>,
 }
 
 struct WireGuardImpl {
@@ -53,36 +189,64 @@ struct WireGuardImpl {
 
     wg_peer_ip_table: WgPeerIpTable,
 
+    // once any client is named, the shared client key is no longer accepted
+    named_clients: bool,
+    clients: WgClientTable,
+
     tasks: Arc<std::sync::Mutex<JoinSet<()>>>,
 }
 
 impl WireGuardImpl {
-    fn new(global_ctx: ArcGlobalCtx, peer_mgr: Arc<PeerManager>) -> Self {
+    fn new(global_ctx: ArcGlobalCtx, peer_mgr: Arc<PeerManager>) -> anyhow::Result<Self> {
         let nid = global_ctx.get_network_identity();
         let wg_config = get_wg_config_for_portal(&nid);
 
         let vpn_cfg = global_ctx.config.get_vpn_portal_config().unwrap();
-        let listener_addr = vpn_cfg.wireguard_listen;
+        let listener_addr = vpn_cfg
+            .wireguard_listen
+            .ok_or_else(|| anyhow::anyhow!("wireguard listen address not set"))?;
+        let clients = load_wg_clients(&nid, &vpn_cfg)?;
 
-        Self {
+        Ok(Self {
             global_ctx,
             peer_mgr,
             wg_config,
             listener_addr,
             wg_peer_ip_table: Arc::new(DashMap::new()),
+            named_clients: !vpn_cfg.wireguard_clients.is_empty(),
+            clients,
             tasks: Arc::new(std::sync::Mutex::new(JoinSet::new())),
-        }
+        })
+    }
+
+    // find the named client behind an accepted tunnel by the key of its handshake
+    fn find_client(
+        l: &WgTunnelListener,
+        t: &dyn Tunnel,
+        clients: &WgClientTable,
+    ) -> Option<(Arc<WgClient>, WgPeerHandle)> {
+        let remote_addr: url::Url = t.info()?.remote_addr?.into();
+        let endpoint = remote_addr.socket_addrs(|| None).ok()?.into_iter().next()?;
+        let peer = l.get_peer_handle(&endpoint)?;
+        let client = clients
+            .iter()
+            .find(|x| x.public_key == *peer.public_key())?
+            .value()
+            .clone();
+        Some((client, peer))
     }
 
     async fn handle_incoming_conn(
         t: Box<dyn Tunnel>,
         peer_mgr: Arc<PeerManager>,
         wg_peer_ip_table: WgPeerIpTable,
+        named_client: Option<(Arc<WgClient>, WgPeerHandle)>,
     ) {
         let info = t.info().unwrap_or_default();
         let mut mpsc_tunnel = MpscTunnel::new(t, None);
         let mut stream = mpsc_tunnel.get_stream();
         let mut ip_registered = false;
+        let (client, peer) = named_client.unzip();
 
         let remote_addr = info.remote_addr.clone();
         let endpoint_addr = remote_addr.clone().map(Into::into);
@@ -95,15 +259,38 @@ impl WireGuardImpl {
 
         let mut map_key = None;
 
+        // named clients always use their fixed address, so it is registered before the first packet
+        if let Some(client) = &client {
+            let client_entry = Arc::new(ClientEntry {
+                endpoint_addr: endpoint_addr.clone(),
+                sink: mpsc_tunnel.get_sink(),
+                client: Some(client.clone()),
+                peer: peer.clone(),
+            });
+            map_key = Some(client.cfg.address);
+            wg_peer_ip_table.insert(client.cfg.address, client_entry);
+            ip_registered = true;
+        }
+        let revoked = client
+            .as_ref()
+            .map(|x| x.revoked.clone())
+            .unwrap_or_default();
+
         loop {
-            let msg = match stream.next().await {
-                Some(Ok(msg)) => msg,
-                Some(Err(err)) => {
-                    tracing::error!(?err, "Failed to receive from wg client");
-                    break;
-                }
-                None => {
-                    tracing::info!("Wireguard client disconnected");
+            let msg = tokio::select! {
+                msg = stream.next() => match msg {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(err)) => {
+                        tracing::error!(?err, "Failed to receive from wg client");
+                        break;
+                    }
+                    None => {
+                        tracing::info!("Wireguard client disconnected");
+                        break;
+                    }
+                },
+                _ = revoked.cancelled() => {
+                    tracing::info!(?remote_addr, "Wireguard client revoked, closing");
                     break;
                 }
             };
@@ -114,10 +301,21 @@ impl WireGuardImpl {
                 tracing::error!(?inner, "Failed to parse ipv4 packet");
                 continue;
             };
+            if let Some(client) = &client {
+                if i.get_source() != client.cfg.address {
+                    tracing::trace!(?i, "Dropping packet not from the address of the wg client");
+                    continue;
+                }
+                client
+                    .rx_bytes
+                    .fetch_add(inner.len() as u64, Ordering::Relaxed);
+            }
             if !ip_registered {
                 let client_entry = Arc::new(ClientEntry {
                     endpoint_addr: endpoint_addr.clone(),
                     sink: mpsc_tunnel.get_sink(),
+                    client: None,
+                    peer: None,
                 });
                 map_key = Some(i.get_source());
                 // Be careful here: we may overwrite an existing entry if the client IP is reused,
@@ -146,6 +344,10 @@ impl WireGuardImpl {
             }
         }
 
+        if let (Some(client), Some(peer)) = (&client, &peer) {
+            client.update_last_handshake(peer).await;
+        }
+
         peer_mgr
             .get_global_ctx()
             .issue_event(GlobalCtxEvent::VpnPortalClientDisconnected(
@@ -168,6 +370,7 @@ impl WireGuardImpl {
                 };
 
                 let payload_bytes = packet.payload();
+                let payload_len = payload_bytes.len();
                 let ipv4 = Ipv4Packet::new(payload_bytes)?;
                 if ipv4.get_version() != 4 {
                     return Some(packet);
@@ -192,6 +395,11 @@ impl WireGuardImpl {
                 match entry.sink.try_send(packet) {
                     Ok(_) => {
                         tracing::trace!("Sent packet to wg client");
+                        if let Some(client) = &entry.client {
+                            client
+                                .tx_bytes
+                                .fetch_add(payload_len as u64, Ordering::Relaxed);
+                        }
                     }
                     Err(e) => {
                         tracing::debug!(?e, "Failed to send packet to wg client");
@@ -214,6 +422,13 @@ impl WireGuardImpl {
         listener_url.set_port(Some(listener_addr.port())).unwrap();
         listener_url.set_ip_host(listener_addr.ip()).unwrap();
         let mut l = WgTunnelListener::new(listener_url.clone(), self.wg_config.clone());
+        if self.named_clients {
+            // revoked clients are removed from the table, so they fail their next handshake
+            let clients = self.clients.clone();
+            l.set_peer_authorizer(Arc::new(move |key| {
+                clients.iter().any(|x| x.public_key == *key)
+            }));
+        }
 
         tracing::info!("Wireguard VPN Portal Starting");
 
@@ -226,15 +441,27 @@ impl WireGuardImpl {
         let tasks = Arc::downgrade(&self.tasks.clone());
         let peer_mgr = self.peer_mgr.clone();
         let wg_peer_ip_table = self.wg_peer_ip_table.clone();
+        let named_clients = self.named_clients;
+        let clients = self.clients.clone();
         self.tasks.lock().unwrap().spawn(async move {
             while let Ok(t) = l.accept().await {
                 let Some(tasks) = tasks.upgrade() else {
                     break;
                 };
+                let named_client = if named_clients {
+                    let Some(named_client) = Self::find_client(&l, t.as_ref(), &clients) else {
+                        tracing::info!(info = ?t.info(), "Wireguard client revoked before accepted");
+                        continue;
+                    };
+                    Some(named_client)
+                } else {
+                    None
+                };
                 tasks.lock().unwrap().spawn(Self::handle_incoming_conn(
                     t,
                     peer_mgr.clone(),
                     wg_peer_ip_table.clone(),
+                    named_client,
                 ));
             }
         });
@@ -290,7 +517,7 @@ impl VpnPortal for WireGuard {
             anyhow::bail!("vpn cfg is not set for wireguard vpn portal");
         }
 
-        let inner = WireGuardImpl::new(global_ctx, peer_mgr);
+        let inner = WireGuardImpl::new(global_ctx, peer_mgr)?;
         inner.start().await?;
         self.inner = Some(inner);
         Ok(())
@@ -331,12 +558,14 @@ impl VpnPortal for WireGuard {
             .collect::<Vec<_>>()
             .join(",");
 
-        let cfg = self.inner.as_ref().unwrap().wg_config.clone();
-        let cfg_str = format!(
-            r#"
+        let inner = self.inner.as_ref().unwrap();
+        let cfg = inner.wg_config.clone();
+        let format_cfg = |peer_secret_key: String, address: String| {
+            format!(
+                r#"
 [Interface]
 PrivateKey = {peer_secret_key}
-Address = {address} # should assign an ip from this cidr manually
+Address = {address}
 
 [Peer]
 PublicKey = {my_public_key}
@@ -344,14 +573,43 @@ AllowedIPs = {allow_ips}
 Endpoint = {listenr_addr} # should be the public ip(or domain) of the vpn server
 PersistentKeepalive = 25
 "#,
-            peer_secret_key = BASE64_STANDARD.encode(cfg.peer_secret_key()),
-            my_public_key = BASE64_STANDARD.encode(cfg.my_public_key()),
-            listenr_addr = self.inner.as_ref().unwrap().listener_addr,
-            allow_ips = allow_ips,
-            address = client_cidr.first_address().to_string() + "/32",
-        );
-
-        cfg_str
+                peer_secret_key = peer_secret_key,
+                my_public_key = BASE64_STANDARD.encode(cfg.my_public_key()),
+                listenr_addr = inner.listener_addr,
+                allow_ips = allow_ips,
+                address = address,
+            )
+        };
+
+        if !inner.named_clients {
+            return format_cfg(
+                BASE64_STANDARD.encode(cfg.peer_secret_key()),
+                client_cidr.first_address().to_string()
+                    + "/32 # should assign an ip from this cidr manually",
+            );
+        }
+
+        let mut clients = inner
+            .clients
+            .iter()
+            .map(|x| x.value().clone())
+            .collect::<Vec<_>>();
+        clients.sort_by(|a, b| a.cfg.name.cmp(&b.cfg.name));
+        clients
+            .iter()
+            .map(|client| {
+                let peer_secret_key = client
+                    .private_key
+                    .map(|key| BASE64_STANDARD.encode(key))
+                    .unwrap_or_else(|| "<private key generated by the client>".to_string());
+                format!(
+                    "# client: {}{}",
+                    client.cfg.name,
+                    format_cfg(peer_secret_key, format!("{}/32", client.cfg.address))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 
     fn name(&self) -> String {
@@ -365,14 +623,71 @@ PersistentKeepalive = 25
                 w.wg_peer_ip_table
                     .iter()
                     .map(|x| {
-                        x.value()
+                        let endpoint_addr = x
+                            .value()
                             .endpoint_addr
                             .as_ref()
                             .map(|x| x.to_string())
-                            .unwrap_or_default()
+                            .unwrap_or_default();
+                        match &x.value().client {
+                            Some(client) => {
+                                format!("{} {} {}", client.cfg.name, x.key(), endpoint_addr)
+                            }
+                            None => endpoint_addr,
+                        }
                     })
                     .collect()
             })
             .unwrap_or_default()
     }
+
+    async fn list_client_infos(&self) -> Vec<VpnPortalClientInfo> {
+        let Some(inner) = self.inner.as_ref() else {
+            return vec![];
+        };
+
+        let clients = inner
+            .clients
+            .iter()
+            .map(|x| x.value().clone())
+            .collect::<Vec<_>>();
+        let mut infos = Vec::with_capacity(clients.len());
+        for client in clients {
+            let entry = inner
+                .wg_peer_ip_table
+                .get(&client.cfg.address)
+                .map(|x| x.value().clone())
+                .filter(|x| x.client.as_ref().is_some_and(|x| Arc::ptr_eq(x, &client)));
+            if let Some(peer) = entry.as_ref().and_then(|x| x.peer.as_ref()) {
+                client.update_last_handshake(peer).await;
+            }
+            infos.push(client.to_info(entry.as_ref().and_then(|x| x.endpoint_addr.as_ref())));
+        }
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        infos
+    }
+
+    async fn revoke_client(&self, name: &str) -> anyhow::Result<()> {
+        let Some(inner) = self.inner.as_ref() else {
+            anyhow::bail!("wireguard vpn portal not started");
+        };
+        let Some((_, client)) = inner.clients.remove(name) else {
+            anyhow::bail!("wireguard client not found: {}", name);
+        };
+        client.revoked.cancel();
+
+        // keep the client in the running config, so its address is not reused
+        let global_ctx = &inner.global_ctx;
+        if let Some(mut vpn_cfg) = global_ctx.config.get_vpn_portal_config() {
+            vpn_cfg
+                .wireguard_clients
+                .iter_mut()
+                .filter(|x| x.name == name)
+                .for_each(|x| x.revoked = true);
+            global_ctx.config.set_vpn_portal_config(vpn_cfg);
+        }
+
+        tracing::info!(name, "Revoked wireguard client");
+        Ok(())
+    }
 }
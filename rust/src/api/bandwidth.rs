@@ -0,0 +1,116 @@
+use crate::api::simple::{instance_rpc_client, RT};
+use easytier::proto::{
+    cli::{
+        BandwidthLimitManageRpc, BandwidthLimitManageRpcClientFactory, ListBandwidthLimitRequest,
+        RemoveBandwidthLimitRequest, SetBandwidthLimitRequest,
+    },
+    common::BandwidthLimitPb,
+    rpc_types::controller::BaseController,
+};
+
+// 带宽限制，peer_id 和 cidr 都为空时限制整个本机
+#[derive(Debug, Clone)]
+pub struct AstralBandwidthLimit {
+    pub peer_id: Option<u32>,
+    pub cidr: Option<String>,
+    // 字节每秒，为空或 0 表示不限制
+    pub upload_bps: Option<u64>,
+    pub download_bps: Option<u64>,
+}
+
+impl From<AstralBandwidthLimit> for BandwidthLimitPb {
+    fn from(l: AstralBandwidthLimit) -> Self {
+        BandwidthLimitPb {
+            peer_id: l.peer_id,
+            cidr: l.cidr,
+            upload_bps: l.upload_bps,
+            download_bps: l.download_bps,
+        }
+    }
+}
+
+impl From<BandwidthLimitPb> for AstralBandwidthLimit {
+    fn from(l: BandwidthLimitPb) -> Self {
+        AstralBandwidthLimit {
+            peer_id: l.peer_id,
+            cidr: l.cidr,
+            upload_bps: l.upload_bps,
+            download_bps: l.download_bps,
+        }
+    }
+}
+
+// 设置带宽限制，相同目标的旧限制会被替换
+pub async fn set_bandwidth_limit(
+    instance_id: String,
+    limit: AstralBandwidthLimit,
+) -> Result<(), String> {
+    RT.spawn(async move {
+        let mut client = instance_rpc_client(&instance_id)?;
+        let rpc = client
+            .scoped_client::<BandwidthLimitManageRpcClientFactory<BaseController>>(String::new())
+            .await
+            .map_err(|e| format!("连接实例RPC失败: {}", e))?;
+        rpc.set_bandwidth_limit(
+            BaseController::default(),
+            SetBandwidthLimitRequest {
+                limit: Some(limit.into()),
+            },
+        )
+        .await
+        .map_err(|e| format!("设置带宽限制失败: {}", e))?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// 移除指定目标的带宽限制
+pub async fn remove_bandwidth_limit(
+    instance_id: String,
+    peer_id: Option<u32>,
+    cidr: Option<String>,
+) -> Result<(), String> {
+    RT.spawn(async move {
+        let mut client = instance_rpc_client(&instance_id)?;
+        let rpc = client
+            .scoped_client::<BandwidthLimitManageRpcClientFactory<BaseController>>(String::new())
+            .await
+            .map_err(|e| format!("连接实例RPC失败: {}", e))?;
+        rpc.remove_bandwidth_limit(
+            BaseController::default(),
+            RemoveBandwidthLimitRequest {
+                limit: Some(BandwidthLimitPb {
+                    peer_id,
+                    cidr,
+                    ..Default::default()
+                }),
+            },
+        )
+        .await
+        .map_err(|e| format!("移除带宽限制失败: {}", e))?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// 列出实例当前的带宽限制
+pub async fn list_bandwidth_limits(
+    instance_id: String,
+) -> Result<Vec<AstralBandwidthLimit>, String> {
+    RT.spawn(async move {
+        let mut client = instance_rpc_client(&instance_id)?;
+        let rpc = client
+            .scoped_client::<BandwidthLimitManageRpcClientFactory<BaseController>>(String::new())
+            .await
+            .map_err(|e| format!("连接实例RPC失败: {}", e))?;
+        let resp = rpc
+            .list_bandwidth_limit(BaseController::default(), ListBandwidthLimitRequest {})
+            .await
+            .map_err(|e| format!("获取带宽限制失败: {}", e))?;
+        Ok(resp.limits.into_iter().map(Into::into).collect())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
@@ -0,0 +1,305 @@
+use crate::frb_generated::StreamSink;
+use easytier::{
+    common::{
+        config::PortForwardConfig,
+        global_ctx::{EventBusSubscriber, GlobalCtxEvent},
+    },
+    proto::cli::PeerConnInfo,
+};
+use lazy_static::lazy_static;
+use std::sync::Mutex;
+use tokio::sync::broadcast::error::RecvError;
+
+lazy_static! {
+    // Flutter 端订阅的事件流，发送失败时自动移除
+    static ref EVENT_SINKS: Mutex<Vec<StreamSink<AstralEvent>>> = Mutex::new(Vec::new());
+}
+
+// 节点连接信息
+#[derive(Debug, Clone)]
+pub struct AstralPeerConn {
+    pub conn_id: String,
+    pub my_peer_id: u32,
+    pub peer_id: u32,
+    pub tunnel_type: String,
+    pub local_addr: String,
+    pub remote_addr: String,
+    pub is_client: bool,
+    pub network_name: String,
+}
+
+// 端口转发配置
+#[derive(Debug, Clone)]
+pub struct AstralPortForward {
+    pub bind_addr: String,
+    pub dst_addr: String,
+    pub proto: String,
+}
+
+// 与 GlobalCtxEvent 一一对应的事件类型，事件数据放在 AstralEvent 的可选字段中
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AstralEventKind {
+    TunDeviceReady,
+    TunDeviceError,
+    PeerAdded,
+    PeerRemoved,
+    PeerConnAdded,
+    PeerConnRemoved,
+    ListenerAdded,
+    ListenerAddFailed,
+    ListenerAcceptFailed,
+    ConnectionAccepted,
+    ConnectionError,
+    Connecting,
+    ConnectError,
+    VpnPortalStarted,
+    VpnPortalClientConnected,
+    VpnPortalClientDisconnected,
+    DhcpIpv4Changed,
+    DhcpIpv4Conflicted,
+    PortForwardAdded,
+    NetworkSecretRotated,
+    // 出口节点切换，old_addr/new_addr 为 None 表示没有可用的出口节点
+    ExitNodeChanged,
+    ExitNodeDown,
+    // 事件处理滞后，丢失了 count 个事件
+    EventsLagged,
+    // 实例的事件通道已关闭
+    EventChannelClosed,
+}
+
+// 推送给 Flutter 的事件，只有与 kind 相关的字段有值
+#[derive(Debug, Clone)]
+pub struct AstralEvent {
+    pub instance_id: String,
+    pub timestamp_ms: i64,
+    pub kind: AstralEventKind,
+    pub peer_id: Option<u32>,
+    pub conn: Option<AstralPeerConn>,
+    pub dev_name: Option<String>,
+    // 监听器或连接目标的地址
+    pub url: Option<String>,
+    pub ip_version: Option<String>,
+    pub local_addr: Option<String>,
+    pub remote_addr: Option<String>,
+    pub portal: Option<String>,
+    pub client_addr: Option<String>,
+    // DHCP 或出口节点切换前后的地址
+    pub old_addr: Option<String>,
+    pub new_addr: Option<String>,
+    // 冲突的 DHCP 地址或故障的出口节点
+    pub addr: Option<String>,
+    pub forward: Option<AstralPortForward>,
+    pub rotation_id: Option<u64>,
+    pub count: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl From<PeerConnInfo> for AstralPeerConn {
+    fn from(p: PeerConnInfo) -> Self {
+        let (tunnel_type, local_addr, remote_addr) = p
+            .tunnel
+            .map(|t| {
+                (
+                    t.tunnel_type,
+                    t.local_addr.map(|a| a.url).unwrap_or_default(),
+                    t.remote_addr.map(|a| a.url).unwrap_or_default(),
+                )
+            })
+            .unwrap_or_default();
+        AstralPeerConn {
+            conn_id: p.conn_id,
+            my_peer_id: p.my_peer_id,
+            peer_id: p.peer_id,
+            tunnel_type,
+            local_addr,
+            remote_addr,
+            is_client: p.is_client,
+            network_name: p.network_name,
+        }
+    }
+}
+
+fn new_event(instance_id: &str, kind: AstralEventKind) -> AstralEvent {
+    AstralEvent {
+        instance_id: instance_id.to_string(),
+        timestamp_ms: chrono::Local::now().timestamp_millis(),
+        kind,
+        peer_id: None,
+        conn: None,
+        dev_name: None,
+        url: None,
+        ip_version: None,
+        local_addr: None,
+        remote_addr: None,
+        portal: None,
+        client_addr: None,
+        old_addr: None,
+        new_addr: None,
+        addr: None,
+        forward: None,
+        rotation_id: None,
+        count: None,
+        error: None,
+    }
+}
+
+fn convert_event(instance_id: &str, e: GlobalCtxEvent) -> AstralEvent {
+    use AstralEventKind as K;
+    match e {
+        GlobalCtxEvent::TunDeviceReady(dev_name) => AstralEvent {
+            dev_name: Some(dev_name),
+            ..new_event(instance_id, K::TunDeviceReady)
+        },
+        GlobalCtxEvent::TunDeviceError(error) => AstralEvent {
+            error: Some(error),
+            ..new_event(instance_id, K::TunDeviceError)
+        },
+        GlobalCtxEvent::PeerAdded(peer_id) => AstralEvent {
+            peer_id: Some(peer_id),
+            ..new_event(instance_id, K::PeerAdded)
+        },
+        GlobalCtxEvent::PeerRemoved(peer_id) => AstralEvent {
+            peer_id: Some(peer_id),
+            ..new_event(instance_id, K::PeerRemoved)
+        },
+        GlobalCtxEvent::PeerConnAdded(p) => AstralEvent {
+            peer_id: Some(p.peer_id),
+            conn: Some(p.into()),
+            ..new_event(instance_id, K::PeerConnAdded)
+        },
+        GlobalCtxEvent::PeerConnRemoved(p) => AstralEvent {
+            peer_id: Some(p.peer_id),
+            conn: Some(p.into()),
+            ..new_event(instance_id, K::PeerConnRemoved)
+        },
+        GlobalCtxEvent::ListenerAdded(url) => AstralEvent {
+            url: Some(url.to_string()),
+            ..new_event(instance_id, K::ListenerAdded)
+        },
+        GlobalCtxEvent::ListenerAddFailed(url, error) => AstralEvent {
+            url: Some(url.to_string()),
+            error: Some(error),
+            ..new_event(instance_id, K::ListenerAddFailed)
+        },
+        GlobalCtxEvent::ListenerAcceptFailed(url, error) => AstralEvent {
+            url: Some(url.to_string()),
+            error: Some(error),
+            ..new_event(instance_id, K::ListenerAcceptFailed)
+        },
+        GlobalCtxEvent::ConnectionAccepted(local_addr, remote_addr) => AstralEvent {
+            local_addr: Some(local_addr),
+            remote_addr: Some(remote_addr),
+            ..new_event(instance_id, K::ConnectionAccepted)
+        },
+        GlobalCtxEvent::ConnectionError(local_addr, remote_addr, error) => AstralEvent {
+            local_addr: Some(local_addr),
+            remote_addr: Some(remote_addr),
+            error: Some(error),
+            ..new_event(instance_id, K::ConnectionError)
+        },
+        GlobalCtxEvent::Connecting(dst) => AstralEvent {
+            url: Some(dst.to_string()),
+            ..new_event(instance_id, K::Connecting)
+        },
+        GlobalCtxEvent::ConnectError(dst_url, ip_version, error) => AstralEvent {
+            url: Some(dst_url),
+            ip_version: Some(ip_version),
+            error: Some(error),
+            ..new_event(instance_id, K::ConnectError)
+        },
+        GlobalCtxEvent::VpnPortalStarted(portal) => AstralEvent {
+            portal: Some(portal),
+            ..new_event(instance_id, K::VpnPortalStarted)
+        },
+        GlobalCtxEvent::VpnPortalClientConnected(portal, client_addr) => AstralEvent {
+            portal: Some(portal),
+            client_addr: Some(client_addr),
+            ..new_event(instance_id, K::VpnPortalClientConnected)
+        },
+        GlobalCtxEvent::VpnPortalClientDisconnected(portal, client_addr) => AstralEvent {
+            portal: Some(portal),
+            client_addr: Some(client_addr),
+            ..new_event(instance_id, K::VpnPortalClientDisconnected)
+        },
+        GlobalCtxEvent::DhcpIpv4Changed(old, new) => AstralEvent {
+            old_addr: old.map(|ip| ip.to_string()),
+            new_addr: new.map(|ip| ip.to_string()),
+            ..new_event(instance_id, K::DhcpIpv4Changed)
+        },
+        GlobalCtxEvent::DhcpIpv4Conflicted(ip) => AstralEvent {
+            addr: ip.map(|ip| ip.to_string()),
+            ..new_event(instance_id, K::DhcpIpv4Conflicted)
+        },
+        GlobalCtxEvent::PortForwardAdded(cfg) => {
+            let cfg = PortForwardConfig::from(cfg);
+            AstralEvent {
+                forward: Some(AstralPortForward {
+                    bind_addr: cfg.bind_addr.to_string(),
+                    dst_addr: cfg.dst_addr.to_string(),
+                    proto: cfg.proto,
+                }),
+                ..new_event(instance_id, K::PortForwardAdded)
+            }
+        }
+        GlobalCtxEvent::NetworkSecretRotated(rotation_id) => AstralEvent {
+            rotation_id: Some(rotation_id),
+            ..new_event(instance_id, K::NetworkSecretRotated)
+        },
+        GlobalCtxEvent::ExitNodeChanged(old, new) => AstralEvent {
+            old_addr: old.map(|addr| addr.to_string()),
+            new_addr: new.map(|addr| addr.to_string()),
+            ..new_event(instance_id, K::ExitNodeChanged)
+        },
+        GlobalCtxEvent::ExitNodeDown(addr, error) => AstralEvent {
+            addr: Some(addr.to_string()),
+            error: Some(error),
+            ..new_event(instance_id, K::ExitNodeDown)
+        },
+    }
+}
+
+// 订阅所有实例的事件，可多次调用以建立多个订阅
+pub fn subscribe_events(sink: StreamSink<AstralEvent>) {
+    EVENT_SINKS.lock().unwrap().push(sink);
+}
+
+fn dispatch_event(event: AstralEvent) {
+    // 发送失败说明 Dart 端已取消订阅
+    EVENT_SINKS
+        .lock()
+        .unwrap()
+        .retain(|sink| sink.add(event.clone()).is_ok());
+}
+
+pub(crate) fn handle_event(
+    instance_id: String,
+    mut events: EventBusSubscriber,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(e) => {
+                    if let GlobalCtxEvent::ListenerAdded(p) = &e {
+                        if p.scheme() == "ring" {
+                            continue;
+                        }
+                    }
+                    dispatch_event(convert_event(&instance_id, e));
+                }
+                Err(RecvError::Closed) => {
+                    println!("事件通道已关闭，停止事件处理。实例: {}", instance_id);
+                    dispatch_event(new_event(&instance_id, AstralEventKind::EventChannelClosed));
+                    break;
+                }
+                Err(RecvError::Lagged(n)) => {
+                    eprintln!("事件处理滞后，丢失了 {} 个事件。实例: {}", n, instance_id);
+                    dispatch_event(AstralEvent {
+                        count: Some(n),
+                        ..new_event(&instance_id, AstralEventKind::EventsLagged)
+                    });
+                }
+            }
+        }
+    })
+}
@@ -1,74 +1,99 @@
 use std::io;
-#[cfg(target_os = "windows")]
-use std::process::Command;
 
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
+use easytier::common::error::Error;
+use easytier::common::ifcfg::{IfConfiger, IfConfiguerTrait, InterfaceInfo};
 
-#[cfg(target_os = "windows")]
-pub fn get_all_interfaces_metrics() -> io::Result<Vec<(String, u32)>> {
-    // 设置UTF-8代码页并执行netsh命令
-    let output = Command::new("cmd")
-        .args(&["/c", "chcp 65001 >nul && netsh interface ipv4 show interfaces"])
-        .creation_flags(0x08000000) // CREATE_NO_WINDOW
-        .output()?;
+use crate::api::simple::RT;
 
-    if !output.status.success() {
-        let error_msg = String::from_utf8(output.stderr)
-            .unwrap_or_else(|e| String::from_utf8_lossy(&e.into_bytes()).to_string());
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            error_msg,
-        ));
-    }
+// 广播优先的网卡使用的跃点数
+const PREFERRED_METRIC: u32 = 1;
 
-    // 尝试使用UTF-8解码，失败则使用lossy转换
-    let output_str = String::from_utf8(output.stdout)
-        .unwrap_or_else(|e| String::from_utf8_lossy(&e.into_bytes()).to_string());
-    
-    let mut interfaces = Vec::new();
+/// 网卡信息
+pub struct AstralInterface {
+    pub index: u32,
+    pub name: String,
+    // Windows 上是网卡的 IPv4 跃点数，Linux 上是经过该网卡的路由中最小的跃点数
+    pub metric: Option<u32>,
+    pub mtu: u32,
+    // CIDR 格式，例如 10.126.126.1/24
+    pub addresses: Vec<String>,
+    pub up: bool,
+}
 
-    for line in output_str.lines().skip(3) { // 跳过前三行表头
-        if line.trim().is_empty() {
-            continue;
+impl From<InterfaceInfo> for AstralInterface {
+    fn from(info: InterfaceInfo) -> Self {
+        Self {
+            index: info.index,
+            name: info.name,
+            metric: info.metric,
+            mtu: info.mtu,
+            addresses: info.addresses.iter().map(|a| a.to_string()).collect(),
+            up: info.up,
         }
+    }
+}
 
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 5 {
-            let name = parts[4..].join(" ");
-            let metric = parts[1].parse::<u32>().unwrap_or(0);
-            interfaces.push((name, metric));
-        }
+fn to_io_error(e: Error) -> io::Error {
+    match e {
+        Error::IOError(e) => e,
+        e => io::Error::new(io::ErrorKind::Other, e.to_string()),
     }
+}
+
+fn list_interfaces() -> io::Result<Vec<InterfaceInfo>> {
+    RT.block_on(async { IfConfiger {}.list_interfaces().await })
+        .map_err(to_io_error)
+}
 
-    Ok(interfaces)
+// 获取所有网卡的信息，Windows 使用 IpHelper，Linux 使用 netlink
+pub fn list_network_interfaces() -> io::Result<Vec<AstralInterface>> {
+    Ok(list_interfaces()?.into_iter().map(Into::into).collect())
 }
-#[cfg(not(target_os = "windows"))]
+
+// 获取所有网卡的跃点数，没有跃点数的网卡不会返回
 pub fn get_all_interfaces_metrics() -> io::Result<Vec<(String, u32)>> {
-    Ok(Vec::new())
+    Ok(list_interfaces()?
+        .into_iter()
+        .filter_map(|i| Some((i.name, i.metric?)))
+        .collect())
 }
 
-#[cfg(target_os = "windows")]
+// 设置网卡的跃点数，Linux 上会修改经过该网卡的所有 IPv4 路由的跃点数
 pub fn set_interface_metric(interface_name: &str, metric: u32) -> io::Result<()> {
-    let cmd = format!("chcp 65001 >nul && netsh interface ipv4 set interface \"{}\" metric={}", interface_name, metric);
-    let output = Command::new("cmd")
-        .args(&["/c", &cmd])
-        .creation_flags(0x08000000) // CREATE_NO_WINDOW
-        .output()?;
+    RT.block_on(async {
+        IfConfiger {}
+            .set_interface_metric(interface_name, metric)
+            .await
+    })
+    .map_err(to_io_error)
+}
+
+// 让游戏的局域网广播 (255.255.255.255) 从指定网卡发出，通常是 astral 虚拟网卡
+// 系统只会从一个网卡发出这种广播：Windows 选择跃点数最小的网卡，Linux 按路由表选择，
+// 所以 Linux 上还会添加一条经过该网卡的广播路由
+pub fn prefer_interface_for_broadcast(interface_name: &str) -> io::Result<()> {
+    RT.block_on(async {
+        let ifcfg = IfConfiger {};
+        ifcfg
+            .set_interface_metric(interface_name, PREFERRED_METRIC)
+            .await?;
+
+        #[cfg(target_os = "linux")]
+        match ifcfg
+            .add_ipv4_route(
+                interface_name,
+                std::net::Ipv4Addr::BROADCAST,
+                32,
+                Some(PREFERRED_METRIC as i32),
+            )
+            .await
+        {
+            // 路由已经存在
+            Err(Error::IOError(e)) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            r => r?,
+        }
 
-    if output.status.success() {
         Ok(())
-    } else {
-        let error_msg = String::from_utf8(output.stderr.clone())
-            .unwrap_or_else(|_| String::from_utf8_lossy(&output.stderr).to_string());
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            error_msg,
-        ))
-    }
+    })
+    .map_err(to_io_error)
 }
-
-#[cfg(not(target_os = "windows"))]
-pub fn set_interface_metric(_interface_name: &str, _metric: u32) -> io::Result<()> {
-    Ok(())
-}
\ No newline at end of file
@@ -1,6 +1,10 @@
 pub mod simple;
+pub mod events;
+pub mod bandwidth;
+pub mod port_forward;
 pub mod firewall;
 pub mod hops;
 pub mod astral_wfp;
 pub mod nt;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
+pub mod secret_rotation;
\ No newline at end of file
@@ -0,0 +1,137 @@
+use crate::api::simple::{instance_rpc_client, Forward, RT};
+use easytier::{
+    common::config::PortForwardConfig,
+    proto::{
+        cli::{
+            AddPortForwardRequest, ListPortForwardRequest, PortForwardManageRpc,
+            PortForwardManageRpcClientFactory, RemovePortForwardRequest,
+        },
+        rpc_types::controller::BaseController,
+    },
+};
+use std::net::SocketAddr;
+
+// 端口转发的运行状态
+#[derive(Debug, Clone)]
+pub struct AstralPortForwardStatus {
+    pub bind_addr: String,
+    pub dst_addr: String,
+    pub proto: String,
+    // 是否已成功绑定本地端口
+    pub bound: bool,
+    // 绑定失败的原因
+    pub error: Option<String>,
+    // UDP 为当前客户端会话数
+    pub active_connections: u32,
+    pub total_connections: u64,
+}
+
+// 校验并转换端口转发配置，避免非法输入导致 panic
+pub(crate) fn parse_forward(forward: &Forward) -> Result<PortForwardConfig, String> {
+    let bind_addr: SocketAddr = forward
+        .bind_addr
+        .trim()
+        .parse()
+        .map_err(|e| format!("无效的绑定地址: {}, 错误: {}", forward.bind_addr, e))?;
+    let dst_addr: SocketAddr = forward
+        .dst_addr
+        .trim()
+        .parse()
+        .map_err(|e| format!("无效的目标地址: {}, 错误: {}", forward.dst_addr, e))?;
+    if bind_addr.port() == 0 || dst_addr.port() == 0 {
+        return Err(format!(
+            "端口不能为 0: {} -> {}",
+            forward.bind_addr, forward.dst_addr
+        ));
+    }
+    let proto = forward.proto.trim().to_lowercase();
+    if proto != "tcp" && proto != "udp" {
+        return Err(format!("不支持的协议: {}，仅支持 tcp / udp", forward.proto));
+    }
+    Ok(PortForwardConfig {
+        bind_addr,
+        dst_addr,
+        proto,
+    })
+}
+
+// 为运行中的实例添加端口转发
+pub async fn add_port_forward(instance_id: String, forward: Forward) -> Result<(), String> {
+    let cfg = parse_forward(&forward)?;
+    RT.spawn(async move {
+        let mut client = instance_rpc_client(&instance_id)?;
+        let rpc = client
+            .scoped_client::<PortForwardManageRpcClientFactory<BaseController>>(String::new())
+            .await
+            .map_err(|e| format!("连接实例RPC失败: {}", e))?;
+        rpc.add_port_forward(
+            BaseController::default(),
+            AddPortForwardRequest {
+                cfg: Some(cfg.into()),
+            },
+        )
+        .await
+        .map_err(|e| format!("添加端口转发失败: {}", e))?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// 移除运行中实例的端口转发
+pub async fn remove_port_forward(instance_id: String, forward: Forward) -> Result<(), String> {
+    let cfg = parse_forward(&forward)?;
+    RT.spawn(async move {
+        let mut client = instance_rpc_client(&instance_id)?;
+        let rpc = client
+            .scoped_client::<PortForwardManageRpcClientFactory<BaseController>>(String::new())
+            .await
+            .map_err(|e| format!("连接实例RPC失败: {}", e))?;
+        rpc.remove_port_forward(
+            BaseController::default(),
+            RemovePortForwardRequest {
+                cfg: Some(cfg.into()),
+            },
+        )
+        .await
+        .map_err(|e| format!("移除端口转发失败: {}", e))?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// 列出实例的端口转发及其运行状态
+pub async fn list_port_forwards(
+    instance_id: String,
+) -> Result<Vec<AstralPortForwardStatus>, String> {
+    RT.spawn(async move {
+        let mut client = instance_rpc_client(&instance_id)?;
+        let rpc = client
+            .scoped_client::<PortForwardManageRpcClientFactory<BaseController>>(String::new())
+            .await
+            .map_err(|e| format!("连接实例RPC失败: {}", e))?;
+        let resp = rpc
+            .list_port_forward(BaseController::default(), ListPortForwardRequest {})
+            .await
+            .map_err(|e| format!("获取端口转发失败: {}", e))?;
+        Ok(resp
+            .statuses
+            .into_iter()
+            .map(|s| {
+                let cfg = PortForwardConfig::from(s.cfg.unwrap_or_default());
+                AstralPortForwardStatus {
+                    bind_addr: cfg.bind_addr.to_string(),
+                    dst_addr: cfg.dst_addr.to_string(),
+                    proto: cfg.proto,
+                    bound: s.bound,
+                    error: s.error,
+                    active_connections: s.active_connections,
+                    total_connections: s.total_connections,
+                }
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
@@ -0,0 +1,102 @@
+use crate::api::simple::{instance_rpc_client, RT};
+use easytier::proto::{
+    cli::{
+        GetSecretRotationStatusRequest, SecretRotationManageRpc,
+        SecretRotationManageRpcClientFactory, StartSecretRotationRequest,
+    },
+    rpc_types::controller::BaseController,
+};
+
+// 单个成员的密钥轮换进度
+#[derive(Debug, Clone)]
+pub struct AstralSecretRotationPeer {
+    pub peer_id: u32,
+    pub hostname: String,
+    // 已收到新密钥
+    pub prepared: bool,
+    // 已切换到新密钥
+    pub committed: bool,
+    pub error: Option<String>,
+}
+
+// 最近一次密钥轮换的状态，rotation_id 为 0 表示尚未轮换
+#[derive(Debug, Clone)]
+pub struct AstralSecretRotationStatus {
+    pub rotation_id: u64,
+    // 是否由本机发起
+    pub initiated_locally: bool,
+    pub committed: bool,
+    // 旧密钥还能使用的秒数
+    pub grace_period_remaining_sec: u32,
+    pub peers: Vec<AstralSecretRotationPeer>,
+}
+
+// 在运行中的实例上发起网络密钥轮换，grace_period_sec 为 0 时使用默认宽限期，最短 30 秒
+pub async fn start_secret_rotation(
+    instance_id: String,
+    network_secret: String,
+    grace_period_sec: u32,
+) -> Result<u64, String> {
+    if network_secret.is_empty() {
+        return Err("网络密钥不能为空".to_string());
+    }
+    RT.spawn(async move {
+        let mut client = instance_rpc_client(&instance_id)?;
+        let rpc = client
+            .scoped_client::<SecretRotationManageRpcClientFactory<BaseController>>(String::new())
+            .await
+            .map_err(|e| format!("连接实例RPC失败: {}", e))?;
+        let resp = rpc
+            .start_secret_rotation(
+                BaseController::default(),
+                StartSecretRotationRequest {
+                    network_secret,
+                    grace_period_sec,
+                },
+            )
+            .await
+            .map_err(|e| format!("发起密钥轮换失败: {}", e))?;
+        Ok(resp.rotation_id)
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// 查询实例最近一次密钥轮换的进度
+pub async fn get_secret_rotation_status(
+    instance_id: String,
+) -> Result<AstralSecretRotationStatus, String> {
+    RT.spawn(async move {
+        let mut client = instance_rpc_client(&instance_id)?;
+        let rpc = client
+            .scoped_client::<SecretRotationManageRpcClientFactory<BaseController>>(String::new())
+            .await
+            .map_err(|e| format!("连接实例RPC失败: {}", e))?;
+        let resp = rpc
+            .get_secret_rotation_status(
+                BaseController::default(),
+                GetSecretRotationStatusRequest::default(),
+            )
+            .await
+            .map_err(|e| format!("获取密钥轮换状态失败: {}", e))?;
+        Ok(AstralSecretRotationStatus {
+            rotation_id: resp.rotation_id,
+            initiated_locally: resp.initiated_locally,
+            committed: resp.committed,
+            grace_period_remaining_sec: resp.grace_period_remaining_sec,
+            peers: resp
+                .peers
+                .into_iter()
+                .map(|p| AstralSecretRotationPeer {
+                    peer_id: p.peer_id,
+                    hostname: p.hostname,
+                    prepared: p.prepared,
+                    committed: p.committed,
+                    error: p.error,
+                })
+                .collect(),
+        })
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
@@ -1,11 +1,13 @@
-use easytier::{common::config::PortForwardConfig, launcher::ConfigSource};
+use crate::api::{events, port_forward};
+use easytier::launcher::ConfigSource;
 pub use easytier::{
     common::{
         self,
         config::{ConfigLoader, NetworkIdentity, PeerConfig, TomlConfigLoader},
         global_ctx::{EventBusSubscriber, GlobalCtxEvent},
     },
-    launcher::NetworkInstance,
+    instance_manager::NetworkInstanceManager,
+    launcher::{NetworkInstance, NetworkInstanceRunningInfo},
     proto,
     proto::{
         cli::{
@@ -23,211 +25,59 @@ pub use easytier::{
         rpc_types::controller::BaseController,
         web::MyNodeInfo,
     },
+    tunnel::tcp::TcpTunnelConnector,
     utils::cost_to_str,
 };
 use lazy_static::lazy_static;
-use serde_json::json;
 pub use std::collections::BTreeMap;
-use std::sync::Mutex;
 use tokio::runtime::Runtime;
 pub use tokio::task::JoinHandle;
 
 pub static DEFAULT_ET_DNS_ZONE: &str = "as.net.";
 
-static INSTANCE: Mutex<Option<NetworkInstance>> = Mutex::new(None);
-// 创建一个 NetworkInstance 类型变量 储存当前服务器
+// 所有房间共用一个实例管理器，按实例 UUID 区分
 lazy_static! {
-    static ref RT: Runtime = Runtime::new().expect("创建 Tokio 运行时失败");
+    pub(crate) static ref RT: Runtime = Runtime::new().expect("创建 Tokio 运行时失败");
+    static ref MANAGER: NetworkInstanceManager = NetworkInstanceManager::new();
 }
 
-fn peer_conn_info_to_string(p: proto::cli::PeerConnInfo) -> String {
-    format!(
-        "my_peer_id: {}, dst_peer_id: {}, tunnel_info: {:?}",
-        p.my_peer_id, p.peer_id, p.tunnel
-    )
+fn parse_instance_id(instance_id: &str) -> Result<uuid::Uuid, String> {
+    instance_id
+        .parse()
+        .map_err(|e| format!("无效的实例ID: {}, 错误: {}", instance_id, e))
 }
 
-pub fn send_udp_to_localhost(message: &str) -> Result<(), String> {
-    use std::net::UdpSocket;
-
-    let socket = match UdpSocket::bind("0.0.0.0:0") {
-        Ok(s) => s,
-        Err(e) => return Err(format!("绑定UDP套接字失败: {}", e)),
-    };
-
-    match socket.send_to(message.as_bytes(), "127.0.0.1:9999") {
-        Ok(_) => Ok(()),
-        Err(e) => Err(format!("发送UDP数据失败: {}", e)),
+// 连接实例的本地 RPC 端口，用于运行时管理
+pub(crate) fn instance_rpc_client(
+    instance_id: &str,
+) -> Result<StandAloneClient<TcpTunnelConnector>, String> {
+    let id = parse_instance_id(instance_id)?;
+    let portal = MANAGER
+        .get_rpc_portal(&id)
+        .ok_or_else(|| format!("实例未运行或未开启RPC: {}", instance_id))?;
+    // 端口为 0 表示 RPC 服务尚未完成监听，实际端口会在监听后回写到配置中
+    if portal.port() == 0 {
+        return Err(format!("实例的RPC服务尚未就绪: {}", instance_id));
     }
+    let url = format!("tcp://127.0.0.1:{}", portal.port())
+        .parse()
+        .map_err(|e| format!("无效的RPC地址: {}, 错误: {}", portal, e))?;
+    Ok(StandAloneClient::new(TcpTunnelConnector::new(url)))
 }
 
-pub fn handle_event(mut events: EventBusSubscriber) -> tokio::task::JoinHandle<()> {
-    tokio::spawn(async move {
-        loop {
-            match events.recv().await {
-                Ok(e) => {
-                    //  println!("Received event: {:?}", e);
-                    match e {
-                        GlobalCtxEvent::PeerAdded(p) => {
-                            println!("{}", format!("新节点已添加。节点ID: {}", p));
-                            let _ = send_udp_to_localhost(&format!("新节点已添加。节点ID: {}", p));
-                        }
-                        GlobalCtxEvent::PeerRemoved(p) => {
-                            println!("{}", format!("节点已移除。节点ID: {}", p));
-                            let _ = send_udp_to_localhost(&format!("节点已移除。节点ID: {}", p));
-                        }
-                        GlobalCtxEvent::PeerConnAdded(p) => {
-                            let conn_info = peer_conn_info_to_string(p);
-                            let msg = format!("新节点连接已添加。连接信息: {}", conn_info);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::PeerConnRemoved(p) => {
-                            let msg = format!(
-                                "节点连接已移除。连接信息: {}",
-                                peer_conn_info_to_string(p)
-                            );
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::ListenerAddFailed(p, msg) => {
-                            let msg = format!("监听器添加失败。监听器: {}, 消息: {}", p, msg);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::ListenerAcceptFailed(p, msg) => {
-                            let msg = format!("监听器接受失败。监听器: {}, 消息: {}", p, msg);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::ListenerAdded(p) => {
-                            if p.scheme() == "ring" {
-                                continue;
-                            }
-                            let msg = format!("新监听器已添加。监听器: {}", p);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::ConnectionAccepted(local, remote) => {
-                            let msg = format!("新连接已接受。本地: {}, 远程: {}", local, remote);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::ConnectionError(local, remote, err) => {
-                            let msg = format!(
-                                "连接错误。本地: {}, 远程: {}, 错误: {}",
-                                local, remote, err
-                            );
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::TunDeviceReady(dev) => {
-                            let msg = format!("TUN 设备就绪。设备: {}", dev);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::TunDeviceError(err) => {
-                            let msg = format!("TUN 设备错误。错误: {}", err);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::Connecting(dst) => {
-                            let msg = format!("正在连接到节点。目标: {}", dst);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::ConnectError(dst, ip_version, err) => {
-                            let msg = format!(
-                                "连接到节点错误。目标: {}, IP版本: {}, 错误: {}",
-                                dst, ip_version, err
-                            );
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::VpnPortalStarted(portal) => {
-                            let msg = format!("VPN 门户已启动。门户: {}", portal);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::VpnPortalClientConnected(portal, client_addr) => {
-                            let msg = format!(
-                                "VPN 门户客户端已连接。门户: {}, 客户端地址: {}",
-                                portal, client_addr
-                            );
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::VpnPortalClientDisconnected(portal, client_addr) => {
-                            let msg = format!(
-                                "VPN 门户客户端已断开连接。门户: {}, 客户端地址: {}",
-                                portal, client_addr
-                            );
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::DhcpIpv4Changed(old, new) => {
-                            let msg = format!("DHCP IP 已更改。旧: {:?}, 新: {:?}", old, new);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::DhcpIpv4Conflicted(ip) => {
-                            let msg = format!("DHCP IP 冲突。IP: {:?}", ip);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                        GlobalCtxEvent::PortForwardAdded(port_forward_config_pb) => {
-                            let msg = format!("端口转发已添加。配置: {:?}", port_forward_config_pb);
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                        }
-                    }
-                }
-                Err(err) => {
-                    eprintln!("接收事件错误: {:?}", err);
-                    // 根据错误类型决定是否中断循环
-                    match err {
-                        tokio::sync::broadcast::error::RecvError::Closed => {
-                            let msg = "事件通道已关闭，停止事件处理。";
-                            println!("{}", msg);
-                            let _ = send_udp_to_localhost(msg);
-                            break; // Exit the loop if the channel is closed
-                        }
-                        tokio::sync::broadcast::error::RecvError::Lagged(n) => {
-                            let msg = format!("事件处理滞后，丢失了 {} 个事件。", n);
-                            eprintln!("{}", msg);
-                            let _ = send_udp_to_localhost(&msg);
-                            // Decide if lagging is critical enough to break or just log
-                        }
-                    }
-                }
-            }
-        }
-    })
-}
-
-async fn create_and_store_network_instance(cfg: TomlConfigLoader) -> Result<(), String> {
+async fn create_and_store_network_instance(cfg: TomlConfigLoader) -> Result<String, String> {
     println!("{:?}", cfg);
 
-    // 在移动 cfg 之前先获取 ID
-    let name = cfg.get_id().to_string();
-    // 创建网络实例
-    let mut network = NetworkInstance::new(cfg, ConfigSource::FFI);
-    // 启动网络实例，并处理可能的错误
-    handle_event(network.start().unwrap());
-    println!("instance {} started", name);
-    // 将实例存储到 INSTANCE 中
-    let mut instance_guard = INSTANCE
-        .lock()
-        .map_err(|e| format!("获取互斥锁失败: {}", e))?;
-    if instance_guard.is_none() {
-        *instance_guard = Some(network);
-        println!("实例已成功储存");
-    } else {
-        println!("网络实例已存在");
+    // 启动网络实例并交给管理器保存
+    let instance_id = MANAGER
+        .run_network_instance(cfg, ConfigSource::FFI)
+        .map_err(|e| format!("启动网络实例失败: {}", e))?;
+    if let Some(events) = MANAGER.subscribe_event(&instance_id) {
+        events::handle_event(instance_id.to_string(), events);
     }
-    print!("成功储存");
+    println!("instance {} started", instance_id);
 
-    Ok(())
+    Ok(instance_id.to_string())
 }
 
 // 返回EasyTier的版本号
@@ -235,261 +85,311 @@ pub fn easytier_version() -> Result<String, String> {
     Ok(easytier::VERSION.to_string())
 }
 
-// 是否在运行
+// 是否有任意实例在运行
 pub fn is_easytier_running() -> bool {
-    let instance = INSTANCE.lock().unwrap();
-    instance.is_some()
+    !MANAGER.list_network_instance_ids().is_empty()
+}
+
+// 指定实例是否在运行
+pub fn is_instance_running(instance_id: String) -> bool {
+    parse_instance_id(&instance_id)
+        .ok()
+        .and_then(|id| MANAGER.get_network_info(&id))
+        .map_or(false, |info| info.running)
+}
+
+// 列出所有实例的ID
+pub fn list_instances() -> Vec<String> {
+    MANAGER
+        .list_network_instance_ids()
+        .iter()
+        .map(|id| id.to_string())
+        .collect()
 }
-// 定义节点跳跃统计信息结构体
-pub struct NodeHopStats {
-    pub target_ip: String, // 目标节点IP
-    pub latency_ms: f64,   // 延迟(毫秒)
-    pub packet_loss: f32,  // 丢包率
-    pub node_name: String, // 节点名称
+// 路径上的一跳
+#[derive(Debug, Clone)]
+pub struct AstralHop {
+    pub peer_id: u32,
+    pub hostname: String,
+    pub ipv4: Option<String>,
+    pub ipv6: Option<String>,
+    pub latency_ms: f64, // 延迟(毫秒)
+    pub loss_rate: f32,  // 丢包率
 }
 
-// 定义节点连接统计信息结构体
-pub struct KVNodeConnectionStats {
-    pub conn_type: String, // 连接类型
+// 节点的单条连接
+#[derive(Debug, Clone)]
+pub struct AstralConnection {
+    pub conn_id: String,
+    pub tunnel_type: String, // 隧道类型，IPv6 隧道带 6 后缀
+    pub local_addr: String,
+    pub remote_addr: String,
+    pub is_client: bool,
+    pub latency_ms: f64,
+    pub loss_rate: f32,
     pub rx_bytes: u64,
     pub tx_bytes: u64,
     pub rx_packets: u64,
     pub tx_packets: u64,
 }
-// 定义节点信息结构体
-pub struct KVNodeInfo {
+
+// 节点信息，包含本地节点
+#[derive(Debug, Clone)]
+pub struct AstralNode {
+    pub peer_id: u32,
+    pub is_local: bool,
     pub hostname: String,
-    pub ipv4: String,
+    pub version: String,
+    pub ipv4: Option<String>, // 虚拟IPv4，带前缀长度
+    pub ipv6: Option<String>, // 虚拟IPv6，带前缀长度
+    pub proxy_cidrs: Vec<String>,
+    pub udp_nat_type: String,
+    pub tcp_nat_type: String,
+    pub next_hop_peer_id: u32,
+    pub cost: i32,
     pub latency_ms: f64,
-    pub nat: String, // NAT类型
-    // NodeHopStats 列表 从近到远
-    pub hops: Vec<NodeHopStats>,
     pub loss_rate: f32,
-    pub connections: Vec<KVNodeConnectionStats>,
-
-    pub tunnel_proto: String,
-    pub conn_type: String, // 连接类型
+    pub tunnel_types: Vec<String>,
     pub rx_bytes: u64,
     pub tx_bytes: u64,
+    pub connections: Vec<AstralConnection>,
+    // 从本地节点到该节点的路径，两端都包含在内
+    pub hops: Vec<AstralHop>,
+}
 
-    pub version: String,
-    pub cost: i32,
+// 实例的网络状态快照
+#[derive(Debug, Clone)]
+pub struct AstralNetworkSnapshot {
+    pub instance_id: String,
+    pub dev_name: String,
+    pub running: bool,
+    pub error_msg: Option<String>,
+    pub listeners: Vec<String>,
+    pub nodes: Vec<AstralNode>,
 }
-// 定义节点网络状态结构体
-pub struct KVNetworkStatus {
-    pub total_nodes: usize,
-    pub nodes: Vec<KVNodeInfo>,
+
+fn nat_type_to_string(nat_type: i32) -> String {
+    format!(
+        "{:?}",
+        NatType::try_from(nat_type).unwrap_or(NatType::Unknown)
+    )
 }
 
-// 获取网络中所有节点的IP地址列表
-pub fn get_ips() -> Vec<String> {
-    let mut result = Vec::new();
+fn conn_latency_ms(conn: &proto::cli::PeerConnInfo) -> f64 {
+    conn.stats
+        .as_ref()
+        .map_or(0.0, |s| s.latency_us as f64 / 1000.0)
+}
 
-    // Lock the mutex and access the instance if it exists
-    let instance = INSTANCE.lock().unwrap();
+fn peer_latency_and_loss(peer: Option<&PeerInfo>) -> (f64, f32) {
+    peer.map_or((0.0, 0.0), |p| {
+        let min_latency = p
+            .conns
+            .iter()
+            .filter(|c| c.stats.is_some())
+            .map(conn_latency_ms)
+            .fold(None, |acc: Option<f64>, l| {
+                Some(acc.map_or(l, |a| a.min(l)))
+            })
+            .unwrap_or(0.0);
+        let avg_loss =
+            p.conns.iter().map(|c| c.loss_rate).sum::<f32>() / p.conns.len().max(1) as f32;
+        (min_latency, avg_loss)
+    })
+}
 
-    if let Some(instance) = instance.as_ref() {
-        if let Some(info) = instance.get_running_info() {
-            // Add all remote node IPs
-            for route in &info.routes {
-                if let Some(ipv4_addr) = &route.ipv4_addr {
-                    if let Some(addr) = &ipv4_addr.address {
-                        let ip = format!(
-                            "{}.{}.{}.{}/{}",
-                            (addr.addr >> 24) & 0xFF,
-                            (addr.addr >> 16) & 0xFF,
-                            (addr.addr >> 8) & 0xFF,
-                            addr.addr & 0xFF,
-                            ipv4_addr.network_length
-                        );
-                        // Avoid duplicates
-                        if !result.contains(&ip) {
-                            result.push(ip);
-                        }
-                    }
-                }
-            }
+fn route_to_hop(route: &Route, peer: Option<&PeerInfo>) -> AstralHop {
+    let (latency_ms, loss_rate) = peer_latency_and_loss(peer);
+    AstralHop {
+        peer_id: route.peer_id,
+        hostname: route.hostname.clone(),
+        ipv4: route.ipv4_addr.map(|addr| addr.to_string()),
+        ipv6: route.ipv6_addr.map(|addr| addr.to_string()),
+        latency_ms,
+        loss_rate,
+    }
+}
+
+fn conn_to_astral(conn: &proto::cli::PeerConnInfo) -> AstralConnection {
+    let stats = conn.stats.clone().unwrap_or_default();
+    let (tunnel_type, local_addr, remote_addr) = conn
+        .tunnel
+        .as_ref()
+        .map(|t| {
+            let local_addr = t.local_addr.clone().map(|a| a.url).unwrap_or_default();
+            // URL 中的 IPv6 主机总是带方括号
+            let is_ipv6 = local_addr.contains('[');
+            (
+                if is_ipv6 {
+                    format!("{}6", t.tunnel_type)
+                } else {
+                    t.tunnel_type.clone()
+                },
+                local_addr,
+                t.remote_addr.clone().map(|a| a.url).unwrap_or_default(),
+            )
+        })
+        .unwrap_or_default();
+    AstralConnection {
+        conn_id: conn.conn_id.clone(),
+        tunnel_type,
+        local_addr,
+        remote_addr,
+        is_client: conn.is_client,
+        latency_ms: conn_latency_ms(conn),
+        loss_rate: conn.loss_rate,
+        rx_bytes: stats.rx_bytes,
+        tx_bytes: stats.tx_bytes,
+        rx_packets: stats.rx_packets,
+        tx_packets: stats.tx_packets,
+    }
+}
+
+// 沿本地路由表的下一跳收集到目标节点的路径
+fn collect_hops(
+    local_hop: &AstralHop,
+    target: &Route,
+    routes: &BTreeMap<u32, &Route>,
+    peers: &BTreeMap<u32, &PeerInfo>,
+) -> Vec<AstralHop> {
+    let mut hops = vec![local_hop.clone()];
+    let mut visited = std::collections::HashSet::from([local_hop.peer_id]);
+    let mut current = target.next_hop_peer_id;
+    while current != 0 && visited.insert(current) {
+        let Some(route) = routes.get(&current) else {
+            break;
+        };
+        hops.push(route_to_hop(route, peers.get(&current).copied()));
+        if current == target.peer_id {
+            return hops;
         }
+        current = route.next_hop_peer_id;
     }
-    result
+    // 中间路径未知时直接补上目标节点
+    if !visited.contains(&target.peer_id) {
+        hops.push(route_to_hop(target, peers.get(&target.peer_id).copied()));
+    }
+    hops
 }
 
-// 设置TUN设备的文件描述符
-pub fn set_tun_fd(fd: i32) -> Result<(), String> {
-    let mut instance = INSTANCE.lock().unwrap();
-    if let Some(instance) = instance.as_mut() {
-        instance.set_tun_fd(fd);
-        Ok(())
-    } else {
-        Err("No instance available".to_string())
+fn build_network_snapshot(
+    instance_id: String,
+    info: NetworkInstanceRunningInfo,
+) -> AstralNetworkSnapshot {
+    let routes: BTreeMap<u32, &Route> = info.routes.iter().map(|r| (r.peer_id, r)).collect();
+    let peers: BTreeMap<u32, &PeerInfo> = info.peers.iter().map(|p| (p.peer_id, p)).collect();
+    let my_node_info = info.my_node_info.clone().unwrap_or_default();
+    let my_stun_info = my_node_info.stun_info.clone().unwrap_or_default();
+
+    let local_hop = AstralHop {
+        peer_id: my_node_info.peer_id,
+        hostname: my_node_info.hostname.clone(),
+        ipv4: my_node_info.virtual_ipv4.map(|addr| addr.to_string()),
+        ipv6: my_node_info.virtual_ipv6.map(|addr| addr.to_string()),
+        latency_ms: 0.0,
+        loss_rate: 0.0,
+    };
+
+    let mut nodes = vec![AstralNode {
+        peer_id: my_node_info.peer_id,
+        is_local: true,
+        hostname: my_node_info.hostname.clone(),
+        version: my_node_info.version.clone(),
+        ipv4: local_hop.ipv4.clone(),
+        ipv6: local_hop.ipv6.clone(),
+        proxy_cidrs: vec![],
+        udp_nat_type: nat_type_to_string(my_stun_info.udp_nat_type),
+        tcp_nat_type: nat_type_to_string(my_stun_info.tcp_nat_type),
+        next_hop_peer_id: my_node_info.peer_id,
+        cost: 0,
+        latency_ms: 0.0,
+        loss_rate: 0.0,
+        tunnel_types: vec![],
+        rx_bytes: 0,
+        tx_bytes: 0,
+        connections: vec![],
+        hops: vec![local_hop.clone()],
+    }];
+
+    for pair in info.peer_route_pairs.iter() {
+        let Some(route) = &pair.route else {
+            continue;
+        };
+        let stun_info = route.stun_info.clone().unwrap_or_default();
+        nodes.push(AstralNode {
+            peer_id: route.peer_id,
+            is_local: false,
+            hostname: route.hostname.clone(),
+            version: route.version.clone(),
+            ipv4: route.ipv4_addr.map(|addr| addr.to_string()),
+            ipv6: route.ipv6_addr.map(|addr| addr.to_string()),
+            proxy_cidrs: route.proxy_cidrs.clone(),
+            udp_nat_type: nat_type_to_string(stun_info.udp_nat_type),
+            tcp_nat_type: nat_type_to_string(stun_info.tcp_nat_type),
+            next_hop_peer_id: route.next_hop_peer_id,
+            cost: route.cost,
+            // 直连节点使用实测延迟，其余使用路径延迟
+            latency_ms: if route.cost == 1 {
+                pair.get_latency_ms().unwrap_or(0.0)
+            } else {
+                route.path_latency_latency_first() as f64
+            },
+            loss_rate: pair.get_loss_rate().unwrap_or(0.0) as f32,
+            tunnel_types: pair.get_conn_protos().unwrap_or_default(),
+            rx_bytes: pair.get_rx_bytes().unwrap_or_default(),
+            tx_bytes: pair.get_tx_bytes().unwrap_or_default(),
+            connections: pair
+                .peer
+                .as_ref()
+                .map(|p| p.conns.iter().map(conn_to_astral).collect())
+                .unwrap_or_default(),
+            hops: collect_hops(&local_hop, route, &routes, &peers),
+        });
+    }
+
+    AstralNetworkSnapshot {
+        instance_id,
+        dev_name: info.dev_name,
+        running: info.running,
+        error_msg: info.error_msg,
+        listeners: my_node_info
+            .listeners
+            .iter()
+            .map(|l| l.url.clone())
+            .collect(),
+        nodes,
     }
 }
 
-pub fn get_running_info() -> String {
-    INSTANCE
-        .lock()
-        .unwrap()
-        .as_ref()
-        .and_then(|instance| instance.get_running_info())
-        .and_then(|info| {
-            // 获取并打印节点路由对信息
-            serde_json::to_string(&json!({
-                "dev_name": info.dev_name,
-                "my_node_info": info.my_node_info.as_ref().map(|node| json!({
-                    "virtual_ipv4": node.virtual_ipv4.as_ref().map(|addr| json!({
-                        "address": addr.address.as_ref().map(|a| json!({ "addr": a.addr })),
-                        "network_length": addr.network_length
-                    })),
-                    "hostname": node.hostname,
-                    "version": node.version,
-                    "ips": node.ips.as_ref().map(|ips| json!({
-                        "public_ipv4": ips.public_ipv4.as_ref().map(|a| json!({ "addr": a.addr })),
-                        "interface_ipv4s": ips.interface_ipv4s.iter().map(|a| json!({ "addr": a.addr })).collect::<Vec<_>>(),
-                        "public_ipv6": ips.public_ipv6.as_ref().map(|a| json!({
-                            "part1": a.part1,
-                            "part2": a.part2,
-                            "part3": a.part3,
-                            "part4": a.part4
-                        })),
-                        "interface_ipv6s": ips.interface_ipv6s.iter().map(|a| json!({
-                            "part1": a.part1,
-                            "part2": a.part2,
-                            "part3": a.part3,
-                            "part4": a.part4
-                        })).collect::<Vec<_>>(),
-                        "listeners": ips.listeners.iter().map(|l| json!({ "url": l.to_string() })).collect::<Vec<_>>()
-                    })),
-                    "stun_info": node.stun_info.as_ref().map(|info| json!({
-                        "udp_nat_type": info.udp_nat_type,
-                        "tcp_nat_type": info.tcp_nat_type,
-                        "last_update_time": info.last_update_time,
-                        "public_ip": info.public_ip,
-                        "min_port": info.min_port,
-                        "max_port": info.max_port
-                    })),
-                    "listeners": node.listeners.iter().map(|l| json!({ "url": l.url })).collect::<Vec<_>>(),
-                    "vpn_portal_cfg": node.vpn_portal_cfg
-                })),
-                "events": info.events,
-                "routes": info.routes.iter().map(|route| json!({
-                    "peer_id": route.peer_id,
-                    "ipv4_addr": route.ipv4_addr.as_ref().map(|addr| json!({
-                        "address": addr.address.as_ref().map(|a| json!({ "addr": a.addr })),
-                        "network_length": addr.network_length
-                    })),
-                    "next_hop_peer_id": route.next_hop_peer_id,
-                    "cost": route.cost,
-                    "path_latency": route.path_latency,
-                    "proxy_cidrs": route.proxy_cidrs,
-                    "hostname": route.hostname,
-                    "stun_info": route.stun_info.as_ref().map(|info| json!({
-                        "udp_nat_type": info.udp_nat_type,
-                        "tcp_nat_type": info.tcp_nat_type,
-                        "last_update_time": info.last_update_time,
-                        "public_ip": info.public_ip,
-                        "min_port": info.min_port,
-                        "max_port": info.max_port
-                    })),
-                    "inst_id": route.inst_id,
-                    "version": route.version,
-                    "feature_flag": route.feature_flag.as_ref().map(|flag| json!({
-                        "is_public_server": flag.is_public_server,
-                        "avoid_relay_data": flag.avoid_relay_data,
-                        "kcp_input": flag.kcp_input,
-                        "no_relay_kcp": flag.no_relay_kcp
-                    })),
-                    "next_hop_peer_id_latency_first": route.next_hop_peer_id_latency_first,
-                    "cost_latency_first": route.cost_latency_first,
-                    "path_latency_latency_first": route.path_latency_latency_first ,
-                })).collect::<Vec<_>>(),
-                "peers": info.peers.iter().map(|peer| 
-                     json!({
-                    "peer_id": peer.peer_id,
-                    "conns": peer.conns.iter().map(|conn| json!({
-                        "conn_id": conn.conn_id,
-                        "my_peer_id": conn.my_peer_id,
-                        "peer_id": conn.peer_id,
-                        "features": conn.features,
-                        "tunnel": conn.tunnel.as_ref().map(|t| json!({
-                            "tunnel_type": t.tunnel_type,
-                            "local_addr": t.local_addr.as_ref().map(|a| json!({ "url": a.url })),
-                            "remote_addr": t.remote_addr.as_ref().map(|a| json!({ "url": a.url }))
-                        })),
-                        "stats": conn.stats.as_ref().map(|s| json!({
-                            "rx_bytes": s.rx_bytes,
-                            "tx_bytes": s.tx_bytes,
-                            "rx_packets": s.rx_packets,
-                            "tx_packets": s.tx_packets,
-                            "latency_us": s.latency_us
-                        })),
-                        "loss_rate": conn.loss_rate,
-                        "is_client": conn.is_client,
-                        "network_name": conn.network_name
-                    })).collect::<Vec<_>>()
-                })).collect::<Vec<_>>(),
-                "peer_route_pairs": info.peer_route_pairs.iter().map(|pair| json!({
-                    "route": pair.route.as_ref().map(|route| json!({
-                        "peer_id": route.peer_id,
-                        "ipv4_addr": route.ipv4_addr.as_ref().map(|addr| json!({
-                            "address": addr.address.as_ref().map(|a| json!({ "addr": a.addr })),
-                            "network_length": addr.network_length
-                        })),
-                        "next_hop_peer_id": route.next_hop_peer_id,
-                        "cost": route.cost,
-                        "path_latency": route.path_latency,
-                        "proxy_cidrs": route.proxy_cidrs,
-                        "hostname": route.hostname,
-                        "stun_info": route.stun_info.as_ref().map(|info| json!({
-                            "udp_nat_type": info.udp_nat_type,
-                            "tcp_nat_type": info.tcp_nat_type,
-                            "last_update_time": info.last_update_time,
-                            "public_ip": info.public_ip,
-                            "min_port": info.min_port,
-                            "max_port": info.max_port
-                        })),
-                        "inst_id": route.inst_id,
-                        "version": route.version,
-                        "feature_flag": route.feature_flag.as_ref().map(|flag| json!({
-                            "is_public_server": flag.is_public_server,
-                            "avoid_relay_data": flag.avoid_relay_data,
-                            "kcp_input": flag.kcp_input,
-                            "no_relay_kcp": flag.no_relay_kcp
-                        })),
-                        "next_hop_peer_id_latency_first": route.next_hop_peer_id_latency_first,
-                        "cost_latency_first": route.cost_latency_first,
-                        "path_latency_latency_first": route.path_latency_latency_first
-                    })),
-                    "peer": pair.peer.as_ref().map(|peer| json!({
-                        "peer_id": peer.peer_id,
-                        "conns": peer.conns.iter().map(|conn| json!({
-                            "conn_id": conn.conn_id,
-                            "my_peer_id": conn.my_peer_id,
-                            "peer_id": conn.peer_id,
-                            "features": conn.features,
-                            "tunnel": conn.tunnel.as_ref().map(|t| json!({
-                                "tunnel_type": t.tunnel_type,
-                                "local_addr": t.local_addr.as_ref().map(|a| json!({ "url": a.url })),
-                                "remote_addr": t.remote_addr.as_ref().map(|a| json!({ "url": a.url }))
-                            })),
-                            "stats": conn.stats.as_ref().map(|s| json!({
-                                "rx_bytes": s.rx_bytes,
-                                "tx_bytes": s.tx_bytes,
-                                "rx_packets": s.rx_packets,
-                                "tx_packets": s.tx_packets,
-                                "latency_us": s.latency_us
-                            })),
-                            "loss_rate": conn.loss_rate,
-                            "is_client": conn.is_client,
-                            "network_name": conn.network_name
-                        })).collect::<Vec<_>>()
-                    }))
-                })).collect::<Vec<_>>(),
+// 获取实例的网络状态快照
+pub fn get_network_snapshot(instance_id: String) -> Result<AstralNetworkSnapshot, String> {
+    let id = parse_instance_id(&instance_id)?;
+    let info = MANAGER
+        .get_network_info(&id)
+        .ok_or_else(|| format!("没有运行中的网络实例: {}", instance_id))?;
+    Ok(build_network_snapshot(instance_id, info))
+}
 
-                "running": info.running,
-                "error_msg": info.error_msg
-            })).ok()
-        })
-        .unwrap_or_else(|| "{}".to_string())
+// 获取网络中所有节点的虚拟IP地址列表
+pub fn get_ips(instance_id: String) -> Vec<String> {
+    let mut result = Vec::new();
+    if let Ok(snapshot) = get_network_snapshot(instance_id) {
+        for node in snapshot.nodes.into_iter().filter(|n| !n.is_local) {
+            for ip in [node.ipv4, node.ipv6].into_iter().flatten() {
+                // Avoid duplicates
+                if !result.contains(&ip) {
+                    result.push(ip);
+                }
+            }
+        }
+    }
+    result
+}
+
+// 设置TUN设备的文件描述符
+pub fn set_tun_fd(instance_id: String, fd: i32) -> Result<(), String> {
+    let id = parse_instance_id(&instance_id)?;
+    MANAGER.set_tun_fd(&id, fd).map_err(|e| e.to_string())
 }
 
 pub struct FlagsC {
@@ -497,6 +397,8 @@ pub struct FlagsC {
     pub dev_name: String,
     pub enable_encryption: bool,
     pub enable_ipv6: bool,
+    // 按本机 ID 从房间的 ULA 前缀中分配固定的虚拟 IPv6，与 IPv4 的 DHCP 相互独立
+    pub dhcp_ipv6: bool,
     pub mtu: u32,
     pub latency_first: bool,
     pub enable_exit_node: bool,
@@ -508,6 +410,7 @@ pub struct FlagsC {
     pub disable_udp_hole_punching: bool,
     pub multi_thread: bool,
     pub data_compress_algo: i32,
+    pub data_compress_level: i32,
     pub bind_device: bool,
     pub enable_kcp_proxy: bool,
     pub disable_kcp_input: bool,
@@ -525,8 +428,8 @@ pub struct Forward {
     pub proto: String,
 }
 
-// 创建服务器
-pub fn create_server(
+// 创建服务器，返回新实例的ID
+pub async fn create_server(
     username: String,
     enable_dhcp: bool,
     specified_ip: String,
@@ -537,11 +440,14 @@ pub fn create_server(
     cidrs: Vec<String>,
     forwards: Vec<Forward>,
     flag: FlagsC,
-) -> JoinHandle<Result<(), String>> {
+) -> Result<String, String> {
     print!("{}", format!("创建服务器: {}，启用DHCP: {}, 指定IP: {}, 房间名称: {}, 房间密码: {}, 服务器URL: {:?}, 监听器URL: {:?}", username, enable_dhcp, specified_ip, room_name, room_password, severurl, onurl));
     RT.spawn(async move {
         // Create config with better error handling
         let cfg = TomlConfigLoader::default();
+        cfg.set_inst_name(room_name.clone());
+        // 仅监听本机，端口为 0 时由启动器自动选择空闲端口
+        cfg.set_rpc_portal(std::net::SocketAddr::from(([127, 0, 0, 1], 0)));
 
         // Set listeners with proper error handling
         let mut listeners = Vec::new();
@@ -557,7 +463,11 @@ pub fn create_server(
         cfg.set_hostname(Some(username));
         cfg.set_dhcp(enable_dhcp);
         for c in cidrs {
-            cfg.add_proxy_cidr(c.parse().unwrap(), None);
+            let cidr = c
+                .parse()
+                .map_err(|e| format!("Invalid proxy CIDR: {}, error: {}", c, e))?;
+            cfg.add_proxy_cidr(cidr, None)
+                .map_err(|e| format!("Invalid proxy CIDR: {}, error: {}", c, e))?;
         }
         let mut old = cfg.get_port_forwards();
 
@@ -570,21 +480,22 @@ pub fn create_server(
                     c.bind_addr, c.dst_addr, c.proto
                 )
             );
-            let port_forward_item = PortForwardConfig {
-                bind_addr: c.bind_addr.parse().unwrap(),
-                dst_addr: c.dst_addr.parse().unwrap(),
-                proto: c.proto,
-            };
-            old.push(port_forward_item);
+            old.push(port_forward::parse_forward(&c)?);
         }
 
         cfg.set_port_forwards(old);
         // Set flags more efficiently by directly mapping from input
         let mut flags = cfg.get_flags();
         flags.default_protocol = flag.default_protocol;
-        flags.dev_name = "astral".to_string();
+        // 未指定设备名时按实例 ID 生成，避免多个房间的 TUN 设备重名（Linux 接口名最长 15 字节）
+        flags.dev_name = if flag.dev_name.is_empty() {
+            format!("astral_{}", &cfg.get_id().simple().to_string()[..8])
+        } else {
+            flag.dev_name
+        };
         flags.enable_encryption = flag.enable_encryption;
         flags.enable_ipv6 = flag.enable_ipv6;
+        flags.dhcp_ipv6 = flag.dhcp_ipv6;
         flags.mtu = flag.mtu;
         flags.latency_first = flag.latency_first;
         flags.enable_exit_node = flag.enable_exit_node;
@@ -596,6 +507,7 @@ pub fn create_server(
         flags.disable_udp_hole_punching = flag.disable_udp_hole_punching;
         flags.multi_thread = flag.multi_thread;
         flags.data_compress_algo = flag.data_compress_algo;
+        flags.data_compress_level = flag.data_compress_level;
         flags.bind_device = flag.bind_device;
         flags.enable_kcp_proxy = flag.enable_kcp_proxy;
         flags.disable_kcp_input = flag.disable_kcp_input;
@@ -637,433 +549,33 @@ pub fn create_server(
         // 直接启动网络实例，无需嵌套 spawn
         create_and_store_network_instance(cfg).await
     })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
 }
 
-// 关闭服务器实例
-pub fn close_server() {
-    RT.spawn(async {
-        // 获取mutex锁
-        let mut locked_instance = INSTANCE.lock().unwrap();
-
-        println!(
-            "关闭前实例状态: {}",
-            if locked_instance.is_some() {
-                "存在"
-            } else {
-                "不存在"
-            }
-        ); // 添加关闭前日志
-
-        // 如果实例存在，则丢弃它
-        if let Some(instance) = locked_instance.take() {
-            println!("正在关闭实例");
-            // 丢弃实例
-            drop(instance);
-            println!("实例已成功关闭");
-        } else {
-            println!("没有找到需要关闭的实例");
-        }
-
-        println!(
-            "关闭后实例状态: {}",
-            if locked_instance.is_some() {
-                "存在"
-            } else {
-                "不存在"
-            }
-        ); // 添加关闭后日志
-    });
-}
-
-// 创建一个网卡跃点数据结构
-// 网卡跃点数据结构
-
-pub fn get_peer_route_pairs() -> Result<Vec<PeerRoutePair>, String> {
-    let instance_guard = INSTANCE
-        .lock()
-        .map_err(|e| format!("获取互斥锁失败: {}", e))?;
-
-    if let Some(instance) = instance_guard.as_ref() {
-        // 获取运行信息
-        if let Some(info) = instance.get_running_info() {
-            let mut pairs = info.peer_route_pairs;
-            // 如果存在本地节点信息，添加到结果中
-            if let Some(my_node_info) = &info.my_node_info {
-                // 获取本地节点ID
-                // 注意：这里的逻辑可能需要根据单例模式调整，假设本地节点信息可以直接从 info 中获取
-                // 使用原始逻辑查找本地 peer_id
-                let my_peer_id = info
-                    .peers
-                    .iter()
-                    .find(|p| p.conns.iter().any(|c| !c.is_client))
-                    .map(|p| p.peer_id)
-                    .unwrap_or(0);
-
-                // 创建一个表示本地节点的Route
-                let my_route = proto::cli::Route {
-                    peer_id: my_peer_id,
-                    ipv4_addr: my_node_info.virtual_ipv4.clone(),
-                    ipv6_addr: None, // 添加ipv6地址字段,目前暂不支持ipv6
-                    next_hop_peer_id: my_peer_id, // 指向自己
-                    cost: 0,                      // 到自己的成本为0
-                    path_latency: 0,              // 到自己的延迟为0
-                    proxy_cidrs: vec![],
-                    hostname: my_node_info.hostname.clone(),
-                    stun_info: my_node_info.stun_info.clone(),
-                    inst_id: "local".to_string(), // 标记为本地实例
-                    version: my_node_info.version.clone(),
-                    feature_flag: None, // 本地节点通常没有特性标志
-                    next_hop_peer_id_latency_first: None,
-                    cost_latency_first: None,
-                    path_latency_latency_first: None,
-                };
-
-                // 创建一个表示本地节点的PeerInfo，包含网络统计信息
-                // 注意：本地节点的PeerInfo可能需要特殊处理或从其他地方获取
-                let my_peer_info = info.peers.iter().find(|p| p.peer_id == my_peer_id).cloned();
-
-                // 创建一个表示本地节点的PeerRoutePair
-                let my_pair = proto::cli::PeerRoutePair {
-                    route: Some(my_route),
-                    peer: my_peer_info, // 使用找到的PeerInfo或None
-                };
-
-                // 添加到结果中
-                pairs.push(my_pair);
-            }
-
-            return Ok(pairs);
-        }
-        return Err("无法获取运行信息".to_string());
+// 关闭指定的服务器实例
+pub fn close_server(instance_id: String) -> Result<(), String> {
+    let id = parse_instance_id(&instance_id)?;
+    if !MANAGER.list_network_instance_ids().contains(&id) {
+        return Err(format!("没有找到需要关闭的实例: {}", instance_id));
     }
-    Err("没有运行中的网络实例".to_string())
+    RT.spawn(async move {
+        println!("正在关闭实例 {}", id);
+        // 从管理器中移除即会丢弃实例
+        let remaining = MANAGER
+            .delete_network_instance(vec![id])
+            .unwrap_or_default();
+        println!("实例已成功关闭，剩余实例数: {}", remaining.len());
+    });
+    Ok(())
 }
 
-// 获取网络状态信息
-pub fn get_network_status() -> KVNetworkStatus {
-    let pairs = get_peer_route_pairs().unwrap_or_default();
-    let mut nodes = Vec::new();
-    for pair in pairs.iter() {
-        if let Some(route) = &pair.route {
-            let cost = route.cost;
-            // 使用 get_running_info 中的逻辑来获取IPv4地址
-            let ipv4 = route
-                .ipv4_addr
-                .as_ref()
-                .and_then(|addr| addr.address.as_ref())
-                .map(|a| {
-                    format!(
-                        "{}.{}.{}.{}",
-                        (a.addr >> 24) & 0xFF,
-                        (a.addr >> 16) & 0xFF,
-                        (a.addr >> 8) & 0xFF,
-                        a.addr & 0xFF
-                    )
-                })
-                .unwrap_or_else(|| "0.0.0.0".to_string());
-            let mut node_info = KVNodeInfo {
-                hostname: route.hostname.clone(),
-
-                hops: {
-                    // 新建递归函数收集完整路径
-                    fn collect_hops(
-                        pairs: &[PeerRoutePair],
-                        current_peer_id: u32,
-                        mut path: Vec<NodeHopStats>,
-                        visited: &mut std::collections::HashSet<u32>,
-                    ) -> Vec<NodeHopStats> {
-                        if visited.contains(&current_peer_id) {
-                            return path;
-                        }
-                        visited.insert(current_peer_id);
-
-                        // 查找当前节点的信息
-                        if let Some(pair) = pairs.iter().find(|p| {
-                            p.route
-                                .as_ref()
-                                .map_or(false, |r| r.peer_id == current_peer_id)
-                        }) {
-                            if let Some(route) = &pair.route {
-                                // 获取IP地址
-                                let ip = route
-                                    .ipv4_addr
-                                    .as_ref()
-                                    .and_then(|addr| addr.address.as_ref())
-                                    .map(|a| {
-                                        format!(
-                                            "{}.{}.{}.{}",
-                                            (a.addr >> 24) & 0xFF,
-                                            (a.addr >> 16) & 0xFF,
-                                            (a.addr >> 8) & 0xFF,
-                                            a.addr & 0xFF
-                                        )
-                                    })
-                                    .unwrap_or_default();
-
-                                // 计算延迟和丢包率
-                                let (latency, loss) = pair.peer.as_ref().map_or((0.0, 0.0), |p| {
-                                    let min_latency = p
-                                        .conns
-                                        .iter()
-                                        .filter_map(|c| c.stats.as_ref().map(|s| s.latency_us))
-                                        .min()
-                                        .unwrap_or(0)
-                                        as f64
-                                        / 1000.0;
-
-                                    let avg_loss = p.conns.iter().map(|c| c.loss_rate).sum::<f32>()
-                                        / p.conns.len().max(1) as f32;
-
-                                    (min_latency, avg_loss as f64)
-                                });
-
-                                // 添加当前节点到路径
-                                path.push(NodeHopStats {
-                                    target_ip: ip,
-                                    latency_ms: latency,
-                                    packet_loss: loss as f32,
-                                    node_name: route.hostname.clone(),
-                                });
-
-                                // 如果下一跳不是自己，继续递归
-                                if route.next_hop_peer_id != current_peer_id
-                                    && route.next_hop_peer_id != 0
-                                {
-                                    // 查找下一跳节点
-                                    return collect_hops(
-                                        pairs,
-                                        route.next_hop_peer_id,
-                                        path,
-                                        visited,
-                                    );
-                                }
-                            }
-                        }
-                        path
-                    }
-
-                    // 使用路由表中的next_hop_peer_id构建完整路径
-                    let mut hops = Vec::new();
-                    if let Some(route) = &pair.route {
-                        let mut visited = std::collections::HashSet::new();
-
-                        // 从当前节点开始，收集到目标节点的完整路径
-                        // 先添加本地节点信息
-                        let instance_guard = INSTANCE.lock().unwrap(); // 使用单例 INSTANCE
-                        if let Some(instance) = instance_guard.as_ref() {
-                            if let Some(info) = instance.get_running_info() {
-                                if let Some(local_node) = &info.my_node_info {
-                                    // 添加本地节点作为起点
-                                    hops.push(NodeHopStats {
-                                        target_ip: local_node
-                                            .virtual_ipv4
-                                            .as_ref()
-                                            .and_then(|addr| addr.address.as_ref())
-                                            .map(|a| {
-                                                format!(
-                                                    "{}.{}.{}.{}",
-                                                    (a.addr >> 24) & 0xFF,
-                                                    (a.addr >> 16) & 0xFF,
-                                                    (a.addr >> 8) & 0xFF,
-                                                    a.addr & 0xFF
-                                                )
-                                            })
-                                            .unwrap_or_else(|| local_node.hostname.clone()),
-                                        latency_ms: 0.0,
-                                        packet_loss: 0.0,
-                                        node_name: local_node.hostname.clone(),
-                                    });
-
-                                    // 查找从本地到目标节点的路由
-                                    if let Some(local_route) =
-                                        info.routes.iter().find(|r| r.peer_id == route.peer_id)
-                                    {
-                                        // 收集中间节点
-                                        let mut next_hops = collect_hops(
-                                            pairs.as_slice(),
-                                            local_route.next_hop_peer_id,
-                                            Vec::new(),
-                                            &mut visited,
-                                        );
-                                        hops.append(&mut next_hops);
-
-                                        // 确保目标节点被添加到路径中
-                                        // 检查最后一个节点是否是目标节点
-                                        let last_node_is_target =
-                                            hops.last().map_or(false, |last| {
-                                                // 比较 hostname
-                                                last.node_name == route.hostname
-                                            });
-
-                                        // 如果最后一个节点不是目标节点，则添加目标节点
-                                        if !last_node_is_target && !visited.contains(&route.peer_id)
-                                        {
-                                            let ip = route
-                                                .ipv4_addr
-                                                .as_ref()
-                                                .and_then(|addr| addr.address.as_ref())
-                                                .map(|a| {
-                                                    format!(
-                                                        "{}.{}.{}.{}",
-                                                        (a.addr >> 24) & 0xFF,
-                                                        (a.addr >> 16) & 0xFF,
-                                                        (a.addr >> 8) & 0xFF,
-                                                        a.addr & 0xFF
-                                                    )
-                                                })
-                                                .unwrap_or_default();
-
-                                            let (latency, loss) =
-                                                pair.peer.as_ref().map_or((0.0, 0.0), |p| {
-                                                    let min_latency = p
-                                                        .conns
-                                                        .iter()
-                                                        .filter_map(|c| {
-                                                            c.stats.as_ref().map(|s| s.latency_us)
-                                                        })
-                                                        .min()
-                                                        .unwrap_or(0)
-                                                        as f64
-                                                        / 1000.0;
-
-                                                    let avg_loss = p
-                                                        .conns
-                                                        .iter()
-                                                        .map(|c| c.loss_rate)
-                                                        .sum::<f32>()
-                                                        / p.conns.len().max(1) as f32;
-
-                                                    (min_latency, avg_loss as f64)
-                                                });
-
-                                            hops.push(NodeHopStats {
-                                                target_ip: ip,
-                                                latency_ms: latency,
-                                                packet_loss: loss as f32,
-                                                node_name: route.hostname.clone(),
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        // 如果没有收集到任何跳点（可能是直连节点），则直接添加目标节点
-                        // 检查 hops 是否只包含本地节点
-                        if hops.len() <= 1 {
-                            // 确保目标节点不在hops中（避免重复添加）
-                            if !hops.iter().any(|h| h.node_name == route.hostname) {
-                                let ip = route
-                                    .ipv4_addr
-                                    .as_ref()
-                                    .and_then(|addr| addr.address.as_ref())
-                                    .map(|a| {
-                                        format!(
-                                            "{}.{}.{}.{}",
-                                            (a.addr >> 24) & 0xFF,
-                                            (a.addr >> 16) & 0xFF,
-                                            (a.addr >> 8) & 0xFF,
-                                            a.addr & 0xFF
-                                        )
-                                    })
-                                    .unwrap_or_default();
-
-                                let (latency, loss) = pair.peer.as_ref().map_or((0.0, 0.0), |p| {
-                                    let min_latency = p
-                                        .conns
-                                        .iter()
-                                        .filter_map(|c| c.stats.as_ref().map(|s| s.latency_us))
-                                        .min()
-                                        .unwrap_or(0)
-                                        as f64
-                                        / 1000.0;
-
-                                    let avg_loss = p.conns.iter().map(|c| c.loss_rate).sum::<f32>()
-                                        / p.conns.len().max(1) as f32;
-
-                                    (min_latency, avg_loss as f64)
-                                });
-
-                                hops.push(NodeHopStats {
-                                    target_ip: ip,
-                                    latency_ms: latency,
-                                    packet_loss: loss as f32,
-                                    node_name: route.hostname.clone(),
-                                });
-                            }
-                        }
-                    }
-                    hops
-                },
-                latency_ms: if route.cost == 1 {
-                    pair.get_latency_ms().unwrap_or(0.0)
-                } else {
-                    route.path_latency_latency_first() as f64
-                },
-                ipv4: ipv4,
-
-                loss_rate: if let Some(peer) = &pair.peer {
-                    let mut total_loss_rate = 0.0;
-                    for conn in &peer.conns {
-                        total_loss_rate += conn.loss_rate;
-                    }
-                    total_loss_rate
-                } else {
-                    0.0 // 如果没有连接信息，默认为0
-                },
-                nat: route.stun_info.as_ref().map_or_else(
-                    || "Unknown".to_string(),
-                    |stun| {
-                        // 使用NatType枚举替代直接匹配数字
-                        let nat_type =
-                            NatType::try_from(stun.udp_nat_type).unwrap_or(NatType::Unknown);
-                        // println!("udp{:?}", NatType::try_from(stun.udp_nat_type).unwrap_or(NatType::Unknown));
-                        // println!("tcp{:?}", NatType::try_from(stun.tcp_nat_type).unwrap_or(NatType::Unknown));
-                        format!("{:?}", nat_type)
-                    },
-                ),
-                connections: Vec::new(),
-                version: route.version.clone(),
-                cost,
-                conn_type: pair.get_udp_nat_type(),
-                tunnel_proto: pair
-                    .get_conn_protos()
-                    .unwrap_or_default()
-                    .join(",")
-                    .to_string(),
-                rx_bytes: pair.get_rx_bytes().unwrap_or_default(),
-                tx_bytes: pair.get_tx_bytes().unwrap_or_default(),
-            };
-
-            // 收集连接统计信息
-            if let Some(peer) = &pair.peer {
-                for conn in &peer.conns {
-                    if let Some(stats) = &conn.stats {
-                        let conn_type = if let Some(tunnel) = &conn.tunnel {
-                            tunnel.tunnel_type.clone()
-                        } else {
-                            "未知".to_string()
-                        };
-
-                        node_info.connections.push(KVNodeConnectionStats {
-                            conn_type,
-                            rx_bytes: stats.rx_bytes,
-                            tx_bytes: stats.tx_bytes,
-                            rx_packets: stats.rx_packets,
-                            tx_packets: stats.tx_packets,
-                        });
-                    }
-                }
-            }
-
-            nodes.push(node_info);
-        }
-    }
-
-    KVNetworkStatus {
-        total_nodes: nodes.len(),
-        nodes,
-    }
+// 关闭所有服务器实例
+pub fn close_all_servers() {
+    RT.spawn(async {
+        let remaining = MANAGER.retain_network_instance(vec![]).unwrap_or_default();
+        println!("所有实例已关闭，剩余实例数: {}", remaining.len());
+    });
 }
 
 pub fn init_app() {
@@ -26,7 +26,6 @@
 // Section: imports
 
 use crate::api::astral_wfp::*;
-use crate::api::simple::*;
 use crate::*;
 use flutter_rust_bridge::for_generated::byteorder::{NativeEndian, ReadBytesExt, WriteBytesExt};
 use flutter_rust_bridge::for_generated::{transform_result_dco, Lifetimeable, Lockable};
@@ -40,7 +39,7 @@ flutter_rust_bridge::frb_generated_boilerplate!(
     default_rust_auto_opaque = RustAutoOpaqueMoi,
 );
 pub(crate) const FLUTTER_RUST_BRIDGE_CODEGEN_VERSION: &str = "2.10.0";
-pub(crate) const FLUTTER_RUST_BRIDGE_CODEGEN_CONTENT_HASH: i32 = 294099415;
+pub(crate) const FLUTTER_RUST_BRIDGE_CODEGEN_CONTENT_HASH: i32 = 1904519848;
 
 // Section: executor
 
@@ -2060,6 +2059,47 @@ fn wire__crate__api__astral_wfp__WfpController_new_impl(
         },
     )
 }
+fn wire__crate__api__port_forward__add_port_forward_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_async::<flutter_rust_bridge::for_generated::SseCodec, _, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "add_port_forward",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
+            let api_forward = <crate::api::simple::Forward>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| async move {
+                transform_result_sse::<_, String>(
+                    (move || async move {
+                        let output_ok = crate::api::port_forward::add_port_forward(
+                            api_instance_id,
+                            api_forward,
+                        )
+                        .await?;
+                        Ok(output_ok)
+                    })()
+                    .await,
+                )
+            }
+        },
+    )
+}
 fn wire__crate__api__utils__check_sudo_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
@@ -2092,7 +2132,7 @@ fn wire__crate__api__utils__check_sudo_impl(
         },
     )
 }
-fn wire__crate__api__simple__close_server_impl(
+fn wire__crate__api__simple__close_all_servers_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
@@ -2100,7 +2140,7 @@ fn wire__crate__api__simple__close_server_impl(
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "close_server",
+            debug_name: "close_all_servers",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2118,7 +2158,7 @@ fn wire__crate__api__simple__close_server_impl(
             move |context| {
                 transform_result_sse::<_, ()>((move || {
                     let output_ok = Result::<_, ()>::Ok({
-                        crate::api::simple::close_server();
+                        crate::api::simple::close_all_servers();
                     })?;
                     Ok(output_ok)
                 })())
@@ -2126,13 +2166,46 @@ fn wire__crate__api__simple__close_server_impl(
         },
     )
 }
-fn wire__crate__api__simple__create_server_impl(
+fn wire__crate__api__simple__close_server_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
     data_len_: i32,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "close_server",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| {
+                transform_result_sse::<_, String>((move || {
+                    let output_ok = crate::api::simple::close_server(api_instance_id)?;
+                    Ok(output_ok)
+                })())
+            }
+        },
+    )
+}
+fn wire__crate__api__simple__create_server_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_async::<flutter_rust_bridge::for_generated::SseCodec, _, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
             debug_name: "create_server",
             port: Some(port_),
@@ -2159,22 +2232,26 @@ fn wire__crate__api__simple__create_server_impl(
             let api_forwards = <Vec<crate::api::simple::Forward>>::sse_decode(&mut deserializer);
             let api_flag = <crate::api::simple::FlagsC>::sse_decode(&mut deserializer);
             deserializer.end();
-            move |context| {
-                transform_result_sse::<_, ()>((move || {
-                    let output_ok = Result::<_, ()>::Ok(crate::api::simple::create_server(
-                        api_username,
-                        api_enable_dhcp,
-                        api_specified_ip,
-                        api_room_name,
-                        api_room_password,
-                        api_severurl,
-                        api_onurl,
-                        api_cidrs,
-                        api_forwards,
-                        api_flag,
-                    ))?;
-                    Ok(output_ok)
-                })())
+            move |context| async move {
+                transform_result_sse::<_, String>(
+                    (move || async move {
+                        let output_ok = crate::api::simple::create_server(
+                            api_username,
+                            api_enable_dhcp,
+                            api_specified_ip,
+                            api_room_name,
+                            api_room_password,
+                            api_severurl,
+                            api_onurl,
+                            api_cidrs,
+                            api_forwards,
+                            api_flag,
+                        )
+                        .await?;
+                        Ok(output_ok)
+                    })()
+                    .await,
+                )
             }
         },
     )
@@ -2303,17 +2380,19 @@ fn wire__crate__api__simple__get_ips_impl(
             };
             let mut deserializer =
                 flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
             deserializer.end();
             move |context| {
                 transform_result_sse::<_, ()>((move || {
-                    let output_ok = Result::<_, ()>::Ok(crate::api::simple::get_ips())?;
+                    let output_ok =
+                        Result::<_, ()>::Ok(crate::api::simple::get_ips(api_instance_id))?;
                     Ok(output_ok)
                 })())
             }
         },
     )
 }
-fn wire__crate__api__simple__get_network_status_impl(
+fn wire__crate__api__simple__get_network_snapshot_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
@@ -2321,7 +2400,7 @@ fn wire__crate__api__simple__get_network_status_impl(
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "get_network_status",
+            debug_name: "get_network_snapshot",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2335,10 +2414,11 @@ fn wire__crate__api__simple__get_network_status_impl(
             };
             let mut deserializer =
                 flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
             deserializer.end();
             move |context| {
-                transform_result_sse::<_, ()>((move || {
-                    let output_ok = Result::<_, ()>::Ok(crate::api::simple::get_network_status())?;
+                transform_result_sse::<_, String>((move || {
+                    let output_ok = crate::api::simple::get_network_snapshot(api_instance_id)?;
                     Ok(output_ok)
                 })())
             }
@@ -2379,15 +2459,15 @@ fn wire__crate__api__nt__get_nt_path_impl(
         },
     )
 }
-fn wire__crate__api__simple__get_peer_route_pairs_impl(
+fn wire__crate__api__secret_rotation__get_secret_rotation_status_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
     data_len_: i32,
 ) {
-    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_async::<flutter_rust_bridge::for_generated::SseCodec, _, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "get_peer_route_pairs",
+            debug_name: "get_secret_rotation_status",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2401,17 +2481,24 @@ fn wire__crate__api__simple__get_peer_route_pairs_impl(
             };
             let mut deserializer =
                 flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
             deserializer.end();
-            move |context| {
-                transform_result_sse::<_, String>((move || {
-                    let output_ok = crate::api::simple::get_peer_route_pairs()?;
-                    Ok(output_ok)
-                })())
+            move |context| async move {
+                transform_result_sse::<_, String>(
+                    (move || async move {
+                        let output_ok = crate::api::secret_rotation::get_secret_rotation_status(
+                            api_instance_id,
+                        )
+                        .await?;
+                        Ok(output_ok)
+                    })()
+                    .await,
+                )
             }
         },
     )
 }
-fn wire__crate__api__simple__get_running_info_impl(
+fn wire__crate__api__simple__init_app_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
@@ -2419,7 +2506,7 @@ fn wire__crate__api__simple__get_running_info_impl(
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "get_running_info",
+            debug_name: "init_app",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2436,14 +2523,16 @@ fn wire__crate__api__simple__get_running_info_impl(
             deserializer.end();
             move |context| {
                 transform_result_sse::<_, ()>((move || {
-                    let output_ok = Result::<_, ()>::Ok(crate::api::simple::get_running_info())?;
+                    let output_ok = Result::<_, ()>::Ok({
+                        crate::api::simple::init_app();
+                    })?;
                     Ok(output_ok)
                 })())
             }
         },
     )
 }
-fn wire__crate__api__simple__handle_event_impl(
+fn wire__crate__api__simple__is_easytier_running_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
@@ -2451,7 +2540,7 @@ fn wire__crate__api__simple__handle_event_impl(
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "handle_event",
+            debug_name: "is_easytier_running",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2465,19 +2554,17 @@ fn wire__crate__api__simple__handle_event_impl(
             };
             let mut deserializer =
                 flutter_rust_bridge::for_generated::SseDeserializer::new(message);
-            let api_events = <EventBusSubscriber>::sse_decode(&mut deserializer);
             deserializer.end();
             move |context| {
                 transform_result_sse::<_, ()>((move || {
-                    let output_ok =
-                        Result::<_, ()>::Ok(crate::api::simple::handle_event(api_events))?;
+                    let output_ok = Result::<_, ()>::Ok(crate::api::simple::is_easytier_running())?;
                     Ok(output_ok)
                 })())
             }
         },
     )
 }
-fn wire__crate__api__simple__init_app_impl(
+fn wire__crate__api__simple__is_instance_running_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
@@ -2485,7 +2572,7 @@ fn wire__crate__api__simple__init_app_impl(
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "init_app",
+            debug_name: "is_instance_running",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2499,27 +2586,28 @@ fn wire__crate__api__simple__init_app_impl(
             };
             let mut deserializer =
                 flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
             deserializer.end();
             move |context| {
                 transform_result_sse::<_, ()>((move || {
-                    let output_ok = Result::<_, ()>::Ok({
-                        crate::api::simple::init_app();
-                    })?;
+                    let output_ok = Result::<_, ()>::Ok(crate::api::simple::is_instance_running(
+                        api_instance_id,
+                    ))?;
                     Ok(output_ok)
                 })())
             }
         },
     )
 }
-fn wire__crate__api__simple__is_easytier_running_impl(
+fn wire__crate__api__bandwidth__list_bandwidth_limits_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
     data_len_: i32,
 ) {
-    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_async::<flutter_rust_bridge::for_generated::SseCodec, _, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "is_easytier_running",
+            debug_name: "list_bandwidth_limits",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2533,17 +2621,22 @@ fn wire__crate__api__simple__is_easytier_running_impl(
             };
             let mut deserializer =
                 flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
             deserializer.end();
-            move |context| {
-                transform_result_sse::<_, ()>((move || {
-                    let output_ok = Result::<_, ()>::Ok(crate::api::simple::is_easytier_running())?;
-                    Ok(output_ok)
-                })())
+            move |context| async move {
+                transform_result_sse::<_, String>(
+                    (move || async move {
+                        let output_ok =
+                            crate::api::bandwidth::list_bandwidth_limits(api_instance_id).await?;
+                        Ok(output_ok)
+                    })()
+                    .await,
+                )
             }
         },
     )
 }
-fn wire__crate__api__simple__send_udp_to_localhost_impl(
+fn wire__crate__api__simple__list_instances_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
@@ -2551,7 +2644,7 @@ fn wire__crate__api__simple__send_udp_to_localhost_impl(
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "send_udp_to_localhost",
+            debug_name: "list_instances",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2565,18 +2658,17 @@ fn wire__crate__api__simple__send_udp_to_localhost_impl(
             };
             let mut deserializer =
                 flutter_rust_bridge::for_generated::SseDeserializer::new(message);
-            let api_message = <String>::sse_decode(&mut deserializer);
             deserializer.end();
             move |context| {
-                transform_result_sse::<_, String>((move || {
-                    let output_ok = crate::api::simple::send_udp_to_localhost(&api_message)?;
+                transform_result_sse::<_, ()>((move || {
+                    let output_ok = Result::<_, ()>::Ok(crate::api::simple::list_instances())?;
                     Ok(output_ok)
                 })())
             }
         },
     )
 }
-fn wire__crate__api__firewall__set_firewall_status_impl(
+fn wire__crate__api__hops__list_network_interfaces_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
@@ -2584,7 +2676,7 @@ fn wire__crate__api__firewall__set_firewall_status_impl(
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "set_firewall_status",
+            debug_name: "list_network_interfaces",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2598,16 +2690,11 @@ fn wire__crate__api__firewall__set_firewall_status_impl(
             };
             let mut deserializer =
                 flutter_rust_bridge::for_generated::SseDeserializer::new(message);
-            let api_profile_index = <u32>::sse_decode(&mut deserializer);
-            let api_enable = <bool>::sse_decode(&mut deserializer);
             deserializer.end();
             move |context| {
                 transform_result_sse::<_, flutter_rust_bridge::for_generated::anyhow::Error>(
                     (move || {
-                        let output_ok = crate::api::firewall::set_firewall_status(
-                            api_profile_index,
-                            api_enable,
-                        )?;
+                        let output_ok = crate::api::hops::list_network_interfaces()?;
                         Ok(output_ok)
                     })(),
                 )
@@ -2615,7 +2702,44 @@ fn wire__crate__api__firewall__set_firewall_status_impl(
         },
     )
 }
-fn wire__crate__api__hops__set_interface_metric_impl(
+fn wire__crate__api__port_forward__list_port_forwards_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_async::<flutter_rust_bridge::for_generated::SseCodec, _, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "list_port_forwards",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| async move {
+                transform_result_sse::<_, String>(
+                    (move || async move {
+                        let output_ok =
+                            crate::api::port_forward::list_port_forwards(api_instance_id).await?;
+                        Ok(output_ok)
+                    })()
+                    .await,
+                )
+            }
+        },
+    )
+}
+fn wire__crate__api__hops__prefer_interface_for_broadcast_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
@@ -2623,7 +2747,7 @@ fn wire__crate__api__hops__set_interface_metric_impl(
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "set_interface_metric",
+            debug_name: "prefer_interface_for_broadcast",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2638,15 +2762,12 @@ fn wire__crate__api__hops__set_interface_metric_impl(
             let mut deserializer =
                 flutter_rust_bridge::for_generated::SseDeserializer::new(message);
             let api_interface_name = <String>::sse_decode(&mut deserializer);
-            let api_metric = <u32>::sse_decode(&mut deserializer);
             deserializer.end();
             move |context| {
                 transform_result_sse::<_, flutter_rust_bridge::for_generated::anyhow::Error>(
                     (move || {
-                        let output_ok = crate::api::hops::set_interface_metric(
-                            &api_interface_name,
-                            api_metric,
-                        )?;
+                        let output_ok =
+                            crate::api::hops::prefer_interface_for_broadcast(&api_interface_name)?;
                         Ok(output_ok)
                     })(),
                 )
@@ -2654,15 +2775,15 @@ fn wire__crate__api__hops__set_interface_metric_impl(
         },
     )
 }
-fn wire__crate__api__simple__set_tun_fd_impl(
+fn wire__crate__api__bandwidth__remove_bandwidth_limit_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
     data_len_: i32,
 ) {
-    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_async::<flutter_rust_bridge::for_generated::SseCodec, _, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "set_tun_fd",
+            debug_name: "remove_bandwidth_limit",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2676,18 +2797,109 @@ fn wire__crate__api__simple__set_tun_fd_impl(
             };
             let mut deserializer =
                 flutter_rust_bridge::for_generated::SseDeserializer::new(message);
-            let api_fd = <i32>::sse_decode(&mut deserializer);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
+            let api_peer_id = <Option<u32>>::sse_decode(&mut deserializer);
+            let api_cidr = <Option<String>>::sse_decode(&mut deserializer);
             deserializer.end();
-            move |context| {
-                transform_result_sse::<_, String>((move || {
-                    let output_ok = crate::api::simple::set_tun_fd(api_fd)?;
-                    Ok(output_ok)
-                })())
+            move |context| async move {
+                transform_result_sse::<_, String>(
+                    (move || async move {
+                        let output_ok = crate::api::bandwidth::remove_bandwidth_limit(
+                            api_instance_id,
+                            api_peer_id,
+                            api_cidr,
+                        )
+                        .await?;
+                        Ok(output_ok)
+                    })()
+                    .await,
+                )
             }
         },
     )
 }
-fn wire__crate__api__astral_wfp__to_wide_string_impl(
+fn wire__crate__api__port_forward__remove_port_forward_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_async::<flutter_rust_bridge::for_generated::SseCodec, _, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "remove_port_forward",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
+            let api_forward = <crate::api::simple::Forward>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| async move {
+                transform_result_sse::<_, String>(
+                    (move || async move {
+                        let output_ok = crate::api::port_forward::remove_port_forward(
+                            api_instance_id,
+                            api_forward,
+                        )
+                        .await?;
+                        Ok(output_ok)
+                    })()
+                    .await,
+                )
+            }
+        },
+    )
+}
+fn wire__crate__api__bandwidth__set_bandwidth_limit_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_async::<flutter_rust_bridge::for_generated::SseCodec, _, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "set_bandwidth_limit",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
+            let api_limit =
+                <crate::api::bandwidth::AstralBandwidthLimit>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| async move {
+                transform_result_sse::<_, String>(
+                    (move || async move {
+                        let output_ok =
+                            crate::api::bandwidth::set_bandwidth_limit(api_instance_id, api_limit)
+                                .await?;
+                        Ok(output_ok)
+                    })()
+                    .await,
+                )
+            }
+        },
+    )
+}
+fn wire__crate__api__firewall__set_firewall_status_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
@@ -2695,7 +2907,7 @@ fn wire__crate__api__astral_wfp__to_wide_string_impl(
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
-            debug_name: "to_wide_string",
+            debug_name: "set_firewall_status",
             port: Some(port_),
             mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
         },
@@ -2709,46 +2921,227 @@ fn wire__crate__api__astral_wfp__to_wide_string_impl(
             };
             let mut deserializer =
                 flutter_rust_bridge::for_generated::SseDeserializer::new(message);
-            let api_s = <String>::sse_decode(&mut deserializer);
+            let api_profile_index = <u32>::sse_decode(&mut deserializer);
+            let api_enable = <bool>::sse_decode(&mut deserializer);
             deserializer.end();
             move |context| {
-                transform_result_sse::<_, ()>((move || {
-                    let output_ok =
-                        Result::<_, ()>::Ok(crate::api::astral_wfp::to_wide_string(&api_s))?;
-                    Ok(output_ok)
-                })())
+                transform_result_sse::<_, flutter_rust_bridge::for_generated::anyhow::Error>(
+                    (move || {
+                        let output_ok = crate::api::firewall::set_firewall_status(
+                            api_profile_index,
+                            api_enable,
+                        )?;
+                        Ok(output_ok)
+                    })(),
+                )
             }
         },
     )
 }
-
-// Section: related_funcs
-
-flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
-    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<EventBusSubscriber>
-);
-flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
-    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<FilterRule>
-);
-flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
-    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<GUID>
-);
-flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
-    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpAddr>
-);
-flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
-    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpNetwork>
-);
-flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
-    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<()>>
-);
-flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
-    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<Result<(), String>>>
-);
-flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
-    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<PeerRoutePair>
-);
-flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
+fn wire__crate__api__hops__set_interface_metric_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "set_interface_metric",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_interface_name = <String>::sse_decode(&mut deserializer);
+            let api_metric = <u32>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| {
+                transform_result_sse::<_, flutter_rust_bridge::for_generated::anyhow::Error>(
+                    (move || {
+                        let output_ok = crate::api::hops::set_interface_metric(
+                            &api_interface_name,
+                            api_metric,
+                        )?;
+                        Ok(output_ok)
+                    })(),
+                )
+            }
+        },
+    )
+}
+fn wire__crate__api__simple__set_tun_fd_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "set_tun_fd",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
+            let api_fd = <i32>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| {
+                transform_result_sse::<_, String>((move || {
+                    let output_ok = crate::api::simple::set_tun_fd(api_instance_id, api_fd)?;
+                    Ok(output_ok)
+                })())
+            }
+        },
+    )
+}
+fn wire__crate__api__secret_rotation__start_secret_rotation_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_async::<flutter_rust_bridge::for_generated::SseCodec, _, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "start_secret_rotation",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_instance_id = <String>::sse_decode(&mut deserializer);
+            let api_network_secret = <String>::sse_decode(&mut deserializer);
+            let api_grace_period_sec = <u32>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| async move {
+                transform_result_sse::<_, String>(
+                    (move || async move {
+                        let output_ok = crate::api::secret_rotation::start_secret_rotation(
+                            api_instance_id,
+                            api_network_secret,
+                            api_grace_period_sec,
+                        )
+                        .await?;
+                        Ok(output_ok)
+                    })()
+                    .await,
+                )
+            }
+        },
+    )
+}
+fn wire__crate__api__events__subscribe_events_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "subscribe_events",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_sink = <StreamSink<
+                crate::api::events::AstralEvent,
+                flutter_rust_bridge::for_generated::SseCodec,
+            >>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| {
+                transform_result_sse::<_, ()>((move || {
+                    let output_ok = Result::<_, ()>::Ok({
+                        crate::api::events::subscribe_events(api_sink);
+                    })?;
+                    Ok(output_ok)
+                })())
+            }
+        },
+    )
+}
+fn wire__crate__api__astral_wfp__to_wide_string_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "to_wide_string",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_s = <String>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| {
+                transform_result_sse::<_, ()>((move || {
+                    let output_ok =
+                        Result::<_, ()>::Ok(crate::api::astral_wfp::to_wide_string(&api_s))?;
+                    Ok(output_ok)
+                })())
+            }
+        },
+    )
+}
+
+// Section: related_funcs
+
+flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
+    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<FilterRule>
+);
+flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
+    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<GUID>
+);
+flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
+    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpAddr>
+);
+flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
+    flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpNetwork>
+);
+flutter_rust_bridge::frb_generated_moi_arc_impl_value!(
     flutter_rust_bridge::for_generated::RustAutoOpaqueInner<WfpController>
 );
 
@@ -2762,16 +3155,6 @@ impl SseDecode for flutter_rust_bridge::for_generated::anyhow::Error {
     }
 }
 
-impl SseDecode for EventBusSubscriber {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <RustOpaqueMoi<
-            flutter_rust_bridge::for_generated::RustAutoOpaqueInner<EventBusSubscriber>,
-        >>::sse_decode(deserializer);
-        return flutter_rust_bridge::for_generated::rust_auto_opaque_decode_owned(inner);
-    }
-}
-
 impl SseDecode for FilterRule {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -2802,48 +3185,44 @@ impl SseDecode for IpNetwork {
     }
 }
 
-impl SseDecode for JoinHandle<()> {
+impl SseDecode for WfpController {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
         let mut inner = <RustOpaqueMoi<
-            flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<()>>,
+            flutter_rust_bridge::for_generated::RustAutoOpaqueInner<WfpController>,
         >>::sse_decode(deserializer);
         return flutter_rust_bridge::for_generated::rust_auto_opaque_decode_owned(inner);
     }
 }
 
-impl SseDecode for JoinHandle<Result<(), String>> {
+impl SseDecode
+    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<FilterRule>>
+{
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <RustOpaqueMoi<
-            flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<Result<(), String>>>,
-        >>::sse_decode(deserializer);
-        return flutter_rust_bridge::for_generated::rust_auto_opaque_decode_owned(inner);
+        let mut inner = <usize>::sse_decode(deserializer);
+        return decode_rust_opaque_moi(inner);
     }
 }
 
-impl SseDecode for PeerRoutePair {
+impl SseDecode for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<GUID>> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <RustOpaqueMoi<
-            flutter_rust_bridge::for_generated::RustAutoOpaqueInner<PeerRoutePair>,
-        >>::sse_decode(deserializer);
-        return flutter_rust_bridge::for_generated::rust_auto_opaque_decode_owned(inner);
+        let mut inner = <usize>::sse_decode(deserializer);
+        return decode_rust_opaque_moi(inner);
     }
 }
 
-impl SseDecode for WfpController {
+impl SseDecode for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpAddr>> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <RustOpaqueMoi<
-            flutter_rust_bridge::for_generated::RustAutoOpaqueInner<WfpController>,
-        >>::sse_decode(deserializer);
-        return flutter_rust_bridge::for_generated::rust_auto_opaque_decode_owned(inner);
+        let mut inner = <usize>::sse_decode(deserializer);
+        return decode_rust_opaque_moi(inner);
     }
 }
 
 impl SseDecode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<EventBusSubscriber>>
+    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpNetwork>>
 {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -2853,7 +3232,7 @@ impl SseDecode
 }
 
 impl SseDecode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<FilterRule>>
+    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<WfpController>>
 {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -2862,79 +3241,350 @@ impl SseDecode
     }
 }
 
-impl SseDecode for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<GUID>> {
+impl SseDecode
+    for StreamSink<crate::api::events::AstralEvent, flutter_rust_bridge::for_generated::SseCodec>
+{
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <usize>::sse_decode(deserializer);
-        return decode_rust_opaque_moi(inner);
+        let mut inner = <String>::sse_decode(deserializer);
+        return StreamSink::deserialize(inner);
     }
 }
 
-impl SseDecode for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpAddr>> {
+impl SseDecode for String {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <usize>::sse_decode(deserializer);
-        return decode_rust_opaque_moi(inner);
+        let mut inner = <Vec<u8>>::sse_decode(deserializer);
+        return String::from_utf8(inner).unwrap();
     }
 }
 
-impl SseDecode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpNetwork>>
-{
+impl SseDecode for crate::api::bandwidth::AstralBandwidthLimit {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <usize>::sse_decode(deserializer);
-        return decode_rust_opaque_moi(inner);
+        let mut var_peerId = <Option<u32>>::sse_decode(deserializer);
+        let mut var_cidr = <Option<String>>::sse_decode(deserializer);
+        let mut var_uploadBps = <Option<u64>>::sse_decode(deserializer);
+        let mut var_downloadBps = <Option<u64>>::sse_decode(deserializer);
+        return crate::api::bandwidth::AstralBandwidthLimit {
+            peer_id: var_peerId,
+            cidr: var_cidr,
+            upload_bps: var_uploadBps,
+            download_bps: var_downloadBps,
+        };
     }
 }
 
-impl SseDecode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<()>>>
-{
+impl SseDecode for crate::api::simple::AstralConnection {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <usize>::sse_decode(deserializer);
-        return decode_rust_opaque_moi(inner);
+        let mut var_connId = <String>::sse_decode(deserializer);
+        let mut var_tunnelType = <String>::sse_decode(deserializer);
+        let mut var_localAddr = <String>::sse_decode(deserializer);
+        let mut var_remoteAddr = <String>::sse_decode(deserializer);
+        let mut var_isClient = <bool>::sse_decode(deserializer);
+        let mut var_latencyMs = <f64>::sse_decode(deserializer);
+        let mut var_lossRate = <f32>::sse_decode(deserializer);
+        let mut var_rxBytes = <u64>::sse_decode(deserializer);
+        let mut var_txBytes = <u64>::sse_decode(deserializer);
+        let mut var_rxPackets = <u64>::sse_decode(deserializer);
+        let mut var_txPackets = <u64>::sse_decode(deserializer);
+        return crate::api::simple::AstralConnection {
+            conn_id: var_connId,
+            tunnel_type: var_tunnelType,
+            local_addr: var_localAddr,
+            remote_addr: var_remoteAddr,
+            is_client: var_isClient,
+            latency_ms: var_latencyMs,
+            loss_rate: var_lossRate,
+            rx_bytes: var_rxBytes,
+            tx_bytes: var_txBytes,
+            rx_packets: var_rxPackets,
+            tx_packets: var_txPackets,
+        };
     }
 }
 
-impl SseDecode
-    for RustOpaqueMoi<
-        flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<Result<(), String>>>,
-    >
-{
+impl SseDecode for crate::api::events::AstralEvent {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <usize>::sse_decode(deserializer);
-        return decode_rust_opaque_moi(inner);
+        let mut var_instanceId = <String>::sse_decode(deserializer);
+        let mut var_timestampMs = <i64>::sse_decode(deserializer);
+        let mut var_kind = <crate::api::events::AstralEventKind>::sse_decode(deserializer);
+        let mut var_peerId = <Option<u32>>::sse_decode(deserializer);
+        let mut var_conn = <Option<crate::api::events::AstralPeerConn>>::sse_decode(deserializer);
+        let mut var_devName = <Option<String>>::sse_decode(deserializer);
+        let mut var_url = <Option<String>>::sse_decode(deserializer);
+        let mut var_ipVersion = <Option<String>>::sse_decode(deserializer);
+        let mut var_localAddr = <Option<String>>::sse_decode(deserializer);
+        let mut var_remoteAddr = <Option<String>>::sse_decode(deserializer);
+        let mut var_portal = <Option<String>>::sse_decode(deserializer);
+        let mut var_clientAddr = <Option<String>>::sse_decode(deserializer);
+        let mut var_oldAddr = <Option<String>>::sse_decode(deserializer);
+        let mut var_newAddr = <Option<String>>::sse_decode(deserializer);
+        let mut var_addr = <Option<String>>::sse_decode(deserializer);
+        let mut var_forward =
+            <Option<crate::api::events::AstralPortForward>>::sse_decode(deserializer);
+        let mut var_rotationId = <Option<u64>>::sse_decode(deserializer);
+        let mut var_count = <Option<u64>>::sse_decode(deserializer);
+        let mut var_error = <Option<String>>::sse_decode(deserializer);
+        return crate::api::events::AstralEvent {
+            instance_id: var_instanceId,
+            timestamp_ms: var_timestampMs,
+            kind: var_kind,
+            peer_id: var_peerId,
+            conn: var_conn,
+            dev_name: var_devName,
+            url: var_url,
+            ip_version: var_ipVersion,
+            local_addr: var_localAddr,
+            remote_addr: var_remoteAddr,
+            portal: var_portal,
+            client_addr: var_clientAddr,
+            old_addr: var_oldAddr,
+            new_addr: var_newAddr,
+            addr: var_addr,
+            forward: var_forward,
+            rotation_id: var_rotationId,
+            count: var_count,
+            error: var_error,
+        };
     }
 }
 
-impl SseDecode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<PeerRoutePair>>
-{
+impl SseDecode for crate::api::events::AstralEventKind {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <usize>::sse_decode(deserializer);
-        return decode_rust_opaque_moi(inner);
+        let mut inner = <i32>::sse_decode(deserializer);
+        return match inner {
+            0 => crate::api::events::AstralEventKind::TunDeviceReady,
+            1 => crate::api::events::AstralEventKind::TunDeviceError,
+            2 => crate::api::events::AstralEventKind::PeerAdded,
+            3 => crate::api::events::AstralEventKind::PeerRemoved,
+            4 => crate::api::events::AstralEventKind::PeerConnAdded,
+            5 => crate::api::events::AstralEventKind::PeerConnRemoved,
+            6 => crate::api::events::AstralEventKind::ListenerAdded,
+            7 => crate::api::events::AstralEventKind::ListenerAddFailed,
+            8 => crate::api::events::AstralEventKind::ListenerAcceptFailed,
+            9 => crate::api::events::AstralEventKind::ConnectionAccepted,
+            10 => crate::api::events::AstralEventKind::ConnectionError,
+            11 => crate::api::events::AstralEventKind::Connecting,
+            12 => crate::api::events::AstralEventKind::ConnectError,
+            13 => crate::api::events::AstralEventKind::VpnPortalStarted,
+            14 => crate::api::events::AstralEventKind::VpnPortalClientConnected,
+            15 => crate::api::events::AstralEventKind::VpnPortalClientDisconnected,
+            16 => crate::api::events::AstralEventKind::DhcpIpv4Changed,
+            17 => crate::api::events::AstralEventKind::DhcpIpv4Conflicted,
+            18 => crate::api::events::AstralEventKind::PortForwardAdded,
+            19 => crate::api::events::AstralEventKind::NetworkSecretRotated,
+            20 => crate::api::events::AstralEventKind::ExitNodeChanged,
+            21 => crate::api::events::AstralEventKind::ExitNodeDown,
+            22 => crate::api::events::AstralEventKind::EventsLagged,
+            23 => crate::api::events::AstralEventKind::EventChannelClosed,
+            _ => unreachable!("Invalid variant for AstralEventKind: {}", inner),
+        };
     }
 }
 
-impl SseDecode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<WfpController>>
-{
+impl SseDecode for crate::api::simple::AstralHop {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <usize>::sse_decode(deserializer);
-        return decode_rust_opaque_moi(inner);
+        let mut var_peerId = <u32>::sse_decode(deserializer);
+        let mut var_hostname = <String>::sse_decode(deserializer);
+        let mut var_ipv4 = <Option<String>>::sse_decode(deserializer);
+        let mut var_ipv6 = <Option<String>>::sse_decode(deserializer);
+        let mut var_latencyMs = <f64>::sse_decode(deserializer);
+        let mut var_lossRate = <f32>::sse_decode(deserializer);
+        return crate::api::simple::AstralHop {
+            peer_id: var_peerId,
+            hostname: var_hostname,
+            ipv4: var_ipv4,
+            ipv6: var_ipv6,
+            latency_ms: var_latencyMs,
+            loss_rate: var_lossRate,
+        };
     }
 }
 
-impl SseDecode for String {
+impl SseDecode for crate::api::hops::AstralInterface {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut inner = <Vec<u8>>::sse_decode(deserializer);
-        return String::from_utf8(inner).unwrap();
+        let mut var_index = <u32>::sse_decode(deserializer);
+        let mut var_name = <String>::sse_decode(deserializer);
+        let mut var_metric = <Option<u32>>::sse_decode(deserializer);
+        let mut var_mtu = <u32>::sse_decode(deserializer);
+        let mut var_addresses = <Vec<String>>::sse_decode(deserializer);
+        let mut var_up = <bool>::sse_decode(deserializer);
+        return crate::api::hops::AstralInterface {
+            index: var_index,
+            name: var_name,
+            metric: var_metric,
+            mtu: var_mtu,
+            addresses: var_addresses,
+            up: var_up,
+        };
+    }
+}
+
+impl SseDecode for crate::api::simple::AstralNetworkSnapshot {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_instanceId = <String>::sse_decode(deserializer);
+        let mut var_devName = <String>::sse_decode(deserializer);
+        let mut var_running = <bool>::sse_decode(deserializer);
+        let mut var_errorMsg = <Option<String>>::sse_decode(deserializer);
+        let mut var_listeners = <Vec<String>>::sse_decode(deserializer);
+        let mut var_nodes = <Vec<crate::api::simple::AstralNode>>::sse_decode(deserializer);
+        return crate::api::simple::AstralNetworkSnapshot {
+            instance_id: var_instanceId,
+            dev_name: var_devName,
+            running: var_running,
+            error_msg: var_errorMsg,
+            listeners: var_listeners,
+            nodes: var_nodes,
+        };
+    }
+}
+
+impl SseDecode for crate::api::simple::AstralNode {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_peerId = <u32>::sse_decode(deserializer);
+        let mut var_isLocal = <bool>::sse_decode(deserializer);
+        let mut var_hostname = <String>::sse_decode(deserializer);
+        let mut var_version = <String>::sse_decode(deserializer);
+        let mut var_ipv4 = <Option<String>>::sse_decode(deserializer);
+        let mut var_ipv6 = <Option<String>>::sse_decode(deserializer);
+        let mut var_proxyCidrs = <Vec<String>>::sse_decode(deserializer);
+        let mut var_udpNatType = <String>::sse_decode(deserializer);
+        let mut var_tcpNatType = <String>::sse_decode(deserializer);
+        let mut var_nextHopPeerId = <u32>::sse_decode(deserializer);
+        let mut var_cost = <i32>::sse_decode(deserializer);
+        let mut var_latencyMs = <f64>::sse_decode(deserializer);
+        let mut var_lossRate = <f32>::sse_decode(deserializer);
+        let mut var_tunnelTypes = <Vec<String>>::sse_decode(deserializer);
+        let mut var_rxBytes = <u64>::sse_decode(deserializer);
+        let mut var_txBytes = <u64>::sse_decode(deserializer);
+        let mut var_connections =
+            <Vec<crate::api::simple::AstralConnection>>::sse_decode(deserializer);
+        let mut var_hops = <Vec<crate::api::simple::AstralHop>>::sse_decode(deserializer);
+        return crate::api::simple::AstralNode {
+            peer_id: var_peerId,
+            is_local: var_isLocal,
+            hostname: var_hostname,
+            version: var_version,
+            ipv4: var_ipv4,
+            ipv6: var_ipv6,
+            proxy_cidrs: var_proxyCidrs,
+            udp_nat_type: var_udpNatType,
+            tcp_nat_type: var_tcpNatType,
+            next_hop_peer_id: var_nextHopPeerId,
+            cost: var_cost,
+            latency_ms: var_latencyMs,
+            loss_rate: var_lossRate,
+            tunnel_types: var_tunnelTypes,
+            rx_bytes: var_rxBytes,
+            tx_bytes: var_txBytes,
+            connections: var_connections,
+            hops: var_hops,
+        };
+    }
+}
+
+impl SseDecode for crate::api::events::AstralPeerConn {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_connId = <String>::sse_decode(deserializer);
+        let mut var_myPeerId = <u32>::sse_decode(deserializer);
+        let mut var_peerId = <u32>::sse_decode(deserializer);
+        let mut var_tunnelType = <String>::sse_decode(deserializer);
+        let mut var_localAddr = <String>::sse_decode(deserializer);
+        let mut var_remoteAddr = <String>::sse_decode(deserializer);
+        let mut var_isClient = <bool>::sse_decode(deserializer);
+        let mut var_networkName = <String>::sse_decode(deserializer);
+        return crate::api::events::AstralPeerConn {
+            conn_id: var_connId,
+            my_peer_id: var_myPeerId,
+            peer_id: var_peerId,
+            tunnel_type: var_tunnelType,
+            local_addr: var_localAddr,
+            remote_addr: var_remoteAddr,
+            is_client: var_isClient,
+            network_name: var_networkName,
+        };
+    }
+}
+
+impl SseDecode for crate::api::events::AstralPortForward {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_bindAddr = <String>::sse_decode(deserializer);
+        let mut var_dstAddr = <String>::sse_decode(deserializer);
+        let mut var_proto = <String>::sse_decode(deserializer);
+        return crate::api::events::AstralPortForward {
+            bind_addr: var_bindAddr,
+            dst_addr: var_dstAddr,
+            proto: var_proto,
+        };
+    }
+}
+
+impl SseDecode for crate::api::port_forward::AstralPortForwardStatus {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_bindAddr = <String>::sse_decode(deserializer);
+        let mut var_dstAddr = <String>::sse_decode(deserializer);
+        let mut var_proto = <String>::sse_decode(deserializer);
+        let mut var_bound = <bool>::sse_decode(deserializer);
+        let mut var_error = <Option<String>>::sse_decode(deserializer);
+        let mut var_activeConnections = <u32>::sse_decode(deserializer);
+        let mut var_totalConnections = <u64>::sse_decode(deserializer);
+        return crate::api::port_forward::AstralPortForwardStatus {
+            bind_addr: var_bindAddr,
+            dst_addr: var_dstAddr,
+            proto: var_proto,
+            bound: var_bound,
+            error: var_error,
+            active_connections: var_activeConnections,
+            total_connections: var_totalConnections,
+        };
+    }
+}
+
+impl SseDecode for crate::api::secret_rotation::AstralSecretRotationPeer {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_peerId = <u32>::sse_decode(deserializer);
+        let mut var_hostname = <String>::sse_decode(deserializer);
+        let mut var_prepared = <bool>::sse_decode(deserializer);
+        let mut var_committed = <bool>::sse_decode(deserializer);
+        let mut var_error = <Option<String>>::sse_decode(deserializer);
+        return crate::api::secret_rotation::AstralSecretRotationPeer {
+            peer_id: var_peerId,
+            hostname: var_hostname,
+            prepared: var_prepared,
+            committed: var_committed,
+            error: var_error,
+        };
+    }
+}
+
+impl SseDecode for crate::api::secret_rotation::AstralSecretRotationStatus {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_rotationId = <u64>::sse_decode(deserializer);
+        let mut var_initiatedLocally = <bool>::sse_decode(deserializer);
+        let mut var_committed = <bool>::sse_decode(deserializer);
+        let mut var_gracePeriodRemainingSec = <u32>::sse_decode(deserializer);
+        let mut var_peers =
+            <Vec<crate::api::secret_rotation::AstralSecretRotationPeer>>::sse_decode(deserializer);
+        return crate::api::secret_rotation::AstralSecretRotationStatus {
+            rotation_id: var_rotationId,
+            initiated_locally: var_initiatedLocally,
+            committed: var_committed,
+            grace_period_remaining_sec: var_gracePeriodRemainingSec,
+            peers: var_peers,
+        };
     }
 }
 
@@ -2991,6 +3641,7 @@ impl SseDecode for crate::api::simple::FlagsC {
         let mut var_devName = <String>::sse_decode(deserializer);
         let mut var_enableEncryption = <bool>::sse_decode(deserializer);
         let mut var_enableIpv6 = <bool>::sse_decode(deserializer);
+        let mut var_dhcpIpv6 = <bool>::sse_decode(deserializer);
         let mut var_mtu = <u32>::sse_decode(deserializer);
         let mut var_latencyFirst = <bool>::sse_decode(deserializer);
         let mut var_enableExitNode = <bool>::sse_decode(deserializer);
@@ -3002,6 +3653,7 @@ impl SseDecode for crate::api::simple::FlagsC {
         let mut var_disableUdpHolePunching = <bool>::sse_decode(deserializer);
         let mut var_multiThread = <bool>::sse_decode(deserializer);
         let mut var_dataCompressAlgo = <i32>::sse_decode(deserializer);
+        let mut var_dataCompressLevel = <i32>::sse_decode(deserializer);
         let mut var_bindDevice = <bool>::sse_decode(deserializer);
         let mut var_enableKcpProxy = <bool>::sse_decode(deserializer);
         let mut var_disableKcpInput = <bool>::sse_decode(deserializer);
@@ -3016,6 +3668,7 @@ impl SseDecode for crate::api::simple::FlagsC {
             dev_name: var_devName,
             enable_encryption: var_enableEncryption,
             enable_ipv6: var_enableIpv6,
+            dhcp_ipv6: var_dhcpIpv6,
             mtu: var_mtu,
             latency_first: var_latencyFirst,
             enable_exit_node: var_enableExitNode,
@@ -3027,6 +3680,7 @@ impl SseDecode for crate::api::simple::FlagsC {
             disable_udp_hole_punching: var_disableUdpHolePunching,
             multi_thread: var_multiThread,
             data_compress_algo: var_dataCompressAlgo,
+            data_compress_level: var_dataCompressLevel,
             bind_device: var_bindDevice,
             enable_kcp_proxy: var_enableKcpProxy,
             disable_kcp_input: var_disableKcpInput,
@@ -3061,152 +3715,138 @@ impl SseDecode for i32 {
     }
 }
 
-impl SseDecode for crate::api::simple::KVNetworkStatus {
+impl SseDecode for i64 {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut var_totalNodes = <usize>::sse_decode(deserializer);
-        let mut var_nodes = <Vec<crate::api::simple::KVNodeInfo>>::sse_decode(deserializer);
-        return crate::api::simple::KVNetworkStatus {
-            total_nodes: var_totalNodes,
-            nodes: var_nodes,
-        };
+        deserializer.cursor.read_i64::<NativeEndian>().unwrap()
     }
 }
 
-impl SseDecode for crate::api::simple::KVNodeConnectionStats {
+impl SseDecode for Vec<FilterRule> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut var_connType = <String>::sse_decode(deserializer);
-        let mut var_rxBytes = <u64>::sse_decode(deserializer);
-        let mut var_txBytes = <u64>::sse_decode(deserializer);
-        let mut var_rxPackets = <u64>::sse_decode(deserializer);
-        let mut var_txPackets = <u64>::sse_decode(deserializer);
-        return crate::api::simple::KVNodeConnectionStats {
-            conn_type: var_connType,
-            rx_bytes: var_rxBytes,
-            tx_bytes: var_txBytes,
-            rx_packets: var_rxPackets,
-            tx_packets: var_txPackets,
-        };
+        let mut len_ = <i32>::sse_decode(deserializer);
+        let mut ans_ = vec![];
+        for idx_ in 0..len_ {
+            ans_.push(<FilterRule>::sse_decode(deserializer));
+        }
+        return ans_;
+    }
+}
+
+impl SseDecode for Vec<String> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut len_ = <i32>::sse_decode(deserializer);
+        let mut ans_ = vec![];
+        for idx_ in 0..len_ {
+            ans_.push(<String>::sse_decode(deserializer));
+        }
+        return ans_;
     }
 }
 
-impl SseDecode for crate::api::simple::KVNodeInfo {
+impl SseDecode for Vec<crate::api::bandwidth::AstralBandwidthLimit> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut var_hostname = <String>::sse_decode(deserializer);
-        let mut var_ipv4 = <String>::sse_decode(deserializer);
-        let mut var_latencyMs = <f64>::sse_decode(deserializer);
-        let mut var_nat = <String>::sse_decode(deserializer);
-        let mut var_hops = <Vec<crate::api::simple::NodeHopStats>>::sse_decode(deserializer);
-        let mut var_lossRate = <f32>::sse_decode(deserializer);
-        let mut var_connections =
-            <Vec<crate::api::simple::KVNodeConnectionStats>>::sse_decode(deserializer);
-        let mut var_tunnelProto = <String>::sse_decode(deserializer);
-        let mut var_connType = <String>::sse_decode(deserializer);
-        let mut var_rxBytes = <u64>::sse_decode(deserializer);
-        let mut var_txBytes = <u64>::sse_decode(deserializer);
-        let mut var_version = <String>::sse_decode(deserializer);
-        let mut var_cost = <i32>::sse_decode(deserializer);
-        return crate::api::simple::KVNodeInfo {
-            hostname: var_hostname,
-            ipv4: var_ipv4,
-            latency_ms: var_latencyMs,
-            nat: var_nat,
-            hops: var_hops,
-            loss_rate: var_lossRate,
-            connections: var_connections,
-            tunnel_proto: var_tunnelProto,
-            conn_type: var_connType,
-            rx_bytes: var_rxBytes,
-            tx_bytes: var_txBytes,
-            version: var_version,
-            cost: var_cost,
-        };
+        let mut len_ = <i32>::sse_decode(deserializer);
+        let mut ans_ = vec![];
+        for idx_ in 0..len_ {
+            ans_.push(<crate::api::bandwidth::AstralBandwidthLimit>::sse_decode(
+                deserializer,
+            ));
+        }
+        return ans_;
     }
 }
 
-impl SseDecode for Vec<FilterRule> {
+impl SseDecode for Vec<crate::api::simple::AstralConnection> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
         let mut len_ = <i32>::sse_decode(deserializer);
         let mut ans_ = vec![];
         for idx_ in 0..len_ {
-            ans_.push(<FilterRule>::sse_decode(deserializer));
+            ans_.push(<crate::api::simple::AstralConnection>::sse_decode(
+                deserializer,
+            ));
         }
         return ans_;
     }
 }
 
-impl SseDecode for Vec<PeerRoutePair> {
+impl SseDecode for Vec<crate::api::simple::AstralHop> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
         let mut len_ = <i32>::sse_decode(deserializer);
         let mut ans_ = vec![];
         for idx_ in 0..len_ {
-            ans_.push(<PeerRoutePair>::sse_decode(deserializer));
+            ans_.push(<crate::api::simple::AstralHop>::sse_decode(deserializer));
         }
         return ans_;
     }
 }
 
-impl SseDecode for Vec<String> {
+impl SseDecode for Vec<crate::api::hops::AstralInterface> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
         let mut len_ = <i32>::sse_decode(deserializer);
         let mut ans_ = vec![];
         for idx_ in 0..len_ {
-            ans_.push(<String>::sse_decode(deserializer));
+            ans_.push(<crate::api::hops::AstralInterface>::sse_decode(
+                deserializer,
+            ));
         }
         return ans_;
     }
 }
 
-impl SseDecode for Vec<crate::api::simple::Forward> {
+impl SseDecode for Vec<crate::api::simple::AstralNode> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
         let mut len_ = <i32>::sse_decode(deserializer);
         let mut ans_ = vec![];
         for idx_ in 0..len_ {
-            ans_.push(<crate::api::simple::Forward>::sse_decode(deserializer));
+            ans_.push(<crate::api::simple::AstralNode>::sse_decode(deserializer));
         }
         return ans_;
     }
 }
 
-impl SseDecode for Vec<crate::api::simple::KVNodeConnectionStats> {
+impl SseDecode for Vec<crate::api::port_forward::AstralPortForwardStatus> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
         let mut len_ = <i32>::sse_decode(deserializer);
         let mut ans_ = vec![];
         for idx_ in 0..len_ {
-            ans_.push(<crate::api::simple::KVNodeConnectionStats>::sse_decode(
-                deserializer,
-            ));
+            ans_.push(
+                <crate::api::port_forward::AstralPortForwardStatus>::sse_decode(deserializer),
+            );
         }
         return ans_;
     }
 }
 
-impl SseDecode for Vec<crate::api::simple::KVNodeInfo> {
+impl SseDecode for Vec<crate::api::secret_rotation::AstralSecretRotationPeer> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
         let mut len_ = <i32>::sse_decode(deserializer);
         let mut ans_ = vec![];
         for idx_ in 0..len_ {
-            ans_.push(<crate::api::simple::KVNodeInfo>::sse_decode(deserializer));
+            ans_.push(
+                <crate::api::secret_rotation::AstralSecretRotationPeer>::sse_decode(deserializer),
+            );
         }
         return ans_;
     }
 }
 
-impl SseDecode for Vec<crate::api::simple::NodeHopStats> {
+impl SseDecode for Vec<crate::api::simple::Forward> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
         let mut len_ = <i32>::sse_decode(deserializer);
         let mut ans_ = vec![];
         for idx_ in 0..len_ {
-            ans_.push(<crate::api::simple::NodeHopStats>::sse_decode(deserializer));
+            ans_.push(<crate::api::simple::Forward>::sse_decode(deserializer));
         }
         return ans_;
     }
@@ -3260,27 +3900,37 @@ impl SseDecode for Vec<(String, u32)> {
     }
 }
 
-impl SseDecode for crate::api::simple::NodeHopStats {
+impl SseDecode for Option<String> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
-        let mut var_targetIp = <String>::sse_decode(deserializer);
-        let mut var_latencyMs = <f64>::sse_decode(deserializer);
-        let mut var_packetLoss = <f32>::sse_decode(deserializer);
-        let mut var_nodeName = <String>::sse_decode(deserializer);
-        return crate::api::simple::NodeHopStats {
-            target_ip: var_targetIp,
-            latency_ms: var_latencyMs,
-            packet_loss: var_packetLoss,
-            node_name: var_nodeName,
-        };
+        if (<bool>::sse_decode(deserializer)) {
+            return Some(<String>::sse_decode(deserializer));
+        } else {
+            return None;
+        }
     }
 }
 
-impl SseDecode for Option<String> {
+impl SseDecode for Option<crate::api::events::AstralPeerConn> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
         if (<bool>::sse_decode(deserializer)) {
-            return Some(<String>::sse_decode(deserializer));
+            return Some(<crate::api::events::AstralPeerConn>::sse_decode(
+                deserializer,
+            ));
+        } else {
+            return None;
+        }
+    }
+}
+
+impl SseDecode for Option<crate::api::events::AstralPortForward> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        if (<bool>::sse_decode(deserializer)) {
+            return Some(<crate::api::events::AstralPortForward>::sse_decode(
+                deserializer,
+            ));
         } else {
             return None;
         }
@@ -3331,6 +3981,17 @@ impl SseDecode for Option<u32> {
     }
 }
 
+impl SseDecode for Option<u64> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        if (<bool>::sse_decode(deserializer)) {
+            return Some(<u64>::sse_decode(deserializer));
+        } else {
+            return None;
+        }
+    }
+}
+
 impl SseDecode for crate::api::astral_wfp::Protocol {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -3461,38 +4122,87 @@ fn pde_ffi_dispatcher_primary_impl(
         41 => {
             wire__crate__api__astral_wfp__WfpController_new_impl(port, ptr, rust_vec_len, data_len)
         }
-        42 => wire__crate__api__utils__check_sudo_impl(port, ptr, rust_vec_len, data_len),
-        43 => wire__crate__api__simple__close_server_impl(port, ptr, rust_vec_len, data_len),
-        44 => wire__crate__api__simple__create_server_impl(port, ptr, rust_vec_len, data_len),
-        45 => wire__crate__api__simple__easytier_version_impl(port, ptr, rust_vec_len, data_len),
-        46 => wire__crate__api__hops__get_all_interfaces_metrics_impl(
+        42 => {
+            wire__crate__api__port_forward__add_port_forward_impl(port, ptr, rust_vec_len, data_len)
+        }
+        43 => wire__crate__api__utils__check_sudo_impl(port, ptr, rust_vec_len, data_len),
+        44 => wire__crate__api__simple__close_all_servers_impl(port, ptr, rust_vec_len, data_len),
+        45 => wire__crate__api__simple__close_server_impl(port, ptr, rust_vec_len, data_len),
+        46 => wire__crate__api__simple__create_server_impl(port, ptr, rust_vec_len, data_len),
+        47 => wire__crate__api__simple__easytier_version_impl(port, ptr, rust_vec_len, data_len),
+        48 => wire__crate__api__hops__get_all_interfaces_metrics_impl(
             port,
             ptr,
             rust_vec_len,
             data_len,
         ),
-        47 => {
+        49 => {
             wire__crate__api__firewall__get_firewall_status_impl(port, ptr, rust_vec_len, data_len)
         }
-        48 => wire__crate__api__simple__get_ips_impl(port, ptr, rust_vec_len, data_len),
-        49 => wire__crate__api__simple__get_network_status_impl(port, ptr, rust_vec_len, data_len),
-        50 => wire__crate__api__nt__get_nt_path_impl(port, ptr, rust_vec_len, data_len),
+        50 => wire__crate__api__simple__get_ips_impl(port, ptr, rust_vec_len, data_len),
         51 => {
-            wire__crate__api__simple__get_peer_route_pairs_impl(port, ptr, rust_vec_len, data_len)
+            wire__crate__api__simple__get_network_snapshot_impl(port, ptr, rust_vec_len, data_len)
         }
-        52 => wire__crate__api__simple__get_running_info_impl(port, ptr, rust_vec_len, data_len),
-        53 => wire__crate__api__simple__handle_event_impl(port, ptr, rust_vec_len, data_len),
+        52 => wire__crate__api__nt__get_nt_path_impl(port, ptr, rust_vec_len, data_len),
+        53 => wire__crate__api__secret_rotation__get_secret_rotation_status_impl(
+            port,
+            ptr,
+            rust_vec_len,
+            data_len,
+        ),
         54 => wire__crate__api__simple__init_app_impl(port, ptr, rust_vec_len, data_len),
         55 => wire__crate__api__simple__is_easytier_running_impl(port, ptr, rust_vec_len, data_len),
-        56 => {
-            wire__crate__api__simple__send_udp_to_localhost_impl(port, ptr, rust_vec_len, data_len)
+        56 => wire__crate__api__simple__is_instance_running_impl(port, ptr, rust_vec_len, data_len),
+        57 => wire__crate__api__bandwidth__list_bandwidth_limits_impl(
+            port,
+            ptr,
+            rust_vec_len,
+            data_len,
+        ),
+        58 => wire__crate__api__simple__list_instances_impl(port, ptr, rust_vec_len, data_len),
+        59 => {
+            wire__crate__api__hops__list_network_interfaces_impl(port, ptr, rust_vec_len, data_len)
+        }
+        60 => wire__crate__api__port_forward__list_port_forwards_impl(
+            port,
+            ptr,
+            rust_vec_len,
+            data_len,
+        ),
+        61 => wire__crate__api__hops__prefer_interface_for_broadcast_impl(
+            port,
+            ptr,
+            rust_vec_len,
+            data_len,
+        ),
+        62 => wire__crate__api__bandwidth__remove_bandwidth_limit_impl(
+            port,
+            ptr,
+            rust_vec_len,
+            data_len,
+        ),
+        63 => wire__crate__api__port_forward__remove_port_forward_impl(
+            port,
+            ptr,
+            rust_vec_len,
+            data_len,
+        ),
+        64 => {
+            wire__crate__api__bandwidth__set_bandwidth_limit_impl(port, ptr, rust_vec_len, data_len)
         }
-        57 => {
+        65 => {
             wire__crate__api__firewall__set_firewall_status_impl(port, ptr, rust_vec_len, data_len)
         }
-        58 => wire__crate__api__hops__set_interface_metric_impl(port, ptr, rust_vec_len, data_len),
-        59 => wire__crate__api__simple__set_tun_fd_impl(port, ptr, rust_vec_len, data_len),
-        60 => wire__crate__api__astral_wfp__to_wide_string_impl(port, ptr, rust_vec_len, data_len),
+        66 => wire__crate__api__hops__set_interface_metric_impl(port, ptr, rust_vec_len, data_len),
+        67 => wire__crate__api__simple__set_tun_fd_impl(port, ptr, rust_vec_len, data_len),
+        68 => wire__crate__api__secret_rotation__start_secret_rotation_impl(
+            port,
+            ptr,
+            rust_vec_len,
+            data_len,
+        ),
+        69 => wire__crate__api__events__subscribe_events_impl(port, ptr, rust_vec_len, data_len),
+        70 => wire__crate__api__astral_wfp__to_wide_string_impl(port, ptr, rust_vec_len, data_len),
         _ => unreachable!(),
     }
 }
@@ -3668,137 +4378,435 @@ fn pde_ffi_dispatcher_sync_impl(
         _ => unreachable!(),
     }
 }
-
-// Section: rust2dart
-
+
+// Section: rust2dart
+
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for FrbWrapper<FilterRule> {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
+            .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for FrbWrapper<FilterRule> {}
+
+impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<FilterRule>> for FilterRule {
+    fn into_into_dart(self) -> FrbWrapper<FilterRule> {
+        self.into()
+    }
+}
+
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for FrbWrapper<IpAddr> {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
+            .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for FrbWrapper<IpAddr> {}
+
+impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<IpAddr>> for IpAddr {
+    fn into_into_dart(self) -> FrbWrapper<IpAddr> {
+        self.into()
+    }
+}
+
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for FrbWrapper<IpNetwork> {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
+            .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for FrbWrapper<IpNetwork> {}
+
+impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<IpNetwork>> for IpNetwork {
+    fn into_into_dart(self) -> FrbWrapper<IpNetwork> {
+        self.into()
+    }
+}
+
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for FrbWrapper<WfpController> {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
+            .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for FrbWrapper<WfpController> {}
+
+impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<WfpController>> for WfpController {
+    fn into_into_dart(self) -> FrbWrapper<WfpController> {
+        self.into()
+    }
+}
+
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::api::bandwidth::AstralBandwidthLimit {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        [
+            self.peer_id.into_into_dart().into_dart(),
+            self.cidr.into_into_dart().into_dart(),
+            self.upload_bps.into_into_dart().into_dart(),
+            self.download_bps.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::bandwidth::AstralBandwidthLimit
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::bandwidth::AstralBandwidthLimit>
+    for crate::api::bandwidth::AstralBandwidthLimit
+{
+    fn into_into_dart(self) -> crate::api::bandwidth::AstralBandwidthLimit {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::api::simple::AstralConnection {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        [
+            self.conn_id.into_into_dart().into_dart(),
+            self.tunnel_type.into_into_dart().into_dart(),
+            self.local_addr.into_into_dart().into_dart(),
+            self.remote_addr.into_into_dart().into_dart(),
+            self.is_client.into_into_dart().into_dart(),
+            self.latency_ms.into_into_dart().into_dart(),
+            self.loss_rate.into_into_dart().into_dart(),
+            self.rx_bytes.into_into_dart().into_dart(),
+            self.tx_bytes.into_into_dart().into_dart(),
+            self.rx_packets.into_into_dart().into_dart(),
+            self.tx_packets.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::simple::AstralConnection
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::simple::AstralConnection>
+    for crate::api::simple::AstralConnection
+{
+    fn into_into_dart(self) -> crate::api::simple::AstralConnection {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::api::events::AstralEvent {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        [
+            self.instance_id.into_into_dart().into_dart(),
+            self.timestamp_ms.into_into_dart().into_dart(),
+            self.kind.into_into_dart().into_dart(),
+            self.peer_id.into_into_dart().into_dart(),
+            self.conn.into_into_dart().into_dart(),
+            self.dev_name.into_into_dart().into_dart(),
+            self.url.into_into_dart().into_dart(),
+            self.ip_version.into_into_dart().into_dart(),
+            self.local_addr.into_into_dart().into_dart(),
+            self.remote_addr.into_into_dart().into_dart(),
+            self.portal.into_into_dart().into_dart(),
+            self.client_addr.into_into_dart().into_dart(),
+            self.old_addr.into_into_dart().into_dart(),
+            self.new_addr.into_into_dart().into_dart(),
+            self.addr.into_into_dart().into_dart(),
+            self.forward.into_into_dart().into_dart(),
+            self.rotation_id.into_into_dart().into_dart(),
+            self.count.into_into_dart().into_dart(),
+            self.error.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::events::AstralEvent
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::events::AstralEvent>
+    for crate::api::events::AstralEvent
+{
+    fn into_into_dart(self) -> crate::api::events::AstralEvent {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::api::events::AstralEventKind {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        match self {
+            Self::TunDeviceReady => 0.into_dart(),
+            Self::TunDeviceError => 1.into_dart(),
+            Self::PeerAdded => 2.into_dart(),
+            Self::PeerRemoved => 3.into_dart(),
+            Self::PeerConnAdded => 4.into_dart(),
+            Self::PeerConnRemoved => 5.into_dart(),
+            Self::ListenerAdded => 6.into_dart(),
+            Self::ListenerAddFailed => 7.into_dart(),
+            Self::ListenerAcceptFailed => 8.into_dart(),
+            Self::ConnectionAccepted => 9.into_dart(),
+            Self::ConnectionError => 10.into_dart(),
+            Self::Connecting => 11.into_dart(),
+            Self::ConnectError => 12.into_dart(),
+            Self::VpnPortalStarted => 13.into_dart(),
+            Self::VpnPortalClientConnected => 14.into_dart(),
+            Self::VpnPortalClientDisconnected => 15.into_dart(),
+            Self::DhcpIpv4Changed => 16.into_dart(),
+            Self::DhcpIpv4Conflicted => 17.into_dart(),
+            Self::PortForwardAdded => 18.into_dart(),
+            Self::NetworkSecretRotated => 19.into_dart(),
+            Self::ExitNodeChanged => 20.into_dart(),
+            Self::ExitNodeDown => 21.into_dart(),
+            Self::EventsLagged => 22.into_dart(),
+            Self::EventChannelClosed => 23.into_dart(),
+            _ => unreachable!(),
+        }
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::events::AstralEventKind
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::events::AstralEventKind>
+    for crate::api::events::AstralEventKind
+{
+    fn into_into_dart(self) -> crate::api::events::AstralEventKind {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::api::simple::AstralHop {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        [
+            self.peer_id.into_into_dart().into_dart(),
+            self.hostname.into_into_dart().into_dart(),
+            self.ipv4.into_into_dart().into_dart(),
+            self.ipv6.into_into_dart().into_dart(),
+            self.latency_ms.into_into_dart().into_dart(),
+            self.loss_rate.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for crate::api::simple::AstralHop {}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::simple::AstralHop>
+    for crate::api::simple::AstralHop
+{
+    fn into_into_dart(self) -> crate::api::simple::AstralHop {
+        self
+    }
+}
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for FrbWrapper<EventBusSubscriber> {
+impl flutter_rust_bridge::IntoDart for crate::api::hops::AstralInterface {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
-            .into_dart()
+        [
+            self.index.into_into_dart().into_dart(),
+            self.name.into_into_dart().into_dart(),
+            self.metric.into_into_dart().into_dart(),
+            self.mtu.into_into_dart().into_dart(),
+            self.addresses.into_into_dart().into_dart(),
+            self.up.into_into_dart().into_dart(),
+        ]
+        .into_dart()
     }
 }
 impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
-    for FrbWrapper<EventBusSubscriber>
+    for crate::api::hops::AstralInterface
 {
 }
-
-impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<EventBusSubscriber>> for EventBusSubscriber {
-    fn into_into_dart(self) -> FrbWrapper<EventBusSubscriber> {
-        self.into()
+impl flutter_rust_bridge::IntoIntoDart<crate::api::hops::AstralInterface>
+    for crate::api::hops::AstralInterface
+{
+    fn into_into_dart(self) -> crate::api::hops::AstralInterface {
+        self
     }
 }
-
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for FrbWrapper<FilterRule> {
+impl flutter_rust_bridge::IntoDart for crate::api::simple::AstralNetworkSnapshot {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
-            .into_dart()
+        [
+            self.instance_id.into_into_dart().into_dart(),
+            self.dev_name.into_into_dart().into_dart(),
+            self.running.into_into_dart().into_dart(),
+            self.error_msg.into_into_dart().into_dart(),
+            self.listeners.into_into_dart().into_dart(),
+            self.nodes.into_into_dart().into_dart(),
+        ]
+        .into_dart()
     }
 }
-impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for FrbWrapper<FilterRule> {}
-
-impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<FilterRule>> for FilterRule {
-    fn into_into_dart(self) -> FrbWrapper<FilterRule> {
-        self.into()
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::simple::AstralNetworkSnapshot
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::simple::AstralNetworkSnapshot>
+    for crate::api::simple::AstralNetworkSnapshot
+{
+    fn into_into_dart(self) -> crate::api::simple::AstralNetworkSnapshot {
+        self
     }
 }
-
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for FrbWrapper<IpAddr> {
+impl flutter_rust_bridge::IntoDart for crate::api::simple::AstralNode {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
-            .into_dart()
+        [
+            self.peer_id.into_into_dart().into_dart(),
+            self.is_local.into_into_dart().into_dart(),
+            self.hostname.into_into_dart().into_dart(),
+            self.version.into_into_dart().into_dart(),
+            self.ipv4.into_into_dart().into_dart(),
+            self.ipv6.into_into_dart().into_dart(),
+            self.proxy_cidrs.into_into_dart().into_dart(),
+            self.udp_nat_type.into_into_dart().into_dart(),
+            self.tcp_nat_type.into_into_dart().into_dart(),
+            self.next_hop_peer_id.into_into_dart().into_dart(),
+            self.cost.into_into_dart().into_dart(),
+            self.latency_ms.into_into_dart().into_dart(),
+            self.loss_rate.into_into_dart().into_dart(),
+            self.tunnel_types.into_into_dart().into_dart(),
+            self.rx_bytes.into_into_dart().into_dart(),
+            self.tx_bytes.into_into_dart().into_dart(),
+            self.connections.into_into_dart().into_dart(),
+            self.hops.into_into_dart().into_dart(),
+        ]
+        .into_dart()
     }
 }
-impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for FrbWrapper<IpAddr> {}
-
-impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<IpAddr>> for IpAddr {
-    fn into_into_dart(self) -> FrbWrapper<IpAddr> {
-        self.into()
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::simple::AstralNode
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::simple::AstralNode>
+    for crate::api::simple::AstralNode
+{
+    fn into_into_dart(self) -> crate::api::simple::AstralNode {
+        self
     }
 }
-
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for FrbWrapper<IpNetwork> {
+impl flutter_rust_bridge::IntoDart for crate::api::events::AstralPeerConn {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
-            .into_dart()
+        [
+            self.conn_id.into_into_dart().into_dart(),
+            self.my_peer_id.into_into_dart().into_dart(),
+            self.peer_id.into_into_dart().into_dart(),
+            self.tunnel_type.into_into_dart().into_dart(),
+            self.local_addr.into_into_dart().into_dart(),
+            self.remote_addr.into_into_dart().into_dart(),
+            self.is_client.into_into_dart().into_dart(),
+            self.network_name.into_into_dart().into_dart(),
+        ]
+        .into_dart()
     }
 }
-impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for FrbWrapper<IpNetwork> {}
-
-impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<IpNetwork>> for IpNetwork {
-    fn into_into_dart(self) -> FrbWrapper<IpNetwork> {
-        self.into()
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::events::AstralPeerConn
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::events::AstralPeerConn>
+    for crate::api::events::AstralPeerConn
+{
+    fn into_into_dart(self) -> crate::api::events::AstralPeerConn {
+        self
     }
 }
-
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for FrbWrapper<JoinHandle<()>> {
+impl flutter_rust_bridge::IntoDart for crate::api::events::AstralPortForward {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
-            .into_dart()
+        [
+            self.bind_addr.into_into_dart().into_dart(),
+            self.dst_addr.into_into_dart().into_dart(),
+            self.proto.into_into_dart().into_dart(),
+        ]
+        .into_dart()
     }
 }
-impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for FrbWrapper<JoinHandle<()>> {}
-
-impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<JoinHandle<()>>> for JoinHandle<()> {
-    fn into_into_dart(self) -> FrbWrapper<JoinHandle<()>> {
-        self.into()
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::events::AstralPortForward
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::events::AstralPortForward>
+    for crate::api::events::AstralPortForward
+{
+    fn into_into_dart(self) -> crate::api::events::AstralPortForward {
+        self
     }
 }
-
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for FrbWrapper<JoinHandle<Result<(), String>>> {
+impl flutter_rust_bridge::IntoDart for crate::api::port_forward::AstralPortForwardStatus {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
-            .into_dart()
+        [
+            self.bind_addr.into_into_dart().into_dart(),
+            self.dst_addr.into_into_dart().into_dart(),
+            self.proto.into_into_dart().into_dart(),
+            self.bound.into_into_dart().into_dart(),
+            self.error.into_into_dart().into_dart(),
+            self.active_connections.into_into_dart().into_dart(),
+            self.total_connections.into_into_dart().into_dart(),
+        ]
+        .into_dart()
     }
 }
 impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
-    for FrbWrapper<JoinHandle<Result<(), String>>>
+    for crate::api::port_forward::AstralPortForwardStatus
 {
 }
-
-impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<JoinHandle<Result<(), String>>>>
-    for JoinHandle<Result<(), String>>
+impl flutter_rust_bridge::IntoIntoDart<crate::api::port_forward::AstralPortForwardStatus>
+    for crate::api::port_forward::AstralPortForwardStatus
 {
-    fn into_into_dart(self) -> FrbWrapper<JoinHandle<Result<(), String>>> {
-        self.into()
+    fn into_into_dart(self) -> crate::api::port_forward::AstralPortForwardStatus {
+        self
     }
 }
-
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for FrbWrapper<PeerRoutePair> {
+impl flutter_rust_bridge::IntoDart for crate::api::secret_rotation::AstralSecretRotationPeer {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
-            .into_dart()
+        [
+            self.peer_id.into_into_dart().into_dart(),
+            self.hostname.into_into_dart().into_dart(),
+            self.prepared.into_into_dart().into_dart(),
+            self.committed.into_into_dart().into_dart(),
+            self.error.into_into_dart().into_dart(),
+        ]
+        .into_dart()
     }
 }
-impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for FrbWrapper<PeerRoutePair> {}
-
-impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<PeerRoutePair>> for PeerRoutePair {
-    fn into_into_dart(self) -> FrbWrapper<PeerRoutePair> {
-        self.into()
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::secret_rotation::AstralSecretRotationPeer
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::secret_rotation::AstralSecretRotationPeer>
+    for crate::api::secret_rotation::AstralSecretRotationPeer
+{
+    fn into_into_dart(self) -> crate::api::secret_rotation::AstralSecretRotationPeer {
+        self
     }
 }
-
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for FrbWrapper<WfpController> {
+impl flutter_rust_bridge::IntoDart for crate::api::secret_rotation::AstralSecretRotationStatus {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self.0)
-            .into_dart()
+        [
+            self.rotation_id.into_into_dart().into_dart(),
+            self.initiated_locally.into_into_dart().into_dart(),
+            self.committed.into_into_dart().into_dart(),
+            self.grace_period_remaining_sec.into_into_dart().into_dart(),
+            self.peers.into_into_dart().into_dart(),
+        ]
+        .into_dart()
     }
 }
-impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for FrbWrapper<WfpController> {}
-
-impl flutter_rust_bridge::IntoIntoDart<FrbWrapper<WfpController>> for WfpController {
-    fn into_into_dart(self) -> FrbWrapper<WfpController> {
-        self.into()
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::secret_rotation::AstralSecretRotationStatus
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::secret_rotation::AstralSecretRotationStatus>
+    for crate::api::secret_rotation::AstralSecretRotationStatus
+{
+    fn into_into_dart(self) -> crate::api::secret_rotation::AstralSecretRotationStatus {
+        self
     }
 }
-
 // Codec=Dco (DartCObject based), see doc to use other codecs
 impl flutter_rust_bridge::IntoDart for crate::api::astral_wfp::Direction {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
@@ -3850,6 +4858,7 @@ impl flutter_rust_bridge::IntoDart for crate::api::simple::FlagsC {
             self.dev_name.into_into_dart().into_dart(),
             self.enable_encryption.into_into_dart().into_dart(),
             self.enable_ipv6.into_into_dart().into_dart(),
+            self.dhcp_ipv6.into_into_dart().into_dart(),
             self.mtu.into_into_dart().into_dart(),
             self.latency_first.into_into_dart().into_dart(),
             self.enable_exit_node.into_into_dart().into_dart(),
@@ -3861,6 +4870,7 @@ impl flutter_rust_bridge::IntoDart for crate::api::simple::FlagsC {
             self.disable_udp_hole_punching.into_into_dart().into_dart(),
             self.multi_thread.into_into_dart().into_dart(),
             self.data_compress_algo.into_into_dart().into_dart(),
+            self.data_compress_level.into_into_dart().into_dart(),
             self.bind_device.into_into_dart().into_dart(),
             self.enable_kcp_proxy.into_into_dart().into_dart(),
             self.disable_kcp_input.into_into_dart().into_dart(),
@@ -3900,296 +4910,336 @@ impl flutter_rust_bridge::IntoIntoDart<crate::api::simple::Forward>
     }
 }
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for crate::api::simple::KVNetworkStatus {
-    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        [
-            self.total_nodes.into_into_dart().into_dart(),
-            self.nodes.into_into_dart().into_dart(),
-        ]
-        .into_dart()
-    }
-}
-impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
-    for crate::api::simple::KVNetworkStatus
-{
-}
-impl flutter_rust_bridge::IntoIntoDart<crate::api::simple::KVNetworkStatus>
-    for crate::api::simple::KVNetworkStatus
-{
-    fn into_into_dart(self) -> crate::api::simple::KVNetworkStatus {
-        self
-    }
-}
-// Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for crate::api::simple::KVNodeConnectionStats {
+impl flutter_rust_bridge::IntoDart for crate::api::astral_wfp::Protocol {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        [
-            self.conn_type.into_into_dart().into_dart(),
-            self.rx_bytes.into_into_dart().into_dart(),
-            self.tx_bytes.into_into_dart().into_dart(),
-            self.rx_packets.into_into_dart().into_dart(),
-            self.tx_packets.into_into_dart().into_dart(),
-        ]
-        .into_dart()
+        match self {
+            Self::Tcp => 0.into_dart(),
+            Self::Udp => 1.into_dart(),
+            _ => unreachable!(),
+        }
     }
 }
 impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
-    for crate::api::simple::KVNodeConnectionStats
+    for crate::api::astral_wfp::Protocol
 {
 }
-impl flutter_rust_bridge::IntoIntoDart<crate::api::simple::KVNodeConnectionStats>
-    for crate::api::simple::KVNodeConnectionStats
+impl flutter_rust_bridge::IntoIntoDart<crate::api::astral_wfp::Protocol>
+    for crate::api::astral_wfp::Protocol
 {
-    fn into_into_dart(self) -> crate::api::simple::KVNodeConnectionStats {
+    fn into_into_dart(self) -> crate::api::astral_wfp::Protocol {
         self
     }
 }
-// Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for crate::api::simple::KVNodeInfo {
-    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        [
-            self.hostname.into_into_dart().into_dart(),
-            self.ipv4.into_into_dart().into_dart(),
-            self.latency_ms.into_into_dart().into_dart(),
-            self.nat.into_into_dart().into_dart(),
-            self.hops.into_into_dart().into_dart(),
-            self.loss_rate.into_into_dart().into_dart(),
-            self.connections.into_into_dart().into_dart(),
-            self.tunnel_proto.into_into_dart().into_dart(),
-            self.conn_type.into_into_dart().into_dart(),
-            self.rx_bytes.into_into_dart().into_dart(),
-            self.tx_bytes.into_into_dart().into_dart(),
-            self.version.into_into_dart().into_dart(),
-            self.cost.into_into_dart().into_dart(),
-        ]
-        .into_dart()
+
+impl SseEncode for flutter_rust_bridge::for_generated::anyhow::Error {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <String>::sse_encode(format!("{:?}", self), serializer);
     }
 }
-impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
-    for crate::api::simple::KVNodeInfo
-{
-}
-impl flutter_rust_bridge::IntoIntoDart<crate::api::simple::KVNodeInfo>
-    for crate::api::simple::KVNodeInfo
-{
-    fn into_into_dart(self) -> crate::api::simple::KVNodeInfo {
-        self
+
+impl SseEncode for FilterRule {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<FilterRule>>>::sse_encode(flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self), serializer);
     }
 }
-// Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for crate::api::simple::NodeHopStats {
-    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        [
-            self.target_ip.into_into_dart().into_dart(),
-            self.latency_ms.into_into_dart().into_dart(),
-            self.packet_loss.into_into_dart().into_dart(),
-            self.node_name.into_into_dart().into_dart(),
-        ]
-        .into_dart()
+
+impl SseEncode for IpAddr {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpAddr>>>::sse_encode(flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self), serializer);
     }
 }
-impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
-    for crate::api::simple::NodeHopStats
-{
-}
-impl flutter_rust_bridge::IntoIntoDart<crate::api::simple::NodeHopStats>
-    for crate::api::simple::NodeHopStats
-{
-    fn into_into_dart(self) -> crate::api::simple::NodeHopStats {
-        self
+
+impl SseEncode for IpNetwork {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpNetwork>>>::sse_encode(flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self), serializer);
     }
 }
-// Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for crate::api::astral_wfp::Protocol {
-    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        match self {
-            Self::Tcp => 0.into_dart(),
-            Self::Udp => 1.into_dart(),
-            _ => unreachable!(),
-        }
+
+impl SseEncode for WfpController {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<WfpController>>>::sse_encode(flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self), serializer);
     }
 }
-impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
-    for crate::api::astral_wfp::Protocol
-{
-}
-impl flutter_rust_bridge::IntoIntoDart<crate::api::astral_wfp::Protocol>
-    for crate::api::astral_wfp::Protocol
+
+impl SseEncode
+    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<FilterRule>>
 {
-    fn into_into_dart(self) -> crate::api::astral_wfp::Protocol {
-        self
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        let (ptr, size) = self.sse_encode_raw();
+        <usize>::sse_encode(ptr, serializer);
+        <i32>::sse_encode(size, serializer);
     }
 }
 
-impl SseEncode for flutter_rust_bridge::for_generated::anyhow::Error {
+impl SseEncode for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<GUID>> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <String>::sse_encode(format!("{:?}", self), serializer);
+        let (ptr, size) = self.sse_encode_raw();
+        <usize>::sse_encode(ptr, serializer);
+        <i32>::sse_encode(size, serializer);
     }
 }
 
-impl SseEncode for EventBusSubscriber {
+impl SseEncode for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpAddr>> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<EventBusSubscriber>>>::sse_encode(flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self), serializer);
+        let (ptr, size) = self.sse_encode_raw();
+        <usize>::sse_encode(ptr, serializer);
+        <i32>::sse_encode(size, serializer);
     }
 }
 
-impl SseEncode for FilterRule {
+impl SseEncode
+    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpNetwork>>
+{
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<FilterRule>>>::sse_encode(flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self), serializer);
+        let (ptr, size) = self.sse_encode_raw();
+        <usize>::sse_encode(ptr, serializer);
+        <i32>::sse_encode(size, serializer);
     }
 }
 
-impl SseEncode for IpAddr {
+impl SseEncode
+    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<WfpController>>
+{
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpAddr>>>::sse_encode(flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self), serializer);
+        let (ptr, size) = self.sse_encode_raw();
+        <usize>::sse_encode(ptr, serializer);
+        <i32>::sse_encode(size, serializer);
     }
 }
 
-impl SseEncode for IpNetwork {
+impl SseEncode
+    for StreamSink<crate::api::events::AstralEvent, flutter_rust_bridge::for_generated::SseCodec>
+{
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpNetwork>>>::sse_encode(flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self), serializer);
+        unimplemented!("")
     }
 }
 
-impl SseEncode for JoinHandle<()> {
+impl SseEncode for String {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner< JoinHandle < () >>>>::sse_encode(flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self), serializer);
+        <Vec<u8>>::sse_encode(self.into_bytes(), serializer);
     }
 }
 
-impl SseEncode for JoinHandle<Result<(), String>> {
+impl SseEncode for crate::api::bandwidth::AstralBandwidthLimit {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <RustOpaqueMoi<
-            flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<Result<(), String>>>,
-        >>::sse_encode(
-            flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self),
-            serializer,
-        );
+        <Option<u32>>::sse_encode(self.peer_id, serializer);
+        <Option<String>>::sse_encode(self.cidr, serializer);
+        <Option<u64>>::sse_encode(self.upload_bps, serializer);
+        <Option<u64>>::sse_encode(self.download_bps, serializer);
     }
 }
 
-impl SseEncode for PeerRoutePair {
+impl SseEncode for crate::api::simple::AstralConnection {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<PeerRoutePair>>>::sse_encode(flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self), serializer);
+        <String>::sse_encode(self.conn_id, serializer);
+        <String>::sse_encode(self.tunnel_type, serializer);
+        <String>::sse_encode(self.local_addr, serializer);
+        <String>::sse_encode(self.remote_addr, serializer);
+        <bool>::sse_encode(self.is_client, serializer);
+        <f64>::sse_encode(self.latency_ms, serializer);
+        <f32>::sse_encode(self.loss_rate, serializer);
+        <u64>::sse_encode(self.rx_bytes, serializer);
+        <u64>::sse_encode(self.tx_bytes, serializer);
+        <u64>::sse_encode(self.rx_packets, serializer);
+        <u64>::sse_encode(self.tx_packets, serializer);
     }
 }
 
-impl SseEncode for WfpController {
+impl SseEncode for crate::api::events::AstralEvent {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<WfpController>>>::sse_encode(flutter_rust_bridge::for_generated::rust_auto_opaque_encode::<_, MoiArc<_>>(self), serializer);
-    }
-}
-
-impl SseEncode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<EventBusSubscriber>>
-{
+        <String>::sse_encode(self.instance_id, serializer);
+        <i64>::sse_encode(self.timestamp_ms, serializer);
+        <crate::api::events::AstralEventKind>::sse_encode(self.kind, serializer);
+        <Option<u32>>::sse_encode(self.peer_id, serializer);
+        <Option<crate::api::events::AstralPeerConn>>::sse_encode(self.conn, serializer);
+        <Option<String>>::sse_encode(self.dev_name, serializer);
+        <Option<String>>::sse_encode(self.url, serializer);
+        <Option<String>>::sse_encode(self.ip_version, serializer);
+        <Option<String>>::sse_encode(self.local_addr, serializer);
+        <Option<String>>::sse_encode(self.remote_addr, serializer);
+        <Option<String>>::sse_encode(self.portal, serializer);
+        <Option<String>>::sse_encode(self.client_addr, serializer);
+        <Option<String>>::sse_encode(self.old_addr, serializer);
+        <Option<String>>::sse_encode(self.new_addr, serializer);
+        <Option<String>>::sse_encode(self.addr, serializer);
+        <Option<crate::api::events::AstralPortForward>>::sse_encode(self.forward, serializer);
+        <Option<u64>>::sse_encode(self.rotation_id, serializer);
+        <Option<u64>>::sse_encode(self.count, serializer);
+        <Option<String>>::sse_encode(self.error, serializer);
+    }
+}
+
+impl SseEncode for crate::api::events::AstralEventKind {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        let (ptr, size) = self.sse_encode_raw();
-        <usize>::sse_encode(ptr, serializer);
-        <i32>::sse_encode(size, serializer);
+        <i32>::sse_encode(
+            match self {
+                crate::api::events::AstralEventKind::TunDeviceReady => 0,
+                crate::api::events::AstralEventKind::TunDeviceError => 1,
+                crate::api::events::AstralEventKind::PeerAdded => 2,
+                crate::api::events::AstralEventKind::PeerRemoved => 3,
+                crate::api::events::AstralEventKind::PeerConnAdded => 4,
+                crate::api::events::AstralEventKind::PeerConnRemoved => 5,
+                crate::api::events::AstralEventKind::ListenerAdded => 6,
+                crate::api::events::AstralEventKind::ListenerAddFailed => 7,
+                crate::api::events::AstralEventKind::ListenerAcceptFailed => 8,
+                crate::api::events::AstralEventKind::ConnectionAccepted => 9,
+                crate::api::events::AstralEventKind::ConnectionError => 10,
+                crate::api::events::AstralEventKind::Connecting => 11,
+                crate::api::events::AstralEventKind::ConnectError => 12,
+                crate::api::events::AstralEventKind::VpnPortalStarted => 13,
+                crate::api::events::AstralEventKind::VpnPortalClientConnected => 14,
+                crate::api::events::AstralEventKind::VpnPortalClientDisconnected => 15,
+                crate::api::events::AstralEventKind::DhcpIpv4Changed => 16,
+                crate::api::events::AstralEventKind::DhcpIpv4Conflicted => 17,
+                crate::api::events::AstralEventKind::PortForwardAdded => 18,
+                crate::api::events::AstralEventKind::NetworkSecretRotated => 19,
+                crate::api::events::AstralEventKind::ExitNodeChanged => 20,
+                crate::api::events::AstralEventKind::ExitNodeDown => 21,
+                crate::api::events::AstralEventKind::EventsLagged => 22,
+                crate::api::events::AstralEventKind::EventChannelClosed => 23,
+                _ => {
+                    unimplemented!("");
+                }
+            },
+            serializer,
+        );
     }
 }
 
-impl SseEncode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<FilterRule>>
-{
+impl SseEncode for crate::api::simple::AstralHop {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        let (ptr, size) = self.sse_encode_raw();
-        <usize>::sse_encode(ptr, serializer);
-        <i32>::sse_encode(size, serializer);
+        <u32>::sse_encode(self.peer_id, serializer);
+        <String>::sse_encode(self.hostname, serializer);
+        <Option<String>>::sse_encode(self.ipv4, serializer);
+        <Option<String>>::sse_encode(self.ipv6, serializer);
+        <f64>::sse_encode(self.latency_ms, serializer);
+        <f32>::sse_encode(self.loss_rate, serializer);
     }
 }
 
-impl SseEncode for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<GUID>> {
+impl SseEncode for crate::api::hops::AstralInterface {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        let (ptr, size) = self.sse_encode_raw();
-        <usize>::sse_encode(ptr, serializer);
-        <i32>::sse_encode(size, serializer);
+        <u32>::sse_encode(self.index, serializer);
+        <String>::sse_encode(self.name, serializer);
+        <Option<u32>>::sse_encode(self.metric, serializer);
+        <u32>::sse_encode(self.mtu, serializer);
+        <Vec<String>>::sse_encode(self.addresses, serializer);
+        <bool>::sse_encode(self.up, serializer);
     }
 }
 
-impl SseEncode for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpAddr>> {
+impl SseEncode for crate::api::simple::AstralNetworkSnapshot {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        let (ptr, size) = self.sse_encode_raw();
-        <usize>::sse_encode(ptr, serializer);
-        <i32>::sse_encode(size, serializer);
+        <String>::sse_encode(self.instance_id, serializer);
+        <String>::sse_encode(self.dev_name, serializer);
+        <bool>::sse_encode(self.running, serializer);
+        <Option<String>>::sse_encode(self.error_msg, serializer);
+        <Vec<String>>::sse_encode(self.listeners, serializer);
+        <Vec<crate::api::simple::AstralNode>>::sse_encode(self.nodes, serializer);
     }
 }
 
-impl SseEncode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpNetwork>>
-{
+impl SseEncode for crate::api::simple::AstralNode {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        let (ptr, size) = self.sse_encode_raw();
-        <usize>::sse_encode(ptr, serializer);
-        <i32>::sse_encode(size, serializer);
+        <u32>::sse_encode(self.peer_id, serializer);
+        <bool>::sse_encode(self.is_local, serializer);
+        <String>::sse_encode(self.hostname, serializer);
+        <String>::sse_encode(self.version, serializer);
+        <Option<String>>::sse_encode(self.ipv4, serializer);
+        <Option<String>>::sse_encode(self.ipv6, serializer);
+        <Vec<String>>::sse_encode(self.proxy_cidrs, serializer);
+        <String>::sse_encode(self.udp_nat_type, serializer);
+        <String>::sse_encode(self.tcp_nat_type, serializer);
+        <u32>::sse_encode(self.next_hop_peer_id, serializer);
+        <i32>::sse_encode(self.cost, serializer);
+        <f64>::sse_encode(self.latency_ms, serializer);
+        <f32>::sse_encode(self.loss_rate, serializer);
+        <Vec<String>>::sse_encode(self.tunnel_types, serializer);
+        <u64>::sse_encode(self.rx_bytes, serializer);
+        <u64>::sse_encode(self.tx_bytes, serializer);
+        <Vec<crate::api::simple::AstralConnection>>::sse_encode(self.connections, serializer);
+        <Vec<crate::api::simple::AstralHop>>::sse_encode(self.hops, serializer);
     }
 }
 
-impl SseEncode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<()>>>
-{
+impl SseEncode for crate::api::events::AstralPeerConn {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        let (ptr, size) = self.sse_encode_raw();
-        <usize>::sse_encode(ptr, serializer);
-        <i32>::sse_encode(size, serializer);
+        <String>::sse_encode(self.conn_id, serializer);
+        <u32>::sse_encode(self.my_peer_id, serializer);
+        <u32>::sse_encode(self.peer_id, serializer);
+        <String>::sse_encode(self.tunnel_type, serializer);
+        <String>::sse_encode(self.local_addr, serializer);
+        <String>::sse_encode(self.remote_addr, serializer);
+        <bool>::sse_encode(self.is_client, serializer);
+        <String>::sse_encode(self.network_name, serializer);
     }
 }
 
-impl SseEncode
-    for RustOpaqueMoi<
-        flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<Result<(), String>>>,
-    >
-{
+impl SseEncode for crate::api::events::AstralPortForward {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        let (ptr, size) = self.sse_encode_raw();
-        <usize>::sse_encode(ptr, serializer);
-        <i32>::sse_encode(size, serializer);
+        <String>::sse_encode(self.bind_addr, serializer);
+        <String>::sse_encode(self.dst_addr, serializer);
+        <String>::sse_encode(self.proto, serializer);
     }
 }
 
-impl SseEncode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<PeerRoutePair>>
-{
+impl SseEncode for crate::api::port_forward::AstralPortForwardStatus {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        let (ptr, size) = self.sse_encode_raw();
-        <usize>::sse_encode(ptr, serializer);
-        <i32>::sse_encode(size, serializer);
+        <String>::sse_encode(self.bind_addr, serializer);
+        <String>::sse_encode(self.dst_addr, serializer);
+        <String>::sse_encode(self.proto, serializer);
+        <bool>::sse_encode(self.bound, serializer);
+        <Option<String>>::sse_encode(self.error, serializer);
+        <u32>::sse_encode(self.active_connections, serializer);
+        <u64>::sse_encode(self.total_connections, serializer);
     }
 }
 
-impl SseEncode
-    for RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<WfpController>>
-{
+impl SseEncode for crate::api::secret_rotation::AstralSecretRotationPeer {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        let (ptr, size) = self.sse_encode_raw();
-        <usize>::sse_encode(ptr, serializer);
-        <i32>::sse_encode(size, serializer);
+        <u32>::sse_encode(self.peer_id, serializer);
+        <String>::sse_encode(self.hostname, serializer);
+        <bool>::sse_encode(self.prepared, serializer);
+        <bool>::sse_encode(self.committed, serializer);
+        <Option<String>>::sse_encode(self.error, serializer);
     }
 }
 
-impl SseEncode for String {
+impl SseEncode for crate::api::secret_rotation::AstralSecretRotationStatus {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <Vec<u8>>::sse_encode(self.into_bytes(), serializer);
+        <u64>::sse_encode(self.rotation_id, serializer);
+        <bool>::sse_encode(self.initiated_locally, serializer);
+        <bool>::sse_encode(self.committed, serializer);
+        <u32>::sse_encode(self.grace_period_remaining_sec, serializer);
+        <Vec<crate::api::secret_rotation::AstralSecretRotationPeer>>::sse_encode(
+            self.peers, serializer,
+        );
     }
 }
 
@@ -4254,6 +5304,7 @@ impl SseEncode for crate::api::simple::FlagsC {
         <String>::sse_encode(self.dev_name, serializer);
         <bool>::sse_encode(self.enable_encryption, serializer);
         <bool>::sse_encode(self.enable_ipv6, serializer);
+        <bool>::sse_encode(self.dhcp_ipv6, serializer);
         <u32>::sse_encode(self.mtu, serializer);
         <bool>::sse_encode(self.latency_first, serializer);
         <bool>::sse_encode(self.enable_exit_node, serializer);
@@ -4265,6 +5316,7 @@ impl SseEncode for crate::api::simple::FlagsC {
         <bool>::sse_encode(self.disable_udp_hole_punching, serializer);
         <bool>::sse_encode(self.multi_thread, serializer);
         <i32>::sse_encode(self.data_compress_algo, serializer);
+        <i32>::sse_encode(self.data_compress_level, serializer);
         <bool>::sse_encode(self.bind_device, serializer);
         <bool>::sse_encode(self.enable_kcp_proxy, serializer);
         <bool>::sse_encode(self.disable_kcp_input, serializer);
@@ -4293,110 +5345,109 @@ impl SseEncode for i32 {
     }
 }
 
-impl SseEncode for crate::api::simple::KVNetworkStatus {
+impl SseEncode for i64 {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <usize>::sse_encode(self.total_nodes, serializer);
-        <Vec<crate::api::simple::KVNodeInfo>>::sse_encode(self.nodes, serializer);
+        serializer.cursor.write_i64::<NativeEndian>(self).unwrap();
     }
 }
 
-impl SseEncode for crate::api::simple::KVNodeConnectionStats {
+impl SseEncode for Vec<FilterRule> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <String>::sse_encode(self.conn_type, serializer);
-        <u64>::sse_encode(self.rx_bytes, serializer);
-        <u64>::sse_encode(self.tx_bytes, serializer);
-        <u64>::sse_encode(self.rx_packets, serializer);
-        <u64>::sse_encode(self.tx_packets, serializer);
+        <i32>::sse_encode(self.len() as _, serializer);
+        for item in self {
+            <FilterRule>::sse_encode(item, serializer);
+        }
     }
 }
 
-impl SseEncode for crate::api::simple::KVNodeInfo {
+impl SseEncode for Vec<String> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <String>::sse_encode(self.hostname, serializer);
-        <String>::sse_encode(self.ipv4, serializer);
-        <f64>::sse_encode(self.latency_ms, serializer);
-        <String>::sse_encode(self.nat, serializer);
-        <Vec<crate::api::simple::NodeHopStats>>::sse_encode(self.hops, serializer);
-        <f32>::sse_encode(self.loss_rate, serializer);
-        <Vec<crate::api::simple::KVNodeConnectionStats>>::sse_encode(self.connections, serializer);
-        <String>::sse_encode(self.tunnel_proto, serializer);
-        <String>::sse_encode(self.conn_type, serializer);
-        <u64>::sse_encode(self.rx_bytes, serializer);
-        <u64>::sse_encode(self.tx_bytes, serializer);
-        <String>::sse_encode(self.version, serializer);
-        <i32>::sse_encode(self.cost, serializer);
+        <i32>::sse_encode(self.len() as _, serializer);
+        for item in self {
+            <String>::sse_encode(item, serializer);
+        }
     }
 }
 
-impl SseEncode for Vec<FilterRule> {
+impl SseEncode for Vec<crate::api::bandwidth::AstralBandwidthLimit> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
         <i32>::sse_encode(self.len() as _, serializer);
         for item in self {
-            <FilterRule>::sse_encode(item, serializer);
+            <crate::api::bandwidth::AstralBandwidthLimit>::sse_encode(item, serializer);
         }
     }
 }
 
-impl SseEncode for Vec<PeerRoutePair> {
+impl SseEncode for Vec<crate::api::simple::AstralConnection> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
         <i32>::sse_encode(self.len() as _, serializer);
         for item in self {
-            <PeerRoutePair>::sse_encode(item, serializer);
+            <crate::api::simple::AstralConnection>::sse_encode(item, serializer);
         }
     }
 }
 
-impl SseEncode for Vec<String> {
+impl SseEncode for Vec<crate::api::simple::AstralHop> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
         <i32>::sse_encode(self.len() as _, serializer);
         for item in self {
-            <String>::sse_encode(item, serializer);
+            <crate::api::simple::AstralHop>::sse_encode(item, serializer);
         }
     }
 }
 
-impl SseEncode for Vec<crate::api::simple::Forward> {
+impl SseEncode for Vec<crate::api::hops::AstralInterface> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
         <i32>::sse_encode(self.len() as _, serializer);
         for item in self {
-            <crate::api::simple::Forward>::sse_encode(item, serializer);
+            <crate::api::hops::AstralInterface>::sse_encode(item, serializer);
+        }
+    }
+}
+
+impl SseEncode for Vec<crate::api::simple::AstralNode> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(self.len() as _, serializer);
+        for item in self {
+            <crate::api::simple::AstralNode>::sse_encode(item, serializer);
         }
     }
 }
 
-impl SseEncode for Vec<crate::api::simple::KVNodeConnectionStats> {
+impl SseEncode for Vec<crate::api::port_forward::AstralPortForwardStatus> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
         <i32>::sse_encode(self.len() as _, serializer);
         for item in self {
-            <crate::api::simple::KVNodeConnectionStats>::sse_encode(item, serializer);
+            <crate::api::port_forward::AstralPortForwardStatus>::sse_encode(item, serializer);
         }
     }
 }
 
-impl SseEncode for Vec<crate::api::simple::KVNodeInfo> {
+impl SseEncode for Vec<crate::api::secret_rotation::AstralSecretRotationPeer> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
         <i32>::sse_encode(self.len() as _, serializer);
         for item in self {
-            <crate::api::simple::KVNodeInfo>::sse_encode(item, serializer);
+            <crate::api::secret_rotation::AstralSecretRotationPeer>::sse_encode(item, serializer);
         }
     }
 }
 
-impl SseEncode for Vec<crate::api::simple::NodeHopStats> {
+impl SseEncode for Vec<crate::api::simple::Forward> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
         <i32>::sse_encode(self.len() as _, serializer);
         for item in self {
-            <crate::api::simple::NodeHopStats>::sse_encode(item, serializer);
+            <crate::api::simple::Forward>::sse_encode(item, serializer);
         }
     }
 }
@@ -4441,22 +5492,32 @@ impl SseEncode for Vec<(String, u32)> {
     }
 }
 
-impl SseEncode for crate::api::simple::NodeHopStats {
+impl SseEncode for Option<String> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <String>::sse_encode(self.target_ip, serializer);
-        <f64>::sse_encode(self.latency_ms, serializer);
-        <f32>::sse_encode(self.packet_loss, serializer);
-        <String>::sse_encode(self.node_name, serializer);
+        <bool>::sse_encode(self.is_some(), serializer);
+        if let Some(value) = self {
+            <String>::sse_encode(value, serializer);
+        }
     }
 }
 
-impl SseEncode for Option<String> {
+impl SseEncode for Option<crate::api::events::AstralPeerConn> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
         <bool>::sse_encode(self.is_some(), serializer);
         if let Some(value) = self {
-            <String>::sse_encode(value, serializer);
+            <crate::api::events::AstralPeerConn>::sse_encode(value, serializer);
+        }
+    }
+}
+
+impl SseEncode for Option<crate::api::events::AstralPortForward> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <bool>::sse_encode(self.is_some(), serializer);
+        if let Some(value) = self {
+            <crate::api::events::AstralPortForward>::sse_encode(value, serializer);
         }
     }
 }
@@ -4501,6 +5562,16 @@ impl SseEncode for Option<u32> {
     }
 }
 
+impl SseEncode for Option<u64> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <bool>::sse_encode(self.is_some(), serializer);
+        if let Some(value) = self {
+            <u64>::sse_encode(value, serializer);
+        }
+    }
+}
+
 impl SseEncode for crate::api::astral_wfp::Protocol {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
@@ -4585,7 +5656,6 @@ mod io {
 
     use super::*;
     use crate::api::astral_wfp::*;
-    use crate::api::simple::*;
     use crate::*;
     use flutter_rust_bridge::for_generated::byteorder::{
         NativeEndian, ReadBytesExt, WriteBytesExt,
@@ -4597,20 +5667,6 @@ mod io {
 
     flutter_rust_bridge::frb_generated_boilerplate_io!();
 
-    #[unsafe(no_mangle)]
-    pub extern "C" fn frbgen_astral_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<EventBusSubscriber>>::increment_strong_count(ptr as _);
-    }
-
-    #[unsafe(no_mangle)]
-    pub extern "C" fn frbgen_astral_rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<EventBusSubscriber>>::decrement_strong_count(ptr as _);
-    }
-
     #[unsafe(no_mangle)]
     pub extern "C" fn frbgen_astral_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
         ptr: *const std::ffi::c_void,
@@ -4667,52 +5723,6 @@ mod io {
         MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpNetwork>>::decrement_strong_count(ptr as _);
     }
 
-    #[unsafe(no_mangle)]
-    pub extern "C" fn frbgen_astral_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner< JoinHandle < () >>>::increment_strong_count(ptr as _);
-    }
-
-    #[unsafe(no_mangle)]
-    pub extern "C" fn frbgen_astral_rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner< JoinHandle < () >>>::decrement_strong_count(ptr as _);
-    }
-
-    #[unsafe(no_mangle)]
-    pub extern "C" fn frbgen_astral_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<
-            flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<Result<(), String>>>,
-        >::increment_strong_count(ptr as _);
-    }
-
-    #[unsafe(no_mangle)]
-    pub extern "C" fn frbgen_astral_rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<
-            flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<Result<(), String>>>,
-        >::decrement_strong_count(ptr as _);
-    }
-
-    #[unsafe(no_mangle)]
-    pub extern "C" fn frbgen_astral_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<PeerRoutePair>>::increment_strong_count(ptr as _);
-    }
-
-    #[unsafe(no_mangle)]
-    pub extern "C" fn frbgen_astral_rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<PeerRoutePair>>::decrement_strong_count(ptr as _);
-    }
-
     #[unsafe(no_mangle)]
     pub extern "C" fn frbgen_astral_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
         ptr: *const std::ffi::c_void,
@@ -4740,7 +5750,6 @@ mod web {
 
     use super::*;
     use crate::api::astral_wfp::*;
-    use crate::api::simple::*;
     use crate::*;
     use flutter_rust_bridge::for_generated::byteorder::{
         NativeEndian, ReadBytesExt, WriteBytesExt,
@@ -4754,20 +5763,6 @@ mod web {
 
     flutter_rust_bridge::frb_generated_boilerplate_web!();
 
-    #[wasm_bindgen]
-    pub fn rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<EventBusSubscriber>>::increment_strong_count(ptr as _);
-    }
-
-    #[wasm_bindgen]
-    pub fn rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerEventBusSubscriber(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<EventBusSubscriber>>::decrement_strong_count(ptr as _);
-    }
-
     #[wasm_bindgen]
     pub fn rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerFilterRule(
         ptr: *const std::ffi::c_void,
@@ -4824,52 +5819,6 @@ mod web {
         MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<IpNetwork>>::decrement_strong_count(ptr as _);
     }
 
-    #[wasm_bindgen]
-    pub fn rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner< JoinHandle < () >>>::increment_strong_count(ptr as _);
-    }
-
-    #[wasm_bindgen]
-    pub fn rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandle(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner< JoinHandle < () >>>::decrement_strong_count(ptr as _);
-    }
-
-    #[wasm_bindgen]
-    pub fn rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<
-            flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<Result<(), String>>>,
-        >::increment_strong_count(ptr as _);
-    }
-
-    #[wasm_bindgen]
-    pub fn rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerJoinHandleResultString(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<
-            flutter_rust_bridge::for_generated::RustAutoOpaqueInner<JoinHandle<Result<(), String>>>,
-        >::decrement_strong_count(ptr as _);
-    }
-
-    #[wasm_bindgen]
-    pub fn rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<PeerRoutePair>>::increment_strong_count(ptr as _);
-    }
-
-    #[wasm_bindgen]
-    pub fn rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerPeerRoutePair(
-        ptr: *const std::ffi::c_void,
-    ) {
-        MoiArc::<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<PeerRoutePair>>::decrement_strong_count(ptr as _);
-    }
-
     #[wasm_bindgen]
     pub fn rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerWfpController(
         ptr: *const std::ffi::c_void,
@@ -1,2 +1,3 @@
 pub mod api;
+mod traffic_filter;
 mod frb_generated;
@@ -0,0 +1,80 @@
+//! 网络流量过滤后端
+//!
+//! Dart 端通过 `api::astral_wfp::WfpController` 使用统一的规则模型，规则由当前平台的后端实现：
+//! - Windows: Windows Filtering Platform (WFP)
+//! - Linux: nftables
+//! - 其他平台: 不支持
+
+use crate::api::astral_wfp::FilterRule;
+
+#[cfg(target_os = "linux")]
+pub mod nftables;
+#[cfg(target_os = "windows")]
+pub mod wfp;
+
+/// 平台过滤后端，规则在交给后端之前已经校验过
+pub trait FilterBackend: Send {
+    /// 后端名称，用于日志
+    fn name(&self) -> &'static str;
+
+    /// 打开过滤引擎
+    fn initialize(&mut self) -> anyhow::Result<()>;
+
+    /// 添加一条规则，返回创建的过滤器 ID，一条规则可能对应多个过滤器
+    fn add_rule(&mut self, rule: &FilterRule) -> anyhow::Result<Vec<u64>>;
+
+    /// 删除一个由 `add_rule` 创建的过滤器
+    fn delete_filter(&mut self, filter_id: u64) -> anyhow::Result<()>;
+
+    /// 删除所有过滤器并关闭过滤引擎
+    fn cleanup(&mut self) -> anyhow::Result<()>;
+}
+
+/// 创建当前平台的过滤后端
+pub fn create_backend() -> Box<dyn FilterBackend> {
+    #[cfg(target_os = "windows")]
+    {
+        Box::new(wfp::WfpBackend::new())
+    }
+    #[cfg(target_os = "linux")]
+    {
+        Box::new(nftables::NftablesBackend::new(
+            nftables::DEFAULT_TABLE,
+            None,
+        ))
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        Box::new(UnsupportedBackend)
+    }
+}
+
+/// 不支持流量过滤的平台，所有操作都返回错误，而不是假装成功
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+struct UnsupportedBackend;
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+impl FilterBackend for UnsupportedBackend {
+    fn name(&self) -> &'static str {
+        "unsupported"
+    }
+
+    fn initialize(&mut self) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!(
+            "当前平台 ({}) 不支持网络流量过滤",
+            std::env::consts::OS
+        ))
+    }
+
+    fn add_rule(&mut self, _rule: &FilterRule) -> anyhow::Result<Vec<u64>> {
+        self.initialize().map(|_| Vec::new())
+    }
+
+    fn delete_filter(&mut self, _filter_id: u64) -> anyhow::Result<()> {
+        self.initialize()
+    }
+
+    fn cleanup(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
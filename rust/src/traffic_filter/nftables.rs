@@ -0,0 +1,543 @@
+//! nftables 后端
+//!
+//! 所有规则放在单独的 `inet` 表中，初始化时重建，清理时整表删除，不影响系统中其他的防火墙规则。
+//! 过滤器 ID 就是 nft 的规则句柄。
+//!
+//! netfilter 无法按可执行文件匹配流量，`FilterRule::app_path` 在 Linux 上的格式为：
+//! - `cgroup:<路径>`: socket 所在的 cgroup v2，路径相对于 cgroup 根目录，
+//!   例如 `cgroup:user.slice/user-1000.slice/app-game.scope`，也可以直接写 `/sys/fs/cgroup/...`
+//! - `uid:<用户ID>` 或 `user:<用户名>`: socket 所属的用户，只能匹配本机发出的流量
+
+use std::net::IpAddr;
+use std::process::Command;
+
+use anyhow::{anyhow, bail, Context};
+
+use super::FilterBackend;
+use crate::api::astral_wfp::{Direction, FilterAction, FilterRule, IpNetwork};
+
+pub const DEFAULT_TABLE: &str = "astral";
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Chain {
+    Input,
+    Output,
+}
+
+impl Chain {
+    fn name(&self) -> &'static str {
+        match self {
+            Chain::Input => "input",
+            Chain::Output => "output",
+        }
+    }
+
+    fn for_direction(direction: &Direction) -> &'static [Chain] {
+        match direction {
+            Direction::Inbound => &[Chain::Input],
+            Direction::Outbound => &[Chain::Output],
+            Direction::Both => &[Chain::Input, Chain::Output],
+        }
+    }
+}
+
+/// app_path 在 Linux 上对应的匹配条件
+#[derive(Debug, PartialEq)]
+enum AppMatch {
+    Cgroup(String),
+    Uid(u32),
+}
+
+fn parse_app_path(app_path: &str) -> anyhow::Result<AppMatch> {
+    if let Some(path) = app_path
+        .strip_prefix("cgroup:")
+        .or_else(|| app_path.strip_prefix(CGROUP_ROOT))
+    {
+        let path = path.trim_matches('/');
+        if path.is_empty() || path.contains('"') {
+            bail!("无效的 cgroup 路径: {}", app_path);
+        }
+        return Ok(AppMatch::Cgroup(path.to_string()));
+    }
+    if let Some(uid) = app_path.strip_prefix("uid:") {
+        return uid
+            .parse()
+            .map(AppMatch::Uid)
+            .map_err(|_| anyhow!("无效的用户ID: {}", uid));
+    }
+    if let Some(user) = app_path.strip_prefix("user:") {
+        return lookup_uid(user).map(AppMatch::Uid);
+    }
+    bail!(
+        "Linux 上无法按程序路径 {} 过滤，请使用 cgroup:<路径>、uid:<用户ID> 或 user:<用户名>",
+        app_path
+    )
+}
+
+// 从 /etc/passwd 查找用户ID
+fn lookup_uid(user: &str) -> anyhow::Result<u32> {
+    let passwd = std::fs::read_to_string("/etc/passwd").context("读取 /etc/passwd 失败")?;
+    passwd
+        .lines()
+        .find_map(|line| {
+            let mut fields = line.split(':');
+            if fields.next()? != user {
+                return None;
+            }
+            fields.nth(1)?.parse().ok()
+        })
+        .ok_or_else(|| anyhow!("找不到用户: {}", user))
+}
+
+/// 把 IP 或 CIDR 转成 nft 的地址表达式，返回地址族 (ip/ip6) 和地址
+fn nft_addr(addr: &str) -> anyhow::Result<(&'static str, String)> {
+    if let Ok(ip) = addr.parse::<IpAddr>() {
+        let family = if ip.is_ipv4() { "ip" } else { "ip6" };
+        return Ok((family, ip.to_string()));
+    }
+    let network = IpNetwork::from_cidr(addr).map_err(|e| anyhow!("无效的地址 {}: {}", addr, e))?;
+    // nft 不接受主机位不为 0 的网段
+    let ip = match network.ip {
+        IpAddr::V4(ip) => IpAddr::V4(ip),
+        IpAddr::V6(ip) => {
+            let mask = u128::MAX
+                .checked_shl(128 - network.prefix_len as u32)
+                .unwrap_or(0);
+            IpAddr::V6((u128::from(ip) & mask).into())
+        }
+    };
+    let family = if ip.is_ipv4() { "ip" } else { "ip6" };
+    Ok((family, format!("{}/{}", ip, network.prefix_len)))
+}
+
+/// 生成规则在指定链上的 nft 语句
+fn rule_statement(rule: &FilterRule, chain: Chain) -> anyhow::Result<String> {
+    let mut exprs = Vec::new();
+    // 出站流量的本地地址是源地址，入站流量相反
+    let (local_addr, remote_addr, local_port, remote_port) = match chain {
+        Chain::Output => ("saddr", "daddr", "sport", "dport"),
+        Chain::Input => ("daddr", "saddr", "dport", "sport"),
+    };
+
+    if let Some(app_path) = &rule.app_path {
+        match parse_app_path(app_path)? {
+            AppMatch::Cgroup(path) => exprs.push(format!(
+                "socket cgroupv2 level {} \"{}\"",
+                path.split('/').count(),
+                path
+            )),
+            AppMatch::Uid(uid) => {
+                if chain == Chain::Input {
+                    bail!("入站流量无法按用户匹配，请使用 cgroup");
+                }
+                exprs.push(format!("meta skuid {}", uid));
+            }
+        }
+    }
+
+    let mut family = None;
+    for (addr, field) in [(&rule.local, local_addr), (&rule.remote, remote_addr)] {
+        let Some(addr) = addr else { continue };
+        let (addr_family, addr) = nft_addr(addr)?;
+        if family
+            .replace(addr_family)
+            .is_some_and(|f| f != addr_family)
+        {
+            bail!("本地地址和远程地址的IP版本不一致");
+        }
+        exprs.push(format!("{} {} {}", addr_family, field, addr));
+    }
+
+    let ports = [
+        (rule.local_port, rule.local_port_range, local_port),
+        (rule.remote_port, rule.remote_port_range, remote_port),
+    ];
+    let has_port = ports
+        .iter()
+        .any(|(port, range, _)| port.is_some() || range.is_some());
+    match &rule.protocol {
+        Some(protocol) => exprs.push(format!(
+            "meta l4proto {}",
+            protocol.to_string().to_lowercase()
+        )),
+        // th 只能用在有端口的传输层协议上
+        None if has_port => exprs.push("meta l4proto { tcp, udp }".to_string()),
+        None => {}
+    }
+    for (port, range, field) in ports {
+        if let Some(port) = port {
+            exprs.push(format!("th {} {}", field, port));
+        } else if let Some((start, end)) = range {
+            exprs.push(format!("th {} {}-{}", field, start, end));
+        }
+    }
+
+    exprs.push(
+        match rule.action {
+            FilterAction::Allow => "accept",
+            FilterAction::Block => "drop",
+        }
+        .to_string(),
+    );
+    // 带上规则名，方便用 nft list 排查
+    exprs.push(format!("comment \"{}\"", rule.name.replace('"', "'")));
+    Ok(exprs.join(" "))
+}
+
+// nft --echo --handle 的输出中带有新规则的句柄，例如 "add rule inet astral output ... # handle 5"
+fn parse_handle(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find_map(|line| line.rsplit_once("# handle ")?.1.trim().parse().ok())
+}
+
+struct NftFilter {
+    handle: u64,
+    chain: Chain,
+    priority: u32,
+}
+
+pub struct NftablesBackend {
+    table: String,
+    // 在指定的网络命名空间中执行 nft，None 为当前命名空间
+    netns: Option<String>,
+    // 同一条链上按优先级从高到低排列，和规则在链上的顺序一致
+    filters: Vec<NftFilter>,
+}
+
+impl NftablesBackend {
+    pub fn new(table: &str, netns: Option<String>) -> Self {
+        Self {
+            table: table.to_string(),
+            netns,
+            filters: Vec::new(),
+        }
+    }
+
+    fn nft(&self, args: &[&str]) -> anyhow::Result<String> {
+        let mut cmd = match &self.netns {
+            Some(netns) => {
+                let mut cmd = Command::new("ip");
+                cmd.args(["netns", "exec", netns, "nft"]);
+                cmd
+            }
+            None => Command::new("nft"),
+        };
+        let output = cmd
+            .args(args)
+            .output()
+            .context("执行 nft 失败，请确认已安装 nftables")?;
+        if !output.status.success() {
+            bail!(
+                "nft {} 失败: {} (可能需要 root 权限)",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn add_rule_to_chain(&mut self, rule: &FilterRule, chain: Chain) -> anyhow::Result<u64> {
+        let statement = rule_statement(rule, chain)?;
+
+        // 同一条链上优先级高的规则先匹配，插到第一条优先级更低的规则之前
+        let pos = self
+            .filters
+            .iter()
+            .position(|f| f.chain == chain && f.priority < rule.priority);
+        let cmd = match pos {
+            Some(pos) => format!(
+                "insert rule inet {} {} position {} {}",
+                self.table,
+                chain.name(),
+                self.filters[pos].handle,
+                statement
+            ),
+            None => format!(
+                "add rule inet {} {} {}",
+                self.table,
+                chain.name(),
+                statement
+            ),
+        };
+        let output = self.nft(&["--echo", "--handle", &cmd])?;
+        let handle = parse_handle(&output)
+            .ok_or_else(|| anyhow!("无法解析 nft 返回的规则句柄: {}", output.trim()))?;
+
+        self.filters.insert(
+            pos.unwrap_or(self.filters.len()),
+            NftFilter {
+                handle,
+                chain,
+                priority: rule.priority,
+            },
+        );
+        Ok(handle)
+    }
+}
+
+impl FilterBackend for NftablesBackend {
+    fn name(&self) -> &'static str {
+        "nftables"
+    }
+
+    fn initialize(&mut self) -> anyhow::Result<()> {
+        println!("正在初始化 nftables 表 {}...", self.table);
+        // 先添加再删除，保证上次没有清理的规则不会残留，整个脚本在一个事务中执行
+        let script = format!(
+            "add table inet {t}\n\
+             delete table inet {t}\n\
+             add table inet {t}\n\
+             add chain inet {t} input {{ type filter hook input priority 0; policy accept; }}\n\
+             add chain inet {t} output {{ type filter hook output priority 0; policy accept; }}",
+            t = self.table
+        );
+        self.nft(&[&script])?;
+        self.filters.clear();
+        println!("✓ nftables 表 {} 已创建", self.table);
+        Ok(())
+    }
+
+    fn add_rule(&mut self, rule: &FilterRule) -> anyhow::Result<Vec<u64>> {
+        let mut added_ids = Vec::new();
+
+        for &chain in Chain::for_direction(&rule.direction) {
+            match self.add_rule_to_chain(rule, chain) {
+                Ok(handle) => {
+                    added_ids.push(handle);
+                    println!("✅ 过滤器在链 {} 上添加成功 (ID: {})", chain.name(), handle);
+                }
+                Err(e) => {
+                    println!("❌ 过滤器在链 {} 上添加失败: {:?}", chain.name(), e);
+                }
+            }
+        }
+
+        if added_ids.is_empty() {
+            Err(anyhow!("规则 {} 没有在任何链上添加成功", rule.name))
+        } else {
+            Ok(added_ids)
+        }
+    }
+
+    fn delete_filter(&mut self, filter_id: u64) -> anyhow::Result<()> {
+        let pos = self
+            .filters
+            .iter()
+            .position(|f| f.handle == filter_id)
+            .ok_or_else(|| anyhow!("未找到过滤器 {}", filter_id))?;
+        self.nft(&[&format!(
+            "delete rule inet {} {} handle {}",
+            self.table,
+            self.filters[pos].chain.name(),
+            filter_id
+        )])?;
+        self.filters.remove(pos);
+        Ok(())
+    }
+
+    fn cleanup(&mut self) -> anyhow::Result<()> {
+        println!("🛑 停止过滤器，正在清理...");
+        self.nft(&[&format!(
+            "add table inet {t}\ndelete table inet {t}",
+            t = self.table
+        )])?;
+        self.filters.clear();
+        println!("✓ nftables 表 {} 已删除", self.table);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+    use crate::api::astral_wfp::Protocol;
+
+    fn rule(
+        app_path: Option<&str>,
+        remote: Option<&str>,
+        remote_port: Option<u16>,
+        protocol: Option<Protocol>,
+        direction: Direction,
+        action: FilterAction,
+        priority: u32,
+    ) -> FilterRule {
+        FilterRule::new_with_params(
+            "test",
+            app_path.map(str::to_string),
+            None,
+            remote.map(str::to_string),
+            None,
+            remote_port,
+            None,
+            None,
+            protocol,
+            direction,
+            action,
+            Some(priority),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_rule_statement() {
+        let r = rule(
+            Some("uid:1000"),
+            Some("10.1.2.3/8"),
+            Some(443),
+            Some(Protocol::Tcp),
+            Direction::Outbound,
+            FilterAction::Block,
+            200,
+        );
+        assert_eq!(
+            rule_statement(&r, Chain::Output).unwrap(),
+            "meta skuid 1000 ip daddr 10.0.0.0/8 meta l4proto tcp th dport 443 drop comment \"test\""
+        );
+        // 入站流量无法按用户匹配
+        assert!(rule_statement(&r, Chain::Input).is_err());
+
+        let r = rule(
+            Some("/sys/fs/cgroup/user.slice/app.scope"),
+            Some("fd00::1/64"),
+            Some(53),
+            None,
+            Direction::Both,
+            FilterAction::Allow,
+            200,
+        );
+        assert_eq!(
+            rule_statement(&r, Chain::Input).unwrap(),
+            "socket cgroupv2 level 2 \"user.slice/app.scope\" ip6 saddr fd00::/64 \
+             meta l4proto { tcp, udp } th sport 53 accept comment \"test\""
+        );
+
+        let r = rule(
+            Some("/usr/bin/game"),
+            None,
+            None,
+            None,
+            Direction::Outbound,
+            FilterAction::Block,
+            200,
+        );
+        assert!(rule_statement(&r, Chain::Output).is_err());
+
+        assert_eq!(
+            parse_handle("add rule inet astral output ip daddr 1.1.1.1 drop # handle 12\n"),
+            Some(12)
+        );
+    }
+
+    // 以下测试在临时的网络命名空间中运行，需要 root 权限以及 ip、nft 和 ping 命令，条件不满足时跳过
+    struct TestNetns(String);
+
+    impl TestNetns {
+        fn new(name: &str) -> Option<Self> {
+            let is_root = Command::new("id")
+                .arg("-u")
+                .output()
+                .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).trim() == "0");
+            let has_tools = ["nft", "ping"]
+                .iter()
+                .all(|tool| Command::new(tool).arg("-V").output().is_ok());
+            if !is_root || !has_tools {
+                println!("跳过网络命名空间测试: 需要 root 权限以及 nft 和 ping 命令");
+                return None;
+            }
+            let ok = Command::new("ip")
+                .args(["netns", "add", name])
+                .status()
+                .is_ok_and(|s| s.success());
+            if !ok {
+                println!("跳过网络命名空间测试: 创建网络命名空间 {} 失败", name);
+                return None;
+            }
+            let netns = TestNetns(name.to_string());
+            netns.exec(&["ip", "link", "set", "lo", "up"]);
+            Some(netns)
+        }
+
+        fn exec(&self, args: &[&str]) -> bool {
+            Command::new("ip")
+                .args(["netns", "exec", &self.0])
+                .args(args)
+                .output()
+                .is_ok_and(|o| o.status.success())
+        }
+
+        fn ping(&self, addr: &str) -> bool {
+            self.exec(&["ping", "-c", "1", "-W", "1", addr])
+        }
+    }
+
+    impl Drop for TestNetns {
+        fn drop(&mut self) {
+            let _ = Command::new("ip").args(["netns", "del", &self.0]).status();
+        }
+    }
+
+    #[test]
+    fn test_nftables_in_netns() {
+        let Some(netns) = TestNetns::new("astral_nft_test") else {
+            return;
+        };
+        let mut backend = NftablesBackend::new(DEFAULT_TABLE, Some(netns.0.clone()));
+        backend.initialize().unwrap();
+        assert!(netns.ping("127.0.0.1"));
+
+        let block = rule(
+            None,
+            Some("127.0.0.1"),
+            None,
+            None,
+            Direction::Outbound,
+            FilterAction::Block,
+            200,
+        );
+        let block_ids = backend.add_rule(&block).unwrap();
+        assert_eq!(block_ids.len(), 1);
+        assert!(!netns.ping("127.0.0.1"));
+
+        // 优先级更高的允许规则插在阻止规则之前
+        let allow = rule(
+            None,
+            Some("127.0.0.0/8"),
+            None,
+            None,
+            Direction::Outbound,
+            FilterAction::Allow,
+            300,
+        );
+        let allow_ids = backend.add_rule(&allow).unwrap();
+        assert!(netns.ping("127.0.0.1"));
+
+        backend.delete_filter(allow_ids[0]).unwrap();
+        assert!(!netns.ping("127.0.0.1"));
+        assert!(backend.delete_filter(allow_ids[0]).is_err());
+
+        // 按用户匹配只能用于出站方向，双向规则只添加到 output 链
+        let both = rule(
+            Some("uid:0"),
+            Some("127.0.0.2"),
+            None,
+            None,
+            Direction::Both,
+            FilterAction::Block,
+            200,
+        );
+        assert_eq!(backend.add_rule(&both).unwrap().len(), 1);
+        assert!(!netns.ping("127.0.0.2"));
+
+        backend.delete_filter(block_ids[0]).unwrap();
+        assert!(netns.ping("127.0.0.1"));
+
+        backend.cleanup().unwrap();
+        assert!(netns.ping("127.0.0.2"));
+        assert!(!netns.exec(&["nft", "list", "table", "inet", DEFAULT_TABLE]));
+    }
+}